@@ -98,7 +98,7 @@ impl query::Engine {
                     }
                 }
 
-                info.sort_by(|a, b| a.id.cmp(&b.id));
+                info.sort_by_key(|a| a.id);
                 let max_diff_lines = info
                     .iter()
                     .map(|i| i.diff.map_or(0, |d| d.lines_removed + d.lines_added))
@@ -1,12 +1,60 @@
-use std::ffi::OsStr;
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::OsStr,
+    ops::ControlFlow,
+    path::PathBuf,
+};
 
-use gix::{bstr::ByteSlice, config::tree};
+use gix::{
+    bstr::{BString, ByteSlice},
+    config::tree,
+    ObjectId,
+};
+
+/// The way blamed lines are written.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Format {
+    /// One line per blamed line, with the commit, line numbers and the file the line came from.
+    #[default]
+    Human,
+    /// Like `git blame --porcelain`, which shows the details of each commit only once.
+    Porcelain,
+    /// Like `git blame --line-porcelain`, which shows the details of the commit for each line.
+    LinePorcelain,
+    /// Like `git blame --incremental`, which shows hunks as soon as the commit that introduced them is known.
+    Incremental,
+}
+
+pub struct Options {
+    pub format: Format,
+    /// The 1-based inclusive ranges of lines to blame.
+    pub ranges: Vec<std::ops::RangeInclusive<u32>>,
+    /// Don't consider commits before the given date.
+    pub since: Option<gix::date::Time>,
+    /// Revisions whose changes should be attributed to their parents, in addition to those of `blame.ignoreRevsFile`.
+    pub ignore_revs: Vec<BString>,
+    /// Files with revisions to ignore, in addition to those of `blame.ignoreRevsFile`.
+    /// An empty path clears the list of revisions read from all files before it.
+    pub ignore_revs_files: Vec<PathBuf>,
+    /// If `true`, blame the file in the worktree, with uncommitted changes attributed to the null id.
+    pub worktree: bool,
+    /// If set, attribute moved or copied lines to the commit that introduced them where they came from.
+    pub moves_and_copies: Option<gix::blame::MovesAndCopies>,
+}
 
 pub fn blame_file(
     mut repo: gix::Repository,
     file: &OsStr,
-    options: gix::blame::Options,
-    out: impl std::io::Write,
+    Options {
+        format,
+        ranges,
+        since,
+        ignore_revs,
+        ignore_revs_files,
+        worktree,
+        moves_and_copies,
+    }: Options,
+    mut out: impl std::io::Write,
     err: Option<&mut dyn std::io::Write>,
 ) -> anyhow::Result<()> {
     {
@@ -35,19 +83,62 @@ pub fn blame_file(
         .next()
         .expect("exactly one pattern");
 
-    let suspect: gix::ObjectId = repo.head()?.into_peeled_id()?.into();
-    let cache: Option<gix::commitgraph::Graph> = repo.commit_graph_if_enabled()?;
-    let mut resource_cache = repo.diff_resource_cache_for_tree_diff()?;
-    let outcome = gix::blame::file(
-        &repo.objects,
-        suspect,
-        cache,
-        &mut resource_cache,
-        file.as_bstr(),
-        options,
-    )?;
-    let statistics = outcome.statistics;
-    show_blame_entries(out, outcome, file)?;
+    let mut ignored = repo.blame_ignore_revs()?;
+    for path in ignore_revs_files {
+        if path.as_os_str().is_empty() {
+            ignored.clear();
+            continue;
+        }
+        ignored.extend(repo.blame_ignore_revs_from_file(&path)?);
+    }
+    for spec in ignore_revs {
+        ignored.push(repo.rev_parse_single(spec.as_bstr())?.object()?.peel_to_commit()?.id);
+    }
+
+    let suspect: ObjectId = repo.head()?.into_peeled_id()?.into();
+    let options = gix::repository::blame_file::Options {
+        diff_algorithm: None,
+        ranges: gix::blame::BlameRanges::from_one_based_inclusive_ranges(ranges)?,
+        since,
+        rewrites: Some(gix::diff::Rewrites::default()),
+        ignore_revs: Some(ignored),
+        worktree,
+        moves_and_copies,
+    };
+
+    let mut details = Details::new(&repo, file.clone(), since);
+    let statistics = match format {
+        Format::Incremental => {
+            let mut write_err = None;
+            let res = repo.blame_file_incremental(file.as_bstr(), suspect, options, |entry| {
+                match details
+                    .write_incremental(&entry, &mut out)
+                    .and_then(|()| out.flush().map_err(Into::into))
+                {
+                    Ok(()) => ControlFlow::Continue(()),
+                    Err(err) => {
+                        write_err = Some(err);
+                        ControlFlow::Break(())
+                    }
+                }
+            });
+            if let Some(err) = write_err {
+                return Err(err);
+            }
+            res?.statistics
+        }
+        Format::Human => {
+            let outcome = repo.blame_file(file.as_bstr(), suspect, options)?;
+            let statistics = outcome.statistics;
+            show_blame_entries(&repo, out, outcome, file)?;
+            statistics
+        }
+        Format::Porcelain | Format::LinePorcelain => {
+            let outcome = repo.blame_file(file.as_bstr(), suspect, options)?;
+            details.write_porcelain(&outcome, format == Format::LinePorcelain, &mut out)?;
+            outcome.statistics
+        }
+    };
 
     if let Some(err) = err {
         writeln!(err, "{statistics:#?}")?;
@@ -56,16 +147,25 @@ pub fn blame_file(
 }
 
 fn show_blame_entries(
+    repo: &gix::Repository,
     mut out: impl std::io::Write,
     outcome: gix::blame::Outcome,
-    source_file_name: gix::bstr::BString,
+    source_file_name: BString,
 ) -> Result<(), std::io::Error> {
+    let config = repo.config_snapshot();
+    let mark_ignored = config.boolean(&tree::Blame::MARK_IGNORED_LINES).unwrap_or_default();
+    let mark_unblamable = config.boolean(&tree::Blame::MARK_UNBLAMABLE_LINES).unwrap_or_default();
     for (entry, lines_in_hunk) in outcome.entries_with_lines() {
         for ((actual_lno, source_lno), line) in entry
             .range_in_blamed_file()
             .zip(entry.range_in_source_file())
             .zip(lines_in_hunk)
         {
+            if mark_unblamable && entry.unblamable {
+                write!(out, "*")?;
+            } else if mark_ignored && entry.ignored {
+                write!(out, "?")?;
+            }
             write!(
                 out,
                 "{short_id} {line_no} ",
@@ -82,3 +182,148 @@ fn show_blame_entries(
 
     Ok(())
 }
+
+/// Write the details of commits like `git blame` does in its machine-readable formats.
+struct Details<'repo> {
+    repo: &'repo gix::Repository,
+    mailmap: gix::mailmap::Snapshot,
+    /// The path of the blamed file.
+    file: BString,
+    since: Option<gix::date::Time>,
+    /// The commits whose details were shown already.
+    shown: HashSet<ObjectId>,
+}
+
+impl<'repo> Details<'repo> {
+    fn new(repo: &'repo gix::Repository, file: BString, since: Option<gix::date::Time>) -> Self {
+        Details {
+            repo,
+            mailmap: repo.open_mailmap(),
+            file,
+            since,
+            shown: HashSet::new(),
+        }
+    }
+
+    fn write_porcelain(
+        &mut self,
+        outcome: &gix::blame::Outcome,
+        repeat: bool,
+        out: &mut impl std::io::Write,
+    ) -> anyhow::Result<()> {
+        // Commits that contributed lines under more than one path always show the path they were found under.
+        let mut paths_by_commit = HashMap::<ObjectId, HashSet<Option<&BString>>>::new();
+        for entry in &outcome.entries {
+            paths_by_commit
+                .entry(entry.commit_id)
+                .or_default()
+                .insert(entry.source_file_name.as_ref());
+        }
+
+        for (entry, lines) in outcome.entries_with_lines() {
+            let hex = entry.commit_id.to_hex();
+            let always_show_path = paths_by_commit
+                .get(&entry.commit_id)
+                .is_some_and(|paths| paths.len() > 1);
+            for (offset, line) in lines.iter().enumerate() {
+                let offset = offset as u32;
+                let (source_lno, blamed_lno) = (
+                    entry.start_in_source_file + offset + 1,
+                    entry.start_in_blamed_file + offset + 1,
+                );
+                if offset == 0 {
+                    writeln!(out, "{hex} {source_lno} {blamed_lno} {}", entry.len)?;
+                } else {
+                    writeln!(out, "{hex} {source_lno} {blamed_lno}")?;
+                }
+                if (offset == 0 || repeat) && (self.write_commit(&entry, repeat, out)? || always_show_path) {
+                    self.write_path(&entry, out)?;
+                }
+                out.write_all(b"\t")?;
+                out.write_all(line)?;
+                if !line.ends_with(b"\n") {
+                    out.write_all(b"\n")?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn write_incremental(
+        &mut self,
+        entry: &gix::blame::BlameEntry,
+        out: &mut impl std::io::Write,
+    ) -> anyhow::Result<()> {
+        writeln!(
+            out,
+            "{} {} {} {}",
+            entry.commit_id,
+            entry.start_in_source_file + 1,
+            entry.start_in_blamed_file + 1,
+            entry.len
+        )?;
+        self.write_commit(entry, false, out)?;
+        self.write_path(entry, out)
+    }
+
+    /// Write the details of the commit of `entry` unless they were shown already and `repeat` is `false`.
+    /// Return `true` if they were written.
+    fn write_commit(
+        &mut self,
+        entry: &gix::blame::BlameEntry,
+        repeat: bool,
+        out: &mut impl std::io::Write,
+    ) -> anyhow::Result<bool> {
+        let id = entry.commit_id;
+        if !self.shown.insert(id) && !repeat {
+            return Ok(false);
+        }
+
+        if id.is_null() {
+            let time = gix::date::Time::now_local_or_utc();
+            for role in ["author", "committer"] {
+                writeln!(out, "{role} Not Committed Yet")?;
+                writeln!(out, "{role}-mail <not.committed.yet>")?;
+                writeln!(out, "{role}-time {}", time.seconds)?;
+                writeln!(out, "{role}-tz {}", format_offset(time.offset))?;
+            }
+            let path = entry.source_file_name.as_ref().unwrap_or(&self.file);
+            writeln!(out, "summary Version of {path} from {path}")?;
+            return Ok(true);
+        }
+
+        let commit = self.repo.find_commit(id)?;
+        for (role, signature) in [("author", commit.author()?), ("committer", commit.committer()?)] {
+            let signature = self.mailmap.resolve(signature.trim());
+            writeln!(out, "{role} {}", signature.name)?;
+            writeln!(out, "{role}-mail <{}>", signature.email)?;
+            writeln!(out, "{role}-time {}", signature.time.seconds)?;
+            writeln!(out, "{role}-tz {}", format_offset(signature.time.offset))?;
+        }
+        writeln!(out, "summary {}", commit.message()?.summary())?;
+        let is_boundary = commit.parent_ids().next().is_none()
+            || self
+                .since
+                .is_some_and(|since| commit.time().is_ok_and(|time| time.seconds < since.seconds));
+        if is_boundary {
+            writeln!(out, "boundary")?;
+        }
+        Ok(true)
+    }
+
+    fn write_path(&self, entry: &gix::blame::BlameEntry, out: &mut impl std::io::Write) -> anyhow::Result<()> {
+        if let Some((id, path)) = &entry.previous {
+            writeln!(out, "previous {id} {path}")?;
+        }
+        let path = entry.source_file_name.as_ref().unwrap_or(&self.file);
+        writeln!(out, "filename {path}")?;
+        Ok(())
+    }
+}
+
+/// Format a timezone `offset` in seconds like `+0130`.
+fn format_offset(offset: i32) -> String {
+    let sign = if offset < 0 { '-' } else { '+' };
+    let minutes = offset.unsigned_abs() / 60;
+    format!("{sign}{:02}{:02}", minutes / 60, minutes % 60)
+}
@@ -7,6 +7,8 @@ pub struct Options {
     pub no_tags: bool,
     pub shallow: gix::remote::fetch::Shallow,
     pub ref_name: Option<gix::refs::PartialName>,
+    pub recurse_submodules: bool,
+    pub bundle_uri: Option<gix::bstr::BString>,
 }
 
 pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
@@ -34,6 +36,8 @@ pub(crate) mod function {
             no_tags,
             ref_name,
             shallow,
+            recurse_submodules,
+            bundle_uri,
         }: Options,
     ) -> anyhow::Result<()>
     where
@@ -72,11 +76,15 @@ pub(crate) mod function {
                 opts
             },
         )?;
+        if let Some(uri) = bundle_uri {
+            prepare = prepare.with_bundle_uri(uri);
+        }
         if no_tags {
             prepare = prepare.configure_remote(|r| Ok(r.with_fetch_tags(gix::remote::fetch::Tags::None)));
         }
         let (mut checkout, fetch_outcome) = prepare
             .with_shallow(shallow)
+            .with_recurse_submodules(recurse_submodules.then_some(true))
             .with_ref_name(ref_name.as_ref())?
             .fetch_then_checkout(&mut progress, &gix::interrupt::IS_INTERRUPTED)?;
 
@@ -55,3 +55,73 @@ pub fn function(repo: Option<gix::Repository>, action: gix::credentials::program
     )
     .map_err(Into::into)
 }
+
+pub mod helper {
+    use std::path::{Path, PathBuf};
+
+    /// The built-in credential helper to run.
+    pub enum Kind {
+        Store {
+            file: Option<PathBuf>,
+        },
+        Cache {
+            timeout: Option<u64>,
+            socket: Option<PathBuf>,
+        },
+    }
+
+    /// Run the helper of `kind` with `action`, reading the credential context from stdin.
+    pub fn function(kind: Kind, action: String) -> anyhow::Result<()> {
+        match kind {
+            Kind::Store { file } => {
+                let options = match file {
+                    Some(file) => gix::credentials::store::Options { files: vec![file] },
+                    None => gix::credentials::store::Options::from_env()?,
+                };
+                gix::credentials::program::main(
+                    Some(action.into()),
+                    std::io::stdin(),
+                    std::io::stdout(),
+                    |action, context| options.invoke(action, context),
+                )?;
+            }
+            #[cfg(unix)]
+            Kind::Cache { timeout, socket } => {
+                let mut options = gix::credentials::cache::Options::from_env()?;
+                if let Some(timeout) = timeout {
+                    options.timeout = std::time::Duration::from_secs(timeout);
+                }
+                if let Some(socket) = socket {
+                    options.socket = socket;
+                }
+                if action == "exit" {
+                    options.exit()?;
+                } else {
+                    gix::credentials::program::main(
+                        Some(action.into()),
+                        std::io::stdin(),
+                        std::io::stdout(),
+                        |action, context| options.invoke(action, context),
+                    )?;
+                }
+            }
+            #[cfg(not(unix))]
+            Kind::Cache { .. } => anyhow::bail!("The 'cache' credential helper is only supported on Unix"),
+        }
+        Ok(())
+    }
+
+    /// Serve credentials for the `cache` helper on `socket` until the daemon times out or is asked to exit.
+    pub fn cache_daemon(socket: &Path) -> anyhow::Result<()> {
+        #[cfg(unix)]
+        {
+            gix::credentials::cache::daemon::serve(socket, || println!("ok"))?;
+            Ok(())
+        }
+        #[cfg(not(unix))]
+        {
+            _ = socket;
+            anyhow::bail!("The credential cache daemon is only supported on Unix")
+        }
+    }
+}
@@ -1,19 +1,75 @@
 use anyhow::Context;
-use gix::diff::blob::unified_diff::ConsumeBinaryHunk;
 use gix::{
     bstr::{BString, ByteSlice},
-    diff::blob::{intern::TokenSource, unified_diff::ContextSize, UnifiedDiff},
+    config::tree::{color, Color, Diff},
+    diff::{
+        blob::{platform::prepare_diff::Operation, whitespace},
+        patch::{self, word_diff},
+        tree_with_rewrites::Change,
+    },
     objs::tree::EntryMode,
     odb::store::RefreshMode,
     prelude::ObjectIdExt,
     ObjectId,
 };
 
+/// Options for [`tree()`] and [`file()`].
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// Write the changes as patch, which is implied when diffing files.
+    pub patch: bool,
+    /// Write a summary of the changes per file, like `git diff --stat`.
+    pub stat: bool,
+    /// Write the amount of added and removed lines per file, like `git diff --numstat`.
+    pub numstat: bool,
+    /// Write the total amount of changes, like `git diff --shortstat`.
+    pub shortstat: bool,
+    /// The amount of unchanged lines to show around each change, or `None` to use the default of 3.
+    pub context_lines: Option<u32>,
+    /// The amount of unchanged lines between changes that may additionally be shown to merge their hunks.
+    pub inter_hunk_context: u32,
+    /// Which whitespace to ignore when comparing lines.
+    pub whitespace: whitespace::Mode,
+    /// If `true`, hide changes that only add or remove blank lines.
+    pub ignore_blank_lines: bool,
+    /// If set, show changed words instead of changed lines, which implies [`patch`](Self::patch).
+    pub word_diff: Option<word_diff::Mode>,
+    /// The regular expression to find words with, which implies a plain [`word_diff`](Self::word_diff) if unset.
+    pub word_diff_regex: Option<String>,
+    /// When to use colors, as one of `always`, `never` or `auto`. If unset, `color.diff` and `color.ui` are used.
+    pub color: Option<BString>,
+    /// How to detect moved lines in colored patches, like `plain`, `blocks`, `zebra` or `no`.
+    /// If unset, `diff.colorMoved` is used.
+    pub color_moved: Option<BString>,
+}
+
+impl Options {
+    fn word_diff(&self) -> Option<word_diff::Mode> {
+        self.word_diff
+            .or_else(|| self.word_diff_regex.as_ref().map(|_| word_diff::Mode::default()))
+    }
+
+    fn wants_patch(&self) -> bool {
+        self.patch || self.word_diff().is_some()
+    }
+
+    fn hunks(&self, algorithm: gix::diff::blob::Algorithm) -> patch::hunks::Options {
+        patch::hunks::Options {
+            algorithm,
+            context_lines: self.context_lines.unwrap_or(3),
+            inter_hunk_context: self.inter_hunk_context,
+            whitespace: self.whitespace,
+            ignore_blank_lines: self.ignore_blank_lines,
+        }
+    }
+}
+
 pub fn tree(
     mut repo: gix::Repository,
     out: &mut dyn std::io::Write,
     old_treeish: BString,
     new_treeish: BString,
+    options: Options,
 ) -> anyhow::Result<()> {
     repo.object_cache_size_if_unset(repo.compute_object_cache_size_for_tree_diffs(&**repo.index_or_empty()?));
     repo.objects.refresh = RefreshMode::Never;
@@ -26,15 +82,161 @@ pub fn tree(
 
     let changes = repo.diff_tree_to_tree(&old_tree, &new_tree, None)?;
 
-    writeln!(
+    if !(options.wants_patch() || options.stat || options.numstat || options.shortstat) {
+        writeln!(
+            out,
+            "Diffing trees `{old_treeish}` ({old_tree_id}) -> `{new_treeish}` ({new_tree_id})\n"
+        )?;
+        write_changes(&repo, out, changes)?;
+        return Ok(());
+    }
+
+    let hunk_options = options.hunks(repo.diff_algorithm()?);
+    let mut resource_cache = repo.diff_resource_cache_for_tree_diff()?;
+    let mut files = Vec::new();
+    for change in changes.iter().filter(|change| !change.entry_mode().is_tree()) {
+        let file = patch_file(&repo, change, &mut resource_cache, hunk_options)?;
+        resource_cache.clear_resource_cache_keep_allocation();
+        files.extend(file);
+    }
+    files.sort_by(|(a, _), (b, _)| a.path().cmp(b.path()));
+    write_files(&repo, out, &files, &options)
+}
+
+/// A patch along with the sizes of the old and the new version of the file if it is binary.
+type FileWithSizes = (patch::File, Option<(u64, u64)>);
+
+/// Turn `change` into a patch, along with the sizes of the old and new version if they are binary,
+/// or return `None` if it only changed in ignored whitespace.
+fn patch_file(
+    repo: &gix::Repository,
+    change: &Change,
+    resource_cache: &mut gix::diff::blob::Platform,
+    options: patch::hunks::Options,
+) -> anyhow::Result<Option<FileWithSizes>> {
+    let file = repo.diff_change_to_patch_file(change, resource_cache, options, false)?;
+    if file.kind == patch::Kind::Modification
+        && file.old_mode == file.new_mode
+        && matches!(&file.content, patch::Content::Hunks(hunks) if hunks.is_empty())
+    {
+        return Ok(None);
+    }
+
+    let binary_sizes = if matches!(file.content, patch::Content::BinaryWithoutData) {
+        let size = |id: Option<&gix::oid>| -> anyhow::Result<u64> {
+            Ok(match id {
+                Some(id) => repo.find_header(id)?.size(),
+                None => 0,
+            })
+        };
+        let old_id = (file.kind != patch::Kind::Addition).then(|| change.source_entry_mode_and_id().1);
+        let new_id = (file.kind != patch::Kind::Deletion).then(|| change.entry_mode_and_id().1);
+        Some((size(old_id)?, size(new_id)?))
+    } else {
+        None
+    };
+    Ok(Some((file, binary_sizes)))
+}
+
+/// Write `files` as configured by `options`, with stats written before the patch, like Git does.
+fn write_files(
+    repo: &gix::Repository,
+    out: &mut dyn std::io::Write,
+    files: &[FileWithSizes],
+    options: &Options,
+) -> anyhow::Result<()> {
+    let stats: Vec<_> = files
+        .iter()
+        .map(|(file, binary_sizes)| {
+            let mut stat = file.stat();
+            if binary_sizes.is_some() {
+                stat.binary = *binary_sizes;
+            }
+            stat
+        })
+        .collect();
+    if options.numstat {
+        patch::stat::write_numstat(&stats, out)?;
+    }
+    if options.stat {
+        patch::stat::write(&stats, 80, out)?;
+    } else if options.shortstat {
+        patch::stat::write_totals(&stats, out)?;
+    }
+    if !options.wants_patch() {
+        return Ok(());
+    }
+    if options.stat || options.numstat || options.shortstat {
+        writeln!(out)?;
+    }
+
+    let config = repo.config_snapshot();
+    let use_color = match options.color.as_ref() {
+        Some(when) => Color::DIFF.try_into_when(when.as_bstr().into())?,
+        None => match config.string(Color::DIFF).or_else(|| config.string(Color::UI)) {
+            Some(when) => Color::DIFF.try_into_when(when)?,
+            None => None,
+        },
+    }
+    .unwrap_or_else(|| {
+        use std::io::IsTerminal;
+        std::io::stdout().is_terminal()
+    });
+    let colors = use_color.then(|| diff_colors(&config)).transpose()?;
+    let color_moved = match options.color_moved.as_ref() {
+        Some(mode) => Diff::COLOR_MOVED.try_into_color_moved(mode.as_bstr().into())?,
+        None => config
+            .string(Diff::COLOR_MOVED)
+            .map(|mode| Diff::COLOR_MOVED.try_into_color_moved(mode))
+            .transpose()?
+            .flatten(),
+    };
+    let word_regex = options
+        .word_diff_regex
+        .as_deref()
+        .map(regex::bytes::Regex::new)
+        .transpose()?;
+    let find_word = |text: &[u8]| word_regex.as_ref()?.find(text).map(|m| m.range());
+
+    let files: Vec<_> = files.iter().map(|(file, _)| file.clone()).collect();
+    patch::write(
+        &files,
+        patch::write::Options {
+            colors: colors.as_ref(),
+            color_moved,
+            word_diff: options.word_diff().map(|mode| word_diff::Options {
+                mode,
+                find_word: word_regex.is_some().then_some(&find_word as word_diff::FindWord<'_>),
+            }),
+        },
         out,
-        "Diffing trees `{old_treeish}` ({old_tree_id}) -> `{new_treeish}` ({new_tree_id})\n"
     )?;
-    write_changes(&repo, out, changes)?;
-
     Ok(())
 }
 
+/// Obtain the colors to use for patches from the `color.diff.<slot>` configuration.
+fn diff_colors(config: &gix::config::Snapshot<'_>) -> anyhow::Result<patch::color::Colors> {
+    let mut colors = patch::color::Colors::default();
+    for (key, slot) in [
+        (&color::Diff::CONTEXT, &mut colors.context),
+        (&color::Diff::META, &mut colors.meta),
+        (&color::Diff::FRAG, &mut colors.frag),
+        (&color::Diff::FUNC, &mut colors.func),
+        (&color::Diff::OLD, &mut colors.old),
+        (&color::Diff::NEW, &mut colors.new),
+        (&color::Diff::WHITESPACE, &mut colors.whitespace),
+        (&color::Diff::OLD_MOVED, &mut colors.old_moved),
+        (&color::Diff::OLD_MOVED_ALTERNATIVE, &mut colors.old_moved_alternative),
+        (&color::Diff::NEW_MOVED, &mut colors.new_moved),
+        (&color::Diff::NEW_MOVED_ALTERNATIVE, &mut colors.new_moved_alternative),
+    ] {
+        if let Some(value) = config.string(key) {
+            *slot = key.try_into_color(value)?.to_ansi();
+        }
+    }
+    Ok(colors)
+}
+
 fn write_changes(
     repo: &gix::Repository,
     mut out: impl std::io::Write,
@@ -153,6 +355,7 @@ pub fn file(
     out: &mut dyn std::io::Write,
     old_revspec: BString,
     new_revspec: BString,
+    options: Options,
 ) -> Result<(), anyhow::Error> {
     repo.object_cache_size_if_unset(repo.compute_object_cache_size_for_tree_diffs(&**repo.index_or_empty()?));
     repo.objects.refresh = RefreshMode::Never;
@@ -184,8 +387,6 @@ pub fn file(
 
     let outcome = resource_cache.prepare_diff()?;
 
-    use gix::diff::blob::platform::prepare_diff::Operation;
-
     let algorithm = match outcome.operation {
         Operation::InternalDiff { algorithm } => algorithm,
         Operation::ExternalCommand { .. } => {
@@ -196,24 +397,21 @@ pub fn file(
         }
     };
 
-    let interner = gix::diff::blob::intern::InternedInput::new(
-        tokens_for_diffing(outcome.old.data.as_slice().unwrap_or_default()),
-        tokens_for_diffing(outcome.new.data.as_slice().unwrap_or_default()),
+    let hunks = patch::hunks(
+        outcome.old.data.as_slice().unwrap_or_default(),
+        outcome.new.data.as_slice().unwrap_or_default(),
+        options.hunks(algorithm),
     );
-
-    let unified_diff = UnifiedDiff::new(
-        &interner,
-        ConsumeBinaryHunk::new(BString::default(), "\n"),
-        ContextSize::symmetrical(3),
-    );
-
-    let unified_diff = gix::diff::blob::diff(algorithm, &interner, unified_diff)?;
-
-    out.write_all(unified_diff.as_bytes())?;
-
-    Ok(())
-}
-
-pub(crate) fn tokens_for_diffing(data: &[u8]) -> impl TokenSource<Token = &[u8]> {
-    gix::diff::blob::sources::byte_lines(data)
+    let file = patch::File {
+        old_path: Some(old_path),
+        new_path: Some(new_path),
+        old_mode: None,
+        new_mode: None,
+        kind: patch::Kind::Modification,
+        similarity: None,
+        old_id: Some(old_blob_id.attach(&repo).shorten_or_id().to_string().into()),
+        new_id: Some(new_blob_id.attach(&repo).shorten_or_id().to_string().into()),
+        content: patch::Content::Hunks(hunks),
+    };
+    write_files(&repo, out, &[(file, None)], &Options { patch: true, ..options })
 }
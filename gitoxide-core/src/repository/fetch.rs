@@ -12,6 +12,10 @@ pub struct Options {
     pub handshake_info: bool,
     pub negotiation_info: bool,
     pub open_negotiation_graph: Option<std::path::PathBuf>,
+    /// How to fetch submodules, or `None` to use the configuration.
+    pub recurse_submodules: Option<gix::submodule::config::FetchRecurse>,
+    /// The number of submodules to fetch in parallel, or `None` to use the configuration.
+    pub jobs: Option<usize>,
 }
 
 pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
@@ -46,6 +50,8 @@ pub(crate) mod function {
             open_negotiation_graph,
             shallow,
             ref_specs,
+            recurse_submodules,
+            jobs,
         }: Options,
     ) -> anyhow::Result<()>
     where
@@ -73,6 +79,21 @@ pub(crate) mod function {
             writeln!(out, "\t{:?}", res.handshake)?;
         }
 
+        let submodules = if dry_run {
+            Vec::new()
+        } else {
+            repo.fetch_submodules(
+                Some(&res),
+                &mut progress,
+                &gix::interrupt::IS_INTERRUPTED,
+                &gix::submodule::fetch::Options {
+                    recurse: recurse_submodules,
+                    jobs,
+                },
+            )?
+            .submodules
+        };
+
         let ref_specs = remote.refspecs(gix::remote::Direction::Fetch);
         match res.status {
             Status::NoPackReceived {
@@ -121,12 +142,28 @@ pub(crate) mod function {
                 Ok(())
             }
         }?;
+        print_submodules(&submodules, "", &mut out)?;
         if dry_run {
             writeln!(out, "DRY-RUN: No ref was updated and no pack was received.").ok();
         }
         Ok(())
     }
 
+    fn print_submodules(
+        items: &[gix::submodule::fetch::Item],
+        prefix: &str,
+        out: &mut impl std::io::Write,
+    ) -> anyhow::Result<()> {
+        for item in items {
+            let path = format!("{prefix}{}", item.path);
+            if item.outcome.is_some() {
+                writeln!(out, "Fetched submodule '{path}'")?;
+            }
+            print_submodules(&item.submodules, &format!("{path}/"), out)?;
+        }
+        Ok(())
+    }
+
     fn render_graph(
         repo: &gix::Repository,
         graph: &gix::negotiate::IdMap,
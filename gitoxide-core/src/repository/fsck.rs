@@ -1,36 +1,197 @@
-use anyhow::Context;
-use gix::{objs::Kind, ObjectId};
-
-pub fn function(mut repo: gix::Repository, spec: Option<String>, mut out: impl std::io::Write) -> anyhow::Result<()> {
-    let spec = spec.unwrap_or("HEAD".into());
-
-    repo.object_cache_size_if_unset(4 * 1024 * 1024);
-    // We expect to be finding a bunch of non-existent objects here - never refresh the ODB
-    repo.objects.refresh_never();
-
-    let id = repo
-        .rev_parse_single(spec.as_str())
-        .context("Only single revisions are supported")?;
-    let commits: gix::revision::Walk<'_> = id
-        .object()?
-        .peel_to_kind(gix::object::Kind::Commit)
-        .context("Need committish as starting point")?
-        .id()
-        .ancestors()
-        .all()?;
-
-    let on_missing = |oid: &ObjectId, kind: Kind| {
-        writeln!(out, "{oid}: {kind}").expect("failed to write output");
-    };
-
-    let mut check = gix_fsck::Connectivity::new(&repo.objects, on_missing);
-    // Walk all commits, checking each one for connectivity
-    for commit in commits {
-        let commit = commit?;
-        check.check_commit(&commit.id)?;
-        // Note that we leave parent-iteration to the commits iterator, as it will
-        // correctly handle shallow repositories which are expected to have the commits
-        // along the shallow boundary missing.
+use gix::ObjectId;
+use gix_fsck::{check::Problem, object::Severity};
+
+pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=2;
+
+pub struct Options {
+    /// Print all unreachable objects, not only the dangling ones.
+    pub unreachable: bool,
+    /// Print dangling objects.
+    pub dangling: bool,
+    /// Write dangling objects into `.git/lost-found`.
+    pub lost_found: bool,
+    /// Only check if all reachable objects are present, without hashing or validating them.
+    pub connectivity_only: bool,
+}
+
+pub(crate) mod function {
+    use std::sync::atomic::AtomicBool;
+
+    use anyhow::{bail, Context};
+    use gix::{config::tree::Fsck, prelude::FindExt};
+    use gix_fsck::object::{MessageId, Severity};
+
+    use super::{print_problem, Options};
+
+    pub fn fsck(
+        mut repo: gix::Repository,
+        spec: Option<String>,
+        mut progress: impl gix::NestedProgress + 'static,
+        mut out: impl std::io::Write,
+        mut err: impl std::io::Write,
+        should_interrupt: &AtomicBool,
+        Options {
+            unreachable,
+            dangling,
+            lost_found,
+            connectivity_only,
+        }: Options,
+    ) -> anyhow::Result<()> {
+        repo.object_cache_size_if_unset(4 * 1024 * 1024);
+        // We expect to be finding a bunch of non-existent objects here - never refresh the ODB
+        repo.objects.refresh_never();
+
+        let tips = match spec {
+            Some(spec) => vec![repo
+                .rev_parse_single(spec.as_str())
+                .context("Only single revisions are supported")?
+                .detach()],
+            None => super::tips(&repo)?,
+        };
+
+        let config = repo.config_snapshot();
+        let mut options = gix_fsck::check::Options {
+            object_hash: repo.object_hash(),
+            verify_objects: !connectivity_only,
+            shallow_commits: repo
+                .shallow_commits()?
+                .map(|commits| commits.iter().copied().collect())
+                .unwrap_or_default(),
+            ..Default::default()
+        };
+        if let Some(path) = config.trusted_path(&Fsck::SKIP_LIST).transpose()? {
+            let data =
+                std::fs::read(&path).with_context(|| format!("Could not read skip-list at '{}'", path.display()))?;
+            options.skip_list = gix_fsck::skip_list::parse(&data, repo.object_hash())?;
+        }
+        for id in MessageId::ALL {
+            let Some(value) = config.string(format!("fsck.{id}").as_str()) else {
+                continue;
+            };
+            match Severity::from_bytes(value.as_ref()) {
+                Some(severity) => {
+                    options.severities.insert(*id, severity);
+                }
+                None => writeln!(err, "warning: ignoring invalid severity '{value}' of fsck.{id}")?,
+            }
+        }
+
+        let mut all_objects = repo.objects.iter()?.collect::<Result<Vec<_>, _>>()?.into_iter();
+        let outcome = gix_fsck::check(
+            &repo.objects,
+            tips,
+            Some(&mut all_objects),
+            &mut progress,
+            should_interrupt,
+            &options,
+        )?;
+
+        for problem in &outcome.problems {
+            print_problem(problem, &mut err)?;
+        }
+        let lost_found_dir = repo.common_dir().join("lost-found");
+        let mut buf = Vec::new();
+        for object in outcome.unreachable.iter().filter(|u| unreachable || u.is_dangling) {
+            if unreachable || dangling {
+                writeln!(
+                    out,
+                    "{} {} {}",
+                    if object.is_dangling { "dangling" } else { "unreachable" },
+                    object.kind,
+                    object.id
+                )?;
+            }
+            if lost_found && object.is_dangling {
+                let data = repo.objects.find(&object.id, &mut buf)?;
+                gix_fsck::lost_found::write(&lost_found_dir, &object.id, data.kind, data.data)?;
+            }
+        }
+
+        let num_errors = outcome.problems.iter().filter(|p| p.is_error()).count();
+        if num_errors != 0 {
+            bail!("Found {num_errors} error(s)");
+        }
+        Ok(())
+    }
+}
+
+/// Collect everything `git fsck` considers reachable by default: `HEAD`, all references and their reflogs,
+/// and everything in the index.
+fn tips(repo: &gix::Repository) -> anyhow::Result<Vec<ObjectId>> {
+    let mut tips = Vec::new();
+    let head = repo.head()?;
+    tips.extend(head.id().map(gix::Id::detach));
+    add_reflog(&mut tips, &mut head.log_iter());
+    for reference in repo.references()?.all()? {
+        let reference = reference.map_err(|err| anyhow::anyhow!(err))?;
+        tips.extend(reference.target().try_id().map(ToOwned::to_owned));
+        add_reflog(&mut tips, &mut reference.log_iter());
+    }
+    let index = repo.index_or_empty()?;
+    tips.extend(
+        index
+            .entries()
+            .iter()
+            .filter(|entry| !entry.mode.is_submodule())
+            .map(|entry| entry.id),
+    );
+    Ok(tips)
+}
+
+/// Add all object ids mentioned in the reflog of `platform` to `tips`, ignoring unreadable logs like `git` does.
+fn add_reflog(tips: &mut Vec<ObjectId>, platform: &mut gix::refs::file::log::iter::Platform<'_, '_>) {
+    if let Ok(Some(lines)) = platform.all() {
+        for line in lines.filter_map(Result::ok) {
+            tips.extend(
+                [line.previous_oid(), line.new_oid()]
+                    .into_iter()
+                    .filter(|id| !id.is_null()),
+            );
+        }
+    }
+}
+
+fn print_problem(problem: &Problem, mut err: impl std::io::Write) -> std::io::Result<()> {
+    match problem {
+        Problem::Missing {
+            id,
+            kind,
+            referenced_by,
+        } => {
+            let kind = kind.map_or_else(|| "object".to_owned(), |kind| kind.to_string());
+            if let Some(parent) = referenced_by {
+                writeln!(err, "broken link from {parent} to {kind} {id}")?;
+            }
+            writeln!(err, "missing {kind} {id}")
+        }
+        Problem::KindMismatch {
+            id,
+            expected,
+            actual,
+            referenced_by,
+        } => writeln!(
+            err,
+            "error: object {id} is a {actual}, not a {expected}, as expected by {referenced_by}"
+        ),
+        Problem::HashMismatch { id, kind, actual } => {
+            writeln!(err, "error: hash mismatch for {kind} {id}, its data hashes to {actual}")
+        }
+        Problem::Unreadable { id, source } => writeln!(err, "error: could not read object {id}: {source}"),
+        Problem::Invalid {
+            id,
+            kind,
+            message,
+            severity,
+        } => writeln!(
+            err,
+            "{} in {kind} {id}: {}: {}",
+            if *severity == Severity::Error {
+                "error"
+            } else {
+                "warning"
+            },
+            message.id,
+            message.detail
+        ),
     }
-    Ok(())
 }
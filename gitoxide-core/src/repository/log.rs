@@ -1,50 +1,78 @@
-use anyhow::bail;
-use gix::bstr::{BString, ByteSlice};
+use gix::{
+    bstr::BString,
+    pretty,
+    revision::walk::{Filter, Simplification, Sorting},
+};
 
-pub fn log(mut repo: gix::Repository, out: &mut dyn std::io::Write, path: Option<BString>) -> anyhow::Result<()> {
+/// How to show commits, overriding the configuration of the repository if set.
+#[derive(Debug, Default)]
+pub struct Format {
+    /// The name of a preset or alias, or a template like `format:%h %s`.
+    pub pretty: Option<BString>,
+    /// Show each commit on a single line with an abbreviated hash, unless `pretty` is set.
+    pub oneline: bool,
+    /// The format of dates, like `iso` or `relative`.
+    pub date: Option<BString>,
+    pub abbrev_commit: bool,
+    pub decorate: bool,
+    /// Use the mailmap to rewrite authors and committers in presets, or don't.
+    pub mailmap: Option<bool>,
+    pub graph: bool,
+    pub color: bool,
+}
+
+pub fn log(
+    mut repo: gix::Repository,
+    out: &mut dyn std::io::Write,
+    path: Option<BString>,
+    simplification: Simplification,
+    filter: Filter,
+    format: Format,
+) -> anyhow::Result<()> {
     repo.object_cache_size_if_unset(repo.compute_object_cache_size_for_tree_diffs(&**repo.index_or_empty()?));
 
-    if let Some(path) = path {
-        log_file(repo, out, path)
-    } else {
-        log_all(repo, out)
+    let mut options = repo.pretty_options()?;
+    if format.oneline {
+        options.format = pretty::Format::Oneline;
+        options.abbrev_commit = true;
     }
-}
+    if let Some(name) = &format.pretty {
+        options.format = repo.pretty_format(name.as_ref())?;
+    }
+    if let Some(date) = &format.date {
+        options.date = pretty::DateMode::from_bytes(date.as_ref())?;
+    }
+    options.abbrev_commit |= format.abbrev_commit;
+    options.decorate |= format.decorate;
+    options.mailmap = format.mailmap.unwrap_or(options.mailmap);
+    options.graph = format.graph;
+    options.color = format.color;
+    // Like Git, the graph needs all children of a commit to be shown before it.
+    let sorting = if format.graph {
+        Sorting::TopoOrder
+    } else {
+        Sorting::ByCommitTime(Default::default())
+    };
 
-fn log_all(repo: gix::Repository, out: &mut dyn std::io::Write) -> Result<(), anyhow::Error> {
     let head = repo.head()?.peel_to_commit()?;
-    let topo = gix::traverse::commit::topo::Builder::from_iters(&repo.objects, [head.id], None::<Vec<gix::ObjectId>>)
-        .build()?;
+    let mut walk = repo.rev_walk([head.id]).sorting(sorting).with_filter(filter);
+    if let Some(path) = path {
+        let index = repo.index_or_empty()?;
+        let pathspec = repo.pathspec(
+            false,
+            [path],
+            true,
+            &index,
+            gix::worktree::stack::state::attributes::Source::IdMapping,
+        )?;
+        walk = walk.with_pathspec(pathspec).with_simplification(simplification);
+    }
+    let commits = walk.all()?;
 
-    for info in topo {
+    let mut printer = repo.pretty_printer(options)?;
+    for info in commits {
         let info = info?;
-
-        write_info(&repo, &mut *out, &info)?;
+        printer.write_commit(&info.object()?, &info.parent_ids, out)?;
     }
-
-    Ok(())
-}
-
-fn log_file(_repo: gix::Repository, _out: &mut dyn std::io::Write, _path: BString) -> anyhow::Result<()> {
-    bail!("File-based lookup isn't yet implemented in a way that is competitively fast");
-}
-
-fn write_info(
-    repo: &gix::Repository,
-    mut out: impl std::io::Write,
-    info: &gix::traverse::commit::Info,
-) -> Result<(), std::io::Error> {
-    let commit = repo.find_commit(info.id).unwrap();
-
-    let message = commit.message_raw_sloppy();
-    let title = message.lines().next();
-
-    writeln!(
-        out,
-        "{} {}",
-        info.id.to_hex_with_len(8),
-        title.map_or_else(|| "<no message>".into(), BString::from)
-    )?;
-
     Ok(())
 }
@@ -13,7 +13,7 @@ pub mod blame;
 pub mod commit;
 pub mod config;
 mod credential;
-pub use credential::function as credential;
+pub use credential::{function as credential, helper as credential_helper};
 pub mod attributes;
 #[cfg(feature = "clean")]
 pub mod clean;
@@ -32,8 +32,8 @@ pub use clone::function::clone;
 pub use fetch::function::fetch;
 
 pub mod commitgraph;
-mod fsck;
-pub use fsck::function as fsck;
+pub mod fsck;
+pub use fsck::function::fsck;
 pub mod index;
 pub mod log;
 pub mod mailmap;
@@ -50,11 +50,14 @@ pub mod tree;
 pub mod verify;
 pub mod worktree;
 
-pub fn init(directory: Option<PathBuf>) -> Result<gix::discover::repository::Path> {
+pub fn init(directory: Option<PathBuf>, object_hash: gix::hash::Kind) -> Result<gix::discover::repository::Path> {
     gix::create::into(
         directory.unwrap_or_default(),
         gix::create::Kind::WithWorktree,
-        gix::create::Options::default(),
+        gix::create::Options {
+            object_hash,
+            ..Default::default()
+        },
     )
     .with_context(|| "Repository initialization failed")
 }
@@ -1,5 +1,5 @@
 use anyhow::bail;
-use gix::{commit::describe::SelectRef, prelude::ObjectIdExt, Repository, Submodule};
+use gix::{bstr::BString, commit::describe::SelectRef, prelude::ObjectIdExt, Repository, Submodule};
 
 use crate::OutputFormat;
 
@@ -74,3 +74,186 @@ fn submodule_short_hash(id: Option<gix::ObjectId>, repo: Option<&Repository>) ->
         |id| repo.map_or_else(|| id.to_string(), |repo| id.attach(repo).shorten_or_id().to_string()),
     )
 }
+
+/// Return the submodules of `repo` with the given `names`, or all of them if `names` is empty.
+fn select<'repo>(repo: &'repo Repository, names: &[BString]) -> anyhow::Result<Vec<Submodule<'repo>>> {
+    let Some(submodules) = repo.submodules()? else {
+        return Ok(Vec::new());
+    };
+    let submodules: Vec<_> = submodules.collect();
+    if let Some(name) = names
+        .iter()
+        .find(|name| !submodules.iter().any(|sm| sm.name() == *name))
+    {
+        bail!("No submodule named '{name}' found");
+    }
+    Ok(submodules
+        .into_iter()
+        .filter(|sm| names.is_empty() || names.iter().any(|name| sm.name() == name))
+        .collect())
+}
+
+pub fn init(repo: Repository, mut out: impl std::io::Write, names: Vec<BString>) -> anyhow::Result<()> {
+    for sm in select(&repo, &names)? {
+        if let Some(url) = sm.init()? {
+            writeln!(
+                out,
+                "Submodule '{}' ({url}) registered for path '{}'",
+                sm.name(),
+                sm.path()?
+            )?;
+        }
+    }
+    Ok(())
+}
+
+pub fn sync(repo: Repository, mut out: impl std::io::Write, names: Vec<BString>) -> anyhow::Result<()> {
+    for sm in select(&repo, &names)? {
+        if sm.sync()?.is_some() {
+            writeln!(out, "Synchronizing submodule url for '{}'", sm.path()?)?;
+        }
+    }
+    Ok(())
+}
+
+pub fn absorb_git_dirs(repo: Repository, mut out: impl std::io::Write, names: Vec<BString>) -> anyhow::Result<()> {
+    for sm in select(&repo, &names)? {
+        if sm.absorb_git_dir()? {
+            writeln!(
+                out,
+                "Migrating git directory of '{}' from '{}' to '{}'",
+                sm.path()?,
+                sm.work_dir()?.join(".git").display(),
+                sm.git_dir().display()
+            )?;
+        }
+    }
+    Ok(())
+}
+
+pub fn deinit(repo: Repository, mut out: impl std::io::Write, names: Vec<BString>) -> anyhow::Result<()> {
+    for sm in select(&repo, &names)? {
+        if sm.deinit()? {
+            writeln!(out, "Cleared directory '{}'", sm.path()?)?;
+        }
+        writeln!(out, "Submodule '{}' unregistered for path '{}'", sm.name(), sm.path()?)?;
+    }
+    Ok(())
+}
+
+pub fn move_to(repo: Repository, mut out: impl std::io::Write, name: BString, path: BString) -> anyhow::Result<()> {
+    let sm = select(&repo, std::slice::from_ref(&name))?
+        .pop()
+        .expect("present or error");
+    sm.move_to(path.as_ref())?;
+    writeln!(out, "Moved submodule '{name}' from '{}' to '{path}'", sm.path()?)?;
+    Ok(())
+}
+
+pub fn remove(repo: Repository, mut out: impl std::io::Write, names: Vec<BString>) -> anyhow::Result<()> {
+    for sm in select(&repo, &names)? {
+        sm.remove()?;
+        writeln!(out, "rm '{}'", sm.path()?)?;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "blocking-client")]
+pub mod add {
+    use gix::bstr::BString;
+
+    pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
+
+    pub fn add(
+        repo: gix::Repository,
+        mut progress: impl gix::NestedProgress + 'static,
+        mut out: impl std::io::Write,
+        url: BString,
+        path: BString,
+        name: Option<BString>,
+    ) -> anyhow::Result<()> {
+        let outcome = repo.add_submodule(
+            url.as_ref(),
+            path.as_ref(),
+            &gix::submodule::add::Options { name },
+            &mut progress,
+            &gix::interrupt::IS_INTERRUPTED,
+        )?;
+        writeln!(
+            out,
+            "Added submodule '{}' ({}) at '{path}', checked out '{}'",
+            outcome.name, outcome.url, outcome.id
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "blocking-client")]
+pub mod update {
+    use gix::{bstr::BString, submodule::update::Action};
+
+    pub struct Options {
+        pub names: Vec<BString>,
+        pub init: bool,
+        pub recursive: bool,
+        pub mode: Option<gix::submodule::config::Update>,
+        pub no_fetch: bool,
+        pub jobs: Option<usize>,
+    }
+
+    pub const PROGRESS_RANGE: std::ops::RangeInclusive<u8> = 1..=3;
+
+    pub fn update(
+        repo: gix::Repository,
+        mut progress: impl gix::NestedProgress + 'static,
+        mut out: impl std::io::Write,
+        Options {
+            names,
+            init,
+            recursive,
+            mode,
+            no_fetch,
+            jobs,
+        }: Options,
+    ) -> anyhow::Result<()> {
+        let outcome = repo.update_submodules(
+            &mut progress,
+            &gix::interrupt::IS_INTERRUPTED,
+            &gix::submodule::update::Options {
+                init,
+                names,
+                recursive,
+                mode,
+                fetch: !no_fetch,
+                jobs,
+            },
+        )?;
+        print_items(&outcome.submodules, "", &mut out)
+    }
+
+    fn print_items(
+        items: &[gix::submodule::update::Item],
+        prefix: &str,
+        out: &mut impl std::io::Write,
+    ) -> anyhow::Result<()> {
+        for item in items {
+            let path = format!("{prefix}{}", item.path);
+            if let Some(url) = &item.initialized_url {
+                writeln!(out, "Submodule '{}' ({url}) registered for path '{path}'", item.name)?;
+            }
+            if item.cloned {
+                writeln!(out, "Cloned submodule '{path}'")?;
+            }
+            match &item.action {
+                Action::Skipped => writeln!(out, "Skipping submodule '{path}'")?,
+                Action::UpToDate => {}
+                Action::CheckedOut(id) => writeln!(out, "Submodule path '{path}': checked out '{id}'")?,
+                Action::Rebased(id) => writeln!(out, "Submodule path '{path}': rebased into '{id}'")?,
+                Action::Merged(id) => writeln!(out, "Submodule path '{path}': merged in '{id}'")?,
+                Action::Command(id) => writeln!(out, "Submodule path '{path}': ran command with '{id}'")?,
+            }
+            print_items(&item.submodules, &format!("{path}/"), out)?;
+        }
+        Ok(())
+    }
+}
@@ -1,4 +1,4 @@
-use anyhow::bail;
+use anyhow::{bail, Context};
 
 use crate::OutputFormat;
 
@@ -27,3 +27,210 @@ pub fn list(repo: gix::Repository, out: &mut dyn std::io::Write, format: OutputF
     }
     Ok(())
 }
+
+pub mod add {
+    use std::path::PathBuf;
+
+    #[derive(Debug)]
+    pub struct Options {
+        pub format: crate::OutputFormat,
+        /// The name of a new branch to create and check out.
+        pub new_branch: Option<String>,
+        /// Detach `HEAD` in the new worktree.
+        pub detach: bool,
+        /// Leave the new worktree empty.
+        pub no_checkout: bool,
+        /// Lock the new worktree, with an optional reason.
+        pub lock: Option<Option<String>>,
+        /// Override safeguards.
+        pub force: bool,
+        /// The location of the new worktree.
+        pub path: PathBuf,
+        /// The commit or branch to check out, defaulting to `HEAD`.
+        pub commitish: Option<String>,
+    }
+}
+
+pub fn add<P>(
+    repo: gix::Repository,
+    mut progress: P,
+    out: &mut dyn std::io::Write,
+    opts: add::Options,
+) -> anyhow::Result<()>
+where
+    P: gix::NestedProgress + 'static,
+    P::SubProgress: gix::NestedProgress + 'static,
+{
+    use gix::worktree::add::Head;
+
+    let add::Options {
+        format,
+        new_branch,
+        detach,
+        no_checkout,
+        lock,
+        force,
+        path,
+        commitish,
+    } = opts;
+    if format != OutputFormat::Human {
+        bail!("JSON output isn't implemented yet");
+    }
+    let target = match commitish.as_deref() {
+        Some(spec) => repo.rev_parse_single(spec)?.object()?.peel_to_commit()?.id,
+        None => repo.head_commit()?.id,
+    };
+    let head = match (new_branch, commitish) {
+        (Some(name), _) => Head::NewBranch {
+            name: format!("refs/heads/{name}").try_into()?,
+            target,
+        },
+        (None, _) if detach => Head::Detached(target),
+        (None, Some(spec)) => match repo.try_find_reference(format!("refs/heads/{spec}").as_str())? {
+            Some(branch) => Head::Branch(branch.name().to_owned()),
+            None => Head::Detached(target),
+        },
+        (None, None) => {
+            let name = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .context("The worktree path needs a valid UTF-8 name to derive a branch from")?;
+            let name: gix::refs::FullName = format!("refs/heads/{name}").try_into()?;
+            match repo.try_find_reference(name.as_ref())? {
+                Some(_) => Head::Branch(name),
+                None => Head::NewBranch { name, target },
+            }
+        }
+    };
+    let (worktree, outcome) = repo.worktree_add(
+        &path,
+        head,
+        &mut progress,
+        &gix::interrupt::IS_INTERRUPTED,
+        gix::worktree::add::Options {
+            id: None,
+            checkout: !no_checkout,
+            lock_reason: lock.map(|reason| reason.unwrap_or_default().into()),
+            force,
+        },
+    )?;
+    writeln!(
+        out,
+        "{base} [{head}] ({files} files)",
+        base = worktree.workdir().expect("linked worktree").display(),
+        head = worktree
+            .head_name()?
+            .map_or("<detached>".into(), |name| name.shorten().to_owned()),
+        files = outcome.files_updated
+    )?;
+    Ok(())
+}
+
+pub fn remove(repo: gix::Repository, worktree: &str, force: u8) -> anyhow::Result<()> {
+    let proxy = find(&repo, worktree)?;
+    proxy.remove(gix::worktree::proxy::remove::Options {
+        force_dirty: force > 0,
+        force_locked: force > 1,
+    })?;
+    Ok(())
+}
+
+pub fn move_to(
+    repo: gix::Repository,
+    worktree: &str,
+    destination: &std::path::Path,
+    force: u8,
+    out: &mut dyn std::io::Write,
+) -> anyhow::Result<()> {
+    let new_base = find(&repo, worktree)?.move_to(destination, force > 1)?;
+    writeln!(out, "{}", new_base.display())?;
+    Ok(())
+}
+
+pub fn lock(repo: gix::Repository, worktree: &str, reason: Option<String>) -> anyhow::Result<()> {
+    let proxy = find(&repo, worktree)?;
+    if !proxy.lock(reason.as_deref().map(Into::into))? {
+        bail!(
+            "Worktree '{}' is already locked{}",
+            proxy.id(),
+            proxy
+                .lock_reason()
+                .filter(|reason| !reason.is_empty())
+                .map(|reason| format!(", reason: {reason}"))
+                .unwrap_or_default()
+        );
+    }
+    Ok(())
+}
+
+pub fn unlock(repo: gix::Repository, worktree: &str) -> anyhow::Result<()> {
+    let proxy = find(&repo, worktree)?;
+    if !proxy.unlock()? {
+        bail!("Worktree '{}' is not locked", proxy.id());
+    }
+    Ok(())
+}
+
+pub fn prune(
+    repo: gix::Repository,
+    dry_run: bool,
+    expire: Option<String>,
+    out: &mut dyn std::io::Write,
+) -> anyhow::Result<()> {
+    let expire = match expire {
+        Some(expire) => gix::config::tree::Gc::WORKTREE_PRUNE_EXPIRE.try_into_expiry(
+            gix::bstr::BStr::new(expire.as_bytes()).into(),
+            std::time::SystemTime::now(),
+        )?,
+        None => None,
+    };
+    for pruned in repo.worktrees_prune(gix::worktree::prune::Options { expire, dry_run })? {
+        writeln!(
+            out,
+            "{}Removing worktrees/{}: {}",
+            if dry_run { "[dry-run] " } else { "" },
+            pruned.id,
+            match pruned.reason {
+                gix::worktree::prune::Reason::NotADirectory => "not a valid directory",
+                gix::worktree::prune::Reason::MissingGitdirFile => "gitdir file does not exist",
+                gix::worktree::prune::Reason::InvalidGitdirFile => "invalid gitdir file",
+                gix::worktree::prune::Reason::MissingCheckout => "gitdir file points to non-existent location",
+            }
+        )?;
+    }
+    Ok(())
+}
+
+pub fn repair(repo: gix::Repository, paths: &[std::path::PathBuf], out: &mut dyn std::io::Write) -> anyhow::Result<()> {
+    for change in repo.worktrees_repair(paths)? {
+        match change {
+            gix::worktree::repair::Change::DotGitFile { id, path } => {
+                writeln!(
+                    out,
+                    "repair: {}: .git file now points to worktrees/{id}",
+                    path.display()
+                )?;
+            }
+            gix::worktree::repair::Change::GitdirFile { id, path } => {
+                writeln!(
+                    out,
+                    "repair: worktrees/{id}: gitdir file now points to {}",
+                    path.display()
+                )?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Find a linked worktree by its id or by the location of its checkout.
+fn find<'repo>(repo: &'repo gix::Repository, worktree: &str) -> anyhow::Result<gix::worktree::Proxy<'repo>> {
+    if let Some(proxy) = repo.worktree_proxy_by_id(worktree) {
+        return Ok(proxy);
+    }
+    let path = gix::path::realpath(worktree).ok();
+    repo.worktrees()?
+        .into_iter()
+        .find(|proxy| proxy.base().ok().and_then(|base| gix::path::realpath(base).ok()) == path && path.is_some())
+        .with_context(|| format!("'{worktree}' is not a linked worktree"))
+}
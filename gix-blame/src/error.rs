@@ -1,6 +1,6 @@
 use gix_object::bstr::BString;
 
-/// The error returned by [file()](crate::file()).
+/// The error returned by [file()](crate::file()) and [incremental()](crate::incremental()).
 #[derive(Debug, thiserror::Error)]
 #[allow(missing_docs)]
 pub enum Error {
@@ -29,10 +29,14 @@ pub enum Error {
     DiffTree(#[from] gix_diff::tree::Error),
     #[error(transparent)]
     DiffTreeWithRewrites(#[from] gix_diff::tree_with_rewrites::Error),
+    #[error("Failed to traverse the tree of a parent to find the files lines could have been copied from")]
+    TraverseTree(#[from] gix_traverse::tree::breadthfirst::Error),
     #[error("Invalid line range was given, line range is expected to be a 1-based inclusive range in the format '<start>,<end>'")]
     InvalidOneBasedLineRange,
     #[error("Failure to decode commit during traversal")]
     DecodeCommit(#[from] gix_object::decode::Error),
     #[error("Failed to get parent from commitgraph during traversal")]
     GetParentFromCommitGraph(#[from] gix_commitgraph::file::commit::Error),
+    #[error("The blame was cancelled by the delegate")]
+    Cancelled,
 }
@@ -1,4 +1,7 @@
-use std::num::NonZeroU32;
+use std::{
+    num::NonZeroU32,
+    ops::{ControlFlow, Range},
+};
 
 use gix_diff::{blob::intern::TokenSource, tree::Visit};
 use gix_hash::ObjectId;
@@ -9,8 +12,8 @@ use gix_object::{
 use gix_traverse::commit::find as find_commit;
 use smallvec::SmallVec;
 
-use super::{process_changes, Change, UnblamedHunk};
-use crate::{types::BlamePathEntry, BlameEntry, Error, Options, Outcome, Statistics};
+use super::{best_unchanged_region, process_changes, process_ignored_changes, score, Change, Split, UnblamedHunk};
+use crate::{types::BlamePathEntry, BlameEntry, CopySource, Error, MovesAndCopies, Options, Outcome, Statistics};
 
 /// Produce a list of consecutive [`BlameEntry`] instances to indicate in which commits the ranges of the file
 /// at `suspect:<file_path>` originated in.
@@ -70,6 +73,37 @@ pub fn file(
 ) -> Result<Outcome, Error> {
     let _span = gix_trace::coarse!("gix_blame::file()", ?file_path, ?suspect);
 
+    let mut entries = Vec::new();
+    let mut outcome = incremental(odb, suspect, cache, resource_cache, file_path, options, |entry| {
+        entries.push(entry);
+        ControlFlow::Continue(())
+    })?;
+
+    // I don’t know yet whether it would make sense to use a data structure instead that preserves
+    // order on insertion.
+    entries.sort_by_key(|a| a.start_in_blamed_file);
+    outcome.entries = coalesce_blame_entries(entries);
+    Ok(outcome)
+}
+
+/// Like [`file()`], but pass each [`BlameEntry`] to `delegate` as soon as the commit that introduced it is known,
+/// similar to `git blame --incremental`.
+///
+/// Entries are passed in no particular order, and adjacent entries of the same commit aren't merged.
+/// Return [`ControlFlow::Break`] from `delegate` to stop the blame, which then fails with [`Error::Cancelled`].
+///
+/// Note that [`Outcome::entries`] is always empty as all entries were passed to `delegate`.
+pub fn incremental(
+    odb: impl gix_object::Find + gix_object::FindHeader,
+    suspect: ObjectId,
+    cache: Option<gix_commitgraph::Graph>,
+    resource_cache: &mut gix_diff::blob::Platform,
+    file_path: &BStr,
+    mut options: Options,
+    mut delegate: impl FnMut(BlameEntry) -> ControlFlow<()>,
+) -> Result<Outcome, Error> {
+    let _span = gix_trace::coarse!("gix_blame::incremental()", ?file_path, ?suspect);
+
     let mut stats = Statistics::default();
     let (mut buf, mut buf2, mut buf3) = (Vec::new(), Vec::new(), Vec::new());
     let blamed_file_entry_id = find_path_entry_in_commit(
@@ -80,12 +114,19 @@ pub fn file(
         &mut buf,
         &mut buf2,
         &mut stats,
-    )?
-    .ok_or_else(|| Error::FileMissing {
-        file_path: file_path.to_owned(),
-        commit_id: suspect,
-    })?;
-    let blamed_file_blob = odb.find_blob(&blamed_file_entry_id, &mut buf)?.data.to_vec();
+    )?;
+    let worktree_blob = options.worktree_blob.take();
+    let is_worktree = worktree_blob.is_some();
+    let blamed_file_blob = match worktree_blob {
+        Some(blob) => blob,
+        None => {
+            let blamed_file_entry_id = blamed_file_entry_id.ok_or_else(|| Error::FileMissing {
+                file_path: file_path.to_owned(),
+                commit_id: suspect,
+            })?;
+            odb.find_blob(&blamed_file_entry_id, &mut buf)?.data.to_vec()
+        }
+    };
     let num_lines_in_blamed = tokens_for_diffing(&blamed_file_blob).tokenize().count() as u32;
 
     // Binary or otherwise empty?
@@ -93,28 +134,78 @@ pub fn file(
         return Ok(Outcome::default());
     }
 
+    // Uncommitted changes are blamed on a virtual commit with the null id, whose only parent is `suspect`.
+    let worktree_id = ObjectId::null(suspect.kind());
+    let first_suspect = if is_worktree { worktree_id } else { suspect };
     let ranges_to_blame = options.ranges.to_zero_based_exclusive_ranges(num_lines_in_blamed);
     let mut hunks_to_blame = ranges_to_blame
         .into_iter()
-        .map(|range| UnblamedHunk::new(range, suspect))
+        .map(|range| UnblamedHunk::new(range, first_suspect))
         .collect::<Vec<_>>();
 
+    let mut out = Vec::new();
+    let mut blame_path = if options.debug_track_path {
+        Some(Vec::new())
+    } else {
+        None
+    };
+    if is_worktree {
+        let Some(entry_id) = blamed_file_entry_id else {
+            unblamed_to_out_is_done(&mut hunks_to_blame, &mut out, worktree_id);
+            emit(&mut out, &mut delegate)?;
+            return Ok(Outcome {
+                entries: Vec::new(),
+                blob: blamed_file_blob,
+                statistics: stats,
+                blame_path,
+            });
+        };
+        let source_blob = odb.find_blob(&entry_id, &mut buf)?.data.to_vec();
+        let changes = changes_between(&source_blob, &blamed_file_blob, options.diff_algorithm);
+        stats.blobs_diffed += 1;
+        hunks_to_blame = process_changes(hunks_to_blame, changes, worktree_id, suspect);
+        if let Some(moves_and_copies) = options.moves_and_copies {
+            // There is no tree to find copies in, so only moves are detected.
+            let (mut buf, mut buf2) = (Vec::new(), Vec::new());
+            pass_blame_for_moves_and_copies(
+                &odb,
+                &mut hunks_to_blame,
+                worktree_id,
+                &blamed_file_blob,
+                file_path,
+                file_path,
+                &[(suspect, Some((entry_id, file_path.to_owned())))],
+                MovesAndCopies {
+                    copies: None,
+                    ..moves_and_copies
+                },
+                cache.as_ref(),
+                options.diff_algorithm,
+                &mut stats,
+                &mut gix_diff::tree::State::default(),
+                resource_cache,
+                &mut buf,
+                &mut buf2,
+            )?;
+        }
+        let previous = (suspect, file_path.to_owned());
+        blamed_hunks_to_out(&mut hunks_to_blame, &mut out, worktree_id, Some(&previous), false);
+    }
+
     let (mut buf, mut buf2) = (Vec::new(), Vec::new());
     let commit = find_commit(cache.as_ref(), &odb, &suspect, &mut buf)?;
     let mut queue: gix_revwalk::PriorityQueue<gix_date::SecondsSinceUnixEpoch, ObjectId> =
         gix_revwalk::PriorityQueue::new();
     queue.insert(commit.commit_time()?, suspect);
 
-    let mut out = Vec::new();
     let mut diff_state = gix_diff::tree::State::default();
-    let mut previous_entry: Option<(ObjectId, ObjectId)> = None;
-    let mut blame_path = if options.debug_track_path {
-        Some(Vec::new())
-    } else {
-        None
-    };
+    let mut previous_entry: Option<(ObjectId, BString, ObjectId)> = None;
+    // Hunks of the current suspect with a *Source File* at another path, which are processed separately.
+    let mut deferred_hunks = Vec::new();
 
     'outer: while let Some(suspect) = queue.pop_value() {
+        hunks_to_blame.append(&mut deferred_hunks);
+        emit(&mut out, &mut delegate)?;
         stats.commits_traversed += 1;
         if hunks_to_blame.is_empty() {
             break;
@@ -158,8 +249,8 @@ pub fn file(
                     if let Some(ref mut blame_path) = blame_path {
                         let entry = previous_entry
                             .take()
-                            .filter(|(id, _)| *id == suspect)
-                            .map(|(_, entry)| entry);
+                            .filter(|(id, path, _)| *id == suspect && *path == current_file_path)
+                            .map(|(_, _, entry)| entry);
 
                         let blame_path_entry = BlamePathEntry {
                             source_file_path: current_file_path.clone(),
@@ -179,10 +270,20 @@ pub fn file(
             continue;
         }
 
+        // Lines can only be blamed on one *Source File* at a time, so those that were copied from other files are
+        // blamed when `suspect` comes up again.
+        deferred_hunks.extend(extract_if(&mut hunks_to_blame, |hunk| {
+            hunk.has_suspect(&suspect)
+                && hunk.source_file_name.as_ref().map_or(file_path, |path| path.as_ref()) != current_file_path
+        }));
+        if !deferred_hunks.is_empty() {
+            queue.insert(commit_time, suspect);
+        }
+
         let mut entry = previous_entry
             .take()
-            .filter(|(id, _)| *id == suspect)
-            .map(|(_, entry)| entry);
+            .filter(|(id, path, _)| *id == suspect && *path == current_file_path)
+            .map(|(_, _, entry)| entry);
         if entry.is_none() {
             entry = find_path_entry_in_commit(
                 &odb,
@@ -200,7 +301,8 @@ pub fn file(
         };
 
         // This block asserts that, for every `UnblamedHunk`, all lines in the *Blamed File* are
-        // identical to the corresponding lines in the *Source File*.
+        // identical to the corresponding lines in the *Source File*, unless they were attributed to
+        // similar lines because of an ignored commit.
         #[cfg(debug_assertions)]
         {
             let source_blob = odb.find_blob(&entry_id, &mut buf)?.data.to_vec();
@@ -216,7 +318,7 @@ pub fn file(
                 .map(|token| blamed_interner.intern(token))
                 .collect();
 
-            for hunk in hunks_to_blame.iter() {
+            for hunk in hunks_to_blame.iter().filter(|hunk| !hunk.ignored) {
                 if let Some(range_in_suspect) = hunk.get_range(&suspect) {
                     let range_in_blamed_file = hunk.range_in_blamed_file.clone();
 
@@ -246,7 +348,7 @@ pub fn file(
             )? {
                 let no_change_in_entry = entry_id == parent_entry_id;
                 if pid == 0 {
-                    previous_entry = Some((*parent_id, parent_entry_id));
+                    previous_entry = Some((*parent_id, current_file_path.clone(), parent_entry_id));
                 }
                 if no_change_in_entry {
                     pass_blame_from_to(suspect, *parent_id, &mut hunks_to_blame);
@@ -256,6 +358,13 @@ pub fn file(
             }
         }
 
+        let is_ignored = options.ignore_revs.contains(&suspect);
+        // The parents to try again for lines that are still blamed on `suspect` if it is ignored.
+        let mut ignored_parents = Vec::new();
+        // The first parent that has the *Source File*, along with its path there.
+        let mut previous = None;
+        // Each parent along with the blob and path of the *Source File* in it, to find moved or copied lines.
+        let mut parent_origins = Vec::new();
         let more_than_one_parent = parent_ids.len() > 1;
         for (index, (parent_id, parent_commit_time)) in parent_ids.iter().enumerate() {
             queue.insert(*parent_commit_time, *parent_id);
@@ -288,11 +397,19 @@ pub fn file(
 
             match modification {
                 TreeDiffChange::Addition { id } => {
+                    parent_origins.push((*parent_id, None));
                     if more_than_one_parent {
                         // Do nothing under the assumption that this always (or almost always)
                         // implies that the file comes from a different parent, compared to which
                         // it was modified, not added.
-                    } else if unblamed_to_out_is_done(&mut hunks_to_blame, &mut out, suspect) {
+                    } else if options
+                        .moves_and_copies
+                        .is_some_and(|moves_and_copies| moves_and_copies.copies.is_some())
+                    {
+                        // The lines may have been copied from other files of the parent, which is checked below.
+                    } else if unblamed_to_out_is_done(&mut hunks_to_blame, &mut out, suspect)
+                        && deferred_hunks.is_empty()
+                    {
                         if let Some(ref mut blame_path) = blame_path {
                             let blame_path_entry = BlamePathEntry {
                                 source_file_path: current_file_path.clone(),
@@ -322,7 +439,12 @@ pub fn file(
                         options.diff_algorithm,
                         &mut stats,
                     )?;
-                    hunks_to_blame = process_changes(hunks_to_blame, changes.clone(), suspect, *parent_id);
+                    previous.get_or_insert_with(|| (*parent_id, current_file_path.clone()));
+                    parent_origins.push((*parent_id, Some((previous_id, current_file_path.clone()))));
+                    if is_ignored {
+                        ignored_parents.push((*parent_id, changes.clone(), previous_id, id, None));
+                    }
+                    hunks_to_blame = process_changes(hunks_to_blame, changes, suspect, *parent_id);
                     if let Some(ref mut blame_path) = blame_path {
                         let has_blame_been_passed = hunks_to_blame.iter().any(|hunk| hunk.has_suspect(parent_id));
 
@@ -354,6 +476,17 @@ pub fn file(
                         options.diff_algorithm,
                         &mut stats,
                     )?;
+                    previous.get_or_insert_with(|| (*parent_id, source_location.clone()));
+                    parent_origins.push((*parent_id, Some((source_id, source_location.clone()))));
+                    if is_ignored {
+                        ignored_parents.push((
+                            *parent_id,
+                            changes.clone(),
+                            source_id,
+                            id,
+                            Some(source_location.clone()),
+                        ));
+                    }
                     hunks_to_blame = process_changes(hunks_to_blame, changes, suspect, *parent_id);
 
                     let mut has_blame_been_passed = false;
@@ -383,21 +516,52 @@ pub fn file(
             }
         }
 
-        hunks_to_blame.retain_mut(|unblamed_hunk| {
-            if unblamed_hunk.suspects.len() == 1 {
-                if let Some(entry) = BlameEntry::from_unblamed_hunk(unblamed_hunk, suspect) {
-                    // At this point, we have copied blame for every hunk to a parent. Hunks
-                    // that have only `suspect` left in `suspects` have not passed blame to any
-                    // parent, and so they can be converted to a `BlameEntry` and moved to
-                    // `out`.
-                    out.push(entry);
-                    return false;
-                }
-            }
-            unblamed_hunk.remove_blame(suspect);
-            true
-        });
+        // Like `git`, lines that no parent could take the blame for are attributed to the most similar lines
+        // of each parent in turn if `suspect` is ignored.
+        for (parent_id, changes, previous_id, id, source_location) in &ignored_parents {
+            let blob = odb.find_blob(id, &mut buf)?.data.to_vec();
+            let previous_blob = odb.find_blob(previous_id, &mut buf)?.data.to_vec();
+            hunks_to_blame = process_ignored_changes(
+                hunks_to_blame,
+                changes,
+                suspect,
+                *parent_id,
+                &tokens_for_diffing(&blob).tokenize().collect::<Vec<_>>(),
+                &tokens_for_diffing(&previous_blob).tokenize().collect::<Vec<_>>(),
+                source_location.as_ref(),
+            );
+        }
+
+        if let Some(moves_and_copies) = options.moves_and_copies {
+            let blob = odb.find_blob(&entry_id, &mut buf)?.data.to_vec();
+            pass_blame_for_moves_and_copies(
+                &odb,
+                &mut hunks_to_blame,
+                suspect,
+                &blob,
+                current_file_path.as_ref(),
+                file_path,
+                &parent_origins,
+                moves_and_copies,
+                cache.as_ref(),
+                options.diff_algorithm,
+                &mut stats,
+                &mut diff_state,
+                resource_cache,
+                &mut buf,
+                &mut buf2,
+            )?;
+        }
+
+        blamed_hunks_to_out(
+            &mut hunks_to_blame,
+            &mut out,
+            suspect,
+            previous.as_ref(),
+            !ignored_parents.is_empty(),
+        );
     }
+    emit(&mut out, &mut delegate)?;
 
     debug_assert_eq!(
         hunks_to_blame,
@@ -405,17 +569,60 @@ pub fn file(
         "only if there is no portion of the file left we have completed the blame"
     );
 
-    // I don’t know yet whether it would make sense to use a data structure instead that preserves
-    // order on insertion.
-    out.sort_by(|a, b| a.start_in_blamed_file.cmp(&b.start_in_blamed_file));
     Ok(Outcome {
-        entries: coalesce_blame_entries(out),
+        entries: Vec::new(),
         blob: blamed_file_blob,
         statistics: stats,
         blame_path,
     })
 }
 
+/// Pass all entries in `out` to `delegate`, and fail if it wants to stop.
+fn emit(out: &mut Vec<BlameEntry>, delegate: &mut impl FnMut(BlameEntry) -> ControlFlow<()>) -> Result<(), Error> {
+    for entry in out.drain(..) {
+        if delegate(entry).is_break() {
+            return Err(Error::Cancelled);
+        }
+    }
+    Ok(())
+}
+
+/// Convert each hunk that only `suspect` is blamed for into a [`BlameEntry`] with `previous` for `out`,
+/// marking it as `unblamable` if needed, and let all other hunks forget about `suspect` as they were passed on to parents.
+fn blamed_hunks_to_out(
+    hunks_to_blame: &mut Vec<UnblamedHunk>,
+    out: &mut Vec<BlameEntry>,
+    suspect: ObjectId,
+    previous: Option<&(ObjectId, BString)>,
+    unblamable: bool,
+) {
+    hunks_to_blame.retain_mut(|unblamed_hunk| {
+        if unblamed_hunk.suspects.len() == 1 {
+            if let Some(entry) = BlameEntry::from_unblamed_hunk(unblamed_hunk, suspect) {
+                // At this point, we have copied blame for every hunk to a parent. Hunks
+                // that have only `suspect` left in `suspects` have not passed blame to any
+                // parent, and so they can be converted to a `BlameEntry` and moved to
+                // `out`.
+                out.push(BlameEntry {
+                    previous: previous.cloned(),
+                    unblamable,
+                    ..entry
+                });
+                return false;
+            }
+        }
+        unblamed_hunk.remove_blame(suspect);
+        true
+    });
+}
+
+/// Remove all hunks matching `predicate` from `hunks` and return them.
+fn extract_if(hunks: &mut Vec<UnblamedHunk>, mut predicate: impl FnMut(&UnblamedHunk) -> bool) -> Vec<UnblamedHunk> {
+    let (extracted, kept) = hunks.drain(..).partition(|hunk| predicate(hunk));
+    *hunks = kept;
+    extracted
+}
+
 /// Pass ownership of each unblamed hunk of `from` to `to`.
 ///
 /// This happens when `from` didn't actually change anything in the blamed file.
@@ -466,6 +673,9 @@ fn coalesce_blame_entries(lines_blamed: Vec<BlameEntry>) -> Vec<BlameEntry> {
                 let previous_source_range = previous_entry.range_in_source_file();
                 let current_source_range = entry.range_in_source_file();
                 if previous_entry.commit_id == entry.commit_id
+                    && previous_entry.source_file_name == entry.source_file_name
+                    && previous_entry.ignored == entry.ignored
+                    && previous_entry.unblamable == entry.unblamable
                     && previous_blamed_range.end == current_blamed_range.start
                     // As of 2024-09-19, the check below only is in `git`, but not in `libgit2`.
                     && previous_source_range.end == current_source_range.start
@@ -475,8 +685,7 @@ fn coalesce_blame_entries(lines_blamed: Vec<BlameEntry>) -> Vec<BlameEntry> {
                         start_in_source_file: previous_source_range.start as u32,
                         len: NonZeroU32::new((current_source_range.end - previous_source_range.start) as u32)
                             .expect("BUG: hunks are never zero-sized"),
-                        commit_id: previous_entry.commit_id,
-                        source_file_name: previous_entry.source_file_name.clone(),
+                        ..previous_entry.clone()
                     };
 
                     acc.pop();
@@ -748,7 +957,6 @@ fn tree_diff_with_rewrites_at_file_path(
 }
 
 #[allow(clippy::too_many_arguments)]
-#[cfg(not(feature = "blob-experimental"))]
 fn blob_changes(
     odb: impl gix_object::Find + gix_object::FindHeader,
     resource_cache: &mut gix_diff::blob::Platform,
@@ -759,8 +967,34 @@ fn blob_changes(
     diff_algorithm: gix_diff::blob::Algorithm,
     stats: &mut Statistics,
 ) -> Result<Vec<Change>, Error> {
-    use std::ops::Range;
+    resource_cache.set_resource(
+        previous_oid,
+        gix_object::tree::EntryKind::Blob,
+        previous_file_path,
+        gix_diff::blob::ResourceKind::OldOrSource,
+        &odb,
+    )?;
+    resource_cache.set_resource(
+        oid,
+        gix_object::tree::EntryKind::Blob,
+        file_path,
+        gix_diff::blob::ResourceKind::NewOrDestination,
+        &odb,
+    )?;
 
+    let outcome = resource_cache.prepare_diff()?;
+    let changes = changes_between(
+        outcome.old.data.as_slice().unwrap_or_default(),
+        outcome.new.data.as_slice().unwrap_or_default(),
+        diff_algorithm,
+    );
+    stats.blobs_diffed += 1;
+    Ok(changes)
+}
+
+/// Return the changes that turn the lines of `old` into the lines of `new`, covering all lines of `new`.
+#[cfg(not(feature = "blob-experimental"))]
+fn changes_between(old: &[u8], new: &[u8], diff_algorithm: gix_diff::blob::Algorithm) -> Vec<Change> {
     /// Record all [`Change`]s to learn about additions, deletions and unchanged portions of a *Source File*.
     struct ChangeRecorder {
         last_seen_after_end: u32,
@@ -814,79 +1048,46 @@ fn blob_changes(
         }
     }
 
-    resource_cache.set_resource(
-        previous_oid,
-        gix_object::tree::EntryKind::Blob,
-        previous_file_path,
-        gix_diff::blob::ResourceKind::OldOrSource,
-        &odb,
-    )?;
-    resource_cache.set_resource(
-        oid,
-        gix_object::tree::EntryKind::Blob,
-        file_path,
-        gix_diff::blob::ResourceKind::NewOrDestination,
-        &odb,
-    )?;
-
-    let outcome = resource_cache.prepare_diff()?;
-    let input = gix_diff::blob::intern::InternedInput::new(
-        tokens_for_diffing(outcome.old.data.as_slice().unwrap_or_default()),
-        tokens_for_diffing(outcome.new.data.as_slice().unwrap_or_default()),
-    );
+    let input = gix_diff::blob::intern::InternedInput::new(tokens_for_diffing(old), tokens_for_diffing(new));
     let number_of_lines_in_destination = input.after.len();
     let change_recorder = ChangeRecorder::new(number_of_lines_in_destination as u32);
 
-    let res = gix_diff::blob::diff(diff_algorithm, &input, change_recorder);
-    stats.blobs_diffed += 1;
-    Ok(res)
+    gix_diff::blob::diff(diff_algorithm, &input, change_recorder)
 }
 
-#[allow(clippy::too_many_arguments)]
+/// Return the changes that turn the lines of `old` into the lines of `new`, covering all lines of `new`.
 #[cfg(feature = "blob-experimental")]
-fn blob_changes(
-    odb: impl gix_object::Find + gix_object::FindHeader,
-    resource_cache: &mut gix_diff::blob::Platform,
-    oid: ObjectId,
-    previous_oid: ObjectId,
-    file_path: &BStr,
-    previous_file_path: &BStr,
-    diff_algorithm: gix_diff::blob::Algorithm,
-    stats: &mut Statistics,
-) -> Result<Vec<Change>, Error> {
+fn changes_between(old: &[u8], new: &[u8], diff_algorithm: gix_diff::blob::Algorithm) -> Vec<Change> {
     use gix_diff::blob::v2::Hunk;
 
-    resource_cache.set_resource(
-        previous_oid,
-        gix_object::tree::EntryKind::Blob,
-        previous_file_path,
-        gix_diff::blob::ResourceKind::OldOrSource,
-        &odb,
-    )?;
-    resource_cache.set_resource(
-        oid,
-        gix_object::tree::EntryKind::Blob,
-        file_path,
-        gix_diff::blob::ResourceKind::NewOrDestination,
-        &odb,
-    )?;
-
-    let outcome = resource_cache.prepare_diff()?;
-    let input = gix_diff::blob::v2::InternedInput::new(
-        outcome.old.data.as_slice().unwrap_or_default(),
-        outcome.new.data.as_slice().unwrap_or_default(),
-    );
-
-    let diff_algorithm: gix_diff::blob::v2::Algorithm = match diff_algorithm {
-        gix_diff::blob::Algorithm::Histogram => gix_diff::blob::v2::Algorithm::Histogram,
-        gix_diff::blob::Algorithm::Myers => gix_diff::blob::v2::Algorithm::Myers,
-        gix_diff::blob::Algorithm::MyersMinimal => gix_diff::blob::v2::Algorithm::MyersMinimal,
+    let input = gix_diff::blob::v2::InternedInput::new(old, new);
+
+    let hunks: Vec<Hunk> = match diff_algorithm {
+        gix_diff::blob::Algorithm::Patience => {
+            // `imara-diff` v0.2 doesn't implement the patience algorithm, so it's computed with our own implementation.
+            let input = gix_diff::blob::intern::InternedInput::new(tokens_for_diffing(old), tokens_for_diffing(new));
+            let mut hunks = Vec::new();
+            gix_diff::blob::diff(diff_algorithm, &input, |before, after| {
+                hunks.push(Hunk { before, after });
+            });
+            hunks
+        }
+        gix_diff::blob::Algorithm::Histogram
+        | gix_diff::blob::Algorithm::Myers
+        | gix_diff::blob::Algorithm::MyersMinimal => {
+            let diff_algorithm = match diff_algorithm {
+                gix_diff::blob::Algorithm::Histogram => gix_diff::blob::v2::Algorithm::Histogram,
+                gix_diff::blob::Algorithm::Myers => gix_diff::blob::v2::Algorithm::Myers,
+                _ => gix_diff::blob::v2::Algorithm::MyersMinimal,
+            };
+            let mut diff = gix_diff::blob::v2::Diff::compute(diff_algorithm, &input);
+            diff.postprocess_lines(&input);
+            diff.hunks().collect()
+        }
     };
-    let mut diff = gix_diff::blob::v2::Diff::compute(diff_algorithm, &input);
-    diff.postprocess_lines(&input);
 
     let mut last_seen_after_end = 0;
-    let mut changes = diff.hunks().fold(Vec::new(), |mut hunks, hunk| {
+    let mut changes = hunks.into_iter().fold(Vec::new(), |mut hunks, hunk| {
         let Hunk { before, after } = hunk;
 
         // This checks for unchanged hunks.
@@ -916,9 +1117,307 @@ fn blob_changes(
     if input.after.len() > last_seen_after_end as usize {
         changes.push(Change::Unchanged(last_seen_after_end..total_number_of_lines));
     }
+    changes
+}
+
+/// Like `git blame -M` and `git blame -C`, attribute lines of `hunks_to_blame` that are still only blamed on
+/// `suspect`, whose *Source File* at `file_path` has the content `blob`, to the lines of parents they were moved
+/// or copied from.
+///
+/// `parents` has the id of each parent along with the blob and path of the *Source File* in it, if it exists there.
+/// Lines found in a parent are blamed on it, under the path they were found at relative to `blamed_file_path`.
+#[allow(clippy::too_many_arguments)]
+fn pass_blame_for_moves_and_copies(
+    odb: impl gix_object::Find + gix_object::FindHeader,
+    hunks_to_blame: &mut Vec<UnblamedHunk>,
+    suspect: ObjectId,
+    blob: &[u8],
+    file_path: &BStr,
+    blamed_file_path: &BStr,
+    parents: &[(ObjectId, Option<(ObjectId, BString)>)],
+    moves_and_copies: MovesAndCopies,
+    cache: Option<&gix_commitgraph::Graph>,
+    diff_algorithm: gix_diff::blob::Algorithm,
+    stats: &mut Statistics,
+    state: &mut gix_diff::tree::State,
+    resource_cache: &mut gix_diff::blob::Platform,
+    buf: &mut Vec<u8>,
+    buf2: &mut Vec<u8>,
+) -> Result<(), Error> {
+    let lines: Vec<&[u8]> = tokens_for_diffing(blob).tokenize().collect();
+    let mut unblamed = extract_if(hunks_to_blame, |hunk| {
+        !hunk.ignored && hunk.suspects.len() == 1 && hunk.has_suspect(&suspect)
+    });
+    if unblamed.is_empty() {
+        return Ok(());
+    }
+
+    let score_of = |hunk: &UnblamedHunk| {
+        let range = &hunk.suspects[0].1;
+        score(&lines[range.start as usize..range.end as usize])
+    };
+    // Hunks with a score too low to ever be passed on, which stay with `suspect`.
+    let mut too_small = Vec::new();
+    let filter_small = |unblamed: &mut Vec<UnblamedHunk>, too_small: &mut Vec<UnblamedHunk>, min_score: u32| {
+        let (large, small): (Vec<_>, Vec<_>) = unblamed.drain(..).partition(|hunk| score_of(hunk) > min_score);
+        *unblamed = large;
+        too_small.extend(small);
+    };
+    let blame_path = |path: &BString| (path != blamed_file_path).then(|| path.clone());
+
+    let move_score = moves_and_copies.move_score;
+    filter_small(&mut unblamed, &mut too_small, move_score);
+    for (parent_id, origin) in parents {
+        let Some((parent_blob_id, parent_path)) = origin else {
+            continue;
+        };
+        if unblamed.is_empty() {
+            break;
+        }
+        let parent_blob = odb.find_blob(parent_blob_id, buf)?.data.to_vec();
+        let mut leftover = Vec::new();
+        while !unblamed.is_empty() {
+            let mut remaining = Vec::new();
+            for hunk in unblamed.drain(..) {
+                match find_copy_in_blob(&lines, &hunk, &parent_blob, diff_algorithm, stats) {
+                    Some(split) if split.score > move_score => split_hunk(
+                        hunk,
+                        split,
+                        *parent_id,
+                        blame_path(parent_path),
+                        &mut remaining,
+                        hunks_to_blame,
+                    ),
+                    _ => leftover.push(hunk),
+                }
+            }
+            unblamed = remaining;
+            filter_small(&mut unblamed, &mut too_small, move_score);
+        }
+        unblamed = leftover;
+    }
 
+    if let Some(copies) = moves_and_copies.copies {
+        match copies.score.cmp(&move_score) {
+            std::cmp::Ordering::Greater => filter_small(&mut unblamed, &mut too_small, copies.score),
+            std::cmp::Ordering::Less => {
+                unblamed.append(&mut too_small);
+                filter_small(&mut unblamed, &mut too_small, copies.score);
+            }
+            std::cmp::Ordering::Equal => {}
+        }
+
+        for (parent_id, origin) in parents {
+            if unblamed.is_empty() {
+                break;
+            }
+            let origin_path = origin.as_ref().map(|(_, path)| path);
+            let all_sources = match copies.source {
+                CopySource::FromSetOfModifiedFiles => false,
+                CopySource::FromSetOfModifiedFilesAndAllSourcesIfCreated => {
+                    origin_path.is_none_or(|path| path != file_path)
+                }
+                CopySource::FromAllSources => true,
+            };
+            let mut candidates = copy_candidates(
+                &odb,
+                suspect,
+                *parent_id,
+                all_sources,
+                cache,
+                stats,
+                state,
+                resource_cache,
+                buf,
+                buf2,
+            )?;
+            // Lines moved within the *Source File* were already found.
+            candidates.retain(|(path, _)| Some(path) != origin_path);
+            let candidates = candidates
+                .into_iter()
+                .map(|(path, blob_id)| Ok((path, odb.find_blob(&blob_id, buf)?.data.to_vec())))
+                .collect::<Result<Vec<_>, Error>>()?;
+
+            let mut leftover = Vec::new();
+            while !unblamed.is_empty() {
+                let mut best_splits: Vec<Option<(Split, &BString)>> = unblamed.iter().map(|_| None).collect();
+                for (path, candidate_blob) in &candidates {
+                    for (hunk, best) in unblamed.iter().zip(best_splits.iter_mut()) {
+                        let Some(split) = find_copy_in_blob(&lines, hunk, candidate_blob, diff_algorithm, stats) else {
+                            continue;
+                        };
+                        if best.as_ref().is_none_or(|(best, _)| split.score >= best.score) {
+                            *best = Some((split, path));
+                        }
+                    }
+                }
+
+                let mut remaining = Vec::new();
+                for (hunk, best) in unblamed.drain(..).zip(best_splits) {
+                    match best {
+                        Some((split, path)) if split.score > copies.score => {
+                            split_hunk(
+                                hunk,
+                                split,
+                                *parent_id,
+                                blame_path(path),
+                                &mut remaining,
+                                hunks_to_blame,
+                            );
+                        }
+                        _ => leftover.push(hunk),
+                    }
+                }
+                unblamed = remaining;
+                filter_small(&mut unblamed, &mut too_small, copies.score);
+            }
+            unblamed = leftover;
+        }
+    }
+
+    hunks_to_blame.extend(unblamed);
+    hunks_to_blame.extend(too_small);
+    Ok(())
+}
+
+/// Find the lines of `hunk`, which is only blamed on a suspect whose *Source File* consists of `lines`, in
+/// `parent_blob` and return the best match.
+fn find_copy_in_blob(
+    lines: &[&[u8]],
+    hunk: &UnblamedHunk,
+    parent_blob: &[u8],
+    diff_algorithm: gix_diff::blob::Algorithm,
+    stats: &mut Statistics,
+) -> Option<Split> {
+    let range = &hunk.suspects[0].1;
+    let lines = &lines[range.start as usize..range.end as usize];
+    let changes = changes_between(parent_blob, &lines.concat(), diff_algorithm);
     stats.blobs_diffed += 1;
-    Ok(changes)
+    best_unchanged_region(lines, &changes)
+}
+
+/// Split `hunk` so that the lines of `split` are blamed on `parent_id`, with `source_file_name`, and put them into
+/// `hunks_to_blame`. The lines before and after them are put into `remaining`.
+fn split_hunk(
+    hunk: UnblamedHunk,
+    split: Split,
+    parent_id: ObjectId,
+    source_file_name: Option<BString>,
+    remaining: &mut Vec<UnblamedHunk>,
+    hunks_to_blame: &mut Vec<UnblamedHunk>,
+) {
+    let (suspect, range_in_suspect) = hunk.suspects[0].clone();
+    let blamed_start = hunk.range_in_blamed_file.start;
+    let len = range_in_suspect.len() as u32;
+    let part = |range: Range<u32>| UnblamedHunk {
+        range_in_blamed_file: blamed_start + range.start..blamed_start + range.end,
+        suspects: [(
+            suspect,
+            range_in_suspect.start + range.start..range_in_suspect.start + range.end,
+        )]
+        .into(),
+        source_file_name: hunk.source_file_name.clone(),
+        ignored: false,
+    };
+    if split.range.start > 0 {
+        remaining.push(part(0..split.range.start));
+    }
+    if split.range.end < len {
+        remaining.push(part(split.range.end..len));
+    }
+    let start_in_parent = split.start_in_parent;
+    hunks_to_blame.push(UnblamedHunk {
+        range_in_blamed_file: blamed_start + split.range.start..blamed_start + split.range.end,
+        suspects: [(parent_id, start_in_parent..start_in_parent + split.range.len() as u32)].into(),
+        source_file_name,
+        ignored: false,
+    });
+}
+
+/// Return the path and blob id of all files in `parent_id` that lines in `suspect` could have been copied from,
+/// ordered by path. These are the sources of all changes in `suspect` as found with rename and copy tracking,
+/// or all files of `parent_id` if `all_sources` is `true`.
+#[allow(clippy::too_many_arguments)]
+fn copy_candidates(
+    odb: impl gix_object::Find + gix_object::FindHeader,
+    suspect: ObjectId,
+    parent_id: ObjectId,
+    all_sources: bool,
+    cache: Option<&gix_commitgraph::Graph>,
+    stats: &mut Statistics,
+    state: &mut gix_diff::tree::State,
+    resource_cache: &mut gix_diff::blob::Platform,
+    buf: &mut Vec<u8>,
+    buf2: &mut Vec<u8>,
+) -> Result<Vec<(BString, ObjectId)>, Error> {
+    let parent_tree_id = find_commit(cache, &odb, &parent_id, buf)?.tree_id()?;
+    let mut candidates: Vec<_> = if all_sources {
+        let mut recorder = gix_traverse::tree::Recorder::default();
+        gix_traverse::tree::depthfirst(
+            parent_tree_id,
+            gix_traverse::tree::depthfirst::State::default(),
+            &odb,
+            &mut recorder,
+        )?;
+        recorder
+            .records
+            .into_iter()
+            .filter(|entry| entry.mode.is_blob_or_symlink())
+            .map(|entry| (entry.filepath, entry.oid))
+            .collect()
+    } else {
+        let tree_id = find_commit(cache, &odb, &suspect, buf)?.tree_id()?;
+        let parent_tree_iter = odb.find_tree_iter(&parent_tree_id, buf)?;
+        let tree_iter = odb.find_tree_iter(&tree_id, buf2)?;
+        stats.trees_decoded += 2;
+        let mut candidates = Vec::new();
+        gix_diff::tree_with_rewrites(
+            parent_tree_iter,
+            tree_iter,
+            resource_cache,
+            state,
+            &odb,
+            |change| -> Result<_, std::convert::Infallible> {
+                use gix_diff::tree_with_rewrites::ChangeRef;
+                let source = match change {
+                    ChangeRef::Deletion {
+                        location,
+                        entry_mode,
+                        id,
+                        ..
+                    }
+                    | ChangeRef::Modification {
+                        location,
+                        previous_entry_mode: entry_mode,
+                        previous_id: id,
+                        ..
+                    }
+                    | ChangeRef::Rewrite {
+                        source_location: location,
+                        source_entry_mode: entry_mode,
+                        source_id: id,
+                        ..
+                    } => entry_mode.is_blob_or_symlink().then_some((location, id)),
+                    ChangeRef::Addition { .. } => None,
+                };
+                candidates.extend(source.map(|(location, id)| (location.to_owned(), id)));
+                Ok(gix_diff::tree_with_rewrites::Action::Continue)
+            },
+            gix_diff::tree_with_rewrites::Options {
+                location: Some(gix_diff::tree::recorder::Location::Path),
+                rewrites: Some(gix_diff::Rewrites {
+                    copies: Some(gix_diff::rewrites::Copies::default()),
+                    ..Default::default()
+                }),
+            },
+        )?;
+        stats.trees_diffed_with_rewrites += 1;
+        candidates
+    };
+    candidates.sort();
+    // The source of copies may also be modified.
+    candidates.dedup();
+    Ok(candidates)
 }
 
 fn find_path_entry_in_commit(
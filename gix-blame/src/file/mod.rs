@@ -361,6 +361,257 @@ fn process_changes(
     new_hunks_to_blame
 }
 
+/// Attribute the lines that only `suspect` is blamed for in `hunks_to_blame` to the most similar lines of `parent`,
+/// as `suspect` is ignored, and return all hunks.
+///
+/// `changes` turn the *Source File* in `parent` into the one in `suspect`, whose lines are `lines` and `parent_lines`
+/// respectively. Lines are only matched with lines they replaced, and those without any match remain blamed on `suspect`.
+/// If the *Source File* was renamed, `source_file_name` is its name in `parent`.
+fn process_ignored_changes(
+    hunks_to_blame: Vec<UnblamedHunk>,
+    changes: &[Change],
+    suspect: ObjectId,
+    parent: ObjectId,
+    lines: &[&[u8]],
+    parent_lines: &[&[u8]],
+    source_file_name: Option<&gix_object::bstr::BString>,
+) -> Vec<UnblamedHunk> {
+    // The lines that were added or replaced in `suspect`, along with the lines in `parent` they replaced.
+    let mut replaced = Vec::new();
+    let mut line_in_parent = 0;
+    for change in changes {
+        match change {
+            Change::Unchanged(range) => line_in_parent += range.len() as u32,
+            Change::AddedOrReplaced(range, number_of_lines_deleted) => {
+                replaced.push((range.clone(), line_in_parent..line_in_parent + number_of_lines_deleted));
+                line_in_parent += number_of_lines_deleted;
+            }
+            Change::Deleted(_, number_of_lines_deleted) => line_in_parent += number_of_lines_deleted,
+        }
+    }
+
+    let mut new_hunks_to_blame = Vec::with_capacity(hunks_to_blame.len());
+    for hunk in hunks_to_blame {
+        let range_in_suspect = match hunk.suspects.as_slice() {
+            [(id, range)] if *id == suspect => range.clone(),
+            _ => {
+                new_hunks_to_blame.push(hunk);
+                continue;
+            }
+        };
+        let Some((added, deleted)) = replaced
+            .iter()
+            .find(|(added, _)| added.start <= range_in_suspect.start && range_in_suspect.end <= added.end)
+        else {
+            new_hunks_to_blame.push(hunk);
+            continue;
+        };
+
+        let matches = guess_line_matches(
+            &lines[added.start as usize..added.end as usize],
+            &parent_lines[deleted.start as usize..deleted.end as usize],
+        );
+        let line_in_parent = |line: u32| matches[(line - added.start) as usize].map(|line| deleted.start + line);
+
+        // Split `hunk` into runs of lines that match consecutive lines in `parent`, or that don't match at all.
+        let mut start = range_in_suspect.start;
+        while start < range_in_suspect.end {
+            let first_line_in_parent = line_in_parent(start);
+            let mut end = start + 1;
+            while end < range_in_suspect.end
+                && match (first_line_in_parent, line_in_parent(end)) {
+                    (Some(first), Some(line)) => line == first + (end - start),
+                    (None, None) => true,
+                    _ => false,
+                }
+            {
+                end += 1;
+            }
+
+            let offset_in_hunk = start - range_in_suspect.start;
+            let range_in_blamed_file = (hunk.range_in_blamed_file.start + offset_in_hunk)
+                ..(hunk.range_in_blamed_file.start + offset_in_hunk + (end - start));
+            new_hunks_to_blame.push(match first_line_in_parent {
+                Some(first) => UnblamedHunk {
+                    range_in_blamed_file,
+                    suspects: [(parent, first..first + (end - start))].into(),
+                    source_file_name: source_file_name.cloned().or_else(|| hunk.source_file_name.clone()),
+                    ignored: true,
+                },
+                None => UnblamedHunk {
+                    range_in_blamed_file,
+                    suspects: [(suspect, start..end)].into(),
+                    source_file_name: hunk.source_file_name.clone(),
+                    ignored: hunk.ignored,
+                },
+            });
+            start = end;
+        }
+    }
+    new_hunks_to_blame
+}
+
+/// For each of `lines`, return the index of the most similar line in `parent_lines` like `git` does, so that the order of lines
+/// is preserved.
+///
+/// Lines that aren't similar to any line fall back to the line at the same index in `parent_lines`, if there is one.
+fn guess_line_matches(lines: &[&[u8]], parent_lines: &[&[u8]]) -> Vec<Option<u32>> {
+    let fingerprints: Vec<_> = lines.iter().map(|line| fingerprint(line)).collect();
+    let parent_fingerprints: Vec<_> = parent_lines.iter().map(|line| fingerprint(line)).collect();
+    let mut matches = vec![None; lines.len()];
+    match_most_similar_lines(
+        &fingerprints,
+        0..lines.len(),
+        &parent_fingerprints,
+        0..parent_lines.len(),
+        &mut matches,
+    );
+    for (index, line_in_parent) in matches.iter_mut().enumerate() {
+        if line_in_parent.is_none() && index < parent_lines.len() {
+            *line_in_parent = Some(index as u32);
+        }
+    }
+    matches
+}
+
+/// The maximum distance of lines to the proportionally same line in the parent to be considered as match.
+const MAX_SEARCH_DISTANCE: usize = 10;
+
+/// Find the most similar pair of lines in `range` and `parent_range`, record it in `matches`, and repeat for the lines
+/// before and after it.
+fn match_most_similar_lines(
+    fingerprints: &[Vec<u16>],
+    range: Range<usize>,
+    parent_fingerprints: &[Vec<u16>],
+    parent_range: Range<usize>,
+    matches: &mut [Option<u32>],
+) {
+    if range.is_empty() || parent_range.is_empty() {
+        return;
+    }
+    // Like `git`, only search near the line at the same relative position to keep this fast for large changes.
+    let max_distance = (2 * MAX_SEARCH_DISTANCE + 1) * parent_range.len() / range.len() + 1;
+    let mut best = None;
+    let mut best_similarity = 0;
+    for line in range.clone() {
+        let expected = parent_range.start + (line - range.start) * parent_range.len() / range.len();
+        let candidates = expected.saturating_sub(max_distance).max(parent_range.start)
+            ..(expected + max_distance + 1).min(parent_range.end);
+        for parent_line in candidates {
+            let similarity = similarity(&fingerprints[line], &parent_fingerprints[parent_line]);
+            if similarity > best_similarity {
+                best_similarity = similarity;
+                best = Some((line, parent_line));
+            }
+        }
+    }
+    let Some((line, parent_line)) = best else {
+        return;
+    };
+    matches[line] = Some(parent_line as u32);
+    match_most_similar_lines(
+        fingerprints,
+        range.start..line,
+        parent_fingerprints,
+        parent_range.start..parent_line,
+        matches,
+    );
+    match_most_similar_lines(
+        fingerprints,
+        line + 1..range.end,
+        parent_fingerprints,
+        parent_line + 1..parent_range.end,
+        matches,
+    );
+}
+
+/// Return the sorted pairs of adjacent bytes in `line`, with whitespace normalized to `0` and letters in lower case,
+/// without pairs of whitespace, just like `git` does.
+fn fingerprint(line: &[u8]) -> Vec<u16> {
+    let mut pairs = Vec::with_capacity(line.len() + 1);
+    let mut previous = 0u8;
+    for byte in line.iter().copied().map(Some).chain(Some(None)) {
+        let current = match byte {
+            Some(byte) if !byte.is_ascii_whitespace() => byte.to_ascii_lowercase(),
+            _ => 0,
+        };
+        let pair = u16::from(previous) | (u16::from(current) << 8);
+        if pair != 0 {
+            pairs.push(pair);
+        }
+        previous = current;
+    }
+    pairs.sort_unstable();
+    pairs
+}
+
+/// Return the amount of pairs that the sorted fingerprints `a` and `b` have in common.
+fn similarity(a: &[u16], b: &[u16]) -> usize {
+    let (mut a, mut b) = (a.iter().peekable(), b.iter().peekable());
+    let mut common = 0;
+    while let (Some(lhs), Some(rhs)) = (a.peek(), b.peek()) {
+        match lhs.cmp(rhs) {
+            std::cmp::Ordering::Less => {
+                a.next();
+            }
+            std::cmp::Ordering::Greater => {
+                b.next();
+            }
+            std::cmp::Ordering::Equal => {
+                common += 1;
+                a.next();
+                b.next();
+            }
+        }
+    }
+    common
+}
+
+/// The part of a hunk whose lines were found in a blob of a parent, as determined by [`best_unchanged_region()`].
+#[derive(Debug, PartialEq)]
+struct Split {
+    /// The lines of the hunk that were found, relative to its start.
+    range: Range<u32>,
+    /// The line in the blob of the parent at which they start.
+    start_in_parent: u32,
+    /// The score of the lines as computed by [`score()`].
+    score: u32,
+}
+
+/// Return the score `git` uses to decide if `lines` are significant enough to have been moved or copied, which is
+/// the number of alphanumeric ASCII characters in them plus one.
+fn score(lines: &[&[u8]]) -> u32 {
+    1 + lines
+        .iter()
+        .flat_map(|line| line.iter())
+        .filter(|byte| byte.is_ascii_alphanumeric())
+        .count() as u32
+}
+
+/// Return the region of `lines` with the highest score that `changes`, which turn a blob of a parent into `lines`,
+/// leave unchanged. Like in `git`, later regions win if their score is the same.
+fn best_unchanged_region(lines: &[&[u8]], changes: &[Change]) -> Option<Split> {
+    let mut best: Option<Split> = None;
+    let mut line_in_parent = 0;
+    for change in changes {
+        match change {
+            Change::Unchanged(range) => {
+                let score = score(&lines[range.start as usize..range.end as usize]);
+                if best.as_ref().is_none_or(|best| score >= best.score) {
+                    best = Some(Split {
+                        range: range.clone(),
+                        start_in_parent: line_in_parent,
+                        score,
+                    });
+                }
+                line_in_parent += range.len() as u32;
+            }
+            Change::AddedOrReplaced(_, deleted) | Change::Deleted(_, deleted) => line_in_parent += deleted,
+        }
+    }
+    best
+}
+
 impl UnblamedHunk {
     fn shift_by(mut self, suspect: ObjectId, offset: Offset) -> Self {
         if let Some(entry) = self.suspects.iter_mut().find(|entry| entry.0 == suspect) {
@@ -394,12 +645,14 @@ impl UnblamedHunk {
                             ..(self.range_in_blamed_file.start + split_at_from_start),
                         suspects: new_suspects_before.collect(),
                         source_file_name: self.source_file_name.clone(),
+                        ignored: self.ignored,
                     };
                     let new_hunk_after = Self {
                         range_in_blamed_file: (self.range_in_blamed_file.start + split_at_from_start)
                             ..(self.range_in_blamed_file.end),
                         suspects: new_suspects_after.collect(),
                         source_file_name: self.source_file_name,
+                        ignored: self.ignored,
                     };
 
                     Either::Right((new_hunk_before, new_hunk_after))
@@ -448,6 +701,9 @@ impl BlameEntry {
             len: force_non_zero(range_in_source_file.len() as u32),
             commit_id,
             source_file_name: unblamed_hunk.source_file_name.clone(),
+            previous: None,
+            ignored: unblamed_hunk.ignored,
+            unblamable: false,
         })
     }
 }
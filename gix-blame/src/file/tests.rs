@@ -31,6 +31,7 @@ impl From<(Range<u32>, ObjectId, Range<u32>)> for UnblamedHunk {
             range_in_blamed_file,
             suspects: [(suspect, range_in_destination)].into(),
             source_file_name: None,
+            ignored: false,
         }
     }
 }
@@ -986,6 +987,55 @@ mod process_changes {
     }
 }
 
+mod best_unchanged_region {
+    use crate::file::{best_unchanged_region, score, Change, Split};
+
+    #[test]
+    fn score_counts_alphanumeric_characters() {
+        assert_eq!(score(&[]), 1);
+        assert_eq!(score(&[b"a1 {\n", b"}\n", b"_b\n"]), 4);
+    }
+
+    #[test]
+    fn the_region_with_the_highest_score_wins() {
+        let lines: [&[u8]; 4] = [b"ab\n", b"new\n", b"abcd\n", b"x\n"];
+        let changes = [
+            Change::Unchanged(0..1),
+            Change::AddedOrReplaced(1..2, 2),
+            Change::Unchanged(2..3),
+            Change::Deleted(3, 1),
+            Change::Unchanged(3..4),
+        ];
+        assert_eq!(
+            best_unchanged_region(&lines, &changes),
+            Some(Split {
+                range: 2..3,
+                start_in_parent: 3,
+                score: 5
+            })
+        );
+    }
+
+    #[test]
+    fn later_regions_win_on_equal_scores() {
+        let lines: [&[u8]; 3] = [b"ab\n", b"new\n", b"ba\n"];
+        let changes = [
+            Change::Unchanged(0..1),
+            Change::AddedOrReplaced(1..2, 0),
+            Change::Unchanged(2..3),
+        ];
+        assert_eq!(
+            best_unchanged_region(&lines, &changes),
+            Some(Split {
+                range: 2..3,
+                start_in_parent: 1,
+                score: 3
+            })
+        );
+        assert_eq!(best_unchanged_region(&lines, &[Change::AddedOrReplaced(0..3, 1)]), None);
+    }
+}
+
 mod blame_ranges {
     use crate::{BlameRanges, Error};
 
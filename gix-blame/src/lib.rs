@@ -17,7 +17,9 @@
 mod error;
 pub use error::Error;
 mod types;
-pub use types::{BlameEntry, BlamePathEntry, BlameRanges, Options, Outcome, Statistics};
+pub use types::{
+    BlameEntry, BlamePathEntry, BlameRanges, Copies, CopySource, MovesAndCopies, Options, Outcome, Statistics,
+};
 
 mod file;
-pub use file::function::file;
+pub use file::function::{file, incremental};
@@ -119,7 +119,7 @@ impl BlameRanges {
                 non_overlapping.push(merged_range);
 
                 *ranges = non_overlapping;
-                ranges.sort_by(|a, b| a.start.cmp(&b.start));
+                ranges.sort_by_key(|a| a.start);
             }
             Self::WholeFile => *self = Self::PartialFile(vec![new_range]),
         }
@@ -164,6 +164,76 @@ pub struct Options {
     /// Collect debug information whenever there's a diff or rename that affects the outcome of a
     /// blame.
     pub debug_track_path: bool,
+    /// The commits whose changes should be ignored, like `git blame --ignore-rev` does.
+    ///
+    /// Lines changed by these commits are attributed to the most similar line in a parent instead, and if there is
+    /// none, they remain attributed to the ignored commit and are marked as [unblamable](BlameEntry::unblamable).
+    pub ignore_revs: Vec<ObjectId>,
+    /// If set, the content of the *Blamed File* with uncommitted changes, typically as read from the worktree and
+    /// converted into what would be stored in `git`.
+    ///
+    /// It's blamed as if it was committed on top of the first suspect, in a commit whose id is the
+    /// [null id](ObjectId::null()), and it may also be new, in which case all of its lines are attributed to that commit.
+    pub worktree_blob: Option<Vec<u8>>,
+    /// If set, lines that were moved within the *Source File*, or copied from other files, are attributed to the
+    /// commit that introduced them there, like `git blame -M` and `git blame -C` do.
+    ///
+    /// Note that copies are not detected for uncommitted changes in the [worktree blob](Self::worktree_blob).
+    pub moves_and_copies: Option<MovesAndCopies>,
+}
+
+/// Configure how lines that were moved within a file or copied from other files are detected, similar to
+/// `git blame -M` and `git blame -C`.
+///
+/// Scores are computed like `git` does, as the number of alphanumeric ASCII characters in the lines in question
+/// plus one, and lines are only attributed to their origin if their score is greater than the respective threshold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MovesAndCopies {
+    /// The score that lines moved within the *Source File* must exceed, like `git blame -M<num>`.
+    pub move_score: u32,
+    /// If set, also detect lines that were copied from other files of a parent, like `git blame -C`.
+    pub copies: Option<Copies>,
+}
+
+impl Default for MovesAndCopies {
+    /// Detect moves with the score `git` uses by default, but no copies.
+    fn default() -> Self {
+        MovesAndCopies {
+            move_score: 20,
+            copies: None,
+        }
+    }
+}
+
+/// Configure how lines copied from other files are detected, like `git blame -C`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Copies {
+    /// The files to consider as the origin of copied lines.
+    pub source: CopySource,
+    /// The score that copied lines must exceed, like `git blame -C<num>`.
+    pub score: u32,
+}
+
+impl Default for Copies {
+    /// Look for copies in modified files with the score `git` uses by default.
+    fn default() -> Self {
+        Copies {
+            source: CopySource::FromSetOfModifiedFiles,
+            score: 40,
+        }
+    }
+}
+
+/// The files of a parent to look for copied lines in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopySource {
+    /// Only consider files that were modified or deleted in the commit, like `git blame -C`.
+    FromSetOfModifiedFiles,
+    /// Like [`FromSetOfModifiedFiles`](Self::FromSetOfModifiedFiles), but consider all files of the parent
+    /// if the *Source File* was created or renamed in the commit, like `git blame -C -C`.
+    FromSetOfModifiedFilesAndAllSourcesIfCreated,
+    /// Consider all files of the parent, like `git blame -C -C -C`. This is the most expensive option.
+    FromAllSources,
 }
 
 /// Represents a change during history traversal for blame. It is supposed to capture enough
@@ -325,6 +395,17 @@ pub struct BlameEntry {
     /// The *Source File*'s name, in case it differs from *Blamed File*'s name.
     /// This happens when the file was renamed.
     pub source_file_name: Option<BString>,
+    /// The first parent of `commit_id` that contains the *Source File*, along with the path of the *Source File* in it,
+    /// or `None` if the *Source File* was added by `commit_id`.
+    ///
+    /// This is the same for all entries of a commit, and what `git blame --porcelain` shows as `previous`.
+    pub previous: Option<(ObjectId, BString)>,
+    /// If `true`, the lines were changed by an [ignored commit](Options::ignore_revs) and attributed to the
+    /// most similar lines of `commit_id` instead.
+    pub ignored: bool,
+    /// If `true`, the lines were changed by `commit_id`, which is [ignored](Options::ignore_revs), but couldn't be
+    /// attributed to a similar line of any of its parents.
+    pub unblamable: bool,
 }
 
 impl BlameEntry {
@@ -351,6 +432,9 @@ impl BlameEntry {
             len: NonZeroU32::new(range_in_blamed_file.len() as u32).expect("BUG: hunks are never empty"),
             commit_id,
             source_file_name,
+            previous: None,
+            ignored: false,
+            unblamable: false,
         }
     }
 }
@@ -389,6 +473,9 @@ pub struct UnblamedHunk {
     pub suspects: SmallVec<[(ObjectId, Range<u32>); 1]>,
     /// The *Source File*'s name, in case it differs from *Blamed File*'s name.
     pub source_file_name: Option<BString>,
+    /// If `true`, the hunk was attributed to the most similar lines of a parent of an ignored commit, so its
+    /// lines in the suspects differ from the ones in the *Blamed File*.
+    pub ignored: bool,
 }
 
 impl UnblamedHunk {
@@ -400,6 +487,7 @@ impl UnblamedHunk {
             range_in_blamed_file: range_start..range_end,
             suspects: [(suspect, range_start..range_end)].into(),
             source_file_name: None,
+            ignored: false,
         }
     }
 
@@ -7,6 +7,7 @@ use gix_object::bstr;
 struct Baseline<'a> {
     lines: bstr::Lines<'a>,
     filenames: BTreeMap<ObjectId, bstr::BString>,
+    previous: BTreeMap<ObjectId, (ObjectId, bstr::BString)>,
 }
 
 mod baseline {
@@ -49,6 +50,7 @@ mod baseline {
             let baseline = Baseline {
                 lines: content.lines(),
                 filenames: BTreeMap::default(),
+                previous: BTreeMap::default(),
             };
 
             Ok(baseline
@@ -122,6 +124,10 @@ mod baseline {
                     source_file_name = Some(fields[1].into());
 
                     self.filenames.insert(commit_id, fields[1].into());
+                } else if fields[0] == "previous" {
+                    // Like the file name, this is only shown the first time a commit is shown.
+                    let previous_id = ObjectId::from_hex(fields[1].as_bytes()).unwrap();
+                    self.previous.insert(commit_id, (previous_id, fields[2].into()));
                 } else if !is_known_header_field(&fields[0]) && ObjectId::from_hex(fields[0].as_bytes()).is_err() {
                     panic!("unexpected line: '{:?}'", line.as_bstr());
                 }
@@ -131,12 +137,15 @@ mod baseline {
                 // No new lines were parsed, so we assume the iterator is finished.
                 return None;
             };
-            Some(BlameEntry::new(
-                range_in_blamed_file,
-                range_in_source_file,
-                commit_id,
-                source_file_name.or_else(|| self.filenames.get(&commit_id).cloned()),
-            ))
+            Some(BlameEntry {
+                previous: self.previous.get(&commit_id).cloned(),
+                ..BlameEntry::new(
+                    range_in_blamed_file,
+                    range_in_source_file,
+                    commit_id,
+                    source_file_name.or_else(|| self.filenames.get(&commit_id).cloned()),
+                )
+            })
         }
     }
 }
@@ -233,6 +242,9 @@ macro_rules! mktest {
                     since: None,
                     rewrites: Some(gix_diff::Rewrites::default()),
                     debug_track_path: false,
+                    ignore_revs: Vec::new(),
+                    worktree_blob: None,
+                    moves_and_copies: None,
                 },
             )?
             .entries;
@@ -334,6 +346,9 @@ fn diff_disparity_base() {
                 since: None,
                 rewrites: Some(gix_diff::Rewrites::default()),
                 debug_track_path: false,
+                ignore_revs: Vec::new(),
+                worktree_blob: None,
+                moves_and_copies: None,
             },
         )
         .unwrap()
@@ -401,6 +416,9 @@ fn since() -> gix_testtools::Result {
             since: Some(gix_date::parse("2025-01-31", None)?),
             rewrites: Some(gix_diff::Rewrites::default()),
             debug_track_path: false,
+            ignore_revs: Vec::new(),
+            worktree_blob: None,
+            moves_and_copies: None,
         },
     )?
     .entries;
@@ -441,6 +459,9 @@ mod blame_ranges {
                 since: None,
                 rewrites: Some(gix_diff::Rewrites::default()),
                 debug_track_path: false,
+                ignore_revs: Vec::new(),
+                worktree_blob: None,
+                moves_and_copies: None,
             },
         )?
         .entries;
@@ -484,6 +505,9 @@ mod blame_ranges {
                 since: None,
                 rewrites: None,
                 debug_track_path: false,
+                ignore_revs: Vec::new(),
+                worktree_blob: None,
+                moves_and_copies: None,
             },
         )?
         .entries;
@@ -525,6 +549,9 @@ mod blame_ranges {
                 since: None,
                 rewrites: None,
                 debug_track_path: false,
+                ignore_revs: Vec::new(),
+                worktree_blob: None,
+                moves_and_copies: None,
             },
         )?
         .entries;
@@ -571,6 +598,9 @@ mod rename_tracking {
                 since: None,
                 rewrites: Some(gix_diff::Rewrites::default()),
                 debug_track_path: false,
+                ignore_revs: Vec::new(),
+                worktree_blob: None,
+                moves_and_copies: None,
             },
         )?
         .entries;
@@ -586,6 +616,345 @@ mod rename_tracking {
     }
 }
 
+mod ignore_revs {
+    use gix_blame::BlameRanges;
+    use gix_object::bstr::ByteSlice;
+
+    use crate::{Baseline, Fixture};
+
+    #[test]
+    fn lines_of_ignored_commits_are_attributed_to_their_parents() -> gix_testtools::Result {
+        let worktree_path = gix_testtools::scripted_fixture_read_only("make_blame_ignore_revs_repo.sh")?;
+
+        let Fixture {
+            odb,
+            mut resource_cache,
+            suspect,
+        } = Fixture::for_worktree_path(worktree_path.to_path_buf())?;
+
+        let git_dir = worktree_path.join(".git");
+        let ignored = gix_hash::ObjectId::from_hex(std::fs::read(git_dir.join("ignored-commit"))?.trim())?;
+        let source_file_name = "file.txt";
+        let lines_blamed = gix_blame::file(
+            &odb,
+            suspect,
+            None,
+            &mut resource_cache,
+            source_file_name.into(),
+            gix_blame::Options {
+                diff_algorithm: gix_diff::blob::Algorithm::Histogram,
+                ranges: BlameRanges::default(),
+                since: None,
+                rewrites: Some(gix_diff::Rewrites::default()),
+                debug_track_path: false,
+                ignore_revs: vec![ignored],
+                worktree_blob: None,
+                moves_and_copies: None,
+            },
+        )?
+        .entries;
+
+        let baseline = Baseline::collect(git_dir.join("ignore-rev.baseline"), source_file_name.into())?;
+        let without_marks: Vec<_> = lines_blamed
+            .iter()
+            .cloned()
+            .map(|entry| gix_blame::BlameEntry {
+                ignored: false,
+                unblamable: false,
+                ..entry
+            })
+            .collect();
+        pretty_assertions::assert_eq!(without_marks, baseline);
+
+        // Git prefixes lines with `?` if they were passed on by an ignored commit, and with `*` if that wasn't possible.
+        let expected_marks: Vec<_> = std::fs::read(git_dir.join("ignore-rev-marks.baseline"))?
+            .lines()
+            .map(|line| {
+                let line = line.strip_prefix(b"^").unwrap_or(line);
+                (line.starts_with(b"?"), line.starts_with(b"*"))
+            })
+            .collect();
+        let actual_marks: Vec<_> = lines_blamed
+            .iter()
+            .flat_map(|entry| std::iter::repeat_n((entry.ignored, entry.unblamable), entry.len.get() as usize))
+            .collect();
+        assert_eq!(actual_marks, expected_marks);
+        assert_eq!(
+            lines_blamed.iter().filter(|entry| entry.unblamable).count(),
+            1,
+            "the line added by the ignored commit is still attributed to it"
+        );
+
+        Ok(())
+    }
+}
+
+mod moves_and_copies {
+    use gix_blame::{BlameRanges, Copies, CopySource, MovesAndCopies};
+
+    use crate::{Baseline, Fixture};
+
+    fn blame(
+        source_file_name: &str,
+        moves_and_copies: Option<MovesAndCopies>,
+        baseline_name: &str,
+    ) -> gix_testtools::Result<(Vec<gix_blame::BlameEntry>, Vec<gix_blame::BlameEntry>)> {
+        let worktree_path = gix_testtools::scripted_fixture_read_only("make_blame_moves_and_copies_repo.sh")?;
+
+        let Fixture {
+            odb,
+            mut resource_cache,
+            suspect,
+        } = Fixture::for_worktree_path(worktree_path.to_path_buf())?;
+
+        let lines_blamed = gix_blame::file(
+            &odb,
+            suspect,
+            None,
+            &mut resource_cache,
+            source_file_name.into(),
+            gix_blame::Options {
+                diff_algorithm: gix_diff::blob::Algorithm::Histogram,
+                ranges: BlameRanges::default(),
+                since: None,
+                rewrites: Some(gix_diff::Rewrites::default()),
+                debug_track_path: false,
+                ignore_revs: Vec::new(),
+                worktree_blob: None,
+                moves_and_copies,
+            },
+        )?
+        .entries;
+
+        let baseline = Baseline::collect(
+            worktree_path.join(".git").join(format!("{baseline_name}.baseline")),
+            source_file_name.into(),
+        )?;
+        Ok((lines_blamed, baseline))
+    }
+
+    fn copies(source: CopySource) -> Option<MovesAndCopies> {
+        Some(MovesAndCopies {
+            copies: Some(Copies {
+                source,
+                ..Default::default()
+            }),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn without_detection() -> gix_testtools::Result {
+        let (lines_blamed, baseline) = blame("file.txt", None, "file")?;
+        pretty_assertions::assert_eq!(lines_blamed, baseline);
+        Ok(())
+    }
+
+    #[test]
+    fn moves_within_the_file() -> gix_testtools::Result {
+        let (lines_blamed, baseline) = blame("file.txt", Some(MovesAndCopies::default()), "file-M")?;
+        pretty_assertions::assert_eq!(lines_blamed, baseline);
+        Ok(())
+    }
+
+    #[test]
+    fn copies_from_modified_files() -> gix_testtools::Result {
+        let (lines_blamed, baseline) = blame("file.txt", copies(CopySource::FromSetOfModifiedFiles), "file-C")?;
+        assert!(
+            lines_blamed
+                .iter()
+                .any(|entry| entry.source_file_name.as_ref().is_some_and(|name| name == "other.txt")),
+            "lines copied from a file modified in the same commit are found"
+        );
+        pretty_assertions::assert_eq!(lines_blamed, baseline);
+        Ok(())
+    }
+
+    #[test]
+    fn copies_from_all_files_if_created() -> gix_testtools::Result {
+        let source = CopySource::FromSetOfModifiedFilesAndAllSourcesIfCreated;
+        let (lines_blamed, baseline) = blame("file.txt", copies(source), "file-CC")?;
+        pretty_assertions::assert_eq!(lines_blamed, baseline);
+
+        let (lines_blamed, baseline) = blame("new.txt", copies(CopySource::FromSetOfModifiedFiles), "new-C")?;
+        assert_eq!(
+            lines_blamed.len(),
+            1,
+            "the new file is entirely attributed to the commit that added it"
+        );
+        pretty_assertions::assert_eq!(lines_blamed, baseline);
+
+        let (lines_blamed, baseline) = blame("new.txt", copies(source), "new-CC")?;
+        assert_eq!(lines_blamed.len(), 2, "all files are searched as the file was created");
+        pretty_assertions::assert_eq!(lines_blamed, baseline);
+        Ok(())
+    }
+
+    #[test]
+    fn copies_from_all_files() -> gix_testtools::Result {
+        let (lines_blamed, baseline) = blame("file.txt", copies(CopySource::FromAllSources), "file-CCC")?;
+        assert!(
+            lines_blamed.iter().any(|entry| entry
+                .source_file_name
+                .as_ref()
+                .is_some_and(|name| name == "unchanged.txt")),
+            "lines copied from files that weren't modified are found as well"
+        );
+        pretty_assertions::assert_eq!(lines_blamed, baseline);
+        Ok(())
+    }
+}
+
+mod worktree {
+    use gix_blame::BlameRanges;
+
+    use crate::{Baseline, Fixture};
+
+    fn blame_worktree_file(
+        source_file_name: &str,
+    ) -> gix_testtools::Result<(Vec<gix_blame::BlameEntry>, Vec<gix_blame::BlameEntry>)> {
+        let worktree_path = gix_testtools::scripted_fixture_read_only("make_blame_worktree_repo.sh")?;
+
+        let Fixture {
+            odb,
+            mut resource_cache,
+            suspect,
+        } = Fixture::for_worktree_path(worktree_path.to_path_buf())?;
+
+        let lines_blamed = gix_blame::file(
+            &odb,
+            suspect,
+            None,
+            &mut resource_cache,
+            source_file_name.into(),
+            gix_blame::Options {
+                diff_algorithm: gix_diff::blob::Algorithm::Histogram,
+                ranges: BlameRanges::default(),
+                since: None,
+                rewrites: Some(gix_diff::Rewrites::default()),
+                debug_track_path: false,
+                ignore_revs: Vec::new(),
+                worktree_blob: Some(std::fs::read(worktree_path.join(source_file_name))?),
+                moves_and_copies: None,
+            },
+        )?
+        .entries;
+
+        let baseline_name = format!("{}.baseline", source_file_name.trim_end_matches(".txt"));
+        let baseline = Baseline::collect(worktree_path.join(".git").join(baseline_name), source_file_name.into())?;
+        Ok((lines_blamed, baseline))
+    }
+
+    #[test]
+    fn uncommitted_changes_are_attributed_to_the_null_id() -> gix_testtools::Result {
+        let (lines_blamed, baseline) = blame_worktree_file("file.txt")?;
+        assert_eq!(
+            lines_blamed.iter().filter(|entry| entry.commit_id.is_null()).count(),
+            2,
+            "a modified and an added line"
+        );
+        pretty_assertions::assert_eq!(lines_blamed, baseline);
+        Ok(())
+    }
+
+    #[test]
+    fn file_that_is_not_in_the_suspect_is_entirely_uncommitted() -> gix_testtools::Result {
+        let (lines_blamed, baseline) = blame_worktree_file("new.txt")?;
+        assert_eq!(lines_blamed.len(), 1);
+        pretty_assertions::assert_eq!(lines_blamed, baseline);
+        Ok(())
+    }
+}
+
+mod incremental {
+    use std::ops::ControlFlow;
+
+    use crate::Fixture;
+
+    fn lines_of(entries: &[gix_blame::BlameEntry]) -> Vec<(u32, gix_hash::ObjectId, u32)> {
+        let mut lines: Vec<_> = entries
+            .iter()
+            .flat_map(|entry| {
+                (0..entry.len.get()).map(move |offset| {
+                    (
+                        entry.start_in_blamed_file + offset,
+                        entry.commit_id,
+                        entry.start_in_source_file + offset,
+                    )
+                })
+            })
+            .collect();
+        lines.sort();
+        lines
+    }
+
+    #[test]
+    fn entries_match_those_of_a_full_blame() -> gix_testtools::Result {
+        let Fixture {
+            odb,
+            mut resource_cache,
+            suspect,
+        } = Fixture::new()?;
+
+        for case in ["simple", "added-lines", "resolved-conflict", "coalesce-adjacent-hunks"] {
+            let source_file_name = format!("{case}.txt");
+            let expected = gix_blame::file(
+                &odb,
+                suspect,
+                None,
+                &mut resource_cache,
+                source_file_name.as_str().into(),
+                gix_blame::Options::default(),
+            )?
+            .entries;
+
+            let mut entries = Vec::new();
+            let outcome = gix_blame::incremental(
+                &odb,
+                suspect,
+                None,
+                &mut resource_cache,
+                source_file_name.as_str().into(),
+                gix_blame::Options::default(),
+                |entry| {
+                    entries.push(entry);
+                    ControlFlow::Continue(())
+                },
+            )?;
+            assert!(outcome.entries.is_empty(), "entries are passed to the delegate instead");
+            assert_eq!(lines_of(&entries), lines_of(&expected), "{case}");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn the_delegate_can_cancel() -> gix_testtools::Result {
+        let Fixture {
+            odb,
+            mut resource_cache,
+            suspect,
+        } = Fixture::new()?;
+
+        let mut calls = 0;
+        let err = gix_blame::incremental(
+            &odb,
+            suspect,
+            None,
+            &mut resource_cache,
+            "simple.txt".into(),
+            gix_blame::Options::default(),
+            |_entry| {
+                calls += 1;
+                ControlFlow::Break(())
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, gix_blame::Error::Cancelled));
+        assert_eq!(calls, 1, "no entry is emitted after cancellation");
+        Ok(())
+    }
+}
+
 fn fixture_path() -> gix_testtools::Result<PathBuf> {
     gix_testtools::scripted_fixture_read_only("make_blame_repo.sh")
 }
@@ -0,0 +1,34 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git config --local diff.algorithm histogram
+
+git checkout -q -b main
+
+printf 'fn one() {\n    1\n}\nfn two() {\n    2\n}\n' > file.txt
+git add file.txt
+git commit -q -m c1
+
+printf 'fn one() {\n    1\n}\nfn two() {\n    2 changed\n}\n' > file.txt
+git commit -q -am c2
+
+# Reformat lines, which is to be ignored, and add a line that can't be attributed to anything else.
+printf 'fn one() {\n  1\n}\nfn two() {\n  2 changed\n}\n// end\n' > file.txt
+git commit -q -am "c3: reformat"
+git rev-parse HEAD > .git/ignored-commit
+
+printf 'fn one() -> () {\n  1\n}\nfn two() {\n  2 changed\n}\n// end\n' > file.txt
+git commit -q -am c4
+
+git blame --porcelain file.txt > .git/file.baseline
+git blame --porcelain --ignore-rev "$(cat .git/ignored-commit)" file.txt > .git/ignore-rev.baseline
+git -c blame.markIgnoredLines=true -c blame.markUnblamableLines=true \
+  blame -s -l --ignore-rev "$(cat .git/ignored-commit)" file.txt > .git/ignore-rev-marks.baseline
+
+{
+  echo "# formatting"
+  echo
+  cat .git/ignored-commit
+} > .git-blame-ignore-revs
+git config blame.ignoreRevsFile .git-blame-ignore-revs
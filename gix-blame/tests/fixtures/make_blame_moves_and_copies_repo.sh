@@ -0,0 +1,100 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git config --local diff.algorithm histogram
+
+git checkout -q -b main
+
+cat <<END > file.txt
+fn first_function_with_a_long_name() {
+    println!("this is the body of the first function");
+} // first_function_with_a_long_name
+fn second_function_with_a_long_name() {
+    println!("this is the body of the second function");
+} // second_function_with_a_long_name
+fn third_function_with_a_long_name() {
+    println!("this is the body of the third function");
+} // third_function_with_a_long_name
+fn fourth_function_with_a_long_name() {
+    println!("this is the body of the fourth function");
+} // fourth_function_with_a_long_name
+END
+cat <<END > other.txt
+struct FirstStructureInTheOtherFile {
+    first_field_of_the_first_structure: u32,
+} // FirstStructureInTheOtherFile
+struct SecondStructureInTheOtherFile {
+    first_field_of_the_second_structure: u32,
+} // SecondStructureInTheOtherFile
+struct ThirdStructureInTheOtherFile {
+    first_field_of_the_third_structure: u32,
+} // ThirdStructureInTheOtherFile
+END
+cat <<END > unchanged.txt
+enum AnEnumerationInAFileThatIsNeverChanged {
+    TheFirstVariantOfTheEnumeration,
+} // AnEnumerationInAFileThatIsNeverChanged
+END
+git add file.txt other.txt unchanged.txt
+git commit -q -m c1
+
+# Move the first function to the end of the file.
+cat <<END > file.txt
+fn second_function_with_a_long_name() {
+    println!("this is the body of the second function");
+} // second_function_with_a_long_name
+fn third_function_with_a_long_name() {
+    println!("this is the body of the third function");
+} // third_function_with_a_long_name
+fn fourth_function_with_a_long_name() {
+    println!("this is the body of the fourth function");
+} // fourth_function_with_a_long_name
+fn first_function_with_a_long_name() {
+    println!("this is the body of the first function");
+} // first_function_with_a_long_name
+END
+git commit -q -am c2
+
+# Copy a structure from a file that is modified in the same commit, and an enumeration from a file
+# that is not.
+cat <<END > file.txt
+fn second_function_with_a_long_name() {
+    println!("this is the body of the second function");
+} // second_function_with_a_long_name
+struct SecondStructureInTheOtherFile {
+    first_field_of_the_second_structure: u32,
+} // SecondStructureInTheOtherFile
+fn third_function_with_a_long_name() {
+    println!("this is the body of the third function");
+} // third_function_with_a_long_name
+fn fourth_function_with_a_long_name() {
+    println!("this is the body of the fourth function");
+} // fourth_function_with_a_long_name
+enum AnEnumerationInAFileThatIsNeverChanged {
+    TheFirstVariantOfTheEnumeration,
+} // AnEnumerationInAFileThatIsNeverChanged
+fn first_function_with_a_long_name() {
+    println!("this is the body of the first function");
+} // first_function_with_a_long_name
+END
+echo "// a modification" >> other.txt
+git commit -q -am c3
+
+# A new file with lines copied from a file that isn't modified.
+cat <<END > new.txt
+// the new file
+fn third_function_with_a_long_name() {
+    println!("this is the body of the third function");
+} // third_function_with_a_long_name
+END
+git add new.txt
+git commit -q -m c4
+
+git blame --porcelain file.txt > .git/file.baseline
+git blame --porcelain -M file.txt > .git/file-M.baseline
+git blame --porcelain -C file.txt > .git/file-C.baseline
+git blame --porcelain -C -C file.txt > .git/file-CC.baseline
+git blame --porcelain -C -C -C file.txt > .git/file-CCC.baseline
+git blame --porcelain -C new.txt > .git/new-C.baseline
+git blame --porcelain -C -C new.txt > .git/new-CC.baseline
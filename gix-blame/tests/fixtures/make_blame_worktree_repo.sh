@@ -0,0 +1,22 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git config --local diff.algorithm histogram
+
+git checkout -q -b main
+
+seq 1 5 > file.txt
+git add file.txt
+git commit -q -m c1
+
+printf '1\n2 changed\n3\n4\n5\n' > file.txt
+git commit -q -am c2
+
+# Uncommitted changes, and a file that was only added to the index.
+printf '1\n2 changed\n3 uncommitted\n4\n5\n6 uncommitted\n' > file.txt
+seq 1 3 > new.txt
+git add new.txt
+
+git blame --porcelain file.txt > .git/file.baseline
+git blame --porcelain new.txt > .git/new.baseline
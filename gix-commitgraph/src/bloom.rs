@@ -0,0 +1,113 @@
+//! Changed-path Bloom filters, which allow to quickly learn that a commit didn't change a path compared to its first parent.
+//!
+//! Each commit in a commit-graph file written with `git commit-graph write --changed-paths` has a filter
+//! containing all paths it changed, along with all of their leading directories.
+//! A filter may yield false positives, but never false negatives.
+
+/// The parameters used for all filters of a commit-graph file, as stored in the header of its `BDAT` chunk.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct Settings {
+    /// The version of the hash function, `1` for the version that sign-extends bytes `>= 0x80`
+    /// like Git on platforms with signed `char`, and `2` for the standard 32-bit murmur3 hash.
+    pub hash_version: u32,
+    /// The amount of bits each path sets in a filter.
+    pub num_hashes: u32,
+    /// The minimal amount of bits per changed path in a filter.
+    pub bits_per_entry: u32,
+}
+
+/// The positions of the bits a path sets in filters using particular [`Settings`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Key {
+    hashes: Vec<u32>,
+}
+
+impl Key {
+    /// Compute the key for `path`, a slash-separated path relative to the repository root without trailing slash,
+    /// for use with filters using `settings`.
+    pub fn new(path: &[u8], settings: &Settings) -> Self {
+        const SEED0: u32 = 0x293a_e76f;
+        const SEED1: u32 = 0x7e64_6e2c;
+        let sign_extend = settings.hash_version != 2;
+        let hash0 = murmur3(SEED0, path, sign_extend);
+        let hash1 = murmur3(SEED1, path, sign_extend);
+        Key {
+            hashes: (0..settings.num_hashes)
+                .map(|idx| hash0.wrapping_add(idx.wrapping_mul(hash1)))
+                .collect(),
+        }
+    }
+}
+
+/// The Bloom filter of a single commit, listing the paths it changed compared to its first parent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Filter<'a> {
+    data: &'a [u8],
+    settings: Settings,
+}
+
+impl<'a> Filter<'a> {
+    pub(crate) fn new(data: &'a [u8], settings: Settings) -> Self {
+        Filter { data, settings }
+    }
+
+    /// The raw bits of the filter.
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// The settings of the filter, which are needed to create [keys](Key) for querying it.
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+
+    /// Return `false` if the path of `key` was definitely not changed, or `true` if it may have been changed.
+    ///
+    /// `key` must have been created with the [settings](Self::settings()) of this filter.
+    /// Empty filters, as written for commits that weren't processed, always return `true`.
+    pub fn contains(&self, key: &Key) -> bool {
+        let num_bits = self.data.len() as u64 * 8;
+        if num_bits == 0 {
+            return true;
+        }
+        key.hashes.iter().all(|hash| {
+            let bit = u64::from(*hash) % num_bits;
+            self.data[(bit / 8) as usize] & (1 << (bit % 8)) != 0
+        })
+    }
+}
+
+/// Compute the 32-bit murmur3 hash of `data` with `seed`, optionally sign-extending each byte like Git
+/// does for hash version 1 on platforms with a signed `char`.
+fn murmur3(mut seed: u32, data: &[u8], sign_extend: bool) -> u32 {
+    const C1: u32 = 0xcc9e_2d51;
+    const C2: u32 = 0x1b87_3593;
+    let byte = |b: u8| if sign_extend { b as i8 as u32 } else { u32::from(b) };
+
+    let mut chunks = data.chunks_exact(4);
+    for chunk in &mut chunks {
+        let mut k = byte(chunk[0]) | byte(chunk[1]) << 8 | byte(chunk[2]) << 16 | byte(chunk[3]) << 24;
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        seed ^= k;
+        seed = seed.rotate_left(13).wrapping_mul(5).wrapping_add(0xe654_6b64);
+    }
+
+    let tail = chunks.remainder();
+    if !tail.is_empty() {
+        let mut k = tail
+            .iter()
+            .enumerate()
+            .fold(0u32, |k, (idx, b)| k ^ byte(*b) << (8 * idx));
+        k = k.wrapping_mul(C1).rotate_left(15).wrapping_mul(C2);
+        seed ^= k;
+    }
+
+    seed ^= data.len() as u32;
+    seed ^= seed >> 16;
+    seed = seed.wrapping_mul(0x85eb_ca6b);
+    seed ^= seed >> 13;
+    seed = seed.wrapping_mul(0xc2b2_ae35);
+    seed ^= seed >> 16;
+    seed
+}
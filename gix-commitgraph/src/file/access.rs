@@ -4,6 +4,7 @@ use std::{
 };
 
 use crate::{
+    bloom,
     file::{self, commit::Commit, COMMIT_DATA_ENTRY_SIZE_SANS_HASH},
     File,
 };
@@ -26,6 +27,36 @@ impl File {
         Commit::new(self, pos)
     }
 
+    /// Return the settings of the changed-path Bloom filters in this file, or `None` if it doesn't have any.
+    pub fn bloom_settings(&self) -> Option<&bloom::Settings> {
+        self.bloom_settings.as_ref()
+    }
+
+    /// Return the changed-path Bloom filter of the commit at the given lexicographical position, or `None` if
+    /// this file doesn't have Bloom filters or if the filter is out of bounds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `pos` is out of bounds.
+    pub fn bloom_filter_at(&self, pos: file::Position) -> Option<bloom::Filter<'_>> {
+        assert!(
+            pos.0 < self.num_commits(),
+            "expected lexicographical position less than {}, got {}",
+            self.num_commits(),
+            pos.0
+        );
+        let (data_range, settings) = (self.bloom_filter_data_range.clone()?, self.bloom_settings?);
+        let end_of = |pos: usize| {
+            let start = self.bloom_filter_index_offset + pos * 4;
+            u32::from_be_bytes(self.data[start..][..4].try_into().unwrap()) as usize
+        };
+        let pos = pos.0 as usize;
+        let start = pos.checked_sub(1).map_or(0, end_of);
+        let end = end_of(pos);
+        (start <= end && end <= data_range.len())
+            .then(|| bloom::Filter::new(&self.data[data_range][start..end], settings))
+    }
+
     /// The kind of hash used in this File.
     ///
     /// Note that it is always conforming to the hash used in the owning repository.
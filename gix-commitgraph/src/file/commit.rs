@@ -104,6 +104,11 @@ impl<'a> Commit<'a> {
     pub fn root_tree_id(&self) -> &gix_hash::oid {
         self.root_tree_id
     }
+
+    /// Return the changed-path Bloom filter of this commit, or `None` if the file it's stored in has none.
+    pub fn bloom_filter(&self) -> Option<crate::bloom::Filter<'a>> {
+        self.file.bloom_filter_at(self.pos)
+    }
 }
 
 impl Debug for Commit<'_> {
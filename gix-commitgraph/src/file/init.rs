@@ -3,8 +3,10 @@ use std::path::{Path, PathBuf};
 use bstr::ByteSlice;
 
 use crate::{
+    bloom,
     file::{
-        ChunkId, BASE_GRAPHS_LIST_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
+        ChunkId, BASE_GRAPHS_LIST_CHUNK_ID, BLOOM_FILTER_DATA_CHUNK_ID, BLOOM_FILTER_DATA_HEADER_LEN,
+        BLOOM_FILTER_INDEX_CHUNK_ID, COMMIT_DATA_CHUNK_ID, COMMIT_DATA_ENTRY_SIZE_SANS_HASH,
         EXTENDED_EDGES_LIST_CHUNK_ID, FAN_LEN, HEADER_LEN, OID_FAN_CHUNK_ID, OID_LOOKUP_CHUNK_ID, SIGNATURE,
     },
     File,
@@ -184,6 +186,33 @@ impl File {
 
         let extra_edges_list_range = chunks.usize_offset_by_id(EXTENDED_EDGES_LIST_CHUNK_ID).ok();
 
+        // Like Git, ignore changed-path Bloom filters with inconsistent chunks or an unknown hash version
+        // instead of failing, as they are merely an optimization.
+        let bloom = chunks
+            .usize_offset_by_id(BLOOM_FILTER_INDEX_CHUNK_ID)
+            .ok()
+            .zip(chunks.usize_offset_by_id(BLOOM_FILTER_DATA_CHUNK_ID).ok())
+            .filter(|(index_range, data_range)| {
+                index_range.len() == commit_data_count as usize * 4 && data_range.len() >= BLOOM_FILTER_DATA_HEADER_LEN
+            })
+            .and_then(|(index_range, data_range)| {
+                let header = &data[data_range.start..][..BLOOM_FILTER_DATA_HEADER_LEN];
+                let settings = bloom::Settings {
+                    hash_version: read_u32(&header[..4]),
+                    num_hashes: read_u32(&header[4..8]),
+                    bits_per_entry: read_u32(&header[8..]),
+                };
+                matches!(settings.hash_version, 1 | 2).then_some((
+                    index_range.start,
+                    data_range.start + BLOOM_FILTER_DATA_HEADER_LEN..data_range.end,
+                    settings,
+                ))
+            });
+        let (bloom_filter_index_offset, bloom_filter_data_range, bloom_settings) = match bloom {
+            Some((index_offset, data_range, settings)) => (index_offset, Some(data_range), Some(settings)),
+            None => (0, None, None),
+        };
+
         let trailer = &data[chunks.highest_offset() as usize..];
         if trailer.len() != object_hash.len_in_bytes() {
             return Err(Error::Trailer(format!(
@@ -220,6 +249,9 @@ impl File {
         Ok(File {
             base_graph_count,
             base_graphs_list_offset,
+            bloom_filter_index_offset,
+            bloom_filter_data_range,
+            bloom_settings,
             commit_data_offset,
             data,
             extra_edges_list_range,
@@ -252,6 +284,10 @@ impl TryFrom<&Path> for File {
     }
 }
 
+fn read_u32(b: &[u8]) -> u32 {
+    u32::from_be_bytes(b.try_into().unwrap())
+}
+
 // Copied from gix-odb/pack/index/init.rs
 fn read_fan(d: &[u8]) -> ([u32; FAN_LEN], usize) {
     assert!(d.len() >= FAN_LEN * 4);
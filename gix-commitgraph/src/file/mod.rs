@@ -12,11 +12,14 @@ pub mod verify;
 const COMMIT_DATA_ENTRY_SIZE_SANS_HASH: usize = 16;
 pub(crate) const FAN_LEN: usize = 256;
 const HEADER_LEN: usize = 8;
+const BLOOM_FILTER_DATA_HEADER_LEN: usize = 12;
 
 const SIGNATURE: &[u8] = b"CGPH";
 
 type ChunkId = gix_chunk::Id;
 const BASE_GRAPHS_LIST_CHUNK_ID: ChunkId = *b"BASE";
+const BLOOM_FILTER_DATA_CHUNK_ID: ChunkId = *b"BDAT";
+const BLOOM_FILTER_INDEX_CHUNK_ID: ChunkId = *b"BIDX";
 const COMMIT_DATA_CHUNK_ID: ChunkId = *b"CDAT";
 const EXTENDED_EDGES_LIST_CHUNK_ID: ChunkId = *b"EDGE";
 const OID_FAN_CHUNK_ID: ChunkId = *b"OIDF";
@@ -24,6 +24,9 @@ use std::path::Path;
 pub struct File {
     base_graph_count: u8,
     base_graphs_list_offset: Option<usize>,
+    bloom_filter_index_offset: usize,
+    bloom_filter_data_range: Option<std::ops::Range<usize>>,
+    bloom_settings: Option<bloom::Settings>,
     commit_data_offset: usize,
     data: memmap2::Mmap,
     extra_edges_list_range: Option<std::ops::Range<usize>>,
@@ -49,6 +52,7 @@ pub fn at(path: impl AsRef<Path>) -> Result<Graph, init::Error> {
 }
 
 mod access;
+pub mod bloom;
 pub mod file;
 ///
 pub mod init;
@@ -1,3 +1,5 @@
+use gix_commitgraph::bloom;
+
 use crate::{check_common, graph_and_expected, graph_and_expected_named};
 
 #[test]
@@ -52,6 +54,72 @@ fn single_commit_future_64bit_dates_work() {
     assert_eq!(actual.generation(), 1);
 }
 
+#[test]
+fn changed_path_bloom_filters() {
+    let (cg, refs) = graph_and_expected("changed_paths.sh", &["root", "nested", "non-ascii", "empty"]);
+    check_common(&cg, &refs);
+
+    let filter = |name: &str| {
+        cg.commit_at(refs[name].pos())
+            .bloom_filter()
+            .expect("all commits have a filter")
+    };
+    let settings = *filter("root").settings();
+    assert_eq!(
+        settings,
+        bloom::Settings {
+            hash_version: 1,
+            num_hashes: 7,
+            bits_per_entry: 10
+        },
+        "Git's defaults"
+    );
+    let key = |path: &str| bloom::Key::new(path.as_bytes(), &settings);
+
+    let root = filter("root");
+    for path in ["dir/sub/file", "dir/sub", "dir", "top", "ü-non-ascii"] {
+        assert!(root.contains(&key(path)), "{path}: all paths are added by root commits");
+    }
+
+    let nested = filter("nested");
+    for path in ["dir/sub/file", "dir/sub", "dir"] {
+        assert!(nested.contains(&key(path)), "{path}: leading directories are contained");
+    }
+    for path in ["top", "ü-non-ascii", "dir/sub/other", "di"] {
+        assert!(!nested.contains(&key(path)), "{path}: unchanged");
+    }
+
+    let non_ascii = filter("non-ascii");
+    assert!(non_ascii.contains(&key("ü-non-ascii")));
+    assert!(
+        !non_ascii.contains(&bloom::Key::new(
+            "ü-non-ascii".as_bytes(),
+            &bloom::Settings {
+                hash_version: 2,
+                ..settings
+            }
+        )),
+        "version 2 hashes bytes >= 0x80 differently"
+    );
+    assert!(!non_ascii.contains(&key("top")));
+
+    let empty = filter("empty");
+    assert_eq!(
+        empty.data(),
+        [0],
+        "commits without changes have a filter with no bit set"
+    );
+    for path in ["dir/sub/file", "dir", "top", "ü-non-ascii"] {
+        assert!(!empty.contains(&key(path)), "{path}");
+    }
+}
+
+#[test]
+fn graphs_without_changed_paths_have_no_bloom_filters() {
+    let (cg, refs) = graph_and_expected("single_parent.sh", &["parent", "child"]);
+    assert!(cg.commit_at(refs["child"].pos()).bloom_filter().is_none());
+}
+
 #[test]
 fn generation_numbers_overflow_is_handled_in_chained_graph() {
     let names = ["extra", "old-2", "future-2", "old-1", "future-1"];
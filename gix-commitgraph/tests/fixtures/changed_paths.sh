@@ -0,0 +1,26 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+mkdir -p dir/sub
+echo a > dir/sub/file
+echo b > top
+echo c > $'\xc3\xbc-non-ascii'
+git add .
+git commit -q -m root
+git tag root
+
+echo a2 > dir/sub/file
+git commit -q -am nested
+git tag nested
+
+echo c2 > $'\xc3\xbc-non-ascii'
+git commit -q -am non-ascii
+git tag non-ascii
+
+git commit -q --allow-empty -m empty
+git tag empty
+
+git commit-graph write --no-progress --reachable --changed-paths
+git repack -adq
@@ -31,6 +31,35 @@ impl Display for Color {
     }
 }
 
+impl Color {
+    /// Return the ANSI escape sequence to switch a terminal to this color, exactly like Git produces it,
+    /// or an empty string if nothing would change.
+    ///
+    /// Attributes come first, followed by the foreground and the background color, and [`reset`](Attribute::RESET)
+    /// clears all previous settings.
+    pub fn to_ansi(&self) -> String {
+        let mut codes = Vec::new();
+        let mut attributes: Vec<u8> = self.attributes.iter().filter_map(Attribute::ansi_code).collect();
+        attributes.sort_unstable();
+        attributes.dedup();
+        codes.extend(attributes.into_iter().map(|code| code.to_string()));
+        codes.extend(self.foreground.and_then(|name| name.ansi_code(false)));
+        codes.extend(self.background.and_then(|name| name.ansi_code(true)));
+
+        let reset = self.attributes.contains(Attribute::RESET);
+        if !reset && codes.is_empty() {
+            return String::new();
+        }
+        let mut out = String::from("\x1b[");
+        if reset && !codes.is_empty() {
+            out.push(';');
+        }
+        out.push_str(&codes.join(";"));
+        out.push('m');
+        out
+    }
+}
+
 fn color_err(input: impl Into<BString>) -> Error {
     Error::new(
         "Colors are specific color values and their attributes, like 'brightred', or 'blue'",
@@ -126,6 +155,38 @@ pub enum Name {
     Rgb(u8, u8, u8),
 }
 
+impl Name {
+    /// Return the parameter of the ANSI escape sequence to use this color as foreground, or as background if `background` is `true`,
+    /// or `None` if it doesn't change the color.
+    fn ansi_code(self, background: bool) -> Option<String> {
+        let base = if background { 40 } else { 30 };
+        let bright_base = base + 60;
+        Some(match self {
+            Self::Normal => return None,
+            Self::Default => (base + 9).to_string(),
+            Self::Black => base.to_string(),
+            Self::Red => (base + 1).to_string(),
+            Self::Green => (base + 2).to_string(),
+            Self::Yellow => (base + 3).to_string(),
+            Self::Blue => (base + 4).to_string(),
+            Self::Magenta => (base + 5).to_string(),
+            Self::Cyan => (base + 6).to_string(),
+            Self::White => (base + 7).to_string(),
+            Self::BrightBlack => bright_base.to_string(),
+            Self::BrightRed => (bright_base + 1).to_string(),
+            Self::BrightGreen => (bright_base + 2).to_string(),
+            Self::BrightYellow => (bright_base + 3).to_string(),
+            Self::BrightBlue => (bright_base + 4).to_string(),
+            Self::BrightMagenta => (bright_base + 5).to_string(),
+            Self::BrightCyan => (bright_base + 6).to_string(),
+            Self::BrightWhite => (bright_base + 7).to_string(),
+            Self::Ansi(num) if num < 8 => (base + u16::from(num)).to_string(),
+            Self::Ansi(num) => format!("{};5;{num}", base + 8),
+            Self::Rgb(r, g, b) => format!("{};2;{r};{g};{b}", base + 8),
+        })
+    }
+}
+
 impl Display for Name {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -257,6 +318,28 @@ bitflags::bitflags! {
     }
 }
 
+impl Attribute {
+    /// Return the parameter of the ANSI escape sequence for this single attribute, or `None` if it has none.
+    fn ansi_code(self) -> Option<u8> {
+        Some(match self {
+            Attribute::BOLD => 1,
+            Attribute::DIM => 2,
+            Attribute::ITALIC => 3,
+            Attribute::UL => 4,
+            Attribute::BLINK => 5,
+            Attribute::REVERSE => 7,
+            Attribute::STRIKE => 9,
+            Attribute::NO_BOLD | Attribute::NO_DIM => 22,
+            Attribute::NO_ITALIC => 23,
+            Attribute::NO_UL => 24,
+            Attribute::NO_BLINK => 25,
+            Attribute::NO_REVERSE => 27,
+            Attribute::NO_STRIKE => 29,
+            _ => return None,
+        })
+    }
+}
+
 impl Display for Attribute {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut write_space = None;
@@ -196,3 +196,52 @@ mod from_git {
         Ok(Color::try_from(name.into())?.to_string())
     }
 }
+
+mod to_ansi {
+    use bstr::BStr;
+    use gix_config_value::Color;
+
+    #[test]
+    fn nothing_to_change_is_empty() {
+        assert_eq!(ansi(""), "");
+        assert_eq!(ansi("normal"), "");
+        assert_eq!(ansi("normal normal"), "");
+    }
+
+    #[test]
+    fn reset() {
+        assert_eq!(ansi("reset"), "\x1b[m");
+        assert_eq!(ansi("reset bold"), "\x1b[;1m");
+    }
+
+    #[test]
+    fn attributes_come_first() {
+        assert_eq!(ansi("bold red"), "\x1b[1;31m");
+        assert_eq!(ansi("blue red ul"), "\x1b[4;34;41m");
+        assert_eq!(ansi("default default no-reverse bold"), "\x1b[1;27;39;49m");
+    }
+
+    #[test]
+    fn negated_bold_and_dim_share_their_code() {
+        assert_eq!(
+            ansi("#ffffff #010203 nobold nodim italic"),
+            "\x1b[3;22;38;2;255;255;255;48;2;1;2;3m"
+        );
+    }
+
+    #[test]
+    fn bright_and_numbered_colors() {
+        assert_eq!(ansi("brightred 3"), "\x1b[91;43m");
+        assert_eq!(ansi("nostrike normal 7"), "\x1b[29;47m");
+        assert_eq!(
+            ansi("254 255 bold dim ul blink reverse"),
+            "\x1b[1;2;4;5;7;38;5;254;48;5;255m"
+        );
+    }
+
+    fn ansi<'a>(name: impl Into<&'a BStr>) -> String {
+        Color::try_from(name.into())
+            .expect("input color is expected to be valid")
+            .to_ansi()
+    }
+}
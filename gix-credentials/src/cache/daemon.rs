@@ -0,0 +1,152 @@
+//! The daemon side of the credential cache, keeping credentials in memory until they expire.
+use std::{
+    io::{Read, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use bstr::ByteSlice;
+
+use crate::protocol::Context;
+
+/// The amount of time the daemon keeps running without any credentials to hold.
+const IDLE_TIMEOUT: Duration = Duration::from_secs(30);
+/// How often to check for new connections and expired credentials.
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// The amount of time a client has to send its request or to receive the response before it's dropped,
+/// so a stuck client can't block the daemon.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(1);
+
+struct Entry {
+    credentials: Context,
+    expires_at: Instant,
+}
+
+/// Listen on `socket` and serve requests of the credential cache client until there are no credentials left to keep,
+/// or until asked to exit.
+///
+/// `on_ready` is called once the socket is bound and clients can connect.
+/// The directory containing `socket` is created if needed and made accessible only to the current user.
+/// Just like `git credential-cache--daemon`, it's an error if others have access to this directory.
+pub fn serve(socket: &Path, on_ready: impl FnOnce()) -> std::io::Result<()> {
+    if let Some(dir) = socket.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+        std::fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+        if dir.metadata()?.permissions().mode() & 0o077 != 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::PermissionDenied,
+                format!(
+                    "The permissions on the socket directory '{}' are too loose as other users may be able to read cached credentials, consider running 'chmod 0700' on it",
+                    dir.display()
+                ),
+            ));
+        }
+    }
+    match std::fs::remove_file(socket) {
+        Ok(()) => {}
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => return Err(err),
+    }
+    let listener = UnixListener::bind(socket)?;
+    listener.set_nonblocking(true)?;
+    on_ready();
+
+    let res = serve_inner(&listener);
+    std::fs::remove_file(socket).ok();
+    res
+}
+
+fn serve_inner(listener: &UnixListener) -> std::io::Result<()> {
+    let mut entries = Vec::<Entry>::new();
+    let mut idle_since = Instant::now();
+    loop {
+        match listener.accept() {
+            Ok((stream, _addr)) => match handle(stream, &mut entries) {
+                Ok(Continue::Exit) => return Ok(()),
+                Ok(Continue::Serve) => {}
+                Err(_) => gix_trace::warn!("Failed to communicate with credential cache client"),
+            },
+            Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => std::thread::sleep(POLL_INTERVAL),
+            Err(err) => return Err(err),
+        }
+
+        let now = Instant::now();
+        entries.retain(|entry| entry.expires_at > now);
+        if !entries.is_empty() {
+            idle_since = now;
+        } else if now.duration_since(idle_since) >= IDLE_TIMEOUT {
+            return Ok(());
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Continue {
+    Serve,
+    Exit,
+}
+
+fn handle(mut stream: UnixStream, entries: &mut Vec<Entry>) -> std::io::Result<Continue> {
+    stream.set_nonblocking(false)?;
+    stream.set_read_timeout(Some(CLIENT_TIMEOUT))?;
+    stream.set_write_timeout(Some(CLIENT_TIMEOUT))?;
+    let mut request = Vec::new();
+    stream.read_to_end(&mut request)?;
+
+    let mut action = None;
+    let mut timeout = None;
+    let mut pos = 0;
+    for line in request.lines_with_terminator() {
+        let Some((key, value)) = line.trim_end_with(|c| c == '\n').split_once_str(b"=") else {
+            break;
+        };
+        match key {
+            b"action" => action = Some(value.as_bstr().to_owned()),
+            b"timeout" => timeout = value.to_str().ok().and_then(|secs| secs.parse().ok()),
+            _ => break,
+        }
+        pos += line.len();
+    }
+    let Ok(query) = Context::from_bytes(&request[pos..]) else {
+        gix_trace::warn!("Ignoring malformed credential cache request");
+        return Ok(Continue::Serve);
+    };
+
+    match action.as_ref().map(|action| action.as_slice()) {
+        Some(b"get") => {
+            if let Some(found) = entries
+                .iter()
+                .find(|entry| query.matches(&entry.credentials, false))
+                .map(|entry| &entry.credentials)
+            {
+                let response = Context {
+                    username: found.username.clone(),
+                    password: found.password.clone(),
+                    oauth_refresh_token: found.oauth_refresh_token.clone(),
+                    password_expiry_utc: found.password_expiry_utc,
+                    ..Default::default()
+                };
+                response.write_to(&mut stream)?;
+            }
+        }
+        Some(b"store") => {
+            let Some(timeout) = timeout else {
+                gix_trace::warn!("Ignoring credential cache 'store' request without timeout");
+                return Ok(Continue::Serve);
+            };
+            entries.retain(|entry| !query.matches(&entry.credentials, false));
+            entries.push(Entry {
+                credentials: query,
+                expires_at: Instant::now() + Duration::from_secs(timeout),
+            });
+        }
+        Some(b"erase") => entries.retain(|entry| !query.matches(&entry.credentials, true)),
+        Some(b"exit") => {
+            stream.flush()?;
+            return Ok(Continue::Exit);
+        }
+        _ => gix_trace::warn!("Ignoring credential cache request with unknown or missing action"),
+    }
+    Ok(Continue::Serve)
+}
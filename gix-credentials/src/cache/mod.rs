@@ -0,0 +1,207 @@
+//! A credential helper keeping credentials in memory of a daemon for a limited time, compatible with `git credential-cache`.
+//!
+//! The client talks to the daemon through a Unix domain socket, using the same protocol as `git credential-cache--daemon`,
+//! and starts the daemon when credentials are stored without it running.
+use std::{
+    io::{BufRead, Read, Write},
+    os::unix::net::UnixStream,
+    path::PathBuf,
+    time::Duration,
+};
+
+use bstr::{BStr, ByteSlice};
+
+use crate::{program::main::Action, protocol::Context};
+
+///
+pub mod daemon;
+
+/// The error returned by [`Options::from_args()`], [`Options::invoke()`] and [`Options::exit()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not parse the arguments of the 'cache' credential helper")]
+    ParseArgs(#[from] shell_words::ParseError),
+    #[error("Unknown argument '{arg}' for the 'cache' credential helper, only '--timeout <seconds>' and '--socket <path>' are supported")]
+    UnknownArgument { arg: String },
+    #[error("The '{name}' argument needs a value")]
+    MissingValue { name: &'static str },
+    #[error("The timeout '{value}' isn't a number of seconds")]
+    InvalidTimeout { value: String },
+    #[error("Could not find the home directory to locate the socket of the credential cache daemon")]
+    MissingHome,
+    #[error(transparent)]
+    UrlMissing(#[from] crate::protocol::Error),
+    #[error(transparent)]
+    ContextDecode(#[from] crate::protocol::context::decode::Error),
+    #[error("Could not communicate with the credential cache daemon at '{}'", socket.display())]
+    Io { socket: PathBuf, source: std::io::Error },
+    #[error("Could not start the credential cache daemon")]
+    SpawnDaemon(#[source] std::io::Error),
+    #[error("The credential cache daemon didn't indicate it was ready")]
+    DaemonNotReady,
+}
+
+/// The amount of time credentials are kept by default, which is 15 minutes.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(900);
+
+/// Options to configure how to reach the credential cache daemon, and for how long it keeps credentials.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Options {
+    /// The location of the socket the daemon listens on.
+    pub socket: PathBuf,
+    /// The amount of time newly stored credentials are kept.
+    pub timeout: Duration,
+    /// The program to run as `<daemon_program> credential cache-daemon <socket>` to start the daemon if it isn't running yet.
+    pub daemon_program: PathBuf,
+}
+
+impl Options {
+    /// Use the default socket location and timeout, like `git credential-cache` does.
+    ///
+    /// The socket is `~/.git-credential-cache/socket` if that directory exists, or `$XDG_CACHE_HOME/git/credential/socket` otherwise.
+    /// The daemon is started with [`default_daemon_program()`].
+    pub fn from_env() -> Result<Self, Error> {
+        Ok(Options {
+            socket: default_socket().ok_or(Error::MissingHome)?,
+            timeout: DEFAULT_TIMEOUT,
+            daemon_program: default_daemon_program(),
+        })
+    }
+
+    /// Parse `args` as passed to the helper, like `--timeout 300 --socket ~/.cache/socket`, with shell quoting rules,
+    /// using [the defaults](Self::from_env()) for everything that isn't set.
+    pub fn from_args(args: &BStr) -> Result<Self, Error> {
+        let args = shell_words::split(&args.to_str_lossy())?;
+        let mut args = args.into_iter();
+        let mut out = Self::from_env()?;
+        while let Some(arg) = args.next() {
+            let (name, value) = match arg.split_once('=') {
+                Some((name, value)) => (name.to_owned(), Some(value.to_owned())),
+                None => (arg, None),
+            };
+            match name.as_str() {
+                "--timeout" => {
+                    let value = value
+                        .or_else(|| args.next())
+                        .ok_or(Error::MissingValue { name: "--timeout" })?;
+                    out.timeout = Duration::from_secs(value.parse().map_err(|_| Error::InvalidTimeout { value })?);
+                }
+                "--socket" => {
+                    let value = value
+                        .or_else(|| args.next())
+                        .ok_or(Error::MissingValue { name: "--socket" })?;
+                    out.socket = match value.strip_prefix("~/") {
+                        Some(relative) => gix_path::env::home_dir().ok_or(Error::MissingHome)?.join(relative),
+                        None => value.into(),
+                    };
+                }
+                _ => return Err(Error::UnknownArgument { arg: name }),
+            }
+        }
+        Ok(out)
+    }
+
+    /// Perform `action` with `ctx` like a credential helper would, returning the credentials if `action` is [`Action::Get`]
+    /// and the daemon knows them.
+    ///
+    /// The daemon is started if credentials are stored but it isn't running yet.
+    pub fn invoke(&self, action: Action, ctx: Context) -> Result<Option<Context>, Error> {
+        let ctx = Context {
+            url: None,
+            ..crate::store::normalize(ctx)?
+        };
+        let mut request = format!("action={}\ntimeout={}\n", action.as_str(), self.timeout.as_secs()).into_bytes();
+        ctx.write_to(&mut request).map_err(|source| self.io_err(source))?;
+        request.push(b'\n');
+
+        let response = match self.send(&request) {
+            Ok(response) => response,
+            Err(err) if is_not_running(&err) => {
+                if !matches!(action, Action::Store) {
+                    return Ok(None);
+                }
+                self.spawn_daemon()?;
+                self.send(&request).map_err(|source| self.io_err(source))?
+            }
+            Err(source) => return Err(self.io_err(source)),
+        };
+        match action {
+            Action::Get => {
+                let found = Context::from_bytes(&response)?;
+                Ok(found.username.is_some().then_some(found))
+            }
+            Action::Store | Action::Erase => Ok(None),
+        }
+    }
+
+    /// Ask the daemon to forget all credentials and shut down, which does nothing if it's not running.
+    pub fn exit(&self) -> Result<(), Error> {
+        match self.send(b"action=exit\n\n") {
+            Ok(_) => Ok(()),
+            Err(err) if is_not_running(&err) => Ok(()),
+            Err(source) => Err(self.io_err(source)),
+        }
+    }
+
+    fn send(&self, request: &[u8]) -> std::io::Result<Vec<u8>> {
+        let mut stream = UnixStream::connect(&self.socket)?;
+        stream.write_all(request)?;
+        stream.shutdown(std::net::Shutdown::Write)?;
+        let mut response = Vec::new();
+        stream.read_to_end(&mut response)?;
+        Ok(response)
+    }
+
+    fn spawn_daemon(&self) -> Result<(), Error> {
+        let mut child = std::process::Command::new(&self.daemon_program)
+            .args(["credential", "cache-daemon"])
+            .arg(&self.socket)
+            .stdin(std::process::Stdio::null())
+            .stdout(std::process::Stdio::piped())
+            .stderr(std::process::Stdio::null())
+            .spawn()
+            .map_err(Error::SpawnDaemon)?;
+        let mut line = String::new();
+        std::io::BufReader::new(child.stdout.take().expect("configured"))
+            .read_line(&mut line)
+            .map_err(Error::SpawnDaemon)?;
+        if line.trim_end() != "ok" {
+            return Err(Error::DaemonNotReady);
+        }
+        Ok(())
+    }
+
+    fn io_err(&self, source: std::io::Error) -> Error {
+        Error::Io {
+            socket: self.socket.clone(),
+            source,
+        }
+    }
+}
+
+/// Return the program to start the daemon with, which is the current executable if it is `gix`, or `gix` as found in `PATH` otherwise.
+pub fn default_daemon_program() -> PathBuf {
+    std::env::current_exe()
+        .ok()
+        .filter(|exe| exe.file_stem().is_some_and(|stem| stem == "gix"))
+        .unwrap_or_else(|| "gix".into())
+}
+
+fn default_socket() -> Option<PathBuf> {
+    let home = gix_path::env::home_dir()?;
+    let legacy_dir = home.join(".git-credential-cache");
+    if legacy_dir.is_dir() {
+        return Some(legacy_dir.join("socket"));
+    }
+    let mut socket = gix_path::env::var("XDG_CACHE_HOME").map_or_else(|| home.join(".cache"), PathBuf::from);
+    socket.extend(["git", "credential", "socket"]);
+    Some(socket)
+}
+
+fn is_not_running(err: &std::io::Error) -> bool {
+    matches!(
+        err.kind(),
+        std::io::ErrorKind::NotFound | std::io::ErrorKind::ConnectionRefused
+    )
+}
@@ -1,6 +1,11 @@
 use std::io::Read;
 
-use crate::helper::{Action, Context, Error, NextAction, Outcome, Result};
+use bstr::ByteSlice;
+
+use crate::{
+    helper::{Action, Context, Error, NextAction, Outcome, Result},
+    program::{main, Kind},
+};
 
 impl Action {
     /// Send ourselves to the given `write` which is expected to be credentials-helper compatible
@@ -41,6 +46,9 @@ pub fn invoke(helper: &mut crate::Program, action: &Action) -> Result {
 }
 
 pub(crate) fn raw(helper: &mut crate::Program, action: &Action) -> std::result::Result<Option<Vec<u8>>, Error> {
+    if let Some(res) = builtin(&helper.kind, action) {
+        return res;
+    }
     let (mut stdin, stdout) = helper.start(action)?;
     if let (Action::Get(_), None) = (&action, &stdout) {
         panic!("BUG: `Helper` impls must return an output handle to read output from if Action::Get is provided")
@@ -67,3 +75,43 @@ pub(crate) fn raw(helper: &mut crate::Program, action: &Action) -> std::result::
         Some(stdout) => Ok(Some(stdout)),
     }
 }
+
+/// Run the helpers we implement ourselves in-process, or return `None` if `kind` needs a program to be launched.
+fn builtin(kind: &Kind, action: &Action) -> Option<std::result::Result<Option<Vec<u8>>, Error>> {
+    let (main_action, ctx) = match action {
+        Action::Get(ctx) => (main::Action::Get, Ok(ctx.clone())),
+        Action::Store(payload) => (main::Action::Store, Context::from_bytes(payload)),
+        Action::Erase(payload) => (main::Action::Erase, Context::from_bytes(payload)),
+    };
+    let invoke = |ctx: Context| -> std::result::Result<Option<Context>, Box<dyn std::error::Error + Send + Sync>> {
+        Ok(match kind {
+            Kind::BuiltinStore { args } => {
+                crate::store::Options::from_args(args.as_bstr())?.invoke(main_action, ctx)?
+            }
+            #[cfg(unix)]
+            Kind::BuiltinCache { args } => {
+                crate::cache::Options::from_args(args.as_bstr())?.invoke(main_action, ctx)?
+            }
+            #[cfg(not(unix))]
+            Kind::BuiltinCache { .. } => return Err("The 'cache' credential helper is only supported on Unix".into()),
+            _ => unreachable!("checked by caller"),
+        })
+    };
+    if !matches!(kind, Kind::BuiltinStore { .. } | Kind::BuiltinCache { .. }) {
+        return None;
+    }
+    Some(ctx.map_err(Into::into).and_then(|ctx| {
+        let out = invoke(ctx).map_err(|err| Error::CredentialsHelperFailed {
+            source: std::io::Error::other(err),
+        })?;
+        Ok(match (action, out) {
+            (Action::Get(_), Some(out)) => {
+                let mut buf = Vec::new();
+                out.write_to(&mut buf)?;
+                Some(buf)
+            }
+            (Action::Get(_), None) => Some(Vec::new()),
+            (Action::Store(_) | Action::Erase(_), _) => None,
+        })
+    }))
+}
@@ -29,6 +29,13 @@ pub mod program;
 ///
 pub mod protocol;
 
+///
+pub mod store;
+
+///
+#[cfg(unix)]
+pub mod cache;
+
 /// Call the `git credential` helper program performing the given `action`, which reads all context from the git configuration
 /// and does everything `git` typically does. The `action` should have been created with [`helper::Action::get_for_url()`] to
 /// contain only the URL to kick off the process, or should be created by [`helper::NextAction`].
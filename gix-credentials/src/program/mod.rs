@@ -24,6 +24,18 @@ pub enum Kind {
     },
     /// A script to execute with `sh`.
     ExternalShellScript(BString),
+    /// The `store` helper implemented by [`crate::store`], which runs in-process just like `git credential-store` would.
+    BuiltinStore {
+        /// Arguments like `--file ~/.my-credentials`, using `sh` shell quoting rules.
+        args: BString,
+    },
+    /// The `cache` helper implemented by [`crate::cache`], which runs in-process just like `git credential-cache` would.
+    ///
+    /// It is only supported on Unix, and fails to run on other platforms.
+    BuiltinCache {
+        /// Arguments like `--timeout 300 --socket ~/.cache/socket`, using `sh` shell quoting rules.
+        args: BString,
+    },
 }
 
 /// Initialization
@@ -39,6 +51,8 @@ impl Program {
 
     /// Parse the given input as per the custom helper definition, supporting `!<script>`, `name` and `/absolute/name`, the latter two
     /// also support arguments which are ignored here.
+    ///
+    /// The names `store` and `cache` refer to the helpers we implement ourselves, see [`Kind::BuiltinStore`] and [`Kind::BuiltinCache`].
     pub fn from_custom_definition(input: impl Into<BString>) -> Self {
         fn from_custom_definition_inner(mut input: BString) -> Program {
             let kind = if input.starts_with(b"!") {
@@ -53,6 +67,10 @@ impl Program {
                 );
                 if gix_path::is_absolute(path) {
                     Kind::ExternalPath { path_and_args: input }
+                } else if let Some(args) = builtin_args(&input, b"store") {
+                    Kind::BuiltinStore { args }
+                } else if let Some(args) = builtin_args(&input, b"cache") {
+                    Kind::BuiltinCache { args }
                 } else {
                     Kind::ExternalName { name_and_args: input }
                 }
@@ -67,6 +85,8 @@ impl Program {
     }
 
     /// Convert the program into the respective command, suitable to invoke `action`.
+    ///
+    /// Built-in helpers are run in-process when invoked, and are represented by their `git credential-<name>` equivalent here.
     pub fn to_command(&self, action: &helper::Action) -> std::process::Command {
         let git_program = gix_path::env::exe_invocation();
         let mut cmd = match &self.kind {
@@ -75,6 +95,20 @@ impl Program {
                 cmd.arg("credential").arg(action.as_arg(false));
                 cmd
             }
+            Kind::BuiltinStore { args } | Kind::BuiltinCache { args } => {
+                let name = if matches!(self.kind, Kind::BuiltinStore { .. }) {
+                    "store"
+                } else {
+                    "cache"
+                };
+                let mut cmd = Command::from(gix_command::prepare(git_program));
+                cmd.arg(format!("credential-{name}"));
+                if let Ok(args) = shell_words::split(&args.to_str_lossy()) {
+                    cmd.args(args);
+                }
+                cmd.arg(action.as_arg(true));
+                cmd
+            }
             Kind::ExternalName { name_and_args } => {
                 let mut args = name_and_args.clone();
                 args.insert_str(0, "credential-");
@@ -104,6 +138,16 @@ impl Program {
     }
 }
 
+/// Return the arguments following `name` in `input` if `input` is `name` or `name <args>`.
+fn builtin_args(input: &BString, name: &[u8]) -> Option<BString> {
+    let args = input.strip_prefix(name)?;
+    if args.is_empty() {
+        Some(BString::default())
+    } else {
+        args.strip_prefix(b" ").map(|args| args.trim_start().into())
+    }
+}
+
 /// Builder
 impl Program {
     /// By default `stderr` of programs is inherited and typically displayed in the terminal.
@@ -70,6 +70,19 @@ mod access {
             }
             buf.into()
         }
+        /// Return `true` if all fields we have set are equal to the ones in `other`, with our password only being
+        /// compared if `match_password` is `true`.
+        pub(crate) fn matches(&self, other: &Context, match_password: bool) -> bool {
+            fn check<T: PartialEq + ?Sized>(want: Option<&T>, have: Option<&T>) -> bool {
+                want.is_none_or(|want| have == Some(want))
+            }
+            check(self.protocol.as_deref(), other.protocol.as_deref())
+                && check(self.host.as_deref(), other.host.as_deref())
+                && check(self.path.as_ref(), other.path.as_ref())
+                && check(self.username.as_deref(), other.username.as_deref())
+                && (!match_password || check(self.password.as_deref(), other.password.as_deref()))
+        }
+
         /// Compute a prompt to obtain the given value.
         pub fn to_prompt(&self, field: &str) -> String {
             match self.to_url() {
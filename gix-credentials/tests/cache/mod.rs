@@ -0,0 +1,143 @@
+use std::time::Duration;
+
+use gix_credentials::{cache, program::main::Action, protocol::Context};
+
+fn ctx(url: &str, username: Option<&str>, password: Option<&str>) -> Context {
+    Context {
+        url: Some(url.into()),
+        username: username.map(Into::into),
+        password: password.map(Into::into),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn store_get_erase_and_exit() -> crate::Result {
+    let dir = gix_testtools::tempfile::tempdir()?;
+    let socket = dir.path().join("cache").join("socket");
+    let options = cache::Options {
+        socket: socket.clone(),
+        timeout: Duration::from_secs(60),
+        daemon_program: "does-not-exist".into(),
+    };
+    assert_eq!(
+        options.invoke(Action::Get, ctx("https://example.com", None, None))?,
+        None,
+        "without daemon, nothing is found"
+    );
+    options.exit()?;
+
+    let (tx, rx) = std::sync::mpsc::channel();
+    let daemon = std::thread::spawn({
+        let socket = socket.clone();
+        move || cache::daemon::serve(&socket, || tx.send(()).expect("receiver alive"))
+    });
+    rx.recv()?;
+
+    options.invoke(Action::Store, ctx("https://example.com", Some("user"), Some("pass")))?;
+    let found = options
+        .invoke(Action::Get, ctx("https://example.com", None, None))?
+        .expect("stored");
+    assert_eq!(found.username.as_deref(), Some("user"));
+    assert_eq!(found.password.as_deref(), Some("pass"));
+    assert_eq!(options.invoke(Action::Get, ctx("https://other.org", None, None))?, None);
+
+    options.invoke(Action::Erase, ctx("https://example.com", Some("user"), Some("wrong")))?;
+    assert!(
+        options
+            .invoke(Action::Get, ctx("https://example.com", None, None))?
+            .is_some(),
+        "the password has to match when erasing"
+    );
+    options.invoke(Action::Erase, ctx("https://example.com", None, None))?;
+    assert_eq!(
+        options.invoke(Action::Get, ctx("https://example.com", None, None))?,
+        None
+    );
+
+    options.exit()?;
+    daemon.join().expect("no panic")?;
+    assert!(!socket.exists(), "the socket is removed when the daemon exits");
+    Ok(())
+}
+
+#[test]
+fn credentials_expire() -> crate::Result {
+    let dir = gix_testtools::tempfile::tempdir()?;
+    let socket = dir.path().join("cache").join("socket");
+    let options = cache::Options {
+        socket: socket.clone(),
+        timeout: Duration::from_secs(0),
+        daemon_program: "does-not-exist".into(),
+    };
+    let (tx, rx) = std::sync::mpsc::channel();
+    let daemon = std::thread::spawn(move || cache::daemon::serve(&socket, || tx.send(()).expect("receiver alive")));
+    rx.recv()?;
+
+    options.invoke(Action::Store, ctx("https://example.com", Some("user"), Some("pass")))?;
+    assert_eq!(
+        options.invoke(Action::Get, ctx("https://example.com", None, None))?,
+        None
+    );
+
+    options.exit()?;
+    daemon.join().expect("no panic")?;
+    Ok(())
+}
+
+#[test]
+fn clients_not_sending_their_request_are_dropped() -> crate::Result {
+    let dir = gix_testtools::tempfile::tempdir()?;
+    let socket = dir.path().join("cache").join("socket");
+    let options = cache::Options {
+        socket: socket.clone(),
+        timeout: Duration::from_secs(60),
+        daemon_program: "does-not-exist".into(),
+    };
+    let (tx, rx) = std::sync::mpsc::channel();
+    let daemon = std::thread::spawn({
+        let socket = socket.clone();
+        move || cache::daemon::serve(&socket, || tx.send(()).expect("receiver alive"))
+    });
+    rx.recv()?;
+
+    let _stuck = std::os::unix::net::UnixStream::connect(&socket)?;
+    options.invoke(Action::Store, ctx("https://example.com", Some("user"), Some("pass")))?;
+    assert!(
+        options
+            .invoke(Action::Get, ctx("https://example.com", None, None))?
+            .is_some(),
+        "the daemon keeps serving after dropping the client that never sent its request"
+    );
+
+    options.exit()?;
+    daemon.join().expect("no panic")?;
+    Ok(())
+}
+
+#[test]
+fn socket_directory_accessible_by_others_is_refused() -> crate::Result {
+    use std::os::unix::fs::PermissionsExt;
+    let dir = gix_testtools::tempfile::tempdir()?;
+    let socket_dir = dir.path().join("cache");
+    std::fs::create_dir(&socket_dir)?;
+    std::fs::set_permissions(&socket_dir, std::fs::Permissions::from_mode(0o750))?;
+
+    let err = cache::daemon::serve(&socket_dir.join("socket"), || unreachable!("never ready"))
+        .expect_err("permissions are too loose");
+    assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+    assert!(!socket_dir.join("socket").exists(), "the socket isn't created");
+    Ok(())
+}
+
+#[test]
+fn options_from_args() -> crate::Result {
+    let options = cache::Options::from_args("--timeout 300 --socket=/tmp/socket".into())?;
+    assert_eq!(options.timeout, Duration::from_secs(300));
+    assert_eq!(options.socket, std::path::Path::new("/tmp/socket"));
+    assert!(matches!(
+        cache::Options::from_args("--timeout soon".into()),
+        Err(cache::Error::InvalidTimeout { .. })
+    ));
+    Ok(())
+}
@@ -118,3 +118,28 @@ fn path_with_simple_args() {
         "a shell is used as there are arguments, and it's generally more flexible, but on windows we split ourselves"
     );
 }
+
+#[test]
+fn builtin_store_and_cache() {
+    let git = *GIT;
+    let prog = Program::from_custom_definition("store --file \"a b\"");
+    assert!(matches!(&prog.kind, Kind::BuiltinStore { args } if args == "--file \"a b\""));
+    assert_eq!(
+        format!("{:?}", prog.to_command(&helper::Action::Store("egal".into()))),
+        format!(r#""{git}" "credential-store" "--file" "a b" "store""#),
+        "the command is what git would run, even though we run the helper in-process"
+    );
+
+    let prog = Program::from_custom_definition("cache");
+    assert!(matches!(&prog.kind, Kind::BuiltinCache { args } if args.is_empty()));
+    assert_eq!(
+        format!("{:?}", prog.to_command(&helper::Action::Erase("egal".into()))),
+        format!(r#""{git}" "credential-cache" "erase""#)
+    );
+
+    let prog = Program::from_custom_definition("storage");
+    assert!(
+        matches!(&prog.kind, Kind::ExternalName { name_and_args } if name_and_args == "storage"),
+        "only the exact name refers to a built-in helper"
+    );
+}
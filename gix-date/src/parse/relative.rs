@@ -5,7 +5,10 @@ use jiff::{tz::TimeZone, Span, Timestamp, Zoned};
 use crate::parse::Error;
 
 fn parse_inner(input: &str) -> Option<Result<Span, Error>> {
-    let mut split = input.split_whitespace();
+    // Like `git`, also accept `2.weeks.ago`, a form commonly used in configuration files.
+    let mut split = input
+        .split(|c: char| c.is_whitespace() || c == '.')
+        .filter(|s| !s.is_empty());
     let units = i64::from_str(split.next()?).ok()?;
     let period = split.next()?;
     if split.next()? != "ago" {
@@ -63,4 +66,10 @@ mod tests {
         let actual = parse_inner("2 weeks ago").unwrap().unwrap();
         assert_eq!(actual.fieldwise(), Span::new().weeks(2));
     }
+
+    #[test]
+    fn two_weeks_ago_with_dots() {
+        let actual = parse_inner("2.weeks.ago").unwrap().unwrap();
+        assert_eq!(actual.fieldwise(), Span::new().weeks(2));
+    }
 }
@@ -1,6 +1,6 @@
 use crate::{
     time::{CustomFormat, Format},
-    Time,
+    SecondsSinceUnixEpoch, Time,
 };
 
 /// E.g. `2018-12-24`
@@ -64,4 +64,69 @@ impl Time {
         let offset = jiff::tz::Offset::from_seconds(self.offset)?;
         Ok(jiff::Timestamp::from_second(self.seconds)?.to_zoned(offset.to_time_zone()))
     }
+
+    /// Return this instance with the offset of the local time zone at this point in time, or unchanged
+    /// if the time can't be represented.
+    pub fn to_local(self) -> Self {
+        match jiff::Timestamp::from_second(self.seconds) {
+            Ok(timestamp) => Time {
+                seconds: self.seconds,
+                offset: timestamp.to_zoned(jiff::tz::TimeZone::system()).offset().seconds(),
+            },
+            Err(_) => self,
+        }
+    }
+
+    /// Format this instance relative to `now` like `git log --date=relative` does, e.g. `3 hours ago`
+    /// or `2 years, 1 month ago`.
+    ///
+    /// Times after `now` are formatted as `in the future`.
+    pub fn format_relative(&self, now: std::time::SystemTime) -> String {
+        let now = match now.duration_since(std::time::UNIX_EPOCH) {
+            Ok(elapsed) => elapsed.as_secs() as SecondsSinceUnixEpoch,
+            Err(err) => -(err.duration().as_secs() as SecondsSinceUnixEpoch),
+        };
+        if now < self.seconds {
+            return "in the future".into();
+        }
+        fn ago(amount: SecondsSinceUnixEpoch, unit: &str) -> String {
+            format!("{amount} {unit}{} ago", if amount == 1 { "" } else { "s" })
+        }
+        let diff = now - self.seconds;
+        if diff < 90 {
+            return ago(diff, "second");
+        }
+        let minutes = (diff + 30) / 60;
+        if minutes < 90 {
+            return ago(minutes, "minute");
+        }
+        let hours = (minutes + 30) / 60;
+        if hours < 36 {
+            return ago(hours, "hour");
+        }
+        let days = (hours + 12) / 24;
+        if days < 14 {
+            return ago(days, "day");
+        }
+        if days < 70 {
+            return ago((days + 3) / 7, "week");
+        }
+        if days < 365 {
+            return ago((days + 15) / 30, "month");
+        }
+        if days < 1825 {
+            let total_months = (days * 12 * 2 + 365) / (365 * 2);
+            let (years, months) = (total_months / 12, total_months % 12);
+            return if months == 0 {
+                ago(years, "year")
+            } else {
+                format!(
+                    "{years} year{}, {}",
+                    if years == 1 { "" } else { "s" },
+                    ago(months, "month")
+                )
+            };
+        }
+        ago((days + 183) / 365, "year")
+    }
 }
@@ -96,6 +96,29 @@ fn git_default() -> gix_testtools::Result {
     Ok(())
 }
 
+#[test]
+fn relative() {
+    let day = 24 * 60 * 60;
+    for (seconds_later, expected) in [
+        (0, "0 seconds ago"),
+        (1, "1 second ago"),
+        (89, "89 seconds ago"),
+        (90, "2 minutes ago"),
+        (5 * 60 * 60, "5 hours ago"),
+        (3 * day, "3 days ago"),
+        (20 * day, "3 weeks ago"),
+        (100 * day, "3 months ago"),
+        (400 * day, "1 year, 1 month ago"),
+        (730 * day, "2 years ago"),
+        (3000 * day, "8 years ago"),
+    ] {
+        let now = std::time::UNIX_EPOCH + std::time::Duration::from_secs((time().seconds + seconds_later) as u64);
+        assert_eq!(time().format_relative(now), expected, "{seconds_later}s later");
+    }
+    let before = std::time::UNIX_EPOCH + std::time::Duration::from_secs(time().seconds as u64 - 1);
+    assert_eq!(time().format_relative(before), "in the future");
+}
+
 fn time() -> Time {
     Time {
         seconds: 123456789,
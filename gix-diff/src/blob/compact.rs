@@ -0,0 +1,346 @@
+//! Move ambiguous groups of changed lines to where a human would expect them, closely following `xdl_change_compact()`
+//! in `xdiff/xdiffi.c` in Git, including its indent heuristic.
+//!
+//! A group of added or removed lines is ambiguous if the lines before it equal its last lines, or if the lines after it
+//! equal its first lines, as it can then be shifted up or down without changing the result.
+use imara_diff::intern::Token;
+
+/// The indentation of a line, or `None` if it is blank.
+pub(super) type Indent = Option<u32>;
+
+/// Return the indentation of `line` with tabs expanding to the next multiple of 8, like Git does it,
+/// or `None` if `line` only consists of whitespace.
+pub(super) fn indent(line: &[u8]) -> Indent {
+    let mut indent = 0;
+    for &b in line {
+        if !crate::blob::whitespace::is_space(b) {
+            return Some(indent);
+        }
+        match b {
+            b' ' => indent += 1,
+            b'\t' => indent += 8 - indent % 8,
+            _ => {}
+        }
+        if indent >= MAX_INDENT {
+            return Some(MAX_INDENT);
+        }
+    }
+    None
+}
+
+/// Compact the `removed` lines of `before` and the `added` lines of `after`, with `indents` being the indentation
+/// of each line in `before` and `after` respectively, used to place groups with Git's indent heuristic.
+pub(super) fn compact(
+    before: &[Token],
+    after: &[Token],
+    removed: &mut [bool],
+    added: &mut [bool],
+    indents: Option<(&[Indent], &[Indent])>,
+) {
+    let (before_indents, after_indents) = indents.unzip();
+    compact_file(
+        File {
+            tokens: before,
+            changed: removed,
+            indents: before_indents,
+        },
+        added,
+    );
+    compact_file(
+        File {
+            tokens: after,
+            changed: added,
+            indents: after_indents,
+        },
+        removed,
+    );
+}
+
+const MAX_INDENT: u32 = 200;
+const MAX_BLANKS: u32 = 20;
+const INDENT_HEURISTIC_MAX_SLIDING: usize = 100;
+
+const START_OF_FILE_PENALTY: i32 = 1;
+const END_OF_FILE_PENALTY: i32 = 21;
+const TOTAL_BLANK_WEIGHT: i32 = -30;
+const POST_BLANK_WEIGHT: i32 = 6;
+const RELATIVE_INDENT_PENALTY: i32 = -4;
+const RELATIVE_INDENT_WITH_BLANK_PENALTY: i32 = 10;
+const RELATIVE_OUTDENT_PENALTY: i32 = 24;
+const RELATIVE_OUTDENT_WITH_BLANK_PENALTY: i32 = 17;
+const RELATIVE_DEDENT_PENALTY: i32 = 23;
+const RELATIVE_DEDENT_WITH_BLANK_PENALTY: i32 = 17;
+const INDENT_WEIGHT: i32 = 60;
+
+/// The file whose changed lines are compacted.
+struct File<'a> {
+    tokens: &'a [Token],
+    changed: &'a mut [bool],
+    indents: Option<&'a [Indent]>,
+}
+
+/// A range of changed lines, which is empty if there is no change at its position.
+#[derive(Clone, Copy)]
+struct Group {
+    start: usize,
+    end: usize,
+}
+
+impl Group {
+    fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+
+    fn first(changed: &[bool]) -> Self {
+        let end = changed.iter().take_while(|changed| **changed).count();
+        Group { start: 0, end }
+    }
+
+    /// Move to the next group, or return `false` if there is none.
+    fn next(&mut self, changed: &[bool]) -> bool {
+        if self.end == changed.len() {
+            return false;
+        }
+        self.start = self.end + 1;
+        self.end = self.start + changed[self.start..].iter().take_while(|changed| **changed).count();
+        true
+    }
+
+    /// Move to the previous group, or return `false` if there is none.
+    fn previous(&mut self, changed: &[bool]) -> bool {
+        if self.start == 0 {
+            return false;
+        }
+        self.end = self.start - 1;
+        self.start = self.end - changed[..self.end].iter().rev().take_while(|changed| **changed).count();
+        true
+    }
+}
+
+impl File<'_> {
+    /// Shift `group` down by one line if possible, merging it with the group that follows it.
+    fn slide_down(&mut self, group: &mut Group) -> bool {
+        if group.end < self.tokens.len() && self.tokens[group.start] == self.tokens[group.end] {
+            self.changed[group.start] = false;
+            self.changed[group.end] = true;
+            group.start += 1;
+            group.end += 1;
+            while group.end < self.changed.len() && self.changed[group.end] {
+                group.end += 1;
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Shift `group` up by one line if possible, merging it with the group that precedes it.
+    fn slide_up(&mut self, group: &mut Group) -> bool {
+        if group.start > 0 && self.tokens[group.start - 1] == self.tokens[group.end - 1] {
+            group.start -= 1;
+            group.end -= 1;
+            self.changed[group.start] = true;
+            self.changed[group.end] = false;
+            while group.start > 0 && self.changed[group.start - 1] {
+                group.start -= 1;
+            }
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Compact the changed lines of `file`, keeping `other_changed`, the changed lines of the other file, in sync.
+fn compact_file(mut file: File<'_>, other_changed: &[bool]) {
+    let mut group = Group::first(file.changed);
+    let mut other = Group::first(other_changed);
+
+    loop {
+        if !group.is_empty() {
+            let mut earliest_end;
+            let mut end_matching_other;
+            let mut group_size;
+            loop {
+                group_size = group.end - group.start;
+                end_matching_other = None;
+
+                while file.slide_up(&mut group) {
+                    assert!(other.previous(other_changed), "BUG: group sync broken sliding up");
+                }
+                earliest_end = group.end;
+                if !other.is_empty() {
+                    end_matching_other = Some(group.end);
+                }
+
+                while file.slide_down(&mut group) {
+                    assert!(other.next(other_changed), "BUG: group sync broken sliding down");
+                    if !other.is_empty() {
+                        end_matching_other = Some(group.end);
+                    }
+                }
+                if group_size == group.end - group.start {
+                    break;
+                }
+            }
+
+            if group.end == earliest_end {
+                // The group can't be shifted.
+            } else if end_matching_other.is_some() {
+                // Line the group up with the last group of changes in the other file that it can align with.
+                while other.is_empty() {
+                    assert!(file.slide_up(&mut group), "BUG: match disappeared");
+                    assert!(other.previous(other_changed), "BUG: group sync broken sliding to match");
+                }
+            } else if let Some(indents) = file.indents {
+                let mut shift = earliest_end
+                    .max((group.end - group_size).saturating_sub(1))
+                    .max(group.end.saturating_sub(INDENT_HEURISTIC_MAX_SLIDING));
+                let mut best: Option<(usize, Score)> = None;
+                while shift <= group.end {
+                    let mut score = Score::default();
+                    score.add_split(&Split::measure(indents, shift));
+                    score.add_split(&Split::measure(indents, shift - group_size));
+                    if best.as_ref().is_none_or(|(_, best_score)| score.cmp(best_score) <= 0) {
+                        best = Some((shift, score));
+                    }
+                    shift += 1;
+                }
+                let best_shift = best.expect("at least one shift was measured").0;
+                while group.end > best_shift {
+                    assert!(file.slide_up(&mut group), "BUG: best shift unreached");
+                    assert!(
+                        other.previous(other_changed),
+                        "BUG: group sync broken sliding to blank line"
+                    );
+                }
+            }
+        }
+
+        if !group.next(file.changed) {
+            break;
+        }
+        assert!(other.next(other_changed), "BUG: group sync broken moving to next group");
+    }
+}
+
+/// Information about the lines around a split between two lines, which is before the line at the split position.
+struct Split {
+    end_of_file: bool,
+    /// The indentation of the line right after the split.
+    indent: Indent,
+    /// The amount of blank lines right before the split.
+    pre_blank: u32,
+    /// The indentation of the first non-blank line before the split.
+    pre_indent: Indent,
+    /// The amount of blank lines after the line right after the split.
+    post_blank: u32,
+    /// The indentation of the first non-blank line after the line right after the split.
+    post_indent: Indent,
+}
+
+impl Split {
+    fn measure(indents: &[Indent], split: usize) -> Self {
+        let (end_of_file, indent) = match indents.get(split) {
+            Some(indent) => (false, *indent),
+            None => (true, None),
+        };
+
+        let mut pre_blank = 0;
+        let mut pre_indent = None;
+        for indent in indents[..split.min(indents.len())].iter().rev() {
+            pre_indent = *indent;
+            if pre_indent.is_some() {
+                break;
+            }
+            pre_blank += 1;
+            if pre_blank == MAX_BLANKS {
+                pre_indent = Some(0);
+                break;
+            }
+        }
+
+        let mut post_blank = 0;
+        let mut post_indent = None;
+        for indent in indents.iter().skip(split + 1) {
+            post_indent = *indent;
+            if post_indent.is_some() {
+                break;
+            }
+            post_blank += 1;
+            if post_blank == MAX_BLANKS {
+                post_indent = Some(0);
+                break;
+            }
+        }
+
+        Split {
+            end_of_file,
+            indent,
+            pre_blank,
+            pre_indent,
+            post_blank,
+            post_indent,
+        }
+    }
+}
+
+/// The badness of a position of a group, with lower scores being better.
+#[derive(Default)]
+struct Score {
+    effective_indent: i32,
+    penalty: i32,
+}
+
+impl Score {
+    fn add_split(&mut self, split: &Split) {
+        if split.pre_indent.is_none() && split.pre_blank == 0 {
+            self.penalty += START_OF_FILE_PENALTY;
+        }
+        if split.end_of_file {
+            self.penalty += END_OF_FILE_PENALTY;
+        }
+
+        let post_blank = if split.indent.is_none() {
+            1 + split.post_blank
+        } else {
+            0
+        };
+        let total_blank = (split.pre_blank + post_blank) as i32;
+        self.penalty += TOTAL_BLANK_WEIGHT * total_blank;
+        self.penalty += POST_BLANK_WEIGHT * post_blank as i32;
+
+        let indent = split.indent.or(split.post_indent);
+        let any_blanks = total_blank != 0;
+        self.effective_indent += indent.map_or(-1, |indent| indent as i32);
+
+        let (Some(indent), Some(pre_indent)) = (indent, split.pre_indent) else {
+            return;
+        };
+        if indent > pre_indent {
+            self.penalty += if any_blanks {
+                RELATIVE_INDENT_WITH_BLANK_PENALTY
+            } else {
+                RELATIVE_INDENT_PENALTY
+            };
+        } else if indent < pre_indent {
+            self.penalty += if split.post_indent.is_some_and(|post_indent| post_indent > indent) {
+                if any_blanks {
+                    RELATIVE_OUTDENT_WITH_BLANK_PENALTY
+                } else {
+                    RELATIVE_OUTDENT_PENALTY
+                }
+            } else if any_blanks {
+                RELATIVE_DEDENT_WITH_BLANK_PENALTY
+            } else {
+                RELATIVE_DEDENT_PENALTY
+            };
+        }
+    }
+
+    fn cmp(&self, other: &Score) -> i32 {
+        let cmp_indents = (self.effective_indent > other.effective_indent) as i32
+            - (self.effective_indent < other.effective_indent) as i32;
+        INDENT_WEIGHT * cmp_indents + (self.penalty - other.penalty)
+    }
+}
@@ -41,6 +41,112 @@ pub fn diff_with_slider_heuristics<T: AsRef<[u8]>>(algorithm: v2::Algorithm, inp
     diff
 }
 
+/// The algorithm to use when computing a diff with [`diff()`].
+///
+/// It mirrors [`imara_diff::Algorithm`], adding the algorithms that `imara-diff` doesn't implement.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Algorithm {
+    /// See [`imara_diff::Algorithm::Histogram`].
+    #[default]
+    Histogram,
+    /// See [`imara_diff::Algorithm::Myers`].
+    Myers,
+    /// See [`imara_diff::Algorithm::MyersMinimal`].
+    MyersMinimal,
+    /// The [`patience` diff algorithm described by Bram Cohen's blog post](https://bramcohen.livejournal.com/73318.html),
+    /// implemented like it is in Git to produce the same hunks.
+    ///
+    /// It anchors the diff on lines that are unique on both sides, and falls back to [`Myers`](Self::Myers) for
+    /// sections that have no unique lines in common.
+    Patience,
+}
+
+impl From<imara_diff::Algorithm> for Algorithm {
+    fn from(value: imara_diff::Algorithm) -> Self {
+        match value {
+            imara_diff::Algorithm::Histogram => Algorithm::Histogram,
+            imara_diff::Algorithm::Myers => Algorithm::Myers,
+            imara_diff::Algorithm::MyersMinimal => Algorithm::MyersMinimal,
+        }
+    }
+}
+
+/// Computes an edit-script that transforms `input.before` into `input.after` using the specified `algorithm`.
+/// The edit-script is passed to `sink.process_change` while it is produced.
+///
+/// This is like [`imara_diff::diff()`], but supports all of our [algorithms](Algorithm).
+pub fn diff<S: Sink, T: AsRef<[u8]>>(algorithm: Algorithm, input: &intern::InternedInput<T>, sink: S) -> S::Out {
+    diff_lines(
+        algorithm,
+        input,
+        &input.before,
+        &input.after,
+        input.interner.num_tokens(),
+        sink,
+    )
+}
+
+/// Like [`diff()`], but compare the lines of `input` by their `tokens`, as [normalized](whitespace::Mode::normalize_input())
+/// to ignore some of their whitespace.
+///
+/// The lines of `input` are still used to place ambiguous changes, so this is preferred over [`diff_with_tokens()`].
+pub fn diff_with_normalized_tokens<S: Sink, T: AsRef<[u8]>>(
+    algorithm: Algorithm,
+    input: &intern::InternedInput<T>,
+    tokens: &whitespace::NormalizedTokens,
+    sink: S,
+) -> S::Out {
+    diff_lines(algorithm, input, &tokens.before, &tokens.after, tokens.num_tokens, sink)
+}
+
+/// Computes an edit-script that transforms `before` into `after` using the specified `algorithm`, with `num_tokens`
+/// being the amount of distinct tokens in both.
+/// The edit-script is passed to `sink.process_change` while it is produced.
+///
+/// This is like [`imara_diff::diff_with_tokens()`], but supports all of our [algorithms](Algorithm).
+/// Note that without the lines the tokens stand for, [`Algorithm::Patience`] can't use Git's indent heuristic to place
+/// changes that could be shifted, and puts them as far down as possible instead.
+pub fn diff_with_tokens<S: Sink>(
+    algorithm: Algorithm,
+    before: &[intern::Token],
+    after: &[intern::Token],
+    num_tokens: u32,
+    sink: S,
+) -> S::Out {
+    let algorithm = match algorithm {
+        Algorithm::Histogram => imara_diff::Algorithm::Histogram,
+        Algorithm::Myers => imara_diff::Algorithm::Myers,
+        Algorithm::MyersMinimal => imara_diff::Algorithm::MyersMinimal,
+        Algorithm::Patience => return patience::diff(before, after, num_tokens, None, sink),
+    };
+    imara_diff::diff_with_tokens(algorithm, before, after, num_tokens, sink)
+}
+
+/// Diff `before` and `after`, the possibly normalized tokens of the lines in `input`.
+fn diff_lines<S: Sink, T: AsRef<[u8]>>(
+    algorithm: Algorithm,
+    input: &intern::InternedInput<T>,
+    before: &[intern::Token],
+    after: &[intern::Token],
+    num_tokens: u32,
+    sink: S,
+) -> S::Out {
+    if algorithm != Algorithm::Patience {
+        return diff_with_tokens(algorithm, before, after, num_tokens, sink);
+    }
+    let indent_by_token: Vec<_> = (0..input.interner.num_tokens())
+        .map(|token| compact::indent(input.interner[intern::Token(token)].as_ref()))
+        .collect();
+    let indents =
+        |tokens: &[intern::Token]| -> Vec<_> { tokens.iter().map(|token| indent_by_token[token.0 as usize]).collect() };
+    let (before_indents, after_indents) = (indents(&input.before), indents(&input.after));
+    patience::diff(before, after, num_tokens, Some((&before_indents, &after_indents)), sink)
+}
+
+mod compact;
+mod patience;
+
 ///
 pub mod pipeline;
 
@@ -50,6 +156,8 @@ pub mod platform;
 pub mod unified_diff;
 pub use unified_diff::impls::UnifiedDiff;
 
+pub mod whitespace;
+
 /// Information about the diff performed to detect similarity.
 #[derive(Debug, Default, Clone, Copy, PartialEq, PartialOrd)]
 pub struct DiffLineStats {
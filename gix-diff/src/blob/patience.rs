@@ -0,0 +1,225 @@
+//! An implementation of the patience diff algorithm, closely following `xdiff/xpatience.c` in Git.
+//!
+//! The algorithm recursively finds the longest common subsequence of lines that are unique on both sides,
+//! uses those as anchors and diffs the ranges between them. Ranges without unique lines in common fall back
+//! to the Myers algorithm.
+use std::{collections::HashMap, ops::Range};
+
+use imara_diff::{intern::Token, Sink};
+
+use super::compact::{self, Indent};
+
+/// Compute the patience diff of `before` and `after`, passing all changes to `sink`.
+///
+/// `num_tokens` is the amount of distinct tokens in both inputs, as obtained from the interner.
+/// Changes are [compacted](compact::compact()) like Git does it after running the algorithm, using `indents` of the lines
+/// in `before` and `after` for its indent heuristic if available.
+pub(super) fn diff<S: Sink>(
+    before: &[Token],
+    after: &[Token],
+    num_tokens: u32,
+    indents: Option<(&[Indent], &[Indent])>,
+    mut sink: S,
+) -> S::Out {
+    let mut state = State {
+        before,
+        after,
+        num_tokens,
+        removed: vec![false; before.len()],
+        added: vec![false; after.len()],
+    };
+    state.diff(0..before.len(), 0..after.len());
+    compact::compact(before, after, &mut state.removed, &mut state.added, indents);
+
+    let (mut i, mut j) = (0, 0);
+    loop {
+        while i < before.len() && j < after.len() && !state.removed[i] && !state.added[j] {
+            i += 1;
+            j += 1;
+        }
+        let (start_i, start_j) = (i, j);
+        while i < before.len() && state.removed[i] {
+            i += 1;
+        }
+        while j < after.len() && state.added[j] {
+            j += 1;
+        }
+        if start_i == i && start_j == j {
+            break;
+        }
+        sink.process_change(start_i as u32..i as u32, start_j as u32..j as u32);
+    }
+    sink.finish()
+}
+
+/// A line in `before` along with the information about its counterpart in `after`.
+struct Entry {
+    /// The position of the line's first occurrence in `before`.
+    before: usize,
+    /// The position of the matching line in `after`.
+    after: Match,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Match {
+    /// No matching line was seen in `after`.
+    None,
+    /// The line is unique on both sides, and this is its position in `after`.
+    Unique(usize),
+    /// The line occurs more than once on either side.
+    NonUnique,
+}
+
+struct State<'a> {
+    before: &'a [Token],
+    after: &'a [Token],
+    num_tokens: u32,
+    /// `true` for each line in `before` that is removed.
+    removed: Vec<bool>,
+    /// `true` for each line in `after` that is added.
+    added: Vec<bool>,
+}
+
+impl State<'_> {
+    fn diff(&mut self, before: Range<usize>, after: Range<usize>) {
+        if before.is_empty() || after.is_empty() {
+            self.removed[before].fill(true);
+            self.added[after].fill(true);
+            return;
+        }
+
+        let Some(entries) = self.unique_lines(before.clone(), after.clone()) else {
+            self.removed[before].fill(true);
+            self.added[after].fill(true);
+            return;
+        };
+        match longest_common_subsequence(&entries) {
+            Some(lcs) => self.walk_common_sequence(&entries, &lcs, before, after),
+            None => self.fall_back_to_myers(before, after),
+        }
+    }
+
+    /// Return all lines of `before` in order of their first occurrence, along with their match in `after`,
+    /// or `None` if there isn't a single line that both sides have in common.
+    fn unique_lines(&self, before: Range<usize>, after: Range<usize>) -> Option<Vec<Entry>> {
+        let mut entries = Vec::<Entry>::new();
+        let mut entry_by_token = HashMap::<Token, usize>::with_capacity(before.len());
+        for pos in before {
+            let token = self.before[pos];
+            match entry_by_token.get(&token) {
+                Some(&idx) => entries[idx].after = Match::NonUnique,
+                None => {
+                    entry_by_token.insert(token, entries.len());
+                    entries.push(Entry {
+                        before: pos,
+                        after: Match::None,
+                    });
+                }
+            }
+        }
+
+        let mut has_matches = false;
+        for pos in after {
+            let Some(&idx) = entry_by_token.get(&self.after[pos]) else {
+                continue;
+            };
+            has_matches = true;
+            let entry = &mut entries[idx];
+            entry.after = match entry.after {
+                Match::None => Match::Unique(pos),
+                Match::Unique(_) | Match::NonUnique => Match::NonUnique,
+            };
+        }
+        has_matches.then_some(entries)
+    }
+
+    /// Walk the longest common subsequence `lcs` of unique lines, extend each common line to the common lines
+    /// around it and diff the ranges between them recursively.
+    fn walk_common_sequence(&mut self, entries: &[Entry], lcs: &[usize], before: Range<usize>, after: Range<usize>) {
+        let (mut line1, mut line2) = (before.start, after.start);
+        let mut lcs = lcs.iter().map(|&idx| &entries[idx]).peekable();
+        loop {
+            let (mut next1, mut next2) = match lcs.peek() {
+                Some(first) => (first.before, first.after.unique()),
+                None => (before.end, after.end),
+            };
+            if lcs.peek().is_some() {
+                while next1 > line1 && next2 > line2 && self.before[next1 - 1] == self.after[next2 - 1] {
+                    next1 -= 1;
+                    next2 -= 1;
+                }
+            }
+            while line1 < next1 && line2 < next2 && self.before[line1] == self.after[line2] {
+                line1 += 1;
+                line2 += 1;
+            }
+
+            if next1 > line1 || next2 > line2 {
+                self.diff(line1..next1, line2..next2);
+            }
+
+            let Some(mut first) = lcs.next() else {
+                return;
+            };
+            while let Some(next) =
+                lcs.next_if(|next| next.before == first.before + 1 && next.after.unique() == first.after.unique() + 1)
+            {
+                first = next;
+            }
+            line1 = first.before + 1;
+            line2 = first.after.unique() + 1;
+        }
+    }
+
+    fn fall_back_to_myers(&mut self, before: Range<usize>, after: Range<usize>) {
+        let (removed, added) = (&mut self.removed, &mut self.added);
+        imara_diff::diff_with_tokens(
+            imara_diff::Algorithm::Myers,
+            &self.before[before.clone()],
+            &self.after[after.clone()],
+            self.num_tokens,
+            |r: Range<u32>, a: Range<u32>| {
+                removed[before.start + r.start as usize..before.start + r.end as usize].fill(true);
+                added[after.start + a.start as usize..after.start + a.end as usize].fill(true);
+            },
+        );
+    }
+}
+
+impl Match {
+    fn unique(self) -> usize {
+        match self {
+            Match::Unique(pos) => pos,
+            Match::None | Match::NonUnique => unreachable!("BUG: only unique lines are part of the common subsequence"),
+        }
+    }
+}
+
+/// Find the longest common subsequence of lines that are unique on both sides using patience sorting,
+/// and return it as list of indices into `entries`, or `None` if there is no unique line in common.
+fn longest_common_subsequence(entries: &[Entry]) -> Option<Vec<usize>> {
+    // The index of the last entry of the best subsequence of length `n + 1` found so far, at `n`.
+    let mut sequence = Vec::<usize>::new();
+    let mut previous = vec![None; entries.len()];
+    for (idx, entry) in entries.iter().enumerate() {
+        let Match::Unique(line2) = entry.after else {
+            continue;
+        };
+        let pos = sequence.partition_point(|&seq_idx| entries[seq_idx].after.unique() < line2);
+        previous[idx] = pos.checked_sub(1).map(|prev| sequence[prev]);
+        if pos == sequence.len() {
+            sequence.push(idx);
+        } else {
+            sequence[pos] = idx;
+        }
+    }
+
+    let mut idx = *sequence.last()?;
+    let mut lcs = vec![idx];
+    while let Some(prev) = previous[idx] {
+        lcs.push(prev);
+        idx = prev;
+    }
+    lcs.reverse();
+    Some(lcs)
+}
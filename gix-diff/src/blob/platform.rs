@@ -220,12 +220,12 @@ pub mod prepare_diff {
     /// The kind of operation that should be performed based on the configuration of the resources involved in the diff.
     #[derive(Debug, Copy, Clone, Eq, PartialEq)]
     pub enum Operation<'a> {
-        /// The [internal diff algorithm](imara_diff::diff) should be called with the provided arguments.
+        /// The [internal diff algorithm](crate::blob::diff()) should be called with the provided arguments.
         /// This only happens if none of the resources are binary, and if there is no external diff program configured via git-attributes
         /// *or* [Options::skip_internal_diff_if_external_is_configured](super::Options::skip_internal_diff_if_external_is_configured)
         /// is `false`.
         ///
-        /// Use [`Outcome::interned_input()`] to easily obtain an interner for use with [`imara_diff::diff()`], or maintain one yourself
+        /// Use [`Outcome::interned_input()`] to easily obtain an interner for use with [`diff()`](crate::blob::diff()), or maintain one yourself
         /// for greater reuse.
         InternalDiff {
             /// The algorithm we determined should be used, which is one of (in order, first set one wins):
@@ -233,7 +233,7 @@ pub mod prepare_diff {
             /// * the driver's override
             /// * the platforms own configuration (typically from git-config)
             /// * the default algorithm
-            algorithm: imara_diff::Algorithm,
+            algorithm: crate::blob::Algorithm,
         },
         /// Run the external diff program according as configured in the `source`-resources driver.
         /// This only happens if [Options::skip_internal_diff_if_external_is_configured](super::Options::skip_internal_diff_if_external_is_configured)
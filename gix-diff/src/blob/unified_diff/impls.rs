@@ -28,6 +28,9 @@ where
     // An index into `before` and the context line to print next,
     // or `None` if this value was never computed to be the correct starting point for an accumulated hunk.
     ctx_pos: Option<u32>,
+    /// The amount of lines to add to a position in `before` to get the position of the same unchanged line in `after`,
+    /// valid for the lines after the last change.
+    after_offset: i64,
 
     /// Symmetrical context before and after the changed hunk.
     ctx_size: u32,
@@ -62,6 +65,7 @@ where
             after_hunk_len: 0,
             after_hunk_start: 0,
             ctx_pos: None,
+            after_offset: 0,
 
             ctx_size: context_size.symmetrical,
 
@@ -104,11 +108,12 @@ where
         Ok(())
     }
 
+    /// Print the context lines at `print` in `before`, using the lines from `after` just like Git does,
+    /// which matters if tokens are considered equal even though their lines differ.
     fn print_context_and_update_pos(&mut self, print: Range<u32>, move_to: u32) {
-        self.print_tokens(
-            &self.before[print.start as usize..print.end as usize],
-            DiffLineKind::Context,
-        );
+        let after_start = (i64::from(print.start) + self.after_offset) as usize;
+        let after_end = (i64::from(print.end) + self.after_offset) as usize;
+        self.print_tokens(&self.after[after_start..after_end], DiffLineKind::Context);
 
         let len = print.end - print.start;
         self.ctx_pos = Some(move_to);
@@ -161,6 +166,7 @@ where
             }
             Some(pos) => pos,
         };
+        self.after_offset = i64::from(after.start) - i64::from(before.start);
         self.print_context_and_update_pos(ctx_pos..before.start, before.end);
         self.before_hunk_len += before.end - before.start;
         self.after_hunk_len += after.end - after.start;
@@ -170,6 +176,7 @@ where
             DiffLineKind::Remove,
         );
         self.print_tokens(&self.after[after.start as usize..after.end as usize], DiffLineKind::Add);
+        self.after_offset = i64::from(after.end) - i64::from(before.end);
     }
 
     fn finish(mut self) -> Self::Out {
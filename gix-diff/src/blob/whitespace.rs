@@ -0,0 +1,104 @@
+//! Compare lines while ignoring some of their whitespace, like `git diff --ignore-space-at-eol`, `-b` and `-w` do.
+use std::borrow::Cow;
+
+use imara_diff::intern::{InternedInput, Interner, Token};
+
+/// Determine which whitespace is ignored when comparing lines.
+///
+/// Note that the line terminator counts as whitespace as well, so a line without newline at the end of a file
+/// is considered equal to the same line with a newline if whitespace is ignored.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Lines are compared as is.
+    #[default]
+    Exact,
+    /// Ignore whitespace at the end of lines, like `--ignore-space-at-eol`.
+    IgnoreAtEol,
+    /// Ignore whitespace at the end of lines, and consider all other sequences of whitespace equal,
+    /// like `--ignore-space-change` or `-b`.
+    IgnoreChange,
+    /// Ignore all whitespace, like `--ignore-all-space` or `-w`.
+    IgnoreAll,
+}
+
+/// The tokens of an [`InternedInput`] after [normalizing](Mode::normalize()) their lines, for use with
+/// [`diff_with_normalized_tokens()`](crate::blob::diff_with_normalized_tokens()).
+///
+/// As each token corresponds to exactly one token in the input, the changes found with these can be
+/// displayed with the original lines, for instance with a [`UnifiedDiff`](crate::blob::UnifiedDiff) created from the input.
+#[derive(Debug, Clone)]
+pub struct NormalizedTokens {
+    /// The normalized tokens of the old version.
+    pub before: Vec<Token>,
+    /// The normalized tokens of the new version.
+    pub after: Vec<Token>,
+    /// The amount of distinct tokens in `before` and `after`.
+    pub num_tokens: u32,
+}
+
+impl Mode {
+    /// Return `line` with all whitespace removed that should be ignored when comparing it.
+    pub fn normalize<'a>(&self, line: &'a [u8]) -> Cow<'a, [u8]> {
+        match self {
+            Mode::Exact => Cow::Borrowed(line),
+            Mode::IgnoreAtEol => {
+                let end = line.iter().rposition(|b| !is_space(*b)).map_or(0, |pos| pos + 1);
+                Cow::Borrowed(&line[..end])
+            }
+            Mode::IgnoreChange => {
+                let mut out = Vec::with_capacity(line.len());
+                let mut in_space = false;
+                for &b in line {
+                    if is_space(b) {
+                        in_space = true;
+                        continue;
+                    }
+                    if in_space {
+                        out.push(b' ');
+                        in_space = false;
+                    }
+                    out.push(b);
+                }
+                Cow::Owned(out)
+            }
+            Mode::IgnoreAll => {
+                if line.iter().any(|b| is_space(*b)) {
+                    Cow::Owned(line.iter().copied().filter(|b| !is_space(*b)).collect())
+                } else {
+                    Cow::Borrowed(line)
+                }
+            }
+        }
+    }
+
+    /// Return `true` if `line` is considered blank, which is when it's empty or only consists of its line terminator,
+    /// or when it consists only of whitespace if whitespace isn't compared [exactly](Mode::Exact).
+    ///
+    /// This is used to determine which changes to hide with `--ignore-blank-lines`.
+    pub fn is_blank(&self, line: &[u8]) -> bool {
+        match self {
+            Mode::Exact => line.is_empty() || line == b"\n",
+            Mode::IgnoreAtEol | Mode::IgnoreChange | Mode::IgnoreAll => line.iter().all(|b| is_space(*b)),
+        }
+    }
+
+    /// Return the tokens of `input` with each line normalized, so that lines that only differ in ignored whitespace
+    /// are represented by the same token.
+    pub fn normalize_input<T: AsRef<[u8]>>(&self, input: &InternedInput<T>) -> NormalizedTokens {
+        let num_tokens = input.interner.num_tokens();
+        let mut interner = Interner::<Cow<'_, [u8]>>::new(num_tokens as usize);
+        let map: Vec<Token> = (0..num_tokens)
+            .map(|token| interner.intern(self.normalize(input.interner[Token(token)].as_ref())))
+            .collect();
+        NormalizedTokens {
+            before: input.before.iter().map(|token| map[token.0 as usize]).collect(),
+            after: input.after.iter().map(|token| map[token.0 as usize]).collect(),
+            num_tokens: interner.num_tokens(),
+        }
+    }
+}
+
+/// Like `isspace()` in the C locale, which is what Git uses.
+pub(crate) fn is_space(b: u8) -> bool {
+    matches!(b, b' ' | b'\t' | b'\n' | b'\r' | b'\x0b' | b'\x0c')
+}
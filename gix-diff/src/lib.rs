@@ -67,3 +67,7 @@ pub use index::function::diff as index;
 ///
 #[cfg(feature = "blob")]
 pub mod blob;
+
+///
+#[cfg(feature = "patch")]
+pub mod patch;
@@ -0,0 +1,191 @@
+/// The error returned by [`apply()`](crate::patch::apply()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Hunk #{hunk} at line {line} did not apply")]
+    HunkFailed {
+        /// The 1-based index of the hunk in the patch of the file.
+        hunk: usize,
+        /// The line the hunk was expected to apply at.
+        line: u32,
+    },
+    #[error("The deletion patch doesn't remove the entire content of the file")]
+    DeletionLeavesContent,
+    #[error("The binary patch doesn't contain the data needed to apply it")]
+    BinaryWithoutData,
+    #[error("The binary patch can't be applied in reverse as it doesn't contain the data for it")]
+    MissingReverseBinaryData,
+    #[error(transparent)]
+    Binary(#[from] crate::patch::binary::Error),
+}
+
+/// Options for use in [`apply()`](crate::patch::apply()).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+    /// If `true`, apply the patch in reverse, turning the new version of a file into the old one, like `git apply --reverse`.
+    pub reverse: bool,
+    /// The minimal amount of leading and trailing context lines that must match for a hunk to apply, like `git apply -C<n>`.
+    ///
+    /// If `None`, all context lines have to match, which is the default.
+    /// Otherwise, context lines are ignored, from the outside inwards, until the hunk applies or only `n` context lines are left.
+    pub min_context: Option<u32>,
+}
+
+pub(super) mod function {
+    use std::borrow::Cow;
+
+    use bstr::ByteSlice;
+
+    use super::{Error, Options};
+    use crate::patch::{binary, Binary, Content, File, Hunk, Kind, LineKind};
+
+    /// Apply the patch for a single `file` to `old`, the content of the file before the change,
+    /// and return the content of the file after the change, according to `options`.
+    ///
+    /// Hunks may apply at a different line than the one they were recorded for if lines were added or removed
+    /// elsewhere, and with [`Options::min_context`] they may apply with some of their context lines not matching.
+    ///
+    /// Note that only the content is affected, it's up to the caller to handle paths and modes of `file`.
+    pub fn apply(file: &File, old: &[u8], options: Options) -> Result<Vec<u8>, Error> {
+        let is_deletion = matches!(
+            (file.kind, options.reverse),
+            (Kind::Deletion, false) | (Kind::Addition, true)
+        );
+        let new = match &file.content {
+            Content::Hunks(hunks) => apply_hunks(hunks, old, options)?,
+            Content::Binary { forward, reverse } => {
+                let binary = if options.reverse {
+                    reverse.as_ref().ok_or(Error::MissingReverseBinaryData)?
+                } else {
+                    forward
+                };
+                match binary {
+                    Binary::Literal(data) => data.clone(),
+                    Binary::Delta(delta) => binary::apply_delta(old, delta)?,
+                }
+            }
+            Content::BinaryWithoutData => return Err(Error::BinaryWithoutData),
+        };
+        if is_deletion && !new.is_empty() {
+            return Err(Error::DeletionLeavesContent);
+        }
+        Ok(new)
+    }
+
+    fn apply_hunks(hunks: &[Hunk], old: &[u8], options: Options) -> Result<Vec<u8>, Error> {
+        let mut image: Vec<Cow<'_, [u8]>> = old.lines_with_terminator().map(Cow::Borrowed).collect();
+        for (idx, hunk) in hunks.iter().enumerate() {
+            apply_hunk(&mut image, hunk, options).map_err(|line| Error::HunkFailed { hunk: idx + 1, line })?;
+        }
+        Ok(image.concat())
+    }
+
+    /// Apply `hunk` to `image` in place, or return the line it should have been applied at on failure.
+    ///
+    /// This closely follows `apply_one_fragment()` in Git.
+    fn apply_hunk(image: &mut Vec<Cow<'_, [u8]>>, hunk: &Hunk, options: Options) -> Result<(), u32> {
+        let (removed, added, old_start, new_start) = if options.reverse {
+            (LineKind::Add, LineKind::Remove, hunk.new_start, hunk.old_start)
+        } else {
+            (LineKind::Remove, LineKind::Add, hunk.old_start, hunk.new_start)
+        };
+        let mut preimage: Vec<&[u8]> = Vec::with_capacity(hunk.lines.len());
+        let mut postimage: Vec<&[u8]> = Vec::with_capacity(hunk.lines.len());
+        for line in &hunk.lines {
+            if line.kind != added {
+                preimage.push(&line.content);
+            }
+            if line.kind != removed {
+                postimage.push(&line.content);
+            }
+        }
+        let mut leading = hunk.lines.iter().take_while(|l| l.kind == LineKind::Context).count();
+        let mut trailing = hunk
+            .lines
+            .iter()
+            .rev()
+            .take_while(|l| l.kind == LineKind::Context)
+            .count();
+        let (mut preimage, mut postimage) = (preimage.as_slice(), postimage.as_slice());
+
+        // Without leading context, a hunk at the start of the file must apply there, and without trailing context,
+        // it must apply at the end.
+        let mut match_beginning = old_start <= 1;
+        let mut match_end = trailing == 0;
+        let mut pos = new_start.saturating_sub(1) as usize;
+        let min_context = options.min_context.map_or(usize::MAX, |n| n as usize);
+        loop {
+            if let Some(at) = find_pos(image, preimage, pos, match_beginning, match_end) {
+                image.splice(
+                    at..at + preimage.len(),
+                    postimage.iter().map(|line| Cow::Owned(line.to_vec())),
+                );
+                return Ok(());
+            }
+
+            if leading <= min_context && trailing <= min_context {
+                return Err(old_start);
+            }
+            if match_beginning || match_end {
+                match_beginning = false;
+                match_end = false;
+                continue;
+            }
+            // Reduce the context, on both sides if they are equal, or the larger one otherwise.
+            if leading >= trailing && leading > 0 {
+                preimage = &preimage[1..];
+                postimage = &postimage[1..];
+                pos += 1;
+                leading -= 1;
+            }
+            if trailing > leading {
+                preimage = &preimage[..preimage.len() - 1];
+                postimage = &postimage[..postimage.len() - 1];
+                trailing -= 1;
+            }
+        }
+    }
+
+    /// Find the position of `preimage` in `image`, starting at `pos` and searching outwards alternating between
+    /// earlier and later lines.
+    fn find_pos(
+        image: &[Cow<'_, [u8]>],
+        preimage: &[&[u8]],
+        pos: usize,
+        match_beginning: bool,
+        match_end: bool,
+    ) -> Option<usize> {
+        let last_possible = image.len().checked_sub(preimage.len())?;
+        let matches_at = |at: usize| {
+            image[at..at + preimage.len()]
+                .iter()
+                .zip(preimage)
+                .all(|(image_line, preimage_line)| image_line.as_ref() == *preimage_line)
+        };
+        if match_beginning || match_end {
+            let at = if match_beginning { 0 } else { last_possible };
+            let anchored = (!match_beginning || at == 0) && (!match_end || at == last_possible);
+            return (anchored && matches_at(at)).then_some(at);
+        }
+
+        let pos = pos.min(last_possible);
+        if matches_at(pos) {
+            return Some(pos);
+        }
+        let mut distance = 1;
+        loop {
+            let backward = pos.checked_sub(distance);
+            let forward = Some(pos + distance).filter(|at| *at <= last_possible);
+            if backward.is_none() && forward.is_none() {
+                return None;
+            }
+            if let Some(at) = backward.filter(|at| matches_at(*at)) {
+                return Some(at);
+            }
+            if let Some(at) = forward.filter(|at| matches_at(*at)) {
+                return Some(at);
+            }
+            distance += 1;
+        }
+    }
+}
@@ -0,0 +1,204 @@
+//! Encoding and decoding of `GIT binary patch` sections and application of the deltas they may contain.
+
+/// The error returned when decoding binary patch data or applying a binary delta.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Invalid base85 data: {message}")]
+    Base85 { message: &'static str },
+    #[error("Could not decompress binary patch data")]
+    Inflate(#[from] gix_features::zlib::inflate::Error),
+    #[error("Binary patch data decompressed to {actual} bytes, but {expected} bytes were announced")]
+    InflatedSize { expected: usize, actual: usize },
+    #[error("The binary delta is corrupt: {message}")]
+    Delta { message: &'static str },
+    #[error("The binary delta expects a base of {expected} bytes, but the file has {actual} bytes")]
+    DeltaBaseSize { expected: u64, actual: usize },
+}
+
+const ALPHABET: &[u8; 85] = b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>?@^_`{|}~";
+
+fn base85_value(byte: u8) -> Option<u32> {
+    ALPHABET.iter().position(|b| *b == byte).map(|pos| pos as u32)
+}
+
+/// Decode a single line of base85 data as found in binary patches, with the first character encoding the amount
+/// of decoded bytes, and append the result to `out`.
+pub(crate) fn decode_base85_line(line: &[u8], out: &mut Vec<u8>) -> Result<(), Error> {
+    let (&len, mut data) = line.split_first().ok_or(Error::Base85 { message: "empty line" })?;
+    let mut remaining = match len {
+        b'A'..=b'Z' => len - b'A' + 1,
+        b'a'..=b'z' => len - b'a' + 27,
+        _ => {
+            return Err(Error::Base85 {
+                message: "invalid line length",
+            })
+        }
+    } as usize;
+    if data.len() != remaining.div_ceil(4) * 5 {
+        return Err(Error::Base85 {
+            message: "line length doesn't match the amount of data",
+        });
+    }
+    while remaining > 0 {
+        let (chunk, rest) = data.split_at(5);
+        let mut acc = 0u32;
+        for &byte in chunk {
+            let value = base85_value(byte).ok_or(Error::Base85 {
+                message: "invalid character",
+            })?;
+            acc = acc
+                .checked_mul(85)
+                .and_then(|acc| acc.checked_add(value))
+                .ok_or(Error::Base85 { message: "overflow" })?;
+        }
+        let count = remaining.min(4);
+        out.extend_from_slice(&acc.to_be_bytes()[..count]);
+        remaining -= count;
+        data = rest;
+    }
+    Ok(())
+}
+
+/// Encode `data` as lines of base85 data as found in binary patches, each starting with the amount of encoded bytes
+/// and ending with a newline, and append them to `out`.
+pub(crate) fn encode_base85_lines(data: &[u8], out: &mut Vec<u8>) {
+    for line in data.chunks(52) {
+        let len = line.len() as u8;
+        out.push(if len <= 26 { b'A' + len - 1 } else { b'a' + len - 27 });
+        for chunk in line.chunks(4) {
+            let mut bytes = [0u8; 4];
+            bytes[..chunk.len()].copy_from_slice(chunk);
+            let mut acc = u32::from_be_bytes(bytes);
+            let mut encoded = [0u8; 5];
+            for byte in encoded.iter_mut().rev() {
+                *byte = ALPHABET[(acc % 85) as usize];
+                acc /= 85;
+            }
+            out.extend_from_slice(&encoded);
+        }
+        out.push(b'\n');
+    }
+}
+
+/// Compress `data` into a zlib stream.
+pub(crate) fn deflate(data: &[u8]) -> std::io::Result<Vec<u8>> {
+    use std::io::Write;
+
+    let mut out = gix_features::zlib::stream::deflate::Write::new(Vec::new());
+    out.write_all(data)?;
+    out.flush()?;
+    Ok(out.into_inner())
+}
+
+/// Return the size of the base and the size of the result of `delta`, or `None` if the delta is corrupt.
+pub(crate) fn delta_sizes(mut delta: &[u8]) -> Option<(u64, u64)> {
+    let base_size = decode_size(&mut delta).ok()?;
+    let result_size = decode_size(&mut delta).ok()?;
+    Some((base_size, result_size))
+}
+
+/// Decompress the zlib stream in `data` which is expected to yield exactly `size` bytes.
+pub(crate) fn inflate(data: &[u8], size: usize) -> Result<Vec<u8>, Error> {
+    use gix_features::zlib::{Inflate, Status};
+
+    // One more byte than needed lets us detect streams that are larger than announced.
+    let mut out = vec![0; size + 1];
+    let (status, _consumed, written) = Inflate::default().once(data, &mut out)?;
+    if status != Status::StreamEnd || written != size {
+        return Err(Error::InflatedSize {
+            expected: size,
+            actual: written,
+        });
+    }
+    out.truncate(size);
+    Ok(out)
+}
+
+fn decode_size(data: &mut &[u8]) -> Result<u64, Error> {
+    let mut size = 0u64;
+    for shift in (0..64).step_by(7) {
+        let (&byte, rest) = data.split_first().ok_or(Error::Delta {
+            message: "truncated size",
+        })?;
+        *data = rest;
+        size |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(size);
+        }
+    }
+    Err(Error::Delta {
+        message: "size is too large",
+    })
+}
+
+/// Apply the git `delta` to `base` and return the result.
+///
+/// Unlike the delta application for packs, the delta is untrusted and thus validated along the way.
+pub(crate) fn apply_delta(base: &[u8], mut delta: &[u8]) -> Result<Vec<u8>, Error> {
+    let base_size = decode_size(&mut delta)?;
+    if base_size != base.len() as u64 {
+        return Err(Error::DeltaBaseSize {
+            expected: base_size,
+            actual: base.len(),
+        });
+    }
+    let result_size = usize::try_from(decode_size(&mut delta)?).map_err(|_| Error::Delta {
+        message: "result is too large",
+    })?;
+
+    let mut out = Vec::with_capacity(result_size.min(base.len() + delta.len() * 128));
+    let truncated = Error::Delta {
+        message: "truncated instruction",
+    };
+    while let Some((&cmd, rest)) = delta.split_first() {
+        delta = rest;
+        if cmd & 0x80 != 0 {
+            let mut read = |bit: u8, shift: u32| -> Result<usize, Error> {
+                if cmd & bit == 0 {
+                    return Ok(0);
+                }
+                let (&byte, rest) = delta.split_first().ok_or(Error::Delta {
+                    message: "truncated copy instruction",
+                })?;
+                delta = rest;
+                Ok(usize::from(byte) << shift)
+            };
+            let ofs = read(0x01, 0)? | read(0x02, 8)? | read(0x04, 16)? | read(0x08, 24)?;
+            let mut size = read(0x10, 0)? | read(0x20, 8)? | read(0x40, 16)?;
+            if size == 0 {
+                size = 0x10000;
+            }
+            let chunk = ofs
+                .checked_add(size)
+                .and_then(|end| base.get(ofs..end))
+                .ok_or(Error::Delta {
+                    message: "copy instruction is out of bounds",
+                })?;
+            out.extend_from_slice(chunk);
+        } else if cmd == 0 {
+            return Err(Error::Delta {
+                message: "unsupported instruction 0",
+            });
+        } else {
+            let size = usize::from(cmd);
+            if delta.len() < size {
+                return Err(truncated);
+            }
+            let (chunk, rest) = delta.split_at(size);
+            out.extend_from_slice(chunk);
+            delta = rest;
+        }
+        if out.len() > result_size {
+            return Err(Error::Delta {
+                message: "result is larger than announced",
+            });
+        }
+    }
+    if out.len() != result_size {
+        return Err(Error::Delta {
+            message: "result is smaller than announced",
+        });
+    }
+    Ok(out)
+}
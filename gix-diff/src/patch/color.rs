@@ -0,0 +1,213 @@
+//! Colors for displaying patches in a terminal, and the detection of moved lines to highlight them, like `git diff --color-moved`.
+use std::collections::HashMap;
+
+use crate::patch::LineKind;
+
+/// The ANSI escape sequences to use for each part of a patch, as configured with `color.diff.<slot>` in Git.
+///
+/// Empty sequences don't change the color, and the defaults are the same as Git's.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Colors {
+    /// Unchanged lines, configured with `color.diff.context`.
+    pub context: String,
+    /// The lines before the hunks, like `diff --git` and `index`, configured with `color.diff.meta`.
+    pub meta: String,
+    /// The `@@ -1 +1 @@` part of hunk headers, configured with `color.diff.frag`.
+    pub frag: String,
+    /// The function name in hunk headers, configured with `color.diff.func`.
+    pub func: String,
+    /// Removed lines, configured with `color.diff.old`.
+    pub old: String,
+    /// Added lines, configured with `color.diff.new`.
+    pub new: String,
+    /// Whitespace errors in added lines, configured with `color.diff.whitespace`.
+    pub whitespace: String,
+    /// Removed lines that were moved elsewhere, configured with `color.diff.oldMoved`.
+    pub old_moved: String,
+    /// Removed lines that were moved elsewhere in a block that directly follows another moved block,
+    /// configured with `color.diff.oldMovedAlternative`.
+    pub old_moved_alternative: String,
+    /// Added lines that were moved from elsewhere, configured with `color.diff.newMoved`.
+    pub new_moved: String,
+    /// Added lines that were moved from elsewhere in a block that directly follows another moved block,
+    /// configured with `color.diff.newMovedAlternative`.
+    pub new_moved_alternative: String,
+}
+
+impl Default for Colors {
+    fn default() -> Self {
+        Colors {
+            context: String::new(),
+            meta: "\x1b[1m".into(),
+            frag: "\x1b[36m".into(),
+            func: String::new(),
+            old: "\x1b[31m".into(),
+            new: "\x1b[32m".into(),
+            whitespace: "\x1b[41m".into(),
+            old_moved: "\x1b[1;35m".into(),
+            old_moved_alternative: "\x1b[1;34m".into(),
+            new_moved: "\x1b[1;36m".into(),
+            new_moved_alternative: "\x1b[1;33m".into(),
+        }
+    }
+}
+
+/// The escape sequence to reset all colors and attributes.
+pub(crate) const RESET: &str = "\x1b[m";
+
+/// Determine how moved lines are detected and displayed, like `git diff --color-moved=<mode>`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Moved {
+    /// Each removed line that was added elsewhere, and each added line that was removed elsewhere, is colored as moved.
+    Plain,
+    /// Blocks of consecutive moved lines are colored as moved if they have at least 20 alphanumeric characters,
+    /// which avoids highlighting trivial lines.
+    Blocks,
+    /// Like [`Blocks`](Moved::Blocks), but blocks that directly follow another moved block use the alternative colors,
+    /// which is what Git does by default.
+    #[default]
+    Zebra,
+}
+
+/// The way a single line is highlighted as moved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Marker {
+    /// The line wasn't moved.
+    None,
+    /// The line was moved.
+    Moved,
+    /// The line was moved, in a block that directly follows another moved block.
+    MovedAlternative,
+}
+
+/// The minimal amount of alphanumeric characters in a block of lines to consider it moved.
+const MIN_ALNUM_COUNT: usize = 20;
+
+/// Determine how each of `lines` is highlighted as moved according to `mode`.
+///
+/// `lines` are all lines in the order in which they are displayed, with `None` for anything that isn't
+/// an added or removed line, like context lines and hunk headers.
+///
+/// This follows `mark_color_as_moved()` in Git.
+pub(crate) fn detect_moved(lines: &[Option<(LineKind, &[u8])>], mode: Moved) -> Vec<Marker> {
+    let strip = |content: &[u8]| content.strip_suffix(b"\n").unwrap_or(content).to_owned();
+    let mut ids = HashMap::new();
+    let id_of_line: Vec<Option<usize>> = lines
+        .iter()
+        .map(|line| {
+            line.filter(|(kind, _)| *kind != LineKind::Context).map(|(_, content)| {
+                let next_id = ids.len();
+                *ids.entry(strip(content)).or_insert(next_id)
+            })
+        })
+        .collect();
+    let kind_of_line = |n: usize| lines[n].map(|(kind, _)| kind).filter(|kind| *kind != LineKind::Context);
+
+    // The lines with the same content by the opposite kind of change, keyed by id.
+    let mut added = HashMap::<usize, Vec<usize>>::new();
+    let mut removed = HashMap::<usize, Vec<usize>>::new();
+    for (n, id) in id_of_line.iter().enumerate() {
+        if let Some(id) = id {
+            match kind_of_line(n) {
+                Some(LineKind::Add) => added.entry(*id).or_default().push(n),
+                _ => removed.entry(*id).or_default().push(n),
+            }
+        }
+    }
+    // The line that follows each line as part of the same run of added or removed lines.
+    let next_line = |n: usize| Some(n + 1).filter(|next| *next < lines.len() && kind_of_line(*next) == kind_of_line(n));
+
+    let mut markers = vec![Marker::None; lines.len()];
+    let adjust_last_block = |markers: &mut [Marker], n: usize, block_length: usize| -> bool {
+        if mode == Moved::Plain {
+            return block_length != 0;
+        }
+        let block = n - block_length..n;
+        let alnum_count: usize = lines[block.clone()]
+            .iter()
+            .flatten()
+            .map(|(_, content)| content.iter().filter(|b| b.is_ascii_alphanumeric()).count())
+            .sum();
+        if alnum_count >= MIN_ALNUM_COUNT {
+            return true;
+        }
+        for marker in &mut markers[block] {
+            *marker = Marker::None;
+        }
+        false
+    };
+
+    // Potentially moved blocks, as the position of the last matching line of each.
+    let mut blocks: Vec<usize> = Vec::new();
+    let mut block_length = 0;
+    let mut flipped_block = false;
+    let mut moved_kind = None;
+    let mut n = 0;
+    while n < lines.len() {
+        let kind = kind_of_line(n);
+        let mut matches = id_of_line[n].and_then(|id| match kind {
+            Some(LineKind::Add) => removed.get(&id),
+            _ => added.get(&id),
+        });
+        if kind.is_none() {
+            flipped_block = false;
+        }
+
+        if !blocks.is_empty() && (matches.is_none() || kind != moved_kind) {
+            if !adjust_last_block(&mut markers, n, block_length) && block_length > 1 {
+                // Rewind in case there is another match starting at the second line of the block.
+                matches = None;
+                n -= block_length;
+            }
+            blocks.clear();
+            block_length = 0;
+            flipped_block = false;
+        }
+        let Some(matches) = matches else {
+            moved_kind = None;
+            n += 1;
+            continue;
+        };
+
+        if mode == Moved::Plain {
+            markers[n] = Marker::Moved;
+            n += 1;
+            continue;
+        }
+
+        blocks.retain_mut(
+            |last| match next_line(*last).filter(|next| id_of_line[*next] == id_of_line[n]) {
+                Some(next) => {
+                    *last = next;
+                    true
+                }
+                None => false,
+            },
+        );
+
+        if blocks.is_empty() {
+            let contiguous = adjust_last_block(&mut markers, n, block_length);
+            if !contiguous && block_length > 1 {
+                // Rewind in case there is another match starting at the second line of the block.
+                n -= block_length;
+            } else {
+                blocks.extend_from_slice(matches);
+            }
+            flipped_block = contiguous && !blocks.is_empty() && moved_kind == kind && !flipped_block;
+            moved_kind = if blocks.is_empty() { None } else { kind };
+            block_length = 0;
+        }
+
+        if !blocks.is_empty() {
+            block_length += 1;
+            markers[n] = if flipped_block && mode != Moved::Blocks {
+                Marker::MovedAlternative
+            } else {
+                Marker::Moved
+            };
+        }
+        n += 1;
+    }
+    adjust_last_block(&mut markers, n, block_length);
+    markers
+}
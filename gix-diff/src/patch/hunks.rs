@@ -0,0 +1,192 @@
+use crate::blob::{whitespace, Algorithm};
+
+/// Options for use in [`hunks()`](crate::patch::hunks()).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Options {
+    /// The algorithm to use for diffing lines.
+    pub algorithm: Algorithm,
+    /// The amount of unchanged lines to show around each change, like `git diff -U<n>`.
+    pub context_lines: u32,
+    /// The amount of unchanged lines between changes that may still be shown to merge their hunks, in addition
+    /// to the lines of context, like `git diff --inter-hunk-context=<n>`.
+    pub inter_hunk_context: u32,
+    /// Which whitespace to ignore when comparing lines.
+    ///
+    /// Note that context lines are taken from the new version of the file, so they may differ in whitespace
+    /// from the old version.
+    pub whitespace: whitespace::Mode,
+    /// If `true`, hide changes that only add or remove [blank](whitespace::Mode::is_blank()) lines, unless they are
+    /// close to other changes, like `git diff --ignore-blank-lines`.
+    pub ignore_blank_lines: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            algorithm: Algorithm::default(),
+            context_lines: 3,
+            inter_hunk_context: 0,
+            whitespace: whitespace::Mode::Exact,
+            ignore_blank_lines: false,
+        }
+    }
+}
+
+pub(super) mod function {
+    use std::ops::Range;
+
+    use bstr::ByteSlice;
+
+    use super::Options;
+    use crate::patch::{Hunk, Line, LineKind};
+
+    /// Diff `old` and `new` line by line as configured by `options`, and return the hunks of a patch that turns
+    /// `old` into `new`.
+    ///
+    /// This follows `xdl_emit_diff()` in Git, so hunks are formed and merged exactly like Git does it.
+    /// Like Git does by default, each hunk is annotated with the closest line before it that starts with a letter,
+    /// `_` or `$`, as a hint to where the hunk is located.
+    pub fn hunks(old: &[u8], new: &[u8], options: Options) -> Vec<Hunk> {
+        let old_lines: Vec<&[u8]> = old.lines_with_terminator().collect();
+        let new_lines: Vec<&[u8]> = new.lines_with_terminator().collect();
+        let input = crate::blob::intern::InternedInput::new(
+            crate::blob::sources::byte_lines_with_terminator(old),
+            crate::blob::sources::byte_lines_with_terminator(new),
+        );
+        let tokens = options.whitespace.normalize_input(&input);
+        let mut changes = Vec::new();
+        crate::blob::diff_with_normalized_tokens(
+            options.algorithm,
+            &input,
+            &tokens,
+            |before: Range<u32>, after: Range<u32>| {
+                let old = before.start as usize..before.end as usize;
+                let new = after.start as usize..after.end as usize;
+                let ignore = options.ignore_blank_lines
+                    && old_lines[old.clone()]
+                        .iter()
+                        .chain(&new_lines[new.clone()])
+                        .all(|line| options.whitespace.is_blank(line));
+                changes.push(Change { old, new, ignore });
+            },
+        );
+
+        let ctx = options.context_lines as usize;
+        let mut hunks = Vec::new();
+        let mut next = 0;
+        while let Some((first, last)) = next_hunk(&changes, next, options) {
+            next = last + 1;
+            let (first_change, last_change) = (&changes[first], &changes[last]);
+            let s1 = first_change.old.start.saturating_sub(ctx);
+            let mut s2 = first_change.new.start.saturating_sub(ctx);
+            let post_ctx = ctx
+                .min(old_lines.len() - last_change.old.end)
+                .min(new_lines.len() - last_change.new.end);
+            let e1 = last_change.old.end + post_ctx;
+            let e2 = last_change.new.end + post_ctx;
+
+            let mut lines = Vec::new();
+            let mut push = |kind: LineKind, content: &[u8]| {
+                lines.push(Line {
+                    kind,
+                    content: content.into(),
+                });
+            };
+            for change in &changes[first..=last] {
+                for line in &new_lines[s2..change.new.start] {
+                    push(LineKind::Context, line);
+                }
+                for line in &old_lines[change.old.clone()] {
+                    push(LineKind::Remove, line);
+                }
+                for line in &new_lines[change.new.clone()] {
+                    push(LineKind::Add, line);
+                }
+                s2 = change.new.end;
+            }
+            for line in &new_lines[s2..e2] {
+                push(LineKind::Context, line);
+            }
+
+            let s2 = first_change.new.start.saturating_sub(ctx);
+            // Empty ranges refer to the line before them, just like Git does it.
+            let start = |start: usize, len: usize| if len == 0 { start } else { start + 1 } as u32;
+            hunks.push(Hunk {
+                old_start: start(s1, e1 - s1),
+                old_len: (e1 - s1) as u32,
+                new_start: start(s2, e2 - s2),
+                new_len: (e2 - s2) as u32,
+                function_name: old_lines[..s1].iter().rev().find_map(|line| function_name(line)),
+                lines,
+            });
+        }
+        hunks
+    }
+
+    /// A single change as produced by the diff algorithm.
+    struct Change {
+        /// The lines removed from the old version.
+        old: Range<usize>,
+        /// The lines added in the new version.
+        new: Range<usize>,
+        /// If `true`, the change only touches blank lines and may be hidden.
+        ignore: bool,
+    }
+
+    /// Return the indices of the first and the last change of the hunk that starts at or after the change at `start`,
+    /// or `None` if there is no further hunk.
+    ///
+    /// This follows `xdl_get_hunk()` in Git.
+    fn next_hunk(changes: &[Change], start: usize, options: Options) -> Option<(usize, usize)> {
+        let max_common = 2 * options.context_lines as usize + options.inter_hunk_context as usize;
+        let max_ignorable = options.context_lines as usize;
+
+        // Skip ignorable changes that are too far away from other changes.
+        let mut first = start;
+        for idx in (start..changes.len()).take_while(|idx| changes[*idx].ignore) {
+            if changes
+                .get(idx + 1)
+                .is_none_or(|next| next.old.start - changes[idx].old.end >= max_ignorable)
+            {
+                first = idx + 1;
+            }
+        }
+        if first >= changes.len() {
+            return None;
+        }
+
+        let mut last = first;
+        let mut ignored = 0;
+        for (prev, cur) in (first..changes.len()).zip(first + 1..changes.len()) {
+            let change = &changes[cur];
+            let distance = change.old.start - changes[prev].old.end;
+            if distance > max_common {
+                break;
+            }
+            if distance < max_ignorable && (!change.ignore || last == prev) {
+                last = cur;
+                ignored = 0;
+            } else if distance < max_ignorable && change.ignore {
+                ignored += change.new.len();
+            } else if last != prev && change.old.start + ignored - changes[last].old.end > max_common {
+                break;
+            } else if !change.ignore {
+                last = cur;
+                ignored = 0;
+            } else {
+                ignored += change.new.len();
+            }
+        }
+        Some((first, last))
+    }
+
+    /// Return `line` if it looks like the start of a function according to Git's default rules, truncated to 80 bytes.
+    fn function_name(line: &[u8]) -> Option<bstr::BString> {
+        let first = *line.first()?;
+        if !(first.is_ascii_alphabetic() || first == b'_' || first == b'$') {
+            return None;
+        }
+        let line = &line[..line.len().min(80)];
+        Some(line.trim_end_with(|c| c.is_ascii_whitespace()).into())
+    }
+}
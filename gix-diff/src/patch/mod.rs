@@ -0,0 +1,142 @@
+use bstr::BString;
+
+/// A patch for a single file, as parsed from the output of `git diff` or `diff -u`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct File {
+    /// The path of the file before the change, or `None` if it was added.
+    ///
+    /// It's stripped of its leading path components as configured in [`parse::Options::strip_components`].
+    pub old_path: Option<BString>,
+    /// The path of the file after the change, or `None` if it was deleted.
+    ///
+    /// It's stripped of its leading path components as configured in [`parse::Options::strip_components`].
+    pub new_path: Option<BString>,
+    /// The mode of the file before the change, if known.
+    pub old_mode: Option<gix_object::tree::EntryMode>,
+    /// The mode of the file after the change, if known.
+    pub new_mode: Option<gix_object::tree::EntryMode>,
+    /// The kind of change to the file.
+    pub kind: Kind,
+    /// The similarity of the source and the destination of a [rename](Kind::Rename) or [copy](Kind::Copy) in percent,
+    /// or the dissimilarity of a complete rewrite.
+    pub similarity: Option<u8>,
+    /// The possibly abbreviated hexadecimal id of the blob before the change, as provided by the `index` line.
+    pub old_id: Option<BString>,
+    /// The possibly abbreviated hexadecimal id of the blob after the change, as provided by the `index` line.
+    pub new_id: Option<BString>,
+    /// The changes to the content of the file.
+    pub content: Content,
+}
+
+/// The kind of change a patch represents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// An existing file is modified in place.
+    Modification,
+    /// A new file is created.
+    Addition,
+    /// An existing file is removed.
+    Deletion,
+    /// An existing file is moved to a new location, possibly with modifications.
+    Rename,
+    /// An existing file is copied to a new location, possibly with modifications.
+    Copy,
+}
+
+/// The changes to the content of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Content {
+    /// Changes to a text file, which may be empty if only the mode or the location of the file changed.
+    Hunks(Vec<Hunk>),
+    /// The complete data needed to transform a binary file, as produced by `git diff --binary`.
+    Binary {
+        /// The data to turn the old version into the new one.
+        forward: Binary,
+        /// The data to turn the new version into the old one, which is absent in patches produced by older Git versions.
+        reverse: Option<Binary>,
+    },
+    /// A binary file changed, but the patch doesn't contain the data to apply the change, like in `Binary files a/x and b/x differ`.
+    BinaryWithoutData,
+}
+
+/// The decoded and decompressed data of a binary patch.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Binary {
+    /// The complete content of the file after the change.
+    Literal(Vec<u8>),
+    /// A delta in the same format as in packs, which produces the file after the change from the one before.
+    Delta(Vec<u8>),
+}
+
+/// A contiguous region of changes, along with the context lines surrounding it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    /// The 1-based line number in the old version of the file at which the hunk starts,
+    /// or the line before the hunk if [`old_len`](Self::old_len) is 0.
+    pub old_start: u32,
+    /// The amount of lines the hunk spans in the old version of the file.
+    pub old_len: u32,
+    /// The 1-based line number in the new version of the file at which the hunk starts,
+    /// or the line before the hunk if [`new_len`](Self::new_len) is 0.
+    pub new_start: u32,
+    /// The amount of lines the hunk spans in the new version of the file.
+    pub new_len: u32,
+    /// The text after the hunk header, which is the closest line before the hunk that looks like the start of a function
+    /// in patches produced by Git.
+    pub function_name: Option<BString>,
+    /// All lines of the hunk, in order.
+    pub lines: Vec<Line>,
+}
+
+/// A single line of a [`Hunk`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Line {
+    /// Whether the line is context, or was added or removed.
+    pub kind: LineKind,
+    /// The content of the line, including its line terminator if it had one.
+    pub content: BString,
+}
+
+/// Identify the role of a [`Line`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    /// The line is present in both versions of the file.
+    Context,
+    /// The line is only present in the old version of the file.
+    Remove,
+    /// The line is only present in the new version of the file.
+    Add,
+}
+
+impl File {
+    /// Return the path of the file after the change if it exists, or its path before the change if it was deleted.
+    pub fn path(&self) -> &BString {
+        self.new_path
+            .as_ref()
+            .or(self.old_path.as_ref())
+            .expect("BUG: the parser assures one of both paths is set")
+    }
+}
+
+mod binary;
+
+///
+pub mod write;
+pub use write::function::write;
+
+pub mod color;
+pub mod word_diff;
+
+///
+pub mod hunks;
+pub use hunks::function::hunks;
+
+pub mod stat;
+
+///
+pub mod parse;
+pub use parse::function::parse;
+
+///
+pub mod apply;
+pub use apply::function::apply;
@@ -0,0 +1,543 @@
+use bstr::BString;
+
+/// The error returned by [`parse()`](crate::patch::parse()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Line {line_number}: invalid hunk header '{line}'")]
+    InvalidHunkHeader { line_number: usize, line: BString },
+    #[error("Line {line_number}: unexpected line '{line}' in hunk")]
+    InvalidHunkLine { line_number: usize, line: BString },
+    #[error("Line {line_number}: the patch ended in the middle of a hunk")]
+    TruncatedHunk { line_number: usize },
+    #[error("Line {line_number}: invalid file mode '{mode}'")]
+    InvalidMode { line_number: usize, mode: BString },
+    #[error("Line {line_number}: invalid similarity index '{value}'")]
+    InvalidSimilarity { line_number: usize, value: BString },
+    #[error("Line {line_number}: invalid 'index' line '{line}'")]
+    InvalidIndexLine { line_number: usize, line: BString },
+    #[error("Line {line_number}: could not unquote path")]
+    Unquote {
+        line_number: usize,
+        source: gix_quote::ansi_c::undo::Error,
+    },
+    #[error("Line {line_number}: the path '{path}' has less than {strip_components} leading components to strip")]
+    StripComponents {
+        line_number: usize,
+        path: BString,
+        strip_components: usize,
+    },
+    #[error("Line {line_number}: could not determine the path of the patched file")]
+    MissingPath { line_number: usize },
+    #[error("Line {line_number}: invalid binary patch")]
+    Binary {
+        line_number: usize,
+        source: crate::patch::binary::Error,
+    },
+    #[error("Line {line_number}: invalid binary patch header '{line}'")]
+    InvalidBinaryHeader { line_number: usize, line: BString },
+}
+
+/// Options for use in [`parse()`](crate::patch::parse()).
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// The amount of leading path components to remove from the paths in the patch, like `git apply -p<n>`.
+    ///
+    /// It defaults to 1, which removes the `a/` and `b/` prefixes of patches produced by `git diff`.
+    pub strip_components: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options { strip_components: 1 }
+    }
+}
+
+pub(super) mod function {
+    use std::borrow::Cow;
+
+    use bstr::{BStr, BString, ByteSlice};
+
+    use super::{Error, Options};
+    use crate::patch::{binary, Binary, Content, File, Hunk, Kind, Line, LineKind};
+
+    /// Parse all file patches in `data`, the output of `git diff` or `diff -u`, with `options`, ignoring everything
+    /// that isn't part of a patch, like the commit message of patches produced by `git format-patch`.
+    ///
+    /// Besides plain unified diffs, this understands the extended headers written by Git to describe
+    /// mode changes, additions, deletions, renames and copies, as well as binary patches.
+    pub fn parse(data: &[u8], options: Options) -> Result<Vec<File>, Error> {
+        let mut lines = Lines::new(data);
+        let mut files = Vec::new();
+        while let Some(line) = lines.peek() {
+            let file = if let Some(header) = line.strip_prefix(b"diff --git ") {
+                let line_number = lines.line_number();
+                lines.next();
+                parse_git_header(header.trim_end().as_bstr(), &mut lines, line_number, options)?
+            } else if line.starts_with(b"--- ")
+                && lines.peek_nth(1).is_some_and(|line| line.starts_with(b"+++ "))
+                && lines.peek_nth(2).is_some_and(|line| line.starts_with(b"@@ -"))
+            {
+                parse_traditional_header(&mut lines, options)?
+            } else {
+                lines.next();
+                continue;
+            };
+            files.push(parse_content(file, &mut lines)?);
+        }
+        Ok(files)
+    }
+
+    struct Lines<'a> {
+        data: &'a [u8],
+        line_number: usize,
+    }
+
+    impl<'a> Lines<'a> {
+        fn new(data: &'a [u8]) -> Self {
+            Lines { data, line_number: 1 }
+        }
+
+        /// The 1-based number of the line that would be returned next.
+        fn line_number(&self) -> usize {
+            self.line_number
+        }
+
+        /// Return the next line including its terminator.
+        fn peek_with_terminator(&self) -> Option<&'a [u8]> {
+            self.data.lines_with_terminator().next()
+        }
+
+        fn peek(&self) -> Option<&'a [u8]> {
+            self.peek_with_terminator().map(without_terminator)
+        }
+
+        fn peek_nth(&self, n: usize) -> Option<&'a [u8]> {
+            self.data.lines_with_terminator().nth(n).map(without_terminator)
+        }
+
+        fn next_with_terminator(&mut self) -> Option<&'a [u8]> {
+            let line = self.peek_with_terminator()?;
+            self.data = &self.data[line.len()..];
+            self.line_number += 1;
+            Some(line)
+        }
+
+        fn next(&mut self) -> Option<&'a [u8]> {
+            self.next_with_terminator().map(without_terminator)
+        }
+    }
+
+    fn without_terminator(line: &[u8]) -> &[u8] {
+        let line = line.strip_suffix(b"\n").unwrap_or(line);
+        line.strip_suffix(b"\r").unwrap_or(line)
+    }
+
+    fn empty_file() -> File {
+        File {
+            old_path: None,
+            new_path: None,
+            old_mode: None,
+            new_mode: None,
+            kind: Kind::Modification,
+            similarity: None,
+            old_id: None,
+            new_id: None,
+            content: Content::Hunks(Vec::new()),
+        }
+    }
+
+    fn parse_git_header(
+        header: &BStr,
+        lines: &mut Lines<'_>,
+        line_number: usize,
+        options: Options,
+    ) -> Result<File, Error> {
+        let mut file = empty_file();
+        let default_path = default_path_from_git_header(header, line_number)?
+            .map(|path| strip(path, options.strip_components, line_number))
+            .transpose()?;
+        let mut saw_old_path = false;
+        let mut saw_new_path = false;
+
+        while let Some(line) = lines.peek() {
+            let line_number = lines.line_number();
+            if let Some(path) = line.strip_prefix(b"--- ") {
+                file.old_path = patch_path(path.as_bstr(), options, line_number)?;
+                saw_old_path = true;
+            } else if let Some(path) = line.strip_prefix(b"+++ ") {
+                file.new_path = patch_path(path.as_bstr(), options, line_number)?;
+                saw_new_path = true;
+            } else if let Some(mode) = line.strip_prefix(b"old mode ") {
+                file.old_mode = Some(parse_mode(mode, line_number)?);
+            } else if let Some(mode) = line.strip_prefix(b"new mode ") {
+                file.new_mode = Some(parse_mode(mode, line_number)?);
+            } else if let Some(mode) = line.strip_prefix(b"deleted file mode ") {
+                file.old_mode = Some(parse_mode(mode, line_number)?);
+                file.kind = Kind::Deletion;
+            } else if let Some(mode) = line.strip_prefix(b"new file mode ") {
+                file.new_mode = Some(parse_mode(mode, line_number)?);
+                file.kind = Kind::Addition;
+            } else if let Some(path) = line.strip_prefix(b"rename from ") {
+                file.old_path = Some(unquote(path.as_bstr(), line_number)?);
+                file.kind = Kind::Rename;
+                saw_old_path = true;
+            } else if let Some(path) = line.strip_prefix(b"rename to ") {
+                file.new_path = Some(unquote(path.as_bstr(), line_number)?);
+                file.kind = Kind::Rename;
+                saw_new_path = true;
+            } else if let Some(path) = line.strip_prefix(b"copy from ") {
+                file.old_path = Some(unquote(path.as_bstr(), line_number)?);
+                file.kind = Kind::Copy;
+                saw_old_path = true;
+            } else if let Some(path) = line.strip_prefix(b"copy to ") {
+                file.new_path = Some(unquote(path.as_bstr(), line_number)?);
+                file.kind = Kind::Copy;
+                saw_new_path = true;
+            } else if let Some(value) = line
+                .strip_prefix(b"similarity index ")
+                .or_else(|| line.strip_prefix(b"dissimilarity index "))
+            {
+                file.similarity = Some(parse_similarity(value, line_number)?);
+            } else if let Some(ids) = line.strip_prefix(b"index ") {
+                let (ids, mode) = match ids.find_byte(b' ') {
+                    Some(pos) => (&ids[..pos], Some(&ids[pos + 1..])),
+                    None => (ids, None),
+                };
+                let (old, new) = ids.split_once_str("..").ok_or_else(|| Error::InvalidIndexLine {
+                    line_number,
+                    line: line.into(),
+                })?;
+                file.old_id = Some(old.into());
+                file.new_id = Some(new.into());
+                if let Some(mode) = mode {
+                    let mode = parse_mode(mode, line_number)?;
+                    file.old_mode = Some(mode);
+                    file.new_mode = Some(mode);
+                }
+            } else {
+                break;
+            }
+            lines.next();
+        }
+
+        match file.kind {
+            Kind::Addition => {
+                file.old_path = None;
+                file.old_mode = None;
+                if !saw_new_path {
+                    file.new_path = default_path;
+                }
+            }
+            Kind::Deletion => {
+                file.new_path = None;
+                file.new_mode = None;
+                if !saw_old_path {
+                    file.old_path = default_path;
+                }
+            }
+            Kind::Modification | Kind::Rename | Kind::Copy => {
+                if !saw_old_path {
+                    file.old_path.clone_from(&default_path);
+                }
+                if !saw_new_path {
+                    file.new_path = default_path;
+                }
+                if file.kind == Kind::Modification && (file.old_path.is_none() || file.new_path.is_none()) {
+                    // Patches with `---` and `+++` lines but without `new file mode` or `deleted file mode`.
+                    file.kind = match (&file.old_path, &file.new_path) {
+                        (None, Some(_)) => Kind::Addition,
+                        (Some(_), None) => Kind::Deletion,
+                        _ => Kind::Modification,
+                    };
+                }
+            }
+        }
+        if file.old_path.is_none() && file.new_path.is_none() {
+            return Err(Error::MissingPath { line_number });
+        }
+        Ok(file)
+    }
+
+    fn parse_traditional_header(lines: &mut Lines<'_>, options: Options) -> Result<File, Error> {
+        let mut file = empty_file();
+        let line_number = lines.line_number();
+        let old = lines.next().expect("peeked").strip_prefix(b"--- ").expect("peeked");
+        file.old_path = patch_path(old.as_bstr(), options, line_number)?;
+        let new = lines.next().expect("peeked").strip_prefix(b"+++ ").expect("peeked");
+        file.new_path = patch_path(new.as_bstr(), options, line_number + 1)?;
+        file.kind = match (&file.old_path, &file.new_path) {
+            (None, Some(_)) => Kind::Addition,
+            (Some(_), None) => Kind::Deletion,
+            (Some(_), Some(_)) => Kind::Modification,
+            (None, None) => return Err(Error::MissingPath { line_number }),
+        };
+        Ok(file)
+    }
+
+    fn parse_content(mut file: File, lines: &mut Lines<'_>) -> Result<File, Error> {
+        let Some(line) = lines.peek() else {
+            return Ok(file);
+        };
+        if line == b"GIT binary patch" {
+            lines.next();
+            let forward = parse_binary(lines)?.ok_or_else(|| Error::InvalidBinaryHeader {
+                line_number: lines.line_number(),
+                line: lines.peek().unwrap_or_default().into(),
+            })?;
+            let reverse = parse_binary(lines)?;
+            file.content = Content::Binary { forward, reverse };
+        } else if line.starts_with(b"Binary files ") && line.ends_with(b" differ") {
+            lines.next();
+            file.content = Content::BinaryWithoutData;
+        } else {
+            let mut hunks = Vec::new();
+            while lines.peek().is_some_and(|line| line.starts_with(b"@@ -")) {
+                hunks.push(parse_hunk(lines)?);
+            }
+            file.content = Content::Hunks(hunks);
+        }
+        Ok(file)
+    }
+
+    fn parse_hunk(lines: &mut Lines<'_>) -> Result<Hunk, Error> {
+        let line_number = lines.line_number();
+        let header = lines.next().expect("peeked");
+        let (old_start, old_len, new_start, new_len, function_name) =
+            parse_hunk_header(header).ok_or_else(|| Error::InvalidHunkHeader {
+                line_number,
+                line: header.into(),
+            })?;
+
+        let mut hunk = Hunk {
+            old_start,
+            old_len,
+            new_start,
+            new_len,
+            function_name,
+            lines: Vec::new(),
+        };
+        let (mut old_remaining, mut new_remaining) = (old_len, new_len);
+        while old_remaining > 0 || new_remaining > 0 {
+            let line_number = lines.line_number();
+            let line = lines
+                .next_with_terminator()
+                .ok_or(Error::TruncatedHunk { line_number })?;
+            let invalid_line = || Error::InvalidHunkLine {
+                line_number,
+                line: without_terminator(line).into(),
+            };
+            let (kind, content) = match line[0] {
+                b' ' => (LineKind::Context, &line[1..]),
+                // An empty context line, as produced by some editors that strip trailing whitespace.
+                b'\n' | b'\r' => (LineKind::Context, line),
+                b'-' => (LineKind::Remove, &line[1..]),
+                b'+' => (LineKind::Add, &line[1..]),
+                b'\\' => {
+                    strip_last_terminator(&mut hunk).ok_or_else(invalid_line)?;
+                    continue;
+                }
+                _ => return Err(invalid_line()),
+            };
+            let (old, new) = match kind {
+                LineKind::Context => (1, 1),
+                LineKind::Remove => (1, 0),
+                LineKind::Add => (0, 1),
+            };
+            old_remaining = old_remaining.checked_sub(old).ok_or_else(invalid_line)?;
+            new_remaining = new_remaining.checked_sub(new).ok_or_else(invalid_line)?;
+            hunk.lines.push(Line {
+                kind,
+                content: content.into(),
+            });
+        }
+        if lines.peek().is_some_and(|line| line.starts_with(b"\\")) {
+            let line_number = lines.line_number();
+            let line = lines.next().expect("peeked");
+            strip_last_terminator(&mut hunk).ok_or_else(|| Error::InvalidHunkLine {
+                line_number,
+                line: line.into(),
+            })?;
+        }
+        Ok(hunk)
+    }
+
+    /// Handle `\ No newline at end of file` by removing the line terminator of the previous line.
+    fn strip_last_terminator(hunk: &mut Hunk) -> Option<()> {
+        let content = &mut hunk.lines.last_mut()?.content;
+        let len = content.len();
+        if content.ends_with(b"\r\n") {
+            content.truncate(len - 2);
+        } else if content.ends_with(b"\n") {
+            content.pop();
+        }
+        Some(())
+    }
+
+    /// Parse `@@ -<start>[,<len>] +<start>[,<len>] @@`.
+    fn parse_hunk_header(line: &[u8]) -> Option<(u32, u32, u32, u32, Option<BString>)> {
+        fn range(input: &[u8]) -> Option<(u32, u32)> {
+            let (start, len) = match input.split_once_str(",") {
+                Some((start, len)) => (start, Some(len)),
+                None => (input, None),
+            };
+            let start = start.to_str().ok()?.parse().ok()?;
+            let len = len.map_or(Some(1), |len| len.to_str().ok()?.parse().ok())?;
+            Some((start, len))
+        }
+        let line = line.strip_prefix(b"@@ -")?;
+        let (old, line) = line.split_once_str(" +")?;
+        let (new, function_name) = line.split_once_str(" @@")?;
+        let (old_start, old_len) = range(old)?;
+        let (new_start, new_len) = range(new)?;
+        let function_name = function_name.strip_prefix(b" ").filter(|name| !name.is_empty());
+        Some((old_start, old_len, new_start, new_len, function_name.map(Into::into)))
+    }
+
+    /// Parse one `literal <size>` or `delta <size>` section, or return `None` if there is none.
+    fn parse_binary(lines: &mut Lines<'_>) -> Result<Option<Binary>, Error> {
+        let Some(header) = lines.peek() else {
+            return Ok(None);
+        };
+        let header_line_number = lines.line_number();
+        let (is_literal, size) = if let Some(size) = header.strip_prefix(b"literal ") {
+            (true, size)
+        } else if let Some(size) = header.strip_prefix(b"delta ") {
+            (false, size)
+        } else {
+            return Ok(None);
+        };
+        let size: usize =
+            size.to_str()
+                .ok()
+                .and_then(|size| size.parse().ok())
+                .ok_or_else(|| Error::InvalidBinaryHeader {
+                    line_number: header_line_number,
+                    line: header.into(),
+                })?;
+        lines.next();
+
+        let mut compressed = Vec::new();
+        loop {
+            let line_number = lines.line_number();
+            match lines.next() {
+                None | Some(b"") => break,
+                Some(line) => binary::decode_base85_line(line, &mut compressed)
+                    .map_err(|source| Error::Binary { line_number, source })?,
+            }
+        }
+        let data = binary::inflate(&compressed, size).map_err(|source| Error::Binary {
+            line_number: header_line_number,
+            source,
+        })?;
+        Ok(Some(if is_literal {
+            Binary::Literal(data)
+        } else {
+            Binary::Delta(data)
+        }))
+    }
+
+    fn parse_mode(mode: &[u8], line_number: usize) -> Result<gix_object::tree::EntryMode, Error> {
+        mode.to_str()
+            .ok()
+            .and_then(|mode| u32::from_str_radix(mode, 8).ok())
+            .and_then(|mode| gix_object::tree::EntryMode::try_from(mode).ok())
+            .ok_or_else(|| Error::InvalidMode {
+                line_number,
+                mode: mode.into(),
+            })
+    }
+
+    fn parse_similarity(value: &[u8], line_number: usize) -> Result<u8, Error> {
+        value
+            .strip_suffix(b"%")
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse().ok())
+            .filter(|value| *value <= 100)
+            .ok_or_else(|| Error::InvalidSimilarity {
+                line_number,
+                value: value.into(),
+            })
+    }
+
+    fn unquote(path: &BStr, line_number: usize) -> Result<BString, Error> {
+        gix_quote::ansi_c::undo(path)
+            .map(|(path, _consumed)| path.into_owned())
+            .map_err(|source| Error::Unquote { line_number, source })
+    }
+
+    /// Parse the path of a `---` or `+++` line, which is `None` for `/dev/null`.
+    fn patch_path(path: &BStr, options: Options, line_number: usize) -> Result<Option<BString>, Error> {
+        let path = if path.starts_with(b"\"") {
+            unquote(path, line_number)?
+        } else {
+            // Traditional diffs may have a timestamp after a tab.
+            path.split_str("\t").next().unwrap_or_default().into()
+        };
+        if path == "/dev/null" {
+            return Ok(None);
+        }
+        strip(path, options.strip_components, line_number).map(Some)
+    }
+
+    fn strip(path: BString, strip_components: usize, line_number: usize) -> Result<BString, Error> {
+        if strip_components == 0 {
+            return Ok(path);
+        }
+        let mut stripped = path.as_slice();
+        for _ in 0..strip_components {
+            stripped = stripped
+                .find_byte(b'/')
+                .map(|pos| &stripped[pos + 1..])
+                .filter(|rest| !rest.is_empty())
+                .ok_or_else(|| Error::StripComponents {
+                    line_number,
+                    path: path.clone(),
+                    strip_components,
+                })?;
+        }
+        Ok(stripped.into())
+    }
+
+    /// Obtain the path from `a/<path> b/<path>`, which is only possible if both paths are the same,
+    /// as otherwise the paths are provided by later header lines.
+    fn default_path_from_git_header(header: &BStr, line_number: usize) -> Result<Option<BString>, Error> {
+        if header.starts_with(b"\"") {
+            let (old, consumed) =
+                gix_quote::ansi_c::undo(header).map_err(|source| Error::Unquote { line_number, source })?;
+            let new = header[consumed..].trim_start().as_bstr();
+            let new: Cow<'_, BStr> = if new.starts_with(b"\"") {
+                gix_quote::ansi_c::undo(new)
+                    .map_err(|source| Error::Unquote { line_number, source })?
+                    .0
+            } else {
+                new.into()
+            };
+            return Ok(same_path_without_prefix(old.as_ref(), new.as_ref()));
+        }
+        // Without quotes, the header is `<old> <new>`, and as both are the same but for the first component,
+        // the separating space must be in the middle.
+        if header.len() % 2 == 0 {
+            return Ok(None);
+        }
+        let mid = header.len() / 2;
+        if header[mid] != b' ' {
+            return Ok(None);
+        }
+        Ok(same_path_without_prefix(
+            header[..mid].as_bstr(),
+            header[mid + 1..].as_bstr(),
+        ))
+    }
+
+    /// Return `old` if it's the same as `new` when ignoring the first path component.
+    fn same_path_without_prefix(old: &BStr, new: &BStr) -> Option<BString> {
+        let old_rest = old.find_byte(b'/').map(|pos| &old[pos + 1..]);
+        let new_rest = new.find_byte(b'/').map(|pos| &new[pos + 1..]);
+        match (old_rest, new_rest) {
+            (Some(a), Some(b)) if a == b => Some(old.into()),
+            (None, None) if old == new => Some(old.into()),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,322 @@
+//! Summaries of patches in the formats of `git diff --stat`, `--numstat`, `--shortstat` and `--summary`.
+use std::io;
+
+use bstr::{BStr, BString, ByteSlice};
+
+use crate::patch::{binary, write::quote_path, Binary, Content, File, Kind, LineKind};
+
+/// The amount of changes to a single file, for display with [`write()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stat {
+    /// The name of the file for display, which is `old => new` for renames and copies, quoted if necessary.
+    pub name: BString,
+    /// The amount of added lines.
+    pub insertions: u32,
+    /// The amount of removed lines.
+    pub deletions: u32,
+    /// The size in bytes of the old and the new version of the file, if it is binary.
+    ///
+    /// Sizes that aren't known are 0.
+    pub binary: Option<(u64, u64)>,
+}
+
+impl File {
+    /// Return the amount of changes in this patch, for display with [`write()`].
+    pub fn stat(&self) -> Stat {
+        let mut name = Vec::new();
+        match self.old_path.as_ref().zip(self.new_path.as_ref()) {
+            Some((old, new)) if old != new => rename_name(old.as_ref(), new.as_ref(), &mut name),
+            _ => quote_path(b"", self.path(), &mut name),
+        }
+        let (mut insertions, mut deletions, mut binary) = (0, 0, None);
+        match &self.content {
+            Content::Hunks(hunks) => {
+                for line in hunks.iter().flat_map(|hunk| &hunk.lines) {
+                    match line.kind {
+                        LineKind::Add => insertions += 1,
+                        LineKind::Remove => deletions += 1,
+                        LineKind::Context => {}
+                    }
+                }
+            }
+            Content::Binary { forward, reverse } => {
+                let (mut old_size, new_size) = match forward {
+                    Binary::Literal(data) => (0, data.len() as u64),
+                    Binary::Delta(delta) => binary::delta_sizes(delta).unwrap_or_default(),
+                };
+                if let Some(Binary::Literal(data)) = reverse {
+                    old_size = data.len() as u64;
+                }
+                binary = Some((old_size, new_size));
+            }
+            Content::BinaryWithoutData => binary = Some((0, 0)),
+        }
+        Stat {
+            name: name.into(),
+            insertions,
+            deletions,
+            binary,
+        }
+    }
+}
+
+/// Write `stats` to `out` like `git diff --stat` would, with each line at most `width` characters wide
+/// unless names can't be shortened enough, followed by the [totals](write_totals()).
+///
+/// Git uses a `width` of 80 for terminals and files, and of 72 for `git format-patch`.
+pub fn write(stats: &[Stat], width: usize, out: &mut dyn io::Write) -> io::Result<()> {
+    let mut max_len = 0;
+    let mut max_change = 0;
+    let mut number_width = 0;
+    let mut bin_width = 0;
+    for stat in stats {
+        max_len = max_len.max(display_width(stat.name.as_ref()));
+        match stat.binary {
+            Some((old, new)) => {
+                // "Bin XXX -> YYY bytes"
+                bin_width = bin_width.max(14 + decimal_width(old) + decimal_width(new));
+                number_width = 3;
+            }
+            None => max_change = max_change.max(u64::from(stat.insertions) + u64::from(stat.deletions)),
+        }
+    }
+    number_width = number_width.max(decimal_width(max_change));
+
+    // This follows the logic in `show_stats()` of Git to split the width between names and the graph.
+    let mut graph_width = if max_change as usize + 4 > bin_width {
+        max_change as usize
+    } else {
+        bin_width - 4
+    };
+    let mut name_width = max_len;
+    if name_width + number_width + 6 + graph_width > width {
+        let max_graph_width = (width * 3 / 8).saturating_sub(number_width + 6);
+        if graph_width > max_graph_width {
+            graph_width = max_graph_width.max(6);
+        }
+        if name_width > width.saturating_sub(number_width + 6 + graph_width) {
+            name_width = width.saturating_sub(number_width + 6 + graph_width);
+        } else {
+            graph_width = width - number_width - 6 - name_width;
+        }
+    }
+
+    let mut buf = Vec::new();
+    for stat in stats {
+        let (prefix, name) = shorten_name(stat.name.as_ref(), name_width);
+        let padding = name_width.saturating_sub(prefix.len() + display_width(name));
+        buf.push(b' ');
+        buf.extend_from_slice(prefix.as_bytes());
+        buf.extend_from_slice(name);
+        buf.resize(buf.len() + padding, b' ');
+        buf.extend_from_slice(b" | ");
+        match stat.binary {
+            Some((old, new)) => {
+                buf.extend_from_slice(format!("{:>number_width$}", "Bin").as_bytes());
+                if old != 0 || new != 0 {
+                    buf.extend_from_slice(format!(" {old} -> {new} bytes").as_bytes());
+                }
+            }
+            None => {
+                let changes = u64::from(stat.insertions) + u64::from(stat.deletions);
+                buf.extend_from_slice(format!("{changes:>number_width$}").as_bytes());
+                if changes != 0 {
+                    buf.push(b' ');
+                }
+                let (mut add, mut del) = (u64::from(stat.insertions), u64::from(stat.deletions));
+                if graph_width as u64 <= max_change {
+                    let mut total = scale_linear(add + del, graph_width as u64, max_change);
+                    if total < 2 && add != 0 && del != 0 {
+                        total = 2;
+                    }
+                    if add < del {
+                        add = scale_linear(add, graph_width as u64, max_change);
+                        del = total - add;
+                    } else {
+                        del = scale_linear(del, graph_width as u64, max_change);
+                        add = total - del;
+                    }
+                }
+                buf.resize(buf.len() + add as usize, b'+');
+                buf.resize(buf.len() + del as usize, b'-');
+            }
+        }
+        buf.push(b'\n');
+    }
+    out.write_all(&buf)?;
+    write_totals(stats, out)
+}
+
+/// Write the total amount of changed files, insertions and deletions in `stats` to `out` like `git diff --shortstat` would.
+pub fn write_totals(stats: &[Stat], out: &mut dyn io::Write) -> io::Result<()> {
+    let files = stats.len();
+    if files == 0 {
+        return out.write_all(b" 0 files changed\n");
+    }
+    let (insertions, deletions) = stats
+        .iter()
+        .filter(|stat| stat.binary.is_none())
+        .fold((0u64, 0u64), |(ins, del), stat| {
+            (ins + u64::from(stat.insertions), del + u64::from(stat.deletions))
+        });
+    let plural = |n: u64| if n == 1 { "" } else { "s" };
+    write!(out, " {files} file{} changed", plural(files as u64))?;
+    if insertions != 0 || deletions == 0 {
+        write!(out, ", {insertions} insertion{}(+)", plural(insertions))?;
+    }
+    if deletions != 0 || insertions == 0 {
+        write!(out, ", {deletions} deletion{}(-)", plural(deletions))?;
+    }
+    writeln!(out)
+}
+
+/// Write `stats` to `out` like `git diff --numstat` would, with the amount of added and removed lines
+/// followed by the name of the file, separated by tabs.
+///
+/// Binary files are written with `-` instead of the amount of lines.
+pub fn write_numstat(stats: &[Stat], out: &mut dyn io::Write) -> io::Result<()> {
+    let mut buf = Vec::new();
+    for stat in stats {
+        match stat.binary {
+            Some(_) => buf.extend_from_slice(b"-\t-\t"),
+            None => buf.extend_from_slice(format!("{}\t{}\t", stat.insertions, stat.deletions).as_bytes()),
+        }
+        buf.extend_from_slice(&stat.name);
+        buf.push(b'\n');
+    }
+    out.write_all(&buf)
+}
+
+/// Write a summary of created, deleted, renamed and copied `files`, as well as of changed modes, to `out`
+/// like `git diff --summary` would.
+pub fn write_summary(files: &[File], out: &mut dyn io::Write) -> io::Result<()> {
+    let mut buf = Vec::new();
+    for file in files {
+        let mode_change = file
+            .old_mode
+            .zip(file.new_mode)
+            .filter(|(old, new)| old != new)
+            .map(|(old, new)| format!(" mode change {:06o} => {:06o}", old.value(), new.value()));
+        match file.kind {
+            Kind::Addition | Kind::Deletion => {
+                let (verb, mode): (&[u8], _) = if file.kind == Kind::Addition {
+                    (b" create", file.new_mode)
+                } else {
+                    (b" delete", file.old_mode)
+                };
+                buf.extend_from_slice(verb);
+                match mode {
+                    Some(mode) => buf.extend_from_slice(format!(" mode {:06o} ", mode.value()).as_bytes()),
+                    None => buf.push(b' '),
+                }
+                quote_path(b"", file.path(), &mut buf);
+                buf.push(b'\n');
+            }
+            Kind::Rename | Kind::Copy => {
+                buf.extend_from_slice(if file.kind == Kind::Rename {
+                    b" rename "
+                } else {
+                    b" copy "
+                });
+                let old = file.old_path.as_ref().unwrap_or(file.path());
+                rename_name(old.as_ref(), file.path().as_ref(), &mut buf);
+                buf.extend_from_slice(format!(" ({}%)\n", file.similarity.unwrap_or(100)).as_bytes());
+                if let Some(mode_change) = mode_change {
+                    buf.extend_from_slice(mode_change.as_bytes());
+                    buf.push(b'\n');
+                }
+            }
+            Kind::Modification => {
+                if let Some(dissimilarity) = file.similarity {
+                    buf.extend_from_slice(b" rewrite ");
+                    quote_path(b"", file.path(), &mut buf);
+                    buf.extend_from_slice(format!(" ({dissimilarity}%)\n").as_bytes());
+                }
+                if let Some(mode_change) = mode_change {
+                    buf.extend_from_slice(mode_change.as_bytes());
+                    if file.similarity.is_none() {
+                        buf.push(b' ');
+                        quote_path(b"", file.path(), &mut buf);
+                    }
+                    buf.push(b'\n');
+                }
+            }
+        }
+    }
+    out.write_all(&buf)
+}
+
+/// Write `old => new` to `out`, but factor out common leading and trailing directories like
+/// `dir/{old => new}/file`, just like `pprint_rename()` in Git.
+fn rename_name(old: &BStr, new: &BStr, out: &mut Vec<u8>) {
+    let mut prefix_len = 0;
+    for (idx, (a, b)) in old.iter().zip(new.iter()).enumerate() {
+        if a != b {
+            break;
+        }
+        if *a == b'/' {
+            prefix_len = idx + 1;
+        }
+    }
+    let mut suffix_len = 0;
+    // With a common prefix, let the search include its trailing slash.
+    let min = prefix_len.saturating_sub(usize::from(prefix_len != 0));
+    let (mut a, mut b) = (old.len(), new.len());
+    while a > min && b > min && old[a - 1] == new[b - 1] {
+        if old[a - 1] == b'/' {
+            suffix_len = old.len() - (a - 1);
+        }
+        a -= 1;
+        b -= 1;
+    }
+    let old_mid = old.len().saturating_sub(prefix_len + suffix_len);
+    let new_mid = new.len().saturating_sub(prefix_len + suffix_len);
+    let needs_braces = prefix_len + suffix_len != 0;
+    let mut name = Vec::new();
+    name.extend_from_slice(&old[..prefix_len]);
+    if needs_braces {
+        name.push(b'{');
+    }
+    name.extend_from_slice(&old[prefix_len..prefix_len + old_mid]);
+    name.extend_from_slice(b" => ");
+    name.extend_from_slice(&new[prefix_len..prefix_len + new_mid]);
+    if needs_braces {
+        name.push(b'}');
+        name.extend_from_slice(&old[old.len() - suffix_len..]);
+    }
+    quote_path(b"", &name, out);
+}
+
+/// Shorten `name` to fit into `width` by cutting off its start, preferably at a directory boundary,
+/// and return the prefix to indicate that along with the shortened name.
+fn shorten_name(name: &BStr, width: usize) -> (&'static str, &[u8]) {
+    if display_width(name) <= width {
+        return ("", name);
+    }
+    let len = width.saturating_sub(3);
+    let mut name = &name[name.len().saturating_sub(len)..];
+    if let Some(pos) = name.find_byte(b'/') {
+        name = &name[pos..];
+    }
+    ("...", name)
+}
+
+fn display_width(name: &[u8]) -> usize {
+    name.chars().count()
+}
+
+fn decimal_width(mut n: u64) -> usize {
+    let mut width = 1;
+    while n >= 10 {
+        n /= 10;
+        width += 1;
+    }
+    width
+}
+
+fn scale_linear(it: u64, width: u64, max_change: u64) -> u64 {
+    if it == 0 {
+        return 0;
+    }
+    1 + it * (width.saturating_sub(1)) / max_change
+}
@@ -0,0 +1,175 @@
+//! Display changes word by word instead of line by line, like `git diff --word-diff`.
+use std::ops::Range;
+
+use imara_diff::intern::{Interner, Token};
+
+use crate::patch::color::{Colors, RESET};
+
+/// Determine how changed words are marked, like `git diff --word-diff=<mode>`.
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Mode {
+    /// Removed words are enclosed in `[-` and `-]`, and added words in `{+` and `+}`.
+    #[default]
+    Plain,
+    /// Changed words are only highlighted with the colors for removed and added lines.
+    Color,
+    /// A line-based format for use by scripts, with each run of words on its own line starting with ` `, `-` or `+`,
+    /// and a line with `~` for each newline.
+    Porcelain,
+}
+
+/// Find the first word in the given text and return its position, or `None` if there is no further word.
+///
+/// Words are never longer than a line, so they are truncated at the first newline.
+/// An empty word is treated like the end of all words.
+pub type FindWord<'a> = &'a dyn Fn(&[u8]) -> Option<Range<usize>>;
+
+/// Options for use in [`Options::word_diff`](crate::patch::write::Options::word_diff).
+#[derive(Default, Clone, Copy)]
+pub struct Options<'a> {
+    /// How changed words are marked.
+    pub mode: Mode,
+    /// The function to find words with, which is typically backed by a regular expression like `diff.wordRegex`.
+    ///
+    /// If `None`, words are sequences of non-whitespace characters, which is Git's default.
+    pub find_word: Option<FindWord<'a>>,
+}
+
+impl std::fmt::Debug for Options<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Options")
+            .field("mode", &self.mode)
+            .field("find_word", &self.find_word.map(|_| "<fn>"))
+            .finish()
+    }
+}
+
+/// The default way of finding words, as sequences of non-whitespace characters.
+fn default_word(text: &[u8]) -> Option<Range<usize>> {
+    let start = text.iter().position(|b| !b.is_ascii_whitespace() && *b != b'\x0b')?;
+    let len = text[start..]
+        .iter()
+        .position(|b| b.is_ascii_whitespace() || *b == b'\x0b')
+        .unwrap_or(text.len() - start);
+    Some(start..start + len)
+}
+
+/// The markup to use for a kind of text.
+struct Style<'a> {
+    color: &'a str,
+    prefix: &'a str,
+    suffix: &'a str,
+}
+
+/// Write the word diff of `old` and `new`, the text of consecutive removed and added lines, to `out`,
+/// with `colors` if set.
+///
+/// This follows `diff_words_show()` in Git.
+pub(crate) fn write(old: &[u8], new: &[u8], options: Options<'_>, colors: Option<&Colors>, out: &mut Vec<u8>) {
+    let (removed, added, context, newline) = match options.mode {
+        Mode::Plain => (("[-", "-]"), ("{+", "+}"), ("", ""), "\n"),
+        Mode::Color => (("", ""), ("", ""), ("", ""), "\n"),
+        Mode::Porcelain => (("-", "\n"), ("+", "\n"), (" ", "\n"), "~\n"),
+    };
+    fn style<'a>((prefix, suffix): (&'a str, &'a str), color: Option<&'a str>) -> Style<'a> {
+        Style {
+            color: color.unwrap_or_default(),
+            prefix,
+            suffix,
+        }
+    }
+    let removed = style(removed, colors.map(|c| c.old.as_str()));
+    let added = style(added, colors.map(|c| c.new.as_str()));
+    let context = style(context, colors.map(|c| c.context.as_str()));
+
+    if new.is_empty() {
+        write_text(old, &removed, newline, out);
+        return;
+    }
+
+    let find_word = options.find_word.unwrap_or(&default_word);
+    let old_words = words(old, find_word);
+    let new_words = words(new, find_word);
+    let mut interner = Interner::new(old_words.len() + new_words.len());
+    let old_tokens: Vec<Token> = old_words.iter().map(|w| interner.intern(&old[w.clone()])).collect();
+    let new_tokens: Vec<Token> = new_words.iter().map(|w| interner.intern(&new[w.clone()])).collect();
+
+    // The position of text in `new` that wasn't written yet.
+    let mut new_pos = 0;
+    let span = |words: &[Range<usize>], changed: Range<u32>| -> Range<usize> {
+        let changed = changed.start as usize..changed.end as usize;
+        if changed.is_empty() {
+            // Empty ranges are positioned after the previous word.
+            let pos = changed.start.checked_sub(1).map_or(0, |prev| words[prev].end);
+            pos..pos
+        } else {
+            words[changed.start].start..words[changed.end - 1].end
+        }
+    };
+    imara_diff::diff_with_tokens(
+        imara_diff::Algorithm::Myers,
+        &old_tokens,
+        &new_tokens,
+        interner.num_tokens(),
+        |before: Range<u32>, after: Range<u32>| {
+            let old_span = span(&old_words, before);
+            let new_span = span(&new_words, after);
+            if new_pos != new_span.start {
+                write_text(&new[new_pos..new_span.start], &context, newline, out);
+            }
+            if !old_span.is_empty() {
+                write_text(&old[old_span], &removed, newline, out);
+            }
+            if !new_span.is_empty() {
+                write_text(&new[new_span.clone()], &added, newline, out);
+            }
+            new_pos = new_span.end;
+        },
+    );
+    if new_pos != new.len() {
+        write_text(&new[new_pos..], &context, newline, out);
+    }
+}
+
+/// Find the positions of all words in `text`.
+fn words(text: &[u8], find_word: FindWord<'_>) -> Vec<Range<usize>> {
+    let mut words = Vec::new();
+    let mut pos = 0;
+    while pos < text.len() {
+        let Some(word) = find_word(&text[pos..]) else {
+            break;
+        };
+        let start = pos + word.start;
+        let end = text[start..pos + word.end]
+            .iter()
+            .position(|b| *b == b'\n')
+            .map_or(pos + word.end, |newline| start + newline);
+        if start >= end {
+            break;
+        }
+        words.push(start..end);
+        pos = end;
+    }
+    words
+}
+
+/// Write `text` with `style` applied to each of its lines, and use `newline` instead of newlines.
+///
+/// This follows `fn_out_diff_words_write_helper()` in Git.
+fn write_text(text: &[u8], style: &Style<'_>, newline: &str, out: &mut Vec<u8>) {
+    for (idx, line) in text.split(|b| *b == b'\n').enumerate() {
+        if idx != 0 {
+            out.extend_from_slice(newline.as_bytes());
+        }
+        if line.is_empty() {
+            continue;
+        }
+        out.extend_from_slice(style.color.as_bytes());
+        out.extend_from_slice(style.prefix.as_bytes());
+        out.extend_from_slice(line);
+        out.extend_from_slice(style.suffix.as_bytes());
+        if !style.color.is_empty() {
+            out.extend_from_slice(RESET.as_bytes());
+        }
+    }
+}
@@ -0,0 +1,521 @@
+use std::io;
+
+use crate::{
+    blob::whitespace::is_space,
+    patch::{
+        binary,
+        color::{self, Colors, Marker, RESET},
+        Binary, File, Hunk, Kind, Line, LineKind,
+    },
+};
+
+/// Options for use in [`write()`](crate::patch::write()).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options<'a> {
+    /// The colors to use for each part of the patch, or `None` to write it without colors.
+    pub colors: Option<&'a Colors>,
+    /// If set, detect lines that were moved within or across files and highlight them with their own colors.
+    ///
+    /// This only has an effect if [`colors`](Self::colors) are set, and no [`word_diff`](Self::word_diff) is used.
+    pub color_moved: Option<color::Moved>,
+    /// If set, changed lines are displayed word by word in the given way.
+    pub word_diff: Option<crate::patch::word_diff::Options<'a>>,
+}
+
+impl File {
+    /// Write this patch to `out` in the format produced by `git diff`, so that [`parse()`](crate::patch::parse())
+    /// would turn it back into an equal instance if paths are stripped by one component.
+    ///
+    /// Object ids are written as they are set, which means they should be abbreviated by the caller if desired.
+    pub fn write_to(&self, out: &mut dyn io::Write) -> io::Result<()> {
+        function::write(std::slice::from_ref(self), Options::default(), out)
+    }
+
+    /// Write the extended header of this patch, everything before the `---` line, to `buf`.
+    fn write_header(&self, buf: &mut Vec<u8>) {
+        let old_path = self.old_path.as_ref().unwrap_or(self.path());
+        let new_path = self.new_path.as_ref().unwrap_or(self.path());
+        buf.extend_from_slice(b"diff --git ");
+        quote_path(b"a/", old_path, buf);
+        buf.push(b' ');
+        quote_path(b"b/", new_path, buf);
+        buf.push(b'\n');
+
+        match self.kind {
+            Kind::Addition => {
+                if let Some(mode) = self.new_mode {
+                    buf.extend_from_slice(format!("new file mode {:06o}\n", mode.value()).as_bytes());
+                }
+            }
+            Kind::Deletion => {
+                if let Some(mode) = self.old_mode {
+                    buf.extend_from_slice(format!("deleted file mode {:06o}\n", mode.value()).as_bytes());
+                }
+            }
+            Kind::Modification | Kind::Rename | Kind::Copy => {
+                if let Some((old, new)) = self.old_mode.zip(self.new_mode).filter(|(old, new)| old != new) {
+                    buf.extend_from_slice(
+                        format!("old mode {:06o}\nnew mode {:06o}\n", old.value(), new.value()).as_bytes(),
+                    );
+                }
+            }
+        }
+        match self.kind {
+            Kind::Rename | Kind::Copy => {
+                let verb: &[u8] = if self.kind == Kind::Rename { b"rename" } else { b"copy" };
+                if let Some(similarity) = self.similarity {
+                    buf.extend_from_slice(format!("similarity index {similarity}%\n").as_bytes());
+                }
+                for (direction, path) in [(b"from".as_slice(), old_path), (b"to", new_path)] {
+                    buf.extend_from_slice(verb);
+                    buf.push(b' ');
+                    buf.extend_from_slice(direction);
+                    buf.push(b' ');
+                    quote_path(b"", path, buf);
+                    buf.push(b'\n');
+                }
+            }
+            Kind::Modification => {
+                if let Some(dissimilarity) = self.similarity {
+                    buf.extend_from_slice(format!("dissimilarity index {dissimilarity}%\n").as_bytes());
+                }
+            }
+            Kind::Addition | Kind::Deletion => {}
+        }
+        if let Some((old_id, new_id)) = self
+            .old_id
+            .as_ref()
+            .zip(self.new_id.as_ref())
+            .filter(|(old, new)| old != new)
+        {
+            buf.extend_from_slice(b"index ");
+            buf.extend_from_slice(old_id);
+            buf.extend_from_slice(b"..");
+            buf.extend_from_slice(new_id);
+            if let Some(mode) = self.old_mode.filter(|mode| {
+                self.new_mode == Some(*mode) && matches!(self.kind, Kind::Modification | Kind::Rename | Kind::Copy)
+            }) {
+                buf.extend_from_slice(format!(" {:06o}", mode.value()).as_bytes());
+            }
+            buf.push(b'\n');
+        }
+    }
+}
+
+pub(super) mod function {
+    use std::io;
+
+    use super::{quote_path, write_binary, write_hunk, write_meta, Options};
+    use crate::patch::{color, Content, File, Kind};
+
+    /// Write `files` to `out` in the format produced by `git diff`, configured by `options`.
+    ///
+    /// Without colors and word diffs, the output can be [parsed](crate::patch::parse()) and applied again.
+    /// Object ids are written as they are set, which means they should be abbreviated by the caller if desired.
+    pub fn write(files: &[File], options: Options<'_>, out: &mut dyn io::Write) -> io::Result<()> {
+        let word_diff = options.word_diff;
+
+        let markers = match (options.colors, options.color_moved) {
+            (Some(_), Some(mode)) if word_diff.is_none() => {
+                let mut lines = Vec::new();
+                for file in files {
+                    if let Content::Hunks(hunks) = &file.content {
+                        for hunk in hunks {
+                            lines.push(None);
+                            for line in &hunk.lines {
+                                lines.push(Some((line.kind, line.content.as_slice())));
+                                if !line.content.ends_with(b"\n") {
+                                    lines.push(None);
+                                }
+                            }
+                        }
+                    }
+                }
+                color::detect_moved(&lines, mode)
+            }
+            _ => Vec::new(),
+        };
+        let mut markers = markers.into_iter();
+
+        let colors = options.colors;
+        let mut buf = Vec::new();
+        for file in files {
+            let mut header = Vec::new();
+            file.write_header(&mut header);
+            for line in header.split_inclusive(|b| *b == b'\n') {
+                write_meta(line.strip_suffix(b"\n").unwrap_or(line), b"", colors, &mut buf);
+            }
+
+            let old_path = file.old_path.as_ref().unwrap_or(file.path());
+            let new_path = file.new_path.as_ref().unwrap_or(file.path());
+            let label = |marker: &[u8], prefix: &[u8], path: &[u8], exists: bool, buf: &mut Vec<u8>| {
+                let mut line = marker.to_vec();
+                if exists {
+                    quote_path(prefix, path, &mut line);
+                } else {
+                    line.extend_from_slice(b"/dev/null");
+                }
+                // Like Git, help tools that consider the end of the path to be the first space.
+                let suffix: &[u8] = if exists && path.contains(&b' ') { b"\t" } else { b"" };
+                write_meta(&line, suffix, colors, buf);
+            };
+            match &file.content {
+                Content::Hunks(hunks) if !hunks.is_empty() => {
+                    label(b"--- ", b"a/", old_path, file.kind != Kind::Addition, &mut buf);
+                    label(b"+++ ", b"b/", new_path, file.kind != Kind::Deletion, &mut buf);
+                    for (idx, hunk) in hunks.iter().enumerate() {
+                        if let Some(word_diff) = word_diff {
+                            super::write_hunk_words(hunk, word_diff, colors, &mut buf);
+                            continue;
+                        }
+                        let is_last = idx + 1 == hunks.len();
+                        write_hunk(hunk, is_last, colors, &mut markers, &mut buf);
+                    }
+                }
+                Content::Hunks(_) => {}
+                Content::Binary { forward, reverse } => {
+                    buf.extend_from_slice(b"GIT binary patch\n");
+                    write_binary(forward, &mut buf)?;
+                    if let Some(reverse) = reverse {
+                        write_binary(reverse, &mut buf)?;
+                    }
+                }
+                Content::BinaryWithoutData => {
+                    buf.extend_from_slice(b"Binary files ");
+                    if file.kind == Kind::Addition {
+                        buf.extend_from_slice(b"/dev/null");
+                    } else {
+                        quote_path(b"a/", old_path, &mut buf);
+                    }
+                    buf.extend_from_slice(b" and ");
+                    if file.kind == Kind::Deletion {
+                        buf.extend_from_slice(b"/dev/null");
+                    } else {
+                        quote_path(b"b/", new_path, &mut buf);
+                    }
+                    buf.extend_from_slice(b" differ\n");
+                }
+            }
+        }
+        out.write_all(&buf)
+    }
+}
+
+/// Write a single line of the header with `suffix` after it, in the color for meta information if `colors` are set.
+fn write_meta(line: &[u8], suffix: &[u8], colors: Option<&Colors>, buf: &mut Vec<u8>) {
+    match colors {
+        Some(colors) => {
+            buf.extend_from_slice(colors.meta.as_bytes());
+            buf.extend_from_slice(line);
+            buf.extend_from_slice(RESET.as_bytes());
+        }
+        None => buf.extend_from_slice(line),
+    }
+    buf.extend_from_slice(suffix);
+    buf.push(b'\n');
+}
+
+/// Write `hunk` to `buf`, with `colors` if set, and highlight moved lines according to `markers`, which has one marker
+/// for each displayed line. `is_last` is `true` if this is the last hunk of the file.
+fn write_hunk(
+    hunk: &Hunk,
+    is_last: bool,
+    colors: Option<&Colors>,
+    markers: &mut impl Iterator<Item = Marker>,
+    buf: &mut Vec<u8>,
+) {
+    write_hunk_header(hunk, colors, buf);
+    let Some(colors) = colors else {
+        for line in &hunk.lines {
+            buf.push(match line.kind {
+                LineKind::Context => b' ',
+                LineKind::Remove => b'-',
+                LineKind::Add => b'+',
+            });
+            buf.extend_from_slice(&line.content);
+            if !line.content.ends_with(b"\n") {
+                buf.extend_from_slice(b"\n\\ No newline at end of file\n");
+            }
+        }
+        return;
+    };
+
+    // Skip the marker of the hunk header.
+    markers.next();
+    let blank_at_eof = if is_last && !colors.whitespace.is_empty() {
+        blank_lines_at_eof(hunk)
+    } else {
+        Vec::new()
+    };
+    for (idx, line) in hunk.lines.iter().enumerate() {
+        let marker = markers.next().unwrap_or(Marker::None);
+        let content = line.content.strip_suffix(b"\n").unwrap_or(&line.content);
+        let (content, cr) = match content.strip_suffix(b"\r") {
+            Some(content) => (content, true),
+            None => (content, false),
+        };
+        match line.kind {
+            LineKind::Context => write_colored(&colors.context, b' ', content, buf),
+            LineKind::Remove => {
+                let color = match marker {
+                    Marker::None => &colors.old,
+                    Marker::Moved => &colors.old_moved,
+                    Marker::MovedAlternative => &colors.old_moved_alternative,
+                };
+                write_colored(color, b'-', content, buf);
+            }
+            LineKind::Add => {
+                let color = match marker {
+                    Marker::None => &colors.new,
+                    Marker::Moved => &colors.new_moved,
+                    Marker::MovedAlternative => &colors.new_moved_alternative,
+                };
+                if blank_at_eof.get(idx).copied().unwrap_or_default() {
+                    write_colored(&colors.whitespace, b'+', content, buf);
+                } else if colors.whitespace.is_empty() {
+                    write_colored(color, b'+', content, buf);
+                } else {
+                    write_colored(color, b'+', b"", buf);
+                    write_with_whitespace_errors(content, color, &colors.whitespace, buf);
+                }
+            }
+        }
+        if cr {
+            buf.push(b'\r');
+        }
+        buf.push(b'\n');
+        if !line.content.ends_with(b"\n") {
+            markers.next();
+            write_colored(&colors.context, b'\\', b" No newline at end of file", buf);
+            buf.push(b'\n');
+        }
+    }
+}
+
+/// Write the header line of `hunk` to `buf`, with `colors` if set.
+fn write_hunk_header(hunk: &Hunk, colors: Option<&Colors>, buf: &mut Vec<u8>) {
+    fn range(start: u32, len: u32) -> String {
+        if len == 1 {
+            start.to_string()
+        } else {
+            format!("{start},{len}")
+        }
+    }
+    let header = format!(
+        "@@ -{} +{} @@",
+        range(hunk.old_start, hunk.old_len),
+        range(hunk.new_start, hunk.new_len)
+    );
+    match colors {
+        Some(colors) => {
+            buf.extend_from_slice(colors.frag.as_bytes());
+            buf.extend_from_slice(header.as_bytes());
+            buf.extend_from_slice(RESET.as_bytes());
+            if let Some(name) = &hunk.function_name {
+                write_colored(&colors.context, b' ', b"", buf);
+                buf.extend_from_slice(colors.func.as_bytes());
+                buf.extend_from_slice(name);
+                buf.extend_from_slice(RESET.as_bytes());
+            }
+        }
+        None => {
+            buf.extend_from_slice(header.as_bytes());
+            if let Some(name) = &hunk.function_name {
+                buf.push(b' ');
+                buf.extend_from_slice(name);
+            }
+        }
+    }
+    buf.push(b'\n');
+}
+
+/// Write `hunk` to `buf` with changed lines displayed word by word as configured in `options`, with `colors` if set.
+///
+/// This follows `fn_out_consume()` in Git, which collects consecutive removed and added lines to compare them
+/// word by word once the next context line is reached.
+fn write_hunk_words(
+    hunk: &Hunk,
+    options: crate::patch::word_diff::Options<'_>,
+    colors: Option<&Colors>,
+    buf: &mut Vec<u8>,
+) {
+    use crate::patch::word_diff;
+
+    write_hunk_header(hunk, colors, buf);
+    let (mut old, mut new) = (Vec::new(), Vec::new());
+    let flush = |old: &mut Vec<u8>, new: &mut Vec<u8>, buf: &mut Vec<u8>| {
+        if !old.is_empty() || !new.is_empty() {
+            word_diff::write(old, new, options, colors, buf);
+            old.clear();
+            new.clear();
+        }
+    };
+    for line in &hunk.lines {
+        let text = match line.kind {
+            LineKind::Remove => &mut old,
+            LineKind::Add => &mut new,
+            LineKind::Context => {
+                flush(&mut old, &mut new, buf);
+                write_word_diff_context(line, options.mode, colors, buf);
+                continue;
+            }
+        };
+        text.extend_from_slice(&line.content);
+        if !line.content.ends_with(b"\n") {
+            text.push(b'\n');
+        }
+    }
+    flush(&mut old, &mut new, buf);
+}
+
+/// Write the context `line` in a word diff of `mode`, with `colors` if set.
+fn write_word_diff_context(
+    line: &Line,
+    mode: crate::patch::word_diff::Mode,
+    colors: Option<&Colors>,
+    buf: &mut Vec<u8>,
+) {
+    let is_porcelain = mode == crate::patch::word_diff::Mode::Porcelain;
+    let content = line.content.strip_suffix(b"\n").unwrap_or(&line.content);
+    if is_porcelain || !content.is_empty() {
+        if let Some(colors) = colors {
+            buf.extend_from_slice(colors.context.as_bytes());
+        }
+        if is_porcelain {
+            buf.push(b' ');
+        }
+        buf.extend_from_slice(content);
+        if colors.is_some() {
+            buf.extend_from_slice(RESET.as_bytes());
+        }
+    }
+    buf.push(b'\n');
+    if is_porcelain {
+        buf.extend_from_slice(b"~\n");
+    }
+}
+
+/// Write `sign` and `content` in `color`, followed by a reset, like `emit_line_0()` in Git.
+fn write_colored(color: &str, sign: u8, content: &[u8], buf: &mut Vec<u8>) {
+    buf.extend_from_slice(color.as_bytes());
+    buf.push(sign);
+    buf.extend_from_slice(content);
+    buf.extend_from_slice(RESET.as_bytes());
+}
+
+/// Write the `content` of an added line in `color`, but highlight whitespace errors in `ws_color`.
+///
+/// Like Git does by default, trailing whitespace and spaces before tabs in the indentation are considered errors.
+/// This follows `ws_check_emit_1()` in Git.
+fn write_with_whitespace_errors(content: &[u8], color: &str, ws_color: &str, buf: &mut Vec<u8>) {
+    let trailing_whitespace = content.iter().rposition(|b| !is_space(*b)).map_or(0, |pos| pos + 1);
+    let mut written = 0;
+    for (idx, b) in content[..trailing_whitespace].iter().enumerate() {
+        match b {
+            b' ' => continue,
+            b'\t' => {
+                if written < idx {
+                    buf.extend_from_slice(ws_color.as_bytes());
+                    buf.extend_from_slice(&content[written..idx]);
+                    buf.extend_from_slice(RESET.as_bytes());
+                    buf.push(b'\t');
+                } else {
+                    buf.extend_from_slice(&content[written..=idx]);
+                }
+                written = idx + 1;
+            }
+            _ => break,
+        }
+    }
+    if trailing_whitespace > written {
+        buf.extend_from_slice(color.as_bytes());
+        buf.extend_from_slice(&content[written..trailing_whitespace]);
+        buf.extend_from_slice(RESET.as_bytes());
+    }
+    if trailing_whitespace != content.len() {
+        buf.extend_from_slice(ws_color.as_bytes());
+        buf.extend_from_slice(&content[trailing_whitespace..]);
+        buf.extend_from_slice(RESET.as_bytes());
+    }
+}
+
+/// Return for each line of `hunk`, the last hunk of a file, if it adds a blank line at the end of the file,
+/// which Git considers a whitespace error.
+///
+/// This is only known if the hunk ends at the end of the file, which is the case if it doesn't end in context lines.
+fn blank_lines_at_eof(hunk: &Hunk) -> Vec<bool> {
+    let mut out = vec![false; hunk.lines.len()];
+    if hunk.lines.last().is_none_or(|line| line.kind == LineKind::Context) {
+        return out;
+    }
+    let is_blank = |line: &&Line| line.content.iter().all(|b| is_space(*b));
+    let side = |excluded: LineKind| hunk.lines.iter().filter(move |line| line.kind != excluded);
+    let (old_len, new_len) = (side(LineKind::Add).count(), side(LineKind::Remove).count());
+    let old_blank = side(LineKind::Add).rev().take_while(is_blank).count();
+    let new_blank = side(LineKind::Remove).rev().take_while(is_blank).count();
+    if new_blank <= old_blank {
+        return out;
+    }
+    let (mut old_pos, mut new_pos) = (0, 0);
+    for (line, is_error) in hunk.lines.iter().zip(&mut out) {
+        match line.kind {
+            LineKind::Context => {
+                old_pos += 1;
+                new_pos += 1;
+            }
+            LineKind::Remove => old_pos += 1,
+            LineKind::Add => {
+                *is_error = new_pos >= new_len - new_blank && old_pos >= old_len - old_blank;
+                new_pos += 1;
+            }
+        }
+    }
+    out
+}
+
+fn write_binary(binary: &Binary, buf: &mut Vec<u8>) -> io::Result<()> {
+    let (name, data) = match binary {
+        Binary::Literal(data) => ("literal", data),
+        Binary::Delta(data) => ("delta", data),
+    };
+    buf.extend_from_slice(format!("{name} {}\n", data.len()).as_bytes());
+    binary::encode_base85_lines(&binary::deflate(data)?, buf);
+    buf.push(b'\n');
+    Ok(())
+}
+
+/// Append `prefix` and `path` to `buf`, and quote both like Git does if `path` contains special characters.
+pub(super) fn quote_path(prefix: &[u8], path: &[u8], buf: &mut Vec<u8>) {
+    if !needs_quoting(path) {
+        buf.extend_from_slice(prefix);
+        buf.extend_from_slice(path);
+        return;
+    }
+    buf.push(b'"');
+    buf.extend_from_slice(prefix);
+    for &b in path {
+        match b {
+            b'\x07' => buf.extend_from_slice(b"\\a"),
+            b'\x08' => buf.extend_from_slice(b"\\b"),
+            b'\t' => buf.extend_from_slice(b"\\t"),
+            b'\n' => buf.extend_from_slice(b"\\n"),
+            b'\x0b' => buf.extend_from_slice(b"\\v"),
+            b'\x0c' => buf.extend_from_slice(b"\\f"),
+            b'\r' => buf.extend_from_slice(b"\\r"),
+            b'"' => buf.extend_from_slice(b"\\\""),
+            b'\\' => buf.extend_from_slice(b"\\\\"),
+            b if is_special(b) => buf.extend_from_slice(format!("\\{b:03o}").as_bytes()),
+            b => buf.push(b),
+        }
+    }
+    buf.push(b'"');
+}
+
+/// Return `true` if `path` would be quoted by [`quote_path()`].
+pub(super) fn needs_quoting(path: &[u8]) -> bool {
+    path.iter().copied().any(is_special)
+}
+
+fn is_special(b: u8) -> bool {
+    b < 0x20 || b == b'"' || b == b'\\' || b >= 0x7f
+}
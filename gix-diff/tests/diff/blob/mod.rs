@@ -1,3 +1,4 @@
+mod patience;
 pub(crate) mod pipeline;
 mod platform;
 mod slider;
@@ -0,0 +1,77 @@
+use gix_testtools::bstr::{BString, ByteVec};
+
+use super::slider::{assert_diffs, baseline, tokens_for_diffing};
+
+#[test]
+fn baseline() -> gix_testtools::Result {
+    use gix_diff::blob::{unified_diff::ContextSize, Algorithm, UnifiedDiff};
+
+    let worktree_path = gix_testtools::scripted_fixture_read_only_standalone("make_diff_for_patience_repo.sh")?;
+    let asset_dir = worktree_path.join("assets");
+
+    let mut diffs = Vec::new();
+    for entry in std::fs::read_dir(&worktree_path)? {
+        let entry = entry?;
+        let Some(baseline::DirEntry {
+            file_name,
+            algorithm,
+            old_data,
+            new_data,
+        }) = baseline::parse_dir_entry(&asset_dir, &entry.file_name())?
+        else {
+            continue;
+        };
+        assert_eq!(algorithm, Algorithm::Patience);
+
+        let interner = gix_diff::blob::intern::InternedInput::new(
+            tokens_for_diffing(old_data.as_slice()),
+            tokens_for_diffing(new_data.as_slice()),
+        );
+        let actual = gix_diff::blob::diff(
+            algorithm,
+            &interner,
+            UnifiedDiff::new(
+                &interner,
+                baseline::DiffHunkRecorder::new(),
+                ContextSize::symmetrical(3),
+            ),
+        )?;
+        let actual = actual
+            .iter()
+            .fold(BString::default(), |mut acc, diff_hunk| {
+                acc.push_str(diff_hunk.header.to_string().as_str());
+                acc.push(b'\n');
+                acc.extend_from_slice(&diff_hunk.lines);
+                acc
+            })
+            .to_string();
+
+        let baseline = std::fs::read(worktree_path.join(&file_name))?;
+        let baseline = baseline::Baseline::new(&baseline).fold_to_unidiff().to_string();
+        let actual_matches_baseline = actual == baseline;
+        diffs.push((actual, baseline, actual_matches_baseline, file_name));
+    }
+
+    assert_eq!(diffs.len(), 8, "all baselines were found");
+    assert_diffs(&diffs);
+    Ok(())
+}
+
+#[test]
+fn identical_and_empty_inputs() {
+    use gix_diff::blob::{intern::InternedInput, Algorithm};
+
+    for (before, after, expected_changes) in [
+        ("a\nb\n", "a\nb\n", 0),
+        ("", "", 0),
+        ("", "a\nb\n", 1),
+        ("a\nb\n", "", 1),
+    ] {
+        let input = InternedInput::new(before, after);
+        let mut changes = Vec::new();
+        gix_diff::blob::diff(Algorithm::Patience, &input, |before, after| {
+            changes.push((before, after));
+        });
+        assert_eq!(changes.len(), expected_changes, "{before:?} -> {after:?}");
+    }
+}
@@ -70,7 +70,7 @@ fn baseline_v1() -> gix_testtools::Result {
     Ok(())
 }
 
-fn tokens_for_diffing(data: &[u8]) -> impl gix_diff::blob::intern::TokenSource<Token = &[u8]> {
+pub(super) fn tokens_for_diffing(data: &[u8]) -> impl gix_diff::blob::intern::TokenSource<Token = &[u8]> {
     gix_diff::blob::sources::byte_lines(data)
 }
 
@@ -105,6 +105,7 @@ fn baseline_v2() -> gix_testtools::Result {
             gix_diff::blob::Algorithm::Myers => Algorithm::Myers,
             gix_diff::blob::Algorithm::Histogram => Algorithm::Histogram,
             gix_diff::blob::Algorithm::MyersMinimal => Algorithm::MyersMinimal,
+            gix_diff::blob::Algorithm::Patience => unreachable!("BUG: slider baselines aren't generated for patience"),
         };
 
         let mut diff = Diff::compute(algorithm, &input);
@@ -136,7 +137,7 @@ fn baseline_v2() -> gix_testtools::Result {
     Ok(())
 }
 
-fn assert_diffs(diffs: &[(String, String, bool, String)]) {
+pub(super) fn assert_diffs(diffs: &[(String, String, bool, String)]) {
     let total_diffs = diffs.len();
     let matching_diffs = diffs
         .iter()
@@ -165,7 +166,7 @@ fn assert_diffs(diffs: &[(String, String, bool, String)]) {
     );
 }
 
-mod baseline {
+pub(super) mod baseline {
     use gix_diff::blob::unified_diff::{ConsumeHunk, HunkHeader};
     use gix_diff::blob::Algorithm;
     use gix_object::bstr::{self, BString};
@@ -366,6 +367,7 @@ mod baseline {
         let algorithm = match algorithm {
             "myers" => Algorithm::Myers,
             "histogram" => Algorithm::Histogram,
+            "patience" => Algorithm::Patience,
             other => unreachable!("BUG: '{other}' is not a supported algorithm"),
         };
 
@@ -6,6 +6,7 @@ fn hex_to_id(hex: &str) -> gix_hash::ObjectId {
 
 mod blob;
 mod index;
+mod patch;
 mod rewrites;
 mod tree;
 mod tree_with_rewrites;
@@ -41,6 +42,7 @@ mod util {
                     buffer.extend_from_slice(data);
                     Ok(Some(gix_object::Data {
                         kind: gix_object::Kind::Blob,
+                        hash_kind: id.kind(),
                         data: buffer.as_slice(),
                     }))
                 }
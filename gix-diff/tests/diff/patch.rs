@@ -0,0 +1,469 @@
+use std::path::Path;
+
+use gix_diff::patch::{self, apply, parse, Content, Kind, LineKind};
+use gix_object::{
+    bstr::{BStr, ByteSlice},
+    tree::EntryKind,
+};
+
+#[test]
+fn baseline_applies_forward_and_in_reverse() -> crate::Result {
+    let dir = gix_testtools::scripted_fixture_read_only_standalone("make_patch_repo.sh")?;
+    let files = patch::parse(&std::fs::read(dir.join("changes.patch"))?, Default::default())?;
+    assert_eq!(files.len(), 13, "each change is recorded as its own file");
+
+    for file in &files {
+        let old = file.old_path.as_ref().map(|p| read(&dir.join("before"), p.as_ref()));
+        let new = file.new_path.as_ref().map(|p| read(&dir.join("after"), p.as_ref()));
+        let (old, new) = (old.unwrap_or_default(), new.unwrap_or_default());
+
+        let actual = patch::apply(file, &old, Default::default())?;
+        assert_eq!(actual.as_bstr(), new.as_bstr(), "{:?} applies forward", file.path());
+
+        let actual = patch::apply(
+            file,
+            &new,
+            apply::Options {
+                reverse: true,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(actual.as_bstr(), old.as_bstr(), "{:?} applies in reverse", file.path());
+    }
+
+    let mbox = patch::parse(&std::fs::read(dir.join("changes.mbox"))?, Default::default())?;
+    assert_eq!(
+        mbox, files,
+        "the commit message and signature of mailbox patches are ignored"
+    );
+    Ok(())
+}
+
+#[test]
+fn baseline_headers() -> crate::Result {
+    let dir = gix_testtools::scripted_fixture_read_only_standalone("make_patch_repo.sh")?;
+    let files = patch::parse(&std::fs::read(dir.join("changes.patch"))?, Default::default())?;
+    let find = |path: &str| {
+        files
+            .iter()
+            .find(|f| f.path() == path)
+            .unwrap_or_else(|| panic!("{path} is in the patch"))
+    };
+
+    let file = find("added");
+    assert_eq!(file.kind, Kind::Addition);
+    assert_eq!(file.old_path, None);
+    assert_eq!(file.new_mode.map(|m| m.kind()), Some(EntryKind::Blob));
+
+    let file = find("deleted");
+    assert_eq!(file.kind, Kind::Deletion);
+    assert_eq!(file.new_path, None);
+
+    let file = find("renamed-destination");
+    assert_eq!(file.kind, Kind::Rename);
+    assert_eq!(
+        file.old_path.as_ref().map(AsRef::as_ref),
+        Some(b"renamed-source".as_bstr())
+    );
+    assert_eq!(file.similarity, Some(96));
+    assert_eq!(file.old_id.as_ref().map(AsRef::as_ref), Some(b"9dbaf49".as_bstr()));
+    assert_eq!(file.new_id.as_ref().map(AsRef::as_ref), Some(b"609c6c5".as_bstr()));
+
+    let file = find("copy-destination");
+    assert_eq!(file.kind, Kind::Copy);
+    assert_eq!(
+        file.old_path.as_ref().map(AsRef::as_ref),
+        Some(b"copy-source".as_bstr())
+    );
+
+    let file = find("executable");
+    assert_eq!(file.kind, Kind::Modification);
+    assert_eq!(file.old_mode.map(|m| m.kind()), Some(EntryKind::Blob));
+    assert_eq!(file.new_mode.map(|m| m.kind()), Some(EntryKind::BlobExecutable));
+    assert_eq!(file.content, Content::Hunks(Vec::new()));
+
+    let file = find("link");
+    assert_eq!(file.new_mode.map(|m| m.kind()), Some(EntryKind::Link));
+
+    let file = find("empty");
+    assert_eq!(file.kind, Kind::Addition);
+    assert_eq!(file.content, Content::Hunks(Vec::new()));
+
+    let file = find("with space");
+    assert_eq!(file.old_path, file.new_path, "trailing tabs after paths are ignored");
+
+    find("ü");
+
+    let file = find("binary");
+    assert!(matches!(
+        file.content,
+        Content::Binary {
+            forward: patch::Binary::Delta(_),
+            reverse: Some(patch::Binary::Delta(_))
+        }
+    ));
+
+    let file = find("modified");
+    let Content::Hunks(hunks) = &file.content else {
+        unreachable!("a text patch")
+    };
+    assert_eq!(hunks.len(), 3);
+    assert_eq!(
+        (
+            hunks[1].old_start,
+            hunks[1].old_len,
+            hunks[1].new_start,
+            hunks[1].new_len
+        ),
+        (7, 7, 8, 7)
+    );
+    let last = hunks[2].lines.last().expect("lines");
+    assert_eq!(last.kind, LineKind::Add);
+    assert_eq!(last.content, "30", "the line without newline is stored as such");
+    Ok(())
+}
+
+#[test]
+fn baseline_written_back() -> crate::Result {
+    let dir = gix_testtools::scripted_fixture_read_only_standalone("make_patch_repo.sh")?;
+    let expected = std::fs::read(dir.join("changes.patch"))?;
+    let files = patch::parse(&expected, Default::default())?;
+    let mut actual = Vec::new();
+    for file in &files {
+        file.write_to(&mut actual)?;
+    }
+    assert_eq!(actual.as_bstr(), expected.as_bstr(), "the output is the same as Git's");
+    assert_eq!(patch::parse(&actual, Default::default())?, files);
+    Ok(())
+}
+
+#[test]
+fn baseline_hunks() -> crate::Result {
+    let dir = gix_testtools::scripted_fixture_read_only_standalone("make_patch_repo.sh")?;
+    let files = patch::parse(&std::fs::read(dir.join("changes.patch"))?, Default::default())?;
+    for path in ["modified", "with space", "ü"] {
+        let file = files.iter().find(|f| f.path() == path).expect("present");
+        let hunks = patch::hunks(
+            &read(&dir.join("before"), path.into()),
+            &read(&dir.join("after"), path.into()),
+            patch::hunks::Options {
+                algorithm: gix_diff::blob::Algorithm::Myers,
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            Content::Hunks(hunks),
+            file.content,
+            "{path}: the hunks are the same as Git's"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn baseline_stat_and_summary() -> crate::Result {
+    let dir = gix_testtools::scripted_fixture_read_only_standalone("make_patch_repo.sh")?;
+    let files = patch::parse(&std::fs::read(dir.join("changes.patch"))?, Default::default())?;
+    let stats: Vec<_> = files.iter().map(patch::File::stat).collect();
+
+    let mut actual = Vec::new();
+    patch::stat::write(&stats, 80, &mut actual)?;
+    patch::stat::write_summary(&files, &mut actual)?;
+    assert_eq!(actual.as_bstr(), std::fs::read(dir.join("changes.stat"))?.as_bstr());
+
+    actual.clear();
+    patch::stat::write(&stats, 40, &mut actual)?;
+    assert_eq!(
+        actual.as_bstr(),
+        std::fs::read(dir.join("changes.narrow-stat"))?.as_bstr(),
+        "names are shortened to make room for the graph"
+    );
+    Ok(())
+}
+
+#[test]
+fn baseline_display_with_colors_and_words() -> crate::Result {
+    use patch::{color, word_diff, write};
+
+    let dir = gix_testtools::scripted_fixture_read_only_standalone("make_patch_repo.sh")?;
+    let files = patch::parse(&std::fs::read(dir.join("display.patch"))?, Default::default())?;
+    let colors = color::Colors::default();
+    // Emulates `--word-diff-regex='[a-z]+|[^[:space:]]'`.
+    let find_word = |text: &[u8]| -> Option<std::ops::Range<usize>> {
+        let start = text.iter().position(|b| !b.is_ascii_whitespace())?;
+        let len = text[start..]
+            .iter()
+            .position(|b| !b.is_ascii_lowercase())
+            .unwrap_or(text.len() - start)
+            .max(1);
+        Some(start..start + len)
+    };
+    let words = |mode| word_diff::Options { mode, find_word: None };
+
+    for (name, options) in [
+        ("display.patch", write::Options::default()),
+        (
+            "display.color",
+            write::Options {
+                colors: Some(&colors),
+                ..Default::default()
+            },
+        ),
+        (
+            "display.color-moved-plain",
+            write::Options {
+                colors: Some(&colors),
+                color_moved: Some(color::Moved::Plain),
+                ..Default::default()
+            },
+        ),
+        (
+            "display.color-moved-blocks",
+            write::Options {
+                colors: Some(&colors),
+                color_moved: Some(color::Moved::Blocks),
+                ..Default::default()
+            },
+        ),
+        (
+            "display.color-moved-zebra",
+            write::Options {
+                colors: Some(&colors),
+                color_moved: Some(color::Moved::Zebra),
+                ..Default::default()
+            },
+        ),
+        (
+            "display.word-diff-plain",
+            write::Options {
+                word_diff: Some(words(word_diff::Mode::Plain)),
+                ..Default::default()
+            },
+        ),
+        (
+            "display.word-diff-plain-color",
+            write::Options {
+                colors: Some(&colors),
+                word_diff: Some(words(word_diff::Mode::Plain)),
+                ..Default::default()
+            },
+        ),
+        (
+            "display.word-diff-color",
+            write::Options {
+                colors: Some(&colors),
+                word_diff: Some(words(word_diff::Mode::Color)),
+                ..Default::default()
+            },
+        ),
+        (
+            "display.word-diff-porcelain",
+            write::Options {
+                word_diff: Some(words(word_diff::Mode::Porcelain)),
+                ..Default::default()
+            },
+        ),
+        (
+            "display.word-diff-regex",
+            write::Options {
+                word_diff: Some(word_diff::Options {
+                    mode: word_diff::Mode::Plain,
+                    find_word: Some(&find_word),
+                }),
+                ..Default::default()
+            },
+        ),
+    ] {
+        let mut actual = Vec::new();
+        patch::write(&files, options, &mut actual)?;
+        assert_eq!(
+            actual.as_bstr(),
+            std::fs::read(dir.join(name))?.as_bstr(),
+            "{name}: {options:?}"
+        );
+    }
+
+    let stats: Vec<_> = files.iter().map(patch::File::stat).collect();
+    let mut actual = Vec::new();
+    patch::stat::write_numstat(&stats, &mut actual)?;
+    assert_eq!(actual.as_bstr(), std::fs::read(dir.join("display.numstat"))?.as_bstr());
+    Ok(())
+}
+
+#[test]
+fn baseline_hunks_with_whitespace_and_context_settings() -> crate::Result {
+    use gix_diff::blob::whitespace;
+
+    let dir = gix_testtools::scripted_fixture_read_only_standalone("make_patch_repo.sh")?;
+    let all = patch::parse(&std::fs::read(dir.join("display.patch"))?, Default::default())?;
+    let defaults = patch::hunks::Options {
+        algorithm: gix_diff::blob::Algorithm::Myers,
+        ..Default::default()
+    };
+    for (name, options) in [
+        (
+            "display.ignore-all-space",
+            patch::hunks::Options {
+                whitespace: whitespace::Mode::IgnoreAll,
+                ..defaults
+            },
+        ),
+        (
+            "display.ignore-space-change",
+            patch::hunks::Options {
+                whitespace: whitespace::Mode::IgnoreChange,
+                ..defaults
+            },
+        ),
+        (
+            "display.ignore-space-at-eol",
+            patch::hunks::Options {
+                whitespace: whitespace::Mode::IgnoreAtEol,
+                ..defaults
+            },
+        ),
+        (
+            "display.ignore-blank-lines",
+            patch::hunks::Options {
+                ignore_blank_lines: true,
+                ..defaults
+            },
+        ),
+        (
+            "display.ignore-blank-lines-U1",
+            patch::hunks::Options {
+                ignore_blank_lines: true,
+                context_lines: 1,
+                ..defaults
+            },
+        ),
+        (
+            "display.inter-hunk-context",
+            patch::hunks::Options {
+                context_lines: 1,
+                inter_hunk_context: 3,
+                ..defaults
+            },
+        ),
+    ] {
+        let expected = patch::parse(&std::fs::read(dir.join(name))?, Default::default())?;
+        for file in all.iter().filter(|file| file.kind == Kind::Modification) {
+            let Content::Hunks(_) = &file.content else { continue };
+            let old = read(&dir.join("display").join("before"), file.path().as_ref());
+            let new = read(&dir.join("display").join("after"), file.path().as_ref());
+            let actual = patch::hunks(&old, &new, options);
+            match expected.iter().find(|e| e.path() == file.path()) {
+                Some(expected) => {
+                    let Content::Hunks(expected) = &expected.content else {
+                        unreachable!("only text is expected")
+                    };
+                    assert_eq!(&actual, expected, "{name}: {:?}", file.path());
+                }
+                None => assert!(
+                    actual.is_empty(),
+                    "{name}: {:?} isn't shown by Git as there are no changes left",
+                    file.path()
+                ),
+            }
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn baseline_with_offset_and_fuzz() -> crate::Result {
+    let dir = gix_testtools::scripted_fixture_read_only_standalone("make_patch_repo.sh")?;
+    let files = patch::parse(&std::fs::read(dir.join("fuzz.patch"))?, Default::default())?;
+    assert_eq!(files.len(), 1);
+    let drifted = std::fs::read(dir.join("fuzz").join("drifted"))?;
+
+    let err = patch::apply(&files[0], &drifted, Default::default()).unwrap_err();
+    assert!(
+        matches!(err, apply::Error::HunkFailed { hunk: 1, line: 7 }),
+        "a mismatching context line prevents the hunk from applying by default"
+    );
+
+    let actual = patch::apply(
+        &files[0],
+        &drifted,
+        apply::Options {
+            min_context: Some(1),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        actual.as_bstr(),
+        std::fs::read(dir.join("fuzz").join("expected"))?.as_bstr(),
+        "it applies at an offset while ignoring some of the context, just like `git apply -C1`"
+    );
+    Ok(())
+}
+
+#[test]
+fn traditional_unified_diff() -> crate::Result {
+    let patch =
+        b"--- old/file.txt\t2024-01-01 00:00:00\n+++ new/file.txt\t2024-01-01 00:00:00\n@@ -1,2 +1,2 @@\n a\n-b\n+c\n";
+    let files = patch::parse(patch, Default::default())?;
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0].kind, Kind::Modification);
+    assert_eq!(files[0].path(), "file.txt");
+    assert_eq!(patch::apply(&files[0], b"a\nb\n", Default::default())?, b"a\nc\n");
+
+    let files = patch::parse(patch, parse::Options { strip_components: 0 })?;
+    assert_eq!(
+        files[0].old_path.as_ref().map(AsRef::as_ref),
+        Some(b"old/file.txt".as_bstr())
+    );
+    assert_eq!(files[0].path(), "new/file.txt");
+    Ok(())
+}
+
+#[test]
+fn hunks_anchored_at_the_end_must_apply_there() -> crate::Result {
+    let files = patch::parse(
+        b"diff --git a/f b/f\n--- a/f\n+++ b/f\n@@ -2,2 +2,1 @@\n a\n-b\n",
+        Default::default(),
+    )?;
+    let err = patch::apply(&files[0], b"x\na\nb\ny\n", Default::default()).unwrap_err();
+    assert!(
+        matches!(err, apply::Error::HunkFailed { hunk: 1, line: 2 }),
+        "without trailing context, the hunk must apply at the end of the file"
+    );
+    assert_eq!(
+        patch::apply(&files[0], b"x\ny\na\nb\n", Default::default())?,
+        b"x\ny\na\n"
+    );
+    Ok(())
+}
+
+#[test]
+fn invalid_patches() {
+    let err = patch::parse(
+        b"diff --git a/f b/f\n--- a/f\n+++ b/f\n@@ -1 +1 @@\n-a\n",
+        Default::default(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, parse::Error::TruncatedHunk { .. }), "{err:?}");
+
+    let err = patch::parse(
+        b"diff --git a/f b/f\n--- a/f\n+++ b/f\n@@ -1 +x @@\n",
+        Default::default(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, parse::Error::InvalidHunkHeader { .. }), "{err:?}");
+
+    let err = patch::parse(b"diff --git a/f b/f\nnew file mode 100999\n", Default::default()).unwrap_err();
+    assert!(matches!(err, parse::Error::InvalidMode { .. }), "{err:?}");
+}
+
+fn read(root: &Path, rela_path: &BStr) -> Vec<u8> {
+    let path = root.join(rela_path.to_str().expect("valid UTF-8"));
+    match std::fs::symlink_metadata(&path) {
+        Ok(meta) if meta.is_symlink() => std::fs::read_link(&path)
+            .expect("valid link")
+            .into_os_string()
+            .into_encoded_bytes(),
+        Ok(_) => std::fs::read(&path).expect("readable file"),
+        Err(_) => Vec::new(),
+    }
+}
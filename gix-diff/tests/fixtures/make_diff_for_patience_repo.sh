@@ -0,0 +1,153 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+# Pairs of files named `<old>-<new>.patience.baseline` along with their blobs in `assets/`, just like the sliders fixture,
+# with the baseline produced by `git diff --patience`.
+mkdir assets
+
+function baseline() {
+  local old=${1:?first argument is the name of the old blob}
+  local new=${2:?second argument is the name of the new blob}
+  git -c diff.algorithm=patience diff --no-index "assets/$old.blob" "assets/$new.blob" > "$old-$new.patience.baseline" || true
+}
+
+# The example of `t4033-diff-patience.sh` in Git, after Bram Cohen's original post.
+cat <<'EOF' >assets/frobnitz_old.blob
+#include <stdio.h>
+
+// Frobs foo heartily
+int frobnitz(int foo)
+{
+    int i;
+    for(i = 0; i < 10; i++)
+    {
+        printf("Your answer is: ");
+        printf("%d\n", foo);
+    }
+}
+
+int fact(int n)
+{
+    if(n > 1)
+    {
+        return fact(n-1) * n;
+    }
+    return 1;
+}
+
+int main(int argc, char **argv)
+{
+    frobnitz(fact(10));
+}
+EOF
+cat <<'EOF' >assets/frobnitz_new.blob
+#include <stdio.h>
+
+int fib(int n)
+{
+    if(n > 2)
+    {
+        return fib(n-1) + fib(n-2);
+    }
+    return 1;
+}
+
+// Frobs foo heartily
+int frobnitz(int foo)
+{
+    int i;
+    for(i = 0; i < 10; i++)
+    {
+        printf("%d\n", foo);
+    }
+}
+
+int main(int argc, char **argv)
+{
+    frobnitz(fib(10));
+}
+EOF
+baseline frobnitz_old frobnitz_new
+
+# The 'unique' case of `lib-diff-alternative.sh` in Git, where unique lines have moved.
+printf "%s\n" 1 2 3 4 5 6 7 8 9 10 >assets/unique_old.blob
+printf "%s\n" 1 5 2 6 3 7 4 8 9 10 >assets/unique_new.blob
+baseline unique_old unique_new
+
+# No unique lines in common, which makes it fall back to Myers.
+printf "%s\n" a b a b a b >assets/repeated_old.blob
+printf "%s\n" b a b a b a b >assets/repeated_new.blob
+baseline repeated_old repeated_new
+
+# Unique lines in common surrounded by lines that aren't unique.
+printf "%s\n" x y unique1 x y z unique2 y x >assets/mixed_old.blob
+printf "%s\n" y x unique1 z y unique2 x x y >assets/mixed_new.blob
+baseline mixed_old mixed_new
+
+# Nothing in common at all.
+printf "%s\n" a b c >assets/disjoint_old.blob
+printf "%s\n" d e f g >assets/disjoint_new.blob
+baseline disjoint_old disjoint_new
+
+# Additions and removals at the beginning and the end.
+printf "%s\n" 1 2 3 4 5 6 7 8 9 >assets/edges_old.blob
+printf "%s\n" 0 1 3 4 5 6 7 8 >assets/edges_new.blob
+baseline edges_old edges_new
+
+# Moved blocks of code.
+cat <<'EOF' >assets/moved_old.blob
+fn first() {
+    one();
+}
+
+fn second() {
+    two();
+}
+
+fn third() {
+    three();
+}
+EOF
+cat <<'EOF' >assets/moved_new.blob
+fn third() {
+    three();
+}
+
+fn first() {
+    one();
+    uno();
+}
+
+fn second() {
+    two();
+}
+EOF
+baseline moved_old moved_new
+
+# An added function that could be shifted, which Git places using its indent heuristic, like in `t4061-diff-indent.sh`.
+cat <<'EOF' >assets/sliders_old.blob
+use std::io;
+
+// Does things.
+fn foo() {
+    things();
+}
+
+fn main() {}
+EOF
+cat <<'EOF' >assets/sliders_new.blob
+use std::io;
+
+// Does things.
+fn bar() {
+    things();
+}
+
+// Does things.
+fn foo() {
+    things();
+}
+
+fn main() {}
+EOF
+baseline sliders_old sliders_new
@@ -0,0 +1,122 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+# `before` and `after` contain the state of the files before and after the change,
+# and `changes.patch` is the patch produced by Git to get from one to the other.
+mkdir before after
+
+seq 1 30 >before/modified
+{ echo 0; seq 1 9; echo ten; seq 11 29; printf 30; } >after/modified
+
+seq 1 5 >before/deleted
+echo added >after/added
+
+seq 100 150 >before/renamed-source
+{ seq 100 149; echo changed; } >after/renamed-destination
+
+seq 200 250 >before/copy-source
+cp before/copy-source after/copy-source
+{ seq 200 249; echo copied; } >after/copy-destination
+
+echo "not executable" >before/executable
+cp before/executable after/executable
+chmod +x after/executable
+
+echo "a space" >"before/with space"
+echo "a changed space" >"after/with space"
+
+echo "unicode" >before/ü
+echo "changed unicode" >after/ü
+
+touch after/empty
+ln -s modified after/link
+
+printf '\x00\x01\x02binary%.0s' $(seq 1 100) >before/binary
+{ cat before/binary; printf '\x00\x03appended'; } >after/binary
+printf '\x00\x01\x02added binary' >after/added-binary
+printf '\x00\x01\x02deleted binary' >before/deleted-binary
+
+git init -q repo
+(cd repo
+  cp -R ../before/. .
+  git add -A
+  git commit -q -m "before"
+
+  git rm -q -r .
+  cp -R ../after/. .
+  git add -A
+  git commit -q -m "after"
+
+  git diff --binary -M -C -C HEAD~1 HEAD >../changes.patch
+  git format-patch -1 --stdout -M -C -C --binary >../changes.mbox
+  git diff --stat=80 --summary -M -C -C HEAD~1 HEAD >../changes.stat
+  git diff --stat=40 -M -C -C HEAD~1 HEAD >../changes.narrow-stat
+)
+
+# A patch that needs to apply at an offset and with fuzz, along with the result of `git apply -C1`.
+mkdir fuzz
+seq 1 20 >fuzz/file
+git init -q fuzz-repo
+(cd fuzz-repo
+  cp ../fuzz/file .
+  git add file
+  git commit -q -m "base"
+  sed -i.bak 's/^10$/ten/' file && rm file.bak
+  git diff >../fuzz.patch
+  { echo new first line; echo another; seq 1 6; echo seven; seq 8 20; } >file
+  cp file ../fuzz/drifted
+  git apply -C1 ../fuzz.patch
+  cp file ../fuzz/expected
+)
+
+# Changes to display with colors, moved lines, word diffs and whitespace settings, along with the way Git displays them.
+mkdir -p display/before display/after
+{ echo "fn first() {"; seq 1 8; echo "}"; seq 1 20 | sed 's/^/moved block line /'; echo "fn last() {"; seq 1 4; echo "}"; } >display/before/moved
+{ echo "fn first() {"; seq 1 4; echo four and a half; seq 5 8; echo "}"; echo "fn last() {"; seq 1 4; echo "}";
+  seq 11 20 | sed 's/^/moved block line /'; seq 1 10 | sed 's/^/moved block line /'; } >display/after/moved
+lines() { seq "$2" "$3" | sed "s/^/$1 /"; }
+{ lines first 1 5; lines kept 1 10; lines second 1 5; lines also-kept 1 10; } >display/before/zebra
+{ lines kept 1 10; lines also-kept 1 10; lines second 1 5; lines first 1 5; } >display/after/zebra
+printf 'int main() {\n\tfoo(1, 2);\n  bar  baz\n    x;\n}\nend\n' >display/before/words
+printf 'int main() {\n\tfoo(1, 3);\n  bar baz \n \tx;\n}\nend\n' >display/after/words
+printf 'line one\nline two\nline three\nto be removed\n' >display/before/lines
+printf 'line one\nline 2 two\nline three\n\n' >display/after/lines
+printf 'a\nb\nc\n' >display/before/whitespace
+printf 'a \nb\n  c\n' >display/after/whitespace
+seq 1 20 >display/before/blank
+{ seq 1 3; echo; seq 4 10; echo X; seq 11 20; echo; } >display/after/blank
+printf 'no newline' >"display/before/no newline"
+printf 'no newline either' >"display/after/no newline"
+printf '\x00binary' >display/before/binary
+printf '\x00binary changed' >display/after/binary
+echo new >display/after/new
+
+git init -q display-repo
+(cd display-repo
+  cp -R ../display/before/. .
+  git add -A
+  git commit -q -m "before"
+
+  git rm -q -r .
+  cp -R ../display/after/. .
+  git add -A
+  git commit -q -m "after"
+
+  git diff HEAD~1 HEAD >../display.patch
+  git diff --color=always --color-moved=no HEAD~1 HEAD >../display.color
+  for mode in plain blocks zebra; do
+    git diff --color=always --color-moved=$mode HEAD~1 HEAD >../display.color-moved-$mode
+  done
+  for mode in plain color porcelain; do
+    git diff --word-diff=$mode HEAD~1 HEAD >../display.word-diff-$mode
+  done
+  git diff --color=always --word-diff=plain HEAD~1 HEAD >../display.word-diff-plain-color
+  git diff --word-diff=plain --word-diff-regex='[a-z]+|[^[:space:]]' HEAD~1 HEAD >../display.word-diff-regex
+  git diff -w HEAD~1 HEAD >../display.ignore-all-space
+  git diff -b HEAD~1 HEAD >../display.ignore-space-change
+  git diff --ignore-space-at-eol HEAD~1 HEAD >../display.ignore-space-at-eol
+  git diff --ignore-blank-lines HEAD~1 HEAD >../display.ignore-blank-lines
+  git diff --ignore-blank-lines -U1 HEAD~1 HEAD >../display.ignore-blank-lines-U1
+  git diff --inter-hunk-context=3 -U1 HEAD~1 HEAD >../display.inter-hunk-context
+  git diff --numstat HEAD~1 HEAD >../display.numstat
+)
@@ -0,0 +1,223 @@
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicBool, Ordering},
+    time::Instant,
+};
+
+use gix_features::progress::{Count, DynNestedProgress, Progress};
+use gix_hash::ObjectId;
+use gix_hashtable::{HashMap, HashSet};
+use gix_object::{Kind, TreeRefIter};
+
+use crate::{
+    check::{Error, Options, Outcome, Problem, ProgressId, Unreachable},
+    object::Severity,
+};
+
+/// Check all objects reachable from `tips` in `objects` for presence, and unless disabled in `options`, for hash and syntax
+/// problems, reporting `progress` and stopping early if `should_interrupt` is set.
+///
+/// `tips` are typically all references, the objects mentioned in their reflogs, `HEAD` and all blobs in the index.
+/// If `all_objects` is set, it's expected to yield all objects in the object database, possibly with duplicates,
+/// which are then checked as well and listed as [unreachable](Outcome::unreachable) if they are not reachable from `tips`.
+///
+/// ### Deviation
+///
+/// Unlike `git fsck`, packs are not verified as a whole, even though every object in them is read.
+/// Use the pack verification of `gix-pack` for that.
+pub fn check(
+    objects: &dyn gix_object::Find,
+    tips: impl IntoIterator<Item = ObjectId>,
+    all_objects: Option<&mut dyn Iterator<Item = ObjectId>>,
+    progress: &mut dyn DynNestedProgress,
+    should_interrupt: &AtomicBool,
+    options: &Options,
+) -> Result<Outcome, Error> {
+    let mut out = Outcome::default();
+    let mut buf = Vec::new();
+    let mut references = Vec::new();
+    let mut reachable = HashMap::<ObjectId, Kind>::default();
+    // Objects that are missing or unreadable, so each of them is only read and reported once.
+    let mut broken = HashSet::<ObjectId>::default();
+
+    let start = Instant::now();
+    let mut reachable_progress = progress.add_child_with_id("reachable".into(), ProgressId::ReachableObjects.into());
+    reachable_progress.init(None, gix_features::progress::count("objects"));
+    let mut queue: VecDeque<_> = tips.into_iter().map(|id| (id, None, None)).collect();
+    while let Some((id, expected_kind, referenced_by)) = queue.pop_front() {
+        if should_interrupt.load(Ordering::Relaxed) {
+            return Err(Error::Interrupted);
+        }
+        if reachable.contains_key(&id) || broken.contains(&id) {
+            continue;
+        }
+        let kind = match read_and_verify(objects, id, &mut buf, &mut references, options, &mut out.problems)? {
+            Read::Found(kind) => kind,
+            Read::Missing => {
+                out.problems.push(Problem::Missing {
+                    id,
+                    kind: expected_kind,
+                    referenced_by,
+                });
+                broken.insert(id);
+                continue;
+            }
+            Read::Unreadable => {
+                broken.insert(id);
+                continue;
+            }
+        };
+        reachable.insert(id, kind);
+        reachable_progress.inc();
+        if let Some((expected, referenced_by)) = expected_kind.zip(referenced_by) {
+            if expected != kind {
+                out.problems.push(Problem::KindMismatch {
+                    id,
+                    expected,
+                    actual: kind,
+                    referenced_by,
+                });
+            }
+        }
+        queue.extend(
+            references
+                .drain(..)
+                .filter(|(ref_id, _)| !reachable.contains_key(ref_id) && !broken.contains(ref_id))
+                .map(|(ref_id, kind)| (ref_id, Some(kind), Some(id))),
+        );
+    }
+    reachable_progress.show_throughput(start);
+    out.num_reachable = reachable.len();
+
+    let Some(all_objects) = all_objects else {
+        return Ok(out);
+    };
+    let start = Instant::now();
+    let mut unreachable_progress =
+        progress.add_child_with_id("unreachable".into(), ProgressId::UnreachableObjects.into());
+    unreachable_progress.init(None, gix_features::progress::count("objects"));
+    let mut unreachable = HashMap::<ObjectId, Kind>::default();
+    let mut referenced = HashSet::<ObjectId>::default();
+    for id in all_objects {
+        if should_interrupt.load(Ordering::Relaxed) {
+            return Err(Error::Interrupted);
+        }
+        if reachable.contains_key(&id) || unreachable.contains_key(&id) || broken.contains(&id) {
+            continue;
+        }
+        match read_and_verify(objects, id, &mut buf, &mut references, options, &mut out.problems)? {
+            Read::Found(kind) => {
+                unreachable.insert(id, kind);
+                referenced.extend(references.drain(..).map(|(id, _kind)| id));
+                unreachable_progress.inc();
+            }
+            // An object can vanish if it was pruned in the meantime, and we ignore that.
+            Read::Missing | Read::Unreadable => {
+                broken.insert(id);
+            }
+        }
+    }
+    unreachable_progress.show_throughput(start);
+
+    out.unreachable = unreachable
+        .into_iter()
+        .map(|(id, kind)| Unreachable {
+            id,
+            kind,
+            is_dangling: !referenced.contains(&id),
+        })
+        .collect();
+    out.unreachable.sort();
+    Ok(out)
+}
+
+enum Read {
+    Found(Kind),
+    Missing,
+    /// The object exists, but couldn't be read, which was recorded as problem.
+    Unreadable,
+}
+
+/// Read the object with `id` into `buf`, verify it according to `options` and record its references in `references`.
+fn read_and_verify(
+    objects: &dyn gix_object::Find,
+    id: ObjectId,
+    buf: &mut Vec<u8>,
+    references: &mut Vec<(ObjectId, Kind)>,
+    options: &Options,
+    problems: &mut Vec<Problem>,
+) -> Result<Read, Error> {
+    references.clear();
+    let data = match objects.try_find(&id, buf) {
+        Ok(Some(data)) => data,
+        Ok(None) => return Ok(Read::Missing),
+        Err(source) => {
+            problems.push(Problem::Unreadable { id, source });
+            return Ok(Read::Unreadable);
+        }
+    };
+    let kind = data.kind;
+    if options.verify_objects {
+        let actual = gix_object::compute_hash(options.object_hash, kind, data.data)
+            .map_err(|source| Error::Hasher { id, source })?;
+        if actual != id {
+            problems.push(Problem::HashMismatch { id, kind, actual });
+        }
+        if !options.skip_list.contains(&id) {
+            for message in crate::object::validate(kind, data.data, options.object_hash) {
+                let severity = options.severity(message.id);
+                if severity != Severity::Ignore {
+                    problems.push(Problem::Invalid {
+                        id,
+                        kind,
+                        message,
+                        severity,
+                    });
+                }
+            }
+        }
+    }
+
+    match kind {
+        Kind::Commit => {
+            for token in gix_object::CommitRefIter::from_bytes(data.data) {
+                match token {
+                    Ok(gix_object::commit::ref_iter::Token::Tree { id }) => references.push((id, Kind::Tree)),
+                    Ok(gix_object::commit::ref_iter::Token::Parent { id: parent }) => {
+                        if !options.shallow_commits.contains(&id) {
+                            references.push((parent, Kind::Commit));
+                        }
+                    }
+                    _ => break,
+                }
+            }
+        }
+        Kind::Tree => {
+            for entry in TreeRefIter::from_bytes(data.data, data.hash_kind) {
+                let Ok(entry) = entry else { break };
+                if entry.mode.is_commit() {
+                    continue;
+                }
+                references.push((
+                    entry.oid.to_owned(),
+                    if entry.mode.is_tree() { Kind::Tree } else { Kind::Blob },
+                ));
+            }
+        }
+        Kind::Tag => {
+            let mut target = None;
+            for token in gix_object::TagRefIter::from_bytes(data.data) {
+                match token {
+                    Ok(gix_object::tag::ref_iter::Token::Target { id }) => target = Some(id),
+                    Ok(gix_object::tag::ref_iter::Token::TargetKind(kind)) => {
+                        references.extend(target.take().map(|id| (id, kind)));
+                        break;
+                    }
+                    _ => break,
+                }
+            }
+        }
+        Kind::Blob => {}
+    }
+    Ok(Read::Found(kind))
+}
@@ -0,0 +1,191 @@
+//! A full check of all objects reachable from a set of tips, with detection of unreachable and dangling objects.
+use std::collections::BTreeMap;
+
+use gix_hash::ObjectId;
+use gix_hashtable::HashSet;
+use gix_object::Kind;
+
+use crate::object::{MessageId, Severity};
+
+pub(crate) mod function;
+
+/// Options for use in [`check()`](crate::check()).
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The kind of hash used in the object database.
+    pub object_hash: gix_hash::Kind,
+    /// If `true` (default), hash each object to see if its id matches its data, and validate its syntax.
+    /// Otherwise, only check that all objects are present.
+    pub verify_objects: bool,
+    /// Objects whose syntax problems should not be reported, as configured with `fsck.skipList`.
+    ///
+    /// Note that objects with mismatching hashes are always reported.
+    pub skip_list: HashSet<ObjectId>,
+    /// Overrides for the default severity of message IDs, as configured with `fsck.<msg-id>`.
+    pub severities: BTreeMap<MessageId, Severity>,
+    /// Commits in a shallow repository whose parents are not expected to be present.
+    pub shallow_commits: HashSet<ObjectId>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            object_hash: gix_hash::Kind::default(),
+            verify_objects: true,
+            skip_list: Default::default(),
+            severities: Default::default(),
+            shallow_commits: Default::default(),
+        }
+    }
+}
+
+impl Options {
+    /// Return the severity of `id`, considering our overrides.
+    pub fn severity(&self, id: MessageId) -> Severity {
+        self.severities
+            .get(&id)
+            .copied()
+            .unwrap_or_else(|| id.default_severity())
+    }
+}
+
+/// A problem that was found during the [check](crate::check()).
+#[derive(Debug)]
+pub enum Problem {
+    /// An object is referenced, but isn't present in the object database.
+    Missing {
+        /// The id of the missing object.
+        id: ObjectId,
+        /// The kind the object is expected to have, or `None` if it was one of the tips.
+        kind: Option<Kind>,
+        /// The object that refers to the missing one, or `None` if it was one of the tips.
+        referenced_by: Option<ObjectId>,
+    },
+    /// An object is of a different kind than what its referrer expects, like a tree entry pointing to a commit.
+    KindMismatch {
+        /// The id of the object.
+        id: ObjectId,
+        /// The kind we expected.
+        expected: Kind,
+        /// The kind the object actually has.
+        actual: Kind,
+        /// The object that refers to this one.
+        referenced_by: ObjectId,
+    },
+    /// The hash of an object doesn't match its id.
+    HashMismatch {
+        /// The id of the object.
+        id: ObjectId,
+        /// The kind of the object.
+        kind: Kind,
+        /// The hash of the object data.
+        actual: ObjectId,
+    },
+    /// The object could not be read from the object database, indicating a corrupt pack or loose object.
+    Unreadable {
+        /// The id of the object.
+        id: ObjectId,
+        /// The error that occurred when trying to read it.
+        source: gix_object::find::Error,
+    },
+    /// The object has a syntax problem.
+    Invalid {
+        /// The id of the object.
+        id: ObjectId,
+        /// The kind of the object.
+        kind: Kind,
+        /// What's wrong with it.
+        message: crate::object::Message,
+        /// The severity of the problem, which is never [`Severity::Ignore`].
+        severity: Severity,
+    },
+}
+
+impl Problem {
+    /// Return `true` if this problem means the repository is corrupt, or `false` if it's merely a warning.
+    pub fn is_error(&self) -> bool {
+        match self {
+            Problem::Invalid { severity, .. } => *severity == Severity::Error,
+            _ => true,
+        }
+    }
+
+    /// The id of the object with the problem.
+    pub fn id(&self) -> &ObjectId {
+        match self {
+            Problem::Missing { id, .. }
+            | Problem::KindMismatch { id, .. }
+            | Problem::HashMismatch { id, .. }
+            | Problem::Unreadable { id, .. }
+            | Problem::Invalid { id, .. } => id,
+        }
+    }
+}
+
+/// An object that isn't reachable from any of the tips.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Unreachable {
+    /// The id of the object.
+    pub id: ObjectId,
+    /// The kind of the object.
+    pub kind: Kind,
+    /// If `true`, no other unreachable object refers to this one, making it the tip of an unreachable graph.
+    pub is_dangling: bool,
+}
+
+/// The outcome of [`check()`](crate::check()).
+#[derive(Debug, Default)]
+pub struct Outcome {
+    /// All problems we found, in the order we found them.
+    pub problems: Vec<Problem>,
+    /// The amount of objects that were reachable from the tips.
+    pub num_reachable: usize,
+    /// All objects that aren't reachable from the tips, sorted by id, if all objects were provided.
+    pub unreachable: Vec<Unreachable>,
+}
+
+impl Outcome {
+    /// Return `true` if at least one of our [problems](Self::problems) [is an error](Problem::is_error()).
+    pub fn has_errors(&self) -> bool {
+        self.problems.iter().any(Problem::is_error)
+    }
+
+    /// Return an iterator over all unreachable objects that no other object refers to.
+    pub fn dangling(&self) -> impl Iterator<Item = &Unreachable> + '_ {
+        self.unreachable.iter().filter(|u| u.is_dangling)
+    }
+}
+
+/// The progress ids used in [`check()`](crate::check()).
+///
+/// Use this information to selectively extract the progress of interest in case the parent application has custom visualization.
+#[derive(Debug, Copy, Clone)]
+pub enum ProgressId {
+    /// The amount of reachable objects that were checked.
+    ReachableObjects,
+    /// The amount of unreachable objects that were checked.
+    UnreachableObjects,
+}
+
+impl From<ProgressId> for gix_features::progress::Id {
+    fn from(v: ProgressId) -> Self {
+        match v {
+            ProgressId::ReachableObjects => *b"FSRO",
+            ProgressId::UnreachableObjects => *b"FSUO",
+        }
+    }
+}
+
+/// The error returned by [`check()`](crate::check()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not hash object {id}")]
+    Hasher {
+        id: ObjectId,
+        #[source]
+        source: gix_hash::hasher::Error,
+    },
+    #[error("Interrupted")]
+    Interrupted,
+}
@@ -0,0 +1,21 @@
+//! Write unreachable objects into the `lost-found` directory like `git fsck --lost-found` does.
+use std::path::{Path, PathBuf};
+
+use gix_hash::oid;
+use gix_object::Kind;
+
+/// Write the object with `id`, `kind` and `data` into `lost_found_dir`, typically `.git/lost-found`, and return the path written to.
+///
+/// Commits go into `commit/<id>` and everything else into `other/<id>`. The file contains the object id, unless it's a blob
+/// in which case it's the blob data itself, which makes it easy to recover lost files.
+pub fn write(lost_found_dir: &Path, id: &oid, kind: Kind, data: &[u8]) -> std::io::Result<PathBuf> {
+    let dir = lost_found_dir.join(if kind == Kind::Commit { "commit" } else { "other" });
+    std::fs::create_dir_all(&dir)?;
+    let path = dir.join(id.to_hex().to_string());
+    if kind == Kind::Blob {
+        std::fs::write(&path, data)?;
+    } else {
+        std::fs::write(&path, format!("{id}\n"))?;
+    }
+    Ok(path)
+}
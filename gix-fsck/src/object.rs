@@ -0,0 +1,482 @@
+//! Validate the syntax of individual objects, reporting problems with the same message IDs that `git fsck` uses.
+use std::collections::HashSet;
+
+use gix_object::{bstr::ByteSlice, Kind};
+
+/// How severe a [`MessageId`] is, which determines whether it's reported and if it's an error.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub enum Severity {
+    /// The problem renders the repository corrupt.
+    Error,
+    /// The problem is reported, but the repository is still considered healthy.
+    Warn,
+    /// The problem isn't reported at all.
+    Ignore,
+}
+
+impl Severity {
+    /// Parse `name` as `error`, `warn` or `ignore` like `git` does for `fsck.<msg-id>` configuration values.
+    pub fn from_bytes(name: &[u8]) -> Option<Self> {
+        Some(match name {
+            b"error" => Severity::Error,
+            b"warn" => Severity::Warn,
+            b"ignore" => Severity::Ignore,
+            _ => return None,
+        })
+    }
+}
+
+macro_rules! message_ids {
+    ($($(#[$doc:meta])* $name:ident => $str:literal, $severity:ident;)*) => {
+        /// Identifies a problem with an object, named just like in `git fsck`.
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+        pub enum MessageId {
+            $($(#[$doc])* $name,)*
+        }
+
+        impl MessageId {
+            /// All message IDs we know.
+            pub const ALL: &'static [MessageId] = &[$(MessageId::$name,)*];
+
+            /// The camel-cased name of the message ID, as used in configuration and in messages, like `badTimezone`.
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(MessageId::$name => $str,)*
+                }
+            }
+
+            /// The severity `git` assigns to this message ID by default.
+            ///
+            /// Note that what `git` considers _info_ is treated as [warning](Severity::Warn), as that's how it is displayed.
+            pub fn default_severity(&self) -> Severity {
+                match self {
+                    $(MessageId::$name => Severity::$severity,)*
+                }
+            }
+        }
+    };
+}
+
+message_ids! {
+    /// The date of an identity can't be parsed.
+    BadDate => "badDate", Error;
+    /// The date of an identity doesn't fit into the data type used to store it.
+    BadDateOverflow => "badDateOverflow", Error;
+    /// The email of an identity contains invalid characters.
+    BadEmail => "badEmail", Error;
+    /// A tree entry has a mode that isn't known.
+    BadFilemode => "badFilemode", Warn;
+    /// The name of an identity contains invalid characters.
+    BadName => "badName", Error;
+    /// The `object` line of a tag doesn't contain a valid hash.
+    BadObjectSha1 => "badObjectSha1", Error;
+    /// A `parent` line of a commit doesn't contain a valid hash.
+    BadParentSha1 => "badParentSha1", Error;
+    /// The name of a tag isn't valid as reference name.
+    BadTagName => "badTagName", Warn;
+    /// The timezone of an identity isn't in the `+HHMM` or `-HHMM` format.
+    BadTimezone => "badTimezone", Error;
+    /// A tree can't be parsed.
+    BadTree => "badTree", Error;
+    /// The `tree` line of a commit doesn't contain a valid hash.
+    BadTreeSha1 => "badTreeSha1", Error;
+    /// The `type` line of a tag names an unknown object type.
+    BadType => "badType", Error;
+    /// A tree contains the same name more than once.
+    DuplicateEntries => "duplicateEntries", Error;
+    /// A tree contains an entry with an empty name.
+    EmptyName => "emptyName", Warn;
+    /// A tree contains an entry with a slash in its name.
+    FullPathname => "fullPathname", Warn;
+    /// A tree contains an entry named `.`.
+    HasDot => "hasDot", Warn;
+    /// A tree contains an entry named `..`.
+    HasDotdot => "hasDotdot", Warn;
+    /// A tree contains an entry named `.git`, in any capitalization.
+    HasDotgit => "hasDotgit", Warn;
+    /// A commit has no `author` line.
+    MissingAuthor => "missingAuthor", Error;
+    /// A commit has no `committer` line.
+    MissingCommitter => "missingCommitter", Error;
+    /// An identity has no email.
+    MissingEmail => "missingEmail", Error;
+    /// An identity has no name in front of the email.
+    MissingNameBeforeEmail => "missingNameBeforeEmail", Error;
+    /// A tag has no `object` line.
+    MissingObject => "missingObject", Error;
+    /// An identity lacks the space between email and date.
+    MissingSpaceBeforeDate => "missingSpaceBeforeDate", Error;
+    /// An identity lacks the space between name and email.
+    MissingSpaceBeforeEmail => "missingSpaceBeforeEmail", Error;
+    /// A tag ends right after its `tag` line.
+    MissingTag => "missingTag", Error;
+    /// A tag has no `tag` line.
+    MissingTagEntry => "missingTagEntry", Error;
+    /// A tag has no `tagger` line.
+    MissingTaggerEntry => "missingTaggerEntry", Warn;
+    /// A commit has no `tree` line.
+    MissingTree => "missingTree", Error;
+    /// A tag ends right after its `type` line.
+    MissingType => "missingType", Error;
+    /// A tag has no `type` line.
+    MissingTypeEntry => "missingTypeEntry", Error;
+    /// A commit has more than one `author` line.
+    MultipleAuthors => "multipleAuthors", Error;
+    /// A commit message contains a null byte.
+    NulInCommit => "nulInCommit", Warn;
+    /// The headers of a commit or tag contain a null byte.
+    NulInHeader => "nulInHeader", Error;
+    /// A tree contains an entry pointing to the null hash.
+    NullSha1 => "nullSha1", Warn;
+    /// A tree isn't sorted the way `git` sorts it.
+    TreeNotSorted => "treeNotSorted", Error;
+    /// The headers of a commit or tag don't end with a newline.
+    UnterminatedHeader => "unterminatedHeader", Error;
+    /// The date of an identity has a leading zero.
+    ZeroPaddedDate => "zeroPaddedDate", Error;
+    /// A tree entry has a mode with a leading zero.
+    ZeroPaddedFilemode => "zeroPaddedFilemode", Warn;
+}
+
+impl MessageId {
+    /// Find the message ID with the given `name`, ignoring case just like `git` does, so `badtimezone` is `badTimezone`.
+    pub fn from_bytes(name: &[u8]) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .find(|id| id.as_str().as_bytes().eq_ignore_ascii_case(name))
+            .copied()
+    }
+}
+
+impl std::fmt::Display for MessageId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A problem found in an object.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Message {
+    /// The kind of problem.
+    pub id: MessageId,
+    /// A human-readable description of the problem, worded like `git` would.
+    pub detail: &'static str,
+}
+
+impl Message {
+    fn new(id: MessageId, detail: &'static str) -> Self {
+        Message { id, detail }
+    }
+}
+
+/// Validate `data` as object of `kind` with hashes of `object_hash` and return all problems we found,
+/// regardless of their [severity](MessageId::default_severity()).
+///
+/// Commits and tags are validated only until the first problem that prevents parsing the rest of the object, just like `git` does.
+/// Blobs are never invalid.
+pub fn validate(kind: Kind, data: &[u8], object_hash: gix_hash::Kind) -> Vec<Message> {
+    let mut out = Vec::new();
+    match kind {
+        Kind::Blob => {}
+        Kind::Tree => validate_tree(data, object_hash, &mut out),
+        Kind::Commit => match validate_commit(data, object_hash) {
+            Ok(()) if data.contains(&0) => {
+                out.push(Message::new(
+                    MessageId::NulInCommit,
+                    "NUL byte in the commit object body",
+                ));
+            }
+            Ok(()) => {}
+            Err(message) => out.push(message),
+        },
+        Kind::Tag => validate_tag(data, object_hash, &mut out),
+    }
+    out
+}
+
+fn validate_tree(mut data: &[u8], object_hash: gix_hash::Kind, out: &mut Vec<Message>) {
+    let hash_len = object_hash.len_in_bytes();
+    let mut flags = HashSet::new();
+    let mut names = HashSet::new();
+    let mut previous: Option<(&[u8], bool)> = None;
+    while !data.is_empty() {
+        let Some(space) = data.find_byte(b' ') else {
+            out.push(Message::new(MessageId::BadTree, "cannot be parsed as a tree"));
+            return;
+        };
+        let mode = &data[..space];
+        let Some(nul) = data[space + 1..].find_byte(0).map(|pos| space + 1 + pos) else {
+            out.push(Message::new(MessageId::BadTree, "cannot be parsed as a tree"));
+            return;
+        };
+        let name = &data[space + 1..nul];
+        let Some(id) = data.get(nul + 1..nul + 1 + hash_len) else {
+            out.push(Message::new(MessageId::BadTree, "cannot be parsed as a tree"));
+            return;
+        };
+        if mode.is_empty() || !mode.iter().all(|b| (b'0'..=b'7').contains(b)) {
+            out.push(Message::new(MessageId::BadTree, "cannot be parsed as a tree"));
+            return;
+        }
+        data = &data[nul + 1 + hash_len..];
+
+        if mode.len() > 1 && mode[0] == b'0' {
+            flags.insert(MessageId::ZeroPaddedFilemode);
+        }
+        let mode = mode
+            .iter()
+            .fold(0u32, |acc, b| acc.saturating_mul(8).saturating_add(u32::from(b - b'0')));
+        let is_dir = mode == 0o040000;
+        if !matches!(mode, 0o100644 | 0o100755 | 0o100664 | 0o120000 | 0o040000 | 0o160000) {
+            flags.insert(MessageId::BadFilemode);
+        }
+        if id.iter().all(|b| *b == 0) {
+            flags.insert(MessageId::NullSha1);
+        }
+        if name.contains(&b'/') {
+            flags.insert(MessageId::FullPathname);
+        }
+        match name {
+            b"" => flags.insert(MessageId::EmptyName),
+            b"." => flags.insert(MessageId::HasDot),
+            b".." => flags.insert(MessageId::HasDotdot),
+            _ if name.eq_ignore_ascii_case(b".git") => flags.insert(MessageId::HasDotgit),
+            _ => false,
+        };
+        if !names.insert(name) {
+            flags.insert(MessageId::DuplicateEntries);
+        }
+        if let Some((prev_name, prev_is_dir)) = previous {
+            if tree_entry_cmp(prev_name, prev_is_dir, name, is_dir) == std::cmp::Ordering::Greater {
+                flags.insert(MessageId::TreeNotSorted);
+            }
+        }
+        previous = Some((name, is_dir));
+    }
+
+    let mut flags: Vec<_> = flags.into_iter().collect();
+    flags.sort();
+    out.extend(flags.into_iter().map(|id| {
+        Message::new(
+            id,
+            match id {
+                MessageId::ZeroPaddedFilemode => "contains zero-padded file modes",
+                MessageId::BadFilemode => "contains bad file modes",
+                MessageId::NullSha1 => "contains entries pointing to null sha1",
+                MessageId::FullPathname => "contains full pathnames",
+                MessageId::EmptyName => "contains empty pathname",
+                MessageId::HasDot => "contains '.'",
+                MessageId::HasDotdot => "contains '..'",
+                MessageId::HasDotgit => "contains '.git'",
+                MessageId::DuplicateEntries => "contains duplicate file entries",
+                MessageId::TreeNotSorted => "not properly sorted",
+                _ => unreachable!("only tree flags are collected"),
+            },
+        )
+    }));
+}
+
+/// Compare names like `git` sorts tree entries, that is as if directories had a trailing slash.
+fn tree_entry_cmp(a: &[u8], a_is_dir: bool, b: &[u8], b_is_dir: bool) -> std::cmp::Ordering {
+    let common = a.len().min(b.len());
+    a[..common].cmp(&b[..common]).then_with(|| {
+        let a_next = a.get(common).copied().or(a_is_dir.then_some(b'/'));
+        let b_next = b.get(common).copied().or(b_is_dir.then_some(b'/'));
+        a_next.cmp(&b_next)
+    })
+}
+
+/// Validate the header lines of a commit or tag, returning an error if they contain a null byte or if they don't end.
+fn validate_headers(data: &[u8]) -> Result<(), Message> {
+    for (idx, byte) in data.iter().enumerate() {
+        match byte {
+            0 => {
+                return Err(Message::new(
+                    MessageId::NulInHeader,
+                    "unterminated header: NUL in header",
+                ))
+            }
+            b'\n' if data.get(idx + 1) == Some(&b'\n') => return Ok(()),
+            _ => {}
+        }
+    }
+    if data.is_empty() || data.last() == Some(&b'\n') {
+        Ok(())
+    } else {
+        Err(Message::new(MessageId::UnterminatedHeader, "unterminated header"))
+    }
+}
+
+/// Parse a hexadecimal hash of `object_hash` followed by a newline at the beginning of `data` and return the remainder.
+fn hash_line(data: &[u8], object_hash: gix_hash::Kind) -> Option<&[u8]> {
+    let hex_len = object_hash.len_in_hex();
+    let hex = data.get(..hex_len)?;
+    gix_hash::ObjectId::from_hex(hex).ok()?;
+    data.get(hex_len..)?.strip_prefix(b"\n")
+}
+
+fn validate_commit(data: &[u8], object_hash: gix_hash::Kind) -> Result<(), Message> {
+    validate_headers(data)?;
+    let data = data.strip_prefix(b"tree ").ok_or(Message::new(
+        MessageId::MissingTree,
+        "invalid format - expected 'tree' line",
+    ))?;
+    let mut data = hash_line(data, object_hash).ok_or(Message::new(
+        MessageId::BadTreeSha1,
+        "invalid 'tree' line format - bad sha1",
+    ))?;
+    while let Some(rest) = data.strip_prefix(b"parent ") {
+        data = hash_line(rest, object_hash).ok_or(Message::new(
+            MessageId::BadParentSha1,
+            "invalid 'parent' line format - bad sha1",
+        ))?;
+    }
+    let mut num_authors = 0;
+    while let Some(rest) = data.strip_prefix(b"author ") {
+        num_authors += 1;
+        data = validate_ident(rest)?;
+    }
+    match num_authors {
+        0 => {
+            return Err(Message::new(
+                MessageId::MissingAuthor,
+                "invalid format - expected 'author' line",
+            ))
+        }
+        1 => {}
+        _ => {
+            return Err(Message::new(
+                MessageId::MultipleAuthors,
+                "invalid format - multiple 'author' lines",
+            ))
+        }
+    }
+    let data = data.strip_prefix(b"committer ").ok_or(Message::new(
+        MessageId::MissingCommitter,
+        "invalid format - expected 'committer' line",
+    ))?;
+    validate_ident(data)?;
+    Ok(())
+}
+
+fn validate_tag(data: &[u8], object_hash: gix_hash::Kind, out: &mut Vec<Message>) {
+    if let Err(message) = validate_tag_inner(data, object_hash, out) {
+        out.push(message);
+    }
+}
+
+fn validate_tag_inner(data: &[u8], object_hash: gix_hash::Kind, out: &mut Vec<Message>) -> Result<(), Message> {
+    validate_headers(data)?;
+    let data = data.strip_prefix(b"object ").ok_or(Message::new(
+        MessageId::MissingObject,
+        "invalid format - expected 'object' line",
+    ))?;
+    let data = hash_line(data, object_hash).ok_or(Message::new(
+        MessageId::BadObjectSha1,
+        "invalid 'object' line format - bad sha1",
+    ))?;
+    let data = data.strip_prefix(b"type ").ok_or(Message::new(
+        MessageId::MissingTypeEntry,
+        "invalid format - expected 'type' line",
+    ))?;
+    let eol = data.find_byte(b'\n').ok_or(Message::new(
+        MessageId::MissingType,
+        "invalid format - unexpected end after 'type' line",
+    ))?;
+    Kind::from_bytes(&data[..eol]).map_err(|_| Message::new(MessageId::BadType, "invalid 'type' value"))?;
+    let data = data[eol + 1..].strip_prefix(b"tag ").ok_or(Message::new(
+        MessageId::MissingTagEntry,
+        "invalid format - expected 'tag' line",
+    ))?;
+    let eol = data.find_byte(b'\n').ok_or(Message::new(
+        MessageId::MissingTag,
+        "invalid format - unexpected end after 'type' line",
+    ))?;
+    if gix_validate::tag::name(data[..eol].as_bstr()).is_err() {
+        out.push(Message::new(MessageId::BadTagName, "invalid 'tag' name"));
+    }
+    match data[eol + 1..].strip_prefix(b"tagger ") {
+        Some(data) => {
+            validate_ident(data)?;
+        }
+        None => out.push(Message::new(
+            MessageId::MissingTaggerEntry,
+            "invalid format - expected 'tagger' line",
+        )),
+    }
+    Ok(())
+}
+
+/// Validate an identity line like `name <email> 1234 +0100\n` and return everything after it.
+fn validate_ident(data: &[u8]) -> Result<&[u8], Message> {
+    if data.first() == Some(&b'<') {
+        return Err(Message::new(
+            MessageId::MissingNameBeforeEmail,
+            "invalid author/committer line - missing space before email",
+        ));
+    }
+    let pos = data.find_byteset(b"<>\n").unwrap_or(data.len());
+    match data.get(pos) {
+        Some(b'>') => {
+            return Err(Message::new(
+                MessageId::BadName,
+                "invalid author/committer line - bad name",
+            ))
+        }
+        Some(b'<') => {}
+        _ => {
+            return Err(Message::new(
+                MessageId::MissingEmail,
+                "invalid author/committer line - missing email",
+            ))
+        }
+    }
+    if pos == 0 || data[pos - 1] != b' ' {
+        return Err(Message::new(
+            MessageId::MissingSpaceBeforeEmail,
+            "invalid author/committer line - missing space before email",
+        ));
+    }
+    let data = &data[pos + 1..];
+    let pos = data.find_byteset(b"<>\n").unwrap_or(data.len());
+    if data.get(pos) != Some(&b'>') {
+        return Err(Message::new(
+            MessageId::BadEmail,
+            "invalid author/committer line - bad email",
+        ));
+    }
+    let data = data[pos + 1..].strip_prefix(b" ").ok_or(Message::new(
+        MessageId::MissingSpaceBeforeDate,
+        "invalid author/committer line - missing space before date",
+    ))?;
+    if data.first() == Some(&b'0') && data.get(1) != Some(&b' ') {
+        return Err(Message::new(
+            MessageId::ZeroPaddedDate,
+            "invalid author/committer line - zero-padded date",
+        ));
+    }
+    let num_digits = data.iter().take_while(|b| b.is_ascii_digit()).count();
+    let digits = &data[..num_digits];
+    if !digits.is_empty() && digits.to_str().ok().and_then(|d| d.parse::<u64>().ok()).is_none() {
+        return Err(Message::new(
+            MessageId::BadDateOverflow,
+            "invalid author/committer line - date causes integer overflow",
+        ));
+    }
+    let data = match data[num_digits..].strip_prefix(b" ") {
+        Some(data) if num_digits != 0 => data,
+        _ => {
+            return Err(Message::new(
+                MessageId::BadDate,
+                "invalid author/committer line - bad date",
+            ))
+        }
+    };
+    match data {
+        [b'+' | b'-', a, b, c, d, b'\n', rest @ ..] if [a, b, c, d].iter().all(|b| b.is_ascii_digit()) => Ok(rest),
+        _ => Err(Message::new(
+            MessageId::BadTimezone,
+            "invalid author/committer line - bad time zone",
+        )),
+    }
+}
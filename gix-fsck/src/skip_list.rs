@@ -0,0 +1,35 @@
+//! Parse the file configured with `fsck.skipList`.
+use gix_hash::ObjectId;
+use gix_hashtable::HashSet;
+use gix_object::bstr::{BString, ByteSlice};
+
+/// The error returned by [`parse()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Line {line_number} of skip-list does not contain a valid object id: {line:?}")]
+    InvalidLine { line_number: usize, line: BString },
+}
+
+/// Parse `input` as skip-list with one full object id of `object_hash` per line, into a set of object ids.
+///
+/// Leading and trailing whitespace is ignored, as are empty lines and comments starting with `#`, which may also trail
+/// an object id.
+pub fn parse(input: &[u8], object_hash: gix_hash::Kind) -> Result<HashSet<ObjectId>, Error> {
+    let mut out = HashSet::default();
+    for (idx, line) in input.lines().enumerate() {
+        let line = line.find_byte(b'#').map_or(line, |pos| &line[..pos]).trim();
+        if line.is_empty() {
+            continue;
+        }
+        let id = (line.len() == object_hash.len_in_hex())
+            .then(|| ObjectId::from_hex(line).ok())
+            .flatten()
+            .ok_or_else(|| Error::InvalidLine {
+                line_number: idx + 1,
+                line: line.into(),
+            })?;
+        out.insert(id);
+    }
+    Ok(out)
+}
@@ -0,0 +1,352 @@
+use std::sync::atomic::AtomicBool;
+
+use gix_fsck::{
+    check::{Options, Problem, Unreachable},
+    object::{MessageId, Severity},
+};
+use gix_hash::ObjectId;
+use gix_hashtable::HashMap;
+use gix_object::Kind;
+
+/// A minimal in-memory object database which allows to store objects under any id.
+#[derive(Default)]
+struct Odb(HashMap<ObjectId, (Kind, Vec<u8>)>);
+
+impl Odb {
+    fn add(&mut self, kind: Kind, data: impl Into<Vec<u8>>) -> ObjectId {
+        let data = data.into();
+        let id = gix_object::compute_hash(gix_hash::Kind::Sha1, kind, &data).expect("no collision");
+        self.0.insert(id, (kind, data));
+        id
+    }
+
+    fn blob(&mut self, content: &str) -> ObjectId {
+        self.add(Kind::Blob, content)
+    }
+
+    fn tree(&mut self, entries: &[(&str, &str, ObjectId)]) -> ObjectId {
+        let mut data = Vec::new();
+        for (mode, name, id) in entries {
+            data.extend_from_slice(format!("{mode} {name}\0").as_bytes());
+            data.extend_from_slice(id.as_slice());
+        }
+        self.add(Kind::Tree, data)
+    }
+
+    fn commit(&mut self, tree: ObjectId, parents: &[ObjectId], message: &str) -> ObjectId {
+        let mut data = format!("tree {tree}\n");
+        data.extend(parents.iter().map(|parent| format!("parent {parent}\n")));
+        data.push_str("author A <a@example.com> 1 +0000\ncommitter C <c@example.com> 1 +0000\n\n");
+        data.push_str(message);
+        self.add(Kind::Commit, data)
+    }
+
+    fn all(&self) -> Vec<ObjectId> {
+        self.0.keys().copied().collect()
+    }
+}
+
+impl gix_object::Find for Odb {
+    fn try_find<'a>(
+        &self,
+        id: &gix_hash::oid,
+        buffer: &'a mut Vec<u8>,
+    ) -> Result<Option<gix_object::Data<'a>>, gix_object::find::Error> {
+        Ok(self.0.get(id).map(|(kind, data)| {
+            buffer.clear();
+            buffer.extend_from_slice(data);
+            gix_object::Data {
+                kind: *kind,
+                hash_kind: id.kind(),
+                data: buffer.as_slice(),
+            }
+        }))
+    }
+}
+
+fn check(odb: &Odb, tips: &[ObjectId], all: bool, options: &Options) -> gix_fsck::check::Outcome {
+    let mut all_objects = odb.all().into_iter();
+    gix_fsck::check(
+        odb,
+        tips.iter().copied(),
+        all.then_some(&mut all_objects as &mut dyn Iterator<Item = ObjectId>),
+        &mut gix_features::progress::Discard,
+        &AtomicBool::default(),
+        options,
+    )
+    .expect("no fatal error")
+}
+
+fn unreachable(id: ObjectId, kind: Kind, is_dangling: bool) -> Unreachable {
+    Unreachable { id, kind, is_dangling }
+}
+
+#[test]
+fn healthy_graph_with_dangling_and_unreachable_objects() {
+    let mut odb = Odb::default();
+    let blob = odb.blob("a\n");
+    let tree = odb.tree(&[("100644", "a", blob)]);
+    let first = odb.commit(tree, &[], "first\n");
+    let second = odb.commit(tree, &[first], "second\n");
+
+    let lost_blob = odb.blob("lost\n");
+    let lost_tree = odb.tree(&[("100644", "lost", lost_blob)]);
+    let lost_commit = odb.commit(lost_tree, &[second], "lost\n");
+    let dangling_blob = odb.blob("dangling\n");
+
+    let outcome = check(&odb, &[second], true, &Options::default());
+    assert!(outcome.problems.is_empty(), "{:?}", outcome.problems);
+    assert!(!outcome.has_errors());
+    assert_eq!(outcome.num_reachable, 4);
+
+    let mut expected = vec![
+        unreachable(lost_blob, Kind::Blob, false),
+        unreachable(lost_tree, Kind::Tree, false),
+        unreachable(lost_commit, Kind::Commit, true),
+        unreachable(dangling_blob, Kind::Blob, true),
+    ];
+    expected.sort();
+    assert_eq!(outcome.unreachable, expected);
+    assert_eq!(
+        outcome.dangling().map(|u| u.id).collect::<Vec<_>>(),
+        expected
+            .iter()
+            .filter(|u| u.is_dangling)
+            .map(|u| u.id)
+            .collect::<Vec<_>>()
+    );
+
+    let outcome = check(&odb, &[second], false, &Options::default());
+    assert!(
+        outcome.unreachable.is_empty(),
+        "without all objects, there is nothing unreachable"
+    );
+}
+
+#[test]
+fn missing_objects_and_kind_mismatches() {
+    let mut odb = Odb::default();
+    let blob = odb.blob("a\n");
+    let missing_blob = gix_hash::ObjectId::from_hex(b"0000000000000000000000000000000000000001").unwrap();
+    let tree = odb.tree(&[("100644", "a", blob), ("100644", "b", missing_blob)]);
+    let commit = odb.commit(tree, &[], "first\n");
+    let other_commit = odb.commit(tree, &[], "other\n");
+    let tree_with_commit_as_blob = odb.tree(&[("100644", "c", other_commit)]);
+    let commit_with_blob_as_tree = odb.commit(blob, &[commit], "second\n");
+    let missing_tip = gix_hash::ObjectId::from_hex(b"0000000000000000000000000000000000000002").unwrap();
+
+    let outcome = check(
+        &odb,
+        &[commit_with_blob_as_tree, tree_with_commit_as_blob, missing_tip],
+        false,
+        &Options::default(),
+    );
+    assert!(outcome.has_errors());
+    let mut problems: Vec<_> = outcome
+        .problems
+        .iter()
+        .map(|p| match p {
+            Problem::Missing {
+                id,
+                kind,
+                referenced_by,
+            } => format!(
+                "missing {kind:?} {id} from {}",
+                referenced_by.map_or("tips".into(), |id| id.to_string())
+            ),
+            Problem::KindMismatch {
+                id,
+                expected,
+                actual,
+                referenced_by,
+            } => format!("{id} is {actual}, not {expected}, in {referenced_by}"),
+            other => unreachable!("unexpected problem: {other:?}"),
+        })
+        .collect();
+    problems.sort();
+    let mut expected = vec![
+        format!("missing None {missing_tip} from tips"),
+        format!("missing Some(Blob) {missing_blob} from {tree}"),
+        format!("{blob} is blob, not tree, in {commit_with_blob_as_tree}"),
+        format!("{other_commit} is commit, not blob, in {tree_with_commit_as_blob}"),
+    ];
+    expected.sort();
+    assert_eq!(problems, expected);
+}
+
+#[test]
+fn objects_missing_in_many_places_are_reported_once() {
+    let mut odb = Odb::default();
+    let missing_blob = gix_hash::ObjectId::from_hex(b"0000000000000000000000000000000000000001").unwrap();
+    let first_tree = odb.tree(&[("100644", "a", missing_blob)]);
+    let second_tree = odb.tree(&[("100644", "b", missing_blob)]);
+    let first = odb.commit(first_tree, &[], "first\n");
+    let second = odb.commit(second_tree, &[first], "second\n");
+
+    let outcome = check(&odb, &[second, missing_blob], true, &Options::default());
+    assert_eq!(
+        outcome
+            .problems
+            .iter()
+            .filter(|p| matches!(p, Problem::Missing { id, .. } if *id == missing_blob))
+            .count(),
+        1,
+        "{:?}",
+        outcome.problems
+    );
+    assert_eq!(outcome.problems.len(), 1);
+}
+
+#[test]
+fn hash_mismatches_and_invalid_objects_respecting_severity_and_skip_list() {
+    let mut odb = Odb::default();
+    let blob = odb.blob("a\n");
+    let empty_tree = odb.tree(&[]);
+    let bad_tree = odb.tree(&[
+        ("100644", "a", blob),
+        ("100644", "a", blob),
+        ("040000", "b", empty_tree),
+    ]);
+    let commit = odb.commit(bad_tree, &[], "first\n");
+    let wrong_id = gix_hash::ObjectId::from_hex(b"0000000000000000000000000000000000000003").unwrap();
+    odb.0.insert(wrong_id, (Kind::Blob, b"content".to_vec()));
+    let tree = odb.tree(&[("100644", "wrong", wrong_id)]);
+
+    let outcome = check(&odb, &[commit, tree], false, &Options::default());
+    let summary: Vec<_> = outcome
+        .problems
+        .iter()
+        .map(|p| match p {
+            Problem::Invalid {
+                id, message, severity, ..
+            } => format!("{id} {} {severity:?}", message.id),
+            Problem::HashMismatch { id, kind, actual } => {
+                assert_eq!(
+                    *actual,
+                    gix_object::compute_hash(gix_hash::Kind::Sha1, *kind, b"content").unwrap()
+                );
+                format!("{id} hash mismatch")
+            }
+            other => unreachable!("unexpected problem: {other:?}"),
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            format!("{bad_tree} duplicateEntries Error"),
+            format!("{bad_tree} zeroPaddedFilemode Warn"),
+            format!("{wrong_id} hash mismatch"),
+        ]
+    );
+    assert!(outcome.has_errors());
+
+    let options = Options {
+        severities: [
+            (MessageId::DuplicateEntries, Severity::Warn),
+            (MessageId::ZeroPaddedFilemode, Severity::Ignore),
+        ]
+        .into_iter()
+        .collect(),
+        ..Default::default()
+    };
+    let outcome = check(&odb, &[commit], false, &options);
+    assert_eq!(outcome.problems.len(), 1);
+    assert!(!outcome.has_errors(), "only a warning remains");
+
+    let options = Options {
+        skip_list: [bad_tree, wrong_id].into_iter().collect(),
+        ..Default::default()
+    };
+    let outcome = check(&odb, &[commit, tree], false, &options);
+    assert_eq!(outcome.problems.len(), 1, "hash mismatches can't be skipped");
+
+    let options = Options {
+        verify_objects: false,
+        ..Default::default()
+    };
+    let outcome = check(&odb, &[commit, tree], false, &options);
+    assert!(outcome.problems.is_empty(), "connectivity is fine");
+}
+
+#[test]
+fn shallow_commits_have_no_parents() {
+    let mut odb = Odb::default();
+    let tree = odb.tree(&[]);
+    let missing_parent = gix_hash::ObjectId::from_hex(b"0000000000000000000000000000000000000004").unwrap();
+    let commit = odb.commit(tree, &[missing_parent], "shallow\n");
+
+    let outcome = check(&odb, &[commit], false, &Options::default());
+    assert_eq!(outcome.problems.len(), 1, "the parent is missing");
+
+    let options = Options {
+        shallow_commits: [commit].into_iter().collect(),
+        ..Default::default()
+    };
+    let outcome = check(&odb, &[commit], false, &options);
+    assert!(outcome.problems.is_empty());
+}
+
+#[test]
+fn real_repository_is_healthy() -> gix_testtools::Result {
+    let objects_dir = gix_testtools::scripted_fixture_read_only("make_test_repos.sh")?
+        .join("base")
+        .join(".git")
+        .join("objects");
+    let odb = gix_odb::at(objects_dir)?;
+    let mut all_objects = odb.iter()?.collect::<Result<Vec<_>, _>>()?.into_iter();
+    let outcome = gix_fsck::check(
+        &odb,
+        [
+            "ebed23648b19484cb1f340c4ee04dda08479188a",
+            "8ff6d0f8891c3cb22827be142cc64606121d47b3",
+            "734c926856a328d1168ffd7088532e0d1ad19bbe",
+        ]
+        .map(crate::hex_to_id),
+        Some(&mut all_objects),
+        &mut gix_features::progress::Discard,
+        &AtomicBool::default(),
+        &Options::default(),
+    )?;
+    assert!(outcome.problems.is_empty(), "{:?}", outcome.problems);
+    assert_eq!(outcome.num_reachable, 10, "3 commits, 4 trees and 3 blobs");
+    assert!(outcome.unreachable.is_empty());
+    Ok(())
+}
+
+mod skip_list {
+    #[test]
+    fn parse() {
+        let ids = gix_fsck::skip_list::parse(
+            b"# comment\n\n  e69de29bb2d1d6434b8b29ae775ad8c2e48c5391  # empty blob\n4b825dc642cb6eb9a060e54bf8d69288fbee4904\n",
+            gix_hash::Kind::Sha1,
+        )
+        .expect("valid");
+        assert_eq!(ids.len(), 2);
+        assert!(ids.contains(&crate::hex_to_id("4b825dc642cb6eb9a060e54bf8d69288fbee4904")));
+
+        let err = gix_fsck::skip_list::parse(b"\ne69de29\n", gix_hash::Kind::Sha1).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Line 2 of skip-list does not contain a valid object id: \"e69de29\""
+        );
+    }
+}
+
+mod lost_found {
+    use gix_object::Kind;
+
+    #[test]
+    fn write() -> gix_testtools::Result {
+        let dir = gix_testtools::tempfile::tempdir()?;
+        let blob = crate::hex_to_id("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391");
+        let commit = crate::hex_to_id("734c926856a328d1168ffd7088532e0d1ad19bbe");
+        let path = gix_fsck::lost_found::write(dir.path(), &blob, Kind::Blob, b"content")?;
+        assert_eq!(path, dir.path().join("other").join(blob.to_string()));
+        assert_eq!(std::fs::read(path)?, b"content", "blobs are written as is");
+
+        let path = gix_fsck::lost_found::write(dir.path(), &commit, Kind::Commit, b"ignored")?;
+        assert_eq!(path, dir.path().join("commit").join(commit.to_string()));
+        assert_eq!(std::fs::read_to_string(path)?, format!("{commit}\n"));
+        Ok(())
+    }
+}
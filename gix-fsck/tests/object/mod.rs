@@ -0,0 +1,225 @@
+use gix_fsck::object::{validate, MessageId, Severity};
+use gix_object::Kind;
+
+const TREE: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+const BLOB: &str = "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391";
+
+fn ids(kind: Kind, data: &[u8]) -> Vec<MessageId> {
+    validate(kind, data, gix_hash::Kind::Sha1)
+        .into_iter()
+        .map(|message| message.id)
+        .collect()
+}
+
+fn commit(headers: &str) -> Vec<u8> {
+    format!("{headers}\nmessage\n").into_bytes()
+}
+
+fn tree(entries: &[(&str, &str, &str)]) -> Vec<u8> {
+    let mut out = Vec::new();
+    for (mode, name, hex) in entries {
+        out.extend_from_slice(format!("{mode} {name}\0").as_bytes());
+        out.extend_from_slice(crate::hex_to_id(hex).as_slice());
+    }
+    out
+}
+
+#[test]
+fn message_ids_round_trip_and_ignore_case() {
+    for id in MessageId::ALL {
+        assert_eq!(MessageId::from_bytes(id.as_str().as_bytes()), Some(*id));
+    }
+    assert_eq!(MessageId::from_bytes(b"BADTIMEZONE"), Some(MessageId::BadTimezone));
+    assert_eq!(MessageId::from_bytes(b"unknown"), None);
+    assert_eq!(MessageId::ZeroPaddedFilemode.default_severity(), Severity::Warn);
+    assert_eq!(MessageId::DuplicateEntries.default_severity(), Severity::Error);
+    assert_eq!(Severity::from_bytes(b"ignore"), Some(Severity::Ignore));
+    assert_eq!(Severity::from_bytes(b"info"), None);
+}
+
+mod commit {
+    use gix_fsck::object::MessageId::*;
+    use gix_object::Kind;
+
+    use super::{commit, ids, TREE};
+
+    #[test]
+    fn valid() {
+        let parent = "0000000000000000000000000000000000000001";
+        assert_eq!(
+            ids(
+                Kind::Commit,
+                &commit(&format!(
+                    "tree {TREE}\nparent {parent}\nparent {parent}\nauthor A U Thor <a@example.com> 1234567890 +0100\ncommitter C O Mitter <c@example.com> 0 -0130\n"
+                ))
+            ),
+            []
+        );
+    }
+
+    #[test]
+    fn header_problems() {
+        for (headers, expected) in [
+            ("author A <a@b> 1 +0000\n", MissingTree),
+            ("tree 1234\nauthor A <a@b> 1 +0000\n", BadTreeSha1),
+            ("tree {TREE}\nparent xyz\n", BadParentSha1),
+            ("tree {TREE}\ncommitter C <c@d> 1 +0000\n", MissingAuthor),
+            (
+                "tree {TREE}\nauthor A <a@b> 1 +0000\nauthor B <b@b> 1 +0000\ncommitter C <c@d> 1 +0000\n",
+                MultipleAuthors,
+            ),
+            ("tree {TREE}\nauthor A <a@b> 1 +0000\n", MissingCommitter),
+        ] {
+            let headers = headers.replace("{TREE}", TREE);
+            assert_eq!(ids(Kind::Commit, &commit(&headers)), [expected], "{headers}");
+        }
+    }
+
+    #[test]
+    fn identity_problems() {
+        for (ident, expected) in [
+            ("<a@b> 1 +0000", MissingNameBeforeEmail),
+            ("A> <a@b> 1 +0000", BadName),
+            ("A 1 +0000", MissingEmail),
+            ("A<a@b> 1 +0000", MissingSpaceBeforeEmail),
+            ("A <a@b 1 +0000", BadEmail),
+            ("A <a@b>1 +0000", MissingSpaceBeforeDate),
+            ("A <a@b> 0123 +0000", ZeroPaddedDate),
+            ("A <a@b> 99999999999999999999999 +0000", BadDateOverflow),
+            ("A <a@b> yesterday +0000", BadDate),
+            ("A <a@b> 1 +01", BadTimezone),
+            ("A <a@b> 1 0100", BadTimezone),
+        ] {
+            let headers = format!("tree {TREE}\nauthor {ident}\ncommitter C <c@d> 1 +0000\n");
+            assert_eq!(ids(Kind::Commit, &commit(&headers)), [expected], "{ident}");
+        }
+    }
+
+    #[test]
+    fn null_bytes() {
+        let mut data = commit(&format!(
+            "tree {TREE}\nauthor A <a@b> 1 +0000\ncommitter C <c@d> 1 +0000\n"
+        ));
+        data.push(0);
+        assert_eq!(ids(Kind::Commit, &data), [NulInCommit], "only a warning");
+        assert_eq!(ids(Kind::Commit, b"tree \0\n\n"), [NulInHeader]);
+        assert_eq!(ids(Kind::Commit, b"tree abc"), [UnterminatedHeader]);
+    }
+}
+
+mod tree {
+    use gix_fsck::object::MessageId::*;
+    use gix_object::Kind;
+
+    use super::{ids, tree, BLOB, TREE};
+
+    #[test]
+    fn valid() {
+        assert_eq!(
+            ids(
+                Kind::Tree,
+                &tree(&[
+                    ("100755", "a.txt", BLOB),
+                    ("40000", "a", TREE),
+                    ("120000", "b", BLOB),
+                    ("160000", "c", BLOB),
+                ])
+            ),
+            [],
+            "directories sort as if they had a trailing slash"
+        );
+        assert_eq!(ids(Kind::Tree, b""), [], "empty trees are fine");
+    }
+
+    #[test]
+    fn entry_problems() {
+        for (entries, expected) in [
+            (vec![("040000", "dir", TREE)], vec![ZeroPaddedFilemode]),
+            (vec![("100600", "file", BLOB)], vec![BadFilemode]),
+            (vec![("100644", "", BLOB)], vec![EmptyName]),
+            (vec![("100644", "a/b", BLOB)], vec![FullPathname]),
+            (vec![("40000", ".", TREE)], vec![HasDot]),
+            (vec![("40000", "..", TREE)], vec![HasDotdot]),
+            (vec![("40000", ".GIT", TREE)], vec![HasDotgit]),
+            (
+                vec![("100644", "null", "0000000000000000000000000000000000000000")],
+                vec![NullSha1],
+            ),
+            (
+                vec![("100644", "a", BLOB), ("100644", "a", BLOB)],
+                vec![DuplicateEntries],
+            ),
+            (vec![("100644", "b", BLOB), ("100644", "a", BLOB)], vec![TreeNotSorted]),
+            (
+                vec![("40000", "a", TREE), ("100644", "a.txt", BLOB)],
+                vec![TreeNotSorted],
+            ),
+            (
+                vec![("040000", "b", TREE), ("100644", "a", BLOB), ("100644", "a", BLOB)],
+                vec![DuplicateEntries, TreeNotSorted, ZeroPaddedFilemode],
+            ),
+        ] {
+            assert_eq!(ids(Kind::Tree, &tree(&entries)), expected, "{entries:?}");
+        }
+    }
+
+    #[test]
+    fn unparsable() {
+        let mut data = tree(&[("100644", "a", BLOB)]);
+        data.pop();
+        assert_eq!(ids(Kind::Tree, &data), [BadTree], "truncated hash");
+        assert_eq!(ids(Kind::Tree, b"100644 a"), [BadTree], "missing null byte");
+        assert_eq!(ids(Kind::Tree, &tree(&[("10x644", "a", BLOB)])), [BadTree]);
+    }
+}
+
+mod tag {
+    use gix_fsck::object::MessageId::*;
+    use gix_object::Kind;
+
+    use super::{ids, TREE};
+
+    fn tag(headers: &str) -> Vec<u8> {
+        headers.replace("{TREE}", TREE).into_bytes()
+    }
+
+    #[test]
+    fn valid() {
+        assert_eq!(
+            ids(
+                Kind::Tag,
+                &tag("object {TREE}\ntype tree\ntag v1.0\ntagger T <t@example.com> 1 +0000\n\nmessage\n")
+            ),
+            []
+        );
+    }
+
+    #[test]
+    fn problems() {
+        for (headers, expected) in [
+            ("type tree\n", vec![MissingObject]),
+            ("object xyz\n", vec![BadObjectSha1]),
+            ("object {TREE}\ntag v1\n", vec![MissingTypeEntry]),
+            ("object {TREE}\ntype tree", vec![UnterminatedHeader]),
+            ("object {TREE}\ntype bush\ntag v1\n", vec![BadType]),
+            (
+                "object {TREE}\ntype tree\ntagger T <t@e> 1 +0000\n",
+                vec![MissingTagEntry],
+            ),
+            (
+                "object {TREE}\ntype tree\ntag v1\n\nmessage\n",
+                vec![MissingTaggerEntry],
+            ),
+            (
+                "object {TREE}\ntype tree\ntag a..b\ntagger T <t@e> 1 +0000\n",
+                vec![BadTagName],
+            ),
+            (
+                "object {TREE}\ntype tree\ntag v1\ntagger T <t@e> 1 +01\n",
+                vec![BadTimezone],
+            ),
+        ] {
+            assert_eq!(ids(Kind::Tag, &tag(headers)), expected, "{headers}");
+        }
+    }
+}
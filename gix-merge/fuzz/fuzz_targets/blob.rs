@@ -23,7 +23,7 @@ fn fuzz_text_merge(
         imara_diff::Algorithm::MyersMinimal,
     ] {
         let mut opts = Options {
-            diff_algorithm,
+            diff_algorithm: diff_algorithm.into(),
             conflict: Default::default(),
         };
         for (left, right) in [(ours, theirs), (theirs, ours)] {
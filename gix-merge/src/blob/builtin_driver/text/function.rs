@@ -1,5 +1,7 @@
 use std::ops::Range;
 
+use gix_diff::blob::whitespace;
+
 use crate::blob::{
     builtin_driver::text::{
         utils::{
@@ -39,14 +41,16 @@ pub fn merge<'a>(
     Options {
         diff_algorithm,
         conflict,
+        whitespace,
     }: Options,
 ) -> Resolution {
     out.clear();
     input.update_before(tokens(ancestor));
     input.update_after(tokens(current));
 
-    let hunks = imara_diff::diff(
+    let hunks = diff(
         diff_algorithm,
+        whitespace,
         input,
         CollectHunks {
             side: Side::Current,
@@ -57,8 +61,9 @@ pub fn merge<'a>(
     let current_tokens = std::mem::take(&mut input.after);
     input.update_after(tokens(other));
 
-    let mut hunks = imara_diff::diff(
+    let mut hunks = diff(
         diff_algorithm,
+        whitespace,
         input,
         CollectHunks {
             side: Side::Other,
@@ -66,12 +71,16 @@ pub fn merge<'a>(
         },
     );
 
+    if whitespace != whitespace::Mode::Exact {
+        take_unchanged_lines_from_current(input, &current_tokens, &hunks);
+    }
+
     if hunks.is_empty() {
         write_ancestor(input, 0, input.before.len(), out);
         return Resolution::Complete;
     }
 
-    hunks.sort_by(|a, b| a.before.start.cmp(&b.before.start));
+    hunks.sort_by_key(|a| a.before.start);
     let mut hunks = hunks.into_iter().peekable();
     let mut intersecting = Vec::new();
     let mut ancestor_integrated_until = 0;
@@ -232,6 +241,48 @@ pub fn merge<'a>(
     resolution
 }
 
+/// Diff `input` with `algorithm`, but ignore changes in `whitespace` if it isn't [exact](whitespace::Mode::Exact).
+fn diff(
+    algorithm: gix_diff::blob::Algorithm,
+    whitespace: whitespace::Mode,
+    input: &imara_diff::intern::InternedInput<&[u8]>,
+    sink: CollectHunks,
+) -> Vec<Hunk> {
+    if whitespace == whitespace::Mode::Exact {
+        return gix_diff::blob::diff(algorithm, input, sink);
+    }
+    let tokens = whitespace.normalize_input(input);
+    gix_diff::blob::diff_with_normalized_tokens(algorithm, input, &tokens, sink)
+}
+
+/// Replace all lines of the ancestor in `input` that weren't changed by the current side according to `hunks`
+/// with the respective lines of `current_tokens`.
+///
+/// That way, whitespace changes of the current side are kept in all places where the other side didn't change
+/// anything, which is what Git does as it takes unchanged lines from *ours*.
+fn take_unchanged_lines_from_current(
+    input: &mut imara_diff::intern::InternedInput<&[u8]>,
+    current_tokens: &[imara_diff::intern::Token],
+    hunks: &[Hunk],
+) {
+    let mut ancestor_pos = 0;
+    let mut current_pos = 0;
+    let current_hunks = hunks.iter().filter(|hunk| hunk.side == Side::Current);
+    for (before, after) in current_hunks
+        .map(|hunk| (hunk.before.clone(), hunk.after.clone()))
+        .chain(Some((
+            input.before.len() as u32..input.before.len() as u32,
+            current_tokens.len() as u32..current_tokens.len() as u32,
+        )))
+    {
+        let unchanged = (before.start - ancestor_pos) as usize;
+        input.before[ancestor_pos as usize..][..unchanged]
+            .copy_from_slice(&current_tokens[current_pos as usize..][..unchanged]);
+        ancestor_pos = before.end;
+        current_pos = after.end;
+    }
+}
+
 fn first_hunk<'a>(front: &'a [Hunk], ours: &'a [Hunk], theirs: &'a [Hunk], back: &'a [Hunk]) -> &'a Hunk {
     front
         .first()
@@ -70,17 +70,24 @@ pub struct Labels<'a> {
 #[derive(Copy, Clone, Debug, Eq, PartialEq)]
 pub struct Options {
     /// Determine of the diff will be performed.
-    /// Defaults to [`imara_diff::Algorithm::Myers`].
-    pub diff_algorithm: imara_diff::Algorithm,
+    /// Defaults to [`gix_diff::blob::Algorithm::Myers`].
+    pub diff_algorithm: gix_diff::blob::Algorithm,
     /// Decide what to do to automatically resolve conflicts, or to keep them.
     pub conflict: Conflict,
+    /// Determine which whitespace to ignore when comparing lines, like `-X ignore-space-change` and friends.
+    ///
+    /// If a side only changed whitespace in lines that the other side didn't change, our version of the lines is used.
+    /// If a side changed lines in a way that isn't only whitespace, that change is used, even if the other side
+    /// changed whitespace in the same lines.
+    pub whitespace: gix_diff::blob::whitespace::Mode,
 }
 
 impl Default for Options {
     fn default() -> Self {
         Options {
             conflict: Default::default(),
-            diff_algorithm: imara_diff::Algorithm::Myers,
+            diff_algorithm: gix_diff::blob::Algorithm::Myers,
+            whitespace: Default::default(),
         }
     }
 }
@@ -30,6 +30,8 @@ pub enum Error {
     },
     #[error("IO failed when dealing with merge-driver output")]
     ExternalDriverIO(#[from] std::io::Error),
+    #[error("Could not replay a recorded conflict resolution")]
+    Rerere(#[from] crate::rerere::Error),
 }
 
 /// The product of a [`PlatformRef::prepare_external_driver()`] operation.
@@ -405,6 +407,9 @@ impl<'parent> PlatformRef<'parent> {
     ///
     /// Generally, it is assumed that standard logic, like deletions of files, is handled before any of this is called, so we are lenient
     /// in terms of buffer handling to make it more useful in the face of missing local files.
+    ///
+    /// If the [rerere store](super::Options::rerere) is set, conflicts left by builtin drivers are resolved with recorded resolutions
+    /// if possible, unless this is a merge for a virtual ancestor.
     pub fn merge(
         &self,
         out: &mut Vec<u8>,
@@ -431,7 +436,25 @@ impl<'parent> PlatformRef<'parent> {
             Err(builtin) => {
                 let mut input = imara_diff::intern::InternedInput::new(&[][..], &[]);
                 out.clear();
-                let (pick, resolution) = self.builtin_merge(builtin, out, &mut input, labels);
+                let (pick, mut resolution) = self.builtin_merge(builtin, out, &mut input, labels);
+                if let Some((store, marker_size)) = self
+                    .parent
+                    .options
+                    .rerere
+                    .as_ref()
+                    .zip(self.options.text.conflict.marker_size())
+                    .filter(|_| {
+                        resolution == Resolution::Conflict
+                            && pick == inner::builtin_merge::Pick::Buffer
+                            && !self.options.is_virtual_ancestor
+                    })
+                {
+                    let mut resolved = Vec::new();
+                    if store.replay(out, marker_size, &mut resolved)?.is_some() {
+                        *out = resolved;
+                        resolution = Resolution::CompleteWithAutoResolvedConflict;
+                    }
+                }
                 Ok((pick, resolution))
             }
         }
@@ -39,6 +39,11 @@ pub struct Options {
     ///
     /// This is the value of the `merge.default` git configuration.
     pub default_driver: Option<BString>,
+    /// If set, conflicts left by the builtin text driver are resolved with resolutions recorded in this store,
+    /// if there are any, in which case the merge is considered [auto-resolved](crate::blob::Resolution::CompleteWithAutoResolvedConflict).
+    ///
+    /// This is typically set if `rerere.enabled` is configured in Git.
+    pub rerere: Option<crate::rerere::Store>,
 }
 
 /// The selection of the driver to use by a resource obtained with [`Platform::prepare_merge()`].
@@ -112,6 +117,22 @@ impl Platform {
     pub fn drivers(&self) -> &[super::Driver] {
         &self.drivers
     }
+
+    /// Return the mode that decides how resources are converted to make them mergeable.
+    pub fn filter_mode(&self) -> pipeline::Mode {
+        self.filter_mode
+    }
+}
+
+/// Mutation
+impl Platform {
+    /// Change the way resources are converted to make them mergeable to `mode`, for instance to
+    /// [renormalize](pipeline::Mode::Renormalize) them like `-X renormalize` would.
+    ///
+    /// Note that this only affects resources that are [set](Self::set_resource()) after this call.
+    pub fn set_filter_mode(&mut self, mode: pipeline::Mode) {
+        self.filter_mode = mode;
+    }
 }
 
 ///
@@ -4,7 +4,7 @@ use gix_object::FindExt;
 
 use crate::{
     blob::builtin_driver,
-    commit::{Error, Options},
+    commit::{Error, Options, Strategy},
 };
 
 /// Like [`tree()`](crate::tree()), but it takes only two commits, `our_commit` and `their_commit` to automatically
@@ -54,41 +54,26 @@ pub fn commit<'objects>(
     options: Options,
 ) -> Result<super::Outcome<'objects>, Error> {
     let merge_bases = gix_revision::merge_base(our_commit, &[their_commit], graph)?;
-    let mut virtual_merge_bases = Vec::new();
     let mut state = gix_diff::tree::State::default();
-    let mut commit_to_tree =
-        |commit_id: gix_hash::ObjectId| objects.find_commit(&commit_id, &mut state.buf1).map(|c| c.tree());
 
-    let (merge_base_tree_id, ancestor_name): (_, Cow<'_, str>) = match merge_bases.clone() {
-        Some(base_commit) if base_commit.len() == 1 => {
-            (commit_to_tree(base_commit[0])?, abbreviate_hash(&base_commit[0]).into())
-        }
-        Some(mut base_commits) => {
-            let virtual_base_tree = if options.use_first_merge_base {
-                let first = base_commits.first().expect("if Some() there is at least one.");
-                commit_to_tree(*first)?
-            } else {
-                let first = base_commits.pop().expect("at least two");
-                let second = base_commits.pop().expect("at least one left");
-                let out = crate::commit::virtual_merge_base(
-                    first,
-                    second,
-                    base_commits,
-                    graph,
-                    diff_resource_cache,
-                    blob_merge,
-                    objects,
-                    abbreviate_hash,
-                    options.tree_merge.clone(),
-                )?;
-                virtual_merge_bases = out.virtual_merge_bases;
-                out.tree_id
-            };
-            (virtual_base_tree, "merged common ancestors".into())
-        }
+    let (mut merge_base_tree_id, ancestor_name, virtual_merge_bases) = match merge_bases.as_deref() {
+        Some(base_commits) => merge_base_tree(
+            base_commits,
+            options.use_first_merge_base || options.strategy == Strategy::Ours,
+            graph,
+            diff_resource_cache,
+            blob_merge,
+            objects,
+            abbreviate_hash,
+            &options.tree_merge,
+        )?,
         None => {
             if options.allow_missing_merge_base {
-                (gix_hash::ObjectId::empty_tree(our_commit.kind()), "empty tree".into())
+                (
+                    gix_hash::ObjectId::empty_tree(our_commit.kind()),
+                    "empty tree".into(),
+                    Vec::new(),
+                )
             } else {
                 return Err(Error::NoMergeBase {
                     our_commit_id: our_commit,
@@ -104,7 +89,26 @@ pub fn commit<'objects>(
     }
 
     let our_tree_id = objects.find_commit(&our_commit, &mut state.buf1)?.tree();
-    let their_tree_id = objects.find_commit(&their_commit, &mut state.buf1)?.tree();
+    let mut their_tree_id = objects.find_commit(&their_commit, &mut state.buf1)?.tree();
+
+    if options.strategy == Strategy::Ours {
+        let our_tree = objects.find_tree(&our_tree_id, &mut state.buf1)?.to_owned();
+        return Ok(super::Outcome {
+            tree_merge: crate::tree::Outcome {
+                tree: gix_object::tree::Editor::new(our_tree, objects, our_tree_id.kind()),
+                conflicts: Vec::new(),
+                failed_on_first_unresolved_conflict: false,
+            },
+            merge_bases,
+            merge_base_tree_id,
+            virtual_merge_bases,
+        });
+    }
+
+    if let Some(how) = &options.subtree_shift {
+        their_tree_id = crate::tree::shift(&our_tree_id, &their_tree_id, how, objects)?;
+        merge_base_tree_id = crate::tree::shift(&our_tree_id, &merge_base_tree_id, how, objects)?;
+    }
 
     let outcome = crate::tree(
         &merge_base_tree_id,
@@ -126,3 +130,51 @@ pub fn commit<'objects>(
         virtual_merge_bases,
     })
 }
+
+/// Return the tree to use as merge-base for all `merge_bases`, along with a label for it and the virtual merge-bases
+/// that had to be created to obtain it.
+///
+/// If there are multiple merge-bases, they are merged into one recursively unless `use_first_merge_base` is `true`.
+/// `graph`, `diff_resource_cache`, `blob_merge`, `objects`, `abbreviate_hash` and `tree_merge` are used for
+/// [creating a virtual merge-base](crate::commit::virtual_merge_base()).
+#[allow(clippy::too_many_arguments)]
+pub(super) fn merge_base_tree(
+    merge_bases: &[gix_hash::ObjectId],
+    use_first_merge_base: bool,
+    graph: &mut gix_revwalk::Graph<'_, '_, gix_revwalk::graph::Commit<gix_revision::merge_base::Flags>>,
+    diff_resource_cache: &mut gix_diff::blob::Platform,
+    blob_merge: &mut crate::blob::Platform,
+    objects: &(impl gix_object::FindObjectOrHeader + gix_object::Write),
+    abbreviate_hash: &mut dyn FnMut(&gix_hash::oid) -> String,
+    tree_merge: &crate::tree::Options,
+) -> Result<(gix_hash::ObjectId, Cow<'static, str>, Vec<gix_hash::ObjectId>), Error> {
+    let mut buf = Vec::new();
+    let mut commit_to_tree =
+        |commit_id: &gix_hash::ObjectId| objects.find_commit(commit_id, &mut buf).map(|c| c.tree());
+    Ok(match merge_bases {
+        [] => unreachable!("BUG: merge-bases are never empty if they are present at all"),
+        [base_commit] => (
+            commit_to_tree(base_commit)?,
+            abbreviate_hash(base_commit).into(),
+            Vec::new(),
+        ),
+        [first, ..] if use_first_merge_base => (commit_to_tree(first)?, "merged common ancestors".into(), Vec::new()),
+        base_commits => {
+            let mut base_commits = base_commits.to_vec();
+            let first = base_commits.pop().expect("at least two");
+            let second = base_commits.pop().expect("at least one left");
+            let out = crate::commit::virtual_merge_base(
+                first,
+                second,
+                base_commits,
+                graph,
+                diff_resource_cache,
+                blob_merge,
+                objects,
+                abbreviate_hash,
+                tree_merge.clone(),
+            )?;
+            (out.tree_id, "merged common ancestors".into(), out.virtual_merge_bases)
+        }
+    })
+}
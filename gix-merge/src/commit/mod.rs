@@ -17,6 +17,8 @@ pub enum Error {
     },
     #[error("Could not find ancestor, our or their commit to extract tree from")]
     FindCommit(#[from] gix_object::find::existing_object::Error),
+    #[error("Could not shift their tree or the merge-base tree to line up with our tree")]
+    SubtreeShift(#[from] crate::tree::shift::Error),
 }
 
 /// A way to configure [`commit()`](crate::commit()).
@@ -30,6 +32,25 @@ pub struct Options {
     // TODO: test
     #[doc(alias = "no_recursive", alias = "git2")]
     pub use_first_merge_base: bool,
+    /// Determine how the trees of both commits are combined.
+    pub strategy: Strategy,
+    /// If `Some(…)`, shift *their* tree and the merge-base tree so that they line up with *our* tree before merging,
+    /// which is useful if one side is a subdirectory of the other side, like `git merge -s subtree` or
+    /// `git merge -X subtree=<path>`.
+    pub subtree_shift: Option<crate::tree::shift::Subtree>,
+}
+
+/// The way the trees of the commits to merge are combined, similar to `git merge --strategy`.
+#[derive(Default, Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Strategy {
+    /// Merge *their* changes with *our* changes using a three-way [tree merge](crate::tree()).
+    #[default]
+    Merge,
+    /// Keep *our* tree as is and discard all of *their* changes, like `git merge -s ours`.
+    ///
+    /// Note that this is different from [resolving conflicts with ours](crate::tree::ResolveWith::Ours),
+    /// as non-conflicting changes of *theirs* are discarded as well.
+    Ours,
 }
 
 /// The result of [`commit()`](crate::commit()).
@@ -55,6 +76,10 @@ pub struct Outcome<'a> {
 
 pub(super) mod function;
 
+///
+pub mod octopus;
+pub use octopus::function::octopus;
+
 ///
 pub mod virtual_merge_base;
 pub use virtual_merge_base::function::virtual_merge_base;
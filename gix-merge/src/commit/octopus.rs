@@ -0,0 +1,174 @@
+use crate::tree::TreatAsUnresolved;
+
+/// The error returned by [`commit::octopus()`](crate::commit::octopus()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Failed to obtain the merge base between the commits to be merged")]
+    MergeBase(#[from] gix_revision::merge_base::Error),
+    #[error("No common ancestor between {our_commit_id} and all of the commits to merge")]
+    NoMergeBase {
+        /// The commit on our side that was to be merged.
+        our_commit_id: gix_hash::ObjectId,
+    },
+    #[error(transparent)]
+    MergeBaseTree(#[from] crate::commit::Error),
+    #[error(transparent)]
+    MergeTree(#[from] crate::tree::Error),
+    #[error("Merging {their_commit_id} caused conflicts, which an octopus merge doesn't allow")]
+    Conflict {
+        /// The commit that couldn't be merged without conflicts.
+        their_commit_id: gix_hash::ObjectId,
+    },
+    #[error("Failed to write the tree of an intermediate merge")]
+    WriteTree(#[from] gix_object::write::Error),
+    #[error("Could not find a commit to extract its tree from")]
+    FindCommit(#[from] gix_object::find::existing_object::Error),
+}
+
+/// A way to configure [`commit::octopus()`](crate::commit::octopus()).
+#[derive(Default, Debug, Clone)]
+pub struct Options {
+    /// If `true`, merging commits that don't share a common ancestor is allowed, with the merge-base being assumed
+    /// as empty tree.
+    pub allow_missing_merge_base: bool,
+    /// Options to define how trees should be merged.
+    pub tree_merge: crate::tree::Options,
+    /// Determine which conflicts are considered unresolved, which makes the octopus merge fail.
+    pub treat_as_unresolved: TreatAsUnresolved,
+}
+
+/// The result of [`commit::octopus()`](crate::commit::octopus()).
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The id of the tree that contains the changes of all merged commits, as written to the object database.
+    pub tree_id: gix_hash::ObjectId,
+    /// The commits to use as parents of the merge commit, in order.
+    ///
+    /// It starts with *our* commit unless it could be fast-forwarded, and it doesn't contain commits that were
+    /// already contained in the merge result.
+    /// If there is just one parent, no merge commit is needed as the merge result is this very commit.
+    pub parents: Vec<gix_hash::ObjectId>,
+    /// All conflicts that were encountered and resolved automatically while merging one commit after another.
+    pub conflicts: Vec<crate::tree::Conflict>,
+}
+
+pub(super) mod function {
+    use gix_object::FindExt;
+
+    use super::{Error, Options, Outcome};
+    use crate::{blob::builtin_driver, commit::function::merge_base_tree};
+
+    /// Merge all `their_commits` into `our_commit` one after another, to produce a tree suitable for a merge commit
+    /// with many parents, like `git merge -s octopus` does.
+    ///
+    /// Commits that are already contained in the merge result are skipped, and as long as no actual merge was needed,
+    /// commits are fast-forwarded to.
+    /// If there is no common ancestor among all commits, the merge fails unless [`allow_missing_merge_base`](Options::allow_missing_merge_base)
+    /// is `true`.
+    /// As in Git, merging any commit must not cause unresolved conflicts, as determined by
+    /// [`treat_as_unresolved`](Options::treat_as_unresolved), otherwise the merge fails.
+    ///
+    /// `labels` are used for text-merges, with [`current`](builtin_driver::text::Labels::current) being a name for
+    /// `our_commit`. If unset, the ancestor label is determined by the merge-bases, and the other label is the
+    /// shortened id of the commit that is merged.
+    ///
+    /// The `graph` is used to find the merge-bases, and `diff_resource_cache`, `blob_merge`, `objects` and `abbreviate_hash`
+    /// are used like in [`commit()`](crate::commit()).
+    /// Trees of intermediate merge results are written to `objects`.
+    ///
+    /// ### Performance
+    ///
+    /// Note that `objects` *should* have an object cache to greatly accelerate tree-retrieval.
+    #[allow(clippy::too_many_arguments)]
+    pub fn octopus(
+        our_commit: gix_hash::ObjectId,
+        their_commits: &[gix_hash::ObjectId],
+        labels: builtin_driver::text::Labels<'_>,
+        graph: &mut gix_revwalk::Graph<'_, '_, gix_revwalk::graph::Commit<gix_revision::merge_base::Flags>>,
+        diff_resource_cache: &mut gix_diff::blob::Platform,
+        blob_merge: &mut crate::blob::Platform,
+        objects: &(impl gix_object::FindObjectOrHeader + gix_object::Write),
+        abbreviate_hash: &mut dyn FnMut(&gix_hash::oid) -> String,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        let mut state = gix_diff::tree::State::default();
+        if gix_revision::merge_base::octopus(our_commit, their_commits, graph)?.is_none()
+            && !options.allow_missing_merge_base
+        {
+            return Err(Error::NoMergeBase {
+                our_commit_id: our_commit,
+            });
+        }
+
+        let mut tree_merge_options = options.tree_merge;
+        tree_merge_options.fail_on_conflict = Some(options.treat_as_unresolved);
+        let mut parents = vec![our_commit];
+        let mut tree_id = objects.find_commit(&our_commit, &mut state.buf1)?.tree();
+        let mut conflicts = Vec::new();
+        let mut needs_merge_commit = false;
+        for their_commit in their_commits.iter().copied() {
+            let merge_bases = gix_revision::merge_base(their_commit, &parents, graph)?;
+            if merge_bases.as_ref().is_some_and(|bases| bases.contains(&their_commit)) {
+                continue;
+            }
+            let their_tree_id = objects.find_commit(&their_commit, &mut state.buf1)?.tree();
+            if !needs_merge_commit && merge_bases.as_deref() == Some(parents.as_slice()) {
+                parents = vec![their_commit];
+                tree_id = their_tree_id;
+                continue;
+            }
+            needs_merge_commit = true;
+
+            let (merge_base_tree_id, ancestor_name, _virtual_merge_bases) = match merge_bases.as_deref() {
+                Some(base_commits) => merge_base_tree(
+                    base_commits,
+                    false,
+                    graph,
+                    diff_resource_cache,
+                    blob_merge,
+                    objects,
+                    abbreviate_hash,
+                    &tree_merge_options,
+                )?,
+                None => (
+                    gix_hash::ObjectId::empty_tree(our_commit.kind()),
+                    "empty tree".into(),
+                    Vec::new(),
+                ),
+            };
+            let their_name = abbreviate_hash(&their_commit);
+            let labels = builtin_driver::text::Labels {
+                ancestor: labels.ancestor.or(Some(ancestor_name.as_ref().into())),
+                current: labels.current,
+                other: labels.other.or(Some(their_name.as_str().into())),
+            };
+            let mut outcome = crate::tree(
+                &merge_base_tree_id,
+                &tree_id,
+                &their_tree_id,
+                labels,
+                objects,
+                |buf| objects.write_buf(gix_object::Kind::Blob, buf),
+                &mut state,
+                diff_resource_cache,
+                blob_merge,
+                tree_merge_options.clone(),
+            )?;
+            if outcome.has_unresolved_conflicts(options.treat_as_unresolved) {
+                return Err(Error::Conflict {
+                    their_commit_id: their_commit,
+                });
+            }
+            tree_id = outcome.tree.write(|tree| objects.write(tree))?;
+            conflicts.extend(outcome.conflicts);
+            parents.push(their_commit);
+        }
+
+        Ok(Outcome {
+            tree_id,
+            parents,
+            conflicts,
+        })
+    }
+}
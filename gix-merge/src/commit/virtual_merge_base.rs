@@ -88,6 +88,8 @@ pub(super) mod function {
                     allow_missing_merge_base: false,
                     tree_merge: options.clone(),
                     use_first_merge_base: false,
+                    strategy: Default::default(),
+                    subtree_shift: None,
                 },
             )?;
             // This shouldn't happen, but if for some buggy reason it does, we rather bail.
@@ -3,6 +3,7 @@
 //! * [blob-merges](blob) look at file content.
 //! * [tree-merges](mod@tree) look at trees and merge them structurally, triggering blob-merges as needed.
 //! * [commit-merges](mod@commit) are like tree merges, but compute or create the merge-base on the fly.
+//! * [rerere](mod@rerere) remembers how conflicts were resolved to resolve them the same way when they are seen again.
 #![deny(rust_2018_idioms)]
 #![deny(missing_docs)]
 #![forbid(unsafe_code)]
@@ -13,5 +14,7 @@ pub mod blob;
 pub mod commit;
 pub use commit::function::commit;
 ///
+pub mod rerere;
+///
 pub mod tree;
 pub use tree::function::tree;
@@ -0,0 +1,46 @@
+//! Read and write the `MERGE_RR` file, which associates paths with conflicts in the [`Store`](super::Store)
+//! while a merge is in progress.
+use bstr::{BString, ByteSlice};
+
+use crate::rerere::Id;
+
+/// An entry of the `MERGE_RR` file.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Entry {
+    /// The conflicts that the file at `path` had when the merge was performed.
+    pub id: Id,
+    /// The worktree-relative path to the conflicted file.
+    pub path: BString,
+}
+
+/// The error returned by [`decode()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not parse MERGE_RR record {record:?}")]
+    InvalidRecord { record: BString },
+}
+
+/// Decode the `MERGE_RR` file in `data`, which consists of records of `<id>\t<path>\0`.
+pub fn decode(data: &[u8]) -> Result<Vec<Entry>, Error> {
+    data.split(|b| *b == 0)
+        .filter(|record| !record.is_empty())
+        .map(|record| {
+            let (id, path) = record
+                .split_once_str(b"\t")
+                .and_then(|(id, path)| Some((Id::from_name(id.as_bstr())?, path)))
+                .ok_or_else(|| Error::InvalidRecord { record: record.into() })?;
+            Ok(Entry { id, path: path.into() })
+        })
+        .collect()
+}
+
+/// Encode `entries` into `out` in the format of the `MERGE_RR` file.
+pub fn encode<'a>(entries: impl IntoIterator<Item = &'a Entry>, out: &mut Vec<u8>) {
+    for entry in entries {
+        out.extend_from_slice(entry.id.to_string().as_bytes());
+        out.push(b'\t');
+        out.extend_from_slice(&entry.path);
+        out.push(0);
+    }
+}
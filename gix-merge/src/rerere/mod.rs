@@ -0,0 +1,87 @@
+//! Reuse recorded resolutions of conflicted merges, similar to `git rerere`.
+//!
+//! Whenever a text-merge leaves conflicts, the conflicted file can be [normalized](normalize()) to obtain
+//! an identifier for the set of conflicts it contains, which doesn't depend on labels, the side conflicting hunks
+//! are on or the ancestor section of `diff3` style conflicts.
+//! The normalized file, the *preimage*, is recorded in the [`Store`], and once the conflicts are resolved by the user,
+//! the resolved file, the *postimage*, is recorded as well.
+//! When the same conflicts are seen again, the recorded resolution can be [replayed](Store::replay()).
+//!
+//! The on-disk format is compatible with the one of Git, and Git's `rr-cache` directory can be used directly.
+use std::path::PathBuf;
+
+use gix_hash::ObjectId;
+
+mod normalize;
+pub use normalize::normalize;
+
+///
+pub mod merge_rr;
+
+mod store;
+
+/// A store of recorded conflict resolutions, typically at `.git/rr-cache`.
+///
+/// Each set of conflicts is stored in a directory named by its normalized [conflict hash](normalize()),
+/// and each directory can hold multiple *variants* if different conflicts happen to have the same hash,
+/// which may happen if the surrounding content differs.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Store {
+    /// The directory that contains all recorded resolutions.
+    path: PathBuf,
+    /// The kind of hash used to identify conflicts.
+    object_hash: gix_hash::Kind,
+}
+
+/// The identifier of a recorded conflict within a [`Store`].
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Ord, PartialOrd)]
+pub struct Id {
+    /// The hash of the normalized conflicts.
+    pub hash: ObjectId,
+    /// The variant among all recorded conflicts with the same `hash`, with `0` being the first one.
+    pub variant: usize,
+}
+
+/// The error returned by [`Store`] operations.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not access '{}' in the rerere store", path.display())]
+    Io { source: std::io::Error, path: PathBuf },
+    #[error(transparent)]
+    Hash(#[from] gix_hash::hasher::Error),
+}
+
+mod id {
+    use bstr::{BStr, ByteSlice};
+    use gix_hash::ObjectId;
+
+    use crate::rerere::Id;
+
+    impl Id {
+        /// Parse `name` as it is used in `MERGE_RR`, which is a hex hash optionally followed by `.<variant>`.
+        pub fn from_name(name: &BStr) -> Option<Self> {
+            let (hash, variant) = match name.find_byte(b'.') {
+                Some(pos) => {
+                    let variant = name[pos + 1..].to_str().ok()?.parse().ok()?;
+                    (&name[..pos], variant)
+                }
+                None => (name, 0),
+            };
+            Some(Id {
+                hash: ObjectId::from_hex(hash).ok()?,
+                variant,
+            })
+        }
+    }
+
+    impl std::fmt::Display for Id {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            if self.variant == 0 {
+                write!(f, "{}", self.hash)
+            } else {
+                write!(f, "{}.{}", self.hash, self.variant)
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use gix_hash::ObjectId;
+
+/// Normalize the conflicts in `data`, marked with conflict markers of `marker_size` characters, and write the
+/// normalized file into `out` (cleared before use).
+/// Return the hash of kind `object_hash` that identifies the conflicts, or `None` if there were no conflicts,
+/// or if they couldn't be parsed.
+///
+/// Normalization removes the labels of all conflict markers and the ancestor section of `diff3` style conflicts,
+/// and orders the sides of each conflict so that the smaller one comes first.
+/// The hash is computed over the sides of all top-level conflicts, and is the same as the one Git computes.
+pub fn normalize(
+    data: &[u8],
+    marker_size: u8,
+    object_hash: gix_hash::Kind,
+    out: &mut Vec<u8>,
+) -> Result<Option<ObjectId>, gix_hash::hasher::Error> {
+    out.clear();
+    let marker_size = usize::from(marker_size);
+    let mut hasher = gix_hash::hasher(object_hash);
+    let mut lines = data.split_inclusive(|b| *b == b'\n');
+    let mut has_conflicts = false;
+    while let Some(line) = lines.next() {
+        if is_marker(line, b'<', marker_size) {
+            if !handle_conflict(&mut lines, marker_size, out, Some(&mut hasher)) {
+                return Ok(None);
+            }
+            has_conflicts = true;
+        } else {
+            out.extend_from_slice(line);
+        }
+    }
+    has_conflicts.then(|| hasher.try_finalize()).transpose()
+}
+
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Hunk {
+    Ours,
+    Ancestor,
+    Theirs,
+}
+
+/// Read the conflict following its opening marker from `lines` and write it to `out` in normalized form,
+/// returning `false` if it ended prematurely or if markers were out of order.
+/// Nested conflicts are normalized as well, but only top-level conflicts are hashed.
+fn handle_conflict<'a>(
+    lines: &mut impl Iterator<Item = &'a [u8]>,
+    marker_size: usize,
+    out: &mut Vec<u8>,
+    hasher: Option<&mut gix_hash::Hasher>,
+) -> bool {
+    let (mut one, mut two) = (Vec::new(), Vec::new());
+    let mut hunk = Hunk::Ours;
+    while let Some(line) = lines.next() {
+        if is_marker(line, b'<', marker_size) {
+            let mut nested = Vec::new();
+            if !handle_conflict(lines, marker_size, &mut nested, None) {
+                return false;
+            }
+            if hunk == Hunk::Ours {
+                one.extend_from_slice(&nested);
+            } else {
+                two.extend_from_slice(&nested);
+            }
+        } else if is_marker(line, b'|', marker_size) {
+            if hunk != Hunk::Ours {
+                return false;
+            }
+            hunk = Hunk::Ancestor;
+        } else if is_marker(line, b'=', marker_size) {
+            if hunk == Hunk::Theirs {
+                return false;
+            }
+            hunk = Hunk::Theirs;
+        } else if is_marker(line, b'>', marker_size) {
+            if hunk != Hunk::Theirs {
+                return false;
+            }
+            if one > two {
+                std::mem::swap(&mut one, &mut two);
+            }
+            push_marker(out, b'<', marker_size);
+            out.extend_from_slice(&one);
+            push_marker(out, b'=', marker_size);
+            out.extend_from_slice(&two);
+            push_marker(out, b'>', marker_size);
+            if let Some(hasher) = hasher {
+                for side in [one, two] {
+                    hasher.update(&side);
+                    hasher.update(&[0]);
+                }
+            }
+            return true;
+        } else {
+            match hunk {
+                Hunk::Ours => one.extend_from_slice(line),
+                Hunk::Ancestor => {}
+                Hunk::Theirs => two.extend_from_slice(line),
+            }
+        }
+    }
+    false
+}
+
+/// Return `true` if `line` starts with `marker_size` times `marker`, followed by whitespace,
+/// which must be a space for the opening and closing markers as these always carry a label.
+fn is_marker(line: &[u8], marker: u8, marker_size: usize) -> bool {
+    line.len() > marker_size
+        && line[..marker_size].iter().all(|b| *b == marker)
+        && if marker == b'<' || marker == b'>' {
+            line[marker_size] == b' '
+        } else {
+            line[marker_size].is_ascii_whitespace()
+        }
+}
+
+fn push_marker(out: &mut Vec<u8>, marker: u8, marker_size: usize) {
+    out.extend(std::iter::repeat_n(marker, marker_size));
+    out.push(b'\n');
+}
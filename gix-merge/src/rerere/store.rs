@@ -0,0 +1,290 @@
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use gix_hash::ObjectId;
+
+use crate::{
+    blob::{builtin_driver, Resolution},
+    rerere::{normalize, Error, Id, Store},
+};
+
+const PREIMAGE: &str = "preimage";
+const POSTIMAGE: &str = "postimage";
+const THISIMAGE: &str = "thisimage";
+
+/// Lifecycle
+impl Store {
+    /// Open the store at `path`, typically `.git/rr-cache`, identifying conflicts with hashes of kind `object_hash`.
+    ///
+    /// The directory doesn't have to exist, it's created once the first conflict is recorded.
+    pub fn at(path: impl Into<PathBuf>, object_hash: gix_hash::Kind) -> Self {
+        Store {
+            path: path.into(),
+            object_hash,
+        }
+    }
+}
+
+/// Access
+impl Store {
+    /// The directory containing all recorded resolutions.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The kind of hash used to identify conflicts.
+    pub fn object_hash(&self) -> gix_hash::Kind {
+        self.object_hash
+    }
+
+    /// Return the variants recorded for conflicts with `hash`, in ascending order.
+    pub fn variants(&self, hash: &gix_hash::oid) -> Result<Vec<usize>, Error> {
+        let dir = self.path.join(hash.to_hex().to_string());
+        let entries = match std::fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(source) => return Err(Error::Io { source, path: dir }),
+        };
+        let mut out = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(|source| Error::Io {
+                source,
+                path: dir.clone(),
+            })?;
+            let name = entry.file_name();
+            let Some(name) = name.to_str() else { continue };
+            let variant = [PREIMAGE, POSTIMAGE].into_iter().find_map(|image| {
+                let suffix = name.strip_prefix(image)?;
+                match suffix.strip_prefix('.') {
+                    None if suffix.is_empty() => Some(0),
+                    None => None,
+                    Some(variant) => variant.parse().ok().filter(|variant| *variant != 0),
+                }
+            });
+            out.extend(variant);
+        }
+        out.sort_unstable();
+        out.dedup();
+        Ok(out)
+    }
+
+    /// Return `true` if a resolution was recorded for the conflicts identified by `id`.
+    pub fn has_resolution(&self, id: &Id) -> bool {
+        self.image_path(id, POSTIMAGE).is_file()
+    }
+
+    /// Return the path of the *preimage* of `id`, the normalized file with the conflicts it identifies.
+    pub fn preimage_path(&self, id: &Id) -> PathBuf {
+        self.image_path(id, PREIMAGE)
+    }
+
+    /// Return the path of the *postimage* of `id`, the file after its conflicts were resolved.
+    pub fn postimage_path(&self, id: &Id) -> PathBuf {
+        self.image_path(id, POSTIMAGE)
+    }
+
+    fn image_path(&self, id: &Id, image: &str) -> PathBuf {
+        let mut path = self.path.join(id.hash.to_hex().to_string());
+        if id.variant == 0 {
+            path.push(image);
+        } else {
+            path.push(format!("{image}.{}", id.variant));
+        }
+        path
+    }
+}
+
+/// Operations
+impl Store {
+    /// Try to resolve the conflicts in `data`, marked with conflict markers of `marker_size` characters, with
+    /// a previously recorded resolution and write the resolved file into `out`.
+    /// Return the identifier of the resolution that was used, or `None` if there was no applicable resolution
+    /// and `out` is meaningless.
+    ///
+    /// A resolution applies if merging the changes between its *preimage* and *postimage* into the normalized
+    /// `data` is possible without conflicts, so changes outside of conflicts are retained.
+    /// The *postimage* of the resolution is touched to note that it was used.
+    pub fn replay(&self, data: &[u8], marker_size: u8, out: &mut Vec<u8>) -> Result<Option<Id>, Error> {
+        let mut current = Vec::new();
+        let Some(hash) = normalize(data, marker_size, self.object_hash, &mut current)? else {
+            return Ok(None);
+        };
+        let Some(id) = self.find_resolution(hash, &current, out)? else {
+            return Ok(None);
+        };
+        let path = self.postimage_path(&id);
+        std::fs::File::options()
+            .append(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(SystemTime::now()))
+            .map_err(|source| Error::Io { source, path })?;
+        Ok(Some(id))
+    }
+
+    /// Record the conflicts in `data`, marked with conflict markers of `marker_size` characters, as a new variant
+    /// so that their resolution can be [recorded](Self::record_resolution()) later.
+    /// Return the identifier of the new variant, or `None` if `data` has no conflicts.
+    pub fn record_conflict(&self, data: &[u8], marker_size: u8) -> Result<Option<Id>, Error> {
+        let mut preimage = Vec::new();
+        let Some(hash) = normalize(data, marker_size, self.object_hash, &mut preimage)? else {
+            return Ok(None);
+        };
+        let variants = self.variants(&hash)?;
+        let variant = variants
+            .iter()
+            .enumerate()
+            .find_map(|(idx, variant)| (idx != *variant).then_some(idx))
+            .unwrap_or(variants.len());
+        let id = Id { hash, variant };
+        self.write_image(&id, PREIMAGE, &preimage)?;
+        Ok(Some(id))
+    }
+
+    /// Record `resolved` as resolution of the conflicts identified by `id`, for it to be [replayed](Self::replay())
+    /// when the same conflicts are seen again.
+    pub fn record_resolution(&self, id: &Id, resolved: &[u8]) -> Result<(), Error> {
+        self.write_image(id, POSTIMAGE, resolved)
+    }
+
+    /// Forget the resolution that would be used to resolve the conflicts in `data`, marked with conflict markers
+    /// of `marker_size` characters, and record `data` as its conflict instead for a new resolution to be recorded.
+    /// Return the identifier of the forgotten resolution, or `None` if there was no applicable resolution.
+    pub fn forget(&self, data: &[u8], marker_size: u8) -> Result<Option<Id>, Error> {
+        let mut current = Vec::new();
+        let Some(hash) = normalize(data, marker_size, self.object_hash, &mut current)? else {
+            return Ok(None);
+        };
+        let Some(id) = self.find_resolution(hash, &current, &mut Vec::new())? else {
+            return Ok(None);
+        };
+        let path = self.postimage_path(&id);
+        std::fs::remove_file(&path).map_err(|source| Error::Io { source, path })?;
+        self.write_image(&id, PREIMAGE, &current)?;
+        Ok(Some(id))
+    }
+
+    /// Remove the recorded conflicts identified by `id` if no resolution was recorded for them, like `git rerere clear`.
+    /// Return `true` if they were removed.
+    pub fn clear(&self, id: &Id) -> Result<bool, Error> {
+        if self.has_resolution(id) {
+            return Ok(false);
+        }
+        self.remove_variant(id)?;
+        Ok(true)
+    }
+
+    /// Remove all recorded resolutions that weren't used since `resolved_cutoff`, and all conflicts without
+    /// resolution that were recorded before `unresolved_cutoff`, like `git rerere gc`.
+    /// A cutoff of `None` keeps the respective entries.
+    /// Return the amount of removed variants.
+    pub fn gc(
+        &self,
+        resolved_cutoff: Option<SystemTime>,
+        unresolved_cutoff: Option<SystemTime>,
+    ) -> Result<usize, Error> {
+        let entries = match std::fs::read_dir(&self.path) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(source) => {
+                return Err(Error::Io {
+                    source,
+                    path: self.path.clone(),
+                })
+            }
+        };
+        let mut removed = 0;
+        for entry in entries {
+            let entry = entry.map_err(|source| Error::Io {
+                source,
+                path: self.path.clone(),
+            })?;
+            let Some(hash) = entry
+                .file_name()
+                .to_str()
+                .and_then(|name| ObjectId::from_hex(name.as_bytes()).ok())
+            else {
+                continue;
+            };
+            for variant in self.variants(&hash)? {
+                let id = Id { hash, variant };
+                let (image, cutoff) = if self.has_resolution(&id) {
+                    (POSTIMAGE, resolved_cutoff)
+                } else {
+                    (PREIMAGE, unresolved_cutoff)
+                };
+                let Some(cutoff) = cutoff else { continue };
+                let path = self.image_path(&id, image);
+                let modified = std::fs::metadata(&path)
+                    .and_then(|md| md.modified())
+                    .map_err(|source| Error::Io { source, path })?;
+                if modified < cutoff {
+                    self.remove_variant(&id)?;
+                    removed += 1;
+                }
+            }
+        }
+        Ok(removed)
+    }
+}
+
+impl Store {
+    /// Find the first variant of `hash` whose resolution applies to the normalized `current` file,
+    /// and write the resolved file into `out`.
+    fn find_resolution(&self, hash: ObjectId, current: &[u8], out: &mut Vec<u8>) -> Result<Option<Id>, Error> {
+        for variant in self.variants(&hash)? {
+            let id = Id { hash, variant };
+            let (Some(preimage), Some(postimage)) = (self.read_image(&id, PREIMAGE)?, self.read_image(&id, POSTIMAGE)?)
+            else {
+                continue;
+            };
+            let mut input = imara_diff::intern::InternedInput::new(&[][..], &[]);
+            let resolution = builtin_driver::text(
+                out,
+                &mut input,
+                Default::default(),
+                current,
+                &preimage,
+                &postimage,
+                Default::default(),
+            );
+            if resolution == Resolution::Complete {
+                return Ok(Some(id));
+            }
+        }
+        Ok(None)
+    }
+
+    fn read_image(&self, id: &Id, image: &str) -> Result<Option<Vec<u8>>, Error> {
+        let path = self.image_path(id, image);
+        match std::fs::read(&path) {
+            Ok(data) => Ok(Some(data)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(source) => Err(Error::Io { source, path }),
+        }
+    }
+
+    fn write_image(&self, id: &Id, image: &str, data: &[u8]) -> Result<(), Error> {
+        let path = self.image_path(id, image);
+        let dir = path.parent().expect("images are in a directory");
+        std::fs::create_dir_all(dir)
+            .and_then(|()| std::fs::write(&path, data))
+            .map_err(|source| Error::Io { source, path })
+    }
+
+    /// Remove all images of `id`, along with its directory if it's empty then.
+    fn remove_variant(&self, id: &Id) -> Result<(), Error> {
+        for image in [PREIMAGE, POSTIMAGE, THISIMAGE] {
+            let path = self.image_path(id, image);
+            match std::fs::remove_file(&path) {
+                Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                    return Err(Error::Io { source: err, path });
+                }
+                _ => {}
+            }
+        }
+        std::fs::remove_dir(self.path.join(id.hash.to_hex().to_string())).ok();
+        Ok(())
+    }
+}
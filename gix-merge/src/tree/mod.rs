@@ -456,6 +456,11 @@ pub enum ResolveWith {
 
 pub(super) mod function;
 mod utils;
+
+///
+pub mod shift;
+pub use shift::function::shift;
+
 ///
 pub mod apply_index_entries {
 
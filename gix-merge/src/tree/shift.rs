@@ -0,0 +1,308 @@
+//! Shift trees so that they line up with another tree whose subdirectory they correspond to, as needed for subtree merges.
+//!
+//! This follows `match-trees.c` in Git.
+use bstr::{BStr, BString, ByteSlice};
+use gix_hash::ObjectId;
+use gix_object::{
+    tree::{EntryKind, EntryMode},
+    FindExt,
+};
+
+/// Determine how a tree is shifted to line up with another tree.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Subtree {
+    /// Find the subdirectory at which one tree matches the other tree best, like `git merge -s subtree` does.
+    Auto,
+    /// Shift by the given slash-separated directory, like `git merge -X subtree=<path>` does.
+    Prefix(BString),
+}
+
+/// The error returned by [`shift()`](crate::tree::shift()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    FindTree(#[from] gix_object::find::existing_object::Error),
+    #[error("Could not find '{path}' in tree {tree_id}")]
+    MissingPath { path: BString, tree_id: ObjectId },
+    #[error("Could not splice tree into another tree")]
+    TreeEdit(#[from] gix_object::tree::editor::Error),
+    #[error("Could not write spliced tree")]
+    WriteTree(#[from] gix_object::write::Error),
+}
+
+pub(super) mod function {
+    use bstr::{BStr, ByteSlice};
+    use gix_hash::ObjectId;
+    use gix_object::{tree::EntryKind, FindExt};
+
+    use super::{entry_at, match_trees, score_trees, Error, Subtree};
+
+    /// Shift `two` so that it lines up with `one` as configured by `how`, and return the id of the shifted tree,
+    /// which is `two` if no shift was needed or possible.
+    ///
+    /// If `two` corresponds to a subdirectory of `one`, it's shifted down by writing a copy of `one`
+    /// with `two` placed at the subdirectory.
+    /// If `one` corresponds to a subdirectory of `two`, it's shifted up by returning the tree of that subdirectory.
+    ///
+    /// For subtree merges, `one` is *our* tree, and both *their* tree and the merge-base tree are shifted to line up with it.
+    /// Trees are written to `objects` only when shifting down.
+    pub fn shift(
+        one: &gix_hash::oid,
+        two: &gix_hash::oid,
+        how: &Subtree,
+        objects: &(impl gix_object::FindObjectOrHeader + gix_object::Write),
+    ) -> Result<ObjectId, Error> {
+        match how {
+            Subtree::Auto => shift_tree(one, two, objects),
+            Subtree::Prefix(prefix) => shift_tree_by(one, two, prefix.as_bstr(), objects),
+        }
+    }
+
+    /// This follows `shift_tree()` in Git.
+    fn shift_tree(
+        one: &gix_hash::oid,
+        two: &gix_hash::oid,
+        objects: &(impl gix_object::FindObjectOrHeader + gix_object::Write),
+    ) -> Result<ObjectId, Error> {
+        // Limit the depth to avoid exponential runtime, just like Git does it.
+        const DEPTH_LIMIT: usize = 2;
+        let mut add_score = score_trees(one, two, objects)?;
+        let mut del_score = add_score;
+        let (mut add_prefix, mut del_prefix) = (Default::default(), Default::default());
+        // Does a subtree of `one` resemble `two`? Then `two` needs to be prefixed with its path.
+        match_trees(
+            one,
+            two,
+            &mut add_score,
+            &mut add_prefix,
+            "".into(),
+            DEPTH_LIMIT,
+            objects,
+        )?;
+        // Does a subtree of `two` resemble `one`? Then only this subtree of `two` should be used.
+        match_trees(
+            two,
+            one,
+            &mut del_score,
+            &mut del_prefix,
+            "".into(),
+            DEPTH_LIMIT,
+            objects,
+        )?;
+
+        if add_score < del_score {
+            if del_prefix.is_empty() {
+                return Ok(two.to_owned());
+            }
+            return entry_at(two, del_prefix.as_bstr(), objects)?
+                .map(|(id, _mode)| id)
+                .ok_or_else(|| Error::MissingPath {
+                    path: del_prefix,
+                    tree_id: two.to_owned(),
+                });
+        }
+        if add_prefix.is_empty() {
+            return Ok(two.to_owned());
+        }
+        splice_tree(one, add_prefix.as_bstr(), two, objects)
+    }
+
+    /// This follows `shift_tree_by()` in Git.
+    fn shift_tree_by(
+        one: &gix_hash::oid,
+        two: &gix_hash::oid,
+        prefix: &BStr,
+        objects: &(impl gix_object::FindObjectOrHeader + gix_object::Write),
+    ) -> Result<ObjectId, Error> {
+        let sub_one = entry_at(one, prefix, objects)?.filter(|(_, mode)| mode.is_tree());
+        let sub_two = entry_at(two, prefix, objects)?.filter(|(_, mode)| mode.is_tree());
+        enum Candidate {
+            None,
+            ShiftDown,
+            ShiftUp(ObjectId),
+        }
+        let candidate = match (sub_one, sub_two) {
+            (None, None) => Candidate::None,
+            (Some(_), None) => Candidate::ShiftDown,
+            (None, Some((sub_two, _))) => Candidate::ShiftUp(sub_two),
+            (Some((sub_one, _)), Some((sub_two, _))) => {
+                // Both are plausible, so pick the one that matches best.
+                let mut best_score = score_trees(one, two, objects)?;
+                let mut candidate = Candidate::None;
+                let score = score_trees(&sub_one, two, objects)?;
+                if score > best_score {
+                    candidate = Candidate::ShiftDown;
+                    best_score = score;
+                }
+                if score_trees(&sub_two, one, objects)? > best_score {
+                    candidate = Candidate::ShiftUp(sub_two);
+                }
+                candidate
+            }
+        };
+        Ok(match candidate {
+            Candidate::None => two.to_owned(),
+            Candidate::ShiftDown => splice_tree(one, prefix, two, objects)?,
+            Candidate::ShiftUp(sub_two) => sub_two,
+        })
+    }
+
+    /// Write a copy of the tree `one` with the directory at `prefix` replaced by `two`, and return its id.
+    fn splice_tree(
+        one: &gix_hash::oid,
+        prefix: &BStr,
+        two: &gix_hash::oid,
+        objects: &(impl gix_object::FindObjectOrHeader + gix_object::Write),
+    ) -> Result<ObjectId, Error> {
+        let mut buf = Vec::new();
+        let root = objects.find_tree(one, &mut buf)?.to_owned();
+        let mut editor = gix_object::tree::Editor::new(root, objects, one.kind());
+        editor.upsert(
+            prefix.split_str("/").filter(|c| !c.is_empty()),
+            EntryKind::Tree,
+            two.to_owned(),
+        )?;
+        Ok(editor.write(|tree| objects.write(tree))?)
+    }
+}
+
+/// Return the id and mode of the entry at the slash-separated `path` in the tree `tree_id`, or `None` if there is none.
+fn entry_at(
+    tree_id: &gix_hash::oid,
+    path: &BStr,
+    objects: &impl gix_object::FindObjectOrHeader,
+) -> Result<Option<(ObjectId, EntryMode)>, Error> {
+    let mut buf = Vec::new();
+    let mut current = (tree_id.to_owned(), EntryMode::from(EntryKind::Tree));
+    for component in path.split_str("/").filter(|c| !c.is_empty()) {
+        if !current.1.is_tree() {
+            return Ok(None);
+        }
+        let tree = objects.find_tree(&current.0, &mut buf)?;
+        match tree.entries.iter().find(|e| e.filename == component) {
+            Some(entry) => current = (entry.oid.to_owned(), entry.mode),
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(current))
+}
+
+/// Find the subdirectory of `one` that resembles `two` the most, and place its path into `best_match` if its
+/// score is higher than `best_score`.
+/// `base` is the path of `one`, and `recurse_limit` is the amount of levels to descend into subdirectories.
+///
+/// This follows `match_trees()` in Git.
+fn match_trees(
+    one: &gix_hash::oid,
+    two: &gix_hash::oid,
+    best_score: &mut i32,
+    best_match: &mut BString,
+    base: &BStr,
+    recurse_limit: usize,
+    objects: &impl gix_object::FindObjectOrHeader,
+) -> Result<(), Error> {
+    let mut buf = Vec::new();
+    let subtrees: Vec<_> = objects
+        .find_tree(one, &mut buf)?
+        .entries
+        .iter()
+        .filter(|e| e.mode.is_tree())
+        .map(|e| (e.oid.to_owned(), e.filename.to_owned()))
+        .collect();
+    for (id, name) in subtrees {
+        let score = score_trees(&id, two, objects)?;
+        let mut path = base.to_owned();
+        path.extend_from_slice(&name);
+        path.push(b'/');
+        if *best_score < score {
+            *best_score = score;
+            *best_match = path.clone();
+        }
+        if recurse_limit > 0 {
+            match_trees(
+                &id,
+                two,
+                best_score,
+                best_match,
+                path.as_bstr(),
+                recurse_limit - 1,
+                objects,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Return a score for how similar the direct entries of the trees `one` and `two` are,
+/// which is higher the more entries they share.
+///
+/// This follows `score_trees()` in Git.
+fn score_trees(
+    one: &gix_hash::oid,
+    two: &gix_hash::oid,
+    objects: &impl gix_object::FindObjectOrHeader,
+) -> Result<i32, Error> {
+    let (mut buf_one, mut buf_two) = (Vec::new(), Vec::new());
+    let one = objects.find_tree(one, &mut buf_one)?;
+    let two = objects.find_tree(two, &mut buf_two)?;
+    let (mut one, mut two) = (one.entries.iter().peekable(), two.entries.iter().peekable());
+    let mut score = 0;
+    loop {
+        score += match (one.peek(), two.peek()) {
+            (None, None) => break,
+            (Some(a), Some(b)) if a.cmp(b) == std::cmp::Ordering::Equal => {
+                let score = if a.oid == b.oid {
+                    score_matches(a.mode, b.mode)
+                } else {
+                    score_differs(a.mode, b.mode)
+                };
+                one.next();
+                two.next();
+                score
+            }
+            (Some(a), Some(b)) if a < b => score_missing(one.next().expect("peeked").mode),
+            (Some(_), None) => score_missing(one.next().expect("peeked").mode),
+            (_, Some(_)) => score_missing(two.next().expect("peeked").mode),
+        };
+    }
+    Ok(score)
+}
+
+fn score_missing(mode: EntryMode) -> i32 {
+    if mode.is_tree() {
+        -1000
+    } else if mode.is_link() {
+        -500
+    } else {
+        -50
+    }
+}
+
+fn score_differs(a: EntryMode, b: EntryMode) -> i32 {
+    if a.is_tree() != b.is_tree() {
+        -100
+    } else if a.is_link() != b.is_link() {
+        -50
+    } else if a.is_tree() {
+        -150
+    } else if a.is_link() {
+        -75
+    } else {
+        -5
+    }
+}
+
+fn score_matches(a: EntryMode, b: EntryMode) -> i32 {
+    if a.is_tree() != b.is_tree() {
+        -100
+    } else if a.is_link() != b.is_link() {
+        -50
+    } else if a.is_tree() {
+        1000
+    } else if a.is_link() {
+        500
+    } else {
+        250
+    }
+}
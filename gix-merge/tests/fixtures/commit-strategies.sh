@@ -0,0 +1,96 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+function tick () {
+  if test -z "${tick+set}"
+  then
+    tick=1112911993
+  else
+    tick=$(($tick + 60))
+  fi
+  GIT_COMMITTER_DATE="$tick -0700"
+  GIT_AUTHOR_DATE="$tick -0700"
+  export GIT_COMMITTER_DATE GIT_AUTHOR_DATE
+}
+
+function write_merged_tree () {
+  local name=${1:?Need the name of the file to write the merged tree into}
+  git rev-parse HEAD^{tree} > "../$name.tree"
+}
+
+tick
+git init octopus
+(cd octopus
+  seq 10 > shared
+  echo base > a
+  echo base > b
+  echo base > c
+  git add . && git commit -m "base"
+
+  git checkout -b A
+  echo A > a
+  tick && git commit -am "A"
+
+  git checkout -b B main
+  echo B > b
+  seq 1 9 > shared
+  tick && git commit -am "B"
+
+  git checkout -b C main
+  echo C > c
+  seq 2 10 > shared
+  tick && git commit -am "C"
+
+  git checkout -b conflicting main
+  echo conflict > a
+  tick && git commit -am "conflicting"
+
+  git checkout -b ahead main
+  echo ahead > ahead
+  git add ahead && tick && git commit -m "ahead"
+
+  git checkout -b merged main
+  tick && git merge --no-edit A B C
+  write_merged_tree octopus-A-B-C
+
+  git checkout -b merged-ff main
+  tick && git merge --no-edit ahead A B
+  write_merged_tree octopus-ahead-A-B
+
+  git checkout main
+)
+
+tick
+git init subtree
+(cd subtree
+  git checkout -b library
+  echo lib > lib.rs
+  mkdir src && echo code > src/code.rs
+  git add . && tick && git commit -m "library"
+
+  git checkout --orphan main
+  git rm -rfq .
+  echo app > app.rs
+  git add app.rs && tick && git commit -m "app"
+  tick && git merge --allow-unrelated-histories -s ours --no-commit library
+  git read-tree --prefix=vendor/lib -u library
+  tick && git commit -m "vendor library"
+
+  echo app-changed > app.rs
+  tick && git commit -am "app changed"
+
+  git checkout library
+  echo lib-changed > lib.rs
+  echo new > src/new.rs
+  git add . && tick && git commit -m "library changed"
+
+  git checkout -b merged-auto main
+  tick && git merge --no-edit -s subtree library
+  write_merged_tree subtree-auto
+
+  git checkout -b merged-prefix main
+  tick && git merge --no-edit -X subtree=vendor/lib library
+  write_merged_tree subtree-prefix
+
+  git checkout main
+)
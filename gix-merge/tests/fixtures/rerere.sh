@@ -0,0 +1,22 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git config rerere.enabled true
+
+seq 1 20 > file
+git add file && git commit -qm base
+
+git checkout -qb theirs
+sed -e 's/^2$/a-theirs/' -e 's/^15$/b-theirs/' file > file.tmp && mv file.tmp file
+git commit -qam theirs
+
+git checkout -qb ours HEAD~1
+sed -e 's/^2$/z-ours/' -e 's/^15$/b-ours/' file > file.tmp && mv file.tmp file
+git commit -qam ours
+
+git -c merge.conflictStyle=diff3 merge theirs >/dev/null || :
+cp file conflicted
+sed -e '/^[<=>|]\{7\}/d' -e '/^2$/d' -e '/^15$/d' -e '/^z-ours$/d' -e '/^b-theirs$/d' file > file.tmp && mv file.tmp file
+git rerere
+cp file resolved
@@ -124,7 +124,8 @@ mod text {
                 &[10, 255, 10, 10, 10, 0, 10][..],
                 builtin_driver::text::Options {
                     conflict: Conflict::ResolveWithUnion,
-                    diff_algorithm: imara_diff::Algorithm::Myers,
+                    diff_algorithm: gix_diff::blob::Algorithm::Myers,
+                    whitespace: Default::default(),
                 },
             ),
             (
@@ -297,6 +298,66 @@ mod text {
         }
     }
 
+    #[test]
+    fn ignoring_whitespace_changes_keeps_our_whitespace_unless_they_changed_more() {
+        // Verified with `git merge -X ignore-space-change`.
+        let base = b"1\n2 x\n3\n4\n5 y\n6\n7\n8\n9 z\n";
+        let theirs = b"1\n2  x\n3\n4\n5 Y\n6\n7\n8\n9  z\n";
+        let ours = b"1\n2 x \n3\n4\n5  y\n6\n7\n8\n9 Z\n";
+        let mut input = imara_diff::intern::InternedInput::default();
+        let mut out = Vec::new();
+        let actual = builtin_driver::text(
+            &mut out,
+            &mut input,
+            Default::default(),
+            ours,
+            base,
+            theirs,
+            builtin_driver::text::Options::default(),
+        );
+        assert_eq!(actual, Resolution::Conflict, "by default, whitespace changes conflict");
+
+        for whitespace in [
+            gix_diff::blob::whitespace::Mode::IgnoreChange,
+            gix_diff::blob::whitespace::Mode::IgnoreAll,
+        ] {
+            let mut input = imara_diff::intern::InternedInput::default();
+            let actual = builtin_driver::text(
+                &mut out,
+                &mut input,
+                Default::default(),
+                ours,
+                base,
+                theirs,
+                builtin_driver::text::Options {
+                    whitespace,
+                    ..Default::default()
+                },
+            );
+            assert_eq!(actual, Resolution::Complete, "{whitespace:?}");
+            assert_str_eq!(
+                out.as_bstr().to_str_lossy(),
+                "1\n2 x \n3\n4\n5 Y\n6\n7\n8\n9 Z\n",
+                "{whitespace:?}: our whitespace is kept, unless they changed the line otherwise"
+            );
+        }
+
+        let mut input = imara_diff::intern::InternedInput::default();
+        let actual = builtin_driver::text(
+            &mut out,
+            &mut input,
+            Default::default(),
+            b"1\n2 a\n3\n",
+            b"1\n2 x\n3\n",
+            b"1\n2  b\n3\n",
+            builtin_driver::text::Options {
+                whitespace: gix_diff::blob::whitespace::Mode::IgnoreChange,
+                ..Default::default()
+            },
+        );
+        assert_eq!(actual, Resolution::Conflict, "actual changes still conflict");
+    }
+
     mod baseline {
         use std::path::Path;
 
@@ -373,7 +434,7 @@ mod text {
                     }
                 }
                 if output.contains("histogram") {
-                    options.diff_algorithm = imara_diff::Algorithm::Histogram;
+                    options.diff_algorithm = gix_diff::blob::Algorithm::Histogram;
                 }
 
                 Some(Expectation {
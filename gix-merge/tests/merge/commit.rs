@@ -0,0 +1,216 @@
+use std::path::{Path, PathBuf};
+
+use gix_hash::ObjectId;
+use gix_merge::{
+    commit::{octopus, Strategy},
+    tree::shift::Subtree,
+};
+use gix_object::{FindExt, Write};
+
+use crate::tree::{basic_merge_options, new_blob_merge_platform, new_diff_resource_cache};
+
+mod octopus_merge {
+    use super::*;
+
+    #[test]
+    fn merges_all_commits_like_git() -> crate::Result {
+        let repo = Fixture::new("octopus")?;
+        let (main, a, b, c) = (repo.rev("main"), repo.rev("A"), repo.rev("B"), repo.rev("C"));
+        let out = repo.octopus(main, &[a, b, c], octopus_options())?;
+        assert_eq!(out.tree_id, repo.expected_tree("octopus-A-B-C"));
+        assert_eq!(
+            out.parents,
+            [a, b, c],
+            "our commit is the merge-base, so it's fast-forwarded to A"
+        );
+        assert_eq!(
+            out.conflicts.len(),
+            1,
+            "B and C both changed the same file, which was merged without conflict markers"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fast_forwards_and_skips_commits_that_are_already_contained() -> crate::Result {
+        let repo = Fixture::new("octopus")?;
+        let (main, ahead, a, b) = (repo.rev("main"), repo.rev("ahead"), repo.rev("A"), repo.rev("B"));
+        let out = repo.octopus(main, &[ahead, a, main, b], octopus_options())?;
+        assert_eq!(out.tree_id, repo.expected_tree("octopus-ahead-A-B"));
+        assert_eq!(
+            out.parents,
+            [ahead, a, b],
+            "our commit was fast-forwarded, and is skipped later as it's contained"
+        );
+
+        let out = repo.octopus(a, &[b, ahead], octopus_options())?;
+        assert_eq!(
+            out.parents,
+            [a, b, ahead],
+            "without fast-forward, our commit is the first parent"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fails_on_conflict() -> crate::Result {
+        let repo = Fixture::new("octopus")?;
+        let (main, a, conflicting) = (repo.rev("main"), repo.rev("A"), repo.rev("conflicting"));
+        let err = repo.octopus(main, &[a, conflicting], octopus_options()).unwrap_err();
+        assert!(
+            matches!(err, octopus::Error::Conflict { their_commit_id } if their_commit_id == conflicting),
+            "{err:?}"
+        );
+        Ok(())
+    }
+
+    fn octopus_options() -> octopus::Options {
+        octopus::Options {
+            allow_missing_merge_base: false,
+            tree_merge: basic_merge_options().tree_merge,
+            treat_as_unresolved: Default::default(),
+        }
+    }
+}
+
+#[test]
+fn ours_strategy_keeps_our_tree() -> crate::Result {
+    let repo = Fixture::new("octopus")?;
+    let (ours, conflicting) = (repo.rev("A"), repo.rev("conflicting"));
+    let mut options = basic_merge_options();
+    options.strategy = Strategy::Ours;
+    let mut out = repo.commit(ours, conflicting, options)?;
+    assert!(out.tree_merge.conflicts.is_empty());
+    let actual = out.tree_merge.tree.write(|tree| repo.odb.write(tree))?;
+    assert_eq!(actual, repo.tree_of(ours));
+    Ok(())
+}
+
+#[test]
+fn subtree_shift() -> crate::Result {
+    let repo = Fixture::new("subtree")?;
+    let (main, library) = (repo.rev("main"), repo.rev("library"));
+    for (how, expected) in [
+        (Subtree::Auto, "subtree-auto"),
+        (Subtree::Prefix("vendor/lib".into()), "subtree-prefix"),
+        (Subtree::Prefix("vendor/lib/".into()), "subtree-prefix"),
+    ] {
+        let mut options = basic_merge_options();
+        options.subtree_shift = Some(how.clone());
+        let mut out = repo.commit(main, library, options)?;
+        assert!(out.tree_merge.conflicts.is_empty(), "{how:?}");
+        let actual = out.tree_merge.tree.write(|tree| repo.odb.write(tree))?;
+        assert_eq!(actual, repo.expected_tree(expected), "{how:?}");
+
+        let shifted = gix_merge::tree::shift(&repo.tree_of(main), &repo.tree_of(library), &how, &repo.odb)?;
+        assert_ne!(shifted, repo.tree_of(library), "{how:?}: their tree is shifted down");
+    }
+
+    let shifted = gix_merge::tree::shift(&repo.tree_of(library), &repo.tree_of(main), &Subtree::Auto, &repo.odb)?;
+    let mut buf = Vec::new();
+    let vendored_library = repo
+        .odb
+        .find_tree(&repo.tree_of(main), &mut buf)?
+        .to_owned()
+        .entries
+        .into_iter()
+        .find(|e| e.filename == "vendor")
+        .expect("present");
+    let vendored_library = repo.odb.find_tree(&vendored_library.oid, &mut buf)?.entries[0]
+        .oid
+        .to_owned();
+    assert_eq!(
+        shifted, vendored_library,
+        "if the other tree contains the subtree, it's shifted up"
+    );
+
+    let unshifted = gix_merge::tree::shift(
+        &repo.tree_of(main),
+        &repo.tree_of(library),
+        &Subtree::Prefix("does-not-exist".into()),
+        &repo.odb,
+    )?;
+    assert_eq!(
+        unshifted,
+        repo.tree_of(library),
+        "without a matching prefix, nothing is shifted"
+    );
+    Ok(())
+}
+
+struct Fixture {
+    root: PathBuf,
+    odb: gix_odb::memory::Proxy<gix_odb::Handle>,
+}
+
+impl Fixture {
+    fn new(name: &str) -> crate::Result<Self> {
+        let root = gix_testtools::scripted_fixture_read_only("commit-strategies.sh")?.join(name);
+        let odb = gix_odb::at(root.join(".git/objects"))?;
+        Ok(Fixture {
+            odb: gix_odb::memory::Proxy::new(odb, gix_hash::Kind::Sha1),
+            root,
+        })
+    }
+
+    fn rev(&self, branch: &str) -> ObjectId {
+        read_id(&self.root.join(".git/refs/heads").join(branch))
+    }
+
+    fn expected_tree(&self, name: &str) -> ObjectId {
+        read_id(&self.root.parent().expect("fixture root").join(format!("{name}.tree")))
+    }
+
+    fn tree_of(&self, commit: ObjectId) -> ObjectId {
+        let mut buf = Vec::new();
+        self.odb
+            .find_commit(&commit, &mut buf)
+            .map(|c| c.tree())
+            .expect("commit exists")
+    }
+
+    fn commit(
+        &self,
+        ours: ObjectId,
+        theirs: ObjectId,
+        options: gix_merge::commit::Options,
+    ) -> Result<gix_merge::commit::Outcome<'_>, gix_merge::commit::Error> {
+        let mut graph = gix_revwalk::Graph::new(&self.odb, None);
+        gix_merge::commit(
+            ours,
+            theirs,
+            Default::default(),
+            &mut graph,
+            &mut new_diff_resource_cache(&self.root),
+            &mut new_blob_merge_platform(&self.root, None),
+            &self.odb,
+            &mut |id| id.to_hex_with_len(7).to_string(),
+            options,
+        )
+    }
+
+    fn octopus(
+        &self,
+        ours: ObjectId,
+        theirs: &[ObjectId],
+        options: octopus::Options,
+    ) -> Result<octopus::Outcome, octopus::Error> {
+        let mut graph = gix_revwalk::Graph::new(&self.odb, None);
+        gix_merge::commit::octopus(
+            ours,
+            theirs,
+            Default::default(),
+            &mut graph,
+            &mut new_diff_resource_cache(&self.root),
+            &mut new_blob_merge_platform(&self.root, None),
+            &self.odb,
+            &mut |id| id.to_hex_with_len(7).to_string(),
+            options,
+        )
+    }
+}
+
+fn read_id(path: &Path) -> ObjectId {
+    let hex = std::fs::read_to_string(path).expect("file with object id exists");
+    ObjectId::from_hex(hex.trim().as_bytes()).expect("valid hex")
+}
@@ -2,6 +2,8 @@ use gix_hash::ObjectId;
 extern crate core;
 
 mod blob;
+mod commit;
+mod rerere;
 mod tree;
 
 pub use gix_testtools::Result;
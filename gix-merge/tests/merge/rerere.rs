@@ -0,0 +1,168 @@
+use std::{
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use gix_merge::rerere::{merge_rr, normalize, Id, Store};
+
+#[test]
+fn normalization_matches_git() -> crate::Result {
+    let root = gix_testtools::scripted_fixture_read_only("rerere.sh")?;
+    let (hash, preimage) = recorded_conflict(&root)?;
+    let mut out = Vec::new();
+    let actual = normalize(&read(&root, "conflicted")?, 7, gix_hash::Kind::Sha1, &mut out)?;
+    assert_eq!(
+        actual,
+        Some(hash),
+        "the conflict hash is the same as the one Git computes"
+    );
+    assert_eq!(
+        out, preimage,
+        "labels and the ancestor are removed, and sides are ordered, like Git does"
+    );
+    Ok(())
+}
+
+#[test]
+fn normalization_without_conflicts_or_with_unterminated_conflicts() -> crate::Result {
+    let mut out = Vec::new();
+    assert_eq!(normalize(b"a\nb\n", 7, gix_hash::Kind::Sha1, &mut out)?, None);
+    assert_eq!(
+        normalize(b"<<<<<<< ours\na\n=======\nb\n", 7, gix_hash::Kind::Sha1, &mut out)?,
+        None,
+        "the closing marker is missing"
+    );
+    assert_eq!(
+        normalize(b"<<<<<<<\na\n=======\nb\n>>>>>>>\n", 7, gix_hash::Kind::Sha1, &mut out)?,
+        None,
+        "opening and closing markers need a label to be recognized"
+    );
+    Ok(())
+}
+
+#[test]
+fn replay_resolution_recorded_by_git() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable("rerere.sh")?;
+    let root = tmp.path();
+    let store = Store::at(root.join(".git/rr-cache"), gix_hash::Kind::Sha1);
+    let mut out = Vec::new();
+    let id = store.replay(&read(root, "conflicted")?, 7, &mut out)?;
+    assert_eq!(
+        id.map(|id| id.to_string()),
+        Some("0f4c3599474e8559d109604eca4d00112db74a7c".into())
+    );
+    assert_eq!(out, read(root, "resolved")?);
+
+    assert_eq!(
+        store.replay(&read(root, "conflicted")?, 10, &mut out)?,
+        None,
+        "with the wrong marker size, no conflict is found"
+    );
+    Ok(())
+}
+
+#[test]
+fn replay_keeps_changes_outside_of_conflicts() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable("rerere.sh")?;
+    let root = tmp.path();
+    let store = Store::at(root.join(".git/rr-cache"), gix_hash::Kind::Sha1);
+    let conflicted = read(root, "conflicted")?;
+    let conflicted = [b"new first line\n".as_slice(), &conflicted].concat();
+    let mut out = Vec::new();
+    assert!(store.replay(&conflicted, 7, &mut out)?.is_some());
+    assert_eq!(out, [b"new first line\n".as_slice(), &read(root, "resolved")?].concat());
+    Ok(())
+}
+
+#[test]
+fn record_forget_clear_and_gc() -> crate::Result {
+    let tmp = gix_testtools::tempfile::TempDir::new()?;
+    let store = Store::at(tmp.path().join("rr-cache"), gix_hash::Kind::Sha1);
+    let conflicted = b"a\n<<<<<<< ours\nb\n=======\nc\n>>>>>>> theirs\nd\n";
+    let resolved = b"a\nbc\nd\n";
+
+    let first = store.record_conflict(conflicted, 7)?.expect("has conflicts");
+    assert_eq!(first.variant, 0);
+    assert!(!store.has_resolution(&first));
+    assert_eq!(
+        store.replay(conflicted, 7, &mut Vec::new())?,
+        None,
+        "there is no resolution yet"
+    );
+    store.record_resolution(&first, resolved)?;
+    assert!(store.has_resolution(&first));
+
+    let mut out = Vec::new();
+    assert_eq!(store.replay(conflicted, 7, &mut out)?, Some(first));
+    assert_eq!(out, resolved);
+
+    let second = store.record_conflict(conflicted, 7)?.expect("has conflicts");
+    assert_eq!(
+        second.hash, first.hash,
+        "the same conflict is recorded as another variant"
+    );
+    assert_eq!(second.variant, 1);
+    assert_eq!(store.variants(&first.hash)?, [0, 1]);
+    assert!(store.clear(&second)?, "unresolved variants can be cleared");
+    assert!(!store.clear(&first)?, "resolved ones are kept");
+    assert_eq!(store.variants(&first.hash)?, [0]);
+
+    assert_eq!(store.forget(conflicted, 7)?, Some(first));
+    assert!(!store.has_resolution(&first), "the resolution is gone");
+    assert_eq!(store.replay(conflicted, 7, &mut out)?, None);
+    assert_eq!(store.forget(conflicted, 7)?, None, "there is nothing left to forget");
+
+    let future = SystemTime::now() + Duration::from_secs(60);
+    assert_eq!(store.gc(Some(future), None)?, 0, "unresolved conflicts are kept");
+    assert_eq!(store.gc(None, Some(future))?, 1);
+    assert!(store.variants(&first.hash)?.is_empty());
+    assert!(
+        !store.path().join(first.hash.to_string()).exists(),
+        "empty directories are removed"
+    );
+    Ok(())
+}
+
+#[test]
+fn merge_rr_round_trip() -> crate::Result {
+    let data = b"0f4c3599474e8559d109604eca4d00112db74a7c\tfile\x000f4c3599474e8559d109604eca4d00112db74a7c.2\tdir/with space\x00";
+    let entries = merge_rr::decode(data)?;
+    assert_eq!(
+        entries,
+        [
+            merge_rr::Entry {
+                id: Id {
+                    hash: crate::hex_to_id("0f4c3599474e8559d109604eca4d00112db74a7c"),
+                    variant: 0
+                },
+                path: "file".into()
+            },
+            merge_rr::Entry {
+                id: Id {
+                    hash: crate::hex_to_id("0f4c3599474e8559d109604eca4d00112db74a7c"),
+                    variant: 2
+                },
+                path: "dir/with space".into()
+            }
+        ]
+    );
+    let mut out = Vec::new();
+    merge_rr::encode(&entries, &mut out);
+    assert_eq!(out, data);
+
+    assert!(merge_rr::decode(b"not-a-hash\tfile\0").is_err());
+    Ok(())
+}
+
+fn read(root: &Path, name: &str) -> std::io::Result<Vec<u8>> {
+    std::fs::read(root.join(name))
+}
+
+/// Return the hash and the preimage of the only conflict that Git recorded.
+fn recorded_conflict(root: &Path) -> crate::Result<(gix_hash::ObjectId, Vec<u8>)> {
+    let mut dirs = std::fs::read_dir(root.join(".git/rr-cache"))?.collect::<Result<Vec<_>, _>>()?;
+    assert_eq!(dirs.len(), 1);
+    let dir = dirs.pop().expect("one").path();
+    let hash = crate::hex_to_id(dir.file_name().and_then(|name| name.to_str()).expect("valid"));
+    Ok((hash, std::fs::read(dir.join("preimage"))?))
+}
@@ -215,10 +215,12 @@ fn run_baseline() -> crate::Result {
     Ok(())
 }
 
-fn basic_merge_options() -> Options {
+pub(crate) fn basic_merge_options() -> Options {
     gix_merge::commit::Options {
         allow_missing_merge_base: true,
         use_first_merge_base: false,
+        strategy: Default::default(),
+        subtree_shift: None,
         tree_merge: gix_merge::tree::Options {
             symlink_conflicts: None,
             tree_conflicts: None,
@@ -236,7 +238,7 @@ fn basic_merge_options() -> Options {
     }
 }
 
-fn new_diff_resource_cache(root: &Path) -> gix_diff::blob::Platform {
+pub(crate) fn new_diff_resource_cache(root: &Path) -> gix_diff::blob::Platform {
     gix_diff::blob::Platform::new(
         Default::default(),
         gix_diff::blob::Pipeline::new(Default::default(), Default::default(), Vec::new(), Default::default()),
@@ -251,7 +253,7 @@ fn new_diff_resource_cache(root: &Path) -> gix_diff::blob::Platform {
     )
 }
 
-fn new_blob_merge_platform(
+pub(crate) fn new_blob_merge_platform(
     root: &Path,
     large_file_threshold_bytes: impl Into<Option<u64>>,
 ) -> gix_merge::blob::Platform {
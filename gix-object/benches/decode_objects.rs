@@ -21,10 +21,10 @@ fn parse_tag(c: &mut Criterion) {
 
 fn parse_tree(c: &mut Criterion) {
     c.bench_function("TreeRef()", |b| {
-        b.iter(|| black_box(gix_object::TreeRef::from_bytes(TREE)).unwrap());
+        b.iter(|| black_box(gix_object::TreeRef::from_bytes(TREE, gix_hash::Kind::Sha1)).unwrap());
     });
     c.bench_function("TreeRefIter()", |b| {
-        b.iter(|| black_box(gix_object::TreeRefIter::from_bytes(TREE).count()));
+        b.iter(|| black_box(gix_object::TreeRefIter::from_bytes(TREE, gix_hash::Kind::Sha1).count()));
     });
 }
 
@@ -237,6 +237,8 @@ pub struct TreeRef<'a> {
 pub struct TreeRefIter<'a> {
     /// The directories and files contained in this tree.
     data: &'a [u8],
+    /// The kind of hash used for object ids within the tree.
+    hash_kind: gix_hash::Kind,
 }
 
 /// A mutable Tree, containing other trees, blobs or commits.
@@ -261,6 +263,8 @@ impl Tree {
 pub struct Data<'a> {
     /// kind of object
     pub kind: Kind,
+    /// The kind of hash used for object ids referenced by the object, needed to decode trees.
+    pub hash_kind: gix_hash::Kind,
     /// decoded, decompressed data, owned by a backing store.
     pub data: &'a [u8],
 }
@@ -1,12 +1,12 @@
 use bstr::BStr;
-use winnow::{error::ParserError, prelude::*};
+use winnow::error::ParserError;
 
 use crate::{tree, tree::EntryRef, TreeRef, TreeRefIter};
 
 impl<'a> TreeRefIter<'a> {
-    /// Instantiate an iterator from the given tree data.
-    pub fn from_bytes(data: &'a [u8]) -> TreeRefIter<'a> {
-        TreeRefIter { data }
+    /// Instantiate an iterator from the given tree data, with object ids in it being of `hash_kind`.
+    pub fn from_bytes(data: &'a [u8], hash_kind: gix_hash::Kind) -> TreeRefIter<'a> {
+        TreeRefIter { data, hash_kind }
     }
 
     /// Follow a sequence of `path` components starting from this instance, and look them up in `odb` one by one using `buffer`
@@ -32,7 +32,7 @@ impl<'a> TreeRefIter<'a> {
         let mut path = path.into_iter().peekable();
         buffer.extend_from_slice(self.data);
         while let Some(component) = path.next() {
-            match TreeRefIter::from_bytes(buffer)
+            match TreeRefIter::from_bytes(buffer, self.hash_kind)
                 .filter_map(Result::ok)
                 .find(|entry| component.eq(entry.filename))
             {
@@ -82,10 +82,10 @@ impl<'a> TreeRefIter<'a> {
 }
 
 impl<'a> TreeRef<'a> {
-    /// Deserialize a Tree from `data`.
-    pub fn from_bytes(mut data: &'a [u8]) -> Result<TreeRef<'a>, crate::decode::Error> {
+    /// Deserialize a Tree from `data`, with object ids in it being of `hash_kind`.
+    pub fn from_bytes(mut data: &'a [u8], hash_kind: gix_hash::Kind) -> Result<TreeRef<'a>, crate::decode::Error> {
         let input = &mut data;
-        match decode::tree.parse_next(input) {
+        match decode::tree(input, hash_kind) {
             Ok(tag) => Ok(tag),
             Err(err) => Err(crate::decode::Error::with_err(err, input)),
         }
@@ -150,7 +150,7 @@ impl<'a> Iterator for TreeRefIter<'a> {
         if self.data.is_empty() {
             return None;
         }
-        match decode::fast_entry(self.data) {
+        match decode::fast_entry(self.data, self.hash_kind) {
             Some((data_left, entry)) => {
                 self.data = data_left;
                 Some(Ok(entry))
@@ -182,30 +182,33 @@ mod decode {
 
     use crate::{tree, tree::EntryRef, TreeRef};
 
-    pub fn fast_entry(i: &[u8]) -> Option<(&[u8], EntryRef<'_>)> {
+    pub fn fast_entry(i: &[u8], hash_kind: gix_hash::Kind) -> Option<(&[u8], EntryRef<'_>)> {
         let (mode, i) = tree::EntryMode::extract_from_bytes(i)?;
         let (filename, i) = i.split_at(i.find_byte(0)?);
         let i = &i[1..];
-        const HASH_LEN_FIXME: usize = 20; // TODO(SHA256): know actual/desired length or we may overshoot
+        let hash_len = hash_kind.len_in_bytes();
         let (oid, i) = match i.len() {
-            len if len < HASH_LEN_FIXME => return None,
-            _ => i.split_at(20),
+            len if len < hash_len => return None,
+            _ => i.split_at(hash_len),
         };
         Some((
             i,
             EntryRef {
                 mode,
                 filename: filename.as_bstr(),
-                oid: gix_hash::oid::try_from_bytes(oid).expect("we counted exactly 20 bytes"),
+                oid: gix_hash::oid::try_from_bytes(oid).expect("we counted exactly as many bytes as the hash has"),
             },
         ))
     }
 
-    pub fn tree<'a, E: ParserError<&'a [u8]>>(i: &mut &'a [u8]) -> ModalResult<TreeRef<'a>, E> {
+    pub fn tree<'a, E: ParserError<&'a [u8]>>(
+        i: &mut &'a [u8],
+        hash_kind: gix_hash::Kind,
+    ) -> ModalResult<TreeRef<'a>, E> {
         let mut out = Vec::new();
         let mut i = &**i;
         while !i.is_empty() {
-            let Some((rest, entry)) = fast_entry(i) else {
+            let Some((rest, entry)) = fast_entry(i, hash_kind) else {
                 #[allow(clippy::unit_arg)]
                 return Err(winnow::error::ErrMode::from_input(&i));
             };
@@ -7,7 +7,13 @@ enum Error {
 }
 
 macro_rules! round_trip {
+    ($owned:ty, $borrowed:ty, hash_kind = $hash_kind:expr, $( $files:literal ), +) => {
+        round_trip!(@impl $owned, $borrowed, [, $hash_kind], $( $files ), +);
+    };
     ($owned:ty, $borrowed:ty, $( $files:literal ), +) => {
+        round_trip!(@impl $owned, $borrowed, [], $( $files ), +);
+    };
+    (@impl $owned:ty, $borrowed:ty, [$( $from_bytes_args:tt )*], $( $files:literal ), +) => {
         #[test]
         fn round_trip() -> Result<(), Box<dyn std::error::Error>> {
             use std::convert::TryFrom;
@@ -22,7 +28,7 @@ macro_rules! round_trip {
                 let input = fixture_bytes(input_name);
                 // Test the parse->borrowed->owned->write chain for an object kind
                 let mut output = Vec::new();
-                let item = <$borrowed>::from_bytes(&input)?;
+                let item = <$borrowed>::from_bytes(&input $( $from_bytes_args )*)?;
                 item.write_to(&mut output)?;
                 assert_eq!(output.as_bstr(), input.as_bstr(), "borrowed: {input_name}");
 
@@ -32,7 +38,7 @@ macro_rules! round_trip {
                 assert_eq!(output.as_bstr(), input.as_bstr());
 
                 // Test the parse->borrowed->owned->write chain for the top-level objects
-                let item = ObjectRef::from(<$borrowed>::from_bytes(&input)?);
+                let item = ObjectRef::from(<$borrowed>::from_bytes(&input $( $from_bytes_args )*)?);
                 output.clear();
                 item.write_to(&mut output)?;
                 assert_eq!(output.as_bstr(), input.as_bstr(), "object-ref");
@@ -43,14 +49,14 @@ macro_rules! round_trip {
                 assert_eq!(output.as_bstr(), input.as_bstr(), "owned");
 
                 // Test the loose serialisation -> parse chain for an object kind
-                let item = <$borrowed>::from_bytes(&input)?;
+                let item = <$borrowed>::from_bytes(&input $( $from_bytes_args )*)?;
                 // serialise a borowed item to a tagged loose object
                 output.clear();
                 {
                     let w = &mut output;
                     w.write_all(&item.loose_header())?;
                     item.write_to(w)?;
-                    let parsed = ObjectRef::from_loose(&output)?;
+                    let parsed = ObjectRef::from_loose(&output, gix_hash::Kind::Sha1)?;
                     let item2 = <$borrowed>::try_from(parsed).or(Err(super::Error::TryFromError))?;
                     assert_eq!(item2, item, "object-ref loose: {input_name} {:?}\n{:?}", output.as_bstr(), input.as_bstr());
                 }
@@ -61,7 +67,7 @@ macro_rules! round_trip {
                 let w = &mut output;
                 w.write_all(&item.loose_header())?;
                 item.write_to(w)?;
-                let parsed = ObjectRef::from_loose(&output)?;
+                let parsed = ObjectRef::from_loose(&output, gix_hash::Kind::Sha1)?;
                 let parsed_borrowed = <$borrowed>::try_from(parsed).or(Err(super::Error::TryFromError))?;
                 let item2: $owned = parsed_borrowed.try_into().or(Err(super::Error::TryFromError))?;
                 assert_eq!(item2, item, "object-ref loose owned: {input_name} {:?}\n{:?}", output.as_bstr(), input.as_bstr());
@@ -138,7 +144,12 @@ mod tree {
         );
     }
 
-    round_trip!(gix_object::Tree, gix_object::TreeRef, "tree/everything.tree");
+    round_trip!(
+        gix_object::Tree,
+        gix_object::TreeRef,
+        hash_kind = gix_hash::Kind::Sha1,
+        "tree/everything.tree"
+    );
 }
 
 mod blob {
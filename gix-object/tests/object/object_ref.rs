@@ -4,7 +4,9 @@ mod from_loose {
     #[test]
     fn shorter_than_advertised() {
         assert_eq!(
-            ObjectRef::from_loose(b"tree 1000\x00").unwrap_err().to_string(),
+            ObjectRef::from_loose(b"tree 1000\x00", gix_hash::Kind::Sha1)
+                .unwrap_err()
+                .to_string(),
             "object data was shorter than its size declared in the header"
         );
     }
@@ -204,7 +204,7 @@ mod expand {
                                     push_obj_count_unique(
                                         &mut out, seen_objs, &tree_id, location, objects, stats, true,
                                     );
-                                    gix_object::TreeRefIter::from_bytes(obj.data)
+                                    gix_object::TreeRefIter::from_bytes(obj.data, obj.hash_kind)
                                 };
 
                                 let objects_ref = if parent_commit_ids.is_empty() {
@@ -242,7 +242,10 @@ mod expand {
                                                 stats,
                                                 true,
                                             );
-                                            gix_object::TreeRefIter::from_bytes(parent_tree_obj.data)
+                                            gix_object::TreeRefIter::from_bytes(
+                                                parent_tree_obj.data,
+                                                parent_tree_obj.hash_kind,
+                                            )
                                         };
 
                                         changes_delegate.clear();
@@ -279,7 +282,7 @@ mod expand {
                                 {
                                     let objects = ExpandedCountingObjects::new(db, out, objects);
                                     gix_traverse::tree::breadthfirst(
-                                        gix_object::TreeRefIter::from_bytes(obj.0.data),
+                                        gix_object::TreeRefIter::from_bytes(obj.0.data, obj.0.hash_kind),
                                         &mut tree_traversal_state,
                                         &objects,
                                         &mut traverse_delegate,
@@ -84,7 +84,7 @@ mod function {
         // Write header
         let mut out = Count::new(std::io::BufWriter::with_capacity(
             8 * 4096,
-            gix_hash::io::Write::new(out, kind.hash()),
+            gix_hash::io::Write::new(out, pack_hash.kind()),
         ));
         out.write_all(V2_SIGNATURE)?;
         out.write_all(&(kind as u32).to_be_bytes())?;
@@ -192,7 +192,7 @@ impl crate::index::File {
                      entry,
                      decompressed: bytes,
                      ..
-                 }| { modify_base(data, entry, bytes, version.hash()) },
+                 }| { modify_base(data, entry, bytes, object_hash) },
                 traverse::Options {
                     object_progress: Box::new(
                         root_progress.add_child_with_id("Resolving".into(), ProgressId::ResolveObjects.into()),
@@ -54,7 +54,9 @@ fn common_prefix_len(patterns: &[gix_glob::search::pattern::Mapping<Spec>]) -> u
         .filter(|p| !p.value.pattern.is_excluded())
         .map(|p| {
             count += 1;
-            if p.value.pattern.signature.contains(MagicSignature::ICASE) {
+            if p.value.pattern.is_nil() {
+                0
+            } else if p.value.pattern.signature.contains(MagicSignature::ICASE) {
                 p.value.pattern.prefix_len
             } else {
                 p.pattern.first_wildcard_pos.unwrap_or(p.pattern.text.len())
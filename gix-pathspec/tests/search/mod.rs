@@ -391,6 +391,17 @@ fn simplified_search_handles_nil() -> crate::Result {
     assert!(search.can_match_relative_path("a".into(), Some(true)));
     assert!(search.can_match_relative_path("a/b".into(), Some(true)));
 
+    let mut search = gix_pathspec::Search::from_specs(pathspecs(&["."]), None, Path::new(""))?;
+    assert_eq!(
+        search.common_prefix(),
+        "",
+        "the current directory at the root has no prefix"
+    );
+    assert!(search.can_match_relative_path("a".into(), Some(true)));
+    assert!(search
+        .pattern_matching_relative_path("a/b".into(), Some(false), &mut no_attrs)
+        .is_some());
+
     let search = gix_pathspec::Search::from_specs(pathspecs(&[":(exclude)"]), None, Path::new(""))?;
     assert!(
         !search.can_match_relative_path("a".into(), None),
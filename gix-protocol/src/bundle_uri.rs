@@ -0,0 +1,369 @@
+//! Types to describe the bundles a server advertises via the V2 `bundle-uri` command, along with a parser for bundle files.
+//!
+//! Bundles are downloaded and unpacked before fetching to only negotiate the objects they don't contain,
+//! as described in [the technical documentation](https://git-scm.com/docs/bundle-uri).
+use bstr::{BStr, BString, ByteSlice};
+
+/// How the bundles in a [`List`] relate to each other.
+#[derive(Default, PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Mode {
+    /// All bundles are needed to obtain the complete set of objects.
+    #[default]
+    All,
+    /// Any of the bundles is sufficient, typically as they are the same bundle hosted at different locations.
+    Any,
+}
+
+/// A hint on how to use the bundles in a [`List`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Heuristic {
+    /// Bundles have a [creation token](Bundle::creation_token) and must be applied in increasing order of it,
+    /// as each of them may depend on the objects of bundles with a lower token.
+    CreationToken,
+}
+
+/// A single bundle in a [`List`].
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Bundle {
+    /// The identifier of the bundle, unique within its list.
+    pub id: BString,
+    /// The location of the bundle, which may be relative to the location of the list.
+    pub uri: BString,
+    /// A number to order bundles by if the [heuristic](List::heuristic) is [`Heuristic::CreationToken`].
+    pub creation_token: Option<u64>,
+}
+
+/// A list of bundles as advertised by a server or as stored in a bundle list file.
+#[derive(Default, PartialEq, Eq, Debug, Hash, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct List {
+    /// How the bundles relate to each other.
+    pub mode: Mode,
+    /// A hint on how to use the bundles, if known.
+    pub heuristic: Option<Heuristic>,
+    /// All bundles with a URI, in the order in which they were listed.
+    pub bundles: Vec<Bundle>,
+}
+
+impl List {
+    /// Parse a list from `key=value` `lines` as sent by the server in response to the `bundle-uri` command,
+    /// with keys like `bundle.version` or `bundle.<id>.uri`.
+    pub fn from_lines<'a>(lines: impl IntoIterator<Item = &'a BStr>) -> Result<Self, parse::Error> {
+        let mut pairs = Vec::new();
+        for line in lines {
+            let line = line.trim_end_with(|c| c == '\n');
+            let (key, value) = line
+                .split_once_str("=")
+                .ok_or_else(|| parse::Error::MalformedLine { line: line.into() })?;
+            pairs.push((key.as_bstr(), value.as_bstr()));
+        }
+        Self::from_key_value_pairs(pairs)
+    }
+
+    /// Parse a list from `(key, value)` `pairs`, with keys like `bundle.version` or `bundle.<id>.uri`, as they are
+    /// sent by the server or found in a bundle list file in `git-config` format.
+    ///
+    /// Unknown keys are ignored, as are bundles without a URI, while the list is rejected if its version or mode is unknown.
+    pub fn from_key_value_pairs<'a>(
+        pairs: impl IntoIterator<Item = (&'a BStr, &'a BStr)>,
+    ) -> Result<Self, parse::Error> {
+        let mut out = List::default();
+        let mut bundles = Vec::<Bundle>::new();
+        for (key, value) in pairs {
+            const SECTION: &[u8] = b"bundle.";
+            let Some(key) = key
+                .get(..SECTION.len())
+                .filter(|section| section.eq_ignore_ascii_case(SECTION))
+                .map(|_| key[SECTION.len()..].as_bstr())
+            else {
+                continue;
+            };
+            match key.rsplit_once_str(".") {
+                None => {
+                    if key.eq_ignore_ascii_case(b"version") {
+                        if value != "1" {
+                            return Err(parse::Error::UnsupportedVersion { version: value.into() });
+                        }
+                    } else if key.eq_ignore_ascii_case(b"mode") {
+                        out.mode = if value.eq_ignore_ascii_case(b"all") {
+                            Mode::All
+                        } else if value.eq_ignore_ascii_case(b"any") {
+                            Mode::Any
+                        } else {
+                            return Err(parse::Error::UnsupportedMode { mode: value.into() });
+                        };
+                    } else if key.eq_ignore_ascii_case(b"heuristic") {
+                        out.heuristic = value
+                            .eq_ignore_ascii_case(b"creationToken")
+                            .then_some(Heuristic::CreationToken);
+                    }
+                }
+                Some((id, key)) => {
+                    let idx = match bundles.iter().position(|b| b.id == id) {
+                        Some(idx) => idx,
+                        None => {
+                            bundles.push(Bundle {
+                                id: id.into(),
+                                uri: BString::default(),
+                                creation_token: None,
+                            });
+                            bundles.len() - 1
+                        }
+                    };
+                    let bundle = &mut bundles[idx];
+                    if key.eq_ignore_ascii_case(b"uri") {
+                        bundle.uri = value.into();
+                    } else if key.eq_ignore_ascii_case(b"creationToken") {
+                        bundle.creation_token =
+                            Some(value.to_str().ok().and_then(|v| v.parse().ok()).ok_or_else(|| {
+                                parse::Error::InvalidCreationToken {
+                                    id: id.into(),
+                                    value: value.into(),
+                                }
+                            })?);
+                    }
+                }
+            }
+        }
+        out.bundles = bundles.into_iter().filter(|b| !b.uri.is_empty()).collect();
+        Ok(out)
+    }
+
+    /// Return the bundles in the order in which they should be applied, which is ascending by creation token if the
+    /// [heuristic](Self::heuristic) calls for it, or the order in which they were listed otherwise.
+    pub fn bundles_in_application_order(&self) -> Vec<&Bundle> {
+        let mut out: Vec<_> = self.bundles.iter().collect();
+        if self.heuristic == Some(Heuristic::CreationToken) {
+            out.sort_by_key(|b| b.creation_token.unwrap_or(u64::MAX));
+        }
+        out
+    }
+}
+
+///
+pub mod parse {
+    use bstr::BString;
+
+    /// The error returned by [`List::from_key_value_pairs()`](super::List::from_key_value_pairs()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Bundle list line {line:?} is not of the form 'key=value'")]
+        MalformedLine { line: BString },
+        #[error("Bundle list version {version:?} is not supported")]
+        UnsupportedVersion { version: BString },
+        #[error("Bundle list mode {mode:?} is not supported")]
+        UnsupportedMode { mode: BString },
+        #[error("The creation token {value:?} of bundle {id:?} is not a positive number")]
+        InvalidCreationToken { id: BString, value: BString },
+    }
+}
+
+/// The header of a bundle file, which is followed by a pack.
+#[derive(PartialEq, Eq, Debug, Hash, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Header {
+    /// The kind of hash used for all objects in the bundle.
+    pub object_hash: gix_hash::Kind,
+    /// Commits that must exist in the repository before the pack of the bundle can be used, as objects in the pack depend on them.
+    pub prerequisites: Vec<gix_hash::ObjectId>,
+    /// The references contained in the bundle, along with the objects they point to.
+    pub refs: Vec<(BString, gix_hash::ObjectId)>,
+}
+
+impl Header {
+    /// Read the header of a bundle of version 2 or 3 from `read`, leaving it positioned at the start of the pack.
+    ///
+    /// Bundles with capabilities other than `object-format` are rejected, as we don't know if we can handle their pack.
+    pub fn from_read(read: &mut dyn std::io::BufRead) -> Result<Self, header::Error> {
+        use header::Error;
+        let mut line = Vec::new();
+        let mut read_line = |line: &mut Vec<u8>| -> Result<bool, Error> {
+            line.clear();
+            read.read_until(b'\n', line)?;
+            if line.pop() != Some(b'\n') {
+                return Err(Error::UnexpectedEof);
+            }
+            Ok(!line.is_empty())
+        };
+        read_line(&mut line)?;
+        let is_v3 = match line.as_slice() {
+            b"# v2 git bundle" => false,
+            b"# v3 git bundle" => true,
+            _ => return Err(Error::UnknownSignature { line: line.into() }),
+        };
+
+        let mut out = Header {
+            object_hash: gix_hash::Kind::Sha1,
+            prerequisites: Vec::new(),
+            refs: Vec::new(),
+        };
+        while read_line(&mut line)? {
+            let malformed = || Error::MalformedLine {
+                line: line.clone().into(),
+            };
+            if let Some(capability) = line.strip_prefix(b"@").filter(|_| is_v3) {
+                let (name, value) = capability.split_once_str("=").unwrap_or((capability, b""));
+                match name {
+                    b"object-format" => {
+                        out.object_hash = value.to_str().ok().and_then(|v| v.parse().ok()).ok_or_else(malformed)?;
+                    }
+                    _ => {
+                        return Err(Error::UnsupportedCapability {
+                            capability: capability.into(),
+                        })
+                    }
+                }
+            } else if let Some(prerequisite) = line.strip_prefix(b"-") {
+                let hex = prerequisite.split_str(" ").next().unwrap_or_default();
+                out.prerequisites
+                    .push(gix_hash::ObjectId::from_hex(hex).map_err(|_| malformed())?);
+            } else {
+                let (hex, name) = line.split_once_str(" ").ok_or_else(malformed)?;
+                let id = gix_hash::ObjectId::from_hex(hex).map_err(|_| malformed())?;
+                out.refs.push((name.into(), id));
+            }
+        }
+        Ok(out)
+    }
+}
+
+///
+pub mod header {
+    use bstr::BString;
+
+    /// The error returned by [`Header::from_read()`](super::Header::from_read()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+        #[error("The bundle ended before its header was complete")]
+        UnexpectedEof,
+        #[error("{line:?} is not the signature of a bundle of version 2 or 3")]
+        UnknownSignature { line: BString },
+        #[error("The bundle header line {line:?} could not be parsed")]
+        MalformedLine { line: BString },
+        #[error("The bundle capability {capability:?} is not supported")]
+        UnsupportedCapability { capability: BString },
+    }
+}
+
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+mod error {
+    /// The error returned by invoking a [`super::function::BundleUriCommand`].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+        #[error(transparent)]
+        Transport(#[from] gix_transport::client::Error),
+        #[error(transparent)]
+        Parse(#[from] super::parse::Error),
+        #[error("The server does not support the 'bundle-uri' command")]
+        Unsupported,
+    }
+
+    impl gix_transport::IsSpuriousError for Error {
+        fn is_spurious(&self) -> bool {
+            match self {
+                Error::Io(err) => err.is_spurious(),
+                Error::Transport(err) => err.is_spurious(),
+                _ => false,
+            }
+        }
+    }
+}
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub use error::Error;
+
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub(crate) mod function {
+    use std::borrow::Cow;
+
+    use bstr::ByteSlice;
+    use gix_transport::client::Capabilities;
+
+    use super::{Error, List};
+    #[cfg(feature = "async-client")]
+    use crate::transport::client::async_io::{self, ReadlineBufRead as _, TransportV2Ext as _};
+    #[cfg(feature = "blocking-client")]
+    use crate::transport::client::blocking_io::{self, ReadlineBufRead as _, TransportV2Ext as _};
+    use crate::Command;
+
+    /// A command to list the bundles a remote Git repository advertises for download.
+    ///
+    /// It acts as a utility to separate the invocation into the shared blocking portion,
+    /// and the one that performs IO either blocking or `async`.
+    pub struct BundleUriCommand<'a> {
+        capabilities: &'a Capabilities,
+        features: Vec<(&'static str, Option<Cow<'static, str>>)>,
+    }
+
+    impl<'a> BundleUriCommand<'a> {
+        /// Build a command to list bundles from the given server `capabilities`,
+        /// using `agent` information to identify ourselves.
+        pub fn new(capabilities: &'a Capabilities, agent: (&'static str, Option<Cow<'static, str>>)) -> Self {
+            let mut features = Command::BundleUri.default_features(gix_transport::Protocol::V2, capabilities);
+            features.push(agent);
+            Self { capabilities, features }
+        }
+
+        /// Return `true` if the server advertises the `bundle-uri` command.
+        pub fn is_supported(&self) -> bool {
+            self.capabilities.contains(Command::BundleUri.as_str())
+        }
+
+        /// Invoke a bundle-uri V2 command on `transport`.
+        ///
+        /// If `trace` is `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
+        #[cfg(feature = "async-client")]
+        pub async fn invoke_async(self, mut transport: impl async_io::Transport, trace: bool) -> Result<List, Error> {
+            let _span = gix_features::trace::detail!("gix_protocol::BundleUriCommand::invoke_async()");
+            if !self.is_supported() {
+                return Err(Error::Unsupported);
+            }
+            let mut lines = transport
+                .invoke(
+                    Command::BundleUri.as_str(),
+                    self.features.into_iter(),
+                    None::<std::iter::Empty<bstr::BString>>,
+                    trace,
+                )
+                .await?;
+            let mut out = Vec::new();
+            let mut line = String::new();
+            while lines.readline_str(&mut line).await? != 0 {
+                out.push(std::mem::take(&mut line));
+            }
+            Ok(List::from_lines(out.iter().map(|l| l.as_bytes().as_bstr()))?)
+        }
+
+        /// Invoke a bundle-uri V2 command on `transport`.
+        ///
+        /// If `trace` is `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
+        #[cfg(feature = "blocking-client")]
+        pub fn invoke_blocking(self, mut transport: impl blocking_io::Transport, trace: bool) -> Result<List, Error> {
+            let _span = gix_features::trace::detail!("gix_protocol::BundleUriCommand::invoke_blocking()");
+            if !self.is_supported() {
+                return Err(Error::Unsupported);
+            }
+            let mut lines = transport.invoke(
+                Command::BundleUri.as_str(),
+                self.features.into_iter(),
+                None::<std::iter::Empty<bstr::BString>>,
+                trace,
+            )?;
+            let mut out = Vec::new();
+            let mut line = String::new();
+            while lines.readline_str(&mut line)? != 0 {
+                out.push(std::mem::take(&mut line));
+            }
+            Ok(List::from_lines(out.iter().map(|l| l.as_bytes().as_bstr()))?)
+        }
+    }
+}
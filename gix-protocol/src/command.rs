@@ -12,6 +12,7 @@ impl Command {
         match self {
             Command::LsRefs => "ls-refs",
             Command::Fetch => "fetch",
+            Command::BundleUri => "bundle-uri",
         }
     }
 }
@@ -53,12 +54,13 @@ mod with_io {
                     // wait-for-done feature
                     "wait-for-done",
                 ],
+                Command::BundleUri => &[],
             }
         }
 
         fn all_features(&self, version: gix_transport::Protocol) -> &'static [&'static str] {
             match self {
-                Command::LsRefs => &[],
+                Command::LsRefs | Command::BundleUri => &[],
                 Command::Fetch => match version {
                     gix_transport::Protocol::V0 | gix_transport::Protocol::V1 => &[
                         "multi_ack",
@@ -109,6 +111,7 @@ mod with_io {
                     )
                     .collect(),
                 Command::LsRefs => vec![b"symrefs".as_bstr().to_owned(), b"peel".as_bstr().to_owned()],
+                Command::BundleUri => Vec::new(),
             }
         }
 
@@ -134,6 +137,7 @@ mod with_io {
                                 feature => server_capabilities.contains(feature),
                             })
                             .map(|s| (s, None))
+                            .chain(object_format(server_capabilities))
                             .collect()
                     }
                     gix_transport::Protocol::V2 => {
@@ -152,10 +156,14 @@ mod with_io {
                             .copied()
                             .filter(|feature| supported_features.iter().any(|supported| supported == feature))
                             .map(|s| (s, None))
+                            .chain(object_format(server_capabilities))
                             .collect()
                     }
                 },
-                Command::LsRefs => vec![],
+                Command::LsRefs | Command::BundleUri => match version {
+                    gix_transport::Protocol::V0 | gix_transport::Protocol::V1 => vec![],
+                    gix_transport::Protocol::V2 => object_format(server_capabilities).into_iter().collect(),
+                },
             }
         }
         /// Return an error if the given `arguments` and `features` don't match what's statically known.
@@ -208,7 +216,7 @@ mod with_io {
                             continue;
                         }
                         match *feature {
-                            "agent" => {}
+                            "agent" | "object-format" => {}
                             _ => {
                                 return Err(Error::UnsupportedCapability {
                                     command: self.as_str(),
@@ -223,6 +231,18 @@ mod with_io {
         }
     }
 
+    /// Return the `object-format` feature to send if the server advertises an object format other than the default, SHA-1.
+    ///
+    /// This way the server knows that we expect object ids of the same kind.
+    fn object_format(server_capabilities: &Capabilities) -> Option<Feature> {
+        let format = server_capabilities
+            .capability("object-format")?
+            .value()?
+            .to_str()
+            .ok()?;
+        (format != "sha1").then(|| ("object-format", Some(format.to_owned().into())))
+    }
+
     ///
     pub mod validate_argument_prefixes {
         use bstr::BString;
@@ -18,6 +18,7 @@ pub struct Arguments {
     deepen_not: bool,
     deepen_relative: bool,
     ref_in_want: bool,
+    packfile_uris: bool,
     supports_include_tag: bool,
 
     features_for_first_want: Option<Vec<String>>,
@@ -77,6 +78,12 @@ impl Arguments {
     pub fn can_use_ref_in_want(&self) -> bool {
         self.ref_in_want
     }
+    /// Return true if the 'packfile-uris' capability is supported.
+    ///
+    /// This allows the server to send parts of the pack as URIs to download them from, which is only available in protocol V2.
+    pub fn can_use_packfile_uris(&self) -> bool {
+        self.packfile_uris
+    }
     /// Return true if the 'include-tag' capability is supported.
     pub fn can_use_include_tag(&self) -> bool {
         self.supports_include_tag
@@ -155,6 +162,13 @@ impl Arguments {
             self.args.push(line);
         }
     }
+    /// Allow the server to send packfiles as URIs using any of the comma-separated `protocols`, like `https,file`.
+    pub fn packfile_uris(&mut self, protocols: &str) {
+        debug_assert!(self.packfile_uris, "'packfile-uris' feature required");
+        if self.packfile_uris {
+            self.prefixed("packfile-uris ", protocols);
+        }
+    }
     /// Set the given filter `spec` when listing references.
     pub fn filter(&mut self, spec: &str) {
         debug_assert!(self.filter, "'filter' feature required");
@@ -207,6 +221,7 @@ impl Arguments {
         let filter = has("filter");
         let shallow = has("shallow");
         let ref_in_want = has("ref-in-want");
+        let packfile_uris = version == gix_transport::Protocol::V2 && has("packfile-uris");
         let mut deepen_since = shallow;
         let mut deepen_not = shallow;
         let mut deepen_relative = shallow;
@@ -246,6 +261,7 @@ impl Arguments {
             deepen_not,
             deepen_relative,
             ref_in_want,
+            packfile_uris,
             deepen_since,
             features_for_first_want,
             trace,
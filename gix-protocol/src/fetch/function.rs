@@ -55,6 +55,7 @@ pub async fn fetch<P, T, E>(
         shallow,
         tags,
         reject_shallow_remote,
+        packfile_uri_protocols,
     }: Options<'_>,
 ) -> Result<Option<Outcome>, Error>
 where
@@ -76,6 +77,7 @@ where
 
     crate::fetch::Response::check_required_features(protocol_version, &fetch_features)?;
     let sideband_all = fetch_features.iter().any(|(n, _)| *n == "sideband-all");
+    let no_done = fetch_features.iter().any(|(n, _)| *n == "no-done");
     let mut arguments = Arguments::new(protocol_version, fetch_features, trace_packetlines);
     if matches!(tags, Tags::Included) {
         if !arguments.can_use_include_tag() {
@@ -88,6 +90,9 @@ where
         }
         arguments.use_include_tag();
     }
+    if let Some(protocols) = packfile_uri_protocols.filter(|_| arguments.can_use_packfile_uris()) {
+        arguments.packfile_uris(protocols);
+    }
     let (shallow_commits, mut shallow_lock) = add_shallow_args(&mut arguments, shallow, &shallow_file)?;
 
     let negotiate_span = gix_trace::detail!(
@@ -131,7 +136,7 @@ where
                     setup_remote_progress(&mut progress, &mut reader, should_interrupt);
                 }
                 let response =
-                    crate::fetch::Response::from_line_reader(protocol_version, &mut reader, is_done, !is_done).await?;
+                    crate::fetch::Response::from_line_reader(protocol_version, &mut reader, is_done, no_done).await?;
                 let has_pack = response.has_pack();
                 previous_response = Some(response);
                 if has_pack {
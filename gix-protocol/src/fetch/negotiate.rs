@@ -441,6 +441,8 @@ pub mod one_round {
 /// making the common commits (as sent by the remote) known to `negotiator` using `previous_response`, if this isn't the first round.
 /// All [commits we have](crate::fetch::Arguments::have()) are added to `arguments` accordingly.
 ///
+/// If the remote signalled it is ready in `previous_response`, no more haves are added as all that's left is to tell it we are done.
+///
 /// Returns information about this round, and `true` if we are done and should stop negotiating *after* the `arguments` have
 /// been sent to the remote one last time.
 pub fn one_round(
@@ -451,6 +453,7 @@ pub fn one_round(
     previous_response: Option<&crate::fetch::Response>,
 ) -> Result<(Round, bool), Error> {
     let mut seen_ack = false;
+    let mut seen_ready = false;
     if let Some(response) = previous_response {
         use crate::fetch::response::Acknowledgement;
         for ack in response.acknowledgements() {
@@ -463,8 +466,9 @@ pub fn one_round(
                     }
                 }
                 Acknowledgement::Ready => {
-                    // NOTE: In git, there is some logic dealing with whether to expect a DELIM or FLUSH package,
-                    //       but we handle this with peeking.
+                    // In V2, the pack follows right away, so we only get here in V1 when the server didn't
+                    // get to send the pack without us telling it we are `done`.
+                    seen_ready = true;
                 }
                 Acknowledgement::Nak => {}
             }
@@ -480,7 +484,8 @@ pub fn one_round(
     }
 
     let mut haves_added = 0;
-    for have_id in (0..state.haves_to_send).map_while(|_| negotiator.next_have(graph)) {
+    let haves_to_send = if seen_ready { 0 } else { state.haves_to_send };
+    for have_id in (0..haves_to_send).map_while(|_| negotiator.next_have(graph)) {
         arguments.have(have_id?);
         haves_added += 1;
     }
@@ -498,7 +503,7 @@ pub fn one_round(
         haves_to_send: state.haves_to_send,
         previous_response_had_at_least_one_in_common: seen_ack,
     };
-    let is_done = haves_added != state.haves_to_send || (state.seen_ack && state.in_vain >= 256);
+    let is_done = seen_ready || haves_added != state.haves_to_send || (state.seen_ack && state.in_vain >= 256);
     state.adjust_window_size();
 
     Ok((round, is_done))
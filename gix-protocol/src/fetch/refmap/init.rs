@@ -86,16 +86,17 @@ impl RefMap {
             })
             .collect();
 
-        // Assume sha1 if server says nothing, otherwise configure anything beyond sha1 in the local repo configuration
+        // Assume sha1 if server says nothing, otherwise use what the server advertises if we support it
         let object_hash = if let Some(object_format) = capabilities.capability("object-format").and_then(|c| c.value())
         {
             let object_format = object_format.to_str().map_err(|_| Error::UnknownObjectFormat {
                 format: object_format.into(),
             })?;
-            match object_format {
-                "sha1" => gix_hash::Kind::Sha1,
-                unknown => return Err(Error::UnknownObjectFormat { format: unknown.into() }),
-            }
+            object_format
+                .parse::<gix_hash::Kind>()
+                .map_err(|_| Error::UnknownObjectFormat {
+                    format: object_format.into(),
+                })?
         } else {
             gix_hash::Kind::Sha1
         };
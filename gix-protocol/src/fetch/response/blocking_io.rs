@@ -5,7 +5,9 @@ use crate::transport::{client::MessageKind, Protocol};
 
 use crate::fetch::{
     response,
-    response::{shallow_update_from_line, Acknowledgement, ShallowUpdate, WantedRef},
+    response::{
+        shallow_update_from_line, Acknowledgement, PackfileUri, ShallowUpdate, V1Parser, V1Progress, WantedRef,
+    },
     Response,
 };
 
@@ -34,77 +36,43 @@ fn parse_v2_section<'a, T>(
 impl Response {
     /// Parse a response of the given `version` of the protocol from `reader`.
     ///
-    /// `client_expects_pack` is only relevant for V1 stateful connections, and if `false`, causes us to stop parsing when seeing `NAK`,
-    /// and if `true` we will keep parsing until we get a pack as the client already signalled to the server that it's done.
-    /// This way of doing things allows us to exploit knowledge about more recent versions of the protocol, which keeps code easier
-    /// and more localized without having to support all the cruft that there is.
+    /// `client_sent_done` and `server_sends_pack_when_ready` are only relevant for V0 and V1, where the response itself doesn't
+    /// indicate where it ends, so we have to know what the server was told to predict what it will send.
+    /// `client_sent_done` is `true` if `done` was sent to the server in the request this is the response to, which is always
+    /// followed by a pack.
+    /// `server_sends_pack_when_ready` is `true` if the `no-done` capability is in use, which makes the server send a pack
+    /// right after the round in which it reported it is ready, without waiting for the client to send `done`.
     ///
-    /// `wants_to_negotiate` should be `false` for clones which is when we don't have sent any haves. The reason for this flag to exist
-    /// is to predict how to parse V1 output only, and neither `client_expects_pack` nor `wants_to_negotiate` are relevant for V2.
-    /// This ugliness is in place to avoid having to resort to an [an even more complex ugliness](https://github.com/git/git/blob/9e49351c3060e1fa6e0d2de64505b7becf157f28/fetch-pack.c#L583-L594)
-    /// that `git` has to use to predict how many acks are supposed to be read. We also genuinely hope that this covers it all….
+    /// Note that in V2, the response describes itself, so these flags are ignored.
     pub fn from_line_reader<'a>(
         version: Protocol,
         reader: &mut impl ExtendedBufRead<'a>,
-        client_expects_pack: bool,
-        wants_to_negotiate: bool,
+        client_sent_done: bool,
+        server_sends_pack_when_ready: bool,
     ) -> Result<Response, response::Error> {
         match version {
             Protocol::V0 | Protocol::V1 => {
                 let mut line = String::new();
-                let mut acks = Vec::<Acknowledgement>::new();
-                let mut shallows = Vec::<ShallowUpdate>::new();
-                let mut saw_ready = false;
-                let has_pack = 'lines: loop {
+                let mut parser = V1Parser::new(client_sent_done, server_sends_pack_when_ready);
+                loop {
                     line.clear();
-                    let peeked_line = match reader.peek_data_line() {
-                        Some(Ok(Ok(line))) => String::from_utf8_lossy(line),
-                        // This special case (hang/block forever) deals with a single NAK being a legitimate EOF sometimes
-                        // Note that this might block forever in stateful connections as there it's not really clear
-                        // if something will be following or not by just looking at the response. Instead you have to know
-                        // [a lot](https://github.com/git/git/blob/9e49351c3060e1fa6e0d2de64505b7becf157f28/fetch-pack.c#L583-L594)
-                        // to deal with this correctly.
-                        // For now this is acceptable, as V2 can be used as a workaround, which also is the default.
-                        Some(Err(err)) if err.kind() == io::ErrorKind::UnexpectedEof => break 'lines false,
-                        Some(Err(err)) => return Err(err.into()),
-                        Some(Ok(Err(err))) => return Err(err.into()),
-                        None => {
-                            // maybe we saw a shallow flush packet, let's reset and retry
-                            debug_assert_eq!(
-                                reader.stopped_at(),
-                                Some(MessageKind::Flush),
-                                "If this isn't a flush packet, we don't know what's going on"
-                            );
-                            reader.readline_str(&mut line)?;
+                    if reader.readline_str(&mut line)? == 0 {
+                        if reader.stopped_at() == Some(MessageKind::Flush) {
+                            // The end of the shallow-info section, acknowledgements follow.
                             reader.reset(Protocol::V1);
-                            match reader.peek_data_line() {
-                                Some(Ok(Ok(line))) => String::from_utf8_lossy(line),
-                                Some(Err(err)) => return Err(err.into()),
-                                Some(Ok(Err(err))) => return Err(err.into()),
-                                None => break 'lines false, // EOF
-                            }
-                        }
-                    };
-
-                    if Response::parse_v1_ack_or_shallow_or_assume_pack(&mut acks, &mut shallows, &peeked_line) {
-                        break 'lines true;
-                    }
-                    assert_ne!(reader.readline_str(&mut line)?, 0, "consuming a peeked line works");
-                    // When the server sends ready, we know there is going to be a pack so no need to stop early.
-                    saw_ready |= matches!(acks.last(), Some(Acknowledgement::Ready));
-                    if let Some(Acknowledgement::Nak) = acks.last().filter(|_| !client_expects_pack || !saw_ready) {
-                        if !wants_to_negotiate {
                             continue;
                         }
-                        break 'lines false;
+                        return Err(response::Error::Io(io::Error::new(
+                            io::ErrorKind::UnexpectedEof,
+                            "The server ended the response before the negotiation round was complete",
+                        )));
                     }
-                };
-                Ok(Response {
-                    acks,
-                    shallows,
-                    wanted_refs: vec![],
-                    has_pack,
-                })
+                    match parser.parse_line(&line)? {
+                        V1Progress::ReadMore => continue,
+                        V1Progress::RoundDone => break Ok(parser.into_response(false)),
+                        V1Progress::PackFollows => break Ok(parser.into_response(true)),
+                    }
+                }
             }
             Protocol::V2 => {
                 // NOTE: We only read acknowledgements and scrub to the pack file, until we have use for the other features
@@ -113,6 +81,7 @@ impl Response {
                 let mut acks = Vec::<Acknowledgement>::new();
                 let mut shallows = Vec::<ShallowUpdate>::new();
                 let mut wanted_refs = Vec::<WantedRef>::new();
+                let mut packfile_uris = Vec::<PackfileUri>::new();
                 let has_pack = 'section: loop {
                     line.clear();
                     if reader.readline_str(&mut line)? == 0 {
@@ -138,6 +107,11 @@ impl Response {
                                 break 'section false;
                             }
                         }
+                        "packfile-uris" => {
+                            if parse_v2_section(&mut line, reader, &mut packfile_uris, PackfileUri::from_line)? {
+                                break 'section false;
+                            }
+                        }
                         "packfile" => {
                             // what follows is the packfile itself, which can be read with a sideband enabled reader
                             break 'section true;
@@ -149,6 +123,7 @@ impl Response {
                     acks,
                     shallows,
                     wanted_refs,
+                    packfile_uris,
                     has_pack,
                 })
             }
@@ -71,6 +71,17 @@ pub struct WantedRef {
     pub path: BString,
 }
 
+/// A packfile-uri line received from the server, describing a pack that has to be downloaded in addition to the one
+/// sent by the server.
+#[derive(PartialEq, Eq, Debug, Hash, Ord, PartialOrd, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PackfileUri {
+    /// The checksum of the pack, which is the trailing hash of the pack file.
+    pub hash: gix_hash::ObjectId,
+    /// The location to download the pack from.
+    pub uri: BString,
+}
+
 /// Parse a `ShallowUpdate` from a `line` as received to the server.
 pub fn shallow_update_from_line(line: &str) -> Result<ShallowUpdate, Error> {
     match line.trim_end().split_once(' ') {
@@ -138,6 +149,20 @@ impl WantedRef {
     }
 }
 
+impl PackfileUri {
+    /// Parse a `PackfileUri` from a `line` as received from the server.
+    pub fn from_line(line: &str) -> Result<PackfileUri, Error> {
+        match line.trim_end().split_once(' ') {
+            Some((hash, uri)) => {
+                let hash = gix_hash::ObjectId::from_hex(hash.as_bytes())
+                    .map_err(|_| Error::UnknownLineType { line: line.to_owned() })?;
+                Ok(PackfileUri { hash, uri: uri.into() })
+            }
+            None => Err(Error::UnknownLineType { line: line.to_owned() }),
+        }
+    }
+}
+
 impl Response {
     /// Return true if the response has a pack which can be read next.
     pub fn has_pack(&self) -> bool {
@@ -197,34 +222,98 @@ impl Response {
     pub fn wanted_refs(&self) -> &[WantedRef] {
         &self.wanted_refs
     }
+
+    /// Return all packfile URIs [parsed previously][Response::from_line_reader()].
+    ///
+    /// The packs they point to have to be downloaded as they are not contained in the pack sent by the server.
+    pub fn packfile_uris(&self) -> &[PackfileUri] {
+        &self.packfile_uris
+    }
 }
 
+/// What to do after a line of a V1 response was parsed.
 #[cfg(any(feature = "async-client", feature = "blocking-client"))]
-impl Response {
-    /// with a friendly server, we just assume that a non-ack line is a pack line
-    /// which is our hint to stop here.
-    fn parse_v1_ack_or_shallow_or_assume_pack(
-        acks: &mut Vec<Acknowledgement>,
-        shallows: &mut Vec<ShallowUpdate>,
-        peeked_line: &str,
-    ) -> bool {
-        match Acknowledgement::from_line(peeked_line) {
-            Ok(ack) => match ack.id() {
-                Some(id) => {
-                    if !acks.iter().any(|a| a.id() == Some(id)) {
-                        acks.push(ack);
-                    }
-                }
-                None => acks.push(ack),
-            },
-            Err(_) => match shallow_update_from_line(peeked_line) {
-                Ok(shallow) => {
-                    shallows.push(shallow);
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum V1Progress {
+    /// The response continues, read the next line.
+    ReadMore,
+    /// The server finished this round of negotiation and now waits for more `have`s or `done`.
+    RoundDone,
+    /// The server is done with the negotiation, and the pack follows right after the line that was just parsed.
+    PackFollows,
+}
+
+/// Parse V1 responses line by line, knowing what was sent to the server so it's clear where the response ends.
+///
+/// With `multi_ack_detailed`, each round of `have`s terminated by a flush is answered with any amount of
+/// `ACK <id> common` and `ACK <id> ready` lines, terminated by a `NAK`. Once `done` was sent, the final line
+/// is either `ACK <id>` or `NAK`, followed by the pack. If `no-done` was negotiated, the server won't wait for `done`
+/// once it is ready and follows up the `NAK` of the round with the final `ACK <id>` and the pack.
+/// Shallow updates may precede the acknowledgements in the first round.
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+struct V1Parser {
+    client_sent_done: bool,
+    server_sends_pack_when_ready: bool,
+    saw_ready: bool,
+    acks: Vec<Acknowledgement>,
+    shallows: Vec<ShallowUpdate>,
+}
+
+#[cfg(any(feature = "async-client", feature = "blocking-client"))]
+impl V1Parser {
+    fn new(client_sent_done: bool, server_sends_pack_when_ready: bool) -> Self {
+        V1Parser {
+            client_sent_done,
+            server_sends_pack_when_ready,
+            saw_ready: false,
+            acks: Vec::new(),
+            shallows: Vec::new(),
+        }
+    }
+
+    fn parse_line(&mut self, line: &str) -> Result<V1Progress, Error> {
+        if line.starts_with("shallow ") || line.starts_with("unshallow ") {
+            self.shallows.push(shallow_update_from_line(line)?);
+            return Ok(V1Progress::ReadMore);
+        }
+        let ack = Acknowledgement::from_line(line)?;
+        // `ACK <id>` without a status is only sent when the negotiation is over.
+        let is_final_ack = matches!(ack, Acknowledgement::Common(_)) && line.trim_end().split(' ').count() == 2;
+        match ack {
+            Acknowledgement::Common(id) => {
+                if !self.acks.iter().any(|a| a.id() == Some(&id)) {
+                    self.acks.push(ack);
                 }
-                Err(_) => return true,
-            },
+            }
+            Acknowledgement::Ready => {
+                self.saw_ready = true;
+                self.acks.push(ack);
+            }
+            Acknowledgement::Nak => self.acks.push(ack),
+        }
+        Ok(if is_final_ack {
+            V1Progress::PackFollows
+        } else if ack == Acknowledgement::Nak {
+            if self.client_sent_done {
+                V1Progress::PackFollows
+            } else if self.saw_ready && self.server_sends_pack_when_ready {
+                V1Progress::ReadMore
+            } else {
+                V1Progress::RoundDone
+            }
+        } else {
+            V1Progress::ReadMore
+        })
+    }
+
+    fn into_response(self, has_pack: bool) -> Response {
+        Response {
+            acks: self.acks,
+            shallows: self.shallows,
+            wanted_refs: vec![],
+            packfile_uris: vec![],
+            has_pack,
         }
-        false
     }
 }
 
@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use crate::fetch::response::{Acknowledgement, ShallowUpdate, WantedRef};
+use crate::fetch::response::{Acknowledgement, PackfileUri, ShallowUpdate, WantedRef};
 
 /// Options for use in [`fetch()`](`crate::fetch()`)
 #[derive(Debug, Clone)]
@@ -16,6 +16,11 @@ pub struct Options<'a> {
     /// If `true`, if we fetch from a remote that only offers shallow clones, the operation will fail with an error
     /// instead of writing the shallow boundary to the shallow file.
     pub reject_shallow_remote: bool,
+    /// If set, the comma-separated list of protocols like `https,file` the server may use to send parts of the pack
+    /// as [URIs to download](crate::fetch::Response::packfile_uris()), if it supports this.
+    ///
+    /// Downloading the packs is left to the caller.
+    pub packfile_uri_protocols: Option<&'a str>,
 }
 
 /// For use in [`crate::Handshake::prepare_lsrefs_or_extract_refmap()`] and [`fetch`](crate::fetch()).
@@ -220,6 +225,7 @@ pub struct Response {
     pub(crate) acks: Vec<Acknowledgement>,
     pub(crate) shallows: Vec<ShallowUpdate>,
     pub(crate) wanted_refs: Vec<WantedRef>,
+    pub(crate) packfile_uris: Vec<PackfileUri>,
     pub(crate) has_pack: bool,
 }
 
@@ -8,6 +8,7 @@
 //!     - [list references](LsRefsCommand)
 //!          - create a mapping between [refspecs and references](fetch::RefMap)
 //!     - [receive a pack](fetch())
+//!     - [list bundle URIs](BundleUriCommand)
 //!
 //! ## Feature Flags
 #![cfg_attr(
@@ -30,6 +31,8 @@ pub enum Command {
     LsRefs,
     /// Fetch a pack.
     Fetch,
+    /// List bundles that can be downloaded before fetching.
+    BundleUri,
 }
 pub mod command;
 
@@ -69,5 +72,10 @@ pub mod ls_refs;
 #[cfg(any(feature = "blocking-client", feature = "async-client"))]
 pub use ls_refs::function::LsRefsCommand;
 
+///
+pub mod bundle_uri;
+#[cfg(any(feature = "blocking-client", feature = "async-client"))]
+pub use bundle_uri::function::BundleUriCommand;
+
 mod util;
 pub use util::*;
@@ -0,0 +1,154 @@
+mod list {
+    use bstr::ByteSlice;
+    use gix_protocol::bundle_uri::{Bundle, Heuristic, List, Mode};
+
+    fn list(lines: &[&str]) -> Result<List, gix_protocol::bundle_uri::parse::Error> {
+        List::from_lines(lines.iter().map(|line| line.as_bytes().as_bstr()))
+    }
+
+    #[test]
+    fn from_lines_with_creation_token_heuristic() -> crate::Result {
+        let list = list(&[
+            "bundle.version=1",
+            "bundle.mode=all",
+            "bundle.heuristic=creationToken",
+            "bundle.latest.uri=https://example.com/latest.bundle",
+            "bundle.latest.creationToken=2",
+            "bundle.base.uri=base.bundle",
+            "bundle.base.creationToken=1",
+            "bundle.no-uri.creationToken=3",
+        ])?;
+        assert_eq!(list.mode, Mode::All);
+        assert_eq!(list.heuristic, Some(Heuristic::CreationToken));
+        assert_eq!(
+            list.bundles,
+            [
+                Bundle {
+                    id: "latest".into(),
+                    uri: "https://example.com/latest.bundle".into(),
+                    creation_token: Some(2),
+                },
+                Bundle {
+                    id: "base".into(),
+                    uri: "base.bundle".into(),
+                    creation_token: Some(1),
+                }
+            ],
+            "bundles without URI are ignored"
+        );
+        assert_eq!(
+            list.bundles_in_application_order()
+                .into_iter()
+                .map(|b| b.id.to_string())
+                .collect::<Vec<_>>(),
+            ["base", "latest"],
+            "lower creation tokens are applied first"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn keys_are_case_insensitive_and_order_is_retained_without_heuristic() -> crate::Result {
+        let list = list(&[
+            "bundle.Version=1",
+            "bundle.MODE=any",
+            "bundle.b.URI=file:///b.bundle",
+            "bundle.a.uri=file:///a.bundle",
+        ])?;
+        assert_eq!(list.mode, Mode::Any);
+        assert_eq!(list.heuristic, None);
+        assert_eq!(
+            list.bundles_in_application_order()
+                .into_iter()
+                .map(|b| b.id.to_string())
+                .collect::<Vec<_>>(),
+            ["b", "a"]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unsupported_values_are_rejected() {
+        assert!(matches!(
+            list(&["bundle.version=2"]),
+            Err(gix_protocol::bundle_uri::parse::Error::UnsupportedVersion { .. })
+        ));
+        assert!(matches!(
+            list(&["bundle.mode=some"]),
+            Err(gix_protocol::bundle_uri::parse::Error::UnsupportedMode { .. })
+        ));
+        assert!(matches!(
+            list(&["bundle.a.uri=a.bundle", "bundle.a.creationToken=-1"]),
+            Err(gix_protocol::bundle_uri::parse::Error::InvalidCreationToken { .. })
+        ));
+        assert!(matches!(
+            list(&["no-separator"]),
+            Err(gix_protocol::bundle_uri::parse::Error::MalformedLine { .. })
+        ));
+    }
+}
+
+mod header {
+    use gix_protocol::bundle_uri::{header, Header};
+
+    fn id(hex: &str) -> gix_hash::ObjectId {
+        gix_hash::ObjectId::from_hex(hex.as_bytes()).expect("valid hex id")
+    }
+
+    #[test]
+    fn v2_with_prerequisites_and_refs() -> crate::Result {
+        let mut input = &b"# v2 git bundle
+-97c5a932b3940a09683e924ef6a92b31a6f7c6de some commit message
+7814e8a05a59c0cf5fb186661d1551c75d1299b5 refs/heads/main
+7814e8a05a59c0cf5fb186661d1551c75d1299b5 HEAD
+
+PACK"[..];
+        let header = Header::from_read(&mut input)?;
+        assert_eq!(
+            header,
+            Header {
+                object_hash: gix_hash::Kind::Sha1,
+                prerequisites: vec![id("97c5a932b3940a09683e924ef6a92b31a6f7c6de")],
+                refs: vec![
+                    ("refs/heads/main".into(), id("7814e8a05a59c0cf5fb186661d1551c75d1299b5")),
+                    ("HEAD".into(), id("7814e8a05a59c0cf5fb186661d1551c75d1299b5")),
+                ],
+            }
+        );
+        assert_eq!(input, b"PACK", "the reader is positioned at the pack");
+        Ok(())
+    }
+
+    #[test]
+    fn v3_with_object_format() -> crate::Result {
+        let mut input = &b"# v3 git bundle
+@object-format=sha1
+7814e8a05a59c0cf5fb186661d1551c75d1299b5 refs/heads/main
+
+"[..];
+        let header = Header::from_read(&mut input)?;
+        assert_eq!(header.object_hash, gix_hash::Kind::Sha1);
+        assert_eq!(header.refs.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_capabilities_and_signatures_are_rejected() {
+        assert!(matches!(
+            Header::from_read(&mut &b"# v3 git bundle\n@filter=blob:none\n\n"[..]),
+            Err(header::Error::UnsupportedCapability { .. })
+        ));
+        assert!(matches!(
+            Header::from_read(&mut &b"# v2 git bundle\n@object-format=sha1\n\n"[..]),
+            Err(header::Error::MalformedLine { .. })
+        ));
+        assert!(matches!(
+            Header::from_read(&mut &b"# v4 git bundle\n\n"[..]),
+            Err(header::Error::UnknownSignature { .. })
+        ));
+        assert!(matches!(
+            Header::from_read(&mut &b"# v2 git bundle\n7814e8a05a59c0cf5fb186661d1551c75d1299b5 refs/heads/main\n"[..]),
+            Err(header::Error::UnexpectedEof)
+        ));
+    }
+}
@@ -45,6 +45,24 @@ mod v1 {
                     "we don't enforce no-progress"
                 );
             }
+
+            #[test]
+            fn object_format_is_requested_unless_it_is_the_default() {
+                assert_eq!(
+                    Command::Fetch.default_features(
+                        gix_transport::Protocol::V1,
+                        &capabilities("ofs-delta object-format=sha256")
+                    ),
+                    &[("ofs-delta", None), ("object-format", Some("sha256".into()))]
+                );
+                assert_eq!(
+                    Command::Fetch.default_features(
+                        gix_transport::Protocol::V1,
+                        &capabilities("ofs-delta object-format=sha1")
+                    ),
+                    &[("ofs-delta", None)]
+                );
+            }
         }
     }
 }
@@ -117,6 +135,20 @@ mod v2 {
                     &[]
                 );
             }
+
+            #[test]
+            fn object_format_is_requested_unless_it_is_the_default() {
+                assert_eq!(
+                    Command::LsRefs
+                        .default_features(gix_transport::Protocol::V2, &capabilities("object-format", "sha256")),
+                    &[("object-format", Some("sha256".into()))]
+                );
+                assert_eq!(
+                    Command::LsRefs
+                        .default_features(gix_transport::Protocol::V2, &capabilities("object-format", "sha1")),
+                    &[]
+                );
+            }
         }
 
         mod validate {
@@ -153,6 +185,18 @@ mod v2 {
                 );
             }
 
+            #[test]
+            fn object_format_can_always_be_used() {
+                assert!(Command::LsRefs
+                    .validate_argument_prefixes(
+                        gix_transport::Protocol::V2,
+                        &capabilities("object-format", "sha256"),
+                        &[],
+                        &[("object-format", Some("sha256".into()))],
+                    )
+                    .is_ok());
+            }
+
             #[test]
             fn unknown_feature() {
                 assert_eq!(
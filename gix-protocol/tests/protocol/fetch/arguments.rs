@@ -418,4 +418,31 @@ mod v2 {
                 .as_bstr()
         );
     }
+
+    #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+    async fn packfile_uris() {
+        let mut out = Vec::new();
+        let mut t = transport(&mut out, false);
+        let mut arguments = arguments_v2(["packfile-uris"].iter().copied());
+        assert!(arguments.can_use_packfile_uris());
+        arguments.packfile_uris("https");
+
+        arguments.want(id("ff333369de1221f9bfbbe03a3a13e9a09bc1ffff"));
+        arguments.send(&mut t, true).await.expect("sending to buffer to work");
+        assert_eq!(
+            out.as_bstr(),
+            b"0012command=fetch
+0001000ethin-pack
+000eofs-delta
+0018packfile-uris https
+0032want ff333369de1221f9bfbbe03a3a13e9a09bc1ffff
+0009done
+0000"
+                .as_bstr()
+        );
+        assert!(
+            !arguments_v2(["does not matter for us here"].iter().copied()).can_use_packfile_uris(),
+            "the server has to advertise it"
+        );
+    }
 }
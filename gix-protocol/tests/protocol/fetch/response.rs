@@ -17,10 +17,10 @@ fn id(hex: &str) -> gix_hash::ObjectId {
 mod v1 {
     mod from_line_reader {
         #[cfg(feature = "blocking-client")]
-        use std::io::Read;
+        use std::io::{BufRead, Read};
 
         #[cfg(feature = "async-client")]
-        use futures_lite::io::AsyncReadExt;
+        use futures_lite::io::{AsyncBufReadExt, AsyncReadExt};
         use gix_protocol::fetch::{
             self,
             response::{Acknowledgement, ShallowUpdate},
@@ -108,7 +108,7 @@ mod v1 {
         async fn fetch_acks_without_pack() -> crate::Result {
             let mut provider = mock_reader("v1/fetch-no-pack.response");
             let r =
-                fetch::Response::from_line_reader(Protocol::V1, &mut provider.as_read_without_sidebands(), true, true)
+                fetch::Response::from_line_reader(Protocol::V1, &mut provider.as_read_without_sidebands(), false, true)
                     .await?;
             assert_eq!(
                 r.acknowledgements(),
@@ -118,6 +118,35 @@ mod v1 {
                     Acknowledgement::Nak,
                 ]
             );
+            assert!(!r.has_pack(), "the NAK ends the round as we didn't say we are done");
+            Ok(())
+        }
+
+        #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+        async fn fetch_acks_with_ready_stops_at_end_of_round_without_no_done() -> crate::Result {
+            let mut provider = mock_reader("v1/fetch.response");
+            let mut reader = provider.as_read_without_sidebands();
+            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader, false, false).await?;
+            assert_eq!(
+                r.acknowledgements(),
+                &[
+                    Acknowledgement::Common(id("6504930888c9c5337e7e065c964f87b60d16a7d7")),
+                    Acknowledgement::Common(id("fe17165c392110d1305674c06e4aec35728bfab7")),
+                    Acknowledgement::Common(id("f22743895a3024bb0c958335981439f1fa747d57")),
+                    Acknowledgement::Ready,
+                    Acknowledgement::Nak,
+                ]
+            );
+            assert!(
+                !r.has_pack(),
+                "without `no-done`, a stateful server waits for `done` even though it is ready"
+            );
+            let mut line = String::new();
+            reader.read_line(&mut line).await?;
+            assert_eq!(
+                line, "ACK f22743895a3024bb0c958335981439f1fa747d57\n",
+                "nothing past the end of the round is consumed as it might never arrive"
+            );
             Ok(())
         }
 
@@ -125,7 +154,7 @@ mod v1 {
         async fn fetch_acks_and_pack() -> crate::Result {
             let mut provider = mock_reader("v1/fetch.response");
             let mut reader = provider.as_read_without_sidebands();
-            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader, true, true).await?;
+            let r = fetch::Response::from_line_reader(Protocol::V1, &mut reader, false, true).await?;
             assert_eq!(
                 r.acknowledgements(),
                 &[
@@ -249,6 +278,28 @@ mod v2 {
             Ok(())
         }
 
+        #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
+        async fn clone_with_packfile_uris() -> crate::Result {
+            let mut provider = mock_reader("v2/clone-only-with-packfile-uris.response");
+            let mut reader = provider.as_read_without_sidebands();
+            let r = fetch::Response::from_line_reader(Protocol::V2, &mut reader, true, true).await?;
+            assert_eq!(
+                r.packfile_uris(),
+                &[fetch::response::PackfileUri {
+                    hash: id("2b2e8e18bd2c7d1e6d4a01d5d0c1b5e0f2bd5a6c"),
+                    uri: "https://cdn.example.com/packs/1.pack".into(),
+                }]
+            );
+            assert!(r.has_pack(), "the remaining objects are still sent as pack");
+            reader.set_progress_handler(Some(Box::new(|_is_err, _text| {
+                gix_transport::packetline::read::ProgressAction::Continue
+            })));
+            let mut buf = Vec::new();
+            let bytes_read = reader.read_to_end(&mut buf).await?;
+            assert_eq!(bytes_read, 876, "should be able to read the whole pack");
+            Ok(())
+        }
+
         #[maybe_async::test(feature = "blocking-client", async(feature = "async-client", async_std::test))]
         async fn shallow_clone() -> crate::Result {
             let mut provider = mock_reader("v2/clone-deepen-1.response");
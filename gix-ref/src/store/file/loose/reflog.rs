@@ -83,6 +83,9 @@ impl file::Store {
     }
 }
 
+///
+pub mod expire;
+
 ///
 pub mod create_or_update {
     use std::{
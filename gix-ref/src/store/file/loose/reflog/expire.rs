@@ -0,0 +1,144 @@
+use std::io::Write;
+
+use gix_object::bstr::ByteSlice;
+
+use crate::{
+    store_impl::{file, file::log},
+    FullNameRef,
+};
+
+/// The outcome of [`file::Store::reflog_expire()`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Outcome {
+    /// The amount of log entries that were kept.
+    pub num_kept: usize,
+    /// The amount of log entries that were removed.
+    pub num_removed: usize,
+}
+
+impl file::Store {
+    /// Rewrite the reflog of the reference `name`, removing each entry for which `remove(index, line)` returns `true`.
+    ///
+    /// `index` is the position of `line` counted from the most recent entry, which is `0`, similar to `HEAD@{<index>}`.
+    /// Lines that can't be parsed are always kept.
+    ///
+    /// The reference itself is locked using `lock_mode` while the log is rewritten to prevent concurrent updates
+    /// from appending to the log, and the new log is written next to the old one to be moved into place atomically.
+    /// The log file isn't touched if no entry was removed, and an empty log file remains if all entries were removed.
+    ///
+    /// Return `Ok(None)` if there is no reflog for `name`.
+    pub fn reflog_expire(
+        &self,
+        name: &FullNameRef,
+        lock_mode: gix_lock::acquire::Fail,
+        mut remove: impl FnMut(usize, &log::LineRef<'_>) -> bool,
+    ) -> Result<Option<Outcome>, Error> {
+        let log_path = self.reflog_path(name);
+        if !log_path.is_file() {
+            return Ok(None);
+        }
+        let _lock = self.lock_reference_for_reflog(name, lock_mode)?;
+
+        let content = match std::fs::read(&log_path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(Error::Io(err)),
+        };
+
+        let lines: Vec<_> = content.lines_with_terminator().collect();
+        let mut keep = vec![true; lines.len()];
+        for (index, (line, keep)) in lines.iter().zip(keep.iter_mut()).rev().enumerate() {
+            if let Ok(line) = log::LineRef::from_bytes(line) {
+                *keep = !remove(index, &line);
+            }
+        }
+        let num_kept = keep.iter().filter(|keep| **keep).count();
+        let out = Outcome {
+            num_kept,
+            num_removed: lines.len() - num_kept,
+        };
+        if out.num_removed == 0 {
+            return Ok(Some(out));
+        }
+
+        let mut file =
+            gix_lock::File::acquire_to_update_resource(&log_path, gix_lock::acquire::Fail::Immediately, None).map_err(
+                |err| Error::LockAcquire {
+                    source: err,
+                    full_name: name.as_bstr().to_owned(),
+                },
+            )?;
+        for line in lines.iter().zip(keep).filter_map(|(line, keep)| keep.then_some(line)) {
+            file.write_all(line)?;
+            if !line.ends_with(b"\n") {
+                file.write_all(b"\n")?;
+            }
+        }
+        file.commit().map_err(|err| Error::Io(err.error))?;
+        Ok(Some(out))
+    }
+
+    /// Delete the reflog of the reference `name` while holding the reference lock acquired with `lock_mode`,
+    /// along with all directories that became empty as a result.
+    ///
+    /// Return `Ok(false)` if there was no reflog to delete. The reference itself isn't touched.
+    pub fn reflog_delete(&self, name: &FullNameRef, lock_mode: gix_lock::acquire::Fail) -> Result<bool, Error> {
+        let (reflog_root, relative_path) = self.reflog_base_and_relative_path(name);
+        let log_path = reflog_root.join(relative_path);
+        if !log_path.is_file() {
+            return Ok(false);
+        }
+        let _lock = self.lock_reference_for_reflog(name, lock_mode)?;
+        match std::fs::remove_file(&log_path) {
+            Ok(()) => {}
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+            Err(err) => return Err(Error::Io(err)),
+        }
+        gix_tempfile::remove_dir::empty_upward_until_boundary(
+            log_path.parent().expect("never without parent"),
+            &reflog_root,
+        )
+        .ok();
+        Ok(true)
+    }
+
+    fn lock_reference_for_reflog(
+        &self,
+        name: &FullNameRef,
+        lock_mode: gix_lock::acquire::Fail,
+    ) -> Result<gix_lock::Marker, Error> {
+        let (base, relative_path) = self.reference_path_with_base(name);
+        let reference_path = base.join(relative_path.as_ref());
+        // Only allow creating (and thus removing) leading directories if they don't exist, as otherwise empty directories
+        // like `refs/heads` of packed references would be removed along with the lock.
+        let boundary = reference_path
+            .parent()
+            .is_some_and(|dir| !dir.is_dir())
+            .then(|| base.into_owned());
+        gix_lock::Marker::acquire_to_hold_resource(&reference_path, lock_mode, boundary).map_err(|err| {
+            Error::LockAcquire {
+                source: err,
+                full_name: name.as_bstr().to_owned(),
+            }
+        })
+    }
+}
+
+mod error {
+    use gix_object::bstr::BString;
+
+    /// The error returned by [`file::Store::reflog_expire()`](crate::file::Store::reflog_expire()) and
+    /// [`file::Store::reflog_delete()`](crate::file::Store::reflog_delete()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not lock reference {full_name:?} or its reflog")]
+        LockAcquire {
+            source: gix_lock::acquire::Error,
+            full_name: BString,
+        },
+        #[error("Could not read or write the reflog")]
+        Io(#[from] std::io::Error),
+    }
+}
+pub use error::Error;
@@ -67,3 +67,145 @@ mod iter_rev {
         Ok(())
     }
 }
+
+mod expire {
+    use gix_ref::file::log;
+
+    fn writable_store() -> crate::Result<(gix_testtools::tempfile::TempDir, crate::file::Store)> {
+        let dir = gix_testtools::scripted_fixture_writable_standalone("make_repo_for_reflog.sh")?;
+        let store = crate::file::Store::at(dir.path().join(".git"), Default::default());
+        Ok((dir, store))
+    }
+
+    fn messages(store: &crate::file::Store, name: &str) -> crate::Result<Vec<String>> {
+        let mut buf = Vec::new();
+        Ok(store
+            .reflog_iter(name, &mut buf)?
+            .expect("exists")
+            .map(|line| line.map(|line| line.message.to_string()))
+            .collect::<Result<_, _>>()?)
+    }
+
+    #[test]
+    fn by_index_from_most_recent() -> crate::Result {
+        let (_tmp, store) = writable_store()?;
+        let before = messages(&store, "HEAD")?;
+        let outcome = store
+            .reflog_expire(
+                "HEAD".try_into()?,
+                gix_lock::acquire::Fail::Immediately,
+                |index, _line| index == 1,
+            )?
+            .expect("log exists");
+        assert_eq!(
+            outcome,
+            log::expire::Outcome {
+                num_kept: before.len() - 1,
+                num_removed: 1
+            }
+        );
+
+        let mut expected = before;
+        expected.remove(expected.len() - 2);
+        assert_eq!(
+            messages(&store, "HEAD")?,
+            expected,
+            "the second most recent entry is gone"
+        );
+        assert!(
+            !store.git_dir().join("HEAD.lock").exists(),
+            "the lock is released once the log was rewritten"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn by_time_possibly_removing_everything() -> crate::Result {
+        let (_tmp, store) = writable_store()?;
+        let outcome = store
+            .reflog_expire(
+                "refs/heads/old".try_into()?,
+                gix_lock::acquire::Fail::Immediately,
+                |_index, line| line.signature.time().expect("valid").seconds < 1727013176,
+            )?
+            .expect("log exists");
+        assert_eq!(outcome.num_removed, 5, "the five oldest entries are removed");
+        assert_eq!(
+            messages(&store, "refs/heads/old")?.first().map(String::as_str),
+            Some("rebase (pick): Get diff between trees")
+        );
+
+        let outcome = store
+            .reflog_expire(
+                "refs/heads/old".try_into()?,
+                gix_lock::acquire::Fail::Immediately,
+                |_, _| true,
+            )?
+            .expect("log exists");
+        assert_eq!(outcome.num_kept, 0);
+        assert!(
+            store.reflog_exists("refs/heads/old")?,
+            "an empty log remains, just like in git"
+        );
+        assert!(messages(&store, "refs/heads/old")?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn missing_log_yields_none_and_unchanged_log_is_untouched() -> crate::Result {
+        let (_tmp, store) = writable_store()?;
+        assert!(store
+            .reflog_expire(
+                "refs/heads/does-not-exist".try_into()?,
+                gix_lock::acquire::Fail::Immediately,
+                |_, _| true,
+            )?
+            .is_none());
+
+        let log_path = store.git_dir().join("logs").join("HEAD");
+        let before = std::fs::read(&log_path)?;
+        let outcome = store
+            .reflog_expire("HEAD".try_into()?, gix_lock::acquire::Fail::Immediately, |_, _| false)?
+            .expect("log exists");
+        assert_eq!(outcome.num_removed, 0);
+        assert_eq!(std::fs::read(&log_path)?, before);
+        Ok(())
+    }
+
+    #[test]
+    fn delete_removes_the_log_but_keeps_the_reference() -> crate::Result {
+        let (_tmp, store) = writable_store()?;
+        assert!(store.reflog_delete("refs/heads/old".try_into()?, gix_lock::acquire::Fail::Immediately)?);
+        assert!(!store.reflog_exists("refs/heads/old")?);
+        assert!(store.reflog_exists("refs/heads/main")?, "other logs are unaffected");
+        assert!(store.try_find("old")?.is_some(), "the reference itself remains");
+        assert!(!store.git_dir().join("refs/heads/old.lock").exists());
+
+        assert!(
+            !store.reflog_delete("refs/heads/old".try_into()?, gix_lock::acquire::Fail::Immediately)?,
+            "there is nothing left to delete"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn locking_references_keeps_existing_empty_directories() -> crate::Result {
+        let (_tmp, store) = writable_store()?;
+        let tags_dir = store.git_dir().join("refs").join("tags");
+        assert!(tags_dir.is_dir(), "an empty directory created by git init");
+        std::fs::create_dir_all(store.git_dir().join("logs/refs/tags"))?;
+        std::fs::write(store.git_dir().join("logs/refs/tags/packed"), b"")?;
+        assert_eq!(
+            store
+                .reflog_expire(
+                    "refs/tags/packed".try_into()?,
+                    gix_lock::acquire::Fail::Immediately,
+                    |_, _| true
+                )?
+                .map(|outcome| outcome.num_kept),
+            Some(0)
+        );
+        assert!(tags_dir.is_dir(), "it wasn't removed along with the lock");
+        Ok(())
+    }
+}
@@ -50,10 +50,12 @@ pub mod write {
         /// after applying all `updates`.
         ///
         /// If this leaves the list of shallow commits empty, the file is removed.
+        /// Duplicate commits are written only once.
         ///
         /// ### Deviation
         ///
-        /// Git also prunes the set of shallow commits while writing, we don't until we support some sort of pruning.
+        /// Git also prunes commits that don't exist anymore while writing, which is left to the caller here as it requires
+        /// access to the object database.
         pub fn write(
             mut file: gix_lock::File,
             shallow_commits: Option<Vec<gix_hash::ObjectId>>,
@@ -68,28 +70,25 @@ pub mod write {
                     Update::Unshallow(id) => shallow_commits.retain(|oid| oid != id),
                 }
             }
-            if shallow_commits.is_empty() {
-                std::fs::remove_file(file.resource_path())?;
-                drop(file);
-                return Ok(());
-            }
-
             if shallow_commits.is_empty() {
                 if let Err(err) = std::fs::remove_file(file.resource_path()) {
                     if err.kind() != std::io::ErrorKind::NotFound {
                         return Err(err.into());
                     }
                 }
-            } else {
-                shallow_commits.sort();
-                let mut buf = Vec::<u8>::new();
-                for commit in shallow_commits {
-                    commit.write_hex_to(&mut buf).map_err(Error::Io)?;
-                    buf.push(b'\n');
-                }
-                file.write_all(&buf).map_err(Error::Io)?;
-                file.flush()?;
+                drop(file);
+                return Ok(());
+            }
+
+            shallow_commits.sort();
+            shallow_commits.dedup();
+            let mut buf = Vec::<u8>::new();
+            for commit in shallow_commits {
+                commit.write_hex_to(&mut buf).map_err(Error::Io)?;
+                buf.push(b'\n');
             }
+            file.write_all(&buf).map_err(Error::Io)?;
+            file.flush()?;
             file.commit()?;
             Ok(())
         }
@@ -4,6 +4,8 @@
 
 use std::{borrow::Cow, collections::BTreeMap};
 
+use bstr::{BStr, BString};
+
 /// All relevant information about a git module, typically from `.gitmodules` files.
 ///
 /// Note that overrides from other configuration might be relevant, which is why this type
@@ -22,6 +24,9 @@ pub mod config;
 ///
 pub mod is_active_platform;
 
+///
+pub mod relative_url;
+
 /// A platform to keep the state necessary to perform repeated active checks, created by [File::is_active_platform()].
 pub struct IsActivePlatform {
     pub(crate) search: Option<gix_pathspec::Search>,
@@ -80,6 +85,96 @@ impl File {
         self.config.append(config_to_append);
         self
     }
+
+    /// Add a submodule with `name`, located at `path` relative to the superproject and cloned from `url`,
+    /// after [validating](gix_validate::submodule::name()) the `name`.
+    ///
+    /// It's an error if a submodule with `name` or with `path` exists already.
+    pub fn add(&mut self, name: &BStr, path: &BStr, url: &BStr) -> Result<(), edit::Error> {
+        let name = gix_validate::submodule::name(name)?;
+        if self.names().any(|existing| existing == name) {
+            return Err(edit::Error::NameExists { name: name.to_owned() });
+        }
+        if let Some(existing) = self.name_by_path(path) {
+            return Err(edit::Error::PathExists {
+                path: path.to_owned(),
+                name: existing.to_owned(),
+            });
+        }
+        let mut section = self
+            .config
+            .new_section("submodule", Some(Cow::Owned(name.to_owned())))?;
+        section.push("path".try_into().expect("valid"), Some(path));
+        section.push("url".try_into().expect("valid"), Some(url));
+        Ok(())
+    }
+
+    /// Change the `path` of the submodule with `name`.
+    ///
+    /// It's an error if there is no submodule with `name`, or if another submodule is located at `path` already.
+    pub fn set_path(&mut self, name: &BStr, path: &BStr) -> Result<(), edit::Error> {
+        if let Some(existing) = self.name_by_path(path).filter(|existing| *existing != name) {
+            return Err(edit::Error::PathExists {
+                path: path.to_owned(),
+                name: existing.to_owned(),
+            });
+        }
+        let ours = self.config.meta_owned();
+        let mut section = self
+            .config
+            .section_mut_filter("submodule", Some(name), |meta| std::ptr::eq(meta, &*ours))
+            .ok()
+            .flatten()
+            .ok_or_else(|| edit::Error::NameMissing { name: name.to_owned() })?;
+        section.set("path".try_into().expect("valid"), path);
+        Ok(())
+    }
+
+    /// Remove all information about the submodule with `name`, and return `true` if it was present.
+    pub fn remove(&mut self, name: &BStr) -> bool {
+        let ours = self.config.meta_owned();
+        let mut removed = false;
+        while self
+            .config
+            .remove_section_filter("submodule", Some(name), |meta| std::ptr::eq(meta, &*ours))
+            .is_some()
+        {
+            removed = true;
+        }
+        removed
+    }
+
+    /// Serialize the module information as it would be stored in a `.gitmodules` file, without any overrides
+    /// from other configuration.
+    pub fn to_bstring(&self) -> BString {
+        let ours = self.config.meta();
+        let mut buf = Vec::new();
+        self.config
+            .write_to_filter(&mut buf, |section| std::ptr::eq(section.meta(), ours))
+            .expect("io error impossible");
+        buf.into()
+    }
+}
+
+///
+pub mod edit {
+    use bstr::BString;
+
+    /// The error returned by [File::add()](crate::File::add()) and [File::set_path()](crate::File::set_path()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        InvalidName(#[from] gix_validate::submodule::name::Error),
+        #[error("A submodule named '{name}' exists already")]
+        NameExists { name: BString },
+        #[error("There is no submodule named '{name}'")]
+        NameMissing { name: BString },
+        #[error("The path '{path}' is used by submodule '{name}' already")]
+        PathExists { path: BString, name: BString },
+        #[error(transparent)]
+        SectionHeader(#[from] gix_config::parse::section::header::Error),
+    }
 }
 
 ///
@@ -0,0 +1,111 @@
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+/// The error returned by [resolve()].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Cannot strip one component off url '{url}'")]
+    StripComponent { url: BString },
+}
+
+/// Return `true` if `url` is relative to the URL of the superproject, which is the case if it starts with `./` or `../`.
+pub fn is_relative(url: &BStr) -> bool {
+    starts_with_dot_slash(url) || starts_with_dot_dot_slash(url)
+}
+
+/// Resolve `url` against `remote_url`, the URL of the default remote of the superproject, like `git submodule` does
+/// for submodule URLs that are [relative](is_relative()).
+///
+/// Each leading `../` in `url` removes the last component of `remote_url`, which may also be the path of an `scp`-like
+/// URL like `user@host:repo`, and leading `./` are ignored. If `url` isn't local or is absolute, it's returned unchanged.
+/// If `remote_url` is a relative path itself, the result is relative as well, and `up_path` is prepended if set.
+/// This is useful to make it relative to the worktree of the submodule instead, with `up_path` being `../`
+/// for each component of the submodule path.
+pub fn resolve(remote_url: &BStr, url: &BStr, up_path: Option<&BStr>) -> Result<BString, Error> {
+    if !is_local_not_ssh(url) || is_absolute_path(url) {
+        return Ok(url.to_owned());
+    }
+
+    let mut remote_url = remote_url.to_owned();
+    if remote_url.last().is_some_and(|b| is_dir_sep(*b)) {
+        remote_url.pop();
+    }
+    let remote_is_relative = is_local_not_ssh(remote_url.as_ref()) && !is_absolute_path(remote_url.as_ref());
+    if remote_is_relative && !is_relative(remote_url.as_ref()) {
+        remote_url.insert_str(0, "./");
+    }
+
+    let mut url = url.as_bytes();
+    let mut colon_separated = false;
+    loop {
+        if starts_with_dot_dot_slash(url.as_bstr()) {
+            url = &url[3..];
+            colon_separated |= chop_last_dir(&mut remote_url, remote_is_relative)?;
+        } else if starts_with_dot_slash(url.as_bstr()) {
+            url = &url[2..];
+        } else {
+            break;
+        }
+    }
+
+    let mut out = remote_url;
+    out.push(if colon_separated { b':' } else { b'/' });
+    out.push_str(url);
+    if url.ends_with(b"/") {
+        out.pop();
+    }
+    if starts_with_dot_slash(out.as_ref()) {
+        out.drain(..2);
+    }
+    if let Some(up_path) = up_path.filter(|_| remote_is_relative) {
+        out.insert_str(0, up_path);
+    }
+    Ok(out)
+}
+
+/// Remove the last directory of `url`, and return `true` if it was separated by a colon like in `scp`-like URLs.
+fn chop_last_dir(url: &mut BString, is_relative: bool) -> Result<bool, Error> {
+    if let Some(pos) = url.iter().rposition(|b| is_dir_sep(*b)) {
+        url.truncate(pos);
+        return Ok(false);
+    }
+    if let Some(pos) = url.rfind_byte(b':') {
+        url.truncate(pos);
+        return Ok(true);
+    }
+    if is_relative || url == "." {
+        return Err(Error::StripComponent { url: url.clone() });
+    }
+    *url = ".".into();
+    Ok(false)
+}
+
+fn starts_with_dot_slash(url: &BStr) -> bool {
+    url.len() >= 2 && url[0] == b'.' && is_dir_sep(url[1])
+}
+
+fn starts_with_dot_dot_slash(url: &BStr) -> bool {
+    url.len() >= 3 && url[..2] == *b".." && is_dir_sep(url[2])
+}
+
+/// Like in Git, a URL is local if it has no colon, or a slash before its first colon, or a drive prefix on Windows.
+fn is_local_not_ssh(url: &BStr) -> bool {
+    match url.find_byte(b':') {
+        None => true,
+        Some(colon) => {
+            url.find_byte(b'/').is_some_and(|slash| slash < colon) || (cfg!(windows) && has_dos_drive_prefix(url))
+        }
+    }
+}
+
+fn is_absolute_path(url: &BStr) -> bool {
+    url.first() == Some(&b'/') || (cfg!(windows) && (has_dos_drive_prefix(url) || url.first() == Some(&b'\\')))
+}
+
+fn has_dos_drive_prefix(url: &BStr) -> bool {
+    url.len() >= 2 && url[0].is_ascii_alphabetic() && url[1] == b':'
+}
+
+fn is_dir_sep(b: u8) -> bool {
+    b == b'/' || (cfg!(windows) && b == b'\\')
+}
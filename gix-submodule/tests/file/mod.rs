@@ -450,4 +450,96 @@ mod append_submodule_overrides {
     }
 }
 
+mod edit {
+    use std::str::FromStr;
+
+    use bstr::ByteSlice;
+
+    use crate::file::submodule;
+
+    #[test]
+    fn add_appends_a_section_with_path_and_url() -> crate::Result {
+        let mut module = submodule("[submodule.a]\n\tpath = a\n\turl = https://example.com/a\n");
+        module.add("b".into(), "dir/b".into(), "../b".into())?;
+        assert_eq!(module.names().collect::<Vec<_>>(), ["a", "b"]);
+        assert_eq!(module.path("b".into())?.as_ref(), "dir/b");
+        assert_eq!(module.url("b".into())?.to_bstring(), "../b");
+        assert_eq!(
+            module.to_bstring(),
+            "[submodule.a]\n\tpath = a\n\turl = https://example.com/a\n[submodule \"b\"]\n\tpath = dir/b\n\turl = ../b\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn add_rejects_invalid_names_and_duplicates() {
+        let mut module = submodule("[submodule.a]\n\tpath = a\n\turl = ../a\n");
+        for name in ["../x", "x/../../y", "..", "x/.."] {
+            assert!(
+                matches!(
+                    module.add(name.into(), "x".into(), "../x".into()),
+                    Err(gix_submodule::edit::Error::InvalidName(_))
+                ),
+                "{name} must be rejected as it could be used to escape the modules directory"
+            );
+        }
+        assert!(matches!(
+            module.add("a".into(), "other".into(), "../a".into()),
+            Err(gix_submodule::edit::Error::NameExists { .. })
+        ));
+        assert!(matches!(
+            module.add("b".into(), "a".into(), "../b".into()),
+            Err(gix_submodule::edit::Error::PathExists { .. })
+        ));
+        assert_eq!(module.names().count(), 1, "nothing was added");
+    }
+
+    #[test]
+    fn set_path() -> crate::Result {
+        let mut module = submodule("[submodule.a]\n\tpath = a\n[submodule.b]\n\tpath = b\n");
+        module.set_path("a".into(), "new/a".into())?;
+        assert_eq!(module.path("a".into())?.as_ref(), "new/a");
+        assert_eq!(module.name_by_path("new/a".into()), Some("a".into()));
+        module.set_path("a".into(), "new/a".into())?;
+
+        assert!(matches!(
+            module.set_path("a".into(), "b".into()),
+            Err(gix_submodule::edit::Error::PathExists { .. })
+        ));
+        assert!(matches!(
+            module.set_path("missing".into(), "c".into()),
+            Err(gix_submodule::edit::Error::NameMissing { .. })
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn remove() {
+        let mut module =
+            submodule("[submodule.a]\n\tpath = a\n[submodule.b]\n\tpath = b\n[submodule.a]\n\turl = ../a\n");
+        assert!(module.remove("a".into()), "all sections are removed");
+        assert!(!module.remove("a".into()));
+        assert_eq!(module.names().collect::<Vec<_>>(), ["b"]);
+        assert_eq!(module.to_bstring(), "[submodule.b]\n\tpath = b\n");
+    }
+
+    #[test]
+    fn to_bstring_excludes_overrides() -> crate::Result {
+        let mut module = submodule("[submodule.a]\n\tpath = a\n\turl = ../a\n");
+        let repo_config = gix_config::File::from_str("[submodule.a]\n\turl = https://example.com/a\n\tactive = true")?;
+        module.append_submodule_overrides(&repo_config);
+        assert_eq!(
+            module.url("a".into())?.to_bstring(),
+            "https://example.com/a",
+            "overrides are visible"
+        );
+        assert_eq!(
+            module.to_bstring().to_str_lossy(),
+            "[submodule.a]\n\tpath = a\n\turl = ../a\n",
+            "but don't end up in the serialized form"
+        );
+        Ok(())
+    }
+}
+
 mod baseline;
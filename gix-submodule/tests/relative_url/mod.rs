@@ -0,0 +1,94 @@
+use bstr::ByteSlice;
+use gix_submodule::relative_url::{is_relative, resolve};
+
+fn resolved(remote_url: &str, url: &str) -> String {
+    resolve(remote_url.into(), url.into(), None)
+        .expect("resolvable")
+        .to_str_lossy()
+        .into_owned()
+}
+
+#[test]
+fn is_relative_only_with_leading_dots() {
+    assert!(is_relative("./sub".into()));
+    assert!(is_relative("../sub".into()));
+    assert!(!is_relative("sub".into()));
+    assert!(!is_relative("/path/to/sub".into()));
+    assert!(!is_relative(".../sub".into()));
+    assert!(!is_relative("https://example.com/sub".into()));
+}
+
+#[test]
+fn relative_remote_urls_stay_relative() {
+    for (remote_url, url, expected) in [
+        ("../foo/bar", "../submodule", "../foo/submodule"),
+        ("../foo/submodule", "../submodule", "../foo/submodule"),
+        ("../foo", "../submodule", "../submodule"),
+        ("./foo/bar", "../submodule", "foo/submodule"),
+        ("./foo", "../submodule", "submodule"),
+        ("foo/bar", "../submodule", "foo/submodule"),
+        ("foo", "../submodule", "submodule"),
+        ("foo", "./submodule", "foo/submodule"),
+    ] {
+        assert_eq!(resolved(remote_url, url), expected, "{remote_url} + {url}");
+    }
+}
+
+#[test]
+fn absolute_paths_and_urls() {
+    for (remote_url, url, expected) in [
+        ("//somewhere else/repo", "../subrepo", "//somewhere else/subrepo"),
+        ("/u//trailing/slash/", "../subrepo", "/u//trailing/subrepo"),
+        ("/path/to/super", "../sub/", "/path/to/sub"),
+        ("/path/to/super", "./sub", "/path/to/super/sub"),
+        ("/path/.", "../.", "/path/."),
+        ("file:///tmp/repo", "../subrepo", "file:///tmp/subrepo"),
+        ("helper:://hostname/repo", "../subrepo", "helper:://hostname/subrepo"),
+        ("ssh://hostname/repo", "../subrepo", "ssh://hostname/subrepo"),
+        ("ssh://hostname:22/repo", "../subrepo", "ssh://hostname:22/subrepo"),
+        (
+            "https://example.com/org/super.git",
+            "../sub.git",
+            "https://example.com/org/sub.git",
+        ),
+    ] {
+        assert_eq!(resolved(remote_url, url), expected, "{remote_url} + {url}");
+    }
+}
+
+#[test]
+fn scp_like_urls_may_lose_their_path() {
+    assert_eq!(
+        resolved("user@host:path/to/repo", "../subrepo"),
+        "user@host:path/to/subrepo"
+    );
+    assert_eq!(resolved("user@host:repo", "../subrepo"), "user@host:subrepo");
+}
+
+#[test]
+fn up_path_is_prepended_only_to_relative_results() {
+    let up_path = Some("../../".into());
+    assert_eq!(
+        resolve("../super".into(), "../sub".into(), up_path).expect("valid"),
+        "../../../sub"
+    );
+    assert_eq!(
+        resolve("/path/to/super".into(), "../sub".into(), up_path).expect("valid"),
+        "/path/to/sub"
+    );
+}
+
+#[test]
+fn non_local_or_absolute_urls_are_returned_unchanged() {
+    assert_eq!(resolved("/path/to/super", "/elsewhere/sub"), "/elsewhere/sub");
+    assert_eq!(
+        resolved("/path/to/super", "https://example.com/sub"),
+        "https://example.com/sub"
+    );
+}
+
+#[test]
+fn too_many_components_to_strip_fail() {
+    let err = resolve("foo".into(), "../../sub".into(), None).unwrap_err();
+    assert_eq!(err.to_string(), "Cannot strip one component off url '.'");
+}
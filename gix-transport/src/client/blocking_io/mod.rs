@@ -10,6 +10,9 @@ pub mod http;
 mod bufread_ext;
 pub use bufread_ext::{ExtendedBufRead, HandleProgress, ReadlineBufRead};
 
+///
+pub mod remote_helper;
+
 mod request;
 pub use request::RequestWriter;
 
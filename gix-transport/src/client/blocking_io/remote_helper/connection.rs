@@ -0,0 +1,221 @@
+use std::{any::Any, borrow::Cow, error::Error, io::Read, process};
+
+use bstr::BStr;
+
+use super::{Helper, Options};
+use crate::{
+    client::{
+        self,
+        blocking_io::{RequestWriter, SetServiceResponse},
+        git::blocking_io::Connection as GitConnection,
+        MessageKind, WriteMode,
+    },
+    Protocol, Service,
+};
+
+/// A transport which spawns a remote helper on demand and lets it connect to the desired service,
+/// to speak the git protocol through it.
+///
+/// It can only be instantiated using [`connect()`].
+pub struct Connection {
+    url: gix_url::Url,
+    options: Options,
+    desired_version: Protocol,
+    connection: Option<GitConnection<Box<dyn Read + Send>, process::ChildStdin>>,
+    child: Option<process::Child>,
+    trace: bool,
+}
+
+impl client::TransportWithoutIO for Connection {
+    fn to_url(&self) -> Cow<'_, BStr> {
+        Cow::Owned(self.url.to_bstring())
+    }
+
+    fn connection_persists_across_multiple_requests(&self) -> bool {
+        true
+    }
+
+    fn configure(&mut self, _config: &dyn Any) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+        Ok(())
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        self.connection.take();
+        if let Some(mut child) = self.child.take() {
+            // Closing the input should have made the helper exit, but it may still be running after errors or interrupts.
+            child.kill().ok();
+            child.wait().ok();
+        }
+    }
+}
+
+impl client::blocking_io::Transport for Connection {
+    fn handshake<'a>(
+        &mut self,
+        service: Service,
+        extra_parameters: &'a [(&'a str, Option<&'a str>)],
+    ) -> Result<SetServiceResponse<'_>, client::Error> {
+        let envs = if self.desired_version != Protocol::V1 {
+            vec![("GIT_PROTOCOL", format!("version={}", self.desired_version as usize))]
+        } else {
+            Vec::new()
+        };
+        let mut helper = Helper::spawn_with_envs(&self.url, &self.options, &envs, self.trace)
+            .map_err(client::Error::RemoteHelper)?;
+        let capabilities = helper.capabilities();
+        let stateless = if self.desired_version == Protocol::V2 {
+            capabilities.stateless_connect
+        } else {
+            !capabilities.connect && capabilities.stateless_connect
+        };
+        if !(capabilities.connect || capabilities.stateless_connect)
+            || !helper
+                .connect(service, stateless)
+                .map_err(client::Error::RemoteHelper)?
+        {
+            return Err(client::Error::RemoteHelper(super::Error::ConnectionUnsupported {
+                service: service.as_str(),
+            }));
+        }
+
+        let (child, stdout, stdin) = helper.into_parts();
+        let stdout: Box<dyn Read + Send> = if stateless {
+            Box::new(SkipResponseEnd::new(stdout))
+        } else {
+            Box::new(stdout)
+        };
+        self.child = Some(child);
+        self.connection = Some(GitConnection::new_for_spawned_process(
+            stdout,
+            stdin,
+            self.desired_version,
+            self.url.path.clone(),
+            self.trace,
+        ));
+        self.connection
+            .as_mut()
+            .expect("connection to be there right after setting it")
+            .handshake(service, extra_parameters)
+    }
+
+    fn request(
+        &mut self,
+        write_mode: WriteMode,
+        on_into_read: MessageKind,
+        trace: bool,
+    ) -> Result<RequestWriter<'_>, client::Error> {
+        self.connection
+            .as_mut()
+            .ok_or(client::Error::MissingHandshake)?
+            .request(write_mode, on_into_read, trace)
+    }
+}
+
+/// Connect to the remote helper program for `url`, which must use a scheme that isn't built-in,
+/// and let it connect to the service requested during the handshake.
+///
+/// `stateless-connect` is preferred if `desired_version` is V2, and `connect` is used otherwise.
+/// If `trace` is `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
+///
+/// This will spawn the helper program on handshake.
+pub fn connect(
+    url: gix_url::Url,
+    desired_version: Protocol,
+    options: Options,
+    trace: bool,
+) -> Result<Connection, super::Error> {
+    if !matches!(url.scheme, gix_url::Scheme::Ext(_)) {
+        return Err(super::Error::UnsupportedScheme(url));
+    }
+    Ok(Connection {
+        url,
+        options,
+        desired_version,
+        connection: None,
+        child: None,
+        trace,
+    })
+}
+
+/// Remove `response-end` packet lines sent after each response via `stateless-connect`,
+/// as the response is already known to be complete after the final flush packet.
+struct SkipResponseEnd<R> {
+    read: R,
+    /// The amount of bytes of the current packet line that are yet to be passed on.
+    remaining: usize,
+    /// The length prefix of the current packet line, and how many bytes of it were passed on already.
+    prefix: [u8; 4],
+    prefix_pos: usize,
+}
+
+impl<R> SkipResponseEnd<R> {
+    fn new(read: R) -> Self {
+        SkipResponseEnd {
+            read,
+            remaining: 0,
+            prefix: [0; 4],
+            prefix_pos: 4,
+        }
+    }
+}
+
+impl<R: Read> Read for SkipResponseEnd<R> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            if self.prefix_pos < self.prefix.len() {
+                let n = buf.len().min(self.prefix.len() - self.prefix_pos);
+                buf[..n].copy_from_slice(&self.prefix[self.prefix_pos..][..n]);
+                self.prefix_pos += n;
+                return Ok(n);
+            }
+            if self.remaining != 0 {
+                let max = buf.len().min(self.remaining);
+                let n = self.read.read(&mut buf[..max])?;
+                if n == 0 && max != 0 {
+                    return Err(std::io::ErrorKind::UnexpectedEof.into());
+                }
+                self.remaining -= n;
+                return Ok(n);
+            }
+            if buf.is_empty() {
+                return Ok(0);
+            }
+
+            let mut prefix = [0; 4];
+            match self.read.read(&mut prefix[..1])? {
+                0 => return Ok(0),
+                _ => self.read.read_exact(&mut prefix[1..])?,
+            }
+            if &prefix == b"0002" {
+                continue;
+            }
+            let len = std::str::from_utf8(&prefix)
+                .ok()
+                .and_then(|hex| u16::from_str_radix(hex, 16).ok())
+                .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid packet line prefix"))?;
+            self.remaining = (len as usize).saturating_sub(prefix.len());
+            self.prefix = prefix;
+            self.prefix_pos = 0;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    #[test]
+    fn response_end_lines_are_skipped() {
+        let input = b"000bversion00020009line\n00000002000eafter end\n0000";
+        let mut out = Vec::new();
+        super::SkipResponseEnd::new(&input[..])
+            .read_to_end(&mut out)
+            .expect("valid input");
+        assert_eq!(
+            out.as_slice(),
+            b"000bversion0009line\n0000000eafter end\n0000".as_slice()
+        );
+    }
+}
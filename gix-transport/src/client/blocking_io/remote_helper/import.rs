@@ -0,0 +1,92 @@
+use std::io::{BufRead, BufReader, Read};
+
+use bstr::ByteSlice;
+
+/// A `git fast-import` stream produced by a remote helper in response to [`import`](super::Helper::import()),
+/// which ends after the `done` command.
+///
+/// It's aware of the `data` command to not mistake its payload for commands.
+pub struct ImportStream<'a> {
+    read: &'a mut BufReader<std::process::ChildStdout>,
+    /// The command line or delimited data line currently being passed on.
+    line: Vec<u8>,
+    line_pos: usize,
+    /// The amount of bytes of a `data <count>` payload that are yet to be passed on.
+    data_remaining: usize,
+    /// The delimiter ending the payload of a `data <<<delimiter>` command that is currently passed on.
+    data_delimiter: Option<Vec<u8>>,
+    is_done: bool,
+}
+
+impl<'a> ImportStream<'a> {
+    pub(crate) fn new(read: &'a mut BufReader<std::process::ChildStdout>) -> Self {
+        ImportStream {
+            read,
+            line: Vec::new(),
+            line_pos: 0,
+            data_remaining: 0,
+            data_delimiter: None,
+            is_done: false,
+        }
+    }
+
+    /// Read the next line and update the state according to the command it contains.
+    fn next_line(&mut self) -> std::io::Result<()> {
+        self.line.clear();
+        self.line_pos = 0;
+        if self.read.read_until(b'\n', &mut self.line)? == 0 {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                "the remote helper exited before the import stream was done",
+            ));
+        }
+        let line = self.line.strip_suffix(b"\n").unwrap_or(&self.line);
+        if let Some(delimiter) = &self.data_delimiter {
+            if line == delimiter.as_slice() {
+                self.data_delimiter = None;
+            }
+        } else if line == b"done" {
+            self.is_done = true;
+        } else if let Some(delimiter) = line.strip_prefix(b"data <<") {
+            self.data_delimiter = Some(delimiter.to_owned());
+        } else if let Some(count) = line.strip_prefix(b"data ") {
+            self.data_remaining = count
+                .to_str()
+                .ok()
+                .and_then(|count| count.parse().ok())
+                .ok_or_else(|| {
+                    std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        format!("invalid data command in import stream: {:?}", line.as_bstr()),
+                    )
+                })?;
+        }
+        Ok(())
+    }
+}
+
+impl Read for ImportStream<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        loop {
+            if self.line_pos < self.line.len() {
+                let n = buf.len().min(self.line.len() - self.line_pos);
+                buf[..n].copy_from_slice(&self.line[self.line_pos..][..n]);
+                self.line_pos += n;
+                return Ok(n);
+            }
+            if self.data_remaining != 0 {
+                let max = buf.len().min(self.data_remaining);
+                let n = self.read.read(&mut buf[..max])?;
+                if n == 0 && max != 0 {
+                    return Err(std::io::ErrorKind::UnexpectedEof.into());
+                }
+                self.data_remaining -= n;
+                return Ok(n);
+            }
+            if self.is_done || buf.is_empty() {
+                return Ok(0);
+            }
+            self.next_line()?;
+        }
+    }
+}
@@ -0,0 +1,518 @@
+//! Support for [remote helpers](https://git-scm.com/docs/gitremote-helpers), programs named `git-remote-<transport>`
+//! which are used for URLs like `<transport>::<address>` or `<transport>://<address>` whose transport isn't built-in.
+//!
+//! A [`Helper`] is spawned for the URL and can be driven directly with the commands it advertises in its [`Capabilities`],
+//! or it's used as [`Transport`](crate::client::blocking_io::Transport) by means of [`connect()`] if it can connect to
+//! git services, in which case the usual git protocol is spoken through it.
+use std::{
+    ffi::OsString,
+    io::{BufRead, BufReader, Write},
+    path::PathBuf,
+    process::{self, Stdio},
+};
+
+use bstr::{BStr, BString, ByteSlice, ByteVec};
+
+mod connection;
+pub use connection::{connect, Connection};
+
+mod import;
+pub use import::ImportStream;
+
+/// The error used in [`Helper`] and [`connect()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The scheme in \"{}\" doesn't refer to a remote helper", .0.to_bstring())]
+    UnsupportedScheme(gix_url::Url),
+    #[error("Failed to invoke remote helper {command:?}")]
+    InvokeProgram { source: std::io::Error, command: OsString },
+    #[error("An IO error occurred when talking to the remote helper")]
+    Io(#[from] std::io::Error),
+    #[error("The remote helper exited unexpectedly")]
+    UnexpectedEof,
+    #[error("The remote helper requires the unsupported capability '{name}'")]
+    UnsupportedMandatoryCapability { name: BString },
+    #[error("The remote helper doesn't support the '{command}' command")]
+    MissingCapability { command: &'static str },
+    #[error("Could not understand the response of the remote helper to '{command}': {line:?}")]
+    UnexpectedResponse { command: &'static str, line: BString },
+    #[error("The remote helper can't connect to service '{service}'")]
+    ConnectionUnsupported { service: &'static str },
+}
+
+impl crate::IsSpuriousError for Error {
+    fn is_spurious(&self) -> bool {
+        match self {
+            Error::Io(err) => err.is_spurious(),
+            _ => false,
+        }
+    }
+}
+
+/// The options for use when spawning a [remote helper](Helper).
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    /// The program or script to use instead of `git-remote-<transport>`, which is looked up in the `PATH` otherwise.
+    pub program: Option<OsString>,
+    /// The name of the remote the URL belongs to, passed as first argument to the helper.
+    /// If unset, the URL itself is passed as it's anonymous.
+    pub remote_name: Option<BString>,
+    /// The `.git` directory of the repository the helper is used for, passed as `GIT_DIR` to the helper.
+    /// It's required for helpers to write objects when [fetching](Helper::fetch()).
+    pub git_dir: Option<PathBuf>,
+}
+
+/// The capabilities advertised by a remote helper in response to the `capabilities` command.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Capabilities {
+    /// The helper can [fetch](Helper::fetch()) objects into the repository.
+    pub fetch: bool,
+    /// The helper can [push](Helper::push()) references.
+    pub push: bool,
+    /// The helper can [import](Helper::import()) references as `git fast-import` stream.
+    pub import: bool,
+    /// The helper can export references from a `git fast-export` stream.
+    pub export: bool,
+    /// The helper can connect to git services, bidirectionally.
+    pub connect: bool,
+    /// The helper can connect to git services using protocol V2, one request at a time.
+    pub stateless_connect: bool,
+    /// The helper understands the [option](Helper::option()) command.
+    pub option: bool,
+    /// The helper can check the connectivity of what it fetched, and reports it.
+    pub check_connectivity: bool,
+    /// The refspecs to map remote references to the private namespace used by the helper when importing or exporting.
+    pub refspecs: Vec<BString>,
+    /// All other capabilities which are understood, but not handled by us, verbatim.
+    pub other: Vec<BString>,
+}
+
+impl Capabilities {
+    /// Parse a single capability `line`, returning an error for mandatory capabilities we don't know.
+    fn parse_line(&mut self, line: &BStr) -> Result<(), Error> {
+        let (is_mandatory, line) = match line.strip_prefix(b"*") {
+            Some(line) => (true, line.as_bstr()),
+            None => (false, line),
+        };
+        match line.as_bytes() {
+            b"fetch" => self.fetch = true,
+            b"push" => self.push = true,
+            b"import" => self.import = true,
+            b"export" => self.export = true,
+            b"connect" => self.connect = true,
+            b"stateless-connect" => self.stateless_connect = true,
+            b"option" => self.option = true,
+            b"check-connectivity" => self.check_connectivity = true,
+            _ => {
+                if let Some(spec) = line.strip_prefix(b"refspec ") {
+                    self.refspecs.push(spec.into());
+                } else if !is_mandatory
+                    || matches!(
+                        line.split_str(" ").next().unwrap_or_default(),
+                        b"signed-tags"
+                            | b"no-private-update"
+                            | b"object-format"
+                            | b"bidi-import"
+                            | b"import-marks"
+                            | b"export-marks"
+                            | b"get"
+                    )
+                {
+                    self.other.push(line.to_owned());
+                } else {
+                    return Err(Error::UnsupportedMandatoryCapability { name: line.to_owned() });
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// What a reference [listed](Helper::list()) by the helper points to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    /// The reference points to the given object.
+    Object(gix_hash::ObjectId),
+    /// The reference is symbolic and points to the reference with the given name.
+    Symbolic(BString),
+    /// The helper doesn't know the value of the reference ahead of time.
+    Unknown,
+}
+
+/// A reference as [listed](Helper::list()) by the helper.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ref {
+    /// The full name of the reference.
+    pub name: BString,
+    /// What the reference points to.
+    pub target: Target,
+    /// Additional attributes like `unchanged`.
+    pub attributes: Vec<BString>,
+}
+
+/// The result of [`Helper::list()`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct List {
+    /// All references known to the remote.
+    pub refs: Vec<Ref>,
+    /// The object format of the remote, if the helper indicated it as `:object-format` keyword.
+    pub object_format: Option<BString>,
+}
+
+/// The response of the helper to the [`option`](Helper::option()) command.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OptionResponse {
+    /// The option was set.
+    Ok,
+    /// The option isn't known to the helper.
+    Unsupported,
+    /// The option is known, but its value couldn't be set for the given reason.
+    Error(BString),
+}
+
+/// The result of [`Helper::fetch()`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FetchOutcome {
+    /// Lock files the helper created to protect the fetched pack, to be removed once its references were updated.
+    pub lock_files: Vec<BString>,
+    /// If `true`, the helper validated the connectivity of all fetched objects.
+    pub connectivity_ok: bool,
+}
+
+/// The status of a single reference after [pushing](Helper::push()) it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PushStatus {
+    /// The name of the remote reference that was pushed to.
+    pub destination: BString,
+    /// If `Some`, the push of this reference failed for the given reason.
+    pub error: Option<BString>,
+}
+
+/// A spawned `git-remote-<transport>` program which is ready to receive commands according to its [`Capabilities`].
+///
+/// Commands must be used in the order mandated by the helper protocol, so [`list()`](Self::list()) must be called before
+/// [`fetch()`](Self::fetch()), [`import()`](Self::import()) or [`push()`](Self::push()).
+pub struct Helper {
+    child: Option<process::Child>,
+    stdin: Option<process::ChildStdin>,
+    stdout: Option<BufReader<process::ChildStdout>>,
+    capabilities: Capabilities,
+    trace: bool,
+}
+
+/// Lifecycle
+impl Helper {
+    /// Spawn the remote helper for `url` and obtain its capabilities.
+    ///
+    /// For `<transport>::<address>` URLs, only `<address>` is passed to `git-remote-<transport>`, otherwise it's the whole URL.
+    /// If `trace` is `true`, all lines sent or received will be passed to the facilities of the `gix-trace` crate.
+    pub fn spawn(url: &gix_url::Url, options: &Options, trace: bool) -> Result<Self, Error> {
+        Self::spawn_with_envs(url, options, &[], trace)
+    }
+
+    pub(crate) fn spawn_with_envs(
+        url: &gix_url::Url,
+        options: &Options,
+        envs: &[(&str, String)],
+        trace: bool,
+    ) -> Result<Self, Error> {
+        let gix_url::Scheme::Ext(transport) = &url.scheme else {
+            return Err(Error::UnsupportedScheme(url.clone()));
+        };
+        let program = options
+            .program
+            .clone()
+            .unwrap_or_else(|| format!("git-remote-{transport}").into());
+        let address = if url.serialize_alternative_form {
+            url.path.clone()
+        } else {
+            url.to_bstring()
+        };
+        let remote = options.remote_name.clone().unwrap_or_else(|| url.to_bstring());
+
+        let mut cmd: process::Command = gix_command::prepare(program.clone())
+            .arg(remote.to_os_str_lossy().into_owned())
+            .arg(address.to_os_str_lossy().into_owned())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::inherit())
+            .into();
+        if let Some(git_dir) = &options.git_dir {
+            cmd.env("GIT_DIR", git_dir);
+        }
+        cmd.envs(envs.iter().map(|(key, value)| (key, value)));
+        gix_features::trace::debug!(command = ?cmd, "gix_transport::remote_helper::Helper");
+        let mut child = cmd.spawn().map_err(|source| Error::InvokeProgram {
+            source,
+            command: program,
+        })?;
+        let mut helper = Helper {
+            stdin: child.stdin.take(),
+            stdout: child.stdout.take().map(BufReader::new),
+            child: Some(child),
+            capabilities: Default::default(),
+            trace,
+        };
+
+        helper.write_line("capabilities")?;
+        helper.flush()?;
+        let mut capabilities = Capabilities::default();
+        while let Some(line) = helper.read_line_until_blank()? {
+            capabilities.parse_line(line.as_ref())?;
+        }
+        helper.capabilities = capabilities;
+        Ok(helper)
+    }
+
+    /// Tell the helper to exit and wait for it to do so.
+    pub fn close(mut self) -> Result<process::ExitStatus, Error> {
+        self.disconnect()?.ok_or(Error::UnexpectedEof)
+    }
+
+    fn disconnect(&mut self) -> Result<Option<process::ExitStatus>, Error> {
+        if let Some(mut stdin) = self.stdin.take() {
+            stdin.write_all(b"\n").and_then(|_| stdin.flush()).ok();
+        }
+        self.stdout.take();
+        self.child
+            .take()
+            .map(|mut child| child.wait())
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    /// Turn this instance into the child process and its output and input, for use after a successful `connect`.
+    pub(crate) fn into_parts(mut self) -> (process::Child, BufReader<process::ChildStdout>, process::ChildStdin) {
+        (
+            self.child.take().expect("present until dropped"),
+            self.stdout.take().expect("present until dropped"),
+            self.stdin.take().expect("present until dropped"),
+        )
+    }
+}
+
+/// Access
+impl Helper {
+    /// Return the capabilities the helper advertised when it was spawned.
+    pub fn capabilities(&self) -> &Capabilities {
+        &self.capabilities
+    }
+}
+
+/// Commands
+impl Helper {
+    /// Set the option `name` to `value`, like `verbosity 1`, `progress true` or `depth 5`.
+    pub fn option(&mut self, name: &str, value: &BStr) -> Result<OptionResponse, Error> {
+        self.require(self.capabilities.option, "option")?;
+        let mut line = BString::from(format!("option {name} "));
+        line.push_str(value);
+        self.write_line(line)?;
+        self.flush()?;
+        let line = self.read_line()?;
+        Ok(match line.as_bytes() {
+            b"ok" => OptionResponse::Ok,
+            b"unsupported" => OptionResponse::Unsupported,
+            _ => match line.strip_prefix(b"error") {
+                Some(message) => OptionResponse::Error(message.trim_start().into()),
+                None => {
+                    return Err(Error::UnexpectedResponse {
+                        command: "option",
+                        line,
+                    })
+                }
+            },
+        })
+    }
+
+    /// List all references of the remote, with `for_push` set if the references are used for pushing.
+    pub fn list(&mut self, for_push: bool) -> Result<List, Error> {
+        self.write_line(if for_push { "list for-push" } else { "list" })?;
+        self.flush()?;
+        let mut out = List::default();
+        while let Some(line) = self.read_line_until_blank()? {
+            let unexpected = || Error::UnexpectedResponse {
+                command: "list",
+                line: line.clone(),
+            };
+            if let Some(keyword) = line.strip_prefix(b":") {
+                let (keyword, value) = keyword.split_once_str(" ").unwrap_or((keyword, b""));
+                if keyword == b"object-format" {
+                    out.object_format = Some(value.into());
+                }
+                continue;
+            }
+            let mut tokens = line.split_str(" ");
+            let (value, name) = tokens
+                .next()
+                .zip(tokens.next())
+                .filter(|(_, name)| !name.is_empty())
+                .ok_or_else(unexpected)?;
+            let target = match value {
+                b"?" => Target::Unknown,
+                _ => match value.strip_prefix(b"@") {
+                    Some(target) => Target::Symbolic(target.into()),
+                    None => Target::Object(gix_hash::ObjectId::from_hex(value).map_err(|_| unexpected())?),
+                },
+            };
+            out.refs.push(Ref {
+                name: name.into(),
+                target,
+                attributes: tokens.map(Into::into).collect(),
+            });
+        }
+        Ok(out)
+    }
+
+    /// Let the helper write the objects needed for `refs`, a list of object ids along with the name of the reference
+    /// pointing to it, into the repository at [`GIT_DIR`](Options::git_dir).
+    pub fn fetch<'a>(
+        &mut self,
+        refs: impl IntoIterator<Item = (&'a gix_hash::oid, &'a BStr)>,
+    ) -> Result<FetchOutcome, Error> {
+        self.require(self.capabilities.fetch, "fetch")?;
+        for (id, name) in refs {
+            let mut line = BString::from(format!("fetch {id} "));
+            line.push_str(name);
+            self.write_line(line)?;
+        }
+        self.write_line("")?;
+        self.flush()?;
+        let mut out = FetchOutcome::default();
+        while let Some(line) = self.read_line_until_blank()? {
+            if line == "connectivity-ok" {
+                out.connectivity_ok = true;
+            } else if let Some(lock_file) = line.strip_prefix(b"lock ") {
+                out.lock_files.push(lock_file.into());
+            } else {
+                return Err(Error::UnexpectedResponse { command: "fetch", line });
+            }
+        }
+        Ok(out)
+    }
+
+    /// Let the helper produce a `git fast-import` stream for the references with the given `names`,
+    /// which must be read until its end.
+    pub fn import<'a>(&mut self, names: impl IntoIterator<Item = &'a BStr>) -> Result<ImportStream<'_>, Error> {
+        self.require(self.capabilities.import, "import")?;
+        for name in names {
+            let mut line = BString::from("import ");
+            line.push_str(name);
+            self.write_line(line)?;
+        }
+        self.write_line("")?;
+        self.flush()?;
+        Ok(ImportStream::new(self.stdout.as_mut().expect("present until dropped")))
+    }
+
+    /// Push according to the given `refspecs`, like `refs/heads/main:refs/heads/main` or `+refs/heads/*:refs/heads/*`,
+    /// and return the status for each remote reference.
+    pub fn push<'a>(&mut self, refspecs: impl IntoIterator<Item = &'a BStr>) -> Result<Vec<PushStatus>, Error> {
+        self.require(self.capabilities.push, "push")?;
+        for spec in refspecs {
+            let mut line = BString::from("push ");
+            line.push_str(spec);
+            self.write_line(line)?;
+        }
+        self.write_line("")?;
+        self.flush()?;
+        let mut out = Vec::new();
+        while let Some(line) = self.read_line_until_blank()? {
+            if let Some(destination) = line.strip_prefix(b"ok ") {
+                out.push(PushStatus {
+                    destination: destination.into(),
+                    error: None,
+                });
+            } else if let Some(rest) = line.strip_prefix(b"error ") {
+                let (destination, reason) = rest.split_once_str(" ").unwrap_or((rest, b""));
+                out.push(PushStatus {
+                    destination: destination.into(),
+                    error: Some(reason.into()),
+                });
+            } else {
+                return Err(Error::UnexpectedResponse { command: "push", line });
+            }
+        }
+        Ok(out)
+    }
+
+    /// Ask the helper to connect to `service`, statelessly if `stateless` is `true`, and return `true` if it did.
+    /// Thereafter, the helper output and input are connected to the service.
+    pub(crate) fn connect(&mut self, service: crate::Service, stateless: bool) -> Result<bool, Error> {
+        let command = if stateless { "stateless-connect" } else { "connect" };
+        self.require(
+            if stateless {
+                self.capabilities.stateless_connect
+            } else {
+                self.capabilities.connect
+            },
+            command,
+        )?;
+        self.write_line(format!("{command} {}", service.as_str()))?;
+        self.flush()?;
+        let line = self.read_line()?;
+        match line.as_bytes() {
+            b"" => Ok(true),
+            b"fallback" => Ok(false),
+            _ => Err(Error::UnexpectedResponse { command, line }),
+        }
+    }
+}
+
+/// Utilities
+impl Helper {
+    fn require(&self, capability: bool, command: &'static str) -> Result<(), Error> {
+        if capability {
+            Ok(())
+        } else {
+            Err(Error::MissingCapability { command })
+        }
+    }
+
+    fn write_line(&mut self, line: impl AsRef<[u8]>) -> Result<(), Error> {
+        let line = line.as_ref();
+        if self.trace {
+            gix_features::trace::trace!(">> {}", line.as_bstr());
+        }
+        let stdin = self.stdin.as_mut().expect("present until dropped");
+        stdin.write_all(line)?;
+        stdin.write_all(b"\n")?;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Error> {
+        Ok(self.stdin.as_mut().expect("present until dropped").flush()?)
+    }
+
+    /// Read a line without its trailing newline, and fail if the helper exited.
+    fn read_line(&mut self) -> Result<BString, Error> {
+        let mut line = Vec::new();
+        if self
+            .stdout
+            .as_mut()
+            .expect("present until dropped")
+            .read_until(b'\n', &mut line)?
+            == 0
+        {
+            return Err(Error::UnexpectedEof);
+        }
+        if line.last() == Some(&b'\n') {
+            line.pop();
+        }
+        if self.trace {
+            gix_features::trace::trace!("<< {}", line.as_bstr());
+        }
+        Ok(line.into())
+    }
+
+    /// Like [`Self::read_line()`], but return `None` if the line is empty, marking the end of a response.
+    fn read_line_until_blank(&mut self) -> Result<Option<BString>, Error> {
+        self.read_line().map(|line| (!line.is_empty()).then_some(line))
+    }
+}
+
+impl Drop for Helper {
+    fn drop(&mut self) {
+        self.disconnect().ok();
+    }
+}
@@ -41,6 +41,9 @@ pub(crate) mod connect {
         #[cfg(feature = "blocking-client")]
         /// Options to use if the scheme of the URL is `ssh`.
         pub ssh: crate::client::blocking_io::ssh::connect::Options,
+        #[cfg(feature = "blocking-client")]
+        /// Options to use if the scheme of the URL isn't built-in, so a remote helper is used.
+        pub remote_helper: crate::client::blocking_io::remote_helper::Options,
         /// If `true`, all packetlines received or sent will be passed to the facilities of the `gix-trace` crate.
         pub trace: bool,
     }
@@ -106,10 +109,14 @@ mod error {
     type HttpError = http::Error;
     #[cfg(feature = "blocking-client")]
     type SshInvocationError = ssh::invocation::Error;
+    #[cfg(feature = "blocking-client")]
+    type RemoteHelperError = crate::client::blocking_io::remote_helper::Error;
     #[cfg(not(feature = "http-client"))]
     type HttpError = std::convert::Infallible;
     #[cfg(not(feature = "blocking-client"))]
     type SshInvocationError = std::convert::Infallible;
+    #[cfg(not(feature = "blocking-client"))]
+    type RemoteHelperError = std::convert::Infallible;
 
     /// The error used in most methods of the [`client`][crate::client] module
     #[derive(thiserror::Error, Debug)]
@@ -147,6 +154,8 @@ mod error {
         SshInvocation(SshInvocationError),
         #[error("The repository path '{path}' could be mistaken for a command-line argument")]
         AmbiguousPath { path: BString },
+        #[error(transparent)]
+        RemoteHelper(RemoteHelperError),
     }
 
     impl crate::IsSpuriousError for Error {
@@ -154,6 +163,8 @@ mod error {
             match self {
                 Error::Io(err) => err.is_spurious(),
                 Error::Http(err) => err.is_spurious(),
+                #[cfg(feature = "blocking-client")]
+                Error::RemoteHelper(err) => err.is_spurious(),
                 _ => false,
             }
         }
@@ -0,0 +1,223 @@
+use std::{
+    io::{BufRead, Read},
+    path::{Path, PathBuf},
+};
+
+use bstr::{BString, ByteSlice};
+use gix_transport::{
+    client::{
+        blocking_io::{
+            remote_helper::{self, Helper, OptionResponse, PushStatus, Target},
+            Transport, TransportV2Ext,
+        },
+        TransportWithoutIO,
+    },
+    Protocol, Service,
+};
+
+fn program() -> PathBuf {
+    std::env::current_dir()
+        .expect("valid cwd")
+        .join("tests/fixtures/remote-helper/git-remote-testing")
+}
+
+fn repos() -> crate::Result<PathBuf> {
+    gix_testtools::scripted_fixture_read_only("make_remote_helper_repos.sh").map_err(|err| err as crate::Error)
+}
+
+fn helper(remote: &Path, git_dir: Option<PathBuf>) -> crate::Result<Helper> {
+    let url = gix_url::parse(format!("testing::{}", remote.display()).as_str().into())?;
+    Ok(Helper::spawn(
+        &url,
+        &remote_helper::Options {
+            program: Some(program().into()),
+            remote_name: Some("origin".into()),
+            git_dir,
+        },
+        false,
+    )?)
+}
+
+fn rev_parse(repo: &Path, spec: &str) -> crate::Result<gix_hash::ObjectId> {
+    let out = std::process::Command::new("git")
+        .args(["rev-parse", spec])
+        .current_dir(repo)
+        .output()?;
+    Ok(gix_hash::ObjectId::from_hex(out.stdout.trim())?)
+}
+
+#[test]
+fn capabilities_are_parsed() -> crate::Result {
+    let dir = repos()?;
+    let helper = helper(&dir.join("remote"), None)?;
+    let caps = helper.capabilities();
+    assert!(caps.option && caps.fetch && caps.import && caps.push && caps.connect);
+    assert!(!caps.export && !caps.stateless_connect && !caps.check_connectivity);
+    assert_eq!(caps.refspecs, ["refs/heads/*:refs/testing/heads/*"]);
+    assert_eq!(
+        caps.other,
+        ["signed-tags", "unknown-optional"],
+        "mandatory capabilities we know but don't handle are kept, along with unknown optional ones"
+    );
+    assert!(helper.close()?.success());
+    Ok(())
+}
+
+#[test]
+fn options_can_be_set() -> crate::Result {
+    let dir = repos()?;
+    let mut helper = helper(&dir.join("remote"), None)?;
+    assert_eq!(helper.option("verbosity", "1".into())?, OptionResponse::Ok);
+    assert_eq!(
+        helper.option("depth", "1".into())?,
+        OptionResponse::Error("depth is not supported".into())
+    );
+    assert_eq!(helper.option("progress", "true".into())?, OptionResponse::Unsupported);
+    Ok(())
+}
+
+#[test]
+fn list_and_fetch() -> crate::Result {
+    let dir =
+        gix_testtools::scripted_fixture_writable("make_remote_helper_repos.sh").map_err(|err| err as crate::Error)?;
+    let (remote, git_dir) = (dir.path().join("remote"), dir.path().join("local").join(".git"));
+
+    let mut helper = helper(&remote, Some(git_dir.clone()))?;
+    let list = helper.list(false)?;
+    let head = rev_parse(&remote, "HEAD")?;
+    let tag = rev_parse(&remote, "v1")?;
+    let names: Vec<_> = list.refs.iter().map(|r| r.name.clone()).collect();
+    assert_eq!(names, ["refs/heads/main", "refs/tags/v1", "HEAD", "refs/heads/unknown"]);
+    assert_eq!(list.refs[0].target, Target::Object(head));
+    assert_eq!(list.refs[1].target, Target::Object(tag));
+    assert_eq!(list.refs[2].target, Target::Symbolic("refs/heads/main".into()));
+    assert_eq!(list.refs[3].target, Target::Unknown);
+    assert_eq!(list.object_format, None);
+
+    let outcome = helper.fetch([(head.as_ref(), "refs/heads/main".into())])?;
+    assert!(!outcome.connectivity_ok);
+    assert_eq!(outcome.lock_files.len(), 1);
+    assert!(outcome.lock_files[0].ends_with_str("objects/pack/testing.keep"));
+    assert!(
+        std::process::Command::new("git")
+            .args(["cat-file", "-e", &head.to_string()])
+            .env("GIT_DIR", &git_dir)
+            .status()?
+            .success(),
+        "the helper wrote the objects into GIT_DIR"
+    );
+    Ok(())
+}
+
+#[test]
+fn import_stream_ends_after_done() -> crate::Result {
+    let dir = repos()?;
+    let mut helper = helper(&dir.join("remote"), None)?;
+    helper.list(false)?;
+    let mut stream = Vec::new();
+    helper.import(["refs/heads/main".into()])?.read_to_end(&mut stream)?;
+    assert_eq!(
+        stream.as_bstr(),
+        "feature done\nblob\nmark :1\ndata 5\ndone\ncommit refs/testing/heads/main\nmark :2\ncommitter T <t@example.com> 0 +0000\ndata <<EOF\ndone\nEOF\nM 100644 :1 file\ndone\n",
+        "'done' in data isn't mistaken for the end of the stream"
+    );
+    assert_eq!(
+        helper.option("verbosity", "1".into())?,
+        OptionResponse::Ok,
+        "the helper is usable after the stream"
+    );
+    Ok(())
+}
+
+#[test]
+fn push_reports_status_per_ref() -> crate::Result {
+    let dir = repos()?;
+    let mut helper = helper(&dir.join("remote"), None)?;
+    helper.list(true)?;
+    let status = helper.push([
+        "refs/heads/main:refs/heads/main".into(),
+        "+refs/tags/v1:refs/tags/v1".into(),
+    ])?;
+    assert_eq!(
+        status,
+        [
+            PushStatus {
+                destination: "refs/heads/main".into(),
+                error: None
+            },
+            PushStatus {
+                destination: "refs/tags/v1".into(),
+                error: Some("not allowed".into())
+            }
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn only_urls_with_unknown_schemes_are_supported() -> crate::Result {
+    let err = remote_helper::connect(
+        gix_url::parse("https://example.com/repo".into())?,
+        Protocol::V2,
+        Default::default(),
+        false,
+    )
+    .map(|_| ())
+    .unwrap_err();
+    assert!(matches!(err, remote_helper::Error::UnsupportedScheme(_)));
+    Ok(())
+}
+
+#[test]
+fn connect_speaks_the_git_protocol() -> crate::Result {
+    let dir = repos()?;
+    let remote = dir.join("remote");
+    let head = rev_parse(&remote, "HEAD")?;
+    for version in [Protocol::V1, Protocol::V2] {
+        let url = format!("testing::{}", remote.display());
+        let mut transport = gix_transport::client::blocking_io::connect::connect(
+            url.as_str(),
+            gix_transport::client::blocking_io::connect::Options {
+                version,
+                remote_helper: remote_helper::Options {
+                    program: Some(program().into()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(transport.to_url().as_ref(), url.as_str());
+
+        let res = transport.handshake(Service::UploadPack, &[])?;
+        assert_eq!(res.actual_protocol, version);
+        let refs: Vec<BString> = match version {
+            Protocol::V1 => {
+                let mut refs = Vec::new();
+                for line in res.refs.expect("present in V1").lines() {
+                    refs.push(line?.into());
+                }
+                refs
+            }
+            _ => {
+                assert!(res.capabilities.contains("ls-refs"));
+                drop(res);
+                let reader = transport.invoke(
+                    "ls-refs",
+                    None::<(&str, Option<&str>)>.into_iter(),
+                    Some(["symrefs".into()].into_iter()),
+                    false,
+                )?;
+                reader
+                    .lines()
+                    .map(|line| line.map(Into::into))
+                    .collect::<Result<_, _>>()?
+            }
+        };
+        assert_eq!(
+            refs.first().and_then(|line| line.split_str(" ").next()),
+            Some(head.to_string().as_bytes()),
+            "{version:?}: {refs:?}"
+        );
+    }
+    Ok(())
+}
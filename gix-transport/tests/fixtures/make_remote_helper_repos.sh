@@ -0,0 +1,12 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q remote
+(cd remote
+  echo content >file
+  git add file
+  git commit -qm c1
+  git tag v1
+)
+
+git init -q local
@@ -57,6 +57,7 @@ pub(super) mod function {
         }
 
         let state = state.borrow_mut();
+        let hash_kind = root.kind();
         let mut stack = vec![Machine::GetTree(root)];
         'outer: while let Some(item) = stack.pop() {
             match item {
@@ -72,7 +73,7 @@ pub(super) mod function {
                     tree_buf: buf,
                     byte_offset_to_next_entry,
                 } => {
-                    let mut iter = TreeRefIter::from_bytes(&buf[byte_offset_to_next_entry..]);
+                    let mut iter = TreeRefIter::from_bytes(&buf[byte_offset_to_next_entry..], hash_kind);
                     delegate.pop_back_tracked_path_and_set_current();
                     while let Some(entry) = iter.next() {
                         let entry = entry?;
@@ -40,6 +40,7 @@ pub fn parse(input: &BStr) -> Result<Url, parse::Error> {
         }
         InputScheme::Url { protocol_end } => parse::url(input, protocol_end),
         InputScheme::Scp { colon } => parse::scp(input, colon),
+        InputScheme::Helper { separator } => parse::helper(input, separator),
     }
 }
 
@@ -129,7 +130,8 @@ pub struct Url {
     pub host: Option<String>,
     /// When serializing, use the alternative forms as it was parsed as such.
     ///
-    /// Alternative forms include SCP-like syntax (`user@host:path`) and bare file paths.
+    /// Alternative forms include SCP-like syntax (`user@host:path`), bare file paths and
+    /// remote helper addresses (`<transport>::<address>`) whose address is stored in `path`.
     /// When `true`, password and port cannot be serialized (will panic in debug builds).
     pub serialize_alternative_form: bool,
     /// The port to use when connecting to a host. If `None`, standard ports depending on `scheme` will be used.
@@ -357,7 +359,7 @@ impl Url {
         // Since alternative form doesn't employ any escape syntax, password and
         // port number cannot be encoded.
         if self.serialize_alternative_form
-            && (self.scheme == Scheme::File || self.scheme == Scheme::Ssh)
+            && matches!(self.scheme, Scheme::File | Scheme::Ssh | Scheme::Ext(_))
             && self.password.is_none()
             && self.port.is_none()
         {
@@ -457,6 +459,12 @@ impl Url {
     }
 
     fn write_alternative_form_to(&self, out: &mut dyn std::io::Write) -> std::io::Result<()> {
+        if let Scheme::Ext(transport) = &self.scheme {
+            out.write_all(transport.as_bytes())?;
+            out.write_all(b"::")?;
+            out.write_all(&self.path)?;
+            return Ok(());
+        }
         let needs_brackets = self.host_needs_brackets();
 
         match (&self.user, &self.host) {
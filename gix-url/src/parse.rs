@@ -45,6 +45,8 @@ pub enum UrlKind {
     Scp,
     ///
     Local,
+    ///
+    Helper,
 }
 
 impl UrlKind {
@@ -53,6 +55,7 @@ impl UrlKind {
             UrlKind::Url => "URL",
             UrlKind::Scp => "SCP-like target",
             UrlKind::Local => "local path",
+            UrlKind::Helper => "remote helper address",
         }
     }
 }
@@ -60,10 +63,30 @@ impl UrlKind {
 pub(crate) enum InputScheme {
     Url { protocol_end: usize },
     Scp { colon: usize },
+    Helper { separator: usize },
     Local,
 }
 
 pub(crate) fn find_scheme(input: &BStr) -> InputScheme {
+    // Like `git`, `<transport>::<address>` selects the remote helper `git-remote-<transport>`,
+    // but `scheme:://` is kept to be an invalid URL.
+    let transport_len = input
+        .iter()
+        .enumerate()
+        .take_while(|(pos, b)| {
+            if *pos == 0 {
+                b.is_ascii_alphabetic()
+            } else {
+                b.is_ascii_alphanumeric() || matches!(b, b'+' | b'-' | b'.')
+            }
+        })
+        .count();
+    if transport_len > 0 && input[transport_len..].starts_with(b"::") && !input[transport_len..].starts_with(b":://") {
+        return InputScheme::Helper {
+            separator: transport_len,
+        };
+    }
+
     // TODO: url's may only contain `:/`, we should additionally check if the characters used for
     //       protocol are all valid
     if let Some(protocol_end) = input.find("://") {
@@ -313,6 +336,26 @@ pub(crate) fn file_url(input: &BStr, protocol_colon: usize) -> Result<crate::Url
     })
 }
 
+pub(crate) fn helper(input: &BStr, separator: usize) -> Result<crate::Url, Error> {
+    let transport = input_to_utf8(&input[..separator], UrlKind::Helper)?;
+    let address = &input[separator + "::".len()..];
+    if address.is_empty() {
+        return Err(Error::MissingRepositoryPath {
+            url: input.to_owned(),
+            kind: UrlKind::Helper,
+        });
+    }
+    Ok(crate::Url {
+        serialize_alternative_form: true,
+        scheme: Scheme::Ext(transport.into()),
+        password: None,
+        user: None,
+        host: None,
+        port: None,
+        path: address.to_owned(),
+    })
+}
+
 pub(crate) fn local(input: &BStr) -> Result<crate::Url, Error> {
     if input.is_empty() {
         return Err(Error::MissingRepositoryPath {
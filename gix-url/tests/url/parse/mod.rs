@@ -154,6 +154,47 @@ mod git {
     }
 }
 
+mod remote_helper {
+    use gix_url::Scheme;
+
+    use crate::parse::{assert_url_roundtrip, parse, url_alternate};
+
+    #[test]
+    fn address_is_stored_verbatim_as_path() -> crate::Result {
+        assert_url_roundtrip(
+            "hg::https://example.com/repo",
+            url_alternate(Scheme::Ext("hg".into()), None, None, None, b"https://example.com/repo"),
+        )?;
+        assert_url_roundtrip(
+            "s3+v2::bucket:path/to/repo",
+            url_alternate(Scheme::Ext("s3+v2".into()), None, None, None, b"bucket:path/to/repo"),
+        )
+    }
+
+    #[test]
+    fn local_addresses_are_not_mistaken_for_scp_like_urls() -> crate::Result {
+        assert_url_roundtrip(
+            "hg::/local/path",
+            url_alternate(Scheme::Ext("hg".into()), None, None, None, b"/local/path"),
+        )
+    }
+
+    #[test]
+    fn empty_address_is_invalid() {
+        assert!(matches!(
+            parse("hg::"),
+            Err(gix_url::parse::Error::MissingRepositoryPath { .. })
+        ));
+    }
+
+    #[test]
+    fn transport_names_must_start_with_a_letter() -> crate::Result {
+        let url = parse("1hg::path")?;
+        assert_eq!(url.scheme, Scheme::Ssh, "it's an scp-like URL instead");
+        Ok(())
+    }
+}
+
 mod unknown {
     use gix_url::Scheme;
 
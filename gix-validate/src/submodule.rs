@@ -1,4 +1,4 @@
-use bstr::{BStr, ByteSlice};
+use bstr::BStr;
 
 ///
 pub mod name {
@@ -14,19 +14,18 @@ pub mod name {
 }
 
 /// Return the original `name` if it is valid, or the respective error indicating what was wrong with it.
+///
+/// Like in Git, a name is invalid if any of its components, separated by `/` or `\\`, is `..`, as it could be used
+/// to escape the directory in which the repositories of submodules are stored.
 pub fn name(name: &BStr) -> Result<&BStr, name::Error> {
     if name.is_empty() {
         return Err(name::Error::Empty);
     }
-    match name.find(b"..") {
-        Some(pos) => {
-            let &b = name.get(pos + 2).ok_or(name::Error::ParentComponent)?;
-            if b == b'/' || b == b'\\' {
-                Err(name::Error::ParentComponent)
-            } else {
-                Ok(name)
-            }
-        }
-        None => Ok(name),
+    if name
+        .split(|b| *b == b'/' || *b == b'\\')
+        .any(|component| component == b"..")
+    {
+        return Err(name::Error::ParentComponent);
     }
+    Ok(name)
 }
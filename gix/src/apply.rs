@@ -0,0 +1,52 @@
+pub use gix_diff::patch;
+
+use crate::bstr::BString;
+
+/// Determine where [`Repository::apply_patch()`](crate::Repository::apply_patch()) reads files from and writes them to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    /// Apply the patch to the files in the worktree, like `git apply`.
+    Worktree,
+    /// Apply the patch to the index only and write it, like `git apply --cached`.
+    Index,
+    /// Apply the patch to the worktree and to the index, and write it, like `git apply --index`.
+    ///
+    /// All affected files must exist in the index, and their worktree version must match it.
+    WorktreeAndIndex,
+    /// Apply the patch to the tree with the given id and write the result to the object database.
+    ///
+    /// Nothing but the object database is touched, which makes this suitable for applying untrusted patches.
+    /// Use [`Repository::with_object_memory()`](crate::Repository::with_object_memory()) to avoid writing objects to disk.
+    Tree(gix_hash::ObjectId),
+}
+
+/// Options for use in [`Repository::apply_patch()`](crate::Repository::apply_patch()).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+    /// If `true`, apply the patch in reverse, like `git apply --reverse`.
+    pub reverse: bool,
+    /// The minimal amount of context lines that must match for a hunk to apply, like `git apply -C<n>`.
+    ///
+    /// If `None`, all context lines must match.
+    pub min_context: Option<u32>,
+    /// If `true`, and if a patch doesn't apply cleanly, fall back to a three-way merge of the file in its current state
+    /// with the result of applying the patch to the version of the file it was created for, like `git apply --3way`.
+    ///
+    /// This requires the blob the patch was created for to be present in the object database, which is looked up
+    /// using the `index` line of the patch.
+    pub three_way: bool,
+}
+
+/// The outcome of [`Repository::apply_patch()`](crate::Repository::apply_patch()).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Outcome {
+    /// The id of the tree with all patches applied, if the location was a [tree](Location::Tree).
+    pub tree_id: Option<gix_hash::ObjectId>,
+    /// The paths of all files that were added, modified or deleted, in order of their first appearance in the patch.
+    pub paths: Vec<BString>,
+    /// The paths of the files that were merged with conflicts during a [three-way merge](Options::three_way).
+    ///
+    /// These files contain conflict markers, and are represented by their conflicting stages in the index,
+    /// if the location involves an index.
+    pub conflicts: Vec<BString>,
+}
@@ -35,6 +35,24 @@ impl PrepareFetch {
         self
     }
 
+    /// Set whether to clone and check out all submodules recursively after checking out the main worktree,
+    /// like `git clone --recurse-submodules` does. If `None`, which is the default, `submodule.recurse` decides.
+    ///
+    /// Note that this only has an effect with [`PrepareFetch::fetch_then_checkout()`].
+    pub fn with_recurse_submodules(mut self, recurse: Option<bool>) -> Self {
+        self.recurse_submodules = recurse;
+        self
+    }
+
+    /// Download the bundle or bundle list at `uri` and apply it before fetching, like `git clone --bundle-uri` does.
+    ///
+    /// It can be a path, a `file://` URL, or an `http(s)://` URL if an HTTP transport is compiled in.
+    /// If it can't be downloaded or applied, the clone proceeds as if it wasn't set.
+    pub fn with_bundle_uri(mut self, uri: impl Into<BString>) -> Self {
+        self.bundle_uri = Some(uri.into());
+        self
+    }
+
     /// Apply the given configuration `values` right before readying the actual fetch from the remote.
     /// The configuration is marked with [source API](gix_config::Source::Api), and will not be written back, it's
     /// retained only in memory.
@@ -35,6 +35,9 @@ pub mod main_worktree {
         FindHead(#[from] crate::reference::find::existing::Error),
         #[error("The HEAD reference could not be located")]
         PeelHeadToId(#[from] crate::head::peel::Error),
+        #[cfg(feature = "blocking-network-client")]
+        #[error(transparent)]
+        Submodules(#[from] crate::submodule::update::Error),
     }
 
     /// The progress ids used in [`PrepareCheckout::main_worktree()`].
@@ -62,6 +65,10 @@ pub mod main_worktree {
         /// Checkout the main worktree, determining how many threads to use by looking at `checkout.workers`, defaulting to using
         /// on thread per logical core.
         ///
+        /// If [enabled](crate::clone::PrepareFetch::with_recurse_submodules()) or if `submodule.recurse` is set, all submodules
+        /// are initialized, cloned and checked out recursively as well. The configuration of the returned repository doesn't
+        /// reflect their initialization, so it has to be reopened to see it.
+        ///
         /// Note that this is a no-op if the remote was empty, leaving this repository empty as well. This can be validated by checking
         /// if the `head()` of the returned repository is *not* unborn.
         ///
@@ -92,57 +99,91 @@ pub mod main_worktree {
                 .repo
                 .as_ref()
                 .expect("BUG: this method may only be called until it is successful");
-            let workdir = repo.workdir().ok_or_else(|| Error::BareRepository {
-                git_dir: repo.git_dir().to_owned(),
-            })?;
-
-            let root_tree_id = match &self.ref_name {
-                Some(reference_val) => Some(repo.find_reference(reference_val)?.peel_to_id()?),
-                None => repo.head()?.try_peel_to_id()?,
-            };
-
-            let root_tree = match root_tree_id {
-                Some(id) => id.object().expect("downloaded from remote").peel_to_tree()?.id,
-                None => {
-                    return Ok((
-                        self.repo.take().expect("still present"),
-                        gix_worktree_state::checkout::Outcome::default(),
-                    ));
+            let outcome = checkout_into_empty_worktree(repo, self.ref_name.as_ref(), progress, should_interrupt)?;
+            #[cfg(feature = "blocking-network-client")]
+            {
+                use crate::config::tree::Submodule;
+                let recurse = match self.recurse_submodules {
+                    Some(recurse) => recurse,
+                    None => repo
+                        .config
+                        .resolved
+                        .boolean(Submodule::RECURSE)
+                        .map(|value| Submodule::RECURSE.enrich_error(value))
+                        .transpose()?
+                        .unwrap_or(false),
+                };
+                if recurse {
+                    repo.update_submodules(
+                        progress,
+                        should_interrupt,
+                        &crate::submodule::update::Options {
+                            init: true,
+                            recursive: true,
+                            ..Default::default()
+                        },
+                    )?;
                 }
-            };
+            }
+            Ok((self.repo.take().expect("still present"), outcome))
+        }
+    }
 
-            let index = gix_index::State::from_tree(&root_tree, &repo.objects, repo.config.protect_options()?)
-                .map_err(|err| Error::IndexFromTree {
+    /// Check out `ref_name` or the commit `HEAD` points to into the empty worktree of `repo` and write its index.
+    /// Nothing is done if `HEAD` is unborn.
+    pub(crate) fn checkout_into_empty_worktree(
+        repo: &Repository,
+        ref_name: Option<&gix_ref::PartialName>,
+        progress: &mut dyn gix_features::progress::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<gix_worktree_state::checkout::Outcome, Error> {
+        let workdir = repo.workdir().ok_or_else(|| Error::BareRepository {
+            git_dir: repo.git_dir().to_owned(),
+        })?;
+
+        let root_tree_id = match ref_name {
+            Some(reference_val) => Some(repo.find_reference(reference_val)?.peel_to_id()?),
+            None => repo.head()?.try_peel_to_id()?,
+        };
+
+        let root_tree = match root_tree_id {
+            Some(id) => id.object().expect("downloaded from remote").peel_to_tree()?.id,
+            None => return Ok(gix_worktree_state::checkout::Outcome::default()),
+        };
+
+        let index =
+            gix_index::State::from_tree(&root_tree, &repo.objects, repo.config.protect_options()?).map_err(|err| {
+                Error::IndexFromTree {
                     id: root_tree,
                     source: err,
-                })?;
-            let mut index = gix_index::File::from_state(index, repo.index_path());
-
-            let mut opts = repo.checkout_options(gix_worktree::stack::state::attributes::Source::IdMapping)?;
-            opts.destination_is_initially_empty = true;
-
-            let mut files = progress.add_child_with_id("checkout".to_string(), ProgressId::CheckoutFiles.into());
-            let mut bytes = progress.add_child_with_id("writing".to_string(), ProgressId::BytesWritten.into());
-
-            files.init(Some(index.entries().len()), crate::progress::count("files"));
-            bytes.init(None, crate::progress::bytes());
-
-            let start = std::time::Instant::now();
-            let outcome = gix_worktree_state::checkout(
-                &mut index,
-                workdir,
-                repo.objects.clone().into_arc()?,
-                &files,
-                &bytes,
-                should_interrupt,
-                opts,
-            )?;
-            files.show_throughput(start);
-            bytes.show_throughput(start);
-
-            index.write(Default::default())?;
-            Ok((self.repo.take().expect("still present").clone(), outcome))
-        }
+                }
+            })?;
+        let mut index = gix_index::File::from_state(index, repo.index_path());
+
+        let mut opts = repo.checkout_options(gix_worktree::stack::state::attributes::Source::IdMapping)?;
+        opts.destination_is_initially_empty = true;
+
+        let mut files = progress.add_child_with_id("checkout".to_string(), ProgressId::CheckoutFiles.into());
+        let mut bytes = progress.add_child_with_id("writing".to_string(), ProgressId::BytesWritten.into());
+
+        files.init(Some(index.entries().len()), crate::progress::count("files"));
+        bytes.init(None, crate::progress::bytes());
+
+        let start = std::time::Instant::now();
+        let outcome = gix_worktree_state::checkout(
+            &mut index,
+            workdir,
+            repo.objects.clone().into_arc()?,
+            &files,
+            &bytes,
+            should_interrupt,
+            opts,
+        )?;
+        files.show_throughput(start);
+        bytes.show_throughput(start);
+
+        index.write(Default::default())?;
+        Ok(outcome)
     }
 }
 
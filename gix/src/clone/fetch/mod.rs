@@ -52,6 +52,12 @@ pub enum Error {
     RefMap(#[from] crate::remote::ref_map::Error),
     #[error(transparent)]
     ReferenceName(#[from] gix_validate::reference::name::Error),
+    #[error("Failed to configure the object format advertised by the remote")]
+    SetObjectFormat(#[from] gix_config::file::set_raw_value::Error),
+    #[error("Failed to reopen the repository after configuring the object format advertised by the remote")]
+    Reopen(#[from] crate::open::Error),
+    #[error(transparent)]
+    FindRemote(#[from] crate::remote::find::existing::Error),
 }
 
 /// Modification
@@ -200,7 +206,13 @@ impl PrepareFetch {
         if let Some(f) = self.configure_remote.as_mut() {
             remote = f(remote).map_err(Error::RemoteConfiguration)?;
         } else {
-            clone_fetch_tags = remote::fetch::Tags::All.into();
+            // Like Git, only follow tags that point into the fetched history if a single branch is cloned.
+            clone_fetch_tags = if target_ref.is_some() {
+                remote::fetch::Tags::Included
+            } else {
+                remote::fetch::Tags::All
+            }
+            .into();
         }
 
         let config = util::write_remote_to_local_config_file(&mut remote, remote_name.clone())?;
@@ -219,69 +231,66 @@ impl PrepareFetch {
         )
         .expect("valid")
         .to_owned();
-        let pending_pack: remote::fetch::Prepare<'_, '_, _> = {
-            // For shallow clones, we already connected once, so we need to connect again
-            let mut connection = remote.connect(remote::Direction::Fetch).await?;
-            if let Some(f) = self.configure_connection.as_mut() {
-                f(&mut connection).map_err(Error::RemoteConnection)?;
+        let fetch_opts = {
+            let mut opts = self.fetch_options.clone();
+            if !opts.extra_refspecs.contains(&head_refspec) {
+                opts.extra_refspecs.push(head_refspec.clone());
             }
-            let mut fetch_opts = {
-                let mut opts = self.fetch_options.clone();
-                if !opts.extra_refspecs.contains(&head_refspec) {
-                    opts.extra_refspecs.push(head_refspec.clone());
-                }
-                if let Some(ref_name) = &self.ref_name {
-                    opts.extra_refspecs.push(
-                        gix_refspec::parse(ref_name.as_ref().as_bstr(), gix_refspec::parse::Operation::Fetch)
-                            .expect("partial names are valid refspecs")
-                            .to_owned(),
-                    );
-                }
-                opts
-            };
-            match connection.prepare_fetch(&mut progress, fetch_opts.clone()).await {
-                Ok(prepare) => prepare,
-                Err(remote::fetch::prepare::Error::RefMap(remote::ref_map::Error::InitRefMap(
-                    gix_protocol::fetch::refmap::init::Error::MappingValidation(err),
-                ))) if err.issues.len() == 1
-                    && fetch_opts.extra_refspecs.contains(&head_refspec)
-                    && matches!(
-                        err.issues.first(),
-                        Some(gix_refspec::match_group::validate::Issue::Conflict {
-                            destination_full_ref_name,
-                            ..
-                        }) if *destination_full_ref_name == head_local_tracking_branch
-                    ) =>
-                {
-                    let head_refspec_idx = fetch_opts
-                        .extra_refspecs
-                        .iter()
-                        .enumerate()
-                        .find_map(|(idx, spec)| (*spec == head_refspec).then_some(idx))
-                        .expect("it's contained");
-                    // On the very special occasion that we fail as there is a remote `refs/heads/HEAD` reference that clashes
-                    // with our implicit refspec, retry without it. Maybe this tells us that we shouldn't have that implicit
-                    // refspec, as git can do this without connecting twice.
-                    let connection = remote.connect(remote::Direction::Fetch).await?;
-                    fetch_opts.extra_refspecs.remove(head_refspec_idx);
-                    connection.prepare_fetch(&mut progress, fetch_opts).await?
-                }
-                Err(err) => return Err(err.into()),
+            if let Some(ref_name) = &self.ref_name {
+                opts.extra_refspecs.push(
+                    gix_refspec::parse(ref_name.as_ref().as_bstr(), gix_refspec::parse::Operation::Fetch)
+                        .expect("partial names are valid refspecs")
+                        .to_owned(),
+                );
             }
+            opts
         };
+        // For shallow clones, we already connected once, so we need to connect again
+        let mut pending_pack = connect_and_prepare_fetch(
+            &remote,
+            self.configure_connection.as_mut(),
+            &mut progress,
+            fetch_opts,
+            &head_refspec,
+            &head_local_tracking_branch,
+        )
+        .await?;
+
+        let object_hash = pending_pack.ref_map().object_hash;
+        let reopened = object_hash != repo.object_hash();
+        if reopened {
+            // The server uses a different object hash than the one our repository was initialized with, so configure it
+            // accordingly and continue with the same connection on a repository that expects it.
+            let detached = pending_pack.detach();
+            drop(remote);
+            util::reopen_with_object_hash(repo, object_hash)?;
+            repo.committer_or_set_generic_fallback()?;
+            if !self.config_overrides.is_empty() {
+                let mut snapshot = repo.config_snapshot_mut();
+                snapshot.append_config(&self.config_overrides, gix_config::Source::Api)?;
+            }
+            remote = repo.find_remote(remote_name.as_bstr())?;
+            if let Some(fetch_tags) = clone_fetch_tags {
+                remote = remote.with_fetch_tags(fetch_tags);
+            }
+            pending_pack = detached.attach(&remote);
+            if let Some(f) = self.configure_connection.as_mut() {
+                f(pending_pack.connection_mut()).map_err(Error::RemoteConnection)?;
+            }
+        }
 
         // Assure problems with custom branch names fail early, not after getting the pack or during negotiation.
         if let Some(ref_name) = &self.ref_name {
             util::find_custom_refname(pending_pack.ref_map(), ref_name)?;
         }
-        if pending_pack.ref_map().object_hash != repo.object_hash() {
-            unimplemented!("configure repository to expect a different object hash as advertised by the server")
-        }
         let reflog_message = {
             let mut b = self.url.to_bstring();
             b.insert_str(0, "clone: from ");
             b
         };
+        if let Some(uri) = self.bundle_uri.take() {
+            pending_pack = pending_pack.with_bundle_uri(uri);
+        }
         let outcome = pending_pack
             .with_write_packed_refs_only(true)
             .with_reflog_message(RefLogMessage::Override {
@@ -291,7 +300,10 @@ impl PrepareFetch {
             .receive(&mut progress, should_interrupt)
             .await?;
 
-        util::append_config_to_repo_config(repo, config);
+        if !reopened {
+            // Otherwise the configuration of the remote was already read from disk.
+            util::append_config_to_repo_config(repo, config);
+        }
         util::update_head(
             repo,
             &outcome.ref_map,
@@ -319,10 +331,65 @@ impl PrepareFetch {
             crate::clone::PrepareCheckout {
                 repo: repo.into(),
                 ref_name: self.ref_name.clone(),
+                recurse_submodules: self.recurse_submodules,
             },
             fetch_outcome,
         ))
     }
 }
 
+/// Connect to `remote` and prepare a fetch with `fetch_opts`.
+///
+/// On the very special occasion that we fail as there is a remote `refs/heads/HEAD` reference that clashes
+/// with our implicit `head_refspec`, retry without it. Maybe this tells us that we shouldn't have that implicit
+/// refspec, as git can do this without connecting twice.
+#[gix_protocol::maybe_async::maybe_async]
+async fn connect_and_prepare_fetch<'remote, 'repo, P>(
+    remote: &'remote crate::Remote<'repo>,
+    configure_connection: Option<&mut super::ConfigureConnectionFn>,
+    progress: &mut P,
+    mut fetch_opts: crate::remote::ref_map::Options,
+    head_refspec: &gix_refspec::RefSpec,
+    head_local_tracking_branch: &str,
+) -> Result<crate::remote::fetch::Prepare<'remote, 'repo, Box<dyn super::Transport + Send>>, Error>
+where
+    P: crate::NestedProgress,
+    P::SubProgress: 'static,
+{
+    use crate::remote;
+
+    let mut connection = remote.connect(remote::Direction::Fetch).await?;
+    if let Some(f) = configure_connection {
+        f(&mut connection).map_err(Error::RemoteConnection)?;
+    }
+    Ok(
+        match connection.prepare_fetch(&mut *progress, fetch_opts.clone()).await {
+            Ok(prepare) => prepare,
+            Err(remote::fetch::prepare::Error::RefMap(remote::ref_map::Error::InitRefMap(
+                gix_protocol::fetch::refmap::init::Error::MappingValidation(err),
+            ))) if err.issues.len() == 1
+                && fetch_opts.extra_refspecs.contains(head_refspec)
+                && matches!(
+                    err.issues.first(),
+                    Some(gix_refspec::match_group::validate::Issue::Conflict {
+                        destination_full_ref_name,
+                        ..
+                    }) if *destination_full_ref_name == head_local_tracking_branch
+                ) =>
+            {
+                let head_refspec_idx = fetch_opts
+                    .extra_refspecs
+                    .iter()
+                    .enumerate()
+                    .find_map(|(idx, spec)| (spec == head_refspec).then_some(idx))
+                    .expect("it's contained");
+                let connection = remote.connect(remote::Direction::Fetch).await?;
+                fetch_opts.extra_refspecs.remove(head_refspec_idx);
+                connection.prepare_fetch(progress, fetch_opts).await?
+            }
+            Err(err) => return Err(err.into()),
+        },
+    )
+}
+
 mod util;
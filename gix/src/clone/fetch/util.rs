@@ -53,6 +53,26 @@ fn write_to_local_config(config: &gix_config::File<'static>, mode: WriteMode) ->
     config.write_to_filter(&mut local_config, |s| s.meta().source == gix_config::Source::Local)
 }
 
+/// Configure `repo` to use `object_hash` by writing `extensions.objectFormat` to its local configuration file,
+/// and reopen it so all of its parts pick up the change.
+pub fn reopen_with_object_hash(repo: &mut Repository, object_hash: gix_hash::Kind) -> Result<(), Error> {
+    use crate::config::tree::{Core, Extensions};
+
+    let config_path = local_config_meta(repo).path.expect("local config with path set");
+    let mut config = gix_config::File::from_path_no_includes(config_path.clone(), gix_config::Source::Local)?;
+    config.set_raw_value(&Core::REPOSITORY_FORMAT_VERSION, "1")?;
+    config.set_raw_value(&Extensions::OBJECT_FORMAT, object_hash.to_string().as_str())?;
+    std::fs::write(config_path, config.to_bstring())?;
+
+    *repo = crate::ThreadSafeRepository::open_from_paths(
+        repo.git_dir().to_owned(),
+        repo.workdir().map(ToOwned::to_owned),
+        repo.options.clone(),
+    )?
+    .into();
+    Ok(())
+}
+
 pub fn append_config_to_repo_config(repo: &mut Repository, config: gix_config::File<'static>) {
     let repo_config = gix_features::threading::OwnShared::make_mut(&mut repo.config.resolved);
     repo_config.append(config);
@@ -42,6 +42,12 @@ pub struct PrepareFetch {
     /// The name of the reference to fetch. If `None`, the reference pointed to by `HEAD` will be checked out.
     #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
     ref_name: Option<gix_ref::PartialName>,
+    /// Whether to clone submodules after the checkout. If `None`, `submodule.recurse` is used.
+    #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
+    recurse_submodules: Option<bool>,
+    /// The bundle or bundle list to apply before fetching the pack.
+    #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
+    bundle_uri: Option<BString>,
 }
 
 /// The error returned by [`PrepareFetch::new()`].
@@ -122,6 +128,8 @@ impl PrepareFetch {
             configure_connection: None,
             shallow: remote::fetch::Shallow::NoChange,
             ref_name: None,
+            recurse_submodules: None,
+            bundle_uri: None,
         })
     }
 }
@@ -136,6 +144,9 @@ pub struct PrepareCheckout {
     pub(self) repo: Option<crate::Repository>,
     /// The name of the reference to check out. If `None`, the reference pointed to by `HEAD` will be checked out.
     pub(self) ref_name: Option<gix_ref::PartialName>,
+    /// Whether to clone submodules after the checkout. If `None`, `submodule.recurse` is used.
+    #[cfg_attr(not(feature = "blocking-network-client"), allow(dead_code))]
+    pub(self) recurse_submodules: Option<bool>,
 }
 
 // This module encapsulates functionality that works with both feature toggles. Can be combined with `fetch`
@@ -20,7 +20,7 @@ use crate::{
 impl Cache {
     #[cfg(feature = "blob-diff")]
     pub(crate) fn diff_algorithm(&self) -> Result<gix_diff::blob::Algorithm, config::diff::algorithm::Error> {
-        use crate::config::{cache::util::ApplyLeniencyDefault, diff::algorithm::Error, tree::Diff};
+        use crate::config::{cache::util::ApplyLeniencyDefault, tree::Diff};
         self.diff_algorithm
             .get_or_try_init(|| {
                 let name = self
@@ -29,10 +29,6 @@ impl Cache {
                     .unwrap_or_else(|| Cow::Borrowed("myers".into()));
                 config::tree::Diff::ALGORITHM
                     .try_into_algorithm(name)
-                    .or_else(|err| match err {
-                        Error::Unimplemented { .. } if self.lenient_config => Ok(gix_diff::blob::Algorithm::Histogram),
-                        err => Err(err),
-                    })
                     .with_lenient_default(self.lenient_config)
             })
             .copied()
@@ -83,12 +79,6 @@ impl Cache {
             if let Some(algorithm) = section.value("algorithm") {
                 driver.algorithm = config::tree::Diff::DRIVER_ALGORITHM
                     .try_into_algorithm(algorithm)
-                    .or_else(|err| match err {
-                        config::diff::algorithm::Error::Unimplemented { .. } if self.lenient_config => {
-                            Ok(gix_diff::blob::Algorithm::Histogram)
-                        }
-                        err => Err(err),
-                    })
                     .with_lenient_default(self.lenient_config)
                     .map_err(|err| config::diff::drivers::Error {
                         name: driver.name.clone(),
@@ -514,7 +504,8 @@ impl Cache {
     /// if it actually wants to use the home directory - we don't want to fail prematurely.
     #[cfg(any(
         feature = "blocking-http-transport-reqwest",
-        feature = "blocking-http-transport-curl"
+        feature = "blocking-http-transport-curl",
+        feature = "blame"
     ))]
     pub(crate) fn home_dir(&self) -> Option<PathBuf> {
         home_dir(self.environment)
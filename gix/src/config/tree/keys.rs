@@ -213,6 +213,9 @@ pub type Url = Any<validate::Url>;
 /// A key that represents a UTF-8 string.
 pub type String = Any<validate::String>;
 
+/// A key that represents a color, like `bold red`.
+pub type Color = Any<validate::Color>;
+
 /// A key that represents a `RefSpec` for pushing.
 pub type PushRefSpec = Any<validate::PushRefSpec>;
 
@@ -471,6 +474,33 @@ mod boolean {
     }
 }
 
+mod color {
+    use std::borrow::Cow;
+
+    use crate::{
+        bstr::BStr,
+        config,
+        config::tree::{keys::Color, Section},
+    };
+
+    impl Color {
+        /// Create a new instance.
+        pub const fn new_color(name: &'static str, section: &'static dyn Section) -> Self {
+            Self::new_with_validate(name, section, super::validate::Color)
+        }
+
+        /// Try to parse `value` as color.
+        pub fn try_into_color(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<gix_config::Color, config::key::GenericErrorWithValue> {
+            gix_config::Color::try_from(value.as_ref()).map_err(|err| {
+                config::key::GenericErrorWithValue::from_value(self, value.into_owned()).with_source(err)
+            })
+        }
+    }
+}
+
 mod remote_name {
     use std::borrow::Cow;
 
@@ -654,6 +684,17 @@ pub mod validate {
         }
     }
 
+    /// Assure that values that parse as git colors are valid.
+    #[derive(Default)]
+    pub struct Color;
+
+    impl Validate for Color {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn Error + Send + Sync + 'static>> {
+            gix_config::Color::try_from(value)?;
+            Ok(())
+        }
+    }
+
     /// Any path - everything is allowed.
     pub struct Path;
     impl Validate for Path {
@@ -18,12 +18,17 @@ pub(crate) mod root {
     impl Tree {
         /// The `author` section.
         pub const AUTHOR: sections::Author = sections::Author;
+        /// The `blame` section.
+        #[cfg(feature = "blame")]
+        pub const BLAME: sections::Blame = sections::Blame;
         /// The `branch` section.
         pub const BRANCH: sections::Branch = sections::Branch;
         /// The `checkout` section.
         pub const CHECKOUT: sections::Checkout = sections::Checkout;
         /// The `clone` section.
         pub const CLONE: sections::Clone = sections::Clone;
+        /// The `color` section.
+        pub const COLOR: sections::Color = sections::Color;
         /// The `committer` section.
         pub const COMMITTER: sections::Committer = sections::Committer;
         /// The `core` section.
@@ -37,14 +42,25 @@ pub(crate) mod root {
         pub const EXTENSIONS: sections::Extensions = sections::Extensions;
         /// The `fetch` section.
         pub const FETCH: sections::Fetch = sections::Fetch;
+        /// The `format` section.
+        pub const FORMAT: sections::Format = sections::Format;
+        /// The `fsck` section.
+        pub const FSCK: sections::Fsck = sections::Fsck;
+        /// The `gc` section.
+        pub const GC: sections::Gc = sections::Gc;
         /// The `gitoxide` section.
         pub const GITOXIDE: sections::Gitoxide = sections::Gitoxide;
+        /// The `gpg` section.
+        pub const GPG: sections::Gpg = sections::Gpg;
         /// The `http` section.
         pub const HTTP: sections::Http = sections::Http;
         /// The `index` section.
         pub const INDEX: sections::Index = sections::Index;
         /// The `init` section.
         pub const INIT: sections::Init = sections::Init;
+        /// The `log` section.
+        #[cfg(feature = "revision")]
+        pub const LOG: sections::Log = sections::Log;
         /// The `mailmap` section.
         pub const MAILMAP: sections::Mailmap = sections::Mailmap;
         /// The `merge` section.
@@ -55,8 +71,12 @@ pub(crate) mod root {
         pub const PROTOCOL: sections::Protocol = sections::Protocol;
         /// The `push` section.
         pub const PUSH: sections::Push = sections::Push;
+        /// The `receive` section.
+        pub const RECEIVE: sections::Receive = sections::Receive;
         /// The `remote` section.
         pub const REMOTE: sections::Remote = sections::Remote;
+        /// The `rerere` section.
+        pub const RERERE: sections::Rerere = sections::Rerere;
         /// The `safe` section.
         pub const SAFE: sections::Safe = sections::Safe;
         /// The `ssh` section.
@@ -64,6 +84,10 @@ pub(crate) mod root {
         /// The `status` section.
         #[cfg(feature = "status")]
         pub const STATUS: sections::Status = sections::Status;
+        /// The `submodule` section.
+        pub const SUBMODULE: sections::Submodule = sections::Submodule;
+        /// The `transfer` section.
+        pub const TRANSFER: sections::Transfer = sections::Transfer;
         /// The `user` section.
         pub const USER: sections::User = sections::User;
         /// The `url` section.
@@ -73,9 +97,12 @@ pub(crate) mod root {
         pub fn sections(&self) -> &[&dyn Section] {
             &[
                 &Self::AUTHOR,
+                #[cfg(feature = "blame")]
+                &Self::BLAME,
                 &Self::BRANCH,
                 &Self::CHECKOUT,
                 &Self::CLONE,
+                &Self::COLOR,
                 &Self::COMMITTER,
                 &Self::CORE,
                 &Self::CREDENTIAL,
@@ -83,20 +110,30 @@ pub(crate) mod root {
                 &Self::DIFF,
                 &Self::EXTENSIONS,
                 &Self::FETCH,
+                &Self::FORMAT,
+                &Self::FSCK,
+                &Self::GC,
                 &Self::GITOXIDE,
+                &Self::GPG,
                 &Self::HTTP,
                 &Self::INDEX,
                 &Self::INIT,
+                #[cfg(feature = "revision")]
+                &Self::LOG,
                 &Self::MAILMAP,
                 &Self::MERGE,
                 &Self::PACK,
                 &Self::PROTOCOL,
                 &Self::PUSH,
+                &Self::RECEIVE,
                 &Self::REMOTE,
+                &Self::RERERE,
                 &Self::SAFE,
                 &Self::SSH,
                 #[cfg(feature = "status")]
                 &Self::STATUS,
+                &Self::SUBMODULE,
+                &Self::TRANSFER,
                 &Self::USER,
                 &Self::URL,
             ]
@@ -105,13 +142,18 @@ pub(crate) mod root {
 }
 
 mod sections;
+#[cfg(feature = "blame")]
+pub use sections::Blame;
 pub use sections::{
-    branch, checkout, core, credential, extensions, fetch, gitoxide, http, index, protocol, push, remote, ssh, Author,
-    Branch, Checkout, Clone, Committer, Core, Credential, Extensions, Fetch, Gitoxide, Http, Index, Init, Mailmap,
-    Merge, Pack, Protocol, Push, Remote, Safe, Ssh, Url, User,
+    branch, checkout, color, core, credential, extensions, fetch, gc, gitoxide, http, index, protocol, push, remote,
+    ssh, Author, Branch, Checkout, Clone, Color, Committer, Core, Credential, Extensions, Fetch, Format, Fsck, Gc,
+    Gitoxide, Gpg, Http, Index, Init, Mailmap, Merge, Pack, Protocol, Push, Receive, Remote, Rerere, Safe, Ssh,
+    Submodule, Transfer, Url, User,
 };
 #[cfg(feature = "blob-diff")]
 pub use sections::{diff, Diff};
+#[cfg(feature = "revision")]
+pub use sections::{log, Log};
 #[cfg(feature = "status")]
 pub use sections::{status, Status};
 
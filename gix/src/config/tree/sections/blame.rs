@@ -0,0 +1,29 @@
+use crate::{
+    config,
+    config::tree::{keys, Blame, Key, Section},
+};
+
+impl Blame {
+    /// The `blame.ignoreRevsFile` key.
+    pub const IGNORE_REVS_FILE: keys::Path = keys::Path::new_path("ignoreRevsFile", &config::Tree::BLAME)
+        .with_note("can be specified multiple times, relative paths are relative to the worktree");
+    /// The `blame.markUnblamableLines` key.
+    pub const MARK_UNBLAMABLE_LINES: keys::Boolean =
+        keys::Boolean::new_boolean("markUnblamableLines", &config::Tree::BLAME);
+    /// The `blame.markIgnoredLines` key.
+    pub const MARK_IGNORED_LINES: keys::Boolean = keys::Boolean::new_boolean("markIgnoredLines", &config::Tree::BLAME);
+}
+
+impl Section for Blame {
+    fn name(&self) -> &str {
+        "blame"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[
+            &Self::IGNORE_REVS_FILE,
+            &Self::MARK_UNBLAMABLE_LINES,
+            &Self::MARK_IGNORED_LINES,
+        ]
+    }
+}
@@ -0,0 +1,134 @@
+use crate::{
+    config,
+    config::tree::{keys, Color, Key, Section},
+};
+
+impl Color {
+    /// The `color.ui` key.
+    pub const UI: When = When::new_with_validate("ui", &config::Tree::COLOR, validate::When);
+    /// The `color.diff` key.
+    pub const DIFF: When = When::new_with_validate("diff", &config::Tree::COLOR, validate::When);
+
+    /// The `color.diff` section.
+    pub const DIFF_SLOTS: Diff = Diff;
+}
+
+impl Section for Color {
+    fn name(&self) -> &str {
+        "color"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::UI, &Self::DIFF]
+    }
+
+    fn sub_sections(&self) -> &[&dyn Section] {
+        &[&Self::DIFF_SLOTS]
+    }
+}
+
+/// The `color.ui` and `color.<command>` keys.
+pub type When = keys::Any<validate::When>;
+
+mod when {
+    use std::borrow::Cow;
+
+    use crate::{
+        bstr::{BStr, ByteSlice},
+        config,
+        config::tree::color::When,
+    };
+
+    impl When {
+        /// Convert `value` into a tri-state boolean that is `None` if colors should only be used when writing to a terminal,
+        /// which is the case for `auto` and `true`, or is `Some(true)` for `always` and `Some(false)` for `never` or `false`.
+        pub fn try_into_when(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<Option<bool>, config::key::GenericErrorWithValue> {
+            Ok(match value.as_bytes() {
+                b"auto" => None,
+                b"always" => Some(true),
+                b"never" => Some(false),
+                _ => match gix_config::Boolean::try_from(value.as_ref()) {
+                    Ok(boolean) => (!boolean.0).then_some(false),
+                    Err(err) => {
+                        return Err(
+                            config::key::GenericErrorWithValue::from_value(self, value.into_owned()).with_source(err)
+                        )
+                    }
+                },
+            })
+        }
+    }
+}
+
+/// The `color.diff` sub-section.
+#[derive(Copy, Clone, Default)]
+pub struct Diff;
+
+impl Diff {
+    /// The `color.diff.context` key.
+    pub const CONTEXT: keys::Color = keys::Color::new_color("context", &Color::DIFF_SLOTS);
+    /// The `color.diff.meta` key.
+    pub const META: keys::Color = keys::Color::new_color("meta", &Color::DIFF_SLOTS);
+    /// The `color.diff.frag` key.
+    pub const FRAG: keys::Color = keys::Color::new_color("frag", &Color::DIFF_SLOTS);
+    /// The `color.diff.func` key.
+    pub const FUNC: keys::Color = keys::Color::new_color("func", &Color::DIFF_SLOTS);
+    /// The `color.diff.old` key.
+    pub const OLD: keys::Color = keys::Color::new_color("old", &Color::DIFF_SLOTS);
+    /// The `color.diff.new` key.
+    pub const NEW: keys::Color = keys::Color::new_color("new", &Color::DIFF_SLOTS);
+    /// The `color.diff.whitespace` key.
+    pub const WHITESPACE: keys::Color = keys::Color::new_color("whitespace", &Color::DIFF_SLOTS);
+    /// The `color.diff.oldMoved` key.
+    pub const OLD_MOVED: keys::Color = keys::Color::new_color("oldMoved", &Color::DIFF_SLOTS);
+    /// The `color.diff.oldMovedAlternative` key.
+    pub const OLD_MOVED_ALTERNATIVE: keys::Color = keys::Color::new_color("oldMovedAlternative", &Color::DIFF_SLOTS);
+    /// The `color.diff.newMoved` key.
+    pub const NEW_MOVED: keys::Color = keys::Color::new_color("newMoved", &Color::DIFF_SLOTS);
+    /// The `color.diff.newMovedAlternative` key.
+    pub const NEW_MOVED_ALTERNATIVE: keys::Color = keys::Color::new_color("newMovedAlternative", &Color::DIFF_SLOTS);
+}
+
+impl Section for Diff {
+    fn name(&self) -> &str {
+        "diff"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[
+            &Self::CONTEXT,
+            &Self::META,
+            &Self::FRAG,
+            &Self::FUNC,
+            &Self::OLD,
+            &Self::NEW,
+            &Self::WHITESPACE,
+            &Self::OLD_MOVED,
+            &Self::OLD_MOVED_ALTERNATIVE,
+            &Self::NEW_MOVED,
+            &Self::NEW_MOVED_ALTERNATIVE,
+        ]
+    }
+
+    fn parent(&self) -> Option<&dyn Section> {
+        Some(&config::Tree::COLOR)
+    }
+}
+
+mod validate {
+    use crate::{
+        bstr::BStr,
+        config::tree::{keys, Color},
+    };
+
+    pub struct When;
+    impl keys::Validate for When {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            Color::UI.try_into_when(value.into())?;
+            Ok(())
+        }
+    }
+}
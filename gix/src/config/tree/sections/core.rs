@@ -24,6 +24,8 @@ impl Core {
         Disambiguate::new_with_validate("disambiguate", &config::Tree::CORE, validate::Disambiguate);
     /// The `core.editor` key.
     pub const EDITOR: keys::Program = keys::Program::new_program("editor", &config::Tree::CORE);
+    /// The `core.hooksPath` key.
+    pub const HOOKS_PATH: keys::Path = keys::Path::new_path("hooksPath", &config::Tree::CORE);
     /// The `core.fileMode` key.
     pub const FILE_MODE: keys::Boolean = keys::Boolean::new_boolean("fileMode", &config::Tree::CORE);
     /// The `core.ignoreCase` key.
@@ -110,6 +112,7 @@ impl Section for Core {
             &Self::DISAMBIGUATE,
             &Self::EDITOR,
             &Self::FILE_MODE,
+            &Self::HOOKS_PATH,
             &Self::IGNORE_CASE,
             &Self::FILES_REF_LOCK_TIMEOUT,
             &Self::PACKED_REFS_TIMEOUT,
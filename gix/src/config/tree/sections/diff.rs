@@ -5,8 +5,9 @@ use crate::{
 
 impl Diff {
     /// The `diff.algorithm` key.
-    pub const ALGORITHM: Algorithm = Algorithm::new_with_validate("algorithm", &config::Tree::DIFF, validate::Algorithm)
-                                        .with_deviation("'patience' diff is not implemented and can default to 'histogram' if lenient config is used, and defaults to histogram if unset for fastest and best results");
+    pub const ALGORITHM: Algorithm =
+        Algorithm::new_with_validate("algorithm", &config::Tree::DIFF, validate::Algorithm)
+            .with_deviation("defaults to histogram if unset for fastest and best results");
     /// The `diff.renameLimit` key.
     pub const RENAME_LIMIT: keys::UnsignedInteger = keys::UnsignedInteger::new_unsigned_integer(
         "renameLimit",
@@ -38,6 +39,11 @@ impl Diff {
     pub const DRIVER_BINARY: Binary = Binary::new_with_validate("binary", &config::Tree::DIFF, validate::Binary)
         .with_subsection_requirement(Some(SubSectionRequirement::Parameter("driver")));
 
+    /// The `diff.colorMoved` key.
+    pub const COLOR_MOVED: ColorMoved =
+        ColorMoved::new_with_validate("colorMoved", &config::Tree::DIFF, validate::ColorMoved)
+            .with_deviation("`dimmed-zebra` is treated like `zebra` as moved lines are never dimmed");
+
     /// The `diff.external` key.
     pub const EXTERNAL: keys::Program =
         keys::Program::new_program("external", &config::Tree::DIFF).with_environment_override("GIT_EXTERNAL_DIFF");
@@ -59,6 +65,7 @@ impl Section for Diff {
             &Self::DRIVER_ALGORITHM,
             &Self::DRIVER_BINARY,
             &Self::EXTERNAL,
+            &Self::COLOR_MOVED,
         ]
     }
 }
@@ -72,6 +79,9 @@ pub type Ignore = keys::Any<validate::Ignore>;
 /// The `diff.renames` key.
 pub type Renames = keys::Any<validate::Renames>;
 
+/// The `diff.colorMoved` key.
+pub type ColorMoved = keys::Any<validate::ColorMoved>;
+
 /// The `diff.<driver>.binary` key.
 pub type Binary = keys::Any<validate::Binary>;
 
@@ -80,7 +90,6 @@ mod algorithm {
 
     use crate::{
         bstr::BStr,
-        config,
         config::{
             diff::algorithm,
             key,
@@ -109,9 +118,7 @@ mod algorithm {
             } else if name.eq_ignore_ascii_case(b"histogram") {
                 gix_diff::blob::Algorithm::Histogram
             } else if name.eq_ignore_ascii_case(b"patience") {
-                return Err(config::diff::algorithm::Error::Unimplemented {
-                    name: name.into_owned(),
-                });
+                gix_diff::blob::Algorithm::Patience
             } else {
                 return Err(algorithm::Error::Unknown {
                     name: name.into_owned(),
@@ -122,6 +129,46 @@ mod algorithm {
     }
 }
 
+mod color_moved {
+    use crate::{
+        bstr::{BStr, ByteSlice},
+        config::tree::diff::ColorMoved,
+    };
+
+    impl ColorMoved {
+        /// Return `true` if `value` is valid, which is the case for booleans and the names of the modes of moved-line detection.
+        pub(super) fn is_valid(value: &BStr) -> bool {
+            matches!(
+                value.as_bytes(),
+                b"default" | b"plain" | b"blocks" | b"zebra" | b"dimmed-zebra" | b"dimmed_zebra"
+            ) || gix_config::Boolean::try_from(value).is_ok()
+        }
+
+        /// Convert `value` into the way moved lines are detected, or `None` if they shouldn't be detected.
+        #[cfg(feature = "apply")]
+        pub fn try_into_color_moved(
+            &'static self,
+            value: std::borrow::Cow<'_, BStr>,
+        ) -> Result<Option<gix_diff::patch::color::Moved>, crate::config::key::GenericErrorWithValue> {
+            use gix_diff::patch::color::Moved;
+            Ok(match value.as_bytes() {
+                b"plain" => Some(Moved::Plain),
+                b"blocks" => Some(Moved::Blocks),
+                b"default" | b"zebra" | b"dimmed-zebra" | b"dimmed_zebra" => Some(Moved::Zebra),
+                _ => match gix_config::Boolean::try_from(value.as_ref()) {
+                    Ok(boolean) => boolean.0.then_some(Moved::Zebra),
+                    Err(err) => {
+                        return Err(
+                            crate::config::key::GenericErrorWithValue::from_value(self, value.into_owned())
+                                .with_source(err),
+                        )
+                    }
+                },
+            })
+        }
+    }
+}
+
 mod binary {
     use crate::config::tree::diff::Binary;
 
@@ -222,6 +269,16 @@ pub(super) mod validate {
         }
     }
 
+    pub struct ColorMoved;
+    impl keys::Validate for ColorMoved {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            if !super::ColorMoved::is_valid(value) {
+                return Err(format!("Value '{value}' is not a valid 'colorMoved' value").into());
+            }
+            Ok(())
+        }
+    }
+
     pub struct Binary;
     impl keys::Validate for Binary {
         fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
@@ -8,12 +8,11 @@ impl Extensions {
     pub const WORKTREE_CONFIG: keys::Boolean = keys::Boolean::new_boolean("worktreeConfig", &config::Tree::EXTENSIONS);
     /// The `extensions.objectFormat` key.
     pub const OBJECT_FORMAT: ObjectFormat =
-        ObjectFormat::new_with_validate("objectFormat", &config::Tree::EXTENSIONS, validate::ObjectFormat).with_note(
-            "Support for SHA256 is prepared but not fully implemented yet. For now we abort when encountered",
-        );
+        ObjectFormat::new_with_validate("objectFormat", &config::Tree::EXTENSIONS, validate::ObjectFormat)
+            .with_note("SHA256 is only supported if the `sha256` feature is enabled");
 }
 
-/// The `core.checkStat` key.
+/// The `extensions.objectFormat` key.
 pub type ObjectFormat = keys::Any<validate::ObjectFormat>;
 
 mod object_format {
@@ -26,11 +25,10 @@ mod object_format {
             &'static self,
             value: Cow<'_, BStr>,
         ) -> Result<gix_hash::Kind, config::key::GenericErrorWithValue> {
-            if value.as_ref().eq_ignore_ascii_case(b"sha1") {
-                Ok(gix_hash::Kind::Sha1)
-            } else {
-                Err(config::key::GenericErrorWithValue::from_value(self, value.into_owned()))
-            }
+            std::str::from_utf8(value.as_ref())
+                .ok()
+                .and_then(|format| format.to_ascii_lowercase().parse::<gix_hash::Kind>().ok())
+                .ok_or_else(|| config::key::GenericErrorWithValue::from_value(self, value.into_owned()))
         }
     }
 }
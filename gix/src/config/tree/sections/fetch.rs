@@ -14,6 +14,10 @@ impl Fetch {
     #[cfg(feature = "attributes")]
     pub const RECURSE_SUBMODULES: RecurseSubmodules =
         RecurseSubmodules::new_with_validate("recurseSubmodules", &config::Tree::FETCH, validate::RecurseSubmodules);
+    /// The `fetch.uriProtocols` key.
+    pub const URI_PROTOCOLS: keys::String = keys::String::new_string("uriProtocols", &config::Tree::FETCH);
+    /// The `fetch.bundleURI` key.
+    pub const BUNDLE_URI: keys::Url = keys::Url::new_url("bundleURI", &config::Tree::FETCH);
 }
 
 impl Section for Fetch {
@@ -26,6 +30,8 @@ impl Section for Fetch {
             &Self::NEGOTIATION_ALGORITHM,
             #[cfg(feature = "attributes")]
             &Self::RECURSE_SUBMODULES,
+            &Self::URI_PROTOCOLS,
+            &Self::BUNDLE_URI,
         ]
     }
 }
@@ -0,0 +1,19 @@
+use crate::{
+    config,
+    config::tree::{keys, Format, Key, Section},
+};
+
+impl Format {
+    /// The `format.pretty` key.
+    pub const PRETTY: keys::String = keys::String::new_string("pretty", &config::Tree::FORMAT);
+}
+
+impl Section for Format {
+    fn name(&self) -> &str {
+        "format"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::PRETTY]
+    }
+}
@@ -0,0 +1,97 @@
+use crate::{
+    config,
+    config::tree::{keys, Gc, Key, Section},
+};
+
+impl Gc {
+    /// The `gc.auto` key.
+    pub const AUTO: keys::UnsignedInteger = keys::UnsignedInteger::new_unsigned_integer("auto", &config::Tree::GC);
+    /// The `gc.autoPackLimit` key.
+    pub const AUTO_PACK_LIMIT: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("autoPackLimit", &config::Tree::GC);
+    /// The `gc.bigPackThreshold` key.
+    pub const BIG_PACK_THRESHOLD: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("bigPackThreshold", &config::Tree::GC);
+    /// The `gc.packRefs` key.
+    pub const PACK_REFS: keys::Any = keys::Any::new("packRefs", &config::Tree::GC);
+    /// The `gc.pruneExpire` key.
+    pub const PRUNE_EXPIRE: Expiry = Expiry::new_with_validate("pruneExpire", &config::Tree::GC, validate::Expiry);
+    /// The `gc.reflogExpire` key.
+    pub const REFLOG_EXPIRE: Expiry = Expiry::new_with_validate("reflogExpire", &config::Tree::GC, validate::Expiry)
+        .with_deviation("the per-pattern variant 'gc.<pattern>.reflogExpire' isn't supported");
+    /// The `gc.reflogExpireUnreachable` key.
+    pub const REFLOG_EXPIRE_UNREACHABLE: Expiry =
+        Expiry::new_with_validate("reflogExpireUnreachable", &config::Tree::GC, validate::Expiry)
+            .with_deviation("the per-pattern variant 'gc.<pattern>.reflogExpireUnreachable' isn't supported");
+    /// The `gc.worktreePruneExpire` key.
+    pub const WORKTREE_PRUNE_EXPIRE: Expiry =
+        Expiry::new_with_validate("worktreePruneExpire", &config::Tree::GC, validate::Expiry);
+}
+
+/// The `gc.pruneExpire`, `gc.reflogExpire`, `gc.reflogExpireUnreachable` and `gc.worktreePruneExpire` keys.
+pub type Expiry = keys::Any<validate::Expiry>;
+
+mod expiry {
+    use std::borrow::Cow;
+
+    use crate::{
+        bstr::{BStr, ByteSlice},
+        config,
+        config::tree::sections::gc::Expiry,
+    };
+
+    impl Expiry {
+        /// Convert `value` into the time before which items expire, with `now` as reference for relative dates.
+        ///
+        /// Return `None` if nothing should ever expire, which is the case for `never` or `false`,
+        /// whereas `now` and `all` cause everything to expire.
+        pub fn try_into_expiry(
+            &'static self,
+            value: Cow<'_, BStr>,
+            now: std::time::SystemTime,
+        ) -> Result<Option<gix_date::Time>, config::time::Error> {
+            let make_err = || config::time::Error::from_value(self, value.as_ref().to_owned());
+            let value_str = value.to_str().map_err(|_| make_err())?;
+            Ok(match value_str {
+                "never" | "false" => None,
+                "now" | "all" => Some(gix_date::Time::new(
+                    now.duration_since(std::time::UNIX_EPOCH)
+                        .map_or(0, |d| d.as_secs() as gix_date::SecondsSinceUnixEpoch),
+                    0,
+                )),
+                _ => Some(gix_date::parse(value_str, Some(now)).map_err(|err| make_err().with_source(err))?),
+            })
+        }
+    }
+}
+
+impl Section for Gc {
+    fn name(&self) -> &str {
+        "gc"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[
+            &Self::AUTO,
+            &Self::AUTO_PACK_LIMIT,
+            &Self::BIG_PACK_THRESHOLD,
+            &Self::PACK_REFS,
+            &Self::PRUNE_EXPIRE,
+            &Self::REFLOG_EXPIRE,
+            &Self::REFLOG_EXPIRE_UNREACHABLE,
+            &Self::WORKTREE_PRUNE_EXPIRE,
+        ]
+    }
+}
+
+mod validate {
+    use crate::{bstr::BStr, config::tree::keys};
+
+    pub struct Expiry;
+    impl keys::Validate for Expiry {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            super::Gc::PRUNE_EXPIRE.try_into_expiry(value.into(), std::time::SystemTime::now())?;
+            Ok(())
+        }
+    }
+}
@@ -0,0 +1,20 @@
+use crate::config::{
+    tree::{keys, Gpg, Key, Section},
+    Tree,
+};
+
+impl Gpg {
+    /// The `gpg.program` key.
+    pub const PROGRAM: keys::Program = keys::Program::new_program("program", &Tree::GPG)
+        .with_note("only used to verify signatures, with `gpg` being the default");
+}
+
+impl Section for Gpg {
+    fn name(&self) -> &str {
+        "gpg"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::PROGRAM]
+    }
+}
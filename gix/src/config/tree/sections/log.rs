@@ -0,0 +1,63 @@
+use crate::{
+    config,
+    config::tree::{keys, Key, Log, Section},
+};
+
+impl Log {
+    /// The `log.date` key.
+    pub const DATE: Date = Date::new_with_validate("date", &config::Tree::LOG, validate::Date)
+        .with_deviation("the 'human' and 'auto:' formats aren't supported");
+    /// The `log.mailmap` key.
+    pub const MAILMAP: keys::Boolean = keys::Boolean::new_boolean("mailmap", &config::Tree::LOG);
+    /// The `log.abbrevCommit` key.
+    pub const ABBREV_COMMIT: keys::Boolean = keys::Boolean::new_boolean("abbrevCommit", &config::Tree::LOG);
+}
+
+impl Section for Log {
+    fn name(&self) -> &str {
+        "log"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::DATE, &Self::MAILMAP, &Self::ABBREV_COMMIT]
+    }
+}
+
+/// The `log.date` key.
+pub type Date = keys::Any<validate::Date>;
+
+mod date {
+    use std::borrow::Cow;
+
+    use crate::{
+        bstr::BStr,
+        config::{key::GenericErrorWithValue, tree::sections::log::Date},
+        pretty::{date, DateMode},
+    };
+
+    impl Date {
+        /// Parse `value` as the format to show dates in, like `iso` or `format:%Y-%m-%d`.
+        pub fn try_into_date_mode(
+            &'static self,
+            value: Cow<'_, BStr>,
+        ) -> Result<DateMode, GenericErrorWithValue<date::Error>> {
+            DateMode::from_bytes(value.as_ref())
+                .map_err(|err| GenericErrorWithValue::from_value(self, value.into_owned()).with_source(err))
+        }
+    }
+}
+
+pub(super) mod validate {
+    use crate::{
+        bstr::BStr,
+        config::tree::{keys, Log},
+    };
+
+    pub struct Date;
+    impl keys::Validate for Date {
+        fn validate(&self, value: &BStr) -> Result<(), Box<dyn std::error::Error + Send + Sync + 'static>> {
+            Log::DATE.try_into_date_mode(value.into())?;
+            Ok(())
+        }
+    }
+}
@@ -6,6 +6,13 @@
 pub struct Author;
 mod author;
 
+/// The `blame` top-level section.
+#[derive(Copy, Clone, Default)]
+#[cfg(feature = "blame")]
+pub struct Blame;
+#[cfg(feature = "blame")]
+mod blame;
+
 /// The `branch` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Branch;
@@ -21,6 +28,11 @@ pub mod checkout;
 pub struct Clone;
 mod clone;
 
+/// The `color` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Color;
+pub mod color;
+
 /// The `committer` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Committer;
@@ -53,11 +65,31 @@ pub mod extensions;
 pub struct Fetch;
 pub mod fetch;
 
+/// The `format` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Format;
+mod format;
+
+/// The `fsck` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Fsck;
+mod fsck;
+
+/// The `gc` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Gc;
+pub mod gc;
+
 /// The `gitoxide` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Gitoxide;
 pub mod gitoxide;
 
+/// The `gpg` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Gpg;
+mod gpg;
+
 /// The `http` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Http;
@@ -73,6 +105,13 @@ pub mod index;
 pub struct Init;
 mod init;
 
+/// The `log` top-level section.
+#[derive(Copy, Clone, Default)]
+#[cfg(feature = "revision")]
+pub struct Log;
+#[cfg(feature = "revision")]
+pub mod log;
+
 #[derive(Copy, Clone, Default)]
 pub struct Mailmap;
 mod mailmap;
@@ -96,11 +135,21 @@ pub mod protocol;
 pub struct Push;
 pub mod push;
 
+/// The `receive` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Receive;
+mod receive;
+
 /// The `remote` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Remote;
 pub mod remote;
 
+/// The `rerere` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Rerere;
+mod rerere;
+
 /// The `safe` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct Safe;
@@ -118,6 +167,16 @@ pub struct Status;
 #[cfg(feature = "status")]
 pub mod status;
 
+/// The `submodule` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Submodule;
+mod submodule;
+
+/// The `transfer` top-level section.
+#[derive(Copy, Clone, Default)]
+pub struct Transfer;
+mod transfer;
+
 /// The `user` top-level section.
 #[derive(Copy, Clone, Default)]
 pub struct User;
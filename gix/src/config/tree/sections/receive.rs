@@ -0,0 +1,28 @@
+use crate::config::{
+    tree::{keys, Key, Receive, Section},
+    Tree,
+};
+
+impl Receive {
+    /// The `receive.denyDeletes` key.
+    pub const DENY_DELETES: keys::Boolean = keys::Boolean::new_boolean("denyDeletes", &Tree::RECEIVE);
+    /// The `receive.denyCurrentBranch` key.
+    pub const DENY_CURRENT_BRANCH: keys::Any = keys::Any::new("denyCurrentBranch", &Tree::RECEIVE)
+        .with_deviation("'updateInstead' is not supported and acts like 'refuse'");
+    /// The `receive.denyNonFastForwards` key.
+    pub const DENY_NON_FAST_FORWARDS: keys::Boolean = keys::Boolean::new_boolean("denyNonFastForwards", &Tree::RECEIVE);
+}
+
+impl Section for Receive {
+    fn name(&self) -> &str {
+        "receive"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[
+            &Self::DENY_CURRENT_BRANCH,
+            &Self::DENY_DELETES,
+            &Self::DENY_NON_FAST_FORWARDS,
+        ]
+    }
+}
@@ -0,0 +1,31 @@
+use crate::{
+    config,
+    config::tree::{keys, Key, Rerere, Section},
+};
+
+impl Rerere {
+    /// The `rerere.enabled` key.
+    pub const ENABLED: keys::Boolean = keys::Boolean::new_boolean("enabled", &config::Tree::RERERE)
+        .with_note("if unset, recorded resolutions are used if the 'rr-cache' directory exists");
+    /// The `rerere.autoUpdate` key.
+    pub const AUTO_UPDATE: keys::Boolean = keys::Boolean::new_boolean("autoUpdate", &config::Tree::RERERE)
+        .with_deviation("replayed resolutions are always added to the index as if this was true");
+    /// The `rerere.resolved` key.
+    pub const RESOLVED: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("resolved", &config::Tree::RERERE)
+            .with_deviation("only a number of days is supported, but no date");
+    /// The `rerere.unresolved` key.
+    pub const UNRESOLVED: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("unresolved", &config::Tree::RERERE)
+            .with_deviation("only a number of days is supported, but no date");
+}
+
+impl Section for Rerere {
+    fn name(&self) -> &str {
+        "rerere"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::ENABLED, &Self::AUTO_UPDATE, &Self::RESOLVED, &Self::UNRESOLVED]
+    }
+}
@@ -0,0 +1,24 @@
+use crate::{
+    config,
+    config::tree::{keys, Key, Section, Submodule},
+};
+
+impl Submodule {
+    /// The `submodule.recurse` key.
+    pub const RECURSE: keys::Boolean =
+        keys::Boolean::new_boolean("recurse", &config::Tree::SUBMODULE).with_note("only affects cloning and fetching");
+    /// The `submodule.fetchJobs` key.
+    pub const FETCH_JOBS: keys::UnsignedInteger =
+        keys::UnsignedInteger::new_unsigned_integer("fetchJobs", &config::Tree::SUBMODULE)
+            .with_note("0 means one job per logical core, and it defaults to 1 if unset");
+}
+
+impl Section for Submodule {
+    fn name(&self) -> &str {
+        "submodule"
+    }
+
+    fn keys(&self) -> &[&dyn Key] {
+        &[&Self::RECURSE, &Self::FETCH_JOBS]
+    }
+}
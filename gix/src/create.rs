@@ -117,6 +117,10 @@ pub struct Options {
     /// If set, use these filesystem capabilities to populate the respective git-config fields.
     /// If `None`, the directory will be probed.
     pub fs_capabilities: Option<gix_fs::Capabilities>,
+    /// The kind of hash to use for object ids in the new repository.
+    ///
+    /// Anything but SHA1 is recorded as `extensions.objectFormat`, which requires `core.repositoryformatversion` to be `1`.
+    pub object_hash: gix_hash::Kind,
 }
 
 /// Create a new `.git` repository of `kind` within the possibly non-existing `directory`
@@ -130,6 +134,7 @@ pub fn into(
     Options {
         fs_capabilities,
         destination_must_be_empty,
+        object_hash,
     }: Options,
 ) -> Result<gix_discover::repository::Path, Error> {
     let mut dot_git = directory.into();
@@ -214,13 +219,21 @@ pub fn into(
             let caps = fs_capabilities.unwrap_or_else(|| gix_fs::Capabilities::probe(&dot_git));
             let mut core = config.new_section("core", None).expect("valid section name");
 
-            core.push(key("repositoryformatversion"), Some("0".into()));
+            let needs_extensions = object_hash != gix_hash::Kind::Sha1;
+            core.push(
+                key("repositoryformatversion"),
+                Some(if needs_extensions { "1" } else { "0" }.into()),
+            );
             core.push(key("filemode"), Some(bool(caps.executable_bit).into()));
             core.push(key("bare"), Some(bool(bare).into()));
             core.push(key("logallrefupdates"), Some(bool(!bare).into()));
             core.push(key("symlinks"), Some(bool(caps.symlink).into()));
             core.push(key("ignorecase"), Some(bool(caps.ignore_case).into()));
             core.push(key("precomposeunicode"), Some(bool(caps.precompose_unicode).into()));
+            if needs_extensions {
+                let mut extensions = config.new_section("extensions", None).expect("valid section name");
+                extensions.push(key("objectformat"), Some(object_hash.to_string().as_str().into()));
+            }
             caps
         };
         config_file
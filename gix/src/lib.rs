@@ -127,6 +127,8 @@ pub use gix_negotiate as negotiate;
 pub use gix_object as objs;
 pub use gix_object::bstr;
 pub use gix_odb as odb;
+#[cfg(feature = "receive-pack")]
+pub use gix_packetline as packetline;
 #[cfg(feature = "credentials")]
 pub use gix_prompt as prompt;
 pub use gix_protocol as protocol;
@@ -207,6 +209,22 @@ pub mod diff;
 #[cfg(feature = "merge")]
 pub mod merge;
 
+///
+#[cfg(feature = "apply")]
+pub mod apply;
+
+///
+#[cfg(feature = "mailbox")]
+pub mod mailbox;
+
+///
+#[cfg(feature = "receive-pack")]
+pub mod receive_pack;
+
+///
+#[cfg(feature = "maintenance")]
+pub mod maintenance;
+
 /// Try to open a git repository in `directory` and search upwards through its parents until one is found,
 /// using default trust options which matters in case the found repository isn't owned by the current user.
 ///
@@ -335,6 +353,10 @@ pub mod worktree;
 
 pub mod revision;
 
+///
+#[cfg(feature = "revision")]
+pub mod pretty;
+
 #[cfg(feature = "attributes")]
 pub mod filter;
 
@@ -0,0 +1,158 @@
+use crate::bstr::{BStr, BString, ByteSlice};
+
+/// Options for use in [`Repository::format_patch()`](crate::Repository::format_patch()).
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The text within the brackets at the start of the subject, like `git format-patch --subject-prefix`.
+    pub subject_prefix: BString,
+    /// If `Some(true)`, always number patches like `[PATCH 1/1]`, and never if `Some(false)`.
+    /// If `None`, patches are numbered if there is more than one, like Git does by default.
+    pub numbered: Option<bool>,
+    /// The number of the first patch, like `git format-patch --start-number`.
+    pub start_number: usize,
+    /// The amount of lines of context around each change.
+    pub context_lines: u32,
+    /// The text to write after the `-- ` line at the end of each email, like `git format-patch --signature`.
+    ///
+    /// If `None`, no signature is written, like `--no-signature`.
+    pub signature: Option<BString>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            subject_prefix: "PATCH".into(),
+            numbered: None,
+            start_number: 1,
+            context_lines: 3,
+            signature: None,
+        }
+    }
+}
+
+/// The maximum length of header lines, as used by Git.
+const MAX_HEADER_LEN: usize = 78;
+/// The maximum length of lines with encoded words, as defined by RFC 2047.
+const MAX_ENCODED_LEN: usize = 76;
+
+/// Append `Subject: {prefix}{title}` to `out`, encoding or wrapping `title` as needed, without trailing newline.
+pub(crate) fn write_subject(prefix: &str, title: &BStr, out: &mut Vec<u8>) {
+    out.extend_from_slice(b"Subject: ");
+    out.extend_from_slice(prefix.as_bytes());
+    let prefix_len = "Subject: ".len() + prefix.len();
+    if needs_encoding(title) {
+        encode_rfc2047(title, prefix_len, false, out);
+    } else {
+        wrap(title, prefix_len, out);
+    }
+}
+
+/// Append `From: {name} <{email}>` to `out`, encoding or quoting `name` as needed, without trailing newline.
+pub(crate) fn write_from(name: &BStr, email: &BStr, out: &mut Vec<u8>) {
+    out.extend_from_slice(b"From: ");
+    if needs_encoding(name) {
+        encode_rfc2047(name, "From: ".len(), true, out);
+    } else if name.iter().any(|b| b"()<>@,;:\\\".[]".contains(b)) {
+        out.push(b'"');
+        for &b in name.iter() {
+            if b == b'"' || b == b'\\' {
+                out.push(b'\\');
+            }
+            out.push(b);
+        }
+        out.push(b'"');
+    } else {
+        out.extend_from_slice(name);
+    }
+    out.extend_from_slice(b" <");
+    out.extend_from_slice(email);
+    out.push(b'>');
+}
+
+pub(crate) fn needs_encoding(text: &BStr) -> bool {
+    !text.is_ascii() || text.contains(&b'\n') || text.contains_str("=?")
+}
+
+/// Encode `text` as RFC 2047 encoded words, breaking lines as needed assuming the current line is already
+/// `line_len` characters long. `is_address` is `true` if the text is a name in an address, which requires more
+/// characters to be encoded.
+///
+/// This follows `add_rfc2047()` in Git.
+fn encode_rfc2047(text: &BStr, mut line_len: usize, is_address: bool, out: &mut Vec<u8>) {
+    const START: &[u8] = b"=?UTF-8?q?";
+    out.extend_from_slice(START);
+    line_len += START.len();
+    for (start, end, _) in text.char_indices() {
+        let bytes = &text[start..end];
+        let is_special = bytes.len() > 1 || {
+            let b = bytes[0];
+            !b.is_ascii_graphic()
+                || b"=?_".contains(&b)
+                || (is_address && !(b.is_ascii_alphanumeric() || b"!*+-/".contains(&b)))
+        };
+        let encoded_len = if is_special { 3 * bytes.len() } else { 1 };
+        if line_len + encoded_len + 2 > MAX_ENCODED_LEN {
+            out.extend_from_slice(b"?=\n ");
+            out.extend_from_slice(START);
+            line_len = START.len() + 1;
+        }
+        if is_special {
+            for b in bytes.iter() {
+                out.extend_from_slice(format!("={b:02X}").as_bytes());
+            }
+        } else {
+            out.extend_from_slice(bytes);
+        }
+        line_len += encoded_len;
+    }
+    out.extend_from_slice(b"?=");
+}
+
+/// Append the words of `text` to `out`, and break lines between words to keep them within [`MAX_HEADER_LEN`],
+/// assuming the current line is already `first_line_len` characters long.
+/// Continuation lines are indented by a single space.
+///
+/// This follows `strbuf_add_wrapped_text()` in Git.
+fn wrap(text: &BStr, first_line_len: usize, out: &mut Vec<u8>) {
+    let (mut bol, mut pos) = (0, 0);
+    let mut width = first_line_len;
+    let mut indent = 0;
+    let mut space = Some(0);
+    loop {
+        let c = text.get(pos).copied();
+        if c.is_some_and(|c| !c.is_ascii_whitespace()) {
+            width += 1;
+            pos += 1;
+            continue;
+        }
+        match space {
+            Some(start) if width > MAX_HEADER_LEN => {
+                out.push(b'\n');
+                pos = start + usize::from(text.get(start).is_some_and(u8::is_ascii_whitespace));
+                bol = pos;
+                space = None;
+                width = 1;
+                indent = 1;
+            }
+            _ => {
+                if c.is_none() && pos == bol {
+                    return;
+                }
+                let start = match space {
+                    Some(start) => start,
+                    None => {
+                        out.resize(out.len() + indent, b' ');
+                        bol
+                    }
+                };
+                out.extend_from_slice(&text[start..pos]);
+                if c.is_none() {
+                    return;
+                }
+                space = Some(pos);
+                width += 1;
+                pos += 1;
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Turn commits into patches sent by email, like `git format-patch`, and apply such patches as commits, like `git am`.
+use crate::bstr::{BString, ByteSlice};
+
+/// A single commit formatted as email by [`Repository::format_patch()`](crate::Repository::format_patch()).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Patch {
+    /// The id of the commit the patch was created from.
+    pub commit_id: gix_hash::ObjectId,
+    /// The number of the patch in its series, starting at [`format::Options::start_number`].
+    pub number: usize,
+    /// The summary of the commit message, without the `[PATCH]` prefix.
+    pub subject: BString,
+    /// The complete email in `mbox` format, starting with the `From <id>` line.
+    ///
+    /// Multiple patches can be concatenated with an empty line in between to form a single `mbox` file.
+    pub data: BString,
+}
+
+impl Patch {
+    /// Return the name of the file Git would store this patch in, like `0001-fix-the-bug.patch`.
+    pub fn file_name(&self) -> String {
+        // Like Git, limit the name to 64 characters including the suffix.
+        const MAX_LEN: usize = 64 - ".patch".len() - 1;
+        let mut name = format!("{:04}-", self.number);
+        let mut separate = false;
+        let mut chars = self.subject.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '_' {
+                if separate && !name.ends_with('-') {
+                    name.push('-');
+                }
+                separate = false;
+                name.push(c);
+                if c == '.' {
+                    while chars.next_if_eq(&'.').is_some() {}
+                }
+            } else {
+                separate = true;
+            }
+        }
+        let trimmed_len = name.trim_end_matches(['.', '-']).len().max(5);
+        name.truncate(trimmed_len.min(MAX_LEN));
+        name.push_str(".patch");
+        name
+    }
+}
+
+/// A patch sent by email, as parsed by [`parse()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    /// The name and email of the author of the patch, from the `From` header.
+    pub author: gix_actor::Identity,
+    /// The time at which the patch was authored, from the `Date` header, if present.
+    pub time: Option<gix_date::Time>,
+    /// The subject of the email, with prefixes like `[PATCH 1/2]` and `Re:` removed unless configured otherwise.
+    pub subject: BString,
+    /// The body of the commit message, without the subject, which may be empty.
+    pub body: BString,
+    /// The patch itself, typically containing a diffstat followed by the patches of all files in the format
+    /// understood by [`patch::parse()`](crate::apply::patch::parse()).
+    pub patch: BString,
+}
+
+impl Message {
+    /// Return the message of the commit to create from this patch.
+    pub fn commit_message(&self) -> BString {
+        let mut message = self.subject.clone();
+        if !self.body.is_empty() {
+            message.extend_from_slice(b"\n\n");
+            message.extend_from_slice(self.body.trim_end());
+        }
+        message.push(b'\n');
+        message
+    }
+}
+
+///
+pub mod format;
+
+///
+pub mod parse;
+pub use parse::function::parse;
+
+///
+pub mod apply {
+    /// Options for use in [`Repository::apply_mailbox()`](crate::Repository::apply_mailbox()).
+    #[derive(Debug, Default, Clone, Copy)]
+    pub struct Options {
+        /// If `true`, fall back to a three-way merge if a patch doesn't apply cleanly, like `git am --3way`.
+        pub three_way: bool,
+        /// If `true`, rewrite the author of each patch according to the repository mailmap.
+        pub use_mailmap: bool,
+    }
+
+    /// The outcome of [`Repository::apply_mailbox()`](crate::Repository::apply_mailbox()).
+    #[derive(Debug, Default, Clone, PartialEq, Eq)]
+    pub struct Outcome {
+        /// The ids of the commits that were created, one for each message, in order.
+        pub commits: Vec<gix_hash::ObjectId>,
+    }
+}
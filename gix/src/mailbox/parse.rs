@@ -0,0 +1,385 @@
+use crate::bstr::BString;
+
+/// The error returned by [`parse()`](crate::mailbox::parse()).
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Message {index}: the 'From' header is missing or lacks an email address")]
+    MissingAuthor { index: usize },
+    #[error("Message {index}: could not parse the date '{date}'")]
+    InvalidDate {
+        index: usize,
+        date: BString,
+        source: gix_date::parse::Error,
+    },
+    #[error("Message {index}: the content transfer encoding '{encoding}' is not supported")]
+    UnsupportedTransferEncoding { index: usize, encoding: BString },
+    #[error("Message {index}: the body is not valid base64")]
+    InvalidBase64 { index: usize },
+}
+
+/// Options for use in [`parse()`](crate::mailbox::parse()).
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+    /// If `true`, keep the subject as is instead of removing prefixes like `[PATCH 1/2]` or `Re:`, like `git am --keep`.
+    pub keep_subject: bool,
+}
+
+pub(super) mod function {
+    use std::borrow::Cow;
+
+    use super::{Error, Options};
+    use crate::{
+        bstr::{BStr, BString, ByteSlice, ByteVec},
+        mailbox::Message,
+    };
+
+    /// Parse all emails in `data`, which is either a single email or a file in `mbox` format as produced by
+    /// `git format-patch`, into messages to apply with [`Repository::apply_mailbox()`](crate::Repository::apply_mailbox()),
+    /// similar to `git mailsplit` followed by `git mailinfo`.
+    ///
+    /// Headers may be encoded according to RFC 2047, and bodies may use the `quoted-printable` or `base64`
+    /// transfer encoding. Text in the ISO-8859-1 charset is converted to UTF-8. Headers at the start of the body,
+    /// like `From: …`, override those of the email.
+    pub fn parse(data: &[u8], options: Options) -> Result<Vec<Message>, Error> {
+        split(data)
+            .into_iter()
+            .enumerate()
+            .map(|(index, email)| parse_email(email, index, options))
+            .collect()
+    }
+
+    /// Split `data` into individual emails, each without its `From ` line.
+    fn split(data: &[u8]) -> Vec<&[u8]> {
+        let mut starts = Vec::new();
+        let mut pos = 0;
+        let mut previous_is_empty = true;
+        let mut lines = data.lines_with_terminator().peekable();
+        while let Some(line) = lines.next() {
+            if previous_is_empty && line.starts_with(b"From ") && lines.peek().is_some_and(|next| is_header(next)) {
+                starts.push((pos, pos + line.len()));
+            }
+            previous_is_empty = line.trim_end().is_empty();
+            pos += line.len();
+        }
+        if starts.first().is_none_or(|(start, _)| *start != 0) {
+            starts.insert(0, (0, 0));
+        }
+        let mut emails = Vec::new();
+        for (idx, (_, content_start)) in starts.iter().enumerate() {
+            let end = starts.get(idx + 1).map_or(data.len(), |(start, _)| *start);
+            let email = &data[*content_start..end];
+            if !email.trim().is_empty() {
+                emails.push(email);
+            }
+        }
+        emails
+    }
+
+    fn is_header(line: &[u8]) -> bool {
+        line.find_byte(b':')
+            .is_some_and(|pos| pos > 0 && line[..pos].iter().all(|b| b.is_ascii_alphanumeric() || *b == b'-'))
+    }
+
+    fn parse_email(email: &[u8], index: usize, options: Options) -> Result<Message, Error> {
+        let mut headers = Vec::<(BString, BString)>::new();
+        let mut lines = email.lines_with_terminator();
+        let mut body_start = email.len();
+        let mut pos = 0;
+        for line in lines.by_ref() {
+            let content = line.trim_end_with(|c| c == '\n' || c == '\r');
+            if content.is_empty() {
+                body_start = pos + line.len();
+                break;
+            }
+            pos += line.len();
+            match headers.last_mut() {
+                Some((_, value)) if content[0] == b' ' || content[0] == b'\t' => value.push_str(content),
+                _ => {
+                    if let Some((name, value)) = content.split_once_str(b":") {
+                        headers.push((name.trim().to_ascii_lowercase().into(), value.trim().into()));
+                    }
+                }
+            }
+        }
+        let header = |name: &str| {
+            headers
+                .iter()
+                .find(|(header_name, _)| header_name == name)
+                .map(|(_, value)| value.as_bstr())
+        };
+
+        let body = &email[body_start.min(email.len())..];
+        let mut body = match header("content-transfer-encoding").map(|value| value.to_ascii_lowercase()) {
+            None => Cow::Borrowed(body),
+            Some(encoding) => match encoding.as_slice() {
+                b"7bit" | b"8bit" | b"binary" => Cow::Borrowed(body),
+                b"quoted-printable" => Cow::Owned(decode_quoted_printable(body, false)),
+                b"base64" => Cow::Owned(decode_base64(body).ok_or(Error::InvalidBase64 { index })?),
+                _ => {
+                    return Err(Error::UnsupportedTransferEncoding {
+                        index,
+                        encoding: encoding.into(),
+                    })
+                }
+            },
+        };
+        if let Some(Cow::Owned(converted)) = header("content-type")
+            .and_then(charset)
+            .map(|charset| to_utf8(charset.as_ref(), &body))
+        {
+            body = Cow::Owned(converted);
+        }
+
+        let mut from = header("from").map(decode_header);
+        let mut subject = header("subject").map(decode_header);
+        let mut date = header("date").map(ToOwned::to_owned);
+
+        // Headers at the start of the body override those of the email, and are followed by an empty line.
+        let mut body_lines = body.lines_with_terminator().peekable();
+        while body_lines.next_if(|line| line.trim().is_empty()).is_some() {}
+        let mut has_inbody_headers = false;
+        while let Some(line) = body_lines.peek() {
+            let line = line.trim_end();
+            if let Some(value) = line.strip_prefix(b"From:") {
+                from = Some(decode_header(value.trim().as_bstr()));
+            } else if let Some(value) = line.strip_prefix(b"Subject:") {
+                subject = Some(decode_header(value.trim().as_bstr()));
+            } else if let Some(value) = line.strip_prefix(b"Date:") {
+                date = Some(value.trim().into());
+            } else {
+                if has_inbody_headers && line.is_empty() {
+                    body_lines.next();
+                }
+                break;
+            }
+            has_inbody_headers = true;
+            body_lines.next();
+        }
+
+        let mut message = BString::default();
+        let mut patch = BString::default();
+        for line in body_lines.by_ref() {
+            if is_patch_start(line) {
+                patch.push_str(line);
+                break;
+            }
+            message.push_str(line);
+        }
+        for line in body_lines {
+            patch.push_str(line);
+        }
+        let message = message.trim();
+        let mut body = BString::from(message);
+        if !body.is_empty() {
+            body.push(b'\n');
+        }
+
+        let author = from
+            .as_ref()
+            .and_then(|from| parse_address(from.as_bstr()))
+            .ok_or(Error::MissingAuthor { index })?;
+        let time = date
+            .map(|date| {
+                gix_date::parse(&date.to_str_lossy(), Some(std::time::SystemTime::now()))
+                    .map_err(|source| Error::InvalidDate { index, date, source })
+            })
+            .transpose()?;
+        let subject = subject.unwrap_or_default();
+        let subject = if options.keep_subject {
+            subject.trim().into()
+        } else {
+            cleanup_subject(subject.as_bstr())
+        };
+        Ok(Message {
+            author,
+            time,
+            subject,
+            body,
+            patch,
+        })
+    }
+
+    fn is_patch_start(line: &[u8]) -> bool {
+        line.strip_prefix(b"---").is_some_and(|rest| rest.trim().is_empty())
+            || line.starts_with(b"diff -")
+            || line.starts_with(b"Index: ")
+    }
+
+    /// Remove prefixes like `Re:` and `[PATCH 1/2]` from `subject`, like `git mailinfo` does.
+    fn cleanup_subject(subject: &BStr) -> BString {
+        let mut subject = subject.as_bytes();
+        loop {
+            subject = match subject.first() {
+                Some(b' ' | b'\t' | b':') => &subject[1..],
+                Some(b'r' | b'R')
+                    if subject.len() > 3 && subject[1].eq_ignore_ascii_case(&b'e') && subject[2] == b':' =>
+                {
+                    &subject[3..]
+                }
+                Some(b'[') => match subject.find_byte(b']') {
+                    Some(end) => &subject[end + 1..],
+                    None => break,
+                },
+                _ => break,
+            };
+        }
+        subject.trim().into()
+    }
+
+    /// Parse `Name <email>`, `"Name" <email>`, `email (Name)` or `email` into an identity, using the email as name
+    /// if there is none.
+    fn parse_address(from: &BStr) -> Option<gix_actor::Identity> {
+        let (name, email) = if let Some((name, rest)) = from.split_once_str(b"<") {
+            let email = rest.split_once_str(b">").map_or(rest, |(email, _)| email);
+            (name.trim(), email.trim())
+        } else if let Some((email, rest)) = from.split_once_str(b"(") {
+            let name = rest.split_once_str(b")").map_or(rest, |(name, _)| name);
+            (name.trim(), email.trim())
+        } else {
+            (&b""[..], from.trim())
+        };
+        if email.is_empty() {
+            return None;
+        }
+        let name = match name.strip_prefix(b"\"").and_then(|name| name.strip_suffix(b"\"")) {
+            Some(quoted) => {
+                let mut name = BString::default();
+                let mut bytes = quoted.iter();
+                while let Some(&b) = bytes.next() {
+                    name.push(if b == b'\\' {
+                        bytes.next().copied().unwrap_or(b)
+                    } else {
+                        b
+                    });
+                }
+                name
+            }
+            None if name.is_empty() => email.into(),
+            None => name.into(),
+        };
+        Some(gix_actor::Identity {
+            name,
+            email: email.into(),
+        })
+    }
+
+    /// Decode all RFC 2047 encoded words in `value`, and drop the whitespace between adjacent ones.
+    fn decode_header(value: &BStr) -> BString {
+        let mut out = BString::default();
+        let mut rest = value.as_bytes();
+        let mut previous_was_encoded = false;
+        while let Some(start) = rest.find(b"=?") {
+            let Some((decoded, len)) = decode_encoded_word(&rest[start..]) else {
+                out.push_str(&rest[..start + 2]);
+                rest = &rest[start + 2..];
+                previous_was_encoded = false;
+                continue;
+            };
+            let gap = &rest[..start];
+            if !(previous_was_encoded && gap.trim().is_empty()) {
+                out.push_str(gap);
+            }
+            out.push_str(decoded);
+            rest = &rest[start + len..];
+            previous_was_encoded = true;
+        }
+        out.push_str(rest);
+        out
+    }
+
+    /// Decode the encoded word `=?charset?encoding?text?=` at the start of `input`, and return it along with its length.
+    fn decode_encoded_word(input: &[u8]) -> Option<(Vec<u8>, usize)> {
+        let mut parts = input[2..].splitn(3, |b| *b == b'?');
+        let charset = parts.next()?;
+        let encoding = parts.next()?;
+        let text_start = 2 + charset.len() + 1 + encoding.len() + 1;
+        let text_len = input.get(text_start..)?.find(b"?=")?;
+        let text = &input[text_start..][..text_len];
+        let decoded = match encoding {
+            b"q" | b"Q" => decode_quoted_printable(text, true),
+            b"b" | b"B" => decode_base64(text)?,
+            _ => return None,
+        };
+        let charset = charset.split(|b| *b == b'*').next().unwrap_or_default();
+        Some((
+            to_utf8(charset.as_bstr(), &decoded).into_owned(),
+            text_start + text_len + 2,
+        ))
+    }
+
+    fn charset(content_type: &BStr) -> Option<BString> {
+        content_type.split(|b| *b == b';').skip(1).find_map(|parameter| {
+            let (name, value) = parameter.split_once_str(b"=")?;
+            name.trim()
+                .eq_ignore_ascii_case(b"charset")
+                .then(|| value.trim().trim_with(|c| c == '"').into())
+        })
+    }
+
+    /// Convert `data` from `charset` to UTF-8 if it is known to differ from it.
+    fn to_utf8<'a>(charset: &BStr, data: &'a [u8]) -> Cow<'a, [u8]> {
+        match charset.to_ascii_lowercase().as_slice() {
+            b"iso-8859-1" | b"latin1" | b"latin-1" => {
+                Cow::Owned(data.iter().map(|b| char::from(*b)).collect::<String>().into_bytes())
+            }
+            _ => Cow::Borrowed(data),
+        }
+    }
+
+    /// Decode `quoted-printable` data, or the `Q` encoding of encoded words in headers if `is_header` is `true`.
+    fn decode_quoted_printable(data: &[u8], is_header: bool) -> Vec<u8> {
+        let mut out = Vec::with_capacity(data.len());
+        let mut pos = 0;
+        while pos < data.len() {
+            match data[pos] {
+                b'=' => {
+                    let rest = &data[pos + 1..];
+                    if let Some(soft_break) = [&b"\r\n"[..], b"\n"].into_iter().find(|nl| rest.starts_with(nl)) {
+                        pos += 1 + soft_break.len();
+                        continue;
+                    }
+                    match rest
+                        .get(..2)
+                        .and_then(|hex| u8::from_str_radix(hex.to_str().ok()?, 16).ok())
+                    {
+                        Some(b) => {
+                            out.push(b);
+                            pos += 3;
+                            continue;
+                        }
+                        None => out.push(b'='),
+                    }
+                }
+                b'_' if is_header => out.push(b' '),
+                b => out.push(b),
+            }
+            pos += 1;
+        }
+        out
+    }
+
+    fn decode_base64(data: &[u8]) -> Option<Vec<u8>> {
+        let mut out = Vec::with_capacity(data.len() * 3 / 4);
+        let (mut buf, mut bits) = (0u32, 0);
+        for &b in data.iter().filter(|b| !b.is_ascii_whitespace()) {
+            let value = match b {
+                b'A'..=b'Z' => b - b'A',
+                b'a'..=b'z' => b - b'a' + 26,
+                b'0'..=b'9' => b - b'0' + 52,
+                b'+' => 62,
+                b'/' => 63,
+                b'=' => break,
+                _ => return None,
+            };
+            buf = (buf << 6) | u32::from(value);
+            bits += 6;
+            if bits >= 8 {
+                bits -= 8;
+                out.push((buf >> bits) as u8);
+                buf &= (1 << bits) - 1;
+            }
+        }
+        Some(out)
+    }
+}
@@ -0,0 +1,264 @@
+use std::sync::atomic::AtomicBool;
+
+use gix_features::progress::DynNestedProgress;
+
+use crate::{
+    bstr::ByteSlice,
+    config::{
+        cache::util::{ApplyLeniency, ApplyLeniencyDefaultValue},
+        tree::Gc,
+    },
+    maintenance::{local_packs, pack_refs, prune, repack},
+    Repository,
+};
+
+/// Options for use in [`Repository::gc()`], typically obtained with [`Repository::gc_options()`].
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// If `true`, only run if there are too many loose objects or packs, similar to `git gc --auto`.
+    pub auto: bool,
+    /// If `true`, move loose references into the `packed-refs` file.
+    pub pack_refs: bool,
+    /// Determine which reflog entries to remove from the reflogs of `HEAD` and all references.
+    pub reflog_expire: crate::reference::log::expire::Options,
+    /// Remove unreachable objects last modified at or before this time, or keep all of them if `None`.
+    pub prune_expire: Option<gix_date::Time>,
+    /// Remove the private git directories of linked worktrees whose checkout is missing and whose `gitdir` file was
+    /// last modified at or before this time, or keep all of them if `None`.
+    pub worktree_prune_expire: Option<gix_date::Time>,
+    /// In [auto-mode](Self::auto), the amount of loose objects that trigger a repack, with `0` disabling auto-mode entirely.
+    pub auto_threshold: u64,
+    /// In [auto-mode](Self::auto), the amount of packs that trigger consolidating all packs into one, with `0` disabling this check.
+    pub auto_pack_limit: u64,
+    /// Packs whose data file is at least this many bytes in size are never consolidated.
+    pub big_pack_threshold: Option<u64>,
+    /// The amount of threads to use when writing packs, or `None` to use all logical cores.
+    pub thread_limit: Option<usize>,
+}
+
+/// The outcome of [`Repository::gc()`].
+#[derive(Debug, Default, Clone)]
+pub struct Outcome {
+    /// The outcome of packing references, if it was performed.
+    pub pack_refs: Option<pack_refs::Outcome>,
+    /// The amount of reflog entries that were removed.
+    pub num_reflog_entries_removed: usize,
+    /// The outcome of repacking objects.
+    pub repack: repack::Outcome,
+    /// The outcome of pruning loose objects, if it was performed.
+    pub prune: Option<prune::Outcome>,
+    /// The linked worktrees that were pruned.
+    pub pruned_worktrees: Vec<crate::worktree::prune::Pruned>,
+}
+
+///
+pub mod options {
+    /// The error returned by [`Repository::gc_options()`](crate::Repository::gc_options()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Time(#[from] crate::config::time::Error),
+        #[error(transparent)]
+        UnsignedInteger(#[from] crate::config::unsigned_integer::Error),
+        #[error(transparent)]
+        Boolean(#[from] crate::config::boolean::Error),
+    }
+}
+
+/// The error returned by [`Repository::gc()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Another garbage collection is already running as '{}' is locked", path.display())]
+    Locked {
+        path: std::path::PathBuf,
+        source: gix_lock::acquire::Error,
+    },
+    #[error("Could not access '{}'", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    PackRefs(#[from] pack_refs::Error),
+    #[error(transparent)]
+    IterReferences(#[from] crate::reference::iter::Error),
+    #[error(transparent)]
+    IterReferencesInit(#[from] crate::reference::iter::init::Error),
+    #[error(transparent)]
+    IterReferencesItem(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
+    #[error(transparent)]
+    ReflogExpire(#[from] crate::reference::log::expire::Error),
+    #[error(transparent)]
+    Repack(#[from] repack::Error),
+    #[error(transparent)]
+    Prune(#[from] prune::Error),
+    #[error(transparent)]
+    PruneWorktrees(#[from] crate::worktree::prune::Error),
+}
+
+impl Repository {
+    /// Obtain options for [`gc()`](Self::gc()) from the `gc.*` configuration, with relative expiry dates resolved against the current time.
+    ///
+    /// [`auto`](Options::auto) is always `false`.
+    pub fn gc_options(&self) -> Result<Options, options::Error> {
+        let now = std::time::SystemTime::now();
+        let config = &self.config.resolved;
+        let lenient = self.config.lenient_config;
+        let expiry = |key: &'static crate::config::tree::gc::Expiry, default: &'static str| {
+            key.try_into_expiry(
+                config
+                    .string(key)
+                    .unwrap_or_else(|| default.as_bytes().as_bstr().into()),
+                now,
+            )
+            .with_leniency(lenient)
+        };
+        let unsigned = |key: &'static crate::config::tree::keys::UnsignedInteger| {
+            config
+                .integer(key)
+                .map(|value| key.try_into_u64(value))
+                .transpose()
+                .with_leniency(lenient)
+        };
+        let pack_refs = match config.string(&Gc::PACK_REFS) {
+            Some(value) if value.as_ref() == "notbare" => !self.is_bare(),
+            Some(value) => gix_config::Boolean::try_from(value.as_ref())
+                .map(|boolean| boolean.0)
+                .map_err(|err| {
+                    crate::config::boolean::Error::from_value(&Gc::PACK_REFS, value.into_owned()).with_source(err)
+                })
+                .with_lenient_default_value(lenient, true)?,
+            None => true,
+        };
+        Ok(Options {
+            auto: false,
+            pack_refs,
+            reflog_expire: self.reflog_expire_options()?,
+            prune_expire: expiry(&Gc::PRUNE_EXPIRE, "2.weeks.ago")?,
+            worktree_prune_expire: expiry(&Gc::WORKTREE_PRUNE_EXPIRE, "3.months.ago")?,
+            auto_threshold: unsigned(&Gc::AUTO)?.unwrap_or(6700),
+            auto_pack_limit: unsigned(&Gc::AUTO_PACK_LIMIT)?.unwrap_or(50),
+            big_pack_threshold: unsigned(&Gc::BIG_PACK_THRESHOLD)?.filter(|threshold| *threshold > 0),
+            thread_limit: None,
+        })
+    }
+
+    /// Pack references, expire reflogs, repack objects, prune unreachable loose objects and stale linked worktrees, similar to `git gc`.
+    ///
+    /// If [`Options::auto`] is set, `None` is returned if the repository doesn't need maintenance. Otherwise,
+    /// all packs that aren't kept are consolidated into one, which is also done in auto-mode if there are too many packs.
+    /// If there are only too many loose objects, these are packed into a new pack instead.
+    ///
+    /// Only one garbage collection may run at a time, which is enforced by holding a lock on `gc.pid` in the common directory.
+    pub fn gc(
+        &self,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: Options,
+    ) -> Result<Option<Outcome>, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::gc()");
+        let objects_dir = self.objects.store_ref().path();
+        let mode = if options.auto {
+            if options.auto_threshold == 0 {
+                return Ok(None);
+            }
+            let io_err = |source| Error::Io {
+                path: objects_dir.to_owned(),
+                source,
+            };
+            let num_packs = local_packs(objects_dir, self.object_hash(), options.big_pack_threshold)
+                .map_err(io_err)?
+                .iter()
+                .filter(|pack| !pack.is_kept)
+                .count() as u64;
+            if options.auto_pack_limit > 0 && num_packs > options.auto_pack_limit {
+                repack::Mode::AllIntoOne
+            } else if too_many_loose_objects(objects_dir, options.auto_threshold).map_err(io_err)? {
+                repack::Mode::LooseObjects
+            } else {
+                return Ok(None);
+            }
+        } else {
+            repack::Mode::AllIntoOne
+        };
+
+        let lock_path = self.common_dir().join("gc.pid");
+        let _lock = gix_lock::Marker::acquire_to_hold_resource(&lock_path, gix_lock::acquire::Fail::Immediately, None)
+            .map_err(|source| Error::Locked {
+                path: lock_path,
+                source,
+            })?;
+
+        let mut out = Outcome::default();
+        if options.pack_refs {
+            out.pack_refs = Some(self.pack_refs()?);
+        }
+        if options.reflog_expire != Default::default() {
+            out.num_reflog_entries_removed = self.expire_reflogs(options.reflog_expire)?;
+        }
+        out.repack = self.repack(
+            progress,
+            should_interrupt,
+            repack::Options {
+                mode,
+                unreachable: match options.prune_expire {
+                    Some(expire) => repack::Unreachable::Loosen { expire: Some(expire) },
+                    None => repack::Unreachable::Keep,
+                },
+                big_pack_threshold: options.big_pack_threshold,
+                thread_limit: options.thread_limit,
+            },
+        )?;
+        if let Some(expire) = options.prune_expire {
+            out.prune = Some(self.prune(progress, should_interrupt, prune::Options { expire: Some(expire) })?);
+        }
+        if let Some(expire) = options.worktree_prune_expire {
+            out.pruned_worktrees = self.worktrees_prune(crate::worktree::prune::Options {
+                expire: Some(expire),
+                dry_run: false,
+            })?;
+        }
+        Ok(Some(out))
+    }
+
+    /// Expire entries of the reflogs of `HEAD` and all references according to `options`.
+    fn expire_reflogs(&self, options: crate::reference::log::expire::Options) -> Result<usize, Error> {
+        let mut names = vec![gix_ref::FullName::try_from("HEAD").expect("valid")];
+        for reference in self.references()?.all()? {
+            names.push(reference?.inner.name);
+        }
+        let cache = self
+            .commit_graph_if_enabled()
+            .map_err(crate::reference::log::expire::Error::from)?;
+        let mut graph = self.revision_graph(cache.as_ref());
+        let mut num_removed = 0;
+        for name in names {
+            let outcome = self.reflog_expire_with_graph(name.as_ref(), options, &mut graph)?;
+            num_removed += outcome.map_or(0, |outcome| outcome.num_removed);
+        }
+        Ok(num_removed)
+    }
+}
+
+/// Estimate if there are more than `threshold` loose objects by sampling the `17` fan-out directory, just like `git` does.
+fn too_many_loose_objects(objects_dir: &std::path::Path, threshold: u64) -> std::io::Result<bool> {
+    let threshold_per_dir = threshold.div_ceil(256);
+    let entries = match std::fs::read_dir(objects_dir.join("17")) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+        Err(err) => return Err(err),
+    };
+    let mut count = 0;
+    for entry in entries {
+        let name = entry?.file_name();
+        if name.len() >= 38 && name.as_encoded_bytes().iter().all(u8::is_ascii_hexdigit) {
+            count += 1;
+            if count > threshold_per_dir {
+                return Ok(true);
+            }
+        }
+    }
+    Ok(false)
+}
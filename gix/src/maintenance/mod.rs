@@ -0,0 +1,156 @@
+//! Keep repositories in shape by consolidating objects and references, similar to `git gc`.
+//!
+//! The building blocks are available individually:
+//!
+//! * [pack references](crate::Repository::pack_refs()) into the `packed-refs` file.
+//! * [repack](crate::Repository::repack()) loose objects and small packs into a new pack.
+//! * [prune](crate::Repository::prune()) unreachable loose objects after a grace period.
+//!
+//! [`Repository::gc()`](crate::Repository::gc()) runs all of them along with reflog expiry, as configured with the `gc.*`
+//! configuration keys.
+//!
+//! ### Concurrent writers
+//!
+//! Objects are only ever deleted after they were written into a pack that is fully in place, or if they are unreachable
+//! and older than a grace period. Objects and packs that appear while maintenance is running are never touched.
+use std::path::{Path, PathBuf};
+
+use gix_hash::oid;
+
+///
+pub mod gc;
+///
+pub mod pack_refs;
+///
+pub mod prune;
+mod reachable;
+///
+pub mod repack;
+
+/// The error shared by operations that need to determine which objects are reachable.
+pub mod reachable_objects {
+    /// The error returned when computing all reachable objects failed.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Interrupted")]
+        Interrupted,
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+        #[error(transparent)]
+        OpenRepository(#[from] crate::open::Error),
+        #[error(transparent)]
+        FindHead(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        Iter(#[from] crate::reference::iter::Error),
+        #[error(transparent)]
+        IterInit(#[from] crate::reference::iter::init::Error),
+        #[error(transparent)]
+        IterItem(Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error(transparent)]
+        FindObject(#[from] gix_object::find::Error),
+    }
+}
+
+/// The identifiers used for the progress of maintenance operations.
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub enum ProgressId {
+    /// The amount of objects found to be reachable.
+    ReachableObjects,
+    /// The amount of objects that were counted for inclusion in a new pack.
+    CountObjects,
+    /// The amount of objects written into the new pack.
+    WriteObjects,
+    /// The amount of loose objects that were removed.
+    RemoveLooseObjects,
+}
+
+impl From<ProgressId> for gix_features::progress::Id {
+    fn from(v: ProgressId) -> Self {
+        match v {
+            ProgressId::ReachableObjects => *b"MARO",
+            ProgressId::CountObjects => *b"MACO",
+            ProgressId::WriteObjects => *b"MAWO",
+            ProgressId::RemoveLooseObjects => *b"MARL",
+        }
+    }
+}
+
+/// A pack in the local object database, i.e. not one of an alternate.
+pub(crate) struct LocalPack {
+    pub index_path: PathBuf,
+    pub index: gix_pack::index::File,
+    /// If `true`, the pack must not be consolidated or deleted.
+    pub is_kept: bool,
+    /// The last time the pack data file was modified.
+    pub modified: std::time::SystemTime,
+}
+
+impl LocalPack {
+    pub fn contains(&self, id: &oid) -> bool {
+        self.index.lookup(id).is_some()
+    }
+
+    pub fn data_path(&self) -> PathBuf {
+        self.index_path.with_extension("pack")
+    }
+
+    /// Delete all files belonging to this pack, starting with the index so readers don't find a pack without index.
+    pub fn delete(&self) -> std::io::Result<()> {
+        for extension in ["idx", "pack", "rev", "bitmap", "mtimes", "promisor"] {
+            match std::fs::remove_file(self.index_path.with_extension(extension)) {
+                Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Return all packs in `objects_dir` that have an index and a data file.
+///
+/// Packs with a `.keep` or `.promisor` file, and packs whose data file is at least `big_pack_threshold` bytes
+/// in size are considered kept.
+pub(crate) fn local_packs(
+    objects_dir: &Path,
+    object_hash: gix_hash::Kind,
+    big_pack_threshold: Option<u64>,
+) -> std::io::Result<Vec<LocalPack>> {
+    let pack_dir = objects_dir.join("pack");
+    let entries = match std::fs::read_dir(&pack_dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    let mut out = Vec::new();
+    for entry in entries {
+        let index_path = entry?.path();
+        if index_path.extension().is_none_or(|ext| ext != "idx") {
+            continue;
+        }
+        let Ok(data_meta) = std::fs::metadata(index_path.with_extension("pack")) else {
+            continue;
+        };
+        let index = gix_pack::index::File::at(&index_path, object_hash).map_err(std::io::Error::other)?;
+        let is_kept = index_path.with_extension("keep").is_file()
+            || index_path.with_extension("promisor").is_file()
+            || big_pack_threshold.is_some_and(|threshold| data_meta.len() >= threshold);
+        out.push(LocalPack {
+            index_path,
+            index,
+            is_kept,
+            modified: data_meta.modified()?,
+        });
+    }
+    out.sort_by(|a, b| a.index_path.cmp(&b.index_path));
+    Ok(out)
+}
+
+/// Return `true` if something last `modified` at the given time is expired according to `expire`, just like `git` would decide.
+pub(crate) fn is_expired(modified: std::time::SystemTime, expire: gix_date::Time) -> bool {
+    modified.duration_since(std::time::UNIX_EPOCH).map_or(true, |d| {
+        (d.as_secs() as gix_date::SecondsSinceUnixEpoch) <= expire.seconds
+    })
+}
@@ -0,0 +1,72 @@
+use gix_ref::{
+    file::transaction::PackedRefs,
+    transaction::{Change, LogChange, PreviousValue, RefEdit},
+};
+
+use crate::Repository;
+
+/// The outcome of [`Repository::pack_refs()`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of loose references that were moved into the `packed-refs` file.
+    pub num_packed: usize,
+}
+
+/// The error returned by [`Repository::pack_refs()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not iterate loose references")]
+    LooseIter(#[from] std::io::Error),
+    #[error(transparent)]
+    LockTimeoutConfiguration(#[from] crate::config::lock_timeout::Error),
+    #[error(transparent)]
+    Prepare(#[from] gix_ref::file::transaction::prepare::Error),
+    #[error(transparent)]
+    Commit(#[from] gix_ref::file::transaction::commit::Error),
+}
+
+impl Repository {
+    /// Move all loose references that point to an object into the `packed-refs` file and delete their loose counterparts,
+    /// similar to `git pack-refs --all`.
+    ///
+    /// Symbolic references, references private to a worktree and loose references that can't be read are left untouched.
+    /// Each reference is locked while it's being moved, and it's only moved if it didn't change in the meantime.
+    pub fn pack_refs(&self) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::pack_refs()");
+        let edits: Vec<_> = self
+            .refs
+            .loose_iter()?
+            .filter_map(Result::ok)
+            .filter(|r| {
+                r.kind() == gix_ref::Kind::Object && !r.name.category().is_some_and(|c| c.is_worktree_private())
+            })
+            .map(|r| RefEdit {
+                change: Change::Update {
+                    log: LogChange::default(),
+                    expected: PreviousValue::MustExistAndMatch(r.target.clone()),
+                    new: r.target,
+                },
+                name: r.name,
+                deref: false,
+            })
+            .collect();
+        if edits.is_empty() {
+            return Ok(Outcome::default());
+        }
+
+        let (file_lock_fail, packed_refs_lock_fail) = self.config.lock_timeout()?;
+        let edits = self
+            .refs
+            .transaction()
+            .packed_refs(PackedRefs::DeletionsAndNonSymbolicUpdatesRemoveLooseSourceReference(
+                Box::new(self.objects.clone()),
+            ))
+            .prepare(edits, file_lock_fail, packed_refs_lock_fail)?
+            // No reflog is written as the value of the references doesn't change.
+            .commit(None)?;
+        Ok(Outcome {
+            num_packed: edits.len(),
+        })
+    }
+}
@@ -0,0 +1,126 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use gix_features::progress::{Count, DynNestedProgress, Progress};
+
+use crate::{
+    maintenance::{is_expired, local_packs, ProgressId},
+    Repository,
+};
+
+/// Options for use in [`Repository::prune()`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+    /// Only remove unreachable loose objects that were last modified at or before this time, which gives concurrent writers
+    /// a grace period to make the objects they just wrote reachable.
+    ///
+    /// If `None`, all unreachable loose objects are removed, which is only safe if nothing else writes to the repository.
+    pub expire: Option<gix_date::Time>,
+}
+
+/// The outcome of [`Repository::prune()`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Outcome {
+    /// The amount of unreachable loose objects that were removed.
+    pub num_unreachable_removed: usize,
+    /// The amount of loose objects that were removed as they are also stored in a pack.
+    pub num_packed_removed: usize,
+    /// The amount of commits that were removed from the shallow boundary as they became unreachable or don't exist anymore.
+    pub num_shallow_commits_removed: usize,
+}
+
+/// The error returned by [`Repository::prune()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Interrupted")]
+    Interrupted,
+    #[error(transparent)]
+    Reachable(#[from] super::reachable_objects::Error),
+    #[error("Could not iterate loose objects")]
+    LooseIter(#[from] gix_odb::loose::iter::Error),
+    #[error("Could not access the loose object or pack at '{}'", path.display())]
+    Io {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    PruneShallow(#[from] crate::shallow::prune::Error),
+}
+
+impl Repository {
+    /// Remove all loose objects that are unreachable and [expired](Options::expire), along with all loose objects that
+    /// are also present in a local pack, similar to `git prune`.
+    ///
+    /// Reachability is determined by traversing from all references, their reflogs, and the `HEAD` and index of all
+    /// worktrees. Objects in packs and in alternates are never removed.
+    ///
+    /// In shallow repositories, unreachable or missing commits are also removed from the shallow boundary.
+    pub fn prune(
+        &self,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::prune()");
+        let objects_dir = self.objects.store_ref().path();
+        let packs = local_packs(objects_dir, self.object_hash(), None).map_err(|source| Error::Io {
+            path: objects_dir.join("pack"),
+            source,
+        })?;
+
+        let reachable = {
+            let mut progress = progress.add_child_with_id("reachable".into(), ProgressId::ReachableObjects.into());
+            progress.init(None, gix_features::progress::count("objects"));
+            self.maintenance_reachable_objects(&progress, should_interrupt)?
+        };
+
+        let mut progress = progress.add_child_with_id("prune".into(), ProgressId::RemoveLooseObjects.into());
+        progress.init(None, gix_features::progress::count("objects"));
+        let loose = gix_odb::loose::Store::at(objects_dir, self.object_hash());
+        let mut out = Outcome::default();
+        let mut dirs_with_removals = std::collections::BTreeSet::new();
+        for id in loose.iter() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            let id = id?;
+            let path = loose.object_path(&id);
+            let is_packed = packs.iter().any(|pack| pack.contains(&id));
+            if !is_packed {
+                if reachable.contains(&id) {
+                    continue;
+                }
+                if let Some(expire) = options.expire {
+                    let modified = match std::fs::metadata(&path).and_then(|meta| meta.modified()) {
+                        Ok(modified) => modified,
+                        Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                        Err(source) => return Err(Error::Io { path, source }),
+                    };
+                    if !is_expired(modified, expire) {
+                        continue;
+                    }
+                }
+            }
+            match std::fs::remove_file(&path) {
+                Ok(()) => {}
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(source) => return Err(Error::Io { path, source }),
+            }
+            if is_packed {
+                out.num_packed_removed += 1;
+            } else {
+                out.num_unreachable_removed += 1;
+            }
+            progress.inc();
+            if let Some(dir) = path.parent() {
+                dirs_with_removals.insert(dir.to_owned());
+            }
+        }
+        for dir in dirs_with_removals {
+            // It's fine if the directory isn't empty or a concurrent writer created it again.
+            std::fs::remove_dir(dir).ok();
+        }
+        out.num_shallow_commits_removed = self.remove_shallow_commits(|id| !reachable.contains(id))?.len();
+        Ok(out)
+    }
+}
@@ -0,0 +1,111 @@
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use gix_features::progress::Count;
+use gix_hash::ObjectId;
+use gix_hashtable::HashSet;
+use gix_object::{Find, Kind, TreeRefIter};
+
+use crate::{maintenance::reachable_objects::Error, Repository};
+
+impl Repository {
+    /// Return the ids of all objects that are reachable from the tips `git gc` considers: the `HEAD` and index of all worktrees,
+    /// all references and the objects mentioned in the reflogs of all of them.
+    ///
+    /// Missing objects are silently skipped, as they may be expected in shallow or partial clones.
+    pub(crate) fn maintenance_reachable_objects(
+        &self,
+        progress: &dyn Count,
+        should_interrupt: &AtomicBool,
+    ) -> Result<HashSet<ObjectId>, Error> {
+        let mut queue: VecDeque<ObjectId> = self.maintenance_tips()?.into();
+        let mut seen = HashSet::<ObjectId>::default();
+        let mut buf = Vec::new();
+        while let Some(id) = queue.pop_front() {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            if !seen.insert(id) {
+                continue;
+            }
+            progress.inc();
+            let Some(data) = self.objects.try_find(&id, &mut buf)? else {
+                continue;
+            };
+            match data.kind {
+                Kind::Commit => {
+                    for token in gix_object::CommitRefIter::from_bytes(data.data) {
+                        match token {
+                            Ok(gix_object::commit::ref_iter::Token::Tree { id }) => queue.push_back(id),
+                            Ok(gix_object::commit::ref_iter::Token::Parent { id }) => queue.push_back(id),
+                            _ => break,
+                        }
+                    }
+                }
+                Kind::Tree => queue.extend(
+                    TreeRefIter::from_bytes(data.data, data.hash_kind)
+                        .filter_map(Result::ok)
+                        .filter(|entry| !entry.mode.is_commit())
+                        .map(|entry| entry.oid.to_owned())
+                        .filter(|id| !seen.contains(id)),
+                ),
+                Kind::Tag => {
+                    if let Some(Ok(gix_object::tag::ref_iter::Token::Target { id })) =
+                        gix_object::TagRefIter::from_bytes(data.data).next()
+                    {
+                        queue.push_back(id);
+                    }
+                }
+                Kind::Blob => {}
+            }
+        }
+        Ok(seen)
+    }
+
+    fn maintenance_tips(&self) -> Result<Vec<ObjectId>, Error> {
+        let mut tips = Vec::new();
+        let main_repo = self.main_repo()?;
+        let mut worktree_repos = vec![main_repo];
+        for proxy in self.worktrees()? {
+            worktree_repos.push(proxy.into_repo_with_possibly_inaccessible_worktree()?);
+        }
+        for repo in &worktree_repos {
+            let head = repo.head()?;
+            tips.extend(head.id().map(crate::Id::detach));
+            add_reflog(&mut tips, &mut head.log_iter())?;
+            if repo.workdir().is_some() {
+                tips.extend(
+                    repo.index_or_empty()?
+                        .entries()
+                        .iter()
+                        .filter(|entry| !entry.mode.is_submodule())
+                        .map(|entry| entry.id),
+                );
+            }
+        }
+        for reference in self.references()?.all()? {
+            let reference = reference.map_err(Error::IterItem)?;
+            tips.extend(reference.target().try_id().map(ToOwned::to_owned));
+            add_reflog(&mut tips, &mut reference.log_iter())?;
+        }
+        Ok(tips)
+    }
+}
+
+fn add_reflog(
+    tips: &mut Vec<ObjectId>,
+    platform: &mut gix_ref::file::log::iter::Platform<'_, '_>,
+) -> Result<(), std::io::Error> {
+    if let Some(lines) = platform.all()? {
+        for line in lines.filter_map(Result::ok) {
+            tips.extend(
+                [line.previous_oid(), line.new_oid()]
+                    .into_iter()
+                    .filter(|id| !id.is_null()),
+            );
+        }
+    }
+    Ok(())
+}
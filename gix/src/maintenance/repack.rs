@@ -0,0 +1,408 @@
+use std::{
+    io::{BufReader, Write},
+    path::PathBuf,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use gix_features::{
+    parallel::InOrderIter,
+    progress::{Count, DynNestedProgress, Progress},
+};
+use gix_hash::ObjectId;
+use gix_hashtable::HashSet;
+use gix_object::{FindExt, Write as _};
+use gix_pack::data::output;
+
+use crate::{
+    maintenance::{is_expired, local_packs, LocalPack, ProgressId},
+    Repository,
+};
+
+/// Determine which objects are packed by [`Repository::repack()`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// Write all reachable loose objects that aren't packed yet into a new pack and leave existing packs alone,
+    /// similar to `git repack -d`.
+    LooseObjects,
+    /// Write all reachable objects of all packs that aren't kept, along with all reachable loose objects, into a new pack
+    /// and delete the packs that were consolidated, similar to `git repack -a -d`.
+    ///
+    /// What happens to unreachable objects in these packs is controlled by [`Options::unreachable`].
+    #[default]
+    AllIntoOne,
+}
+
+/// Determine what happens to unreachable objects in packs that are consolidated in [`Mode::AllIntoOne`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Unreachable {
+    /// Write unreachable objects into the new pack as well, so no object is ever lost.
+    #[default]
+    Keep,
+    /// Write unreachable objects as loose objects so they can be [pruned](Repository::prune()) once they expired,
+    /// unless the pack they are in was last modified at or before `expire`, in which case they are dropped right away.
+    ///
+    /// If `expire` is `None`, no unreachable object is dropped.
+    Loosen {
+        /// The time at or before which packs must have been modified for their unreachable objects to be dropped.
+        expire: Option<gix_date::Time>,
+    },
+}
+
+/// Options for use in [`Repository::repack()`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+    /// Determine which objects to pack.
+    pub mode: Mode,
+    /// Determine what to do with unreachable objects in packs that are consolidated.
+    pub unreachable: Unreachable,
+    /// If set, packs whose data file is at least this many bytes in size are kept just like packs with a `.keep` file.
+    pub big_pack_threshold: Option<u64>,
+    /// The amount of threads to use when writing the new pack, or `None` to use all logical cores.
+    pub thread_limit: Option<usize>,
+}
+
+/// The outcome of [`Repository::repack()`].
+#[derive(Debug, Default, Clone)]
+pub struct Outcome {
+    /// The newly written pack, or `None` if there was nothing to pack.
+    ///
+    /// Its `keep_path` is always `None` as the `.keep` file is removed once the repack is complete.
+    pub write_pack_bundle: Option<gix_pack::bundle::write::Outcome>,
+    /// The paths to the indices of packs that were deleted after their objects were consolidated.
+    pub removed_packs: Vec<PathBuf>,
+    /// The amount of loose objects that were deleted as they are now contained in a pack.
+    pub num_loose_objects_removed: usize,
+    /// The amount of unreachable objects in removed packs that were written as loose objects.
+    pub num_unreachable_objects_loosened: usize,
+    /// The amount of unreachable objects in removed packs that were dropped.
+    pub num_unreachable_objects_dropped: usize,
+}
+
+/// The error returned by [`Repository::repack()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Interrupted")]
+    Interrupted,
+    #[error(transparent)]
+    Reachable(#[from] super::reachable_objects::Error),
+    #[error("Could not iterate loose objects")]
+    LooseIter(#[from] gix_odb::loose::iter::Error),
+    #[error("Could not access '{}'", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error("Could not prepare the object database for multi-threaded access")]
+    OpenObjectDatabase(#[source] std::io::Error),
+    #[error(transparent)]
+    CountObjects(#[from] output::count::objects::Error),
+    #[error(transparent)]
+    WritePack(#[from] output::bytes::Error<output::entry::iter_from_counts::Error>),
+    #[error(transparent)]
+    WriteBundle(#[from] gix_pack::bundle::write::Error),
+    #[error(transparent)]
+    FindObject(#[from] gix_object::find::existing::Error),
+    #[error(transparent)]
+    WriteLooseObject(#[from] gix_object::write::Error),
+    #[error(transparent)]
+    WriteMultiIndex(#[from] gix_pack::multi_index::write::Error),
+}
+
+impl Repository {
+    /// Write reachable loose objects and, depending on [`Options::mode`], all reachable objects of packs that aren't kept,
+    /// into a new pack, and remove what was consolidated, similar to `git repack -d`.
+    ///
+    /// Packs with a `.keep` or `.promisor` file are never touched, and objects they contain are not written into the new pack.
+    /// Packs and loose objects in alternates are also left alone. Unreachable loose objects are kept, use
+    /// [`prune()`](Self::prune()) to remove them.
+    ///
+    /// Reachability is determined by traversing from all references, their reflogs, and the `HEAD` and index of all
+    /// worktrees. If a `multi-pack-index` exists, it's rewritten to match the packs that remain.
+    ///
+    /// ### Deviation
+    ///
+    /// Unreachable objects that are loosened receive the current time as modification time, while `git` uses the modification
+    /// time of their pack. This means these objects will be pruned later than they would be with `git`.
+    pub fn repack(
+        &self,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::repack()");
+        let object_hash = self.object_hash();
+        let objects_dir = self.objects.store_ref().path();
+        let pack_dir = objects_dir.join("pack");
+        let packs = local_packs(objects_dir, object_hash, options.big_pack_threshold).map_err(|source| Error::Io {
+            path: pack_dir.clone(),
+            source,
+        })?;
+        let (kept_packs, packs_to_consolidate): (Vec<_>, Vec<_>) = match options.mode {
+            Mode::LooseObjects => (packs.iter().collect(), Vec::new()),
+            Mode::AllIntoOne => packs.iter().partition(|pack| pack.is_kept),
+        };
+
+        let reachable = {
+            let mut progress = progress.add_child_with_id("reachable".into(), ProgressId::ReachableObjects.into());
+            progress.init(None, gix_features::progress::count("objects"));
+            self.maintenance_reachable_objects(&progress, should_interrupt)?
+        };
+
+        let loose = gix_odb::loose::Store::at(objects_dir, object_hash);
+        let loose_ids = loose.iter().collect::<Result<Vec<_>, _>>()?;
+        let is_in_kept_pack = |id: &ObjectId| kept_packs.iter().any(|pack| pack.contains(id));
+        let mut to_pack = HashSet::<ObjectId>::default();
+        to_pack.extend(
+            loose_ids
+                .iter()
+                .filter(|id| reachable.contains(*id) && !is_in_kept_pack(id))
+                .copied(),
+        );
+        let mut unreachable = Vec::new();
+        let mut seen_unreachable = HashSet::<ObjectId>::default();
+        for pack in &packs_to_consolidate {
+            if should_interrupt.load(Ordering::Relaxed) {
+                return Err(Error::Interrupted);
+            }
+            for entry in pack.index.iter() {
+                let id = entry.oid;
+                if is_in_kept_pack(&id) {
+                    continue;
+                }
+                if reachable.contains(&id) || options.unreachable == Unreachable::Keep {
+                    to_pack.insert(id);
+                } else if seen_unreachable.insert(id) {
+                    unreachable.push((id, pack.modified));
+                }
+            }
+        }
+
+        let nothing_changes = packs_to_consolidate.len() <= 1
+            && unreachable.is_empty()
+            && to_pack
+                .iter()
+                .all(|id| packs_to_consolidate.iter().any(|pack| pack.contains(id)));
+        if to_pack.is_empty() && packs_to_consolidate.is_empty() || nothing_changes {
+            return Ok(Outcome::default());
+        }
+
+        let mut out = Outcome::default();
+        if !to_pack.is_empty() {
+            let mut to_pack: Vec<_> = to_pack.into_iter().collect();
+            to_pack.sort();
+            out.write_pack_bundle = Some(self.write_pack(to_pack, progress, should_interrupt, options.thread_limit)?);
+        }
+        let new_pack = match out.write_pack_bundle.as_ref().and_then(|b| b.index_path.as_ref()) {
+            Some(index_path) => Some(
+                gix_pack::index::File::at(index_path, object_hash).map_err(|err| Error::Io {
+                    path: index_path.clone(),
+                    source: std::io::Error::other(err),
+                })?,
+            ),
+            None => None,
+        };
+
+        // Loosen unreachable objects before their pack goes away.
+        let mut buf = Vec::new();
+        for (id, pack_modified) in unreachable {
+            let is_expired = match options.unreachable {
+                Unreachable::Keep => unreachable!("unreachable objects are packed in this mode"),
+                Unreachable::Loosen { expire } => expire.is_some_and(|expire| is_expired(pack_modified, expire)),
+            };
+            if is_expired {
+                out.num_unreachable_objects_dropped += 1;
+                continue;
+            }
+            if loose.contains(&id) {
+                continue;
+            }
+            let object = self.objects.find(&id, &mut buf)?;
+            loose.write_buf(object.kind, object.data)?;
+            out.num_unreachable_objects_loosened += 1;
+        }
+
+        // Now that all objects are safely stored elsewhere, remove loose objects that are packed and the consolidated packs.
+        let is_packed =
+            |id: &ObjectId| new_pack.as_ref().is_some_and(|pack| pack.lookup(id).is_some()) || is_in_kept_pack(id);
+        for id in loose_ids.iter().filter(|id| is_packed(id)) {
+            let path = loose.object_path(id);
+            match std::fs::remove_file(&path) {
+                Ok(()) => out.num_loose_objects_removed += 1,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(source) => return Err(Error::Io { path, source }),
+            }
+            if let Some(dir) = path.parent() {
+                std::fs::remove_dir(dir).ok();
+            }
+        }
+        let new_data_path = out.write_pack_bundle.as_ref().and_then(|b| b.data_path.clone());
+        let packs_to_remove: Vec<_> = packs_to_consolidate
+            .into_iter()
+            .filter(|pack| Some(pack.data_path()) != new_data_path)
+            .collect();
+        // Like `git`, make the multi-pack-index stop referring to the packs before they are deleted.
+        let removed_index_paths: Vec<_> = packs_to_remove.iter().map(|pack| pack.index_path.clone()).collect();
+        self.rewrite_multi_pack_index(&packs, &removed_index_paths, &out, progress, should_interrupt)?;
+        for pack in packs_to_remove {
+            pack.delete().map_err(|source| Error::Io {
+                path: pack.index_path.clone(),
+                source,
+            })?;
+            out.removed_packs.push(pack.index_path.clone());
+        }
+
+        if let Some(keep_path) = out.write_pack_bundle.as_mut().and_then(|b| b.keep_path.take()) {
+            std::fs::remove_file(&keep_path).map_err(|source| Error::Io {
+                path: keep_path,
+                source,
+            })?;
+        }
+        Ok(out)
+    }
+
+    fn write_pack(
+        &self,
+        ids: Vec<ObjectId>,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        thread_limit: Option<usize>,
+    ) -> Result<gix_pack::bundle::write::Outcome, Error> {
+        let object_hash = self.object_hash();
+        let pack_dir = self.objects.store_ref().path().join("pack");
+        let mut db = self
+            .objects
+            .clone()
+            .into_inner()
+            .into_arc()
+            .map_err(Error::OpenObjectDatabase)?;
+        db.prevent_pack_unload();
+        db.ignore_replacements = true;
+
+        let counts = {
+            let mut progress = progress.add_child_with_id("counting".into(), ProgressId::CountObjects.into());
+            progress.init(Some(ids.len()), gix_features::progress::count("objects"));
+            let mut input = ids.into_iter().map(Ok);
+            let (counts, _outcome) = output::count::objects_unthreaded(
+                &db,
+                &mut input,
+                &progress,
+                should_interrupt,
+                output::count::objects::ObjectExpansion::AsIs,
+            )?;
+            counts
+        };
+
+        let num_objects = counts.len();
+        let mut entries = InOrderIter::from(output::entry::iter_from_counts(
+            counts,
+            db,
+            Box::new(progress.add_child("creating entries".into())),
+            output::entry::iter_from_counts::Options {
+                thread_limit,
+                mode: output::entry::iter_from_counts::Mode::PackCopyAndBaseObjects,
+                allow_thin_pack: false,
+                chunk_size: 1000,
+                version: Default::default(),
+            },
+        ));
+
+        let mut pack_file = gix_tempfile::new(
+            &pack_dir,
+            gix_tempfile::ContainingDirectory::Exists,
+            gix_tempfile::AutoRemove::Tempfile,
+        )
+        .map_err(|source| Error::Io {
+            path: pack_dir.clone(),
+            source,
+        })?;
+        {
+            let mut write_progress = progress.add_child_with_id("writing".into(), ProgressId::WriteObjects.into());
+            write_progress.init(Some(num_objects), gix_features::progress::count("objects"));
+            let mut pack_writer = output::bytes::FromEntriesIter::new(
+                entries.by_ref().inspect(|entries| {
+                    if let Ok(entries) = entries {
+                        write_progress.inc_by(entries.len());
+                    }
+                }),
+                &mut pack_file,
+                num_objects as u32,
+                gix_pack::data::Version::default(),
+                object_hash,
+            );
+            for res in pack_writer.by_ref() {
+                if should_interrupt.load(Ordering::Relaxed) {
+                    return Err(Error::Interrupted);
+                }
+                res?;
+            }
+        }
+        let io_err = |source| Error::Io {
+            path: pack_dir.clone(),
+            source,
+        };
+        pack_file.flush().map_err(io_err)?;
+        let pack_data = pack_file
+            .with_mut(|file| file.reopen())
+            .and_then(|file| file)
+            .map_err(io_err)?;
+
+        let bundle = gix_pack::Bundle::write_to_directory(
+            &mut BufReader::new(pack_data),
+            Some(&pack_dir),
+            progress,
+            should_interrupt,
+            None::<gix_object::find::Never>,
+            gix_pack::bundle::write::Options {
+                thread_limit,
+                iteration_mode: gix_pack::data::input::Mode::Verify,
+                index_version: Default::default(),
+                object_hash,
+            },
+        )?;
+        Ok(bundle)
+    }
+
+    /// If there is a `multi-pack-index`, rewrite it to contain all packs that remain after repacking, without those at `removed_index_paths`.
+    fn rewrite_multi_pack_index(
+        &self,
+        packs: &[LocalPack],
+        removed_index_paths: &[PathBuf],
+        out: &Outcome,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<(), Error> {
+        let pack_dir = self.objects.store_ref().path().join("pack");
+        let multi_index_path = pack_dir.join("multi-pack-index");
+        if !multi_index_path.is_file() {
+            return Ok(());
+        }
+        let mut index_paths: Vec<_> = packs
+            .iter()
+            .map(|pack| pack.index_path.clone())
+            .filter(|path| !removed_index_paths.contains(path))
+            .collect();
+        if let Some(new_index) = out.write_pack_bundle.as_ref().and_then(|b| b.index_path.clone()) {
+            if !index_paths.contains(&new_index) {
+                index_paths.push(new_index);
+            }
+        }
+        let mut file =
+            gix_lock::File::acquire_to_update_resource(&multi_index_path, gix_lock::acquire::Fail::Immediately, None)
+                .map_err(|err| Error::Io {
+                path: multi_index_path.clone(),
+                source: std::io::Error::other(err),
+            })?;
+        gix_pack::multi_index::File::write_from_index_paths(
+            index_paths,
+            &mut file,
+            progress,
+            should_interrupt,
+            gix_pack::multi_index::write::Options {
+                object_hash: self.object_hash(),
+            },
+        )?;
+        file.commit().map_err(|err| Error::Io {
+            path: multi_index_path,
+            source: err.error,
+        })?;
+        Ok(())
+    }
+}
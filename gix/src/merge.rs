@@ -1,5 +1,5 @@
 pub use gix_merge as plumbing;
-pub use gix_merge::blob;
+pub use gix_merge::{blob, rerere};
 
 ///
 pub mod virtual_merge_base {
@@ -42,21 +42,22 @@ pub mod commit {
         pub virtual_merge_bases: Vec<gix_hash::ObjectId>,
     }
 
+    pub use gix_merge::commit::Strategy;
+    pub use gix_merge::tree::shift::Subtree;
+
     /// A way to configure [`Repository::merge_commits()`](crate::Repository::merge_commits()).
     #[derive(Default, Debug, Clone)]
     pub struct Options {
         allow_missing_merge_base: bool,
         tree_merge: crate::merge::tree::Options,
         use_first_merge_base: bool,
+        strategy: Strategy,
+        subtree_shift: Option<Subtree>,
     }
 
     impl From<gix_merge::tree::Options> for Options {
         fn from(value: gix_merge::tree::Options) -> Self {
-            Options {
-                tree_merge: value.into(),
-                use_first_merge_base: false,
-                allow_missing_merge_base: false,
-            }
+            crate::merge::tree::Options::from(value).into()
         }
     }
 
@@ -66,6 +67,8 @@ pub mod commit {
                 tree_merge: value,
                 use_first_merge_base: false,
                 allow_missing_merge_base: false,
+                strategy: Strategy::default(),
+                subtree_shift: None,
             }
         }
     }
@@ -76,16 +79,26 @@ pub mod commit {
                 allow_missing_merge_base,
                 tree_merge,
                 use_first_merge_base,
+                strategy,
+                subtree_shift,
             }: Options,
         ) -> Self {
             gix_merge::commit::Options {
                 allow_missing_merge_base,
                 tree_merge: tree_merge.into(),
                 use_first_merge_base,
+                strategy,
+                subtree_shift,
             }
         }
     }
 
+    impl Options {
+        pub(crate) fn tree_merge(&self) -> &crate::merge::tree::Options {
+            &self.tree_merge
+        }
+    }
+
     /// Builder
     impl Options {
         /// If `true`, merging unrelated commits is allowed, with the merge-base being assumed as empty tree.
@@ -100,9 +113,174 @@ pub mod commit {
             self.use_first_merge_base = use_first_merge_base;
             self
         }
+
+        /// Determine how the trees of both commits are combined, like `git merge --strategy` does.
+        /// Use [`Strategy::Ours`] to keep *our* tree as is.
+        pub fn with_strategy(mut self, strategy: Strategy) -> Self {
+            self.strategy = strategy;
+            self
+        }
+
+        /// If `Some(how)`, shift *their* tree to line up with *our* tree before merging,
+        /// like `git merge -s subtree` or `git merge -X subtree=<path>` do.
+        pub fn with_subtree_shift(mut self, subtree_shift: Option<Subtree>) -> Self {
+            self.subtree_shift = subtree_shift;
+            self
+        }
     }
 }
 
+///
+pub mod octopus {
+    use crate::Id;
+
+    /// The outcome produced by [`Repository::merge_commits_octopus()`](crate::Repository::merge_commits_octopus()).
+    #[derive(Debug, Clone)]
+    pub struct Outcome<'repo> {
+        /// The id of the tree that contains the changes of all merged commits, as written to the object database.
+        pub tree_id: Id<'repo>,
+        /// The commits to use as parents of the merge commit, in order.
+        ///
+        /// It starts with *our* commit unless it could be fast-forwarded, and it doesn't contain commits that were
+        /// already contained in the merge result.
+        /// If there is just one parent, no merge commit is needed as the merge result is this very commit.
+        pub parents: Vec<Id<'repo>>,
+        /// All conflicts that were encountered and resolved automatically while merging one commit after another.
+        pub conflicts: Vec<crate::merge::tree::Conflict>,
+    }
+
+    /// A way to configure [`Repository::merge_commits_octopus()`](crate::Repository::merge_commits_octopus()).
+    #[derive(Default, Debug, Clone)]
+    pub struct Options {
+        allow_missing_merge_base: bool,
+        tree_merge: crate::merge::tree::Options,
+        treat_as_unresolved: crate::merge::tree::TreatAsUnresolved,
+    }
+
+    impl From<crate::merge::tree::Options> for Options {
+        fn from(value: crate::merge::tree::Options) -> Self {
+            Options {
+                allow_missing_merge_base: false,
+                tree_merge: value,
+                treat_as_unresolved: Default::default(),
+            }
+        }
+    }
+
+    impl From<Options> for gix_merge::commit::octopus::Options {
+        fn from(
+            Options {
+                allow_missing_merge_base,
+                tree_merge,
+                treat_as_unresolved,
+            }: Options,
+        ) -> Self {
+            gix_merge::commit::octopus::Options {
+                allow_missing_merge_base,
+                tree_merge: tree_merge.into(),
+                treat_as_unresolved,
+            }
+        }
+    }
+
+    impl Options {
+        pub(crate) fn tree_merge(&self) -> &crate::merge::tree::Options {
+            &self.tree_merge
+        }
+    }
+
+    /// Builder
+    impl Options {
+        /// If `true`, merging commits that don't share a common ancestor is allowed, with the merge-base being assumed as empty tree.
+        pub fn with_allow_missing_merge_base(mut self, allow_missing_merge_base: bool) -> Self {
+            self.allow_missing_merge_base = allow_missing_merge_base;
+            self
+        }
+
+        /// Determine which conflicts are considered unresolved, which makes the octopus merge fail.
+        pub fn with_treat_as_unresolved(mut self, treat_as_unresolved: crate::merge::tree::TreatAsUnresolved) -> Self {
+            self.treat_as_unresolved = treat_as_unresolved;
+            self
+        }
+    }
+}
+
+///
+#[cfg(feature = "worktree-mutation")]
+pub mod worktree {
+    use crate::{bstr::BString, Id};
+
+    /// The outcome produced by [`Repository::merge_into_worktree()`](crate::Repository::merge_into_worktree()).
+    #[derive(Debug, Clone)]
+    pub struct Outcome<'repo> {
+        /// The id of the merged tree as it was written to the worktree, which includes files with conflict markers.
+        ///
+        /// If there are unresolved conflicts, it's also stored in `AUTO_MERGE`.
+        pub tree_id: Id<'repo>,
+        /// The set of conflicts we encountered, resolved or not.
+        pub conflicts: Vec<crate::merge::tree::Conflict>,
+        /// The paths that were added to the index with their conflicting stages, and which need to be resolved before
+        /// the merge can be [finished](crate::Repository::finish_merge()).
+        /// If empty, the merge can be finished right away.
+        pub unresolved: Vec<BString>,
+    }
+
+    /// A way to configure [`Repository::merge_into_worktree()`](crate::Repository::merge_into_worktree()).
+    #[derive(Default, Debug, Clone)]
+    pub struct Options {
+        commit_merge: crate::merge::commit::Options,
+        treat_as_unresolved: crate::merge::tree::TreatAsUnresolved,
+        message: Option<BString>,
+    }
+
+    impl From<crate::merge::tree::Options> for Options {
+        fn from(value: crate::merge::tree::Options) -> Self {
+            crate::merge::commit::Options::from(value).into()
+        }
+    }
+
+    impl From<crate::merge::commit::Options> for Options {
+        fn from(value: crate::merge::commit::Options) -> Self {
+            Options {
+                commit_merge: value,
+                treat_as_unresolved: Default::default(),
+                message: None,
+            }
+        }
+    }
+
+    impl Options {
+        pub(crate) fn into_parts(
+            self,
+        ) -> (
+            crate::merge::commit::Options,
+            crate::merge::tree::TreatAsUnresolved,
+            Option<BString>,
+        ) {
+            (self.commit_merge, self.treat_as_unresolved, self.message)
+        }
+    }
+
+    /// Builder
+    impl Options {
+        /// Determine which conflicts are considered unresolved, which are the ones that are added to the index
+        /// with their conflicting stages.
+        pub fn with_treat_as_unresolved(mut self, treat_as_unresolved: crate::merge::tree::TreatAsUnresolved) -> Self {
+            self.treat_as_unresolved = treat_as_unresolved;
+            self
+        }
+
+        /// Set the message to write into `MERGE_MSG` for use as commit message once the merge is finished.
+        /// If `None`, a message like the one by `git merge` is used.
+        pub fn with_message(mut self, message: Option<BString>) -> Self {
+            self.message = message;
+            self
+        }
+    }
+
+    pub use crate::worktree::update;
+}
+
 ///
 pub mod tree {
     use gix_merge::blob::builtin_driver;
@@ -160,6 +338,7 @@ pub mod tree {
         inner: gix_merge::tree::Options,
         file_favor: Option<FileFavor>,
         tree_favor: Option<TreeFavor>,
+        renormalize: Option<bool>,
     }
 
     impl From<gix_merge::tree::Options> for Options {
@@ -168,10 +347,24 @@ pub mod tree {
                 inner: opts,
                 file_favor: None,
                 tree_favor: None,
+                renormalize: None,
             }
         }
     }
 
+    impl Options {
+        /// Return the way to convert resources for merging, if it should differ from what's configured.
+        pub(crate) fn filter_mode(&self) -> Option<gix_merge::blob::pipeline::Mode> {
+            self.renormalize.map(|renormalize| {
+                if renormalize {
+                    gix_merge::blob::pipeline::Mode::Renormalize
+                } else {
+                    gix_merge::blob::pipeline::Mode::ToGit
+                }
+            })
+        }
+    }
+
     impl From<Options> for gix_merge::tree::Options {
         fn from(value: Options) -> Self {
             let mut opts = value.inner;
@@ -281,5 +474,22 @@ pub mod tree {
             self.tree_favor = tree_favor;
             self
         }
+
+        /// Determine which whitespace to ignore when merging lines of text, like `git merge -X ignore-space-change`
+        /// and its siblings do.
+        ///
+        /// If one side only changed whitespace in lines the other side didn't change, *our* version of these lines is used.
+        pub fn with_whitespace(mut self, whitespace: gix_diff::blob::whitespace::Mode) -> Self {
+            self.inner.blob_merge.text.whitespace = whitespace;
+            self
+        }
+
+        /// If `Some(true)`, convert all versions of a file to what would be checked out and back before merging them,
+        /// like `git merge -X renormalize` does, or don't do so with `Some(false)`, like `-X no-renormalize`.
+        /// If `None`, the `merge.renormalize` configuration decides.
+        pub fn with_renormalize(mut self, renormalize: Option<bool>) -> Self {
+            self.renormalize = renormalize;
+            self
+        }
     }
 }
@@ -327,11 +327,11 @@ impl ThreadSafeRepository {
                 None if !config.is_bare && looks_like_standard_git_dir() => {
                     worktree_dir = Some(git_dir.parent().expect("parent is always available").to_owned());
                 }
-                Some(_) => {
-                    // We may assume that the presence of a worktree-dir means it's not bare, but only if there
-                    // is no configuration saying otherwise.
-                    // Thus, if we are here and the common-dir config claims it's bare and we have inferred a worktree anyway,
-                    // forget about it.
+                // We may assume that the presence of a worktree-dir means it's not bare, but only if there
+                // is no configuration saying otherwise.
+                // Thus, if we are here and the common-dir config claims it's bare and we have inferred a worktree anyway,
+                // forget about it.
+                Some(_)
                     if looks_like_standard_git_dir()
                         && config
                             .resolved
@@ -340,12 +340,11 @@ impl ThreadSafeRepository {
                             .ok()
                             .flatten()
                             .is_some()
-                        && config.is_bare
-                    {
-                        worktree_dir = None;
-                    }
+                        && config.is_bare =>
+                {
+                    worktree_dir = None;
                 }
-                None => {}
+                Some(_) | None => {}
             }
         }
 
@@ -0,0 +1,132 @@
+use std::{fmt::Write, time::SystemTime};
+
+use gix_date::{
+    time::{format, CustomFormat},
+    Time,
+};
+
+use crate::bstr::{BStr, BString, ByteSlice};
+
+/// How to format dates, similar to the `--date` option of `git log`.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DateMode {
+    /// The format to use.
+    pub format: DateFormat,
+    /// If `true`, show dates in the local time zone instead of the time zone they were recorded in.
+    pub local: bool,
+}
+
+/// The formats a [`DateMode`] can use.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DateFormat {
+    /// Like `Thu Sep 24 14:26:40 2020 +0200`, without the time zone if [local](DateMode::local).
+    #[default]
+    Default,
+    /// Like `2 hours ago`.
+    Relative,
+    /// Like `2020-09-24 14:26:40 +0200`.
+    Iso8601,
+    /// Like `2020-09-24T14:26:40+02:00`.
+    Iso8601Strict,
+    /// Like `Thu, 24 Sep 2020 14:26:40 +0200`.
+    Rfc2822,
+    /// Like `2020-09-24`.
+    Short,
+    /// Like `1600950400 +0200`.
+    Raw,
+    /// Like `1600950400`.
+    Unix,
+    /// A `strftime` format like `%Y/%m/%d %H:%M`.
+    Custom(String),
+}
+
+impl From<DateFormat> for DateMode {
+    fn from(format: DateFormat) -> Self {
+        DateMode { format, local: false }
+    }
+}
+
+/// Like [`format::DEFAULT`], but without time zone, as used for local times.
+const DEFAULT_LOCAL: CustomFormat = CustomFormat::new("%a %b %-d %H:%M:%S %Y");
+
+/// The error returned by [`DateMode::from_bytes()`].
+#[derive(Debug, thiserror::Error)]
+#[error("Unknown date format '{format}'")]
+pub struct Error {
+    /// The date format that couldn't be parsed.
+    pub format: BString,
+}
+
+impl DateMode {
+    /// Parse `input` like the `--date` option of `git log` or the `log.date` configuration, with all formats also
+    /// supporting the `-local` suffix, like `iso-local`, and `format:<strftime>` or `format-local:<strftime>` for custom formats.
+    ///
+    /// The `human` and `auto:` formats aren't supported.
+    pub fn from_bytes(input: &BStr) -> Result<Self, Error> {
+        let err = || Error {
+            format: input.to_owned(),
+        };
+        let formats: &[(&[u8], Option<DateFormat>)] = &[
+            (b"relative", Some(DateFormat::Relative)),
+            (b"iso8601-strict", Some(DateFormat::Iso8601Strict)),
+            (b"iso-strict", Some(DateFormat::Iso8601Strict)),
+            (b"iso8601", Some(DateFormat::Iso8601)),
+            (b"iso", Some(DateFormat::Iso8601)),
+            (b"rfc2822", Some(DateFormat::Rfc2822)),
+            (b"rfc", Some(DateFormat::Rfc2822)),
+            (b"short", Some(DateFormat::Short)),
+            (b"default", Some(DateFormat::Default)),
+            (b"raw", Some(DateFormat::Raw)),
+            (b"unix", Some(DateFormat::Unix)),
+            (b"format", None),
+        ];
+        let input_or_alias = if input == "local" {
+            b"default-local".as_bstr()
+        } else {
+            input
+        };
+        let (format, rest) = formats
+            .iter()
+            .find_map(|(name, format)| input_or_alias.strip_prefix(*name).map(|rest| (format.clone(), rest)))
+            .ok_or_else(err)?;
+        let (local, rest) = match rest.strip_prefix(b"-local") {
+            Some(rest) => (true, rest),
+            None => (false, rest),
+        };
+        let format = match format {
+            Some(format) if rest.is_empty() => format,
+            None => DateFormat::Custom(
+                rest.strip_prefix(b":")
+                    .and_then(|custom| custom.to_str().ok())
+                    .ok_or_else(err)?
+                    .to_owned(),
+            ),
+            Some(_) => return Err(err()),
+        };
+        Ok(DateMode { format, local })
+    }
+
+    /// Format `time` in this mode, using `now` for [relative](DateFormat::Relative) dates.
+    pub fn format(&self, time: Time, now: SystemTime) -> String {
+        let time = if self.local { time.to_local() } else { time };
+        match &self.format {
+            DateFormat::Default if self.local => time.format_or_unix(DEFAULT_LOCAL),
+            DateFormat::Default => time.format_or_unix(format::DEFAULT),
+            DateFormat::Relative => time.format_relative(now),
+            DateFormat::Iso8601 => time.format_or_unix(format::ISO8601),
+            DateFormat::Iso8601Strict => time.format_or_unix(format::ISO8601_STRICT),
+            DateFormat::Rfc2822 => time.format_or_unix(format::GIT_RFC2822),
+            DateFormat::Short => time.format_or_unix(format::SHORT),
+            DateFormat::Raw => time.format_or_unix(format::RAW),
+            DateFormat::Unix => time.format_or_unix(format::UNIX),
+            DateFormat::Custom(format) => {
+                // Invalid formats make the formatting fail, which would panic with `to_string()`.
+                let mut out = String::new();
+                match time.to_zoned() {
+                    Ok(zoned) if write!(out, "{}", zoned.strftime(format)).is_ok() => out,
+                    _ => time.seconds.to_string(),
+                }
+            }
+        }
+    }
+}
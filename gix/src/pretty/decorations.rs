@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+
+use gix_hash::ObjectId;
+
+use crate::bstr::{BString, ByteSlice};
+
+/// The kind of reference a decoration was created for, which determines its color.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Kind {
+    Head,
+    LocalBranch,
+    RemoteBranch,
+    Tag,
+    Stash,
+}
+
+impl Kind {
+    fn color(&self) -> &'static str {
+        match self {
+            Kind::Head => "\x1b[1;36m",
+            Kind::LocalBranch => "\x1b[1;32m",
+            Kind::RemoteBranch => "\x1b[1;31m",
+            Kind::Tag => "\x1b[1;33m",
+            Kind::Stash => "\x1b[1;35m",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Decoration {
+    kind: Kind,
+    /// The full name of the reference.
+    name: BString,
+}
+
+impl Decoration {
+    /// The name to show, which is shortened for branches and tags.
+    fn short_name(&self) -> &[u8] {
+        let name = self.name.as_bytes();
+        [&b"refs/heads/"[..], b"refs/tags/", b"refs/remotes/"]
+            .iter()
+            .find_map(|prefix| name.strip_prefix(*prefix))
+            .unwrap_or(name)
+    }
+}
+
+/// The names of the references pointing to each commit, like those shown by `git log --decorate`.
+#[derive(Debug, Default, Clone)]
+pub(super) struct Decorations {
+    by_id: HashMap<ObjectId, Vec<Decoration>>,
+    /// The full name of the branch `HEAD` points to, if it is a symbolic reference.
+    head_branch: Option<BString>,
+}
+
+/// The strings to put around and between the decorations of a commit.
+pub(super) struct Separators {
+    pub prefix: &'static str,
+    pub separator: &'static str,
+    pub suffix: &'static str,
+}
+
+impl Separators {
+    /// The separators used in presets and by `%d`.
+    pub const PARENTHESES: Separators = Separators {
+        prefix: " (",
+        separator: ", ",
+        suffix: ")",
+    };
+    /// The separators used by `%D`.
+    pub const PLAIN: Separators = Separators {
+        prefix: "",
+        separator: ", ",
+        suffix: "",
+    };
+}
+
+impl Decorations {
+    /// Load the decorations for local and remote branches, tags, the stash and `HEAD`, like Git does by default.
+    pub(super) fn load(repo: &crate::Repository) -> Result<Self, crate::repository::pretty_printer::Error> {
+        let mut out = Decorations::default();
+        let platform = repo.references()?;
+        for reference in platform.all()? {
+            let reference = reference.map_err(crate::repository::pretty_printer::Error::IterReferences)?;
+            let name = reference.name().as_bstr();
+            let kind = if name.starts_with(b"refs/heads/") {
+                Kind::LocalBranch
+            } else if name.starts_with(b"refs/remotes/") {
+                Kind::RemoteBranch
+            } else if name.starts_with(b"refs/tags/") {
+                Kind::Tag
+            } else if name == "refs/stash" {
+                Kind::Stash
+            } else {
+                continue;
+            };
+            out.add(repo, reference, kind)?;
+        }
+
+        let head = repo.head()?;
+        out.head_branch = head.referent_name().map(|name| name.as_bstr().to_owned());
+        let detached_id = head.id().map(crate::Id::detach);
+        match head.try_into_referent() {
+            Some(reference) => out.add(repo, reference, Kind::Head)?,
+            None => {
+                if let Some(id) = detached_id {
+                    out.push(id, Kind::Head, "HEAD".into());
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// Decorate the object `reference` points to, and the objects of all tags it leads to.
+    fn add(
+        &mut self,
+        repo: &crate::Repository,
+        mut reference: crate::Reference<'_>,
+        kind: Kind,
+    ) -> Result<(), crate::repository::pretty_printer::Error> {
+        let name: BString = if kind == Kind::Head {
+            "HEAD".into()
+        } else {
+            reference.name().as_bstr().to_owned()
+        };
+        // References to missing objects are ignored, just like Git does.
+        let Ok(mut id) = reference.follow_to_object().map(crate::Id::detach) else {
+            return Ok(());
+        };
+        self.push(id, kind, name.clone());
+        while let Some(object) = repo.try_find_object(id)? {
+            if object.kind != gix_object::Kind::Tag {
+                break;
+            }
+            id = object.into_tag().target_id()?.detach();
+            self.push(id, Kind::Tag, name.clone());
+        }
+        Ok(())
+    }
+
+    fn push(&mut self, id: ObjectId, kind: Kind, name: BString) {
+        self.by_id.entry(id).or_default().push(Decoration { kind, name });
+    }
+
+    /// Write the decorations of `id` to `out`, if there are any, colored with ANSI escape codes if `color` is `true`.
+    pub(super) fn write(&self, id: &ObjectId, color: bool, separators: &Separators, out: &mut Vec<u8>) {
+        let Some(decorations) = self.by_id.get(id) else {
+            return;
+        };
+        let (color_commit, color_reset) = if color { ("\x1b[33m", "\x1b[m") } else { ("", "") };
+        let color_of = |kind: Kind| if color { kind.color() } else { "" };
+
+        // `HEAD` is shown along with the branch it points to, which is otherwise not shown by itself.
+        let current_branch = decorations.iter().any(|d| d.kind == Kind::Head).then(|| {
+            decorations
+                .iter()
+                .find(|d| d.kind == Kind::LocalBranch && Some(&d.name) == self.head_branch.as_ref())
+        });
+        let current_branch = current_branch.flatten();
+
+        // Decorations are shown in the reverse order in which they were added.
+        let mut prefix = separators.prefix;
+        for decoration in decorations.iter().rev() {
+            if current_branch.is_some_and(|current| std::ptr::eq(current, decoration)) {
+                continue;
+            }
+            let color = color_of(decoration.kind);
+            if !prefix.is_empty() {
+                out.extend_from_slice(color_commit.as_bytes());
+                out.extend_from_slice(prefix.as_bytes());
+                out.extend_from_slice(color_reset.as_bytes());
+            }
+            out.extend_from_slice(color.as_bytes());
+            if decoration.kind == Kind::Tag {
+                out.extend_from_slice(b"tag: ");
+            }
+            out.extend_from_slice(decoration.short_name());
+            if let Some(current) = current_branch.filter(|_| decoration.kind == Kind::Head) {
+                out.extend_from_slice(b" -> ");
+                out.extend_from_slice(color_reset.as_bytes());
+                out.extend_from_slice(color_of(current.kind).as_bytes());
+                out.extend_from_slice(current.short_name());
+            }
+            out.extend_from_slice(color_reset.as_bytes());
+            prefix = separators.separator;
+        }
+        out.extend_from_slice(color_commit.as_bytes());
+        out.extend_from_slice(separators.suffix.as_bytes());
+        out.extend_from_slice(color_reset.as_bytes());
+    }
+}
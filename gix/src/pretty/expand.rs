@@ -0,0 +1,878 @@
+use std::time::SystemTime;
+
+use gix_hash::ObjectId;
+use gix_object::CommitRef;
+
+use super::{
+    decorations::{Decorations, Separators},
+    signature, DateFormat, DateMode, Mailmap,
+};
+use crate::{
+    bstr::{BStr, ByteSlice},
+    ext::ObjectIdExt,
+    pretty::printer::Error,
+};
+
+const COLOR_COMMIT: &str = "\x1b[33m";
+const COLOR_RESET: &str = "\x1b[m";
+
+/// Everything needed to format a single commit.
+pub(super) struct Context<'a> {
+    pub repo: &'a crate::Repository,
+    pub id: ObjectId,
+    /// The commit as stored in the object database.
+    pub data: &'a [u8],
+    pub commit: CommitRef<'a>,
+    /// The parents to show, which may differ from the ones in `commit` if history was simplified.
+    pub parents: &'a [ObjectId],
+    pub mailmap: &'a Mailmap,
+    pub decorations: &'a Decorations,
+    pub date: &'a DateMode,
+    pub now: SystemTime,
+    pub color: bool,
+    /// The width of the graph in front of each line, for placeholders that pad to a column.
+    pub graph_width: usize,
+}
+
+impl Context<'_> {
+    /// Return the abbreviated form of `id`.
+    pub fn abbreviate(&self, id: ObjectId) -> String {
+        id.attach(self.repo).shorten_or_id().to_string()
+    }
+}
+
+/// Expand all placeholders in `template` for the commit in `ctx` and append the result to `out`, like
+/// `format_commit_message()` of Git does.
+pub(super) fn expand(template: &[u8], ctx: &Context<'_>, out: &mut Vec<u8>) -> Result<(), Error> {
+    let mut expander = Expander {
+        ctx,
+        auto_color: false,
+        flush: Flush::None,
+        padding: 0,
+        truncate: Truncate::None,
+        signature: None,
+    };
+    let mut rest = template;
+    loop {
+        match rest.find_byte(b'%') {
+            Some(pos) => {
+                out.extend_from_slice(&rest[..pos]);
+                rest = &rest[pos + 1..];
+            }
+            None => {
+                out.extend_from_slice(rest);
+                break;
+            }
+        }
+        if let Some(after_escape) = rest.strip_prefix(b"%") {
+            out.push(b'%');
+            rest = after_escape;
+            continue;
+        }
+        // Unknown placeholders are shown as they are.
+        match expander.item(rest, out)? {
+            0 => out.push(b'%'),
+            consumed => rest = &rest[consumed..],
+        }
+    }
+    Ok(())
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Flush {
+    None,
+    /// Pad on the left.
+    Left,
+    /// Pad on the right.
+    Right,
+    /// Pad on both sides.
+    Both,
+    /// Pad on the left, using trailing spaces of the output so far first.
+    LeftAndSteal,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Truncate {
+    None,
+    Left,
+    Middle,
+    Right,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Magic {
+    None,
+    /// `%-x`: remove newlines before `x` if it expands to nothing.
+    DeleteNewlinesBeforeEmpty,
+    /// `%+x`: add a newline before `x` if it expands to something.
+    AddNewlineBeforeNonEmpty,
+    /// `% x`: add a space before `x` if it expands to something.
+    AddSpaceBeforeNonEmpty,
+}
+
+struct Expander<'c, 'a> {
+    ctx: &'c Context<'a>,
+    /// If `true`, placeholders like `%h` and `%d` are colored, as set by `%C(auto)`.
+    auto_color: bool,
+    /// How to pad the next placeholder.
+    flush: Flush,
+    /// The width to pad the next placeholder to, or the negated column to pad to.
+    padding: isize,
+    truncate: Truncate,
+    /// The verified signature of the commit, once a placeholder needed it.
+    signature: Option<signature::Check>,
+}
+
+impl Expander<'_, '_> {
+    /// Expand the placeholder at the beginning of `placeholder`, without its `%`, and return the amount of bytes it
+    /// takes, or `0` if it is unknown.
+    fn item(&mut self, placeholder: &[u8], out: &mut Vec<u8>) -> Result<usize, Error> {
+        let magic = match placeholder.first() {
+            Some(b'-') => Magic::DeleteNewlinesBeforeEmpty,
+            Some(b'+') => Magic::AddNewlineBeforeNonEmpty,
+            Some(b' ') => Magic::AddSpaceBeforeNonEmpty,
+            _ => Magic::None,
+        };
+        let placeholder = if magic == Magic::None {
+            placeholder
+        } else {
+            &placeholder[1..]
+        };
+
+        let orig_len = out.len();
+        let consumed = if self.flush == Flush::None {
+            self.one(placeholder, out)?
+        } else {
+            self.pad(placeholder, out)?
+        };
+        if magic == Magic::None {
+            return Ok(consumed);
+        }
+
+        if orig_len == out.len() {
+            if magic == Magic::DeleteNewlinesBeforeEmpty {
+                while out.last() == Some(&b'\n') {
+                    out.pop();
+                }
+            }
+        } else if magic == Magic::AddNewlineBeforeNonEmpty {
+            out.insert(orig_len, b'\n');
+        } else if magic == Magic::AddSpaceBeforeNonEmpty {
+            out.insert(orig_len, b' ');
+        }
+        Ok(consumed + 1)
+    }
+
+    /// Expand `placeholder` into a separate buffer and pad or truncate it as configured by the previous placeholder.
+    fn pad(&mut self, mut placeholder: &[u8], out: &mut Vec<u8>) -> Result<usize, Error> {
+        let mut padding = self.padding;
+        if padding < 0 {
+            let line_start = out.rfind_byte(b'\n').unwrap_or(0);
+            let occupied = display_width(&out[line_start..]) + self.ctx.graph_width;
+            padding = -padding - occupied as isize;
+        }
+
+        // Colors don't count as placeholder to pad, so they are expanded along with the placeholder following them.
+        let mut local = Vec::new();
+        let mut total_consumed = 0;
+        loop {
+            let is_color = placeholder.first() == Some(&b'C');
+            let consumed = self.one(placeholder, &mut local)?;
+            total_consumed += consumed;
+            if !is_color {
+                break;
+            }
+            placeholder = &placeholder[consumed..];
+            match placeholder.strip_prefix(b"%") {
+                Some(next) => {
+                    placeholder = next;
+                    total_consumed += 1;
+                }
+                None => break,
+            }
+        }
+
+        let len = display_width(&local) as isize;
+        if self.flush == Flush::LeftAndSteal {
+            // Take trailing spaces from the output so far, moving color changes in between to the padded value.
+            let mut end = out.len();
+            while len > padding && end > 1 {
+                let last = end - 1;
+                if out[last] == b' ' {
+                    end -= 1;
+                    padding += 1;
+                    continue;
+                }
+                if out[last] != b'm' {
+                    break;
+                }
+                let mut start = last - 1;
+                while start > 0 && last - start < 10 && out[start] != 0x1b {
+                    start -= 1;
+                }
+                if out[start] != 0x1b || last + 1 - start != ansi_sequence_len(&out[start..]) {
+                    break;
+                }
+                local.splice(0..0, out[start..=last].iter().copied());
+                end = start;
+            }
+            out.truncate(end);
+            self.flush = Flush::Left;
+        }
+
+        if len > padding {
+            let removed_width = len - (padding - 2);
+            let local = match self.truncate {
+                Truncate::None => local,
+                Truncate::Left => replace_columns(&local, 0, removed_width, ".."),
+                Truncate::Middle => replace_columns(&local, padding / 2 - 1, removed_width, ".."),
+                Truncate::Right => replace_columns(&local, padding - 2, removed_width, ".."),
+            };
+            out.extend_from_slice(&local);
+        } else {
+            let offset = match self.flush {
+                Flush::Left => padding - len,
+                Flush::Both => (padding - len) / 2,
+                Flush::Right | Flush::None | Flush::LeftAndSteal => 0,
+            } as usize;
+            // The padding is counted in columns, but the value may contain color codes which take no space.
+            let padded_len = (padding - len) as usize + local.len();
+            let start = out.len();
+            out.resize(start + padded_len, b' ');
+            out[start + offset..][..local.len()].copy_from_slice(&local);
+        }
+        self.flush = Flush::None;
+        Ok(total_consumed)
+    }
+
+    /// Expand a single placeholder, returning the amount of bytes it takes, or `0` if it is unknown.
+    fn one(&mut self, placeholder: &[u8], out: &mut Vec<u8>) -> Result<usize, Error> {
+        let ctx = self.ctx;
+        let Some(&first) = placeholder.first() else {
+            return Ok(0);
+        };
+
+        // Placeholders that don't depend on the commit.
+        match first {
+            b'C' => {
+                if placeholder[1..].starts_with(b"(auto)") {
+                    self.auto_color = ctx.color;
+                    if self.auto_color && !out.is_empty() {
+                        out.extend_from_slice(COLOR_RESET.as_bytes());
+                    }
+                    return Ok(7);
+                }
+                let consumed = self.color(placeholder, out)?;
+                // Unknown colors are shown as they are, and a previous `%C(auto)` remains in effect.
+                if consumed != 0 {
+                    self.auto_color = false;
+                }
+                return Ok(consumed);
+            }
+            b'n' => {
+                out.push(b'\n');
+                return Ok(1);
+            }
+            b'x' => {
+                return Ok(match parse_hex_byte(&placeholder[1..]) {
+                    Some(byte) => {
+                        out.push(byte);
+                        3
+                    }
+                    None => 0,
+                })
+            }
+            b'<' | b'>' => return Ok(self.parse_padding(placeholder)),
+            _ => {}
+        }
+
+        let (color_commit, color_reset) = if self.auto_color {
+            (COLOR_COMMIT, COLOR_RESET)
+        } else {
+            ("", "")
+        };
+        let mut write_ids = |ids: &mut dyn Iterator<Item = String>| {
+            for (idx, id) in ids.enumerate() {
+                if idx != 0 {
+                    out.push(b' ');
+                }
+                out.extend_from_slice(id.as_bytes());
+            }
+            1
+        };
+        match first {
+            b'H' | b'h' => {
+                out.extend_from_slice(color_commit.as_bytes());
+                if first == b'H' {
+                    out.extend_from_slice(ctx.id.to_hex().to_string().as_bytes());
+                } else {
+                    out.extend_from_slice(ctx.abbreviate(ctx.id).as_bytes());
+                }
+                out.extend_from_slice(color_reset.as_bytes());
+                return Ok(1);
+            }
+            b'T' => return Ok(write_ids(&mut std::iter::once(ctx.commit.tree().to_string()))),
+            b't' => return Ok(write_ids(&mut std::iter::once(ctx.abbreviate(ctx.commit.tree())))),
+            b'P' => return Ok(write_ids(&mut ctx.parents.iter().map(ToString::to_string))),
+            b'p' => return Ok(write_ids(&mut ctx.parents.iter().map(|id| ctx.abbreviate(*id)))),
+            b'm' => {
+                out.push(b'>');
+                return Ok(1);
+            }
+            b'd' | b'D' => {
+                let separators = if first == b'd' {
+                    Separators::PARENTHESES
+                } else {
+                    Separators::PLAIN
+                };
+                ctx.decorations.write(&ctx.id, self.auto_color, &separators, out);
+                return Ok(1);
+            }
+            b'G' => {
+                let Some(&kind @ (b'?' | b'G' | b'S' | b'K' | b'F' | b'P' | b'T')) = placeholder.get(1) else {
+                    return Ok(0);
+                };
+                let check = self
+                    .signature
+                    .get_or_insert_with(|| signature::verify(ctx.repo, ctx.data));
+                match kind {
+                    b'?' => out.push(check.status()),
+                    b'G' => out.extend_from_slice(&check.output),
+                    b'T' => out.extend_from_slice(check.trust.map_or("", |trust| trust.as_str()).as_bytes()),
+                    _ => {
+                        let value = match kind {
+                            b'S' => &check.signer,
+                            b'K' => &check.key,
+                            b'F' => &check.fingerprint,
+                            _ => &check.primary_key_fingerprint,
+                        };
+                        if let Some(value) = value {
+                            out.extend_from_slice(value);
+                        }
+                    }
+                }
+                return Ok(2);
+            }
+            _ => {}
+        }
+
+        let message = ctx.commit.message.as_bytes();
+        match first {
+            b'a' | b'c' => {
+                let signature = if first == b'a' {
+                    ctx.commit.author()
+                } else {
+                    ctx.commit.committer()
+                };
+                return Ok(match signature {
+                    Ok(signature) => self.person_part(placeholder.get(1).copied(), signature, out),
+                    Err(_) => 0,
+                });
+            }
+            b'e' => {
+                if let Some(encoding) = ctx.commit.encoding {
+                    out.extend_from_slice(encoding);
+                }
+                return Ok(1);
+            }
+            b'B' => {
+                out.extend_from_slice(message);
+                return Ok(1);
+            }
+            b's' => {
+                format_subject(skip_blank_lines(message), b" ", Some(out));
+                return Ok(1);
+            }
+            b'f' => {
+                let subject = skip_blank_lines(message);
+                let first_line = subject.lines().next().unwrap_or_default();
+                format_sanitized_subject(first_line, out);
+                return Ok(1);
+            }
+            b'b' => {
+                out.extend_from_slice(body(message));
+                return Ok(1);
+            }
+            _ => {}
+        }
+
+        Ok(placeholder
+            .strip_prefix(b"(trailers")
+            .and_then(|args| self.trailers(args, out))
+            .map_or(0, |consumed| consumed + "(trailers".len()))
+    }
+
+    /// Handle `%C…`, returning the amount of bytes consumed.
+    fn color(&self, placeholder: &[u8], out: &mut Vec<u8>) -> Result<usize, Error> {
+        let ctx = self.ctx;
+        if let Some(spec) = placeholder[1..].strip_prefix(b"(") {
+            let Some(end) = spec.find_byte(b')') else {
+                return Ok(0);
+            };
+            let consumed = end + 3;
+            let spec = &spec[..end];
+            let spec = if let Some(spec) = spec.strip_prefix(b"auto,") {
+                if !ctx.color {
+                    return Ok(consumed);
+                }
+                spec
+            } else if let Some(spec) = spec.strip_prefix(b"always,") {
+                spec
+            } else {
+                if !ctx.color {
+                    return Ok(consumed);
+                }
+                spec
+            };
+            let color = gix_config::Color::try_from(spec.as_bstr()).map_err(|_| Error::Color {
+                spec: spec.as_bstr().to_owned(),
+            })?;
+            out.extend_from_slice(color.to_ansi().as_bytes());
+            return Ok(consumed);
+        }
+
+        // A few colors are supported without parentheses for historical reasons.
+        let Some((name, color)) = [
+            ("red", "\x1b[31m"),
+            ("green", "\x1b[32m"),
+            ("blue", "\x1b[34m"),
+            ("reset", COLOR_RESET),
+        ]
+        .into_iter()
+        .find(|(name, _)| placeholder[1..].starts_with(name.as_bytes())) else {
+            return Ok(0);
+        };
+        if ctx.color {
+            out.extend_from_slice(color.as_bytes());
+        }
+        Ok(1 + name.len())
+    }
+
+    /// Parse `%<(N)` and its variants to pad the next placeholder, returning the amount of bytes consumed.
+    fn parse_padding(&mut self, placeholder: &[u8]) -> usize {
+        let mut rest = &placeholder[1..];
+        let flush = match placeholder[0] {
+            b'<' => Flush::Right,
+            _ => match rest.first() {
+                Some(b'<') => {
+                    rest = &rest[1..];
+                    Flush::Both
+                }
+                Some(b'>') => {
+                    rest = &rest[1..];
+                    Flush::LeftAndSteal
+                }
+                _ => Flush::Left,
+            },
+        };
+        let to_column = match rest.strip_prefix(b"|") {
+            Some(after) => {
+                rest = after;
+                true
+            }
+            None => false,
+        };
+        let Some(args) = rest.strip_prefix(b"(") else {
+            return 0;
+        };
+        let Some(end) = args.find_byteset(b",)") else {
+            return 0;
+        };
+        // Limit the padding to avoid unbounded allocations.
+        let Some(width) = args[..end]
+            .to_str()
+            .ok()
+            .and_then(|width| width.trim().parse::<isize>().ok())
+            .filter(|width| *width > 0 && *width <= 16 * 1024)
+        else {
+            return 0;
+        };
+
+        let (truncate, end) = if args[end] == b',' {
+            let options = &args[end + 1..];
+            let Some(options_end) = options.find_byte(b')').filter(|end| *end != 0) else {
+                return 0;
+            };
+            let truncate = match &options[..options_end] {
+                b"trunc" => Truncate::Right,
+                b"ltrunc" => Truncate::Left,
+                b"mtrunc" => Truncate::Middle,
+                _ => return 0,
+            };
+            (truncate, end + 1 + options_end)
+        } else {
+            (Truncate::None, end)
+        };
+        self.padding = if to_column { -width } else { width };
+        self.flush = flush;
+        self.truncate = truncate;
+        placeholder.len() - args.len() + end + 1
+    }
+
+    /// Expand the part `part` of `signature`, like `n` for `%an`, returning the amount of bytes consumed.
+    fn person_part(&self, part: Option<u8>, signature: gix_actor::SignatureRef<'_>, out: &mut Vec<u8>) -> usize {
+        const PLACEHOLDER_LEN: usize = 2;
+        let ctx = self.ctx;
+        let Some(part) = part else {
+            return 0;
+        };
+        let signature = signature.trim();
+        let (name, email) = match part {
+            b'N' | b'E' | b'L' => ctx.mailmap.resolve(signature),
+            _ => (signature.name, signature.email),
+        };
+        match part {
+            b'n' | b'N' => out.extend_from_slice(name),
+            b'e' | b'E' => out.extend_from_slice(email),
+            b'l' | b'L' => out.extend_from_slice(email.split_str("@").next().unwrap_or_default()),
+            b't' => out.extend_from_slice(signature.time.split(' ').next().unwrap_or_default().as_bytes()),
+            b'd' | b'D' | b'r' | b'i' | b'I' | b's' => {
+                let mode = match part {
+                    b'd' => ctx.date.clone(),
+                    b'D' => DateFormat::Rfc2822.into(),
+                    b'r' => DateFormat::Relative.into(),
+                    b'i' => DateFormat::Iso8601.into(),
+                    b'I' => DateFormat::Iso8601Strict.into(),
+                    _ => DateFormat::Short.into(),
+                };
+                if let Ok(time) = signature.time() {
+                    out.extend_from_slice(mode.format(time, ctx.now).as_bytes());
+                }
+            }
+            _ => return 0,
+        }
+        PLACEHOLDER_LEN
+    }
+
+    /// Expand `%(trailers…)` with `args` being everything after `(trailers`, returning the amount of bytes of `args`
+    /// that were consumed, or `None` if the options were invalid.
+    fn trailers(&self, args: &[u8], out: &mut Vec<u8>) -> Option<usize> {
+        let mut options = TrailerOptions::default();
+        let mut rest = args;
+        if let Some(after_colon) = rest.strip_prefix(b":") {
+            rest = after_colon;
+            while !rest.starts_with(b")") {
+                let end = rest.find_byteset(b",)")?;
+                let (name, value) = match rest[..end].find_byte(b'=') {
+                    Some(pos) => (&rest[..pos], Some(&rest[pos + 1..end])),
+                    None => (&rest[..end], None),
+                };
+                match name {
+                    b"key" => {
+                        let key = value?;
+                        options.keys.push(key.strip_suffix(b":").unwrap_or(key).as_bstr());
+                        options.only = true;
+                    }
+                    b"separator" => options.separator = Some(expand_literals(value?)),
+                    b"key_value_separator" => options.key_value_separator = Some(expand_literals(value?)),
+                    b"only" => options.only = parse_bool(value)?,
+                    b"unfold" => options.unfold = parse_bool(value)?,
+                    b"keyonly" => options.key_only = parse_bool(value)?,
+                    b"valueonly" => options.value_only = parse_bool(value)?,
+                    _ => return None,
+                }
+                rest = &rest[end..];
+                rest = rest.strip_prefix(b",").unwrap_or(rest);
+            }
+        }
+        rest.strip_prefix(b")")?;
+
+        let block = trailer_block(&self.ctx.commit);
+        if options.is_default() {
+            out.extend_from_slice(block);
+            return Some(args.len() - rest.len() + 1);
+        }
+
+        let start = out.len();
+        for item in trailer_items(block, options.unfold) {
+            match item.token {
+                Some(token) => {
+                    if !options.keys.is_empty() && !options.keys.iter().any(|key| key.eq_ignore_ascii_case(token)) {
+                        continue;
+                    }
+                    if let Some(separator) = options.separator.as_ref().filter(|_| out.len() != start) {
+                        out.extend_from_slice(separator);
+                    }
+                    if !options.value_only {
+                        out.extend_from_slice(token);
+                    }
+                    if !options.key_only && !options.value_only {
+                        out.extend_from_slice(options.key_value_separator.as_deref().unwrap_or(b": ".as_slice()));
+                    }
+                    if !options.key_only {
+                        out.extend_from_slice(&item.value);
+                    }
+                }
+                None if options.only => continue,
+                None => {
+                    if let Some(separator) = options.separator.as_ref().filter(|_| out.len() != start) {
+                        out.extend_from_slice(separator);
+                    }
+                    out.extend_from_slice(&item.value);
+                }
+            }
+            if options.separator.is_none() {
+                out.push(b'\n');
+            }
+        }
+        Some(args.len() - rest.len() + 1)
+    }
+}
+
+/// Options for `%(trailers)`.
+#[derive(Default)]
+struct TrailerOptions<'a> {
+    /// Only show trailers with one of these tokens, compared case-insensitively.
+    keys: Vec<&'a BStr>,
+    /// Only show trailers, instead of all lines of the trailer block.
+    only: bool,
+    /// Join values that span multiple lines into a single line.
+    unfold: bool,
+    key_only: bool,
+    value_only: bool,
+    separator: Option<Vec<u8>>,
+    key_value_separator: Option<Vec<u8>>,
+}
+
+impl TrailerOptions<'_> {
+    /// Return `true` if no option is set, which shows the trailer block as is.
+    fn is_default(&self) -> bool {
+        self.keys.is_empty()
+            && !self.only
+            && !self.unfold
+            && !self.key_only
+            && !self.value_only
+            && self.separator.is_none()
+            && self.key_value_separator.is_none()
+    }
+}
+
+/// A line of the trailer block along with its continuation lines.
+struct TrailerItem<'a> {
+    /// The token of the trailer, or `None` if the line isn't a trailer.
+    token: Option<&'a BStr>,
+    /// The value of the trailer, or the whole line if it isn't one.
+    value: Vec<u8>,
+}
+
+/// Return the last paragraph of the message of `commit` if it contains trailers, or an empty slice.
+fn trailer_block<'a>(commit: &CommitRef<'a>) -> &'a [u8] {
+    if commit.message_trailers().next().is_none() {
+        return &[];
+    }
+    let message = commit.message.as_bytes();
+    let message = &message[..(message.trim_end().len() + 1).min(message.len())];
+    message.rfind(b"\n\n").map_or(&[], |pos| &message[pos + 2..])
+}
+
+/// Split the trailer `block` into its trailers and other lines, with lines starting with whitespace continuing the
+/// previous one. If `unfold` is `true`, continuation lines are joined with a space, like Git does it.
+fn trailer_items(block: &[u8], unfold: bool) -> Vec<TrailerItem<'_>> {
+    let mut items = Vec::<TrailerItem<'_>>::new();
+    for line in block.lines() {
+        let line = line.trim_end();
+        if let Some(item) = items
+            .last_mut()
+            .filter(|_| line.starts_with(b" ") || line.starts_with(b"\t"))
+        {
+            if unfold {
+                item.value.push(b' ');
+                item.value.extend_from_slice(line.trim_start());
+            } else {
+                item.value.push(b'\n');
+                item.value.extend_from_slice(line);
+            }
+            continue;
+        }
+        items.push(match trailer_separator(line) {
+            Some(pos) => TrailerItem {
+                token: Some(line[..pos].trim_end().as_bstr()),
+                value: line[pos + 1..].trim_start().to_vec(),
+            },
+            None => TrailerItem {
+                token: None,
+                value: line.to_vec(),
+            },
+        });
+    }
+    items
+}
+
+/// Return the position of the `:` separating the token of a trailer from its value in `line`, if it's a trailer.
+///
+/// Like in Git, tokens consist of alphanumeric characters and `-`, optionally followed by whitespace.
+fn trailer_separator(line: &[u8]) -> Option<usize> {
+    let token_len = line
+        .iter()
+        .take_while(|b| b.is_ascii_alphanumeric() || **b == b'-')
+        .count();
+    let pos = token_len
+        + line[token_len..]
+            .iter()
+            .take_while(|b| matches!(b, b' ' | b'\t'))
+            .count();
+    (token_len != 0 && line.get(pos) == Some(&b':')).then_some(pos)
+}
+
+fn parse_bool(value: Option<&[u8]>) -> Option<bool> {
+    match value {
+        None | Some(b"true" | b"yes" | b"on" | b"1") => Some(true),
+        Some(b"false" | b"no" | b"off" | b"0") => Some(false),
+        Some(_) => None,
+    }
+}
+
+/// Expand `%n` and `%xNN` in `value`, leaving everything else as is.
+fn expand_literals(value: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(pos) = rest.find_byte(b'%') {
+        out.extend_from_slice(&rest[..pos]);
+        rest = &rest[pos + 1..];
+        if let Some(after) = rest.strip_prefix(b"n") {
+            out.push(b'\n');
+            rest = after;
+        } else if let Some(byte) = rest.strip_prefix(b"x").and_then(parse_hex_byte) {
+            out.push(byte);
+            rest = &rest[3..];
+        } else {
+            out.push(b'%');
+        }
+    }
+    out.extend_from_slice(rest);
+    out
+}
+
+fn parse_hex_byte(input: &[u8]) -> Option<u8> {
+    let hex = input.get(..2)?;
+    u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()
+}
+
+/// Return the length of the ANSI escape sequence to change the display mode at the beginning of `input`, or `0`.
+fn ansi_sequence_len(input: &[u8]) -> usize {
+    let Some(rest) = input.strip_prefix(b"\x1b[") else {
+        return 0;
+    };
+    match rest.iter().position(|b| !(b.is_ascii_digit() || *b == b';')) {
+        Some(pos) if rest[pos] == b'm' => pos + 3,
+        _ => 0,
+    }
+}
+
+/// Return `true` if `c` is a control character, which takes no space on screen.
+fn is_control(c: char) -> bool {
+    c < ' ' || ('\x7f'..'\u{a0}').contains(&c)
+}
+
+/// Return the amount of columns `input` takes on screen, ignoring ANSI escape sequences.
+pub(super) fn display_width(mut input: &[u8]) -> usize {
+    let mut width = 0;
+    while !input.is_empty() {
+        let ansi_len = ansi_sequence_len(input);
+        if ansi_len != 0 {
+            input = &input[ansi_len..];
+            continue;
+        }
+        let (c, len) = crate::bstr::decode_utf8(input);
+        if !c.is_some_and(is_control) {
+            width += 1;
+        }
+        input = &input[len.max(1)..];
+    }
+    width
+}
+
+/// Replace the characters of `input` from column `pos` that take `width` columns with `replacement`, keeping all
+/// ANSI escape sequences and control characters.
+fn replace_columns(mut input: &[u8], pos: isize, width: isize, replacement: &str) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len());
+    let mut replacement = Some(replacement);
+    let mut column = 0isize;
+    while !input.is_empty() {
+        let ansi_len = ansi_sequence_len(input);
+        if ansi_len != 0 {
+            out.extend_from_slice(&input[..ansi_len]);
+            input = &input[ansi_len..];
+            continue;
+        }
+        let (c, len) = crate::bstr::decode_utf8(input);
+        let len = len.max(1);
+        if c.is_some_and(is_control) {
+            out.extend_from_slice(&input[..len]);
+        } else {
+            if column >= pos && column < pos + width {
+                if let Some(replacement) = replacement.take() {
+                    out.extend_from_slice(replacement.as_bytes());
+                }
+            } else {
+                out.extend_from_slice(&input[..len]);
+            }
+            column += 1;
+        }
+        input = &input[len..];
+    }
+    out
+}
+
+/// Return `true` if `line` is empty or only consists of whitespace.
+fn is_blank(line: &[u8]) -> bool {
+    line.iter().all(u8::is_ascii_whitespace)
+}
+
+/// Return `message` without its leading blank lines.
+pub(super) fn skip_blank_lines(mut message: &[u8]) -> &[u8] {
+    while let Some(line) = message.lines_with_terminator().next().filter(|line| is_blank(line)) {
+        message = &message[line.len()..];
+    }
+    message
+}
+
+/// Append the lines of the subject at the beginning of `message` to `out` if set, each without trailing whitespace and
+/// joined with `separator`, and return the rest of the message after the subject.
+pub(super) fn format_subject<'a>(mut message: &'a [u8], separator: &[u8], mut out: Option<&mut Vec<u8>>) -> &'a [u8] {
+    let mut first = true;
+    while let Some(line) = message.lines_with_terminator().next() {
+        message = &message[line.len()..];
+        if is_blank(line) {
+            break;
+        }
+        let Some(out) = out.as_deref_mut() else {
+            continue;
+        };
+        if !first {
+            out.extend_from_slice(separator);
+        }
+        out.extend_from_slice(line.trim_end_with(|c| c.is_ascii_whitespace()));
+        first = false;
+    }
+    message
+}
+
+/// Return the body of `message`, which is everything after the subject and the blank lines following it.
+fn body(message: &[u8]) -> &[u8] {
+    skip_blank_lines(format_subject(skip_blank_lines(message), b"", None))
+}
+
+/// Append `subject` to `out` so that it is suitable as file name, like `%f`.
+fn format_sanitized_subject(subject: &[u8], out: &mut Vec<u8>) {
+    let start = out.len();
+    // `0` means a title character was seen last, `1` that a separator is needed, and `2` that nothing was added yet.
+    let mut space = 2;
+    let mut bytes = subject.iter().copied().peekable();
+    while let Some(byte) = bytes.next() {
+        if byte.is_ascii_alphanumeric() || byte == b'.' || byte == b'_' {
+            if space == 1 {
+                out.push(b'-');
+            }
+            space = 0;
+            out.push(byte);
+            if byte == b'.' {
+                while bytes.next_if_eq(&b'.').is_some() {}
+            }
+        } else {
+            space |= 1;
+        }
+    }
+    let trimmed_len = out[start..].trim_end_with(|c| c == '.' || c == '-').len();
+    out.truncate(start + trimmed_len);
+}
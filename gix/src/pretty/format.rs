@@ -0,0 +1,83 @@
+use crate::bstr::{BStr, BString, ByteSlice};
+
+/// How to format commits, similar to the `--pretty` and `--format` options of `git log`.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// The hash and the subject on a single line.
+    Oneline,
+    /// The hash, the author and the subject.
+    Short,
+    /// The hash, the author, the author date and the message.
+    #[default]
+    Medium,
+    /// The hash, the author, the committer and the message.
+    Full,
+    /// The hash, the author, the author date, the committer, the commit date and the message.
+    Fuller,
+    /// The hash, the commit headers as stored and the message.
+    Raw,
+    /// A template with placeholders, like `%h %s`.
+    Custom {
+        /// The template to expand for each commit, with placeholders like `%H`, `%an` or `%(trailers)`.
+        ///
+        /// Unsupported placeholders are left as is. The ones starting with `%G` verify the signature of the commit
+        /// with the program configured in `gpg.program`, or `gpg`.
+        template: BString,
+        /// If `true`, each commit is followed by a newline like with `tformat:`, otherwise commits are separated by
+        /// newlines like with `format:`, so the last commit isn't followed by one.
+        terminate: bool,
+    },
+}
+
+/// The error returned by [`Format::from_bytes()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Unknown pretty format '{name}'")]
+    Unknown { name: BString },
+    #[error("The alias of pretty format '{name}' refers to itself")]
+    AliasLoop { name: BString },
+}
+
+impl Format {
+    /// Parse `name` like the `--pretty` option of `git log`, which is the name of a preset like `oneline` or `fuller`,
+    /// or a template prefixed with `format:` or `tformat:`. Names with a `%` or empty names are templates as well,
+    /// like with `tformat:`.
+    ///
+    /// Use [`Repository::pretty_format()`](crate::Repository::pretty_format()) to also resolve aliases configured in `pretty.<name>`.
+    pub fn from_bytes(name: &BStr) -> Result<Self, Error> {
+        if let Some(template) = name.strip_prefix(b"format:") {
+            return Ok(Format::Custom {
+                template: template.into(),
+                terminate: false,
+            });
+        }
+        if let Some(template) = name
+            .strip_prefix(b"tformat:")
+            .or_else(|| (name.is_empty() || name.contains(&b'%')).then_some(name.as_bytes()))
+        {
+            return Ok(Format::Custom {
+                template: template.into(),
+                terminate: true,
+            });
+        }
+        Ok(match name.as_bytes() {
+            b"oneline" => Format::Oneline,
+            b"short" => Format::Short,
+            b"medium" => Format::Medium,
+            b"full" => Format::Full,
+            b"fuller" => Format::Fuller,
+            b"raw" => Format::Raw,
+            _ => return Err(Error::Unknown { name: name.to_owned() }),
+        })
+    }
+
+    /// Return `true` if each commit is followed by a newline, instead of newlines separating commits.
+    pub fn is_terminated(&self) -> bool {
+        match self {
+            Format::Oneline => true,
+            Format::Short | Format::Medium | Format::Full | Format::Fuller | Format::Raw => false,
+            Format::Custom { terminate, .. } => *terminate,
+        }
+    }
+}
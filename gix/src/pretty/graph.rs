@@ -0,0 +1,639 @@
+use gix_hash::ObjectId;
+
+/// The colors of the branch lines, in the order they are assigned to new columns.
+const COLUMN_COLORS: [&str; 12] = [
+    "\x1b[31m",
+    "\x1b[32m",
+    "\x1b[33m",
+    "\x1b[34m",
+    "\x1b[35m",
+    "\x1b[36m",
+    "\x1b[1;31m",
+    "\x1b[1;32m",
+    "\x1b[1;33m",
+    "\x1b[1;34m",
+    "\x1b[1;35m",
+    "\x1b[1;36m",
+];
+/// A column color that means that no color is used.
+const NO_COLOR: usize = COLUMN_COLORS.len();
+const RESET: &str = "\x1b[m";
+
+/// The state of a [`Graph`], which is also the kind of line it will output next.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum State {
+    Padding,
+    Skip,
+    PreCommit,
+    Commit,
+    PostMerge,
+    Collapsing,
+}
+
+/// A branch line, leading to `commit`.
+#[derive(Debug, Copy, Clone)]
+struct Column {
+    commit: ObjectId,
+    color: usize,
+}
+
+/// Draw the history of commits as ASCII art like `git log --graph` does, one line at a time.
+///
+/// Commits are passed [one at a time](Self::update()) in the order they are shown, which must be a topological order,
+/// and each yields at least one [line](Self::next_line()) which contains the commit, along with lines to make room for it
+/// and lines to connect it to its parents.
+/// The output of each commit is finished once [`is_commit_finished()`](Self::is_commit_finished()) returns `true`, with
+/// [padding lines](Self::padding_line()) being available to show more information next to it.
+///
+/// All lines of a commit are padded to the same [`width()`](Self::width()).
+#[derive(Debug, Clone)]
+pub struct Graph {
+    /// The commit that is currently being shown.
+    commit: Option<ObjectId>,
+    /// The parents of `commit` that are connected to it.
+    parents: Vec<ObjectId>,
+    state: State,
+    prev_state: State,
+    /// The column of `commit`.
+    commit_index: usize,
+    prev_commit_index: usize,
+    /// Whether the merge line goes to the left (`0`) or the right (`1`) of the merge, or `-1` if not decided yet.
+    merge_layout: isize,
+    /// The amount of columns added by the current merge.
+    edges_added: isize,
+    prev_edges_added: isize,
+    /// The width of all lines of the current commit.
+    width: usize,
+    /// The amount of expansion rows shown so far before an octopus merge.
+    expansion_row: usize,
+    /// The branch lines before `commit` was shown.
+    columns: Vec<Column>,
+    /// The branch lines after `commit` was shown.
+    new_columns: Vec<Column>,
+    /// The index of the column in `new_columns` that each screen column is moving to, or `-1`.
+    mapping: Vec<isize>,
+    old_mapping: Vec<isize>,
+    /// The amount of entries in `mapping` that are in use.
+    mapping_size: usize,
+    /// The color of the next column.
+    default_column_color: usize,
+    color: bool,
+}
+
+impl Graph {
+    /// Create a new instance, which colors branch lines with ANSI escape codes if `color` is `true`.
+    pub fn new(color: bool) -> Self {
+        Graph {
+            commit: None,
+            parents: Vec::new(),
+            state: State::Padding,
+            prev_state: State::Padding,
+            commit_index: 0,
+            prev_commit_index: 0,
+            merge_layout: 0,
+            edges_added: 0,
+            prev_edges_added: 0,
+            width: 0,
+            expansion_row: 0,
+            columns: Vec::new(),
+            new_columns: Vec::new(),
+            mapping: Vec::new(),
+            old_mapping: Vec::new(),
+            mapping_size: 0,
+            default_column_color: COLUMN_COLORS.len() - 1,
+            color,
+        }
+    }
+
+    /// Show `commit` next, which will be connected to `parents`.
+    ///
+    /// `parents` should only contain the parents that are going to be shown, or whose branch lines should be drawn anyway.
+    /// If the output of the previous commit wasn't [finished](Self::is_commit_finished()), a line with `...` indicates
+    /// that a portion of the graph is missing.
+    pub fn update(&mut self, commit: ObjectId, parents: &[ObjectId]) {
+        self.commit = Some(commit);
+        self.parents.clear();
+        self.parents.extend_from_slice(parents);
+        self.prev_commit_index = self.commit_index;
+        self.update_columns();
+        self.expansion_row = 0;
+
+        // Don't update `prev_state` as no line for the current state was shown.
+        self.state = if self.state != State::Padding {
+            State::Skip
+        } else if self.needs_pre_commit_line() {
+            State::PreCommit
+        } else {
+            State::Commit
+        };
+    }
+
+    /// Return `true` if all lines needed to show the current commit and connect it to its parents were output.
+    pub fn is_commit_finished(&self) -> bool {
+        self.state == State::Padding
+    }
+
+    /// Return the width of each line of the current commit, in screen columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Append the next line of the graph to `out`, without trailing newline, and return `true` if it's the line
+    /// showing the commit itself.
+    ///
+    /// Once the commit is [finished](Self::is_commit_finished()), these are padding lines.
+    pub fn next_line(&mut self, out: &mut Vec<u8>) -> bool {
+        if self.commit.is_none() {
+            return false;
+        }
+        let mut line = Line { buf: out, width: 0 };
+        let mut is_commit_line = false;
+        match self.state {
+            State::Padding => self.output_padding_line(&mut line),
+            State::Skip => self.output_skip_line(&mut line),
+            State::PreCommit => self.output_pre_commit_line(&mut line),
+            State::Commit => {
+                self.output_commit_line(&mut line);
+                is_commit_line = true;
+            }
+            State::PostMerge => self.output_post_merge_line(&mut line),
+            State::Collapsing => self.output_collapsing_line(&mut line),
+        }
+        self.pad_horizontally(&mut line);
+        is_commit_line
+    }
+
+    /// Append a line to `out` that leaves all branch lines unchanged, without trailing newline, to show more information
+    /// next to the graph.
+    ///
+    /// If the current commit isn't shown yet, this is the [next line](Self::next_line()) instead, as otherwise the graph
+    /// would be broken.
+    pub fn padding_line(&mut self, out: &mut Vec<u8>) {
+        if self.state != State::Commit {
+            self.next_line(out);
+            return;
+        }
+
+        // The current commit is shown on the next line, so it may not be in any of the existing columns yet.
+        let mut line = Line { buf: out, width: 0 };
+        for col in &self.columns {
+            line.write_column(col, b'|');
+            if Some(col.commit) == self.commit && self.parents.len() > 2 {
+                line.add_chars(b' ', (self.parents.len() - 2) * self.expansion_row);
+            } else {
+                line.add_chars(b' ', 1);
+            }
+        }
+        self.pad_horizontally(&mut line);
+        self.prev_state = State::Padding;
+    }
+}
+
+/// Utilities
+impl Graph {
+    fn update_state(&mut self, state: State) {
+        self.prev_state = self.state;
+        self.state = state;
+    }
+
+    fn num_dashed_parents(&self) -> usize {
+        (self.parents.len() as isize + self.merge_layout - 3).max(0) as usize
+    }
+
+    fn num_expansion_rows(&self) -> usize {
+        self.num_dashed_parents() * 2
+    }
+
+    fn needs_pre_commit_line(&self) -> bool {
+        self.parents.len() >= 3
+            && self.commit_index + 1 < self.columns.len()
+            && self.expansion_row < self.num_expansion_rows()
+    }
+
+    fn is_mapping_correct(&self) -> bool {
+        // Entries one greater than their target are drawn as `/`, which is correct on the next line.
+        self.mapping[..self.mapping_size]
+            .iter()
+            .enumerate()
+            .all(|(idx, &target)| target < 0 || target as usize == idx / 2)
+    }
+
+    fn pad_horizontally(&self, line: &mut Line<'_>) {
+        if line.width < self.width {
+            line.add_chars(b' ', self.width - line.width);
+        }
+    }
+
+    fn current_column_color(&self) -> usize {
+        if self.color {
+            self.default_column_color
+        } else {
+            NO_COLOR
+        }
+    }
+
+    fn increment_column_color(&mut self) {
+        self.default_column_color = (self.default_column_color + 1) % COLUMN_COLORS.len();
+    }
+
+    fn find_commit_color(&self, commit: &ObjectId) -> usize {
+        self.columns
+            .iter()
+            .find(|col| col.commit == *commit)
+            .map_or_else(|| self.current_column_color(), |col| col.color)
+    }
+
+    fn find_new_column_by_commit(&self, commit: &ObjectId) -> Option<usize> {
+        self.new_columns.iter().position(|col| col.commit == *commit)
+    }
+
+    /// The commit in column `idx` of `columns`, where the current commit follows after all existing columns.
+    fn column_commit(&self, idx: usize) -> ObjectId {
+        self.columns
+            .get(idx)
+            .map_or_else(|| self.commit.expect("set when called"), |col| col.commit)
+    }
+}
+
+/// Column management
+impl Graph {
+    fn update_columns(&mut self) {
+        // `new_columns` describes the columns of the current commit, and the old columns are reused as storage
+        // for the columns of the commit after it.
+        std::mem::swap(&mut self.columns, &mut self.new_columns);
+        self.new_columns.clear();
+
+        let max_new_columns = self.columns.len() + self.parents.len();
+        self.mapping_size = 2 * max_new_columns;
+        if self.mapping.len() < self.mapping_size {
+            self.mapping.resize(self.mapping_size, -1);
+            self.old_mapping.resize(self.mapping_size, -1);
+        }
+        self.mapping[..self.mapping_size].fill(-1);
+
+        self.width = 0;
+        self.prev_edges_added = self.edges_added;
+        self.edges_added = 0;
+
+        // Some parents of the commit may already be in `columns`, and each commit must only have a single column.
+        // `mapping` tells where each existing branch line ends up after collapsing them.
+        let commit = self.commit.expect("set when called");
+        let mut seen_this = false;
+        let mut is_commit_in_columns = true;
+        for idx in 0..=self.columns.len() {
+            let col_commit = if idx == self.columns.len() {
+                if seen_this {
+                    break;
+                }
+                is_commit_in_columns = false;
+                commit
+            } else {
+                self.columns[idx].commit
+            };
+
+            if col_commit == commit {
+                seen_this = true;
+                self.commit_index = idx;
+                self.merge_layout = -1;
+                for parent_idx in 0..self.parents.len() {
+                    // Merges and the start of new childless columns change the color.
+                    if self.parents.len() > 1 || !is_commit_in_columns {
+                        self.increment_column_color();
+                    }
+                    let parent = self.parents[parent_idx];
+                    self.insert_into_new_columns(parent, Some(idx));
+                }
+                // The commit always takes up at least two screen columns.
+                if self.parents.is_empty() {
+                    self.width += 2;
+                }
+            } else {
+                self.insert_into_new_columns(col_commit, None);
+            }
+        }
+
+        while self.mapping_size > 1 && self.mapping[self.mapping_size - 1] < 0 {
+            self.mapping_size -= 1;
+        }
+    }
+
+    /// Add `commit` to the new columns unless it's already present, with `commit_column` being the column of the
+    /// current commit if `commit` is one of its parents.
+    fn insert_into_new_columns(&mut self, commit: ObjectId, commit_column: Option<usize>) {
+        let idx = match self.find_new_column_by_commit(&commit) {
+            Some(idx) => idx,
+            None => {
+                let color = self.find_commit_color(&commit);
+                self.new_columns.push(Column { commit, color });
+                self.new_columns.len() - 1
+            }
+        };
+
+        let mapping_idx = match commit_column {
+            Some(commit_column) if self.parents.len() > 1 && self.merge_layout == -1 => {
+                // The first parent of a merge decides whether the merge line goes left or right.
+                let dist = commit_column as isize - idx as isize;
+                let shift = if dist > 1 { 2 * dist - 3 } else { 1 };
+                self.merge_layout = if dist > 0 { 0 } else { 1 };
+                self.edges_added = self.parents.len() as isize + self.merge_layout - 2;
+                let mapping_idx = self.width as isize + (self.merge_layout - 1) * shift;
+                self.width += 2 * self.merge_layout as usize;
+                mapping_idx as usize
+            }
+            _ if self.edges_added > 0 && self.width >= 2 && self.mapping[self.width - 2] == idx as isize => {
+                // Edges of a merge that end up in the last existing column join it immediately.
+                self.edges_added = -1;
+                self.width - 2
+            }
+            _ => {
+                let mapping_idx = self.width;
+                self.width += 2;
+                mapping_idx
+            }
+        };
+        self.mapping[mapping_idx] = idx as isize;
+    }
+}
+
+/// Line output
+impl Graph {
+    fn output_padding_line(&mut self, line: &mut Line<'_>) {
+        for col in &self.new_columns {
+            line.write_column(col, b'|');
+            line.add_chars(b' ', 1);
+        }
+    }
+
+    fn output_skip_line(&mut self, line: &mut Line<'_>) {
+        line.add_str("...");
+        if self.needs_pre_commit_line() {
+            self.update_state(State::PreCommit);
+        } else {
+            self.update_state(State::Commit);
+        }
+    }
+
+    /// Make room for the edges of an octopus merge, with two lines for each parent after the second one.
+    fn output_pre_commit_line(&mut self, line: &mut Line<'_>) {
+        let mut seen_this = false;
+        for (idx, col) in self.columns.iter().enumerate() {
+            if Some(col.commit) == self.commit {
+                seen_this = true;
+                line.write_column(col, b'|');
+                line.add_chars(b' ', self.expansion_row);
+            } else if seen_this && self.expansion_row == 0 {
+                // Branch lines of a previous merge that were shown as `\` continue that way.
+                if self.prev_state == State::PostMerge && self.prev_commit_index < idx {
+                    line.write_column(col, b'\\');
+                } else {
+                    line.write_column(col, b'|');
+                }
+            } else if seen_this && self.expansion_row > 0 {
+                line.write_column(col, b'\\');
+            } else {
+                line.write_column(col, b'|');
+            }
+            line.add_chars(b' ', 1);
+        }
+
+        self.expansion_row += 1;
+        if !self.needs_pre_commit_line() {
+            self.update_state(State::Commit);
+        }
+    }
+
+    /// Draw the horizontal dashes of an octopus merge, colored like the column each of its edges collapses to.
+    fn draw_octopus_merge(&self, line: &mut Line<'_>) {
+        let dashed_parents = self.num_dashed_parents();
+        for idx in 0..dashed_parents {
+            let Some(col) = usize::try_from(self.mapping[(self.commit_index + idx + 2) * 2])
+                .ok()
+                .and_then(|target| self.new_columns.get(target))
+            else {
+                continue;
+            };
+            line.write_column(col, b'-');
+            line.write_column(col, if idx == dashed_parents - 1 { b'.' } else { b'-' });
+        }
+    }
+
+    fn output_commit_line(&mut self, line: &mut Line<'_>) {
+        // The current commit may not be in any of the existing columns yet if none of its children were shown.
+        let mut seen_this = false;
+        for idx in 0..=self.columns.len() {
+            if idx == self.columns.len() && seen_this {
+                break;
+            }
+            let col_commit = self.column_commit(idx);
+            let col = self.columns.get(idx).copied().unwrap_or(Column {
+                commit: col_commit,
+                color: NO_COLOR,
+            });
+
+            if Some(col_commit) == self.commit {
+                seen_this = true;
+                line.add_str("*");
+                if self.parents.len() > 2 {
+                    self.draw_octopus_merge(line);
+                }
+            } else if seen_this && self.edges_added > 1 {
+                line.write_column(&col, b'\\');
+            } else if seen_this && self.edges_added == 1 {
+                // Right-skewed 2-way merges and left-skewed 3-way merges have no pre-commit line, so continue the
+                // branch lines of a merge shown on the previous line as `\`.
+                if self.prev_state == State::PostMerge && self.prev_edges_added > 0 && self.prev_commit_index < idx {
+                    line.write_column(&col, b'\\');
+                } else {
+                    line.write_column(&col, b'|');
+                }
+            } else if self.prev_state == State::Collapsing
+                && self.old_mapping.get(2 * idx + 1).copied() == Some(idx as isize)
+                && self.mapping[2 * idx] < idx as isize
+            {
+                line.write_column(&col, b'/');
+            } else {
+                line.write_column(&col, b'|');
+            }
+            line.add_chars(b' ', 1);
+        }
+
+        if self.parents.len() > 1 {
+            self.update_state(State::PostMerge);
+        } else if self.is_mapping_correct() {
+            self.update_state(State::Padding);
+        } else {
+            self.update_state(State::Collapsing);
+        }
+    }
+
+    /// Draw the edges from a merge to its parents.
+    fn output_post_merge_line(&mut self, line: &mut Line<'_>) {
+        const MERGE_CHARS: [u8; 3] = [b'/', b'|', b'\\'];
+        let first_parent = self.parents.first().copied();
+        let mut parent_col = None::<Column>;
+        let mut seen_this = false;
+        for idx in 0..=self.columns.len() {
+            if idx == self.columns.len() && seen_this {
+                break;
+            }
+            let col_commit = self.column_commit(idx);
+            let col = self.columns.get(idx).copied().unwrap_or(Column {
+                commit: col_commit,
+                color: NO_COLOR,
+            });
+
+            if Some(col_commit) == self.commit {
+                // Use the columns of the parents to draw the edges to them.
+                seen_this = true;
+                let mut char_idx = self.merge_layout.max(0) as usize;
+                for (parent_idx, parent) in self.parents.iter().enumerate() {
+                    let par_column = self
+                        .find_new_column_by_commit(parent)
+                        .expect("each parent has a column");
+                    line.write_column(&self.new_columns[par_column], MERGE_CHARS[char_idx]);
+                    if char_idx == 2 {
+                        if self.edges_added > 0 || parent_idx + 1 < self.parents.len() {
+                            line.add_chars(b' ', 1);
+                        }
+                    } else {
+                        char_idx += 1;
+                    }
+                }
+                if self.edges_added == 0 {
+                    line.add_chars(b' ', 1);
+                }
+            } else if seen_this {
+                if self.edges_added > 0 {
+                    line.write_column(&col, b'\\');
+                } else {
+                    line.write_column(&col, b'|');
+                }
+                line.add_chars(b' ', 1);
+            } else {
+                line.write_column(&col, b'|');
+                if self.merge_layout != 0 || idx + 1 != self.commit_index {
+                    match &parent_col {
+                        Some(parent_col) => line.write_column(parent_col, b'_'),
+                        None => line.add_chars(b' ', 1),
+                    }
+                }
+            }
+
+            if Some(col_commit) == first_parent {
+                parent_col = Some(col);
+            }
+        }
+
+        if self.is_mapping_correct() {
+            self.update_state(State::Padding);
+        } else {
+            self.update_state(State::Collapsing);
+        }
+    }
+
+    /// Move branch lines to the left until they reach their column, crossing at most one other line at a time.
+    fn output_collapsing_line(&mut self, line: &mut Line<'_>) {
+        std::mem::swap(&mut self.mapping, &mut self.old_mapping);
+        self.mapping[..self.mapping_size].fill(-1);
+
+        let mut used_horizontal = false;
+        let mut horizontal_edge = None::<usize>;
+        let mut horizontal_edge_target = None::<isize>;
+        for idx in 0..self.mapping_size {
+            let target = self.old_mapping[idx];
+            if target < 0 {
+                continue;
+            }
+            // As the leftmost column is always inserted first, branch lines never need to move to the right.
+            let target_column = 2 * target as usize;
+            if target_column == idx {
+                // The branch line is already in its column.
+                self.mapping[idx] = target;
+            } else if self.mapping[idx - 1] < 0 {
+                // Nothing is to the left, so move there, and move horizontally unless another line already does.
+                self.mapping[idx - 1] = target;
+                if horizontal_edge.is_none() {
+                    horizontal_edge = Some(idx);
+                    horizontal_edge_target = Some(target);
+                    for horizontal in (target_column + 3..idx.saturating_sub(2)).step_by(2) {
+                        self.mapping[horizontal] = target;
+                    }
+                }
+            } else if self.mapping[idx - 1] == target {
+                // The branch line to the left leads to the same commit, so both are combined.
+            } else {
+                // Cross over the branch line to the left, which has an empty space to its left.
+                self.mapping[idx - 2] = target;
+                if horizontal_edge.is_none() {
+                    horizontal_edge = Some(idx - 1);
+                    horizontal_edge_target = Some(target);
+                    for horizontal in (target_column + 3..idx.saturating_sub(2)).step_by(2) {
+                        self.mapping[horizontal] = target;
+                    }
+                }
+            }
+        }
+
+        self.old_mapping[..self.mapping_size].copy_from_slice(&self.mapping[..self.mapping_size]);
+        if self.mapping[self.mapping_size - 1] < 0 {
+            self.mapping_size -= 1;
+        }
+
+        for idx in 0..self.mapping_size {
+            let target = self.mapping[idx];
+            if target < 0 {
+                line.add_chars(b' ', 1);
+                continue;
+            }
+            let col = self.new_columns[target as usize];
+            if 2 * target as usize == idx {
+                line.write_column(&col, b'|');
+            } else if Some(target) == horizontal_edge_target && Some(idx + 1) != horizontal_edge {
+                // Only the first segment of the horizontal line continues on the next line.
+                if idx != 2 * target as usize + 3 {
+                    self.mapping[idx] = -1;
+                }
+                used_horizontal = true;
+                line.write_column(&col, b'_');
+            } else {
+                if used_horizontal && horizontal_edge.is_some_and(|edge| idx < edge) {
+                    self.mapping[idx] = -1;
+                }
+                line.write_column(&col, b'/');
+            }
+        }
+
+        if self.is_mapping_correct() {
+            self.update_state(State::Padding);
+        }
+    }
+}
+
+/// A line of output along with its width in screen columns.
+struct Line<'a> {
+    buf: &'a mut Vec<u8>,
+    width: usize,
+}
+
+impl Line<'_> {
+    fn add_chars(&mut self, byte: u8, count: usize) {
+        self.buf.extend(std::iter::repeat_n(byte, count));
+        self.width += count;
+    }
+
+    fn add_str(&mut self, s: &str) {
+        self.buf.extend_from_slice(s.as_bytes());
+        self.width += s.len();
+    }
+
+    fn write_column(&mut self, col: &Column, byte: u8) {
+        let color = COLUMN_COLORS.get(col.color);
+        if let Some(color) = color {
+            self.buf.extend_from_slice(color.as_bytes());
+        }
+        self.add_chars(byte, 1);
+        if color.is_some() {
+            self.buf.extend_from_slice(RESET.as_bytes());
+        }
+    }
+}
@@ -0,0 +1,78 @@
+//! Format commits for display like `git log` does, using the presets and placeholders of its `--pretty` option,
+//! optionally next to a graph of the history like with `git log --graph`.
+//!
+//! Use [`Repository::pretty_printer()`](crate::Repository::pretty_printer()) to obtain a [`Printer`], and
+//! [`Repository::pretty_options()`](crate::Repository::pretty_options()) for options that respect the configuration
+//! of the repository.
+use crate::bstr::BStr;
+
+///
+pub mod date;
+pub use date::{DateFormat, DateMode};
+
+///
+pub mod format;
+pub use format::Format;
+
+mod graph;
+pub use graph::Graph;
+
+///
+pub mod printer;
+pub use printer::Printer;
+
+mod decorations;
+mod expand;
+mod preset;
+mod signature;
+
+/// Options to control how a [`Printer`] formats commits, similar to the options of `git log`.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Options {
+    /// The format to show commits in, like `--pretty`.
+    pub format: Format,
+    /// The format of dates in presets and in the `%ad` and `%cd` placeholders, like `--date`.
+    pub date: DateMode,
+    /// If `true`, show abbreviated hashes in the header line of presets, like `--abbrev-commit`.
+    pub abbrev_commit: bool,
+    /// If `true`, show the names of references pointing to each commit after its hash in presets, like `--decorate`.
+    ///
+    /// The `%d` and `%D` placeholders always show them.
+    pub decorate: bool,
+    /// If `true`, show the authors and committers of presets as rewritten by the mailmap of the repository, like `--use-mailmap`.
+    ///
+    /// The `%aN` and `%cN` placeholders and similar always use the mailmap.
+    pub mailmap: bool,
+    /// If `true`, show a graph of the history to the left of each commit, like `--graph`.
+    ///
+    /// This requires commits to be passed in a topological order, like [`Sorting::TopoOrder`](crate::revision::walk::Sorting::TopoOrder).
+    pub graph: bool,
+    /// If `true`, use ANSI escape codes to color the output, like `--color=always`.
+    pub color: bool,
+}
+
+/// A mailmap to use with signatures, which is empty unless the `mailmap` feature is enabled.
+#[derive(Default, Clone)]
+struct Mailmap {
+    #[cfg(feature = "mailmap")]
+    snapshot: Option<gix_mailmap::Snapshot>,
+}
+
+impl Mailmap {
+    /// Return the name and email of `signature`, rewritten by the mailmap if there is one.
+    fn resolve<'a>(&'a self, signature: gix_actor::SignatureRef<'a>) -> (&'a BStr, &'a BStr) {
+        let signature = signature.trim();
+        #[cfg(feature = "mailmap")]
+        if let Some(resolved) = self
+            .snapshot
+            .as_ref()
+            .and_then(|snapshot| snapshot.try_resolve_ref(signature))
+        {
+            return (
+                resolved.name.unwrap_or(signature.name),
+                resolved.email.unwrap_or(signature.email),
+            );
+        }
+        (signature.name, signature.email)
+    }
+}
@@ -0,0 +1,134 @@
+use super::{
+    expand::{display_width, format_subject, skip_blank_lines, Context},
+    Format,
+};
+use crate::bstr::ByteSlice;
+
+/// The amount of spaces to indent message lines with.
+const INDENT: usize = 4;
+/// The distance of tab stops in message lines of formats that expand tabs.
+const TAB_WIDTH: usize = 8;
+
+/// Append the headers and the message of the commit in `ctx` to `out` according to the preset `format`, like
+/// `pretty_print_commit()` of Git does.
+///
+/// If `mailmap` is `true`, authors and committers are rewritten by the mailmap.
+pub(super) fn write(format: &Format, ctx: &Context<'_>, mailmap: bool, out: &mut Vec<u8>) {
+    let start = out.len();
+    if *format == Format::Raw {
+        let header_end = ctx.data.find(b"\n\n").map_or(ctx.data.len(), |pos| pos + 1);
+        out.extend_from_slice(&ctx.data[..header_end]);
+    } else if *format != Format::Oneline {
+        if ctx.parents.len() > 1 {
+            out.extend_from_slice(b"Merge:");
+            for parent in ctx.parents {
+                out.push(b' ');
+                out.extend_from_slice(ctx.abbreviate(*parent).as_bytes());
+            }
+            out.push(b'\n');
+        }
+        if let Ok(author) = ctx.commit.author() {
+            write_person(format, ctx, "Author", author, mailmap, out);
+        }
+        if matches!(format, Format::Full | Format::Fuller) {
+            if let Ok(committer) = ctx.commit.committer() {
+                write_person(format, ctx, "Commit", committer, mailmap, out);
+            }
+        }
+    }
+
+    if *format != Format::Oneline {
+        out.push(b'\n');
+    }
+    let mut message = skip_blank_lines(ctx.commit.message.as_bytes());
+    if *format == Format::Oneline {
+        format_subject(message, b" ", Some(out));
+    } else {
+        let expand_tabs = matches!(format, Format::Medium | Format::Full | Format::Fuller);
+        let mut first = true;
+        while let Some(line) = message.lines_with_terminator().next() {
+            message = &message[line.len()..];
+            let line = line.trim_end_with(|c| c.is_ascii_whitespace());
+            if line.is_empty() {
+                if first {
+                    continue;
+                }
+                if *format == Format::Short {
+                    break;
+                }
+            }
+            first = false;
+            out.extend(std::iter::repeat_n(b' ', INDENT));
+            if expand_tabs {
+                write_with_expanded_tabs(line, out);
+            } else {
+                out.extend_from_slice(line);
+            }
+            out.push(b'\n');
+        }
+    }
+
+    let trimmed_len = out[start..].trim_end_with(|c| c.is_ascii_whitespace()).len();
+    out.truncate(start + trimmed_len);
+    if *format != Format::Oneline {
+        out.push(b'\n');
+    }
+}
+
+/// Write a header line like `Author: name <email>` for `signature`, followed by a line with its date if `format` shows it.
+fn write_person(
+    format: &Format,
+    ctx: &Context<'_>,
+    what: &str,
+    signature: gix_actor::SignatureRef<'_>,
+    mailmap: bool,
+    out: &mut Vec<u8>,
+) {
+    let signature = signature.trim();
+    let (name, email) = if mailmap {
+        ctx.mailmap.resolve(signature)
+    } else {
+        (signature.name, signature.email)
+    };
+    out.extend_from_slice(what.as_bytes());
+    out.extend_from_slice(b": ");
+    if *format == Format::Fuller {
+        out.extend_from_slice(b"    ");
+    }
+    out.extend_from_slice(name);
+    out.extend_from_slice(b" <");
+    out.extend_from_slice(email);
+    out.extend_from_slice(b">\n");
+
+    let date = || {
+        signature
+            .time()
+            .map(|time| ctx.date.format(time, ctx.now))
+            .unwrap_or_default()
+    };
+    match format {
+        Format::Medium => {
+            out.extend_from_slice(b"Date:   ");
+            out.extend_from_slice(date().as_bytes());
+            out.push(b'\n');
+        }
+        Format::Fuller => {
+            out.extend_from_slice(what.as_bytes());
+            out.extend_from_slice(b"Date: ");
+            out.extend_from_slice(date().as_bytes());
+            out.push(b'\n');
+        }
+        _ => {}
+    }
+}
+
+/// Append `line` to `out` with each tab replaced by spaces up to the next tab stop.
+fn write_with_expanded_tabs(mut line: &[u8], out: &mut Vec<u8>) {
+    while let Some(tab) = line.find_byte(b'\t') {
+        out.extend_from_slice(&line[..tab]);
+        let width = display_width(&line[..tab]);
+        out.extend(std::iter::repeat_n(b' ', TAB_WIDTH - width % TAB_WIDTH));
+        line = &line[tab + 1..];
+    }
+    out.extend_from_slice(line);
+}
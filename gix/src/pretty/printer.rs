@@ -0,0 +1,247 @@
+use std::time::SystemTime;
+
+use gix_hash::ObjectId;
+
+use super::{
+    decorations::{Decorations, Separators},
+    expand, preset, Format, Graph, Mailmap, Options,
+};
+use crate::bstr::{BString, ByteSlice};
+
+/// The error returned by [`Printer::write_commit()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Decode(#[from] gix_object::decode::Error),
+    #[error("Could not parse color '{spec}' of the pretty format")]
+    Color { spec: BString },
+}
+
+/// Write commits one after another like `git log` does, as obtained by [`Repository::pretty_printer()`](crate::Repository::pretty_printer()).
+///
+/// Commits are separated or terminated by newlines as needed by the [format](Options::format), and shown along with
+/// a graph of the history if [enabled](Options::graph).
+pub struct Printer<'repo> {
+    repo: &'repo crate::Repository,
+    options: Options,
+    mailmap: Mailmap,
+    decorations: Decorations,
+    graph: Option<Graph>,
+    now: SystemTime,
+    /// If `true`, a commit was written already.
+    shown_one: bool,
+    /// If `true`, the message of the last commit didn't end with a newline.
+    missing_newline: bool,
+}
+
+/// Lifecycle
+impl<'repo> Printer<'repo> {
+    pub(crate) fn new(
+        repo: &'repo crate::Repository,
+        options: Options,
+    ) -> Result<Self, crate::repository::pretty_printer::Error> {
+        let template = match &options.format {
+            Format::Custom { template, .. } => Some(template.as_bytes()),
+            _ => None,
+        };
+        // Placeholders like `%aN` use the mailmap, and `%d` the decorations, no matter what the options say.
+        let mailmap = Mailmap {
+            #[cfg(feature = "mailmap")]
+            snapshot: (options.mailmap || template.is_some()).then(|| repo.open_mailmap()),
+        };
+        let uses_decorations = template.is_some_and(|template| {
+            ["%", "%-", "%+", "% "].iter().any(|prefix| {
+                template.contains_str(format!("{prefix}d")) || template.contains_str(format!("{prefix}D"))
+            })
+        });
+        let decorations = if options.decorate || uses_decorations {
+            Decorations::load(repo)?
+        } else {
+            Decorations::default()
+        };
+        Ok(Printer {
+            repo,
+            graph: options.graph.then(|| Graph::new(options.color)),
+            options,
+            mailmap,
+            decorations,
+            now: SystemTime::now(),
+            shown_one: false,
+            missing_newline: false,
+        })
+    }
+}
+
+impl Printer<'_> {
+    /// Return the options used for formatting.
+    pub fn options(&self) -> &Options {
+        &self.options
+    }
+
+    /// Write `commit` to `out`, connected to `parents` in the graph and listing them in placeholders like `%P`.
+    ///
+    /// `parents` are typically the parents as seen by a [revision walk](crate::revision::walk::Info), which
+    /// differ from the actual parents of `commit` if history was simplified.
+    pub fn write_commit(
+        &mut self,
+        commit: &crate::Commit<'_>,
+        parents: &[ObjectId],
+        out: &mut dyn std::io::Write,
+    ) -> Result<(), Error> {
+        let Printer {
+            repo,
+            options,
+            mailmap,
+            decorations,
+            graph,
+            now,
+            shown_one,
+            missing_newline,
+        } = self;
+        if let Some(graph) = graph.as_mut() {
+            graph.update(commit.id, parents);
+        }
+        let ctx = expand::Context {
+            repo,
+            id: commit.id,
+            data: &commit.data,
+            commit: gix_object::CommitRef::from_bytes(&commit.data)?,
+            parents,
+            mailmap,
+            decorations,
+            date: &options.date,
+            now: *now,
+            color: options.color,
+            graph_width: graph.as_ref().map_or(0, Graph::width),
+        };
+
+        let mut buf = Vec::new();
+        let use_terminator = options.format.is_terminated();
+        if *shown_one && !use_terminator {
+            // Separate commits by a line that continues the graph, unless the previous one had no newline.
+            if !*missing_newline {
+                show_padding(graph.as_mut(), &mut buf);
+            }
+            buf.push(b'\n');
+        }
+        *shown_one = true;
+        show_commit(graph.as_mut(), &mut buf);
+
+        if !matches!(options.format, Format::Custom { .. }) {
+            let (color_commit, color_reset) = if options.color {
+                ("\x1b[33m", "\x1b[m")
+            } else {
+                ("", "")
+            };
+            buf.extend_from_slice(color_commit.as_bytes());
+            if options.format != Format::Oneline {
+                buf.extend_from_slice(b"commit ");
+            }
+            if options.abbrev_commit {
+                buf.extend_from_slice(ctx.abbreviate(commit.id).as_bytes());
+            } else {
+                buf.extend_from_slice(commit.id.to_hex().to_string().as_bytes());
+            }
+            buf.extend_from_slice(color_reset.as_bytes());
+            if options.decorate {
+                ctx.decorations
+                    .write(&commit.id, options.color, &Separators::PARENTHESES, &mut buf);
+            }
+            if options.format == Format::Oneline {
+                buf.push(b' ');
+            } else {
+                buf.push(b'\n');
+                show_oneline(graph.as_mut(), &mut buf);
+            }
+        }
+
+        let mut message = Vec::new();
+        let is_empty_format = match &options.format {
+            Format::Custom { template, .. } => {
+                expand::expand(template, &ctx, &mut message)?;
+                template.is_empty()
+            }
+            preset => {
+                preset::write(preset, &ctx, options.mailmap, &mut message);
+                false
+            }
+        };
+        *missing_newline = message.last() != Some(&b'\n');
+        show_commit_message(graph.as_mut(), &message, &mut buf);
+        if use_terminator && !is_empty_format {
+            if !*missing_newline {
+                show_padding(graph.as_mut(), &mut buf);
+            }
+            buf.push(b'\n');
+        }
+        out.write_all(&buf)?;
+        Ok(())
+    }
+}
+
+/// Write the lines of the graph up to and including the line of the current commit, without trailing newline.
+fn show_commit(graph: Option<&mut Graph>, out: &mut Vec<u8>) {
+    let Some(graph) = graph else {
+        return;
+    };
+    if graph.is_commit_finished() {
+        graph.padding_line(out);
+        return;
+    }
+    while !graph.is_commit_finished() {
+        if graph.next_line(out) {
+            break;
+        }
+        out.push(b'\n');
+    }
+}
+
+/// Write the next line of the graph.
+fn show_oneline(graph: Option<&mut Graph>, out: &mut Vec<u8>) {
+    if let Some(graph) = graph {
+        graph.next_line(out);
+    }
+}
+
+/// Write a line of the graph that doesn't change any branch lines.
+fn show_padding(graph: Option<&mut Graph>, out: &mut Vec<u8>) {
+    if let Some(graph) = graph {
+        graph.padding_line(out);
+    }
+}
+
+/// Write `message` with the graph in front of each line but the first, followed by the remaining lines of the graph
+/// for the current commit.
+fn show_commit_message(graph: Option<&mut Graph>, message: &[u8], out: &mut Vec<u8>) {
+    let Some(graph) = graph else {
+        out.extend_from_slice(message);
+        return;
+    };
+    let mut lines = message.lines_with_terminator().peekable();
+    while let Some(line) = lines.next() {
+        out.extend_from_slice(line);
+        if line.ends_with(b"\n") && lines.peek().is_some() {
+            graph.next_line(out);
+        }
+    }
+
+    if !graph.is_commit_finished() {
+        let newline_terminated = message.ends_with(b"\n");
+        if !newline_terminated {
+            out.push(b'\n');
+        }
+        loop {
+            graph.next_line(out);
+            if graph.is_commit_finished() {
+                break;
+            }
+            out.push(b'\n');
+        }
+        if newline_terminated {
+            out.push(b'\n');
+        }
+    }
+}
@@ -0,0 +1,218 @@
+use crate::bstr::{BString, ByteSlice};
+
+/// The trust in the key that made a good signature, as reported by `gpg`.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(not(feature = "command"), allow(dead_code))]
+pub(super) enum Trust {
+    Undefined,
+    Never,
+    Marginal,
+    Fully,
+    Ultimate,
+}
+
+impl Trust {
+    #[cfg(feature = "command")]
+    fn from_status(level: &[u8]) -> Option<Self> {
+        Some(match level {
+            b"UNDEFINED" => Trust::Undefined,
+            b"NEVER" => Trust::Never,
+            b"MARGINAL" => Trust::Marginal,
+            b"FULLY" => Trust::Fully,
+            b"ULTIMATE" => Trust::Ultimate,
+            _ => return None,
+        })
+    }
+
+    /// Return the name of the trust level as shown by `%GT`.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Trust::Undefined => "undefined",
+            Trust::Never => "never",
+            Trust::Marginal => "marginal",
+            Trust::Fully => "fully",
+            Trust::Ultimate => "ultimate",
+        }
+    }
+}
+
+/// The outcome of verifying the signature of a commit, like `struct signature_check` in Git.
+#[derive(Debug)]
+pub(super) struct Check {
+    /// `G` for a good signature, `B` for a bad one, `X` for a good signature that expired, `Y` for a good signature made
+    /// by an expired key, `R` for a good signature made by a revoked key, `E` if it couldn't be checked and `N` if
+    /// there is no signature.
+    pub result: u8,
+    /// The trust in the key of a good signature, if known.
+    pub trust: Option<Trust>,
+    /// The user id of the signer.
+    pub signer: Option<BString>,
+    /// The id of the key used for signing.
+    pub key: Option<BString>,
+    /// The fingerprint of the key used for signing.
+    pub fingerprint: Option<BString>,
+    /// The fingerprint of the primary key of the key used for signing.
+    pub primary_key_fingerprint: Option<BString>,
+    /// Everything the verification program wrote to `stderr`, meant for humans.
+    pub output: Vec<u8>,
+}
+
+impl Check {
+    fn new(result: u8) -> Self {
+        Check {
+            result,
+            trust: None,
+            signer: None,
+            key: None,
+            fingerprint: None,
+            primary_key_fingerprint: None,
+            output: Vec::new(),
+        }
+    }
+
+    fn error(message: impl std::fmt::Display) -> Self {
+        Check {
+            output: format!("error: {message}\n").into_bytes(),
+            ..Check::new(b'E')
+        }
+    }
+
+    /// Return the character to show for `%G?`, which takes the trust in the key of good signatures into account.
+    pub fn status(&self) -> u8 {
+        match (self.result, self.trust) {
+            (b'G', Some(Trust::Undefined | Trust::Never)) => b'U',
+            (result, _) => result,
+        }
+    }
+}
+
+/// Verify the signature of the commit stored as `data` with the program in `gpg.program`, or `gpg` if it's unset.
+///
+/// Signatures that aren't OpenPGP signatures, and signatures that can't be verified because the program couldn't be run
+/// or the `command` feature is disabled, are reported with result `E`.
+pub(super) fn verify(repo: &crate::Repository, data: &[u8]) -> Check {
+    let (signature, signed_data) = match gix_object::CommitRefIter::signature(data) {
+        Ok(Some(signature)) => signature,
+        Ok(None) | Err(_) => return Check::new(b'N'),
+    };
+    if !(signature.starts_with(b"-----BEGIN PGP SIGNATURE-----")
+        || signature.starts_with(b"-----BEGIN PGP MESSAGE-----"))
+    {
+        return Check::error("only OpenPGP signatures can be verified");
+    }
+    run_gpg(repo, signature.as_bytes(), &signed_data.to_bstring())
+}
+
+#[cfg(not(feature = "command"))]
+fn run_gpg(_repo: &crate::Repository, _signature: &[u8], _payload: &[u8]) -> Check {
+    Check::error("signatures can only be verified with the `command` feature")
+}
+
+/// Run `gpg` to verify `signature` of `payload` and parse its output.
+#[cfg(feature = "command")]
+fn run_gpg(repo: &crate::Repository, signature: &[u8], payload: &[u8]) -> Check {
+    use std::{io::Write, process::Stdio};
+
+    use crate::config::tree::Gpg;
+
+    // `gpg` reads the signed data from `stdin`, so the signature has to be passed as file.
+    let mut signature_file = match gix_tempfile::new(
+        std::env::temp_dir(),
+        gix_tempfile::ContainingDirectory::Exists,
+        gix_tempfile::AutoRemove::Tempfile,
+    ) {
+        Ok(file) => file,
+        Err(err) => return Check::error(format_args!("could not create temporary file: {err}")),
+    };
+    let signature_path = match signature_file
+        .write_all(signature)
+        .and_then(|()| signature_file.with_mut(|file| file.path().to_owned()))
+    {
+        Ok(path) => path,
+        Err(err) => return Check::error(format_args!("could not write signature to temporary file: {err}")),
+    };
+
+    let program = repo
+        .config_snapshot()
+        .trusted_program(&Gpg::PROGRAM)
+        .map_or_else(|| "gpg".into(), std::borrow::Cow::into_owned);
+    let mut cmd: std::process::Command = gix_command::prepare(program)
+        .arg("--status-fd=1")
+        .arg("--verify")
+        .arg(signature_path)
+        .arg("-")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .into();
+    gix_trace::debug!(cmd = ?cmd);
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(err) => return Check::error(format_args!("could not run {cmd:?} to verify signature: {err}")),
+    };
+    let input = child.stdin.take();
+    let output = std::thread::scope(|scope| {
+        scope.spawn(move || {
+            if let Some(mut input) = input {
+                // Failing to write is fine, as the program will tell us whatever went wrong.
+                input.write_all(payload).ok();
+            }
+        });
+        child.wait_with_output()
+    });
+    let output = match output {
+        Ok(output) => output,
+        Err(err) => return Check::error(format_args!("could not communicate with {cmd:?}: {err}")),
+    };
+
+    let mut check = parse_status(&output.stdout);
+    check.output = output.stderr;
+    check
+}
+
+/// Parse the `--status-fd` output of `gpg` like `parse_gpg_output()` in Git.
+#[cfg(feature = "command")]
+fn parse_status(status: &[u8]) -> Check {
+    let mut check = Check::new(b'N');
+    let mut seen_signature = false;
+    for line in status.lines() {
+        let Some(line) = line.strip_prefix(b"[GNUPG:] ") else {
+            continue;
+        };
+        if let Some(level) = line.strip_prefix(b"TRUST_") {
+            let level = level.split_str(" ").next().unwrap_or_default();
+            check.trust = Trust::from_status(level);
+            continue;
+        }
+
+        let (keyword, args) = line.split_once_str(" ").unwrap_or((line, &[]));
+        let result = match keyword {
+            b"GOODSIG" => b'G',
+            b"BADSIG" => b'B',
+            b"ERRSIG" => b'E',
+            b"EXPSIG" => b'X',
+            b"EXPKEYSIG" => b'Y',
+            b"REVKEYSIG" => b'R',
+            b"VALIDSIG" => {
+                let mut fields = args.split_str(" ");
+                check.fingerprint = fields.next().map(Into::into);
+                // The fingerprint of the primary key is the 10th field, if present.
+                check.primary_key_fingerprint = fields.nth(8).map(Into::into);
+                continue;
+            }
+            _ => continue,
+        };
+        // More than one signature can't be represented, so we consider it an error just like Git does.
+        if seen_signature {
+            return Check::new(b'E');
+        }
+        seen_signature = true;
+        check.result = result;
+        let (key, signer) = args.split_once_str(" ").unwrap_or((args, &[]));
+        check.key = Some(key.into());
+        if result != b'E' {
+            check.signer = Some(signer.into());
+        }
+    }
+    check
+}
@@ -0,0 +1,75 @@
+use gix_packetline::blocking_io::StreamingPeekableIter;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    receive_pack::{Command, Error},
+};
+
+/// Everything the client sends before the pack.
+pub(crate) struct Request {
+    pub commands: Vec<Command>,
+    pub capabilities: Vec<BString>,
+    pub push_options: Vec<BString>,
+}
+
+impl Request {
+    pub fn has_capability(&self, name: &str) -> bool {
+        self.capabilities.iter().any(|c| c == name)
+    }
+}
+
+/// Read the command list, and push-options if negotiated, from `lines`, leaving the underlying reader at the beginning of the pack.
+pub(crate) fn read_request<T: std::io::Read>(
+    lines: &mut StreamingPeekableIter<T>,
+    object_hash: gix_hash::Kind,
+) -> Result<Request, Error> {
+    let mut out = Request {
+        commands: Vec::new(),
+        capabilities: Vec::new(),
+        push_options: Vec::new(),
+    };
+    while let Some(line) = lines.read_line() {
+        let line = line??;
+        let Some(line) = line.as_bstr() else { continue };
+        let line = line.strip_suffix(b"\n").unwrap_or(line).as_bstr();
+        let line = match line.find_byte(0) {
+            Some(pos) if out.commands.is_empty() => {
+                out.capabilities = line[pos + 1..]
+                    .split_str(" ")
+                    .filter(|c| !c.is_empty())
+                    .map(Into::into)
+                    .collect();
+                line[..pos].as_bstr()
+            }
+            _ => line,
+        };
+        if line.starts_with(b"shallow ") {
+            return Err(Error::ShallowUnsupported { line: line.into() });
+        }
+        out.commands.push(parse_command(line, object_hash)?);
+    }
+
+    if out.has_capability("push-options") && !out.commands.is_empty() {
+        lines.reset();
+        while let Some(line) = lines.read_line() {
+            let line = line??;
+            if let Some(option) = line.as_bstr() {
+                out.push_options
+                    .push(option.strip_suffix(b"\n").unwrap_or(option).as_bstr().to_owned());
+            }
+        }
+    }
+    Ok(out)
+}
+
+fn parse_command(line: &BStr, object_hash: gix_hash::Kind) -> Result<Command, Error> {
+    let malformed = || Error::MalformedCommand { line: line.into() };
+    let hex_len = object_hash.len_in_hex();
+    if line.len() < hex_len * 2 + 3 || line[hex_len] != b' ' || line[hex_len * 2 + 1] != b' ' {
+        return Err(malformed());
+    }
+    let old_id = gix_hash::ObjectId::from_hex(&line[..hex_len]).map_err(|_| malformed())?;
+    let new_id = gix_hash::ObjectId::from_hex(&line[hex_len + 1..hex_len * 2 + 1]).map_err(|_| malformed())?;
+    let name = gix_ref::FullName::try_from(line[hex_len * 2 + 2..].as_bstr())?;
+    Ok(Command { old_id, new_id, name })
+}
@@ -0,0 +1,526 @@
+use std::{ffi::OsString, io, sync::atomic::AtomicBool};
+
+use gix_object::{Exists, Find};
+use gix_packetline::{blocking_io::encode, Channel, PacketLineRef};
+use gix_ref::{
+    transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog},
+    Target,
+};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    config::{cache::util::ApplyLeniencyDefault, tree::Receive},
+    receive_pack::{
+        advertise, command, hook, quarantine::Quarantine, Command, Error, Options, Outcome, Status, CAPABILITIES,
+    },
+};
+
+impl crate::Repository {
+    /// Write the reference advertisement that `git receive-pack` sends as very first message of a push to `out`.
+    ///
+    /// All references are listed along with the object they point to, with symbolic references being followed, and
+    /// the first line also carries our [capabilities](CAPABILITIES). If there is no reference,
+    /// the capabilities are advertised on a placeholder line as mandated by the protocol.
+    pub fn receive_pack_advertisement(&self, out: &mut dyn io::Write) -> Result<(), advertise::Error> {
+        let capabilities = self.receive_pack_capabilities();
+        let mut capabilities = Some(capabilities.as_bstr());
+        for reference in self.references()?.all()? {
+            let mut reference = reference?;
+            // Just like `git`, do not advertise what doesn't point to an object.
+            let Ok(id) = reference.follow_to_object() else {
+                continue;
+            };
+            write_advertisement_line(&mut *out, &id, reference.name().as_bstr(), capabilities.take())?;
+        }
+        if capabilities.is_some() {
+            write_advertisement_line(
+                &mut *out,
+                &self.object_hash().null(),
+                "capabilities^{}".into(),
+                capabilities.take(),
+            )?;
+        }
+        encode::flush_to_write(out)?;
+        Ok(())
+    }
+
+    /// Receive a push from `input`, the stream sent by the client in response to the
+    /// [advertisement](Self::receive_pack_advertisement()), and write our reply to `out`.
+    ///
+    /// The pack sent by the client is written into a quarantine directory with `progress` and can be interrupted
+    /// with `should_interrupt`. It's only moved into the object database if all objects it contains can be connected to
+    /// objects we already have, and if the `pre-receive` hook agrees with all commands.
+    /// Then each command is checked for compliance with `receive.denyDeletes`, `receive.denyNonFastForwards` and
+    /// `receive.denyCurrentBranch`, and passed to the `update` hook if there is one, before references are changed.
+    /// If the client asked for an `atomic` push, all references are changed in one transaction or not at all.
+    /// Finally, the `post-receive` hook learns about all changed references.
+    ///
+    /// Hooks are run with `options`, and their output is sent to the client if side-bands were negotiated, or to `stderr`
+    /// otherwise.
+    /// Hooks that can't be run are treated as if they declined.
+    pub fn receive_pack(
+        &self,
+        input: &mut dyn io::BufRead,
+        out: &mut dyn io::Write,
+        progress: &mut dyn crate::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: Options,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::receive_pack()");
+        let mut lines = gix_packetline::blocking_io::StreamingPeekableIter::new(
+            input,
+            &[PacketLineRef::Flush],
+            options.trace_packetlines,
+        );
+        let request = command::read_request(&mut lines, self.object_hash())?;
+        let input = lines.into_inner();
+
+        let report_status = request.has_capability("report-status");
+        let side_band = if request.has_capability("side-band-64k") {
+            Some(SideBand::Large)
+        } else if request.has_capability("side-band") {
+            Some(SideBand::Small)
+        } else {
+            None
+        };
+        let atomic = request.has_capability("atomic");
+        let command::Request {
+            commands,
+            capabilities,
+            push_options,
+        } = request;
+
+        let mut outcome = Outcome {
+            capabilities,
+            push_options,
+            write_pack_bundle: None,
+            unpack_error: None,
+            commands: Vec::new(),
+        };
+        if commands.is_empty() {
+            return Ok(outcome);
+        }
+
+        let mut statuses: Vec<Option<Status>> = vec![None; commands.len()];
+        let mut quarantine = None;
+        if commands.iter().any(|cmd| !cmd.is_delete()) {
+            let objects_dir = self.objects.store_ref().path();
+            let q = Quarantine::new(objects_dir).map_err(|source| Error::CreateQuarantine {
+                objects_dir: objects_dir.to_owned(),
+                source,
+            })?;
+            match gix_pack::Bundle::write_to_directory(
+                input,
+                Some(&q.pack_dir()),
+                progress,
+                should_interrupt,
+                Some(self.objects.clone()),
+                gix_pack::bundle::write::Options {
+                    thread_limit: options.thread_limit,
+                    iteration_mode: gix_pack::data::input::Mode::Verify,
+                    index_version: Default::default(),
+                    object_hash: self.object_hash(),
+                },
+            ) {
+                Ok(bundle) => {
+                    if !is_connected(&q, bundle.index_path.as_deref(), &commands, self.object_hash())? {
+                        reject_pending(&mut statuses, "missing necessary objects");
+                    }
+                    outcome.write_pack_bundle = Some(bundle);
+                    quarantine = Some(q);
+                }
+                Err(err) => {
+                    outcome.unpack_error = Some(err.to_string().into());
+                    reject_pending(&mut statuses, "unpacker error");
+                }
+            }
+        }
+
+        let git_dir = std::path::absolute(self.git_dir())?;
+        let hooks_dir = if options.run_hooks {
+            Some(hook::directory(self)?)
+        } else {
+            None
+        };
+        let find_hook = |name: &str| hooks_dir.as_deref().and_then(|dir| hook::find(dir, name));
+        let mut push_option_env = vec![(
+            OsString::from("GIT_PUSH_OPTION_COUNT"),
+            OsString::from(outcome.push_options.len().to_string()),
+        )];
+        for (idx, option) in outcome.push_options.iter().enumerate() {
+            push_option_env.push((
+                format!("GIT_PUSH_OPTION_{idx}").into(),
+                gix_path::from_bstr(option.as_bstr()).into_owned().into(),
+            ));
+        }
+
+        if let Some(pre_receive) = find_hook("pre-receive").filter(|_| statuses.iter().any(Option::is_none)) {
+            let mut env = push_option_env.clone();
+            if let Some(q) = &quarantine {
+                env.extend([
+                    ("GIT_QUARANTINE_PATH".into(), q.path().into()),
+                    ("GIT_OBJECT_DIRECTORY".into(), q.path().into()),
+                    ("GIT_ALTERNATE_OBJECT_DIRECTORIES".into(), q.objects_dir().into()),
+                ]);
+            }
+            let pending = commands.iter().zip(statuses.iter()).filter(|(_, s)| s.is_none());
+            let hook_out = run_hook(&pre_receive, &git_dir, &[], &env, &hook_input(pending.map(|(c, _)| c)));
+            send_messages(&mut *out, side_band, &hook_out.messages)?;
+            if !hook_out.success {
+                reject_pending(&mut statuses, "pre-receive hook declined");
+            }
+        }
+
+        if statuses.iter().any(Option::is_none) {
+            if let Some(q) = quarantine.take() {
+                let (objects_dir, quarantined_pack_dir) = (q.objects_dir().join("pack"), q.pack_dir());
+                q.migrate().map_err(Error::MigrateQuarantine)?;
+                if let Some(bundle) = outcome.write_pack_bundle.as_mut() {
+                    for path in [&mut bundle.index_path, &mut bundle.data_path, &mut bundle.keep_path]
+                        .into_iter()
+                        .flatten()
+                    {
+                        if let Ok(file_name) = path.strip_prefix(&quarantined_pack_dir) {
+                            *path = objects_dir.join(file_name);
+                        }
+                    }
+                }
+            }
+        }
+        drop(quarantine);
+
+        self.check_commands(&commands, &mut statuses)?;
+        if let Some(update) = find_hook("update") {
+            for (cmd, status) in commands.iter().zip(statuses.iter_mut()) {
+                if status.is_some() {
+                    continue;
+                }
+                let args = [
+                    gix_path::from_bstr(cmd.name.as_bstr()).into_owned().into(),
+                    cmd.old_id.to_string().into(),
+                    cmd.new_id.to_string().into(),
+                ];
+                let hook_out = run_hook(&update, &git_dir, &args, &push_option_env, &[]);
+                send_messages(&mut *out, side_band, &hook_out.messages)?;
+                if !hook_out.success {
+                    *status = Some(Status::rejected("hook declined"));
+                }
+            }
+        }
+
+        if atomic && statuses.iter().any(Option::is_some) {
+            reject_pending(&mut statuses, "atomic push failure");
+        }
+        let edits: Vec<_> = commands
+            .iter()
+            .zip(statuses.iter())
+            .enumerate()
+            .filter(|(_, (_, status))| status.is_none())
+            .map(|(idx, (cmd, _))| (idx, to_edit(cmd)))
+            .collect();
+        if atomic {
+            if let Err(_err) = self.edit_references(edits.iter().map(|(_, edit)| edit.clone())) {
+                gix_trace::warn!("atomic reference transaction failed: {_err}");
+                reject_pending(&mut statuses, "failed to update refs");
+            }
+        } else {
+            for (idx, edit) in edits {
+                if let Err(_err) = self.edit_reference(edit) {
+                    gix_trace::warn!("reference transaction failed: {_err}");
+                    statuses[idx] = Some(Status::rejected("failed to update ref"));
+                }
+            }
+        }
+        let statuses: Vec<_> = statuses.into_iter().map(|s| s.unwrap_or(Status::Ok)).collect();
+
+        if let Some(path) = outcome.write_pack_bundle.as_mut().and_then(|b| b.keep_path.take()) {
+            match std::fs::remove_file(&path) {
+                Err(err) if err.kind() != io::ErrorKind::NotFound => {
+                    return Err(Error::RemovePackKeepFile { path, source: err })
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(post_receive) = find_hook("post-receive") {
+            let updated = commands.iter().zip(statuses.iter()).filter(|(_, s)| s.is_ok());
+            let stdin = hook_input(updated.map(|(c, _)| c));
+            if !stdin.is_empty() {
+                let hook_out = run_hook(&post_receive, &git_dir, &[], &push_option_env, &stdin);
+                send_messages(&mut *out, side_band, &hook_out.messages)?;
+            }
+        }
+
+        outcome.commands = commands.into_iter().zip(statuses).collect();
+        if report_status {
+            let mut report = Vec::new();
+            match &outcome.unpack_error {
+                None => encode::text_to_write(b"unpack ok", &mut report)?,
+                Some(err) => encode::text_to_write(format!("unpack {err}").as_bytes(), &mut report)?,
+            };
+            for (cmd, status) in &outcome.commands {
+                let mut line = BString::default();
+                match status {
+                    Status::Ok => {
+                        line.extend_from_slice(b"ok ");
+                        line.extend_from_slice(cmd.name.as_bstr());
+                    }
+                    Status::Rejected { reason } => {
+                        line.extend_from_slice(b"ng ");
+                        line.extend_from_slice(cmd.name.as_bstr());
+                        line.push(b' ');
+                        line.extend_from_slice(reason);
+                    }
+                }
+                encode::text_to_write(&line, &mut report)?;
+            }
+            encode::flush_to_write(&mut report)?;
+            match side_band {
+                Some(side_band) => {
+                    for chunk in report.chunks(side_band.max_data_len()) {
+                        encode::band_to_write(Channel::Data, chunk, &mut *out)?;
+                    }
+                }
+                None => out.write_all(&report)?,
+            }
+        }
+        if side_band.is_some() {
+            encode::flush_to_write(&mut *out)?;
+        }
+        out.flush()?;
+        Ok(outcome)
+    }
+
+    fn receive_pack_capabilities(&self) -> BString {
+        let mut out: BString = CAPABILITIES.join(" ").into();
+        out.extend_from_slice(
+            format!(
+                " object-format={} agent=git/{}",
+                self.object_hash(),
+                crate::env::agent()
+            )
+            .as_bytes(),
+        );
+        out
+    }
+
+    /// Reject pending `commands` in `statuses` if they violate our configuration.
+    fn check_commands(&self, commands: &[Command], statuses: &mut [Option<Status>]) -> Result<(), Error> {
+        let lenient = self.config.lenient_config;
+        let deny_deletes = self
+            .config
+            .resolved
+            .boolean(&Receive::DENY_DELETES)
+            .map(|res| Receive::DENY_DELETES.enrich_error(res))
+            .transpose()
+            .with_lenient_default(lenient)?
+            .unwrap_or_default();
+        let deny_non_fast_forwards = self
+            .config
+            .resolved
+            .boolean(&Receive::DENY_NON_FAST_FORWARDS)
+            .map(|res| Receive::DENY_NON_FAST_FORWARDS.enrich_error(res))
+            .transpose()
+            .with_lenient_default(lenient)?
+            .unwrap_or_default();
+        let deny_current_branch = self
+            .config
+            .resolved
+            .string(&Receive::DENY_CURRENT_BRANCH)
+            .is_none_or(|value| {
+                !matches!(
+                    value.as_ref().as_bytes(),
+                    b"ignore" | b"warn" | b"false" | b"no" | b"off"
+                )
+            });
+        let checked_out_branch = if self.is_bare() { None } else { self.head_name()? };
+
+        for (cmd, status) in commands.iter().zip(statuses.iter_mut()) {
+            if status.is_some() {
+                continue;
+            }
+            let is_checked_out = deny_current_branch && checked_out_branch.as_ref() == Some(&cmd.name);
+            *status = if !cmd.name.as_bstr().starts_with(b"refs/") {
+                Some(Status::rejected("funny refname"))
+            } else if cmd.is_delete() && is_checked_out {
+                Some(Status::rejected("deletion of the current branch prohibited"))
+            } else if cmd.is_delete() && deny_deletes {
+                Some(Status::rejected("deletion prohibited"))
+            } else if is_checked_out {
+                Some(Status::rejected("branch is currently checked out"))
+            } else if deny_non_fast_forwards
+                && !cmd.is_create()
+                && !cmd.is_delete()
+                && self.merge_base(cmd.old_id, cmd.new_id).ok().map(crate::Id::detach) != Some(cmd.old_id)
+            {
+                Some(Status::rejected("non-fast-forward"))
+            } else {
+                None
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+enum SideBand {
+    /// `side-band`, with packet lines of up to 1000 bytes.
+    Small,
+    /// `side-band-64k`, with packet lines of up to 65520 bytes.
+    Large,
+}
+
+impl SideBand {
+    /// The amount of bytes we can send per packet line, considering the length prefix and the band.
+    fn max_data_len(&self) -> usize {
+        match self {
+            SideBand::Small => 1000 - 5,
+            SideBand::Large => 65520 - 5,
+        }
+    }
+}
+
+fn write_advertisement_line(
+    out: &mut dyn io::Write,
+    id: &gix_hash::oid,
+    name: &BStr,
+    capabilities: Option<&BStr>,
+) -> io::Result<()> {
+    let mut line: BString = id.to_hex().to_string().into();
+    line.push(b' ');
+    line.extend_from_slice(name);
+    if let Some(capabilities) = capabilities {
+        line.push(0);
+        line.extend_from_slice(capabilities);
+    }
+    encode::text_to_write(&line, out).map(|_| ())
+}
+
+/// Return `true` if all objects in the pack at `index_path` only refer to objects that exist, and if the new
+/// value of each command can be found.
+///
+/// As objects that were already present are assumed to be connected, this is equivalent to the closure of the new objects.
+fn is_connected(
+    quarantine: &Quarantine,
+    index_path: Option<&std::path::Path>,
+    commands: &[Command],
+    object_hash: gix_hash::Kind,
+) -> Result<bool, Error> {
+    let objects = gix_odb::at(quarantine.path())?;
+    if commands
+        .iter()
+        .any(|cmd| !cmd.is_delete() && !objects.exists(&cmd.new_id))
+    {
+        return Ok(false);
+    }
+    let Some(index_path) = index_path else {
+        return Ok(true);
+    };
+    let index = gix_pack::index::File::at(index_path, object_hash).map_err(io::Error::other)?;
+    let mut buf = Vec::new();
+    for entry in index.iter() {
+        let Some(data) = objects.try_find(&entry.oid, &mut buf).map_err(io::Error::other)? else {
+            return Ok(false);
+        };
+        let is_complete = match data.kind {
+            gix_object::Kind::Commit => {
+                let mut commit = gix_object::CommitRefIter::from_bytes(data.data);
+                commit.tree_id().is_ok_and(|tree| objects.exists(&tree))
+                    && commit.parent_ids().all(|id| objects.exists(&id))
+            }
+            gix_object::Kind::Tree => gix_object::TreeRefIter::from_bytes(data.data, data.hash_kind)
+                .all(|entry| entry.is_ok_and(|entry| entry.mode.is_commit() || objects.exists(entry.oid))),
+            gix_object::Kind::Tag => gix_object::TagRefIter::from_bytes(data.data)
+                .target_id()
+                .is_ok_and(|id| objects.exists(&id)),
+            gix_object::Kind::Blob => true,
+        };
+        if !is_complete {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+fn to_edit(cmd: &Command) -> RefEdit {
+    let previous = || PreviousValue::MustExistAndMatch(Target::Object(cmd.old_id));
+    RefEdit {
+        change: if cmd.is_delete() {
+            Change::Delete {
+                expected: if cmd.is_create() {
+                    PreviousValue::MustExist
+                } else {
+                    previous()
+                },
+                log: RefLog::AndReference,
+            }
+        } else {
+            Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: "push".into(),
+                },
+                expected: if cmd.is_create() {
+                    PreviousValue::MustNotExist
+                } else {
+                    previous()
+                },
+                new: Target::Object(cmd.new_id),
+            }
+        },
+        name: cmd.name.clone(),
+        deref: false,
+    }
+}
+
+fn reject_pending(statuses: &mut [Option<Status>], reason: &str) {
+    for status in statuses.iter_mut().filter(|s| s.is_none()) {
+        *status = Some(Status::rejected(reason));
+    }
+}
+
+/// Run the hook at `path` like [`hook::run()`], but treat a hook that can't be run like one that declined, just like `git` does,
+/// so the client still receives a status for each command.
+fn run_hook(
+    path: &std::path::Path,
+    git_dir: &std::path::Path,
+    args: &[OsString],
+    env: &[(OsString, OsString)],
+    stdin: &[u8],
+) -> hook::Output {
+    hook::run(path, git_dir, args, env, stdin).unwrap_or_else(|err| {
+        gix_trace::warn!("{err}");
+        hook::Output {
+            success: false,
+            messages: format!("error: {err}\n").into_bytes(),
+        }
+    })
+}
+
+/// Produce the lines hooks like `pre-receive` expect on `stdin`.
+fn hook_input<'a>(commands: impl Iterator<Item = &'a Command>) -> Vec<u8> {
+    let mut out = Vec::new();
+    for cmd in commands {
+        out.extend_from_slice(format!("{} {} ", cmd.old_id, cmd.new_id).as_bytes());
+        out.extend_from_slice(cmd.name.as_bstr());
+        out.push(b'\n');
+    }
+    out
+}
+
+/// Forward `messages` of hooks to the client if we can, or to our own `stderr` otherwise.
+fn send_messages(out: &mut dyn io::Write, side_band: Option<SideBand>, messages: &[u8]) -> io::Result<()> {
+    if messages.is_empty() {
+        return Ok(());
+    }
+    match side_band {
+        Some(side_band) => {
+            for chunk in messages.chunks(side_band.max_data_len()) {
+                encode::band_to_write(Channel::Progress, chunk, &mut *out)?;
+            }
+        }
+        None => io::Write::write_all(&mut io::stderr(), messages)?,
+    }
+    Ok(())
+}
@@ -0,0 +1,103 @@
+use std::{
+    ffi::OsString,
+    io::Write,
+    path::{Path, PathBuf},
+    process::Stdio,
+};
+
+use crate::config::tree::Core;
+
+/// The error returned when locating or running hooks.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not interpolate the path configured in 'core.hooksPath'")]
+    HooksPath(#[from] gix_config::path::interpolate::Error),
+    #[error("Could not spawn hook at '{}'", path.display())]
+    Spawn { path: PathBuf, source: std::io::Error },
+    #[error("Could not communicate with hook at '{}'", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+}
+
+/// What a hook said and how it exited.
+pub(crate) struct Output {
+    /// `true` if the hook exited with status 0.
+    pub success: bool,
+    /// Everything the hook wrote to `stdout`, followed by everything it wrote to `stderr`.
+    pub messages: Vec<u8>,
+}
+
+/// Return the directory containing hooks, as configured by `core.hooksPath`, or `hooks` in the common directory of `repo`.
+///
+/// Relative configured paths are relative to the working tree, or the `.git` directory in bare repositories, just like in `git`.
+pub(crate) fn directory(repo: &crate::Repository) -> Result<PathBuf, Error> {
+    Ok(match repo.config.trusted_file_path(&Core::HOOKS_PATH).transpose()? {
+        Some(path) if path.is_relative() => repo.workdir().unwrap_or_else(|| repo.git_dir()).join(path),
+        Some(path) => path.into_owned(),
+        None => repo.common_dir().join("hooks"),
+    })
+}
+
+/// Return the path to the hook with `name` in `hooks_dir` if it exists and is executable.
+pub(crate) fn find(hooks_dir: &Path, name: &str) -> Option<PathBuf> {
+    let path = hooks_dir.join(name);
+    let metadata = path.metadata().ok()?;
+    (metadata.is_file() && gix_fs::is_executable(&metadata)).then_some(path)
+}
+
+/// Run the hook at `path` with `args` and `env`, from within `git_dir`, feeding `stdin` to it and collecting all of its output.
+pub(crate) fn run(
+    path: &Path,
+    git_dir: &Path,
+    args: &[OsString],
+    env: &[(OsString, OsString)],
+    stdin: &[u8],
+) -> Result<Output, Error> {
+    let mut cmd: std::process::Command = gix_command::prepare(path)
+        .with_context(gix_command::Context {
+            git_dir: Some(git_dir.to_owned()),
+            ..Default::default()
+        })
+        .args(args.iter().cloned())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .into();
+    for (key, value) in env {
+        cmd.env(key, value);
+    }
+    cmd.current_dir(git_dir);
+    gix_trace::debug!(cmd = ?cmd);
+    let mut child = cmd.spawn().map_err(|source| Error::Spawn {
+        path: path.to_owned(),
+        source,
+    })?;
+
+    let io_err = |source| Error::Io {
+        path: path.to_owned(),
+        source,
+    };
+    let input = child.stdin.take();
+    // Write the input while the output is drained so hooks writing a lot can't block on a full pipe while we wait for them to read.
+    let (output, written) = std::thread::scope(|scope| {
+        let writer = scope.spawn(move || match input {
+            Some(mut input) => input.write_all(stdin),
+            None => Ok(()),
+        });
+        let output = child.wait_with_output();
+        (output, writer.join().expect("writer thread doesn't panic"))
+    });
+    let output = output.map_err(io_err)?;
+    if let Err(err) = written {
+        // Hooks are free to not read their input, just like in `git`.
+        if err.kind() != std::io::ErrorKind::BrokenPipe {
+            return Err(io_err(err));
+        }
+    }
+    let mut messages = output.stdout;
+    messages.extend_from_slice(&output.stderr);
+    Ok(Output {
+        success: output.status.success(),
+        messages,
+    })
+}
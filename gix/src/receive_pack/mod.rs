@@ -0,0 +1,172 @@
+//! Accept pushes the way `git receive-pack` does.
+//!
+//! The server side of a push consists of two phases:
+//!
+//! * [advertise](crate::Repository::receive_pack_advertisement()) all references along with the capabilities we support.
+//! * [receive](crate::Repository::receive_pack()) the update commands and the pack sent by the client. The pack is written
+//!   into a quarantine directory which is only migrated into the object database once it was found to be complete and
+//!   the `pre-receive` hook accepted it. Then `update` hooks are run and references are changed, and finally the
+//!   `post-receive` hook is informed about what changed.
+//!
+//! The reply to the client uses the `report-status` format, optionally multiplexed over side-bands.
+use gix_hash::ObjectId;
+use gix_ref::FullName;
+
+use crate::bstr::BString;
+
+mod command;
+mod function;
+///
+pub mod hook;
+mod quarantine;
+
+/// The capabilities we advertise, without `object-format` and `agent` which depend on the repository and version respectively.
+pub const CAPABILITIES: &[&str] = &[
+    "report-status",
+    "delete-refs",
+    "side-band-64k",
+    "quiet",
+    "atomic",
+    "ofs-delta",
+    "push-options",
+];
+
+/// A single reference update as requested by the client, i.e. `<old-id> <new-id> <refname>`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Command {
+    /// The value the client believes the reference has right now, or the null-id if the reference is supposed to be created.
+    pub old_id: ObjectId,
+    /// The value the reference should have after the update, or the null-id if the reference is supposed to be deleted.
+    pub new_id: ObjectId,
+    /// The name of the reference to change.
+    pub name: FullName,
+}
+
+impl Command {
+    /// Return `true` if this command would delete the reference.
+    pub fn is_delete(&self) -> bool {
+        self.new_id.is_null()
+    }
+
+    /// Return `true` if this command would create the reference.
+    pub fn is_create(&self) -> bool {
+        self.old_id.is_null()
+    }
+}
+
+/// The status of a [`Command`] after it was processed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Status {
+    /// The reference was changed as requested.
+    Ok,
+    /// The reference was left untouched for the given `reason`, which is also sent to the client.
+    Rejected {
+        /// A single-line and human-readable reason for why the update was rejected, like `non-fast-forward`.
+        reason: BString,
+    },
+}
+
+impl Status {
+    pub(crate) fn rejected(reason: impl Into<BString>) -> Self {
+        Status::Rejected { reason: reason.into() }
+    }
+
+    /// Return `true` if the reference was updated.
+    pub fn is_ok(&self) -> bool {
+        matches!(self, Status::Ok)
+    }
+}
+
+/// Options for use in [`Repository::receive_pack()`](crate::Repository::receive_pack()).
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// If `true` (default), run the `pre-receive`, `update` and `post-receive` hooks if they are present and executable.
+    pub run_hooks: bool,
+    /// The amount of threads to use when indexing the received pack, or `None` to use all logical cores.
+    pub thread_limit: Option<usize>,
+    /// If `true`, trace all packet lines sent and received using `gix-trace`.
+    pub trace_packetlines: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            run_hooks: true,
+            thread_limit: None,
+            trace_packetlines: false,
+        }
+    }
+}
+
+/// The outcome of [`Repository::receive_pack()`](crate::Repository::receive_pack()).
+#[derive(Debug)]
+pub struct Outcome {
+    /// The capabilities the client asked for.
+    pub capabilities: Vec<BString>,
+    /// The push-options the client sent, if it negotiated `push-options`.
+    pub push_options: Vec<BString>,
+    /// The pack that was received and migrated into the object database, if there was one.
+    ///
+    /// Its `keep_path` is always `None` as the `.keep` file was removed once the references were updated.
+    pub write_pack_bundle: Option<gix_pack::bundle::write::Outcome>,
+    /// If the pack could not be received or indexed, this is the reason which was also reported to the client.
+    pub unpack_error: Option<BString>,
+    /// All commands in the order they were received, along with what happened to them.
+    pub commands: Vec<(Command, Status)>,
+}
+
+///
+pub mod advertise {
+    /// The error returned by [`Repository::receive_pack_advertisement()`](crate::Repository::receive_pack_advertisement()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+        #[error(transparent)]
+        IterInit(#[from] crate::reference::iter::init::Error),
+        #[error(transparent)]
+        Iter(#[from] crate::reference::iter::Error),
+        #[error(transparent)]
+        IterItem(#[from] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error(transparent)]
+        FollowToObject(#[from] crate::reference::follow::to_object::Error),
+    }
+}
+
+/// The error returned by [`Repository::receive_pack()`](crate::Repository::receive_pack()).
+///
+/// Note that problems with the received pack or with individual commands are not errors, but are reported to the client
+/// and in the [`Outcome`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error("Could not decode a packet line sent by the client")]
+    PacketlineDecode(#[from] gix_packetline::decode::Error),
+    #[error("Received an invalid command line: {line:?}")]
+    MalformedCommand { line: BString },
+    #[error("Shallow updates are not supported, but client sent {line:?}")]
+    ShallowUnsupported { line: BString },
+    #[error("Invalid reference name in command line")]
+    InvalidRefName(#[from] gix_validate::reference::name::Error),
+    #[error("Could not create quarantine directory in '{}'", objects_dir.display())]
+    CreateQuarantine {
+        objects_dir: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Could not move received objects out of quarantine")]
+    MigrateQuarantine(#[source] std::io::Error),
+    #[error("Could not remove the '.keep' file at '{}'", path.display())]
+    RemovePackKeepFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error(transparent)]
+    Hook(#[from] hook::Error),
+    #[error(transparent)]
+    ConfigBoolean(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    FindHead(#[from] crate::reference::find::existing::Error),
+}
@@ -0,0 +1,105 @@
+use std::path::{Path, PathBuf};
+
+/// A temporary object directory inside the object database that holds all received objects until they are deemed acceptable.
+///
+/// It's removed with all of its contents on drop unless it was [migrated](Self::migrate()).
+pub(crate) struct Quarantine {
+    path: PathBuf,
+    objects_dir: PathBuf,
+}
+
+impl Quarantine {
+    /// Create a new quarantine directory within `objects_dir`, with `objects_dir` being configured as alternate so that
+    /// objects in the quarantine can refer to objects that are already present.
+    pub fn new(objects_dir: &Path) -> std::io::Result<Self> {
+        let objects_dir = std::path::absolute(objects_dir)?;
+        let mut attempt = 0;
+        let path = loop {
+            let nanos = std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |d| d.subsec_nanos());
+            let path = objects_dir.join(format!("incoming-{:x}{:x}", std::process::id(), nanos ^ attempt));
+            match std::fs::create_dir(&path) {
+                Ok(()) => break path,
+                Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists && attempt < 16 => attempt += 1,
+                Err(err) => return Err(err),
+            }
+        };
+        let quarantine = Quarantine { path, objects_dir };
+        std::fs::create_dir(quarantine.pack_dir())?;
+        std::fs::create_dir(quarantine.path.join("info"))?;
+        std::fs::write(
+            quarantine.path.join("info").join("alternates"),
+            gix_path::into_bstr(quarantine.objects_dir.as_path()).as_ref(),
+        )?;
+        Ok(quarantine)
+    }
+
+    /// The path to the quarantine directory, which is an object directory.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The object directory we are going to migrate to.
+    pub fn objects_dir(&self) -> &Path {
+        &self.objects_dir
+    }
+
+    /// The directory into which packs should be written.
+    pub fn pack_dir(&self) -> PathBuf {
+        self.path.join("pack")
+    }
+
+    /// Move all objects into the actual object database and delete the quarantine directory.
+    ///
+    /// Packs are moved with their `.keep` file first, and their `.idx` last to not make them visible before they are complete.
+    /// Objects that already exist in the destination are skipped.
+    pub fn migrate(self) -> std::io::Result<()> {
+        let pack_dir = self.pack_dir();
+        let mut pack_files: Vec<_> = std::fs::read_dir(&pack_dir)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<Result<_, _>>()?;
+        pack_files.sort_by_key(|path| match path.extension().and_then(|ext| ext.to_str()) {
+            Some("keep") => 0,
+            Some("pack") => 1,
+            Some("idx") => 3,
+            _ => 2,
+        });
+        let dest_pack_dir = self.objects_dir.join("pack");
+        for path in pack_files {
+            let file_name = path.file_name().expect("read_dir entries have a name");
+            move_if_missing(&path, &dest_pack_dir.join(file_name))?;
+        }
+
+        for entry in std::fs::read_dir(&self.path)? {
+            let entry = entry?;
+            let name = entry.file_name();
+            let is_loose_object_dir = name.len() == 2
+                && name.to_str().is_some_and(|n| n.bytes().all(|b| b.is_ascii_hexdigit()))
+                && entry.file_type()?.is_dir();
+            if !is_loose_object_dir {
+                continue;
+            }
+            let dest_dir = self.objects_dir.join(&name);
+            std::fs::create_dir_all(&dest_dir)?;
+            for object in std::fs::read_dir(entry.path())? {
+                let object = object?;
+                move_if_missing(&object.path(), &dest_dir.join(object.file_name()))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn move_if_missing(source: &Path, destination: &Path) -> std::io::Result<()> {
+    if destination.exists() {
+        return Ok(());
+    }
+    std::fs::rename(source, destination)
+}
+
+impl Drop for Quarantine {
+    fn drop(&mut self) {
+        std::fs::remove_dir_all(&self.path).ok();
+    }
+}
@@ -40,3 +40,45 @@ pub(crate) fn commit_type_by_parents(count: usize) -> Option<&'static str> {
         _two_or_more => "merge",
     })
 }
+
+///
+pub mod expire {
+    /// Options for use in [`Repository::reflog_expire()`](crate::Repository::reflog_expire()), typically obtained
+    /// with [`Repository::reflog_expire_options()`](crate::Repository::reflog_expire_options()).
+    #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+    pub struct Options {
+        /// Remove all entries created before this time, or keep them if `None`.
+        pub expire: Option<gix_date::Time>,
+        /// Remove entries created before this time if the commits they refer to aren't reachable from the
+        /// current value of the reference, or keep them if `None`.
+        ///
+        /// For `HEAD`, commits reachable from any reference are considered reachable.
+        pub expire_unreachable: Option<gix_date::Time>,
+    }
+
+    /// The error returned by [`Repository::reflog_expire()`](crate::Repository::reflog_expire()) and related methods.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        LockTimeoutConfiguration(#[from] crate::config::lock_timeout::Error),
+        #[error(transparent)]
+        Expire(#[from] gix_ref::file::log::expire::Error),
+        #[error(transparent)]
+        ReadLog(#[from] gix_ref::file::log::Error),
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::Error),
+        #[error(transparent)]
+        FindHead(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        IterInit(#[from] crate::reference::iter::init::Error),
+        #[error(transparent)]
+        Iter(#[from] crate::reference::iter::Error),
+        #[error(transparent)]
+        IterItem(Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error(transparent)]
+        OpenCommitGraph(#[from] crate::repository::commit_graph_if_enabled::Error),
+        #[error(transparent)]
+        LookupCommit(#[from] gix_revwalk::graph::get_or_insert_default::Error),
+    }
+}
@@ -99,6 +99,12 @@ impl<'repo> Remote<'repo> {
                     .then(|| self.repo.ssh_connect_options())
                     .transpose()?
                     .unwrap_or_default(),
+                #[cfg(feature = "blocking-network-client")]
+                remote_helper: gix_protocol::transport::client::blocking_io::remote_helper::Options {
+                    program: None,
+                    remote_name: self.name().map(|name| name.as_bstr().to_owned()),
+                    git_dir: Some(self.repo.git_dir().to_owned()),
+                },
                 trace: self.repo.config.trace_packet(),
             },
         )
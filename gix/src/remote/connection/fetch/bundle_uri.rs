@@ -0,0 +1,544 @@
+use std::{
+    io::BufRead,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use gix_features::progress::DynNestedProgress;
+use gix_protocol::bundle_uri::{Header, List, Mode};
+use gix_ref::{
+    transaction::{LogChange, PreviousValue, RefEdit, RefLog},
+    Target,
+};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    remote::fetch::outcome,
+    Repository,
+};
+
+/// The error returned when downloading or applying a bundle, or when downloading a pack advertised as packfile URI.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Url(#[from] gix_url::parse::Error),
+    #[error("Cannot download '{uri}' as the '{scheme}' scheme isn't supported for downloads")]
+    UnsupportedScheme { uri: BString, scheme: gix_url::Scheme },
+    #[error("Could not open '{}' for reading", path.display())]
+    Open {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[cfg(any(
+        feature = "blocking-http-transport-reqwest",
+        feature = "blocking-http-transport-curl"
+    ))]
+    #[error(transparent)]
+    TransportOptions(#[from] crate::config::transport::Error),
+    #[cfg(any(
+        feature = "blocking-http-transport-reqwest",
+        feature = "blocking-http-transport-curl"
+    ))]
+    #[error("Could not configure the HTTP client to download '{uri}'")]
+    ConfigureHttp {
+        uri: BString,
+        source: Box<dyn std::error::Error + Send + Sync + 'static>,
+    },
+    #[cfg(any(
+        feature = "blocking-http-transport-reqwest",
+        feature = "blocking-http-transport-curl"
+    ))]
+    #[error(transparent)]
+    Http(#[from] gix_protocol::transport::client::blocking_io::http::Error),
+    #[error("Could not read the bundle list downloaded from '{uri}'")]
+    ReadList { uri: BString, source: std::io::Error },
+    #[error("Could not parse the bundle list downloaded from '{uri}'")]
+    ParseListFile {
+        uri: BString,
+        source: gix_config::file::init::Error,
+    },
+    #[error(transparent)]
+    ParseList(#[from] gix_protocol::bundle_uri::parse::Error),
+    #[error("The bundle list at '{uri}' refers to another bundle list, which isn't supported")]
+    NestedList { uri: BString },
+    #[error(transparent)]
+    Header(#[from] gix_protocol::bundle_uri::header::Error),
+    #[error("The bundle uses {bundle} object hashes while the local repository uses {local}")]
+    IncompatibleObjectHash {
+        local: gix_hash::Kind,
+        bundle: gix_hash::Kind,
+    },
+    #[error("The bundle requires commit {id} which isn't present locally")]
+    MissingPrerequisite { id: gix_hash::ObjectId },
+    #[error(transparent)]
+    WritePack(#[from] gix_pack::bundle::write::Error),
+    #[error("The bundle is missing object {id} for reference '{name}'")]
+    MissingRefTarget { name: BString, id: gix_hash::ObjectId },
+    #[error(transparent)]
+    RefName(#[from] gix_validate::reference::name::Error),
+    #[error(transparent)]
+    EditReferences(#[from] crate::reference::edit::Error),
+    #[error("Failed to remove .keep file at \"{}\"", path.display())]
+    RemovePackKeepFile {
+        path: std::path::PathBuf,
+        source: std::io::Error,
+    },
+    #[error("Refusing to download the pack at '{uri}' as its protocol isn't one of {allowed:?} configured in 'fetch.uriProtocols'")]
+    DisallowedProtocol { uri: BString, allowed: Vec<String> },
+    #[error("The pack downloaded from '{uri}' has checksum {actual}, but {expected} was advertised")]
+    PackChecksumMismatch {
+        uri: BString,
+        expected: gix_hash::ObjectId,
+        actual: gix_hash::ObjectId,
+    },
+    #[error(transparent)]
+    ProtocolAllowConfig(#[from] crate::config::protocol::allow::Error),
+    #[error("Refusing to download '{uri}' advertised by the remote as only 'http' and 'https' URLs allowed by 'protocol.allow' may be used")]
+    AdvertisedProtocolDenied { uri: BString },
+    #[error("Interrupted")]
+    Interrupted,
+}
+
+/// Download the bundle or bundle list at `uri` and apply all bundles, whose references are written to `refs/bundles/`.
+///
+/// Bundles that fail to download or apply are skipped, and `on_failure(uri, err)` is called for each of them,
+/// as fetching normally will obtain the objects that are still missing.
+pub(super) fn apply_from_uri(
+    repo: &Repository,
+    uri: &BStr,
+    progress: &mut dyn DynNestedProgress,
+    should_interrupt: &AtomicBool,
+    options: &gix_pack::bundle::write::Options,
+    on_failure: &mut dyn FnMut(&BStr, Error),
+) -> Vec<outcome::Bundle> {
+    let mut read = match open(repo, uri) {
+        Ok(read) => read,
+        Err(err) => {
+            on_failure(uri, err);
+            return Vec::new();
+        }
+    };
+    match read.fill_buf() {
+        Ok(buf) if buf.starts_with(b"# v2 git bundle\n") || buf.starts_with(b"# v3 git bundle\n") => {
+            match apply(repo, uri, &mut *read, progress, should_interrupt, options) {
+                Ok(bundle) => vec![bundle],
+                Err(err) => {
+                    on_failure(uri, err);
+                    Vec::new()
+                }
+            }
+        }
+        Ok(_) => match read_list(uri, &mut *read) {
+            Ok(list) => apply_list(repo, &list, uri, false, progress, should_interrupt, options, on_failure),
+            Err(err) => {
+                on_failure(uri, err);
+                Vec::new()
+            }
+        },
+        Err(source) => {
+            on_failure(
+                uri,
+                Error::ReadList {
+                    uri: uri.to_owned(),
+                    source,
+                },
+            );
+            Vec::new()
+        }
+    }
+}
+
+/// Apply the bundles in `list` in order, resolving their URIs relative to `base_uri`.
+///
+/// If `advertised_by_remote` is `true`, only `http` and `https` URIs allowed by `protocol.allow` are used, so the remote
+/// can't make us read local files.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn apply_list(
+    repo: &Repository,
+    list: &List,
+    base_uri: &BStr,
+    advertised_by_remote: bool,
+    progress: &mut dyn DynNestedProgress,
+    should_interrupt: &AtomicBool,
+    options: &gix_pack::bundle::write::Options,
+    on_failure: &mut dyn FnMut(&BStr, Error),
+) -> Vec<outcome::Bundle> {
+    let mut out = Vec::new();
+    for bundle in list.bundles_in_application_order() {
+        if should_interrupt.load(Ordering::Relaxed) {
+            on_failure(bundle.uri.as_ref(), Error::Interrupted);
+            break;
+        }
+        let uri = resolve_relative(base_uri, bundle.uri.as_ref());
+        let res = if advertised_by_remote {
+            repo.config
+                .url_scheme()
+                .map_err(Error::from)
+                .and_then(|permission| check_advertised_scheme(uri.as_ref(), permission))
+        } else {
+            Ok(())
+        };
+        let res = res.and_then(|()| open(repo, uri.as_ref())).and_then(|mut read| {
+            let buf = read.fill_buf().map_err(|source| Error::ReadList {
+                uri: uri.clone(),
+                source,
+            })?;
+            if !buf.starts_with(b"# v") {
+                return Err(Error::NestedList { uri: uri.clone() });
+            }
+            apply(repo, uri.as_ref(), &mut *read, progress, should_interrupt, options)
+        });
+        match res {
+            Ok(bundle) => {
+                out.push(bundle);
+                if list.mode == Mode::Any {
+                    break;
+                }
+            }
+            Err(err) => on_failure(uri.as_ref(), err),
+        }
+    }
+    out
+}
+
+/// Download the pack at `uri` into the object database, and verify it has the `expected` checksum.
+///
+/// `uri` is refused unless it uses one of the `allowed_protocols`, like `https`.
+pub(super) fn write_pack_from_uri(
+    repo: &Repository,
+    uri: &BStr,
+    expected: gix_hash::ObjectId,
+    allowed_protocols: &[&str],
+    progress: &mut dyn DynNestedProgress,
+    should_interrupt: &AtomicBool,
+    options: &gix_pack::bundle::write::Options,
+) -> Result<gix_pack::bundle::write::Outcome, Error> {
+    check_protocol(uri, allowed_protocols)?;
+    let mut read = open(repo, uri)?;
+    let out = write_pack(repo, &mut *read, progress, should_interrupt, options)?;
+    let actual = out.index.data_hash;
+    if actual != expected {
+        for path in [&out.data_path, &out.index_path, &out.keep_path].into_iter().flatten() {
+            std::fs::remove_file(path).ok();
+        }
+        return Err(Error::PackChecksumMismatch {
+            uri: uri.to_owned(),
+            expected,
+            actual,
+        });
+    }
+    Ok(out)
+}
+
+/// Return an error if `uri` doesn't start with `<protocol>://` for one of the `allowed_protocols`, which rejects
+/// plain paths as well.
+fn check_protocol(uri: &BStr, allowed_protocols: &[&str]) -> Result<(), Error> {
+    let is_allowed = allowed_protocols.iter().any(|protocol| {
+        uri.strip_prefix(protocol.as_bytes())
+            .is_some_and(|rest| rest.starts_with(b"://"))
+    });
+    if is_allowed {
+        Ok(())
+    } else {
+        Err(Error::DisallowedProtocol {
+            uri: uri.to_owned(),
+            allowed: allowed_protocols.iter().map(ToString::to_string).collect(),
+        })
+    }
+}
+
+/// Return an error unless `uri`, as advertised by a remote, is an `http` or `https` URL that `permission` allows.
+fn check_advertised_scheme(uri: &BStr, permission: &crate::remote::url::SchemePermission) -> Result<(), Error> {
+    let url = gix_url::parse(uri)?;
+    if matches!(url.scheme, gix_url::Scheme::Http | gix_url::Scheme::Https) && permission.allow(&url.scheme) {
+        Ok(())
+    } else {
+        Err(Error::AdvertisedProtocolDenied { uri: uri.to_owned() })
+    }
+}
+
+/// Resolve `uri` relative to `base` if it isn't absolute, treating `base` as directory if it ends with a slash.
+pub(super) fn resolve_relative(base: &BStr, uri: &BStr) -> BString {
+    if uri.contains_str("://") || gix_path::from_bstr(uri).is_absolute() {
+        return uri.to_owned();
+    }
+    let mut out: BString = match base.rfind_byte(b'/') {
+        Some(pos) => base[..=pos].into(),
+        None => BString::default(),
+    };
+    out.extend_from_slice(uri.trim_start_with(|c| c == '/').as_bytes());
+    out
+}
+
+fn read_list(uri: &BStr, read: &mut dyn BufRead) -> Result<List, Error> {
+    let mut buf = Vec::new();
+    read.read_to_end(&mut buf).map_err(|source| Error::ReadList {
+        uri: uri.to_owned(),
+        source,
+    })?;
+    let file = gix_config::File::from_bytes_no_includes(&buf, gix_config::file::Metadata::api(), Default::default())
+        .map_err(|source| Error::ParseListFile {
+            uri: uri.to_owned(),
+            source,
+        })?;
+    let mut pairs = Vec::<(BString, BString)>::new();
+    for section in file.sections_by_name("bundle").into_iter().flatten() {
+        let prefix = match section.header().subsection_name() {
+            Some(id) => format!("bundle.{id}."),
+            None => "bundle.".into(),
+        };
+        for key in section.body().value_names() {
+            if let Some(value) = section.body().value(key) {
+                pairs.push((format!("{prefix}{key}").into(), value.into_owned()));
+            }
+        }
+    }
+    Ok(List::from_key_value_pairs(
+        pairs.iter().map(|(key, value)| (key.as_bstr(), value.as_bstr())),
+    )?)
+}
+
+fn apply(
+    repo: &Repository,
+    uri: &BStr,
+    read: &mut dyn BufRead,
+    progress: &mut dyn DynNestedProgress,
+    should_interrupt: &AtomicBool,
+    options: &gix_pack::bundle::write::Options,
+) -> Result<outcome::Bundle, Error> {
+    let header = Header::from_read(read)?;
+    if header.object_hash != repo.object_hash() {
+        return Err(Error::IncompatibleObjectHash {
+            local: repo.object_hash(),
+            bundle: header.object_hash,
+        });
+    }
+    if let Some(id) = header.prerequisites.iter().find(|id| !repo.has_object(id)) {
+        return Err(Error::MissingPrerequisite { id: *id });
+    }
+    let mut write_pack_bundle = write_pack(repo, read, progress, should_interrupt, options)?;
+
+    let mut edits = Vec::with_capacity(header.refs.len());
+    for (name, id) in &header.refs {
+        if !repo.has_object(id) {
+            return Err(Error::MissingRefTarget {
+                name: name.clone(),
+                id: *id,
+            });
+        }
+        let Some(short_name) = name.strip_prefix(b"refs/") else {
+            continue;
+        };
+        let mut bundle_name = BString::from("refs/bundles/");
+        bundle_name.extend_from_slice(short_name);
+        edits.push(RefEdit {
+            change: gix_ref::transaction::Change::Update {
+                log: LogChange {
+                    mode: RefLog::AndReference,
+                    force_create_reflog: false,
+                    message: format!("bundle: {uri}").into(),
+                },
+                expected: PreviousValue::Any,
+                new: Target::Object(*id),
+            },
+            name: bundle_name.try_into()?,
+            deref: false,
+        });
+    }
+    let refs = edits.iter().map(|edit| edit.name.clone()).collect();
+    repo.edit_references(edits)?;
+    if let Some(path) = write_pack_bundle.keep_path.take() {
+        std::fs::remove_file(&path).map_err(|source| Error::RemovePackKeepFile { path, source })?;
+    }
+    Ok(outcome::Bundle {
+        uri: uri.to_owned(),
+        write_pack_bundle,
+        refs,
+    })
+}
+
+fn write_pack(
+    repo: &Repository,
+    read: &mut dyn BufRead,
+    progress: &mut dyn DynNestedProgress,
+    should_interrupt: &AtomicBool,
+    options: &gix_pack::bundle::write::Options,
+) -> Result<gix_pack::bundle::write::Outcome, Error> {
+    Ok(gix_pack::Bundle::write_to_directory(
+        read,
+        Some(&repo.objects.store_ref().path().join("pack")),
+        progress,
+        should_interrupt,
+        Some(Box::new({
+            let repo = repo.clone();
+            repo.objects
+        })),
+        options.clone(),
+    )?)
+}
+
+/// Open `uri` for reading, which may be a local path, a `file://` URL, or an `http(s)://` URL if HTTP support is compiled in.
+fn open(repo: &Repository, uri: &BStr) -> Result<Box<dyn BufRead>, Error> {
+    let url = gix_url::parse(uri)?;
+    match url.scheme {
+        gix_url::Scheme::File => {
+            let path = gix_path::from_bstr(url.path.as_bstr()).into_owned();
+            let file = std::fs::File::open(&path).map_err(|source| Error::Open { path, source })?;
+            Ok(Box::new(std::io::BufReader::new(file)))
+        }
+        #[cfg(any(
+            feature = "blocking-http-transport-reqwest",
+            feature = "blocking-http-transport-curl"
+        ))]
+        gix_url::Scheme::Http | gix_url::Scheme::Https => http::get(repo, uri),
+        scheme => {
+            let _ = repo;
+            Err(Error::UnsupportedScheme {
+                uri: uri.to_owned(),
+                scheme,
+            })
+        }
+    }
+}
+
+#[cfg(any(
+    feature = "blocking-http-transport-reqwest",
+    feature = "blocking-http-transport-curl"
+))]
+mod http {
+    use std::io::BufRead;
+
+    use gix_protocol::transport::client::blocking_io::http::Http as _;
+
+    use super::Error;
+    use crate::{
+        bstr::{BStr, ByteSlice},
+        Repository,
+    };
+
+    #[cfg(feature = "blocking-http-transport-curl")]
+    type Impl = gix_protocol::transport::client::blocking_io::http::curl::Curl;
+    #[cfg(all(
+        feature = "blocking-http-transport-reqwest",
+        not(feature = "blocking-http-transport-curl")
+    ))]
+    type Impl = gix_protocol::transport::client::blocking_io::http::reqwest::Remote;
+
+    type Body = <Impl as gix_protocol::transport::client::blocking_io::http::Http>::ResponseBody;
+
+    /// Keep the HTTP client alive for as long as the body is read.
+    struct Download {
+        _http: Impl,
+        body: Body,
+    }
+
+    impl std::io::Read for Download {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            self.body.read(buf)
+        }
+    }
+
+    impl BufRead for Download {
+        fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+            self.body.fill_buf()
+        }
+
+        fn consume(&mut self, amt: usize) {
+            self.body.consume(amt);
+        }
+    }
+
+    pub(super) fn get(repo: &Repository, uri: &BStr) -> Result<Box<dyn BufRead>, Error> {
+        let mut http = Impl::default();
+        if let Some(options) = repo.transport_options(uri, None)? {
+            http.configure(&*options).map_err(|source| Error::ConfigureHttp {
+                uri: uri.to_owned(),
+                source,
+            })?;
+        }
+        let url = uri.to_str_lossy();
+        let body = http.get(&url, &url, None::<&str>)?.body;
+        Ok(Box::new(Download { _http: http, body }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{check_advertised_scheme, check_protocol, Error};
+    use crate::{bstr::ByteSlice, remote::url::SchemePermission};
+
+    fn permission(config: &'static str) -> SchemePermission {
+        let config = gix_config::File::from_bytes_no_includes(
+            config.as_bytes(),
+            gix_config::file::Metadata::api(),
+            Default::default(),
+        )
+        .expect("valid");
+        SchemePermission::from_config(&config, |_| true).expect("valid")
+    }
+
+    #[test]
+    fn bundle_uris_advertised_by_the_remote_must_not_be_local() {
+        let default = permission("");
+        let allow_all = permission("[protocol]\n\tallow = always\n[protocol \"file\"]\n\tallow = always");
+        for uri in [
+            "/etc/passwd",
+            "../relative.bundle",
+            "file:///tmp/all.bundle",
+            "ssh://example.com/all.bundle",
+            "git://example.com/all.bundle",
+        ] {
+            for permission in [&default, &allow_all] {
+                assert!(
+                    matches!(
+                        check_advertised_scheme(uri.as_bytes().as_bstr(), permission),
+                        Err(Error::AdvertisedProtocolDenied { .. })
+                    ),
+                    "{uri} is refused"
+                );
+            }
+        }
+        for uri in ["https://example.com/all.bundle", "http://example.com/all.bundle"] {
+            assert!(check_advertised_scheme(uri.as_bytes().as_bstr(), &default).is_ok());
+        }
+
+        let deny_http = permission("[protocol \"http\"]\n\tallow = never");
+        assert!(
+            check_advertised_scheme(b"http://example.com/all.bundle".as_bstr(), &deny_http).is_err(),
+            "protocol.allow is respected"
+        );
+        assert!(check_advertised_scheme(b"https://example.com/all.bundle".as_bstr(), &deny_http).is_ok());
+    }
+
+    #[test]
+    fn packfile_uris_with_disallowed_protocols_are_refused() {
+        for uri in [
+            "file:///tmp/pack.pack",
+            "/tmp/pack.pack",
+            "pack.pack",
+            "http://example.com/pack.pack",
+            "ssh://example.com/pack.pack",
+            "httpss://example.com/pack.pack",
+            "https:example.com/pack.pack",
+        ] {
+            assert!(
+                matches!(
+                    check_protocol(uri.as_bytes().as_bstr(), &["https"]),
+                    Err(Error::DisallowedProtocol { .. })
+                ),
+                "{uri} is refused"
+            );
+        }
+        assert!(
+            check_protocol(b"file:///tmp/pack.pack".as_bstr(), &[]).is_err(),
+            "an empty list allows nothing"
+        );
+    }
+
+    #[test]
+    fn packfile_uris_with_allowed_protocols_are_accepted() {
+        assert!(check_protocol(b"https://example.com/pack.pack".as_bstr(), &["https"]).is_ok());
+        assert!(check_protocol(b"http://example.com/pack.pack".as_bstr(), &["https", "http"]).is_ok());
+        assert!(check_protocol(b"file:///tmp/pack.pack".as_bstr(), &["file"]).is_ok());
+    }
+}
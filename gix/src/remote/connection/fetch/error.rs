@@ -36,6 +36,13 @@ pub enum Error {
     RejectShallowRemoteConfig(#[from] config::boolean::Error),
     #[error(transparent)]
     NegotiationAlgorithmConfig(#[from] config::key::GenericErrorWithValue),
+    #[error("Could not obtain configuration to learn if bundles should be requested from the remote")]
+    BundleUriConfig(#[source] config::boolean::Error),
+    #[error(transparent)]
+    BundleUri(gix_protocol::bundle_uri::Error),
+    #[cfg(feature = "blocking-network-client")]
+    #[error(transparent)]
+    PackfileUri(#[from] super::bundle_uri::Error),
 }
 
 impl gix_protocol::transport::IsSpuriousError for Error {
@@ -43,6 +50,7 @@ impl gix_protocol::transport::IsSpuriousError for Error {
         match self {
             Error::Fetch(err) => err.is_spurious(),
             Error::Client(err) => err.is_spurious(),
+            Error::BundleUri(err) => err.is_spurious(),
             _ => false,
         }
     }
@@ -16,6 +16,10 @@ use crate::{
 mod error;
 pub use error::Error;
 
+///
+#[cfg(feature = "blocking-network-client")]
+pub mod bundle_uri;
+
 use crate::remote::fetch::WritePackedRefs;
 
 /// The way reflog messages should be composed whenever a ref is written with recent objects from a remote.
@@ -79,6 +83,10 @@ pub struct Outcome {
     pub handshake: gix_protocol::Handshake,
     /// The status of the operation to indicate what happened.
     pub status: Status,
+    /// The bundles that were downloaded and applied before negotiating the pack, if any.
+    pub bundles: Vec<outcome::Bundle>,
+    /// Information about each pack downloaded from a packfile URI the server sent instead of including the objects in its pack.
+    pub packfile_uris: Vec<gix_pack::bundle::write::Outcome>,
 }
 
 /// Additional types related to the outcome of a fetch operation.
@@ -93,6 +101,17 @@ pub mod outcome {
         /// Additional information for each round of negotiation.
         pub rounds: Vec<gix_protocol::fetch::negotiate::Round>,
     }
+
+    /// Information about a bundle that was applied before fetching.
+    #[derive(Debug, Clone)]
+    pub struct Bundle {
+        /// The location the bundle was downloaded from.
+        pub uri: crate::bstr::BString,
+        /// Information collected while writing the pack of the bundle and its index.
+        pub write_pack_bundle: gix_pack::bundle::write::Outcome,
+        /// The references that were written for each reference in the bundle, all of them in `refs/bundles/`.
+        pub refs: Vec<gix_ref::FullName>,
+    }
 }
 
 pub use gix_protocol::fetch::ProgressId;
@@ -153,6 +172,7 @@ where
             reflog_message: None,
             write_packed_refs: WritePackedRefs::Never,
             shallow: Default::default(),
+            bundle_uri: None,
         })
     }
 }
@@ -167,6 +187,81 @@ where
     }
 }
 
+/// A [`Prepare`] without the remote it was created with, which allows to change the repository of the remote without
+/// connecting again.
+#[cfg(any(feature = "async-network-client-async-std", feature = "blocking-network-client"))]
+pub(crate) struct Detached<T>
+where
+    T: Transport,
+{
+    transport: gix_protocol::SendFlushOnDrop<T>,
+    transport_options: Option<Box<dyn std::any::Any>>,
+    handshake: Option<gix_protocol::Handshake>,
+    trace: bool,
+    ref_map: RefMap,
+    dry_run: DryRun,
+    reflog_message: Option<RefLogMessage>,
+    write_packed_refs: WritePackedRefs,
+    shallow: remote::fetch::Shallow,
+    bundle_uri: Option<BString>,
+}
+
+#[cfg(any(feature = "async-network-client-async-std", feature = "blocking-network-client"))]
+impl<'remote, 'repo, T> Prepare<'remote, 'repo, T>
+where
+    T: Transport,
+{
+    /// Detach the established connection and the listed refs from the remote they were obtained with.
+    ///
+    /// Custom credentials are dropped along with the remote and have to be set again after [attaching](Detached::attach()).
+    pub(crate) fn detach(mut self) -> Detached<T> {
+        let con = self.con.take().expect("present until the pack was received");
+        Detached {
+            transport: con.transport,
+            transport_options: con.transport_options,
+            handshake: con.handshake,
+            trace: con.trace,
+            ref_map: self.ref_map,
+            dry_run: self.dry_run,
+            reflog_message: self.reflog_message,
+            write_packed_refs: self.write_packed_refs,
+            shallow: self.shallow,
+            bundle_uri: self.bundle_uri,
+        }
+    }
+
+    /// Return the connection to use for receiving the pack.
+    pub(crate) fn connection_mut(&mut self) -> &mut Connection<'remote, 'repo, T> {
+        self.con.as_mut().expect("present until the pack was received")
+    }
+}
+
+#[cfg(any(feature = "async-network-client-async-std", feature = "blocking-network-client"))]
+impl<T> Detached<T>
+where
+    T: Transport,
+{
+    /// Continue where the [`Prepare`] was detached, with `remote` which must be configured like the one it was created with.
+    pub(crate) fn attach<'remote, 'repo>(self, remote: &'remote crate::Remote<'repo>) -> Prepare<'remote, 'repo, T> {
+        Prepare {
+            con: Some(Connection {
+                remote,
+                authenticate: None,
+                transport_options: self.transport_options,
+                transport: self.transport,
+                handshake: self.handshake,
+                trace: self.trace,
+            }),
+            ref_map: self.ref_map,
+            dry_run: self.dry_run,
+            reflog_message: self.reflog_message,
+            write_packed_refs: self.write_packed_refs,
+            shallow: self.shallow,
+            bundle_uri: self.bundle_uri,
+        }
+    }
+}
+
 mod config;
 mod receive_pack;
 ///
@@ -184,6 +279,7 @@ where
     reflog_message: Option<RefLogMessage>,
     write_packed_refs: WritePackedRefs,
     shallow: remote::fetch::Shallow,
+    bundle_uri: Option<BString>,
 }
 
 /// Builder
@@ -225,4 +321,13 @@ where
         self.shallow = shallow;
         self
     }
+
+    /// Download the bundle or bundle list at `uri` and apply it before negotiating the pack, which overrides `fetch.bundleURI`.
+    ///
+    /// It can be a path, a `file://` URL, or an `http(s)://` URL if an HTTP transport is compiled in.
+    /// Bundles that can't be downloaded or applied are skipped, as the fetch obtains all missing objects either way.
+    pub fn with_bundle_uri(mut self, uri: impl Into<BString>) -> Self {
+        self.bundle_uri = Some(uri.into());
+        self
+    }
 }
@@ -7,7 +7,13 @@ use gix_transport::client::async_io::Transport;
 #[cfg(feature = "blocking-network-client")]
 use gix_transport::client::blocking_io::Transport;
 
+#[cfg(feature = "blocking-network-client")]
+use gix_features::progress::Progress;
+#[cfg(feature = "blocking-network-client")]
+use gix_protocol::transport::IsSpuriousError;
+
 use crate::{
+    bstr::ByteSlice,
     config::{
         cache::util::ApplyLeniency,
         tree::{Clone, Fetch},
@@ -19,6 +25,8 @@ use crate::{
         fetch::{negotiate::Algorithm, outcome, refs, Error, Outcome, Prepare, RefLogMessage, Status},
     },
 };
+#[cfg(feature = "blocking-network-client")]
+use crate::{config::tree::Transfer, remote::connection::fetch::bundle_uri};
 
 impl<T> Prepare<'_, '_, T>
 where
@@ -69,6 +77,21 @@ where
     /// ### Configuration
     ///
     /// - `gitoxide.userAgent` is read to obtain the application user agent for git servers and for HTTP servers as well.
+    /// - `fetch.bundleURI` is the bundle or bundle list to apply before negotiating the pack, unless overridden with
+    ///   [`with_bundle_uri()`](Self::with_bundle_uri()).
+    /// - `transfer.bundleURI`, if `true`, makes us ask V2 servers for bundles to apply before negotiating the pack if no bundle URI is set.
+    ///   Only `http` and `https` URLs the server advertises are used, and only if `protocol.allow` permits it.
+    /// - `fetch.uriProtocols` is the comma-separated list of protocols the server may use to offload parts of the pack to packfile URIs.
+    ///   Only packfile URIs using one of these protocols are downloaded, which defaults to `https` if unset.
+    ///
+    /// ### Bundles and packfile URIs
+    ///
+    /// Bundles are applied in blocking mode only, before negotiation, and their references are written to `refs/bundles/`
+    /// so they are used as starting point for negotiation.
+    /// Failing to download or apply a bundle is non-fatal as the fetch will obtain all missing objects either way.
+    ///
+    /// Packs the server advertises as packfile URIs are downloaded after receiving the pack, and their checksum must
+    /// match the one advertised by the server.
     ///
     #[gix_protocol::maybe_async::maybe_async]
     pub async fn receive<P>(mut self, progress: P, should_interrupt: &AtomicBool) -> Result<Outcome, Error>
@@ -98,7 +121,109 @@ where
             });
         }
 
+        let write_pack_options = gix_pack::bundle::write::Options {
+            thread_limit: config::index_threads(repo)?,
+            index_version: config::pack_index_version(repo)?,
+            iteration_mode: gix_pack::data::input::Mode::Verify,
+            object_hash: con.remote.repo.object_hash(),
+        };
+        #[cfg(feature = "blocking-network-client")]
+        let mut progress = progress;
+        #[cfg(feature = "blocking-network-client")]
+        let bundles = if matches!(self.dry_run, fetch::DryRun::No) {
+            let mut failures = Vec::new();
+            let mut bundle_progress = progress.add_child("apply bundles");
+            let bundles = match self.bundle_uri.take().or_else(|| {
+                repo.config
+                    .resolved
+                    .string(Fetch::BUNDLE_URI)
+                    .map(std::borrow::Cow::into_owned)
+            }) {
+                Some(uri) => bundle_uri::apply_from_uri(
+                    repo,
+                    uri.as_ref(),
+                    &mut bundle_progress,
+                    should_interrupt,
+                    &write_pack_options,
+                    &mut |uri, err| failures.push((uri.to_owned(), err)),
+                ),
+                None if repo
+                    .config
+                    .resolved
+                    .boolean(Transfer::BUNDLE_URI)
+                    .map(|val| Transfer::BUNDLE_URI.enrich_error(val))
+                    .transpose()
+                    .with_leniency(repo.config.lenient_config)
+                    .map_err(Error::BundleUriConfig)?
+                    .unwrap_or(false) =>
+                {
+                    let command =
+                        gix_protocol::BundleUriCommand::new(&handshake.capabilities, repo.config.user_agent_tuple());
+                    if command.is_supported() {
+                        let base_uri = con
+                            .remote
+                            .url(remote::Direction::Fetch)
+                            .map(|url| {
+                                let mut base = url.to_bstring();
+                                base.push(b'/');
+                                base
+                            })
+                            .unwrap_or_default();
+                        match command.invoke_blocking(&mut con.transport.inner, con.trace) {
+                            Ok(list) => bundle_uri::apply_list(
+                                repo,
+                                &list,
+                                base_uri.as_ref(),
+                                true,
+                                &mut bundle_progress,
+                                should_interrupt,
+                                &write_pack_options,
+                                &mut |uri, err| failures.push((uri.to_owned(), err)),
+                            ),
+                            Err(err) if err.is_spurious() => return Err(Error::BundleUri(err)),
+                            Err(err) => {
+                                bundle_progress.fail(format!("Could not obtain bundle list from remote: {err}"));
+                                Vec::new()
+                            }
+                        }
+                    } else {
+                        Vec::new()
+                    }
+                }
+                None => Vec::new(),
+            };
+            for (uri, err) in failures {
+                bundle_progress.fail(format!("Skipped bundle at '{uri}': {err}"));
+            }
+            bundles
+        } else {
+            Vec::new()
+        };
+        #[cfg(not(feature = "blocking-network-client"))]
+        let bundles = Vec::new();
+
+        let uri_protocols = repo
+            .config
+            .resolved
+            .string(Fetch::URI_PROTOCOLS)
+            // packs at packfile URIs are only downloaded in blocking mode.
+            .filter(|_| cfg!(feature = "blocking-network-client") && matches!(self.dry_run, fetch::DryRun::No));
+        let uri_protocols = uri_protocols.as_ref().and_then(|p| p.to_str().ok());
+        #[cfg(feature = "blocking-network-client")]
+        let allowed_uri_protocols: Vec<_> = uri_protocols.map_or_else(
+            || vec!["https"],
+            |protocols| {
+                protocols
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|protocol| !protocol.is_empty())
+                    .collect()
+            },
+        );
+        #[cfg(feature = "blocking-network-client")]
+        let mut packfile_uri_progress = progress.add_child("download packfile uris");
         let fetch_options = gix_protocol::fetch::Options {
+            packfile_uri_protocols: uri_protocols,
             shallow_file: repo.shallow_file(),
             shallow: &self.shallow,
             tags: con.remote.fetch_tags,
@@ -149,12 +274,6 @@ where
             open_options: repo.options.clone(),
         };
 
-        let write_pack_options = gix_pack::bundle::write::Options {
-            thread_limit: config::index_threads(repo)?,
-            index_version: config::pack_index_version(repo)?,
-            iteration_mode: gix_pack::data::input::Mode::Verify,
-            object_hash: con.remote.repo.object_hash(),
-        };
         let mut write_pack_bundle = None;
 
         let res = gix_protocol::fetch(
@@ -171,7 +290,7 @@ where
                             let repo = repo.clone();
                             repo.objects
                         })),
-                        write_pack_options,
+                        write_pack_options.clone(),
                     )?;
                     may_read_to_end = true;
                     Some(res)
@@ -186,6 +305,28 @@ where
             fetch_options,
         )
         .await?;
+
+        #[cfg(feature = "blocking-network-client")]
+        let packfile_uris = match res.as_ref() {
+            Some(res) => {
+                let mut packs = Vec::with_capacity(res.last_response.packfile_uris().len());
+                for packfile_uri in res.last_response.packfile_uris() {
+                    packs.push(bundle_uri::write_pack_from_uri(
+                        repo,
+                        packfile_uri.uri.as_ref(),
+                        packfile_uri.hash,
+                        &allowed_uri_protocols,
+                        &mut packfile_uri_progress,
+                        should_interrupt,
+                        &write_pack_options,
+                    )?);
+                }
+                packs
+            }
+            None => Vec::new(),
+        };
+        #[cfg(not(feature = "blocking-network-client"))]
+        let packfile_uris = Vec::<gix_pack::bundle::write::Outcome>::new();
         let negotiate = res.map(|v| outcome::Negotiate {
             graph: graph.detach(),
             rounds: v.negotiate.rounds,
@@ -217,6 +358,14 @@ where
                 }
             }
         }
+        let mut packfile_uris = packfile_uris;
+        if !update_refs.edits.is_empty() {
+            for pack in &mut packfile_uris {
+                if let Some(path) = pack.keep_path.take() {
+                    std::fs::remove_file(&path).map_err(|err| Error::RemovePackKeepFile { path, source: err })?;
+                }
+            }
+        }
 
         let out = Outcome {
             handshake,
@@ -233,6 +382,8 @@ where
                     update_refs,
                 },
             },
+            bundles,
+            packfile_uris,
         };
         Ok(out)
     }
@@ -0,0 +1,533 @@
+use std::{
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use gix_diff::patch::{self, Kind};
+use gix_object::tree::EntryKind;
+
+use crate::{
+    apply::{Location, Options, Outcome},
+    bstr::{BStr, BString, ByteSlice},
+    repository::apply_patch::Error,
+    worktree::IndexPersistedOrInMemory,
+    Repository,
+};
+
+impl Repository {
+    /// Apply the patches of all `files`, as parsed by [`patch::parse()`](crate::apply::patch::parse()), to `location`
+    /// according to `options`, similar to `git apply`.
+    ///
+    /// Like in Git, either all patches apply, or nothing is changed. Multiple patches for the same file are applied
+    /// in order, and only paths that are valid and that don't lead through symbolic links are touched.
+    pub fn apply_patch(&self, files: &[patch::File], location: Location, options: Options) -> Result<Outcome, Error> {
+        let mut state = State::new(self, location)?;
+        for file in files {
+            state.apply(file, options)?;
+        }
+        state.write()
+    }
+}
+
+/// A file as it is stored in `git`.
+#[derive(Clone)]
+struct Blob {
+    data: Vec<u8>,
+    kind: EntryKind,
+}
+
+/// The versions of a file that couldn't be merged without conflicts.
+struct Conflict {
+    base: gix_hash::ObjectId,
+    ours: Vec<u8>,
+    theirs: Vec<u8>,
+}
+
+/// A change that will be written once all patches have been applied.
+struct Change {
+    path: BString,
+    /// The new version of the file at `path`, or `None` if it is deleted.
+    new: Option<Blob>,
+    conflict: Option<Conflict>,
+}
+
+struct State<'repo> {
+    repo: &'repo Repository,
+    location: Location,
+    tree: Option<crate::Tree<'repo>>,
+    index: Option<gix_index::File>,
+    worktree: Option<(PathBuf, crate::filter::Pipeline<'repo>, IndexPersistedOrInMemory)>,
+    protect: gix_validate::path::component::Options,
+    changes: Vec<Change>,
+    paths: Vec<BString>,
+    conflicts: Vec<BString>,
+}
+
+impl<'repo> State<'repo> {
+    fn new(repo: &'repo Repository, location: Location) -> Result<Self, Error> {
+        let tree = match location {
+            Location::Tree(id) => Some(repo.find_tree(id)?),
+            Location::Worktree | Location::Index | Location::WorktreeAndIndex => None,
+        };
+        let index = match location {
+            Location::Index | Location::WorktreeAndIndex => Some(gix_index::File::clone(&**repo.index_or_empty()?)),
+            Location::Worktree | Location::Tree(_) => None,
+        };
+        let worktree = match location {
+            Location::Worktree | Location::WorktreeAndIndex => {
+                let workdir = repo.workdir().ok_or(Error::MissingWorktree)?.to_owned();
+                let (pipeline, index) = repo.filter_pipeline(None)?;
+                Some((workdir, pipeline, index))
+            }
+            Location::Index | Location::Tree(_) => None,
+        };
+        Ok(State {
+            repo,
+            location,
+            tree,
+            index,
+            worktree,
+            protect: repo.config.protect_options()?,
+            changes: Vec::new(),
+            paths: Vec::new(),
+            conflicts: Vec::new(),
+        })
+    }
+
+    fn apply(&mut self, file: &patch::File, options: Options) -> Result<(), Error> {
+        let (source, destination, destination_mode, base_id, change_kind) = if options.reverse {
+            let kind = match file.kind {
+                Kind::Addition => Kind::Deletion,
+                Kind::Deletion => Kind::Addition,
+                kind => kind,
+            };
+            (&file.new_path, &file.old_path, file.old_mode, &file.new_id, kind)
+        } else {
+            (&file.old_path, &file.new_path, file.new_mode, &file.old_id, file.kind)
+        };
+        // Reversing a copy removes the copy, and leaves its source alone.
+        let (remove_source, write_destination) = match change_kind {
+            Kind::Deletion => (true, false),
+            Kind::Addition | Kind::Modification => (false, true),
+            Kind::Rename => (true, true),
+            Kind::Copy => (options.reverse, !options.reverse),
+        };
+        for path in [source, destination].into_iter().flatten() {
+            self.validate(path.as_bstr(), destination_mode.map(|mode| mode.kind()))?;
+        }
+
+        let current = match source {
+            Some(path) => Some(
+                self.read(path.as_bstr())?
+                    .ok_or_else(|| Error::NotFound { path: path.clone() })?,
+            ),
+            None => None,
+        };
+        if let Some(path) = destination
+            .as_ref()
+            .filter(|path| write_destination && Some(*path) != source.as_ref())
+        {
+            if self.read(path.as_bstr())?.is_some() {
+                return Err(Error::AlreadyExists { path: path.clone() });
+            }
+        }
+
+        let current_data = current.as_ref().map_or(&[][..], |blob| blob.data.as_slice());
+        let apply_options = patch::apply::Options {
+            reverse: options.reverse,
+            min_context: options.min_context,
+        };
+        let (data, conflict) = match patch::apply(file, current_data, apply_options) {
+            Ok(data) => (data, None),
+            Err(err @ patch::apply::Error::HunkFailed { .. }) if options.three_way && current.is_some() => {
+                self.merge(file, current_data, base_id.as_ref(), apply_options, err)?
+            }
+            Err(source) => {
+                return Err(Error::Apply {
+                    path: file.path().clone(),
+                    source,
+                })
+            }
+        };
+
+        if let Some(path) = source.as_ref().filter(|_| remove_source) {
+            self.record(path.clone(), None, None);
+        }
+        if let Some(path) = destination.as_ref().filter(|_| write_destination) {
+            let kind = destination_mode
+                .map(|mode| mode.kind())
+                .or_else(|| current.as_ref().map(|blob| blob.kind))
+                .unwrap_or(EntryKind::Blob);
+            if matches!(kind, EntryKind::Commit | EntryKind::Tree) {
+                return Err(Error::Submodule { path: path.clone() });
+            }
+            if conflict.is_some() {
+                self.conflicts.push(path.clone());
+            }
+            self.record(path.clone(), Some(Blob { data, kind }), conflict);
+        }
+        Ok(())
+    }
+
+    /// Record the new state of `path`, replacing earlier changes to it.
+    fn record(&mut self, path: BString, new: Option<Blob>, conflict: Option<Conflict>) {
+        if !self.paths.contains(&path) {
+            self.paths.push(path.clone());
+        }
+        self.changes.retain(|change| change.path != path);
+        self.changes.push(Change { path, new, conflict });
+    }
+
+    /// Fall back to a three-way merge of `ours`, the current version of the file, with the result of applying the patch to
+    /// the version of the file it was created for, which is identified by the `base_id` prefix.
+    /// Return `err` if there is no such version.
+    fn merge(
+        &self,
+        file: &patch::File,
+        ours: &[u8],
+        base_id: Option<&BString>,
+        options: patch::apply::Options,
+        err: patch::apply::Error,
+    ) -> Result<(Vec<u8>, Option<Conflict>), Error> {
+        let base_id = base_id
+            .and_then(|hex| hex.to_str().ok())
+            .and_then(|hex| gix_hash::Prefix::from_hex(hex).ok())
+            .map(|prefix| self.repo.objects.lookup_prefix(prefix, None))
+            .transpose()?
+            .flatten()
+            .and_then(Result::ok);
+        let Some(base_id) = base_id else {
+            return Err(Error::MissingThreeWayBase {
+                path: file.path().clone(),
+                source: err,
+            });
+        };
+        let base = self.repo.find_blob(base_id)?.take_data();
+        let theirs = patch::apply(file, &base, options).map_err(|source| Error::Apply {
+            path: file.path().clone(),
+            source,
+        })?;
+
+        let text_options = self.repo.blob_merge_options()?.text;
+        let mut input = gix_diff::blob::intern::InternedInput::default();
+        let mut out = Vec::new();
+        let resolution = gix_merge::blob::builtin_driver::text(
+            &mut out,
+            &mut input,
+            gix_merge::blob::builtin_driver::text::Labels {
+                ancestor: None,
+                current: Some("ours".into()),
+                other: Some("theirs".into()),
+            },
+            ours,
+            &base,
+            &theirs,
+            text_options,
+        );
+        let conflict = (resolution == gix_merge::blob::Resolution::Conflict).then(|| Conflict {
+            base: base_id,
+            ours: ours.to_owned(),
+            theirs,
+        });
+        Ok((out, conflict))
+    }
+
+    /// Assure `path` is valid and doesn't lead through a symbolic link or file, be it existing or one we are about to write.
+    fn validate(&self, path: &BStr, kind: Option<EntryKind>) -> Result<(), Error> {
+        let mut components = path.split_str("/").peekable();
+        while let Some(component) = components.next() {
+            let is_last = components.peek().is_none();
+            let mode =
+                (is_last && kind == Some(EntryKind::Link)).then_some(gix_validate::path::component::Mode::Symlink);
+            gix_validate::path::component(component.as_bstr(), mode, self.protect).map_err(|source| {
+                Error::InvalidPath {
+                    path: path.to_owned(),
+                    source,
+                }
+            })?;
+        }
+
+        for pos in path.find_iter("/") {
+            let leading = path[..pos].as_bstr();
+            match self.kind_of(leading)? {
+                None | Some(EntryKind::Tree) => {}
+                Some(EntryKind::Link) => return Err(Error::BeyondSymlink { path: path.to_owned() }),
+                Some(EntryKind::Blob | EntryKind::BlobExecutable | EntryKind::Commit) => {
+                    return Err(Error::AlreadyExists {
+                        path: leading.to_owned(),
+                    })
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Return the kind of the entry at `path`, taking pending changes into account, or `None` if there is none.
+    fn kind_of(&self, path: &BStr) -> Result<Option<EntryKind>, Error> {
+        if let Some(change) = self.changes.iter().find(|change| change.path == path) {
+            return Ok(change.new.as_ref().map(|blob| blob.kind));
+        }
+        let in_worktree = match &self.worktree {
+            Some((workdir, _, _)) => match std::fs::symlink_metadata(workdir.join(gix_path::from_bstr(path))) {
+                Ok(md) if md.is_symlink() => Some(EntryKind::Link),
+                Ok(md) if md.is_dir() => Some(EntryKind::Tree),
+                Ok(_) => Some(EntryKind::Blob),
+                Err(_) => None,
+            },
+            None => None,
+        };
+        let in_index = self.index.as_ref().and_then(|index| {
+            index.entry_by_path(path).map(|entry| {
+                entry
+                    .mode
+                    .to_tree_entry_mode()
+                    .map_or(EntryKind::Blob, |mode| mode.kind())
+            })
+        });
+        let in_tree = match &self.tree {
+            Some(tree) => tree.lookup_entry(path.split_str("/"))?.map(|entry| entry.mode().kind()),
+            None => None,
+        };
+        Ok(in_worktree.or(in_index).or(in_tree))
+    }
+
+    /// Read the current version of the file at `path`, or `None` if it doesn't exist.
+    fn read(&mut self, path: &BStr) -> Result<Option<Blob>, Error> {
+        if let Some(change) = self.changes.iter().find(|change| change.path == path) {
+            return Ok(change.new.clone());
+        }
+        match self.location {
+            Location::Tree(_) => {
+                let tree = self.tree.as_ref().expect("set for tree location");
+                let Some(entry) = tree.lookup_entry(path.split_str("/"))? else {
+                    return Ok(None);
+                };
+                self.blob(path, entry.object_id(), entry.mode().kind())
+            }
+            Location::Index => self.read_index(path),
+            Location::Worktree => self.read_worktree(path),
+            Location::WorktreeAndIndex => {
+                let in_worktree = self.read_worktree(path)?;
+                let in_index = self.read_index(path)?;
+                match (&in_worktree, &in_index) {
+                    (None, None) => {}
+                    (Some(worktree), Some(index)) if worktree.kind == index.kind && worktree.data == index.data => {}
+                    _ => return Err(Error::WorktreeDoesNotMatchIndex { path: path.to_owned() }),
+                }
+                Ok(in_worktree)
+            }
+        }
+    }
+
+    fn read_index(&self, path: &BStr) -> Result<Option<Blob>, Error> {
+        let index = self.index.as_ref().expect("set for index locations");
+        match index.entry_by_path_and_stage(path, gix_index::entry::Stage::Unconflicted) {
+            Some(entry) => {
+                let kind = entry
+                    .mode
+                    .to_tree_entry_mode()
+                    .map_or(EntryKind::Blob, |mode| mode.kind());
+                self.blob(path, entry.id, kind)
+            }
+            None if index.entry_by_path(path).is_some() => Err(Error::Unmerged { path: path.to_owned() }),
+            None => Ok(None),
+        }
+    }
+
+    fn read_worktree(&mut self, path: &BStr) -> Result<Option<Blob>, Error> {
+        use gix_filter::pipeline::convert::ToGitOutcome;
+
+        let (workdir, pipeline, index) = self.worktree.as_mut().expect("set for worktree locations");
+        let rela_path = gix_path::from_bstr(path);
+        let full_path = workdir.join(&rela_path);
+        let io_err = |source| Error::Io {
+            source,
+            path: full_path.clone(),
+        };
+        let md = match std::fs::symlink_metadata(&full_path) {
+            Ok(md) => md,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(io_err(err)),
+        };
+        let blob = if md.is_symlink() {
+            let target = std::fs::read_link(&full_path).map_err(io_err)?;
+            Blob {
+                data: gix_path::into_bstr(target).into_owned().into(),
+                kind: EntryKind::Link,
+            }
+        } else if md.is_file() {
+            let file = std::fs::File::open(&full_path).map_err(io_err)?;
+            let mut data = Vec::new();
+            match pipeline.convert_to_git(file, &rela_path, index)? {
+                ToGitOutcome::Unchanged(mut file) => {
+                    file.read_to_end(&mut data).map_err(io_err)?;
+                }
+                ToGitOutcome::Process(mut read) => {
+                    read.read_to_end(&mut data).map_err(io_err)?;
+                }
+                ToGitOutcome::Buffer(buf) => data.extend_from_slice(buf),
+            }
+            let kind = if gix_fs::is_executable(&md) {
+                EntryKind::BlobExecutable
+            } else {
+                EntryKind::Blob
+            };
+            Blob { data, kind }
+        } else {
+            return Err(Error::NotAFile { path: path.to_owned() });
+        };
+        Ok(Some(blob))
+    }
+
+    fn blob(&self, path: &BStr, id: gix_hash::ObjectId, kind: EntryKind) -> Result<Option<Blob>, Error> {
+        match kind {
+            EntryKind::Blob | EntryKind::BlobExecutable | EntryKind::Link => Ok(Some(Blob {
+                data: self.repo.find_blob(id)?.take_data(),
+                kind,
+            })),
+            EntryKind::Commit => Err(Error::Submodule { path: path.to_owned() }),
+            EntryKind::Tree => Err(Error::NotAFile { path: path.to_owned() }),
+        }
+    }
+
+    fn write(mut self) -> Result<Outcome, Error> {
+        // Deletions go first to make room for files that replace directories, and vice versa.
+        self.changes.sort_by_key(|change| change.new.is_some());
+
+        let mut tree_id = None;
+        if let Some(tree) = self.tree.take() {
+            let mut editor = self.repo.edit_tree(tree.id)?;
+            for change in &self.changes {
+                match &change.new {
+                    Some(blob) => {
+                        let id = self.repo.write_blob(&blob.data)?;
+                        editor.upsert(change.path.as_bstr(), blob.kind, id)?;
+                    }
+                    None => {
+                        editor.remove(change.path.as_bstr())?;
+                    }
+                }
+            }
+            tree_id = Some(editor.write()?.detach());
+        }
+
+        if let Some((workdir, pipeline, _)) = self.worktree.as_mut() {
+            for change in &self.changes {
+                write_to_worktree(workdir, pipeline, change)?;
+            }
+        }
+
+        if let Some(mut index) = self.index.take() {
+            for change in &self.changes {
+                let path = change.path.as_bstr();
+                index.remove_entries(|_, entry_path, _| entry_path == path);
+                let Some(blob) = &change.new else { continue };
+                let mode = gix_index::entry::Mode::from(gix_object::tree::EntryMode::from(blob.kind));
+                let id = self.repo.write_blob(&blob.data)?.detach();
+                match &change.conflict {
+                    Some(conflict) => {
+                        let ours = self.repo.write_blob(&conflict.ours)?.detach();
+                        let theirs = self.repo.write_blob(&conflict.theirs)?.detach();
+                        for (stage, id) in [
+                            (gix_index::entry::Stage::Base, conflict.base),
+                            (gix_index::entry::Stage::Ours, ours),
+                            (gix_index::entry::Stage::Theirs, theirs),
+                        ] {
+                            let flags = gix_index::entry::Flags::from_stage(stage);
+                            index.dangerously_push_entry(Default::default(), id, flags, mode, path);
+                        }
+                    }
+                    None => {
+                        let stat = match &self.worktree {
+                            Some((workdir, _, _)) => {
+                                let full_path = workdir.join(gix_path::from_bstr(path));
+                                gix_index::fs::Metadata::from_path_no_follow(&full_path)
+                                    .ok()
+                                    .and_then(|md| gix_index::entry::Stat::from_fs(&md).ok())
+                                    .unwrap_or_default()
+                            }
+                            None => Default::default(),
+                        };
+                        index.dangerously_push_entry(stat, id, gix_index::entry::Flags::empty(), mode, path);
+                    }
+                }
+            }
+            index.sort_entries();
+            index.write(Default::default())?;
+        }
+
+        Ok(Outcome {
+            tree_id,
+            paths: self.paths,
+            conflicts: self.conflicts,
+        })
+    }
+}
+
+fn write_to_worktree(workdir: &Path, pipeline: &mut crate::filter::Pipeline<'_>, change: &Change) -> Result<(), Error> {
+    use gix_filter::{driver::apply::MaybeDelayed, pipeline::convert::ToWorktreeOutcome};
+
+    let full_path = workdir.join(gix_path::from_bstr(change.path.as_bstr()));
+    let io_err = |source| Error::Io {
+        source,
+        path: full_path.clone(),
+    };
+    // Never write through existing files, which may be symbolic links.
+    match std::fs::symlink_metadata(&full_path) {
+        Ok(md) if md.is_dir() => {
+            return Err(Error::NotAFile {
+                path: change.path.clone(),
+            })
+        }
+        Ok(_) => std::fs::remove_file(&full_path).map_err(io_err)?,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {}
+        Err(err) => return Err(io_err(err)),
+    }
+
+    let Some(blob) = &change.new else {
+        let mut dir = full_path.parent();
+        while let Some(parent) = dir.filter(|dir| *dir != workdir) {
+            if std::fs::remove_dir(parent).is_err() {
+                break;
+            }
+            dir = parent.parent();
+        }
+        return Ok(());
+    };
+
+    if let Some(parent) = full_path.parent() {
+        std::fs::create_dir_all(parent).map_err(io_err)?;
+    }
+    if blob.kind == EntryKind::Link {
+        let target = gix_path::try_from_byte_slice(&blob.data).map_err(|_| Error::InvalidSymlinkTarget {
+            path: change.path.clone(),
+        })?;
+        gix_fs::symlink::create(target, &full_path).map_err(io_err)?;
+        return Ok(());
+    }
+
+    let mut file = std::fs::File::create(&full_path).map_err(io_err)?;
+    match pipeline.convert_to_worktree(
+        &blob.data,
+        change.path.as_bstr(),
+        gix_filter::driver::apply::Delay::Forbid,
+    )? {
+        ToWorktreeOutcome::Unchanged(buf) | ToWorktreeOutcome::Buffer(buf) => {
+            std::io::Write::write_all(&mut file, buf).map_err(io_err)?;
+        }
+        ToWorktreeOutcome::Process(MaybeDelayed::Immediate(mut read)) => {
+            std::io::copy(&mut read, &mut file).map_err(io_err)?;
+        }
+        ToWorktreeOutcome::Process(MaybeDelayed::Delayed(_)) => {
+            unreachable!("we forbid delaying the entry")
+        }
+    }
+    #[cfg(unix)]
+    if blob.kind == EntryKind::BlobExecutable {
+        use std::os::unix::fs::PermissionsExt;
+        let mut permissions = file.metadata().map_err(io_err)?.permissions();
+        permissions.set_mode(permissions.mode() | ((permissions.mode() & 0o444) >> 2));
+        file.set_permissions(permissions).map_err(io_err)?;
+    }
+    Ok(())
+}
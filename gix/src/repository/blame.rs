@@ -1,13 +1,22 @@
 use gix_hash::ObjectId;
 use gix_ref::bstr::BStr;
 
-use crate::{repository::blame_file, Repository};
+use crate::{
+    bstr::ByteSlice,
+    config::{cache::util::ApplyLeniency, tree::Blame},
+    repository::{blame_file, blame_ignore_revs},
+    Repository,
+};
 
 impl Repository {
     /// Produce a list of consecutive [`gix_blame::BlameEntry`] instances. Each `BlameEntry`
     /// corresponds to a hunk of consecutive lines of the file at `suspect:<file_path>` that got
     /// introduced by a specific commit.
     ///
+    /// If [`blame_file::Options::worktree`] is set, the file is read from the worktree instead and
+    /// converted to its representation in Git, with uncommitted lines attributed to the null id.
+    /// Note that unlike Git, untracked files can be blamed as well.
+    ///
     /// For details, see the documentation of [`gix_blame::file()`].
     pub fn blame_file(
         &self,
@@ -17,35 +26,191 @@ impl Repository {
     ) -> Result<gix_blame::Outcome, blame_file::Error> {
         let cache = self.commit_graph_if_enabled()?;
         let mut resource_cache = self.diff_resource_cache_for_tree_diff()?;
+        let options = self.blame_options(file_path, options)?;
+
+        let outcome = gix_blame::file(
+            &self.objects,
+            suspect.into(),
+            cache,
+            &mut resource_cache,
+            file_path,
+            options,
+        )?;
+
+        Ok(outcome)
+    }
+
+    /// Like [`blame_file()`](Self::blame_file()), but pass each [`gix_blame::BlameEntry`] to `delegate` as soon as
+    /// the commit that introduced it is known, instead of returning all of them at the end.
+    ///
+    /// Return [`ControlFlow::Break`](std::ops::ControlFlow::Break) from `delegate` to stop early, which
+    /// causes the blame to fail with [`gix_blame::Error::Cancelled`].
+    ///
+    /// For details, see the documentation of [`gix_blame::incremental()`].
+    pub fn blame_file_incremental(
+        &self,
+        file_path: &BStr,
+        suspect: impl Into<ObjectId>,
+        options: blame_file::Options,
+        delegate: impl FnMut(gix_blame::BlameEntry) -> std::ops::ControlFlow<()>,
+    ) -> Result<gix_blame::Outcome, blame_file::Error> {
+        let cache = self.commit_graph_if_enabled()?;
+        let mut resource_cache = self.diff_resource_cache_for_tree_diff()?;
+        let options = self.blame_options(file_path, options)?;
+
+        let outcome = gix_blame::incremental(
+            &self.objects,
+            suspect.into(),
+            cache,
+            &mut resource_cache,
+            file_path,
+            options,
+            delegate,
+        )?;
+
+        Ok(outcome)
+    }
+
+    /// Return the commits listed in the files configured in `blame.ignoreRevsFile`, whose changes should be attributed
+    /// to their parents when blaming.
+    ///
+    /// Like in Git, each line of these files holds a full object id, and everything after `#` is a comment.
+    /// Relative paths are relative to the worktree, or to the `.git` directory in bare repositories.
+    pub fn blame_ignore_revs(&self) -> Result<Vec<ObjectId>, blame_ignore_revs::Error> {
+        let config = &self.config.resolved;
+        let base = self.workdir().unwrap_or_else(|| self.git_dir());
+        let mut paths = Vec::new();
+        for value in config.strings(Blame::IGNORE_REVS_FILE).unwrap_or_default() {
+            // An empty value resets the list of files configured so far.
+            if value.is_empty() {
+                paths.clear();
+                continue;
+            }
+            let path = gix_config::Path::from(value);
+            let is_optional = path.is_optional;
+            let Some(path) = path
+                .interpolate(crate::config::cache::interpolate_context(
+                    self.install_dir().ok().as_deref(),
+                    self.config.home_dir().as_deref(),
+                ))
+                .map(std::borrow::Cow::into_owned)
+                .map(Some)
+                .with_leniency(self.config.lenient_config)?
+            else {
+                continue;
+            };
+            let path = base.join(path);
+            if is_optional && !path.is_file() {
+                continue;
+            }
+            paths.push(path);
+        }
+
+        let mut out = Vec::new();
+        for path in paths {
+            for id in self.blame_ignore_revs_from_file(&path)? {
+                if !out.contains(&id) {
+                    out.push(id);
+                }
+            }
+        }
+        Ok(out)
+    }
 
+    /// Return the commits listed in the file at `path`, in the format used by `blame.ignoreRevsFile`.
+    pub fn blame_ignore_revs_from_file(
+        &self,
+        path: &std::path::Path,
+    ) -> Result<Vec<ObjectId>, blame_ignore_revs::Error> {
+        use blame_ignore_revs::Error;
+
+        let content = std::fs::read(path).map_err(|source| Error::Io {
+            source,
+            path: path.to_owned(),
+        })?;
+        let mut out = Vec::new();
+        for line in content.lines() {
+            let line = line.find_byte(b'#').map_or(line, |pos| &line[..pos]).trim();
+            if line.is_empty() {
+                continue;
+            }
+            let id = ObjectId::from_hex(line).map_err(|_| Error::InvalidObjectName {
+                line: line.into(),
+                path: path.to_owned(),
+            })?;
+            out.push(self.find_object(id)?.peel_to_commit()?.id);
+        }
+        Ok(out)
+    }
+
+    /// Turn `options` into those of `gix_blame`, using the configuration for all values that aren't set.
+    fn blame_options(
+        &self,
+        file_path: &BStr,
+        options: blame_file::Options,
+    ) -> Result<gix_blame::Options, blame_file::Error> {
         let blame_file::Options {
             diff_algorithm,
             ranges,
             since,
             rewrites,
+            ignore_revs,
+            worktree,
+            moves_and_copies,
         } = options;
         let diff_algorithm = match diff_algorithm {
             Some(diff_algorithm) => diff_algorithm,
             None => self.diff_algorithm()?,
         };
+        let ignore_revs = match ignore_revs {
+            Some(ignore_revs) => ignore_revs,
+            None => self.blame_ignore_revs()?,
+        };
+        let worktree_blob = worktree.then(|| self.worktree_blob(file_path)).transpose()?;
 
-        let options = gix_blame::Options {
+        Ok(gix_blame::Options {
             diff_algorithm,
             ranges,
             since,
             rewrites,
             debug_track_path: false,
-        };
+            ignore_revs,
+            worktree_blob,
+            moves_and_copies,
+        })
+    }
 
-        let outcome = gix_blame::file(
-            &self.objects,
-            suspect.into(),
-            cache,
-            &mut resource_cache,
-            file_path,
-            options,
-        )?;
+    /// Read the file at `file_path` from the worktree and convert it to what would be stored in Git.
+    fn worktree_blob(&self, file_path: &BStr) -> Result<Vec<u8>, blame_file::Error> {
+        use std::io::Read;
 
-        Ok(outcome)
+        use gix_filter::pipeline::convert::ToGitOutcome;
+
+        let workdir = self.workdir().ok_or(blame_file::Error::MissingWorktree)?;
+        let rela_path = gix_path::from_bstr(file_path);
+        let path = workdir.join(&rela_path);
+        let read_error = |source| blame_file::Error::ReadWorktreeFile {
+            source,
+            path: path.clone(),
+        };
+        let metadata = std::fs::symlink_metadata(&path).map_err(read_error)?;
+        if metadata.is_symlink() {
+            let target = std::fs::read_link(&path).map_err(read_error)?;
+            return Ok(gix_path::into_bstr(target).into_owned().into());
+        }
+
+        let file = std::fs::File::open(&path).map_err(read_error)?;
+        let (mut pipeline, index) = self.filter_pipeline(None)?;
+        let mut buf = Vec::new();
+        match pipeline.convert_to_git(file, rela_path.as_ref(), &index)? {
+            ToGitOutcome::Unchanged(mut file) => {
+                file.read_to_end(&mut buf).map_err(read_error)?;
+            }
+            ToGitOutcome::Process(mut read) => {
+                read.read_to_end(&mut buf).map_err(read_error)?;
+            }
+            ToGitOutcome::Buffer(converted) => buf.extend_from_slice(converted),
+        }
+        Ok(buf)
     }
 }
@@ -1,4 +1,15 @@
-use crate::{config, Repository};
+#![cfg_attr(not(any(feature = "merge", feature = "blocking-network-client")), allow(dead_code))]
+use std::path::Path;
+
+use gix_index::entry::Stage;
+use gix_object::tree::EntryKind;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    config,
+    worktree::update,
+    Repository,
+};
 
 impl Repository {
     /// Return options that can be used to drive a low-level checkout operation.
@@ -11,4 +22,168 @@ impl Repository {
     ) -> Result<gix_worktree_state::checkout::Options, config::checkout_options::Error> {
         self.config.checkout_options(self, attributes_source)
     }
+
+    /// Return those of `paths` whose files in the worktree don't match their unconflicted entry in `index`,
+    /// or which are untracked files that would be overwritten.
+    /// Files that don't exist in the worktree are not considered changed.
+    pub(crate) fn worktree_changes(
+        &self,
+        index: &gix_index::State,
+        paths: &[BString],
+    ) -> Result<Vec<BString>, update::Error> {
+        // Hashing worktree files must not write them to the object database.
+        let repo = self.clone().with_object_memory();
+        let (mut pipeline, _) = repo.filter_pipeline(None)?;
+        let workdir = self.workdir().expect("only called with a worktree");
+        let mut out = Vec::new();
+        for path in paths {
+            let path = path.as_bstr();
+            let is_changed = match index.entry_by_path_and_stage(path, Stage::Unconflicted) {
+                Some(entry) if entry.mode == gix_index::entry::Mode::COMMIT => false,
+                Some(entry) => pipeline
+                    .worktree_file_to_object(path, index)?
+                    .is_some_and(|(id, kind, _md)| {
+                        id != entry.id || (kind == EntryKind::Link) != (entry.mode == gix_index::entry::Mode::SYMLINK)
+                    }),
+                None => match std::fs::symlink_metadata(workdir.join(gix_path::from_bstr(path))) {
+                    Ok(md) if md.is_dir() => {
+                        let mut prefix = path.to_owned();
+                        prefix.push(b'/');
+                        index.prefixed_entries(prefix.as_bstr()).is_none()
+                    }
+                    Ok(_) => true,
+                    Err(_) => false,
+                },
+            };
+            if is_changed {
+                out.push(path.to_owned());
+            }
+        }
+        Ok(out)
+    }
+
+    /// Write the unconflicted entries of `target` at `paths` to the worktree, and remove the files at `paths`
+    /// that have no such entry.
+    /// Return the written entries with their stat information.
+    pub(crate) fn update_worktree(
+        &self,
+        workdir: &Path,
+        target: &gix_index::State,
+        paths: &[BString],
+    ) -> Result<gix_index::State, update::Error> {
+        let mut checkout = gix_index::State::new(self.object_hash());
+        for path in paths {
+            match target.entry_by_path_and_stage(path.as_bstr(), Stage::Unconflicted) {
+                Some(entry) => checkout.dangerously_push_entry(
+                    Default::default(),
+                    entry.id,
+                    gix_index::entry::Flags::empty(),
+                    entry.mode,
+                    path.as_bstr(),
+                ),
+                None => remove_from_worktree(workdir, path.as_bstr())?,
+            }
+        }
+        checkout.sort_entries();
+
+        let mut options =
+            self.checkout_options(gix_worktree::stack::state::attributes::Source::WorktreeThenIdMapping)?;
+        options.overwrite_existing = true;
+        gix_worktree_state::checkout(
+            &mut checkout,
+            workdir,
+            self.objects.clone().into_arc().map_err(update::Error::OpenArcOdb)?,
+            &gix_features::progress::Discard,
+            &gix_features::progress::Discard,
+            &Default::default(),
+            options,
+        )?;
+        Ok(checkout)
+    }
+}
+
+/// Return the paths of all entries that differ between `a` and `b`, including their stages, in order and without duplicates.
+pub(crate) fn changed_paths(a: &gix_index::State, b: &gix_index::State) -> Vec<BString> {
+    fn key<'a>(state: &'a gix_index::State, entry: &gix_index::Entry) -> (&'a BStr, u32) {
+        (entry.path(state), entry.stage_raw())
+    }
+    let (mut a_entries, mut b_entries) = (a.entries().iter().peekable(), b.entries().iter().peekable());
+    let mut out = Vec::<BString>::new();
+    loop {
+        let path = match (a_entries.peek().copied(), b_entries.peek().copied()) {
+            (None, None) => break,
+            (Some(a_entry), Some(b_entry)) => match key(a, a_entry).cmp(&key(b, b_entry)) {
+                std::cmp::Ordering::Equal => {
+                    a_entries.next();
+                    b_entries.next();
+                    if a_entry.id == b_entry.id && a_entry.mode == b_entry.mode {
+                        continue;
+                    }
+                    a_entry.path(a)
+                }
+                std::cmp::Ordering::Less => {
+                    a_entries.next();
+                    a_entry.path(a)
+                }
+                std::cmp::Ordering::Greater => {
+                    b_entries.next();
+                    b_entry.path(b)
+                }
+            },
+            (Some(a_entry), None) => {
+                a_entries.next();
+                a_entry.path(a)
+            }
+            (None, Some(b_entry)) => {
+                b_entries.next();
+                b_entry.path(b)
+            }
+        };
+        if out.last().map(|last| last.as_bstr()) != Some(path) {
+            out.push(path.to_owned());
+        }
+    }
+    out
+}
+
+/// Set the stat information of unconflicted entries in `index` to the one of the `written` entries,
+/// or to the one of the same unchanged entry in `previous`, to know that they match the worktree.
+pub(crate) fn update_stat(index: &mut gix_index::State, written: &gix_index::State, previous: &gix_index::State) {
+    for (entry, path) in index.entries_mut_with_paths() {
+        if entry.stage() != Stage::Unconflicted {
+            continue;
+        }
+        if let Some(written) = written.entry_by_path_and_stage(path, Stage::Unconflicted) {
+            entry.stat = written.stat;
+        } else if let Some(previous) = previous
+            .entry_by_path_and_stage(path, Stage::Unconflicted)
+            .filter(|previous| previous.id == entry.id && previous.mode == entry.mode)
+        {
+            entry.stat = previous.stat;
+        }
+    }
+}
+
+/// Remove the file at `rela_path`, along with the directories leading to it that are empty then.
+pub(crate) fn remove_from_worktree(workdir: &Path, rela_path: &BStr) -> Result<(), update::Error> {
+    let path = workdir.join(gix_path::from_bstr(rela_path));
+    remove_file_if_present(&path).map_err(|source| update::Error::Remove {
+        source,
+        path: path.clone(),
+    })?;
+    let mut dir = path.parent();
+    while let Some(parent) = dir.filter(|dir| *dir != workdir) {
+        if std::fs::remove_dir(parent).is_err() {
+            break;
+        }
+        dir = parent.parent();
+    }
+    Ok(())
+}
+
+pub(crate) fn remove_file_if_present(path: &Path) -> std::io::Result<()> {
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        res => res,
+    }
 }
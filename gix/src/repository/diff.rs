@@ -64,8 +64,8 @@ impl Repository {
         let new_tree = new_tree.into().unwrap_or(&empty_tree);
         let mut out = Vec::new();
         gix_diff::tree_with_rewrites(
-            TreeRefIter::from_bytes(&old_tree.data),
-            TreeRefIter::from_bytes(&new_tree.data),
+            TreeRefIter::from_bytes(&old_tree.data, old_tree.id.kind()),
+            TreeRefIter::from_bytes(&new_tree.data, new_tree.id.kind()),
             &mut cache,
             &mut Default::default(),
             &self.objects,
@@ -87,4 +87,114 @@ impl Repository {
             gix_diff::blob::pipeline::WorktreeRoots::default(),
         )
     }
+
+    /// Turn `change`, as obtained by a [tree diff](Self::diff_tree_to_tree()), into a patch of a single file whose content
+    /// is diffed line by line as configured by `options`, using `resource_cache` to obtain diffable data and the
+    /// algorithm configured for its path.
+    ///
+    /// Binary files are represented by their literal data if `with_binary_data` is `true`, like `git diff --binary` does,
+    /// and [without data](gix_diff::patch::Content::BinaryWithoutData) otherwise.
+    /// Changes to submodules are shown as changes to a single `Subproject commit <id>` line, just like Git does.
+    /// Object ids are abbreviated unless the patch contains binary data.
+    ///
+    /// Note that `resource_cache` should be [cleared](gix_diff::blob::Platform::clear_resource_cache_keep_allocation())
+    /// between calls to keep memory usage in check.
+    #[cfg(feature = "apply")]
+    pub fn diff_change_to_patch_file(
+        &self,
+        change: &gix_diff::tree_with_rewrites::Change,
+        resource_cache: &mut gix_diff::blob::Platform,
+        options: gix_diff::patch::hunks::Options,
+        with_binary_data: bool,
+    ) -> Result<gix_diff::patch::File, crate::repository::diff_change_to_patch_file::Error> {
+        use gix_diff::{
+            blob::platform::prepare_diff::Operation,
+            patch::{self, Kind},
+            tree_with_rewrites::Change,
+        };
+        use gix_hash::ObjectId;
+
+        use crate::{bstr::BString, ext::ObjectIdExt};
+
+        let (source_mode, source_id) = change.source_entry_mode_and_id();
+        let (mode, id) = change.entry_mode_and_id();
+        let (kind, similarity) = match change {
+            Change::Addition { .. } => (Kind::Addition, None),
+            Change::Deletion { .. } => (Kind::Deletion, None),
+            Change::Modification { .. } => (Kind::Modification, None),
+            Change::Rewrite { diff, copy, .. } => (
+                if *copy { Kind::Copy } else { Kind::Rename },
+                Some(diff.map_or(100, |diff| (diff.similarity * 100.0) as u8)),
+            ),
+        };
+        let old_id = (kind != Kind::Addition).then(|| source_id.to_owned());
+        let new_id = (kind != Kind::Deletion).then(|| id.to_owned());
+
+        let content = if mode.is_commit() || source_mode.is_commit() {
+            let submodule = |id: Option<ObjectId>| {
+                id.map(|id| format!("Subproject commit {id}\n").into_bytes())
+                    .unwrap_or_default()
+            };
+            let old = submodule(old_id.filter(|_| source_mode.is_commit()));
+            let new = submodule(new_id.filter(|_| mode.is_commit()));
+            patch::Content::Hunks(patch::hunks(&old, &new, options))
+        } else {
+            resource_cache.set_resource_by_change(change.to_ref(), &self.objects)?;
+            let prepared = resource_cache.prepare_diff()?;
+            let algorithm = match prepared.operation {
+                Operation::InternalDiff { algorithm } => Some(algorithm),
+                // External diff programs are for display only, so patches are always created internally.
+                Operation::ExternalCommand { .. } => Some(options.algorithm),
+                Operation::SourceOrDestinationIsBinary => None,
+            };
+            match algorithm {
+                Some(algorithm) => patch::Content::Hunks(patch::hunks(
+                    prepared.old.data.as_slice().unwrap_or_default(),
+                    prepared.new.data.as_slice().unwrap_or_default(),
+                    patch::hunks::Options { algorithm, ..options },
+                )),
+                None if with_binary_data => {
+                    let data =
+                        |id: Option<ObjectId>| -> Result<Vec<u8>, crate::repository::diff_change_to_patch_file::Error> {
+                            Ok(match id {
+                                Some(id) => self.find_blob(id)?.take_data(),
+                                None => Vec::new(),
+                            })
+                        };
+                    patch::Content::Binary {
+                        forward: patch::Binary::Literal(data(new_id)?),
+                        reverse: Some(patch::Binary::Literal(data(old_id)?)),
+                    }
+                }
+                None => patch::Content::BinaryWithoutData,
+            }
+        };
+
+        // Like Git, abbreviate object ids, but keep them complete for binary patches so they can be verified.
+        let is_binary = matches!(content, patch::Content::Binary { .. });
+        let hex = |id: Option<ObjectId>, other: Option<ObjectId>| -> BString {
+            match id {
+                Some(id) if is_binary => id.to_string().into(),
+                Some(id) => id.attach(self).shorten_or_id().to_string().into(),
+                None => {
+                    let len = match other {
+                        Some(other) if !is_binary => other.attach(self).shorten_or_id().hex_len(),
+                        _ => self.object_hash().len_in_hex(),
+                    };
+                    "0".repeat(len).into()
+                }
+            }
+        };
+        Ok(patch::File {
+            old_path: (kind != Kind::Addition).then(|| change.source_location().to_owned()),
+            new_path: (kind != Kind::Deletion).then(|| change.location().to_owned()),
+            old_mode: (kind != Kind::Addition).then_some(source_mode),
+            new_mode: (kind != Kind::Deletion).then_some(mode),
+            kind,
+            similarity,
+            old_id: Some(hex(old_id, new_id)),
+            new_id: Some(hex(new_id, old_id)),
+            content,
+        })
+    }
 }
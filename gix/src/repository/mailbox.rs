@@ -0,0 +1,247 @@
+use std::io::Write;
+
+use gix_diff::patch;
+use gix_hash::ObjectId;
+
+use crate::{
+    apply::Location,
+    bstr::{BStr, BString, ByteSlice},
+    mailbox::{apply, format, Message, Patch},
+    repository::{apply_mailbox, format_patch},
+    Repository,
+};
+
+impl Repository {
+    /// Turn each commit of the revision range `spec` into an email with a patch of its changes compared to its first parent,
+    /// configured by `options`, similar to `git format-patch --stdout <spec>`.
+    ///
+    /// `spec` is either a range like `since..until`, or a single revision `since` which is the same as `since..HEAD`.
+    /// Patches are ordered from the oldest to the newest commit, see [`format_patch()`](Self::format_patch()) for details.
+    pub fn format_patch_range<'a>(
+        &self,
+        spec: impl Into<&'a BStr>,
+        options: format::Options,
+    ) -> Result<Vec<Patch>, format_patch::Error> {
+        let spec = spec.into();
+        let (since, until) = match self.rev_parse(spec)?.detach() {
+            gix_revision::Spec::Include(since) => (since, self.head_id()?.detach()),
+            gix_revision::Spec::Range { from, to } => (from, to),
+            _ => return Err(format_patch::Error::UnsupportedSpec { spec: spec.into() }),
+        };
+        let mut commits = self
+            .rev_walk(Some(until))
+            .with_hidden(Some(since))
+            .all()?
+            .map(|info| info.map(|info| info.id))
+            .collect::<Result<Vec<_>, _>>()?;
+        commits.reverse();
+        self.format_patch(commits, options)
+    }
+
+    /// Turn each of `commits` into an email with a patch of its changes compared to its first parent, configured by `options`,
+    /// similar to `git format-patch --stdout`.
+    ///
+    /// Merge commits are skipped, just like commits without changes, and patches are numbered only after skipping them.
+    /// Renames are detected as configured in `diff.renames`, and binary files are written as binary patches.
+    pub fn format_patch(
+        &self,
+        commits: impl IntoIterator<Item = impl Into<ObjectId>>,
+        options: format::Options,
+    ) -> Result<Vec<Patch>, format_patch::Error> {
+        let diff_options = crate::diff::Options::from_configuration(&self.config)?;
+        let algorithm = self.config.diff_algorithm()?;
+        let hunk_options = patch::hunks::Options {
+            algorithm,
+            context_lines: options.context_lines,
+            ..Default::default()
+        };
+        let mut resource_cache = self.diff_resource_cache_for_tree_diff()?;
+
+        let mut commits_with_files = Vec::new();
+        for id in commits {
+            let commit = self.find_commit(id)?;
+            if commit.parent_ids().nth(1).is_some() {
+                continue;
+            }
+            let parent_tree = match commit.parent_ids().next() {
+                Some(id) => id.object()?.peel_to_tree()?,
+                None => self.empty_tree(),
+            };
+            let changes = self.diff_tree_to_tree(&parent_tree, &commit.tree()?, diff_options)?;
+            let mut files = Vec::new();
+            for change in changes.iter().filter(|change| !change.entry_mode().is_tree()) {
+                files.push(self.diff_change_to_patch_file(change, &mut resource_cache, hunk_options, true)?);
+                resource_cache.clear_resource_cache_keep_allocation();
+            }
+            if files.is_empty() {
+                continue;
+            }
+            files.sort_by(|a, b| a.path().cmp(b.path()));
+            commits_with_files.push((commit, files));
+        }
+        if commits_with_files.is_empty() {
+            return Ok(Vec::new());
+        }
+        let total = options.start_number + commits_with_files.len() - 1;
+        let numbered = options.numbered.unwrap_or(commits_with_files.len() > 1);
+
+        let mut patches = Vec::new();
+        for (idx, (commit, files)) in commits_with_files.into_iter().enumerate() {
+            let number = options.start_number + idx;
+            let (title, body) = split_message(commit.message_raw()?);
+            let prefix = match (numbered, options.subject_prefix.is_empty()) {
+                (true, false) => format!("[{} {number}/{total}] ", options.subject_prefix),
+                (true, true) => format!("[{number}/{total}] "),
+                (false, false) => format!("[{}] ", options.subject_prefix),
+                (false, true) => String::new(),
+            };
+            let author = commit.author()?;
+            let mut out = Vec::new();
+            writeln!(out, "From {} Mon Sep 17 00:00:00 2001", commit.id)?;
+            format::write_from(author.name, author.email, &mut out);
+            out.push(b'\n');
+            writeln!(
+                out,
+                "Date: {}",
+                author.time()?.format_or_unix(gix_date::time::format::GIT_RFC2822)
+            )?;
+            format::write_subject(&prefix, title.as_ref(), &mut out);
+            out.push(b'\n');
+            if !commit.data.is_ascii() {
+                out.extend_from_slice(
+                    b"MIME-Version: 1.0\nContent-Type: text/plain; charset=UTF-8\nContent-Transfer-Encoding: 8bit\n",
+                );
+            }
+            out.push(b'\n');
+            out.extend_from_slice(&body);
+            out.extend_from_slice(b"---\n");
+            let stats: Vec<_> = files.iter().map(patch::File::stat).collect();
+            patch::stat::write(&stats, 72, &mut out)?;
+            patch::stat::write_summary(&files, &mut out)?;
+            out.push(b'\n');
+            for file in &files {
+                file.write_to(&mut out)?;
+            }
+            if let Some(signature) = &options.signature {
+                out.extend_from_slice(b"-- \n");
+                out.extend_from_slice(signature);
+                out.extend_from_slice(b"\n\n");
+            }
+            patches.push(Patch {
+                commit_id: commit.id,
+                number,
+                subject: title,
+                data: out.into(),
+            });
+        }
+        Ok(patches)
+    }
+
+    /// Create a commit on top of `HEAD` for each of `messages`, typically obtained with [`mailbox::parse()`](crate::mailbox::parse()),
+    /// by applying its patch as configured by `options`, similar to `git am`.
+    ///
+    /// The author and the message of each commit are taken from the email, while the committer is configured as usual.
+    /// If there is a worktree, the index and the worktree are updated as well, which requires all files touched by a
+    /// patch to be unchanged in the index and the worktree compared to `HEAD`.
+    ///
+    /// Patches are applied one at a time, so if one fails, the commits of all previous patches are kept.
+    pub fn apply_mailbox(
+        &self,
+        messages: &[Message],
+        options: apply::Options,
+    ) -> Result<apply::Outcome, apply_mailbox::Error> {
+        let mailmap = options.use_mailmap.then(|| self.open_mailmap());
+        let apply_options = crate::apply::Options {
+            three_way: options.three_way,
+            ..Default::default()
+        };
+        let mut out = apply::Outcome::default();
+        for (index, message) in messages.iter().enumerate() {
+            let files = patch::parse(&message.patch, Default::default())
+                .map_err(|source| apply_mailbox::Error::ParsePatch { index, source })?;
+            if files.is_empty() {
+                return Err(apply_mailbox::Error::EmptyPatch { index });
+            }
+
+            let parent = self.head()?.try_peel_to_id()?.map(crate::Id::detach);
+            let head_tree = match parent {
+                Some(id) => self.find_commit(id)?.tree()?,
+                None => self.empty_tree(),
+            };
+            let applied = self
+                .apply_patch(&files, Location::Tree(head_tree.id), apply_options)
+                .map_err(|source| apply_mailbox::Error::Apply { index, source })?;
+            if !applied.conflicts.is_empty() {
+                return Err(apply_mailbox::Error::Conflict {
+                    index,
+                    paths: applied.conflicts,
+                });
+            }
+            let tree_id = applied.tree_id.expect("always set when applying to a tree");
+
+            if self.workdir().is_some() {
+                let index_file = self.index_or_empty()?;
+                for path in &applied.paths {
+                    let in_head = head_tree
+                        .lookup_entry(path.split(|b| *b == b'/'))?
+                        .map(|entry| (entry.object_id(), entry.mode()));
+                    let in_index = index_file
+                        .entry_by_path(path.as_bstr())
+                        .and_then(|entry| Some((entry.id, entry.mode.to_tree_entry_mode()?)));
+                    if in_head != in_index {
+                        return Err(apply_mailbox::Error::IndexDoesNotMatchHead { path: path.clone() });
+                    }
+                }
+                self.apply_patch(&files, Location::WorktreeAndIndex, apply_options)
+                    .map_err(|source| apply_mailbox::Error::Apply { index, source })?;
+            }
+
+            let mut author = gix_actor::Signature {
+                name: message.author.name.clone(),
+                email: message.author.email.clone(),
+                time: message.time.unwrap_or_else(gix_date::Time::now_local_or_utc),
+            };
+            if let Some(mailmap) = &mailmap {
+                let mut time_buf = Default::default();
+                if let Some(resolved) = mailmap.try_resolve(author.to_ref(&mut time_buf)) {
+                    author = resolved;
+                }
+            }
+            let committer = self.committer().ok_or(crate::commit::Error::CommitterMissing)??;
+            let mut time_buf = Default::default();
+            let commit_message = message.commit_message();
+            let id = self.commit_as(
+                committer,
+                author.to_ref(&mut time_buf),
+                "HEAD",
+                commit_message.to_str_lossy(),
+                tree_id,
+                parent,
+            )?;
+            out.commits.push(id.detach());
+        }
+        Ok(out)
+    }
+}
+
+/// Split `message` into its title, the first paragraph joined into a single line, and the remaining body,
+/// which ends with a newline unless it's empty.
+fn split_message(message: &BStr) -> (BString, Vec<u8>) {
+    let mut lines = message.lines().skip_while(|line| line.trim().is_empty()).peekable();
+    let mut title = BString::default();
+    while let Some(line) = lines.next_if(|line| !line.trim().is_empty()) {
+        if !title.is_empty() {
+            title.push(b' ');
+        }
+        title.extend_from_slice(line.trim());
+    }
+    let mut body = Vec::new();
+    for line in lines.skip_while(|line| line.trim().is_empty()) {
+        body.extend_from_slice(line.trim_end());
+        body.push(b'\n');
+    }
+    while body.ends_with(b"\n\n") {
+        body.pop();
+    }
+    (title, body)
+}
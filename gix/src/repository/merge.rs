@@ -7,8 +7,8 @@ use crate::{
     config::{cache::util::ApplyLeniencyDefault, tree},
     prelude::ObjectIdExt,
     repository::{
-        blob_merge_options, merge_commits, merge_resource_cache, merge_trees, tree_merge_options, virtual_merge_base,
-        virtual_merge_base_with_graph,
+        blob_merge_options, merge_commits, merge_commits_octopus, merge_resource_cache, merge_trees,
+        tree_merge_options, virtual_merge_base, virtual_merge_base_with_graph,
     },
     Repository,
 };
@@ -58,6 +58,7 @@ impl Repository {
         let filter = gix_merge::blob::Pipeline::new(worktree_roots, filter, self.config.merge_pipeline_options()?);
         let options = gix_merge::blob::platform::Options {
             default_driver: self.config.resolved.string(&tree::Merge::DEFAULT).map(Cow::into_owned),
+            rerere: self.rerere_store()?,
         };
         let drivers = self.config.merge_drivers()?;
         Ok(gix_merge::blob::Platform::new(filter, mode, attrs, drivers, options))
@@ -85,6 +86,7 @@ impl Repository {
                         .unwrap_or_default(),
                     marker_size: text::Conflict::DEFAULT_MARKER_SIZE.try_into().unwrap(),
                 },
+                whitespace: Default::default(),
             },
         })
     }
@@ -142,6 +144,9 @@ impl Repository {
     ) -> Result<crate::merge::tree::Outcome<'_>, merge_trees::Error> {
         let mut diff_cache = self.diff_resource_cache_for_tree_diff()?;
         let mut blob_merge = self.merge_resource_cache(Default::default())?;
+        if let Some(mode) = options.filter_mode() {
+            blob_merge.set_filter_mode(mode);
+        }
         let gix_merge::tree::Outcome {
             tree,
             conflicts,
@@ -196,6 +201,9 @@ impl Repository {
     ) -> Result<crate::merge::commit::Outcome<'_>, merge_commits::Error> {
         let mut diff_cache = self.diff_resource_cache_for_tree_diff()?;
         let mut blob_merge = self.merge_resource_cache(Default::default())?;
+        if let Some(mode) = options.tree_merge().filter_mode() {
+            blob_merge.set_filter_mode(mode);
+        }
         let commit_graph = self.commit_graph_if_enabled()?;
         let mut graph = self.revision_graph(commit_graph.as_ref());
         let gix_merge::commit::Outcome {
@@ -238,6 +246,64 @@ impl Repository {
         })
     }
 
+    /// Merge all `their_commits` into `our_commit` one after another to yield a tree for a merge commit with many parents,
+    /// like `git merge` does when merging more than one commit, which is known as *octopus merge*.
+    /// No change to the worktree or index is made, but objects may be written to the object database as merge results
+    /// and intermediate trees are stored.
+    /// If these changes should not be observable outside of this instance, consider [enabling object memory](Self::with_object_memory).
+    ///
+    /// Commits that are already contained in the merge result are skipped, and commits are fast-forwarded to as long as
+    /// no actual merge was needed, which is why the [parents of the merge commit](crate::merge::octopus::Outcome::parents)
+    /// are returned.
+    /// The merge fails if not all commits share a [common ancestor](Self::merge_base_octopus_with_graph()), unless this
+    /// is explicitly allowed, or if merging any of the commits causes a conflict that would need to be resolved by hand.
+    ///
+    /// `labels` are typically chosen to identify the ref or name for `our_commit`, with the other labels being set
+    /// automatically for each merged commit.
+    ///
+    /// `options` should be initialized with [`Repository::tree_merge_options().into()`](Self::tree_merge_options()).
+    ///
+    /// ### Performance
+    ///
+    /// It's highly recommended to [set an object cache](Repository::compute_object_cache_size_for_tree_diffs)
+    /// to avoid extracting the same object multiple times.
+    pub fn merge_commits_octopus(
+        &self,
+        our_commit: impl Into<gix_hash::ObjectId>,
+        their_commits: impl IntoIterator<Item = impl Into<gix_hash::ObjectId>>,
+        labels: gix_merge::blob::builtin_driver::text::Labels<'_>,
+        options: crate::merge::octopus::Options,
+    ) -> Result<crate::merge::octopus::Outcome<'_>, merge_commits_octopus::Error> {
+        let their_commits: Vec<_> = their_commits.into_iter().map(Into::into).collect();
+        let mut diff_cache = self.diff_resource_cache_for_tree_diff()?;
+        let mut blob_merge = self.merge_resource_cache(Default::default())?;
+        if let Some(mode) = options.tree_merge().filter_mode() {
+            blob_merge.set_filter_mode(mode);
+        }
+        let commit_graph = self.commit_graph_if_enabled()?;
+        let mut graph = self.revision_graph(commit_graph.as_ref());
+        let gix_merge::commit::octopus::Outcome {
+            tree_id,
+            parents,
+            conflicts,
+        } = gix_merge::commit::octopus(
+            our_commit.into(),
+            &their_commits,
+            labels,
+            &mut graph,
+            &mut diff_cache,
+            &mut blob_merge,
+            self,
+            &mut |id| id.to_owned().attach(self).shorten_or_id().to_string(),
+            options.into(),
+        )?;
+        Ok(crate::merge::octopus::Outcome {
+            tree_id: tree_id.attach(self),
+            parents: parents.into_iter().map(|id| id.attach(self)).collect(),
+            conflicts,
+        })
+    }
+
     /// Create a single virtual merge-base by merging all `merge_bases` into one.
     /// If the list is empty, an error will be returned as the histories are then unrelated.
     /// If there is only one commit in the list, it is returned directly with this case clearly marked in the outcome.
@@ -281,6 +347,9 @@ impl Repository {
 
         let mut diff_cache = self.diff_resource_cache_for_tree_diff()?;
         let mut blob_merge = self.merge_resource_cache(Default::default())?;
+        if let Some(mode) = options.filter_mode() {
+            blob_merge.set_filter_mode(mode);
+        }
 
         let gix_merge::commit::virtual_merge_base::Outcome {
             virtual_merge_bases,
@@ -0,0 +1,339 @@
+use gix_hash::ObjectId;
+use gix_index::entry::Stage;
+use gix_merge::{
+    blob::builtin_driver::text::{Conflict, Labels},
+    rerere::merge_rr,
+};
+use gix_object::tree::EntryKind;
+use gix_ref::transaction::PreviousValue;
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    ext::ObjectIdExt,
+    merge::worktree::{Options, Outcome},
+    repository::{
+        abort_merge,
+        checkout::{changed_paths, remove_file_if_present, update_stat},
+        finish_merge, merge_into_worktree,
+    },
+    Id, Repository,
+};
+
+/// The files in the git directory that record a merge in progress, in addition to the `AUTO_MERGE` reference.
+const MERGE_STATE_FILES: [&str; 4] = ["MERGE_HEAD", "MERGE_MSG", "MERGE_MODE", "MERGE_RR"];
+
+impl Repository {
+    /// Merge `their_commit` into the commit `HEAD` points to and write the result into the index and the worktree,
+    /// like `git merge --no-commit --no-ff` does, for the merge to be [finished](Self::finish_merge())
+    /// or [aborted](Self::abort_merge()) later.
+    ///
+    /// The merge is performed like [`merge_commits()`](Self::merge_commits()) does, and `labels` are used the same way,
+    /// with *our* side being labelled `HEAD` and *their* side being labelled with the abbreviated id of `their_commit`
+    /// if unset. Files with unresolved conflicts, as determined by
+    /// [`Options::with_treat_as_unresolved()`](crate::merge::worktree::Options::with_treat_as_unresolved()),
+    /// are written with conflict markers and added to the index with their conflicting stages.
+    /// Then `MERGE_HEAD`, `MERGE_MSG` and `MERGE_MODE` are written, `ORIG_HEAD` is set to `HEAD`,
+    /// and `AUTO_MERGE` is set to the merged tree if there are unresolved conflicts.
+    ///
+    /// If [rerere is enabled](Self::rerere_store()), conflicts are resolved with recorded resolutions if possible,
+    /// and the conflicts of files that remain unresolved are recorded in the store and in `MERGE_RR`,
+    /// for their resolution to be recorded when the merge is [finished](Self::finish_merge()).
+    ///
+    /// Like in Git, nothing is changed if the index doesn't match `HEAD`, or if the merge would change files with
+    /// local changes in the worktree, or overwrite untracked files.
+    /// Merging a commit that is already contained in `HEAD` fails, and no fast-forward is performed,
+    /// so the merge always ends with a merge commit.
+    pub fn merge_into_worktree(
+        &self,
+        their_commit: impl Into<ObjectId>,
+        labels: Labels<'_>,
+        options: Options,
+    ) -> Result<Outcome<'_>, merge_into_worktree::Error> {
+        use merge_into_worktree::Error;
+
+        let workdir = self.workdir().ok_or(Error::MissingWorktree)?;
+        if let Some(state) = self.state() {
+            return Err(Error::InProgress { state });
+        }
+        let their_commit = their_commit.into();
+        let our_commit = self.head_id()?.detach();
+        match self.merge_base(our_commit, their_commit) {
+            Ok(base) if base == their_commit => {
+                return Err(Error::AlreadyUpToDate {
+                    their_commit_id: their_commit,
+                })
+            }
+            Ok(_) | Err(crate::repository::merge_base::Error::NotFound { .. }) => {}
+            Err(err) => return Err(err.into()),
+        }
+
+        let head_index = self.index_from_tree(&self.find_commit(our_commit)?.tree_id()?)?;
+        let index = self.index_or_empty()?;
+        let staged = changed_paths(&index, &head_index);
+        if !staged.is_empty() {
+            return Err(Error::LocalChanges { paths: staged });
+        }
+
+        let (commit_options, treat_as_unresolved, message) = options.into_parts();
+        let their_name = their_commit.attach(self).shorten_or_id().to_string();
+        let labels = Labels {
+            current: labels.current.or(Some("HEAD".into())),
+            other: labels.other.or(Some(their_name.as_str().into())),
+            ..labels
+        };
+        let mut merge = self.merge_commits(our_commit, their_commit, labels, commit_options)?;
+        if merge.tree_merge.failed_on_first_unresolved_conflict {
+            return Err(Error::FailedOnFirstUnresolvedConflict);
+        }
+        let tree_id = merge.tree_merge.tree.write()?;
+        let merged_index = self.index_from_tree(&tree_id)?;
+        let changes = changed_paths(&head_index, &merged_index);
+        let local_changes = self.worktree_changes(&head_index, &changes)?;
+        if !local_changes.is_empty() {
+            return Err(Error::LocalChanges { paths: local_changes });
+        }
+
+        let written = self.update_worktree(workdir, &merged_index, &changes)?;
+        let mut new_index = merged_index;
+        merge.tree_merge.index_changed_after_applying_conflicts(
+            &mut new_index,
+            treat_as_unresolved,
+            crate::merge::tree::apply_index_entries::RemovalMode::Prune,
+        );
+        update_stat(&mut new_index, &written, &index);
+        new_index.write(Default::default())?;
+
+        let unresolved = unmerged_paths(&new_index);
+        if let Some(store) = self.rerere_store()? {
+            let mut entries = Vec::new();
+            for path in &unresolved {
+                let Ok(data) = std::fs::read(workdir.join(gix_path::from_bstr(path.as_bstr()))) else {
+                    continue;
+                };
+                if let Some(id) = store.record_conflict(&data, Conflict::DEFAULT_MARKER_SIZE)? {
+                    entries.push(merge_rr::Entry { id, path: path.clone() });
+                }
+            }
+            if !entries.is_empty() {
+                let mut data = Vec::new();
+                merge_rr::encode(&entries, &mut data);
+                let path = self.git_dir().join("MERGE_RR");
+                std::fs::write(&path, data).map_err(|source| Error::Io { source, path })?;
+            }
+        }
+        self.reference("ORIG_HEAD", our_commit, PreviousValue::Any, "merge: updating ORIG_HEAD")?;
+        if !unresolved.is_empty() {
+            self.reference("AUTO_MERGE", tree_id, PreviousValue::Any, "merge: updating AUTO_MERGE")?;
+        }
+        let mut message = message.unwrap_or_else(|| format!("Merge commit '{their_commit}'").into());
+        if !message.ends_with(b"\n") {
+            message.push(b'\n');
+        }
+        if !unresolved.is_empty() {
+            message.extend_from_slice(b"\n# Conflicts:\n");
+            for path in &unresolved {
+                message.extend_from_slice(b"#\t");
+                message.extend_from_slice(path);
+                message.push(b'\n');
+            }
+        }
+        // `no-ff` makes sure that `git commit` doesn't turn the merge into a fast-forward either.
+        for (name, content) in [
+            ("MERGE_HEAD", format!("{their_commit}\n").into_bytes()),
+            ("MERGE_MODE", b"no-ff".to_vec()),
+            ("MERGE_MSG", message.into()),
+        ] {
+            let path = self.git_dir().join(name);
+            std::fs::write(&path, content).map_err(|source| Error::Io { source, path })?;
+        }
+
+        Ok(Outcome {
+            tree_id,
+            conflicts: merge.tree_merge.conflicts,
+            unresolved,
+        })
+    }
+
+    /// Create a merge commit for the merge in progress, as started by [`merge_into_worktree()`](Self::merge_into_worktree()),
+    /// with the tree of the current index and `message`, or the message in `MERGE_MSG` without comments if `None`,
+    /// and update `HEAD` to point to it, like `git merge --continue` does. Return the id of the new commit.
+    ///
+    /// The parents of the commit are `HEAD` and all commits in `MERGE_HEAD`, and all conflicts in the index must have
+    /// been resolved.
+    /// If [rerere is enabled](Self::rerere_store()), the resolutions of the files in `MERGE_RR` are recorded
+    /// if they don't contain conflict markers anymore.
+    /// Afterwards, the merge state is removed.
+    pub fn finish_merge(&self, message: Option<&str>) -> Result<Id<'_>, finish_merge::Error> {
+        use finish_merge::Error;
+
+        let merge_head_path = self.git_dir().join("MERGE_HEAD");
+        let merge_heads = match std::fs::read(&merge_head_path) {
+            Ok(data) => data,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Err(Error::NoMergeInProgress),
+            Err(source) => {
+                return Err(Error::Io {
+                    source,
+                    path: merge_head_path,
+                })
+            }
+        };
+        let merge_heads = merge_heads
+            .lines()
+            .map(ByteSlice::trim)
+            .filter(|line| !line.is_empty())
+            .map(|line| ObjectId::from_hex(line).map_err(|_| Error::InvalidMergeHead { line: line.into() }))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let index = self.index_or_empty()?;
+        let unmerged = unmerged_paths(&index);
+        if !unmerged.is_empty() {
+            return Err(Error::Unmerged { paths: unmerged });
+        }
+        let mut editor = self.edit_tree(ObjectId::empty_tree(self.object_hash()))?;
+        for entry in index.entries() {
+            if entry.flags.contains(gix_index::entry::Flags::INTENT_TO_ADD) {
+                continue;
+            }
+            let kind = entry
+                .mode
+                .to_tree_entry_mode()
+                .map_or(EntryKind::Blob, |mode| mode.kind());
+            editor.upsert(entry.path(&index), kind, entry.id)?;
+        }
+        let tree_id = editor.write()?;
+
+        let message = match message {
+            Some(message) => message.to_owned(),
+            None => {
+                let path = self.git_dir().join("MERGE_MSG");
+                let message = std::fs::read(&path).map_err(|source| Error::Io { source, path })?;
+                strip_comments(message.as_bstr())
+            }
+        };
+        if message.trim().is_empty() {
+            return Err(Error::EmptyMessage);
+        }
+        let head = self.head_id()?.detach();
+        let commit_id = self.commit("HEAD", message, tree_id, Some(head).into_iter().chain(merge_heads))?;
+        self.record_resolutions()?;
+
+        for path in MERGE_STATE_FILES.map(|name| self.git_dir().join(name)) {
+            remove_file_if_present(&path).map_err(|source| Error::Io { source, path })?;
+        }
+        if let Some(auto_merge) = self.try_find_reference("AUTO_MERGE")? {
+            auto_merge.delete()?;
+        }
+        Ok(commit_id)
+    }
+
+    /// Abort the merge in progress, as started by [`merge_into_worktree()`](Self::merge_into_worktree()), by resetting
+    /// the index and all files in the worktree that differ from `HEAD` in the index, and by removing the merge state,
+    /// like `git merge --abort` does.
+    ///
+    /// Files whose conflicts were resolved must not have changed in the worktree since they were added to the index,
+    /// so that no local changes are lost.
+    pub fn abort_merge(&self) -> Result<(), abort_merge::Error> {
+        use abort_merge::Error;
+
+        let workdir = self.workdir().ok_or(Error::MissingWorktree)?;
+        if !self.git_dir().join("MERGE_HEAD").is_file() {
+            return Err(Error::NoMergeInProgress);
+        }
+        let head_index = self.index_from_tree(&self.head_tree_id()?)?;
+        let index = self.index_or_empty()?;
+        let changes = changed_paths(&index, &head_index);
+        let resolved: Vec<_> = changes
+            .iter()
+            .filter(|path| {
+                index
+                    .entry_by_path_and_stage(path.as_bstr(), Stage::Unconflicted)
+                    .is_some()
+            })
+            .cloned()
+            .collect();
+        let local_changes = self.worktree_changes(&index, &resolved)?;
+        if !local_changes.is_empty() {
+            return Err(Error::LocalChanges { paths: local_changes });
+        }
+
+        let written = self.update_worktree(workdir, &head_index, &changes)?;
+        let mut new_index = head_index;
+        update_stat(&mut new_index, &written, &index);
+        new_index.write(Default::default())?;
+
+        for path in MERGE_STATE_FILES.map(|name| self.git_dir().join(name)) {
+            remove_file_if_present(&path).map_err(|source| Error::Io { source, path })?;
+        }
+        if let Some(auto_merge) = self.try_find_reference("AUTO_MERGE")? {
+            auto_merge.delete()?;
+        }
+        Ok(())
+    }
+
+    /// Record the resolutions of all files in `MERGE_RR` that don't have conflicts anymore, if rerere is enabled.
+    fn record_resolutions(&self) -> Result<(), finish_merge::Error> {
+        use finish_merge::Error;
+
+        let Some((store, workdir)) = self.rerere_store()?.zip(self.workdir()) else {
+            return Ok(());
+        };
+        let merge_rr_path = self.git_dir().join("MERGE_RR");
+        let entries = match std::fs::read(&merge_rr_path) {
+            Ok(data) => merge_rr::decode(&data)?,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+            Err(source) => {
+                return Err(Error::Io {
+                    source,
+                    path: merge_rr_path,
+                })
+            }
+        };
+        let mut buf = Vec::new();
+        for entry in entries {
+            let Ok(data) = std::fs::read(workdir.join(gix_path::from_bstr(entry.path.as_bstr()))) else {
+                continue;
+            };
+            let has_conflicts =
+                gix_merge::rerere::normalize(&data, Conflict::DEFAULT_MARKER_SIZE, self.object_hash(), &mut buf)
+                    .map_err(gix_merge::rerere::Error::from)?
+                    .is_some();
+            if !has_conflicts {
+                store.record_resolution(&entry.id, &data)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Return the paths of all entries in `index` with conflicting stages, without duplicates.
+fn unmerged_paths(index: &gix_index::State) -> Vec<BString> {
+    let mut out = Vec::<BString>::new();
+    for entry in index
+        .entries()
+        .iter()
+        .filter(|entry| entry.stage() != Stage::Unconflicted)
+    {
+        let path = entry.path(index);
+        if out.last().map(|last| last.as_bstr()) != Some(path) {
+            out.push(path.to_owned());
+        }
+    }
+    out
+}
+
+/// Remove comment lines and leading and trailing empty lines from `message`, like `git commit --cleanup=strip` does.
+fn strip_comments(message: &BStr) -> String {
+    let mut out = String::new();
+    for line in message.lines().filter(|line| !line.starts_with(b"#")) {
+        let line = line.trim_end();
+        if out.is_empty() && line.is_empty() {
+            continue;
+        }
+        out.push_str(&line.to_str_lossy());
+        out.push('\n');
+    }
+    while out.ends_with("\n\n") {
+        out.pop();
+    }
+    out
+}
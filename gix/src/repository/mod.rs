@@ -17,6 +17,8 @@ pub enum Kind {
     },
 }
 
+#[cfg(feature = "apply")]
+mod apply;
 #[cfg(any(feature = "attributes", feature = "excludes"))]
 pub mod attributes;
 ///
@@ -24,7 +26,7 @@ pub mod attributes;
 mod blame;
 mod cache;
 #[cfg(feature = "worktree-mutation")]
-mod checkout;
+pub(crate) mod checkout;
 mod config;
 
 ///
@@ -46,16 +48,25 @@ mod index;
 pub(crate) mod init;
 mod kind;
 mod location;
+#[cfg(feature = "mailbox")]
+mod mailbox;
 #[cfg(feature = "mailmap")]
 mod mailmap;
 ///
 #[cfg(feature = "merge")]
 mod merge;
+#[cfg(all(feature = "merge", feature = "worktree-mutation"))]
+mod merge_worktree;
 mod object;
 #[cfg(feature = "attributes")]
 mod pathspec;
+#[cfg(feature = "revision")]
+mod pretty;
 mod reference;
+mod reflog;
 mod remote;
+#[cfg(feature = "merge")]
+mod rerere;
 mod revision;
 mod shallow;
 mod state;
@@ -82,7 +93,7 @@ mod new_commit {
 }
 
 ///
-mod new_commit_as {
+pub(crate) mod new_commit_as {
     /// The error returned by [`new_commit_as(…)`](crate::Repository::new_commit_as()).
     #[derive(Debug, thiserror::Error)]
     #[allow(missing_docs)]
@@ -94,10 +105,182 @@ mod new_commit_as {
     }
 }
 
+///
+#[cfg(feature = "apply")]
+pub mod apply_patch {
+    use crate::bstr::BString;
+
+    /// The error returned by [Repository::apply_patch()](crate::Repository::apply_patch()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not apply the patch to '{path}'")]
+        Apply {
+            path: BString,
+            source: gix_diff::patch::apply::Error,
+        },
+        #[error(
+            "Could not find the version of '{path}' the patch was created for, which is needed for a three-way merge"
+        )]
+        MissingThreeWayBase {
+            path: BString,
+            source: gix_diff::patch::apply::Error,
+        },
+        #[error("'{path}' does not exist")]
+        NotFound { path: BString },
+        #[error("'{path}' already exists")]
+        AlreadyExists { path: BString },
+        #[error("'{path}' is not a file")]
+        NotAFile { path: BString },
+        #[error("'{path}' is a submodule, which can't be patched")]
+        Submodule { path: BString },
+        #[error("'{path}' has conflicts in the index")]
+        Unmerged { path: BString },
+        #[error("'{path}' does not match the index")]
+        WorktreeDoesNotMatchIndex { path: BString },
+        #[error("'{path}' is beyond a symbolic link")]
+        BeyondSymlink { path: BString },
+        #[error("The path '{path}' is invalid")]
+        InvalidPath {
+            path: BString,
+            source: gix_validate::path::component::Error,
+        },
+        #[error("The symbolic link '{path}' has an invalid target")]
+        InvalidSymlinkTarget { path: BString },
+        #[error("Patches can't be applied to the worktree of a bare repository")]
+        MissingWorktree,
+        #[error("Could not read or write '{}'", path.display())]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+        #[error(transparent)]
+        ProtectOptions(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_index::file::write::Error),
+        #[error(transparent)]
+        FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+        #[error(transparent)]
+        ConvertToGit(#[from] crate::filter::pipeline::convert_to_git::Error),
+        #[error(transparent)]
+        ConvertToWorktree(#[from] crate::filter::pipeline::convert_to_worktree::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        FindTree(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        LookupPrefix(#[from] gix_odb::store::prefix::lookup::Error),
+        #[error(transparent)]
+        WriteObject(#[from] crate::object::write::Error),
+        #[error(transparent)]
+        EditTree(#[from] crate::repository::edit_tree::Error),
+        #[error(transparent)]
+        TreeEditor(#[from] gix_object::tree::editor::Error),
+        #[error(transparent)]
+        WriteTree(#[from] crate::object::tree::editor::write::Error),
+        #[error(transparent)]
+        MergeOptions(#[from] crate::repository::blob_merge_options::Error),
+    }
+}
+
+///
+#[cfg(feature = "mailbox")]
+pub mod format_patch {
+    use crate::bstr::BString;
+
+    /// The error returned by [Repository::format_patch()](crate::Repository::format_patch()) and
+    /// [Repository::format_patch_range()](crate::Repository::format_patch_range()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The revision specification {spec:?} must be a range like `since..until` or a single revision")]
+        UnsupportedSpec { spec: BString },
+        #[error(transparent)]
+        RevParse(#[from] crate::revision::spec::parse::Error),
+        #[error(transparent)]
+        HeadId(#[from] crate::reference::head_id::Error),
+        #[error(transparent)]
+        Walk(#[from] crate::revision::walk::Error),
+        #[error(transparent)]
+        WalkIter(#[from] crate::revision::walk::iter::Error),
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        PeelToTree(#[from] crate::object::peel::to_kind::Error),
+        #[error(transparent)]
+        CommitTree(#[from] crate::object::commit::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        ParseDate(#[from] gix_date::parse::Error),
+        #[error(transparent)]
+        DiffOptions(#[from] crate::diff::options::init::Error),
+        #[error(transparent)]
+        DiffAlgorithm(#[from] crate::config::diff::algorithm::Error),
+        #[error(transparent)]
+        CreateResourceCache(#[from] super::diff_resource_cache::Error),
+        #[error(transparent)]
+        DiffTree(#[from] super::diff_tree_to_tree::Error),
+        #[error(transparent)]
+        PatchFile(#[from] super::diff_change_to_patch_file::Error),
+        #[error(transparent)]
+        Io(#[from] std::io::Error),
+    }
+}
+
+///
+#[cfg(feature = "mailbox")]
+pub mod apply_mailbox {
+    use crate::bstr::BString;
+
+    /// The error returned by [Repository::apply_mailbox()](crate::Repository::apply_mailbox()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Could not parse the patch of message {index}")]
+        ParsePatch {
+            index: usize,
+            source: gix_diff::patch::parse::Error,
+        },
+        #[error("Message {index} does not contain a patch")]
+        EmptyPatch { index: usize },
+        #[error("Could not apply the patch of message {index}")]
+        Apply {
+            index: usize,
+            source: super::apply_patch::Error,
+        },
+        #[error("The patch of message {index} could only be applied with conflicts in {}", paths.iter().map(|path| format!("'{path}'")).collect::<Vec<_>>().join(", "))]
+        Conflict { index: usize, paths: Vec<BString> },
+        #[error("'{path}' has changes in the index compared to HEAD")]
+        IndexDoesNotMatchHead { path: BString },
+        #[error(transparent)]
+        FindHead(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        PeelHead(#[from] crate::head::peel::Error),
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        CommitTree(#[from] crate::object::commit::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        CommitterTime(#[from] crate::config::time::Error),
+        #[error(transparent)]
+        Commit(#[from] crate::commit::Error),
+    }
+}
+
 ///
 #[cfg(feature = "blame")]
 pub mod blame_file {
-    /// Options to be passed to [Repository::blame_file()](crate::Repository::blame_file()).
+    /// Options to be passed to [Repository::blame_file()](crate::Repository::blame_file()) and
+    /// [Repository::blame_file_incremental()](crate::Repository::blame_file_incremental()).
     #[derive(Default, Debug, Clone)]
     pub struct Options {
         /// The algorithm to use for diffing. If `None`, `diff.algorithm` will be used.
@@ -108,15 +291,37 @@ pub mod blame_file {
         pub since: Option<gix_date::Time>,
         /// Determine if rename tracking should be performed, and how.
         pub rewrites: Option<gix_diff::Rewrites>,
+        /// The commits whose changes should be attributed to their parents instead, as far as possible.
+        /// If `None`, the commits listed in the files of `blame.ignoreRevsFile` will be used.
+        pub ignore_revs: Option<Vec<gix_hash::ObjectId>>,
+        /// If `true`, blame the file as it is in the worktree, with uncommitted changes attributed to the null id.
+        pub worktree: bool,
+        /// If set, attribute lines that were moved within the file or copied from other files to the commit that
+        /// introduced them there, like `git blame -M` and `git blame -C` do.
+        pub moves_and_copies: Option<gix_blame::MovesAndCopies>,
     }
 
-    /// The error returned by [Repository::blame_file()](crate::Repository::blame_file()).
+    /// The error returned by [Repository::blame_file()](crate::Repository::blame_file()) and
+    /// [Repository::blame_file_incremental()](crate::Repository::blame_file_incremental()).
     #[derive(Debug, thiserror::Error)]
     #[allow(missing_docs)]
     pub enum Error {
         #[error(transparent)]
         CommitGraphIfEnabled(#[from] super::commit_graph_if_enabled::Error),
         #[error(transparent)]
+        IgnoreRevs(#[from] super::blame_ignore_revs::Error),
+        #[error("Cannot blame the worktree version of a file in a bare repository")]
+        MissingWorktree,
+        #[error("Could not read '{}' from the worktree", path.display())]
+        ReadWorktreeFile {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+        #[error(transparent)]
+        FilterPipeline(#[from] super::filter::pipeline::Error),
+        #[error(transparent)]
+        ConvertToGit(#[from] crate::filter::pipeline::convert_to_git::Error),
+        #[error(transparent)]
         DiffAlgorithm(#[from] crate::config::diff::algorithm::Error),
         #[error(transparent)]
         DiffResourceCache(#[from] super::diff_resource_cache::Error),
@@ -125,6 +330,33 @@ pub mod blame_file {
     }
 }
 
+///
+#[cfg(feature = "blame")]
+pub mod blame_ignore_revs {
+    /// The error returned by [Repository::blame_ignore_revs()](crate::Repository::blame_ignore_revs()) and
+    /// [Repository::blame_ignore_revs_from_file()](crate::Repository::blame_ignore_revs_from_file()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The path of 'blame.ignoreRevsFile' could not be interpolated")]
+        InterpolatePath(#[from] gix_config::path::interpolate::Error),
+        #[error("Could not read the list of revisions to ignore at '{}'", path.display())]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+        #[error("Invalid object name '{line}' in '{}'", path.display())]
+        InvalidObjectName {
+            line: crate::bstr::BString,
+            path: std::path::PathBuf,
+        },
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::existing::Error),
+        #[error(transparent)]
+        PeelToCommit(#[from] crate::object::peel::to_kind::Error),
+    }
+}
+
 ///
 #[cfg(feature = "blob-diff")]
 pub mod diff_tree_to_tree {
@@ -141,6 +373,22 @@ pub mod diff_tree_to_tree {
     }
 }
 
+///
+#[cfg(feature = "apply")]
+pub mod diff_change_to_patch_file {
+    /// The error returned by [Repository::diff_change_to_patch_file()](crate::Repository::diff_change_to_patch_file()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindBlob(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        SetResource(#[from] gix_diff::blob::platform::set_resource::Error),
+        #[error(transparent)]
+        PrepareDiff(#[from] gix_diff::blob::platform::prepare_diff::Error),
+    }
+}
+
 ///
 #[cfg(feature = "merge")]
 pub mod blob_merge_options {
@@ -176,6 +424,8 @@ pub mod merge_resource_cache {
         FilterPipeline(#[from] crate::filter::pipeline::options::Error),
         #[error(transparent)]
         DriversConfig(#[from] crate::config::merge::drivers::Error),
+        #[error(transparent)]
+        RerereStore(#[from] super::rerere_store::Error),
     }
 }
 
@@ -217,6 +467,230 @@ pub mod merge_commits {
     }
 }
 
+///
+#[cfg(feature = "merge")]
+pub mod merge_commits_octopus {
+    /// The error returned by [Repository::merge_commits_octopus()](crate::Repository::merge_commits_octopus()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        OpenCommitGraph(#[from] super::commit_graph_if_enabled::Error),
+        #[error(transparent)]
+        MergeResourceCache(#[from] super::merge_resource_cache::Error),
+        #[error(transparent)]
+        DiffResourceCache(#[from] super::diff_resource_cache::Error),
+        #[error(transparent)]
+        OctopusMerge(#[from] gix_merge::commit::octopus::Error),
+    }
+}
+
+///
+#[cfg(all(feature = "merge", feature = "worktree-mutation"))]
+pub mod merge_into_worktree {
+    use crate::bstr::BString;
+
+    /// The error returned by [Repository::merge_into_worktree()](crate::Repository::merge_into_worktree()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Cannot merge into the worktree of a bare repository")]
+        MissingWorktree,
+        #[error("Cannot merge while another operation is in progress: {state:?}")]
+        InProgress { state: crate::state::InProgress },
+        #[error("{their_commit_id} is already contained in HEAD")]
+        AlreadyUpToDate { their_commit_id: gix_hash::ObjectId },
+        #[error("Local changes to {} would be overwritten by the merge", paths.iter().map(|path| format!("'{path}'")).collect::<Vec<_>>().join(", "))]
+        LocalChanges { paths: Vec<BString> },
+        #[error("The merge stopped at the first unresolved conflict, and nothing was changed")]
+        FailedOnFirstUnresolvedConflict,
+        #[error("Could not write '{}'", path.display())]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+        #[error(transparent)]
+        HeadId(#[from] crate::reference::head_id::Error),
+        #[error(transparent)]
+        MergeBase(#[from] super::merge_base::Error),
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        IndexFromTree(#[from] super::index_from_tree::Error),
+        #[error(transparent)]
+        MergeCommits(#[from] super::merge_commits::Error),
+        #[error(transparent)]
+        WriteTree(#[from] crate::object::tree::editor::write::Error),
+        #[error(transparent)]
+        UpdateWorktree(#[from] crate::merge::worktree::update::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_index::file::write::Error),
+        #[error(transparent)]
+        RerereStore(#[from] super::rerere_store::Error),
+        #[error(transparent)]
+        Rerere(#[from] gix_merge::rerere::Error),
+        #[error(transparent)]
+        EditReference(#[from] crate::reference::edit::Error),
+    }
+}
+
+///
+#[cfg(all(feature = "merge", feature = "worktree-mutation"))]
+pub mod finish_merge {
+    use crate::bstr::BString;
+
+    /// The error returned by [Repository::finish_merge()](crate::Repository::finish_merge()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("There is no merge to finish")]
+        NoMergeInProgress,
+        #[error("'MERGE_HEAD' contains the invalid object id {line:?}")]
+        InvalidMergeHead { line: BString },
+        #[error("{} still have conflicts in the index", paths.iter().map(|path| format!("'{path}'")).collect::<Vec<_>>().join(", "))]
+        Unmerged { paths: Vec<BString> },
+        #[error("The commit message is empty")]
+        EmptyMessage,
+        #[error("Could not read or remove '{}'", path.display())]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+        #[error(transparent)]
+        HeadId(#[from] crate::reference::head_id::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        EditTree(#[from] super::edit_tree::Error),
+        #[error(transparent)]
+        UpsertEntry(#[from] gix_object::tree::editor::Error),
+        #[error(transparent)]
+        WriteTree(#[from] crate::object::tree::editor::write::Error),
+        #[error(transparent)]
+        Commit(#[from] crate::commit::Error),
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::Error),
+        #[error(transparent)]
+        RerereStore(#[from] super::rerere_store::Error),
+        #[error(transparent)]
+        Rerere(#[from] gix_merge::rerere::Error),
+        #[error(transparent)]
+        DecodeMergeRr(#[from] gix_merge::rerere::merge_rr::Error),
+        #[error(transparent)]
+        EditReference(#[from] crate::reference::edit::Error),
+    }
+}
+
+///
+#[cfg(all(feature = "merge", feature = "worktree-mutation"))]
+pub mod abort_merge {
+    use crate::bstr::BString;
+
+    /// The error returned by [Repository::abort_merge()](crate::Repository::abort_merge()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Cannot abort a merge in a bare repository")]
+        MissingWorktree,
+        #[error("There is no merge to abort")]
+        NoMergeInProgress,
+        #[error("Local changes to {} would be lost when aborting the merge", paths.iter().map(|path| format!("'{path}'")).collect::<Vec<_>>().join(", "))]
+        LocalChanges { paths: Vec<BString> },
+        #[error("Could not remove '{}'", path.display())]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+        #[error(transparent)]
+        HeadTreeId(#[from] crate::reference::head_tree_id::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        IndexFromTree(#[from] super::index_from_tree::Error),
+        #[error(transparent)]
+        UpdateWorktree(#[from] crate::merge::worktree::update::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_index::file::write::Error),
+        #[error(transparent)]
+        FindReference(#[from] crate::reference::find::Error),
+        #[error(transparent)]
+        EditReference(#[from] crate::reference::edit::Error),
+    }
+}
+
+///
+#[cfg(feature = "merge")]
+pub mod rerere_store {
+    /// The error returned by [Repository::rerere_store()](crate::Repository::rerere_store()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        EnabledConfig(#[from] crate::config::boolean::Error),
+    }
+}
+
+///
+#[cfg(feature = "merge")]
+pub mod rerere_forget {
+    use crate::bstr::BString;
+
+    /// The error returned by [Repository::rerere_forget()](crate::Repository::rerere_forget()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Recording and replaying conflict resolutions is disabled")]
+        Disabled,
+        #[error("'{rela_path}' has no conflicts in the index")]
+        NotUnmerged { rela_path: BString },
+        #[error("Could not read or write '{}'", path.display())]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+        #[error(transparent)]
+        RerereStore(#[from] super::rerere_store::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        MergeResourceCache(#[from] super::merge_resource_cache::Error),
+        #[error(transparent)]
+        BlobMergeOptions(#[from] super::blob_merge_options::Error),
+        #[error(transparent)]
+        CommandContext(#[from] crate::config::command_context::Error),
+        #[error(transparent)]
+        SetResource(#[from] gix_merge::blob::platform::set_resource::Error),
+        #[error(transparent)]
+        PrepareMerge(#[from] gix_merge::blob::platform::prepare_merge::Error),
+        #[error(transparent)]
+        Merge(#[from] gix_merge::blob::platform::merge::Error),
+        #[error(transparent)]
+        Rerere(#[from] gix_merge::rerere::Error),
+        #[error(transparent)]
+        DecodeMergeRr(#[from] gix_merge::rerere::merge_rr::Error),
+    }
+}
+
+///
+#[cfg(feature = "merge")]
+pub mod rerere_gc {
+    /// The error returned by [Repository::rerere_gc()](crate::Repository::rerere_gc()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        RerereStore(#[from] super::rerere_store::Error),
+        #[error(transparent)]
+        ExpiryConfig(#[from] crate::config::unsigned_integer::Error),
+        #[error(transparent)]
+        Rerere(#[from] gix_merge::rerere::Error),
+    }
+}
+
 ///
 #[cfg(feature = "merge")]
 pub mod virtual_merge_base {
@@ -512,6 +986,44 @@ pub mod index_or_load_from_head_or_empty {
     }
 }
 
+///
+#[cfg(feature = "revision")]
+pub mod pretty_options {
+    /// The error returned by [Repository::pretty_options()](crate::Repository::pretty_options()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Format(#[from] crate::pretty::format::Error),
+        #[error(transparent)]
+        Date(#[from] crate::config::key::GenericErrorWithValue<crate::pretty::date::Error>),
+        #[error(transparent)]
+        Boolean(#[from] crate::config::boolean::Error),
+    }
+}
+
+///
+#[cfg(feature = "revision")]
+pub mod pretty_printer {
+    /// The error returned by [Repository::pretty_printer()](crate::Repository::pretty_printer()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        References(#[from] crate::reference::iter::Error),
+        #[error(transparent)]
+        ReferencesInit(#[from] crate::reference::iter::init::Error),
+        #[error("Could not read a reference to decorate commits with")]
+        IterReferences(#[source] Box<dyn std::error::Error + Send + Sync + 'static>),
+        #[error(transparent)]
+        FindHead(#[from] crate::reference::find::existing::Error),
+        #[error(transparent)]
+        FindObject(#[from] crate::object::find::Error),
+        #[error(transparent)]
+        DecodeTag(#[from] gix_object::decode::Error),
+    }
+}
+
 ///
 #[cfg(feature = "worktree-stream")]
 pub mod worktree_stream {
@@ -0,0 +1,120 @@
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    config::{
+        cache::util::{ApplyLeniency, ApplyLeniencyDefault, ApplyLeniencyDefaultValue},
+        tree,
+    },
+    pretty,
+    repository::{pretty_options, pretty_printer},
+    Repository,
+};
+
+/// The names of the preset formats, which can't be redefined by aliases.
+const PRESETS: &[&str] = &["oneline", "short", "medium", "full", "fuller", "raw"];
+
+/// Format commits like `git log`
+impl Repository {
+    /// Return the options to format commits with, as configured in `format.pretty`, `log.date`, `log.mailmap` and `log.abbrevCommit`.
+    ///
+    /// Options that can't be configured are left at their default, and the mailmap is used unless disabled.
+    pub fn pretty_options(&self) -> Result<pretty::Options, pretty_options::Error> {
+        let config = &self.config.resolved;
+        let lenient = self.config.lenient_config;
+        let format = match config.string(tree::Format::PRETTY) {
+            Some(name) => self.pretty_format(name.as_ref())?,
+            None => pretty::Format::default(),
+        };
+        let date = config
+            .string(tree::Log::DATE)
+            .map(|value| tree::Log::DATE.try_into_date_mode(value))
+            .transpose()
+            .with_leniency(lenient)?
+            .unwrap_or_default();
+        let mailmap = config.boolean(tree::Log::MAILMAP).map_or(Ok(true), |res| {
+            tree::Log::MAILMAP
+                .enrich_error(res)
+                .with_lenient_default_value(lenient, true)
+        })?;
+        let abbrev_commit = config
+            .boolean(tree::Log::ABBREV_COMMIT)
+            .map(|res| tree::Log::ABBREV_COMMIT.enrich_error(res))
+            .transpose()
+            .with_lenient_default(lenient)?
+            .unwrap_or_default();
+        Ok(pretty::Options {
+            format,
+            date,
+            abbrev_commit,
+            mailmap,
+            ..Default::default()
+        })
+    }
+
+    /// Parse `name` like the `--pretty` option of `git log` does, which also resolves aliases configured as `pretty.<name>`.
+    ///
+    /// Names of presets and aliases may be abbreviated as long as they are unambiguous, and are matched case-insensitively.
+    /// Presets can't be redefined by aliases.
+    pub fn pretty_format(&self, name: &BStr) -> Result<pretty::Format, pretty::format::Error> {
+        if let Ok(format @ pretty::Format::Custom { .. }) = pretty::Format::from_bytes(name) {
+            return Ok(format);
+        }
+
+        // Later definitions override earlier ones.
+        let mut aliases = Vec::<(BString, BString)>::new();
+        for section in self.config.resolved.sections_by_name("pretty").into_iter().flatten() {
+            for value_name in section.value_names() {
+                let alias_name = BString::from(value_name.as_ref());
+                if PRESETS
+                    .iter()
+                    .any(|preset| alias_name.as_bytes().eq_ignore_ascii_case(preset.as_bytes()))
+                {
+                    continue;
+                }
+                let Some(value) = section.value(value_name.as_ref()) else {
+                    continue;
+                };
+                aliases.retain(|(name, _)| !name.as_bytes().eq_ignore_ascii_case(&alias_name));
+                aliases.push((alias_name, value.into_owned()));
+            }
+        }
+
+        let mut sought = name.to_owned();
+        for _ in 0..=aliases.len() {
+            // Like Git, the shortest name with the given prefix wins.
+            let is_match = |candidate: &[u8]| {
+                candidate.len() >= sought.len() && candidate[..sought.len()].eq_ignore_ascii_case(&sought)
+            };
+            let preset = PRESETS
+                .iter()
+                .filter(|preset| is_match(preset.as_bytes()))
+                .min_by_key(|preset| preset.len())
+                .map(|preset| (preset.as_bytes(), None));
+            let alias = aliases
+                .iter()
+                .filter(|(name, _)| is_match(name))
+                .min_by_key(|(name, _)| name.len())
+                .map(|(name, value)| (name.as_bytes(), Some(value)));
+            let found = match (preset, alias) {
+                (Some(preset), Some(alias)) if alias.0.len() < preset.0.len() => alias,
+                (Some(preset), _) => preset,
+                (None, Some(alias)) => alias,
+                (None, None) => return Err(pretty::format::Error::Unknown { name: name.to_owned() }),
+            };
+            match found {
+                (preset, None) => return pretty::Format::from_bytes(preset.as_bstr()),
+                (_, Some(value)) => match pretty::Format::from_bytes(value.as_ref()) {
+                    Ok(format @ pretty::Format::Custom { .. }) => return Ok(format),
+                    _ => sought = value.clone(),
+                },
+            }
+        }
+        Err(pretty::format::Error::AliasLoop { name: name.to_owned() })
+    }
+
+    /// Return a printer to write commits formatted according to `options`, like `git log` does.
+    ///
+    /// The mailmap and the names of references are loaded as needed by `options`.
+    pub fn pretty_printer(&self, options: pretty::Options) -> Result<pretty::Printer<'_>, pretty_printer::Error> {
+        pretty::Printer::new(self, options)
+    }
+}
@@ -0,0 +1,214 @@
+use gix_date::SecondsSinceUnixEpoch;
+use gix_hash::ObjectId;
+use gix_ref::{file::log::expire::Outcome, FullNameRef};
+
+use crate::{
+    config::{cache::util::ApplyLeniency, tree::Gc},
+    reference::log::expire::{Error, Options},
+};
+
+/// A graph of commits along with a flag telling if they are reachable from the tips of the reflog that is expired.
+pub(crate) type Graph<'find, 'cache> = gix_revwalk::Graph<'find, 'cache, gix_revwalk::graph::Commit<bool>>;
+
+/// Expire and delete reference logs.
+impl crate::Repository {
+    /// Obtain options for [`reflog_expire()`](Self::reflog_expire()) from `gc.reflogExpire` and `gc.reflogExpireUnreachable`,
+    /// defaulting to 90 and 30 days respectively, just like `git` does.
+    pub fn reflog_expire_options(&self) -> Result<Options, crate::config::time::Error> {
+        let now = std::time::SystemTime::now();
+        let expiry = |key: &'static crate::config::tree::gc::Expiry, default: &'static str| {
+            key.try_into_expiry(
+                self.config
+                    .resolved
+                    .string(key)
+                    .unwrap_or_else(|| crate::bstr::BStr::new(default).into()),
+                now,
+            )
+            .with_leniency(self.config.lenient_config)
+        };
+        Ok(Options {
+            expire: expiry(&Gc::REFLOG_EXPIRE, "90.days.ago")?,
+            expire_unreachable: expiry(&Gc::REFLOG_EXPIRE_UNREACHABLE, "30.days.ago")?,
+        })
+    }
+
+    /// Remove entries from the reflog of the reference `name` according to `options`, similar to `git reflog expire`.
+    ///
+    /// An entry is unreachable if it refers to a commit that doesn't exist or that isn't reachable from the current value
+    /// of the reference, and all entries are unreachable if the reference doesn't point to a commit.
+    /// The reference is locked while its log is rewritten.
+    ///
+    /// Return `Ok(None)` if there is no reflog for `name`.
+    pub fn reflog_expire(&self, name: &FullNameRef, options: Options) -> Result<Option<Outcome>, Error> {
+        let cache = self.commit_graph_if_enabled()?;
+        let mut graph = self.revision_graph(cache.as_ref());
+        self.reflog_expire_with_graph(name, options, &mut graph)
+    }
+
+    /// Like [`reflog_expire()`](Self::reflog_expire()), but use `graph` to look up commits so they can be reused when
+    /// expiring the reflogs of multiple references.
+    pub(crate) fn reflog_expire_with_graph(
+        &self,
+        name: &FullNameRef,
+        options: Options,
+        graph: &mut Graph<'_, '_>,
+    ) -> Result<Option<Outcome>, Error> {
+        let expire = options.expire.map(|time| time.seconds);
+        let expire_unreachable = options
+            .expire_unreachable
+            .map(|time| time.seconds)
+            .filter(|unreachable| expire.is_none_or(|expire| *unreachable > expire));
+
+        let ids_to_check = match expire_unreachable {
+            Some(expire_unreachable) => self.reflog_ids_to_check(name, expire, expire_unreachable)?,
+            None => Vec::new(),
+        };
+        let has_tips = if ids_to_check.is_empty() {
+            None
+        } else {
+            Some(self.reflog_mark_reachable(name, expire, &ids_to_check, graph)?)
+        };
+        let is_unreachable = |id: ObjectId| {
+            !id.is_null()
+                && has_tips.is_some_and(|has_tips| !has_tips || !graph.get(&id).is_some_and(|commit| commit.data))
+        };
+
+        let (lock_fail, _) = self.config.lock_timeout()?;
+        Ok(self.refs.reflog_expire(name, lock_fail, |_index, line| {
+            let Ok(time) = line.signature.time() else {
+                return false;
+            };
+            if expire.is_some_and(|expire| time.seconds < expire) {
+                return true;
+            }
+            expire_unreachable.is_some_and(|expire_unreachable| time.seconds < expire_unreachable)
+                && (is_unreachable(line.previous_oid()) || is_unreachable(line.new_oid()))
+        })?)
+    }
+
+    /// Remove the entries at `indices` from the reflog of the reference `name`, where `0` is the most recent entry,
+    /// similar to `git reflog delete <name>@{<index>}`.
+    ///
+    /// Note that the reference itself isn't changed, even if the most recent entry is removed.
+    /// Return `Ok(None)` if there is no reflog for `name`.
+    pub fn reflog_delete_entries(&self, name: &FullNameRef, indices: &[usize]) -> Result<Option<Outcome>, Error> {
+        let (lock_fail, _) = self.config.lock_timeout()?;
+        Ok(self
+            .refs
+            .reflog_expire(name, lock_fail, |index, _line| indices.contains(&index))?)
+    }
+
+    /// Delete the reflog of the reference `name` entirely, leaving the reference itself untouched.
+    ///
+    /// Return `Ok(false)` if there was no reflog.
+    pub fn reflog_delete(&self, name: &FullNameRef) -> Result<bool, Error> {
+        let (lock_fail, _) = self.config.lock_timeout()?;
+        Ok(self.refs.reflog_delete(name, lock_fail)?)
+    }
+
+    /// Return the ids of all entries in the reflog of `name` that are only removed if they are unreachable, as they are
+    /// older than `expire_unreachable` but not older than `expire`.
+    fn reflog_ids_to_check(
+        &self,
+        name: &FullNameRef,
+        expire: Option<SecondsSinceUnixEpoch>,
+        expire_unreachable: SecondsSinceUnixEpoch,
+    ) -> Result<Vec<ObjectId>, Error> {
+        let mut buf = Vec::new();
+        let mut ids = Vec::new();
+        let Some(lines) = self.refs.reflog_iter(name, &mut buf)? else {
+            return Ok(ids);
+        };
+        for line in lines.filter_map(Result::ok) {
+            let Ok(time) = line.signature.time() else {
+                continue;
+            };
+            if time.seconds < expire_unreachable && expire.is_none_or(|expire| time.seconds >= expire) {
+                ids.extend(
+                    [line.previous_oid(), line.new_oid()]
+                        .into_iter()
+                        .filter(|id| !id.is_null()),
+                );
+            }
+        }
+        Ok(ids)
+    }
+
+    /// Mark all commits in `graph` that are reachable from the tips relevant for the reflog of `name`, or return `false`
+    /// if there is no tip that is a commit, which makes all entries unreachable.
+    ///
+    /// Like `git`, commits older than `expire` are only traversed if one of `ids_to_check` isn't reachable otherwise,
+    /// as entries for these are removed either way.
+    fn reflog_mark_reachable(
+        &self,
+        name: &FullNameRef,
+        expire: Option<SecondsSinceUnixEpoch>,
+        ids_to_check: &[ObjectId],
+        graph: &mut Graph<'_, '_>,
+    ) -> Result<bool, Error> {
+        let mut tips = Vec::new();
+        if name.as_bstr() == "HEAD" {
+            tips.extend(self.head()?.id().map(crate::Id::detach));
+            for reference in self.references()?.all()? {
+                let reference = reference.map_err(Error::IterItem)?;
+                // Like `git`, ignore references that are dangling or can't be peeled.
+                let Ok(id) = reference.into_fully_peeled_id() else {
+                    continue;
+                };
+                tips.push(id.detach());
+            }
+        } else if let Some(reference) = self.try_find_reference(name)? {
+            tips.extend(reference.into_fully_peeled_id().ok().map(crate::Id::detach));
+        }
+
+        // The graph may be shared with previous calls, so forget what was reachable from their tips.
+        graph.clear_commit_data(|reachable| *reachable = false);
+        let mut queue = Vec::new();
+        for tip in tips {
+            if graph.get_or_insert_commit(tip, |_| {})?.is_some() {
+                queue.push(tip);
+            }
+        }
+        if queue.is_empty() {
+            return Ok(false);
+        }
+
+        let mut leftover = Vec::new();
+        mark_reachable(graph, queue, expire, &mut leftover)?;
+        let is_reachable = |graph: &Graph<'_, '_>, id: &ObjectId| graph.get(id).is_some_and(|commit| commit.data);
+        if !leftover.is_empty() && !ids_to_check.iter().all(|id| is_reachable(graph, id)) {
+            let queue = leftover
+                .iter()
+                .filter_map(|id| graph.get(id))
+                .flat_map(|commit| commit.parents.iter().copied())
+                .collect();
+            mark_reachable(graph, queue, None, &mut leftover)?;
+        }
+        Ok(true)
+    }
+}
+
+/// Mark all commits reachable from `queue` as reachable, but don't traverse the parents of commits older than `cutoff`,
+/// which are collected in `leftover` instead.
+fn mark_reachable(
+    graph: &mut Graph<'_, '_>,
+    mut queue: Vec<ObjectId>,
+    cutoff: Option<SecondsSinceUnixEpoch>,
+    leftover: &mut Vec<ObjectId>,
+) -> Result<(), Error> {
+    while let Some(id) = queue.pop() {
+        let Some(commit) = graph.get_or_insert_commit(id, |_| {})? else {
+            continue;
+        };
+        if commit.data {
+            continue;
+        }
+        commit.data = true;
+        if cutoff.is_some_and(|cutoff| commit.commit_time < cutoff) {
+            leftover.push(id);
+            continue;
+        }
+        queue.extend(commit.parents.iter().copied());
+    }
+    Ok(())
+}
@@ -0,0 +1,151 @@
+use std::time::{Duration, SystemTime};
+
+use gix_hash::ObjectId;
+use gix_index::entry::Stage;
+use gix_merge::{
+    blob::{builtin_driver::text, ResourceKind},
+    rerere,
+};
+
+use crate::{
+    bstr::BStr,
+    config::{
+        cache::util::{ApplyLeniency, ApplyLeniencyDefault},
+        tree,
+    },
+    repository::{rerere_forget, rerere_gc, rerere_store},
+    Repository,
+};
+
+/// Reuse recorded resolutions
+impl Repository {
+    /// Return the store of recorded conflict resolutions at `rr-cache` in the common git directory, or `None`
+    /// if recording and replaying resolutions is disabled.
+    ///
+    /// It's enabled if `rerere.enabled` is `true`, or if it's unset and the `rr-cache` directory exists, like in Git.
+    /// If enabled, [blob-merges](Self::merge_resource_cache()) replay recorded resolutions, and merges
+    /// [in the worktree](Self::merge_into_worktree()) record conflicts and their resolutions.
+    pub fn rerere_store(&self) -> Result<Option<rerere::Store>, rerere_store::Error> {
+        let path = self.common_dir().join("rr-cache");
+        let enabled = self
+            .config
+            .resolved
+            .boolean(&tree::Rerere::ENABLED)
+            .map(|res| {
+                tree::Rerere::ENABLED
+                    .enrich_error(res)
+                    .with_lenient_default(self.config.lenient_config)
+            })
+            .transpose()?
+            .unwrap_or_else(|| path.is_dir());
+        Ok(enabled.then(|| rerere::Store::at(path, self.object_hash())))
+    }
+
+    /// Forget the recorded resolution of the conflicts that the file at `rela_path` has according to its conflicting
+    /// stages in the index, so that a new resolution can be recorded, like `git rerere forget <path>` does.
+    /// Return the identifier of the forgotten resolution, or `None` if there was none.
+    ///
+    /// The conflicts are recreated by merging the stages in the index with conflict markers of the default size.
+    /// If a merge is in progress, the path is added to `MERGE_RR` to record its new resolution when the merge is
+    /// [finished](Self::finish_merge()).
+    pub fn rerere_forget(&self, rela_path: &BStr) -> Result<Option<rerere::Id>, rerere_forget::Error> {
+        use rerere_forget::Error;
+
+        let store = self.rerere_store()?.ok_or(Error::Disabled)?;
+        let index = self.index_or_empty()?;
+        let mut stages = [Stage::Base, Stage::Ours, Stage::Theirs].map(|stage| {
+            index
+                .entry_by_path_and_stage(rela_path, stage)
+                .map(|entry| (entry.id, entry.mode))
+        });
+        if stages[1].is_none() && stages[2].is_none() {
+            return Err(Error::NotUnmerged {
+                rela_path: rela_path.to_owned(),
+            });
+        }
+
+        let mut platform = self.merge_resource_cache(Default::default())?;
+        platform.options.rerere = None;
+        for (stage, kind) in stages.iter_mut().zip([
+            ResourceKind::CommonAncestorOrBase,
+            ResourceKind::CurrentOrOurs,
+            ResourceKind::OtherOrTheirs,
+        ]) {
+            let (id, mode) = stage
+                .take()
+                .unwrap_or((ObjectId::null(self.object_hash()), gix_index::entry::Mode::FILE));
+            let mode = mode
+                .to_tree_entry_mode()
+                .map_or(gix_object::tree::EntryKind::Blob, |mode| mode.kind());
+            platform.set_resource(id, mode, rela_path, kind, &self.objects)?;
+        }
+        let mut options = self.blob_merge_options()?;
+        options.text.conflict = text::Conflict::default();
+        let merge = platform.prepare_merge(&self.objects, options)?;
+        let mut buf = Vec::new();
+        let labels = text::Labels {
+            ancestor: None,
+            current: Some("ours".into()),
+            other: Some("theirs".into()),
+        };
+        let (pick, _resolution) = merge.merge(&mut buf, labels, &self.command_context()?)?;
+        let data = match merge.buffer_by_pick(pick) {
+            Ok(Some(data)) => data,
+            Ok(None) => &buf,
+            Err(()) => return Ok(None),
+        };
+        let Some(id) = store.forget(data, text::Conflict::DEFAULT_MARKER_SIZE)? else {
+            return Ok(None);
+        };
+
+        let merge_rr_path = self.git_dir().join("MERGE_RR");
+        if self.git_dir().join("MERGE_HEAD").is_file() {
+            let mut entries = match std::fs::read(&merge_rr_path) {
+                Ok(data) => rerere::merge_rr::decode(&data)?,
+                Err(err) if err.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+                Err(source) => {
+                    return Err(Error::Io {
+                        source,
+                        path: merge_rr_path,
+                    })
+                }
+            };
+            entries.retain(|entry| entry.path != rela_path);
+            entries.push(rerere::merge_rr::Entry {
+                id,
+                path: rela_path.to_owned(),
+            });
+            let mut data = Vec::new();
+            rerere::merge_rr::encode(&entries, &mut data);
+            std::fs::write(&merge_rr_path, data).map_err(|source| Error::Io {
+                source,
+                path: merge_rr_path,
+            })?;
+        }
+        Ok(Some(id))
+    }
+
+    /// Remove recorded resolutions that weren't used for `rerere.resolved` days, 60 by default, and recorded
+    /// conflicts that weren't resolved for `rerere.unresolved` days, 15 by default, like `git rerere gc` does.
+    /// Return the amount of removed entries, or `None` if [rerere is disabled](Self::rerere_store()).
+    pub fn rerere_gc(&self) -> Result<Option<usize>, rerere_gc::Error> {
+        let Some(store) = self.rerere_store()? else {
+            return Ok(None);
+        };
+        let now = SystemTime::now();
+        let cutoff = |key: &'static tree::keys::UnsignedInteger, default_days: u64| {
+            let days = self
+                .config
+                .resolved
+                .integer(key)
+                .map(|value| key.try_into_u64(value))
+                .transpose()
+                .with_leniency(self.config.lenient_config)?
+                .unwrap_or(default_days);
+            Ok::<_, rerere_gc::Error>(now.checked_sub(Duration::from_secs(days.saturating_mul(24 * 60 * 60))))
+        };
+        let resolved = cutoff(&tree::Rerere::RESOLVED, 60)?;
+        let unresolved = cutoff(&tree::Rerere::UNRESOLVED, 15)?;
+        Ok(Some(store.gc(resolved, unresolved)?))
+    }
+}
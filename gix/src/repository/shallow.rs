@@ -1,5 +1,7 @@
 use std::{borrow::Cow, path::PathBuf};
 
+use gix_hash::ObjectId;
+
 use crate::{config::tree::gitoxide, Repository};
 
 impl Repository {
@@ -35,4 +37,44 @@ impl Repository {
             .unwrap_or_else(|| Cow::Borrowed("shallow".into()));
         self.common_dir().join(gix_path::from_bstr(shallow_name))
     }
+
+    /// Remove all commits from the shallow boundary that don't exist anymore and return them, similar to what Git does
+    /// when pruning.
+    ///
+    /// This keeps the `shallow` file consistent after objects were removed, and removes it if no shallow commit remains.
+    pub fn prune_shallow_commits(&self) -> Result<Vec<ObjectId>, crate::shallow::prune::Error> {
+        self.remove_shallow_commits(|_| false)
+    }
+
+    /// Like [`prune_shallow_commits()`](Self::prune_shallow_commits()), but also remove all shallow commits for which
+    /// `should_remove(id)` returns `true`.
+    pub(crate) fn remove_shallow_commits(
+        &self,
+        mut should_remove: impl FnMut(&gix_hash::oid) -> bool,
+    ) -> Result<Vec<ObjectId>, crate::shallow::prune::Error> {
+        let shallow_file = self.shallow_file();
+        if gix_shallow::read(&shallow_file)?.is_none() {
+            return Ok(Vec::new());
+        }
+        let lock =
+            gix_lock::File::acquire_to_update_resource(&shallow_file, gix_lock::acquire::Fail::Immediately, None)?;
+        // Read again now that we hold the lock to not lose concurrent changes.
+        let Some(commits) = gix_shallow::read(&shallow_file)? else {
+            return Ok(Vec::new());
+        };
+        let mut removed = Vec::new();
+        for id in &commits {
+            let exists = self
+                .try_find_header(*id)?
+                .is_some_and(|header| header.kind() == gix_object::Kind::Commit);
+            if !exists || should_remove(id) {
+                removed.push(*id);
+            }
+        }
+        if !removed.is_empty() {
+            let updates: Vec<_> = removed.iter().copied().map(gix_shallow::Update::Unshallow).collect();
+            gix_shallow::write(lock, Some(commits), &updates)?;
+        }
+        Ok(removed)
+    }
 }
@@ -214,7 +214,7 @@ impl delegate::Revision for Delegate<'_> {
             Ok(Some((ref_name, id))) => {
                 let id = match self.repo.find_reference(ref_name.as_bstr()) {
                     Ok(mut r) => {
-                        let id = r.peel_to_id().map(crate::Id::detach).unwrap_or(id);
+                        let id = r.peel_to_id().map_or(id, crate::Id::detach);
                         self.refs[self.idx] = Some(r.detach());
                         id
                     }
@@ -226,9 +226,7 @@ impl delegate::Revision for Delegate<'_> {
             Ok(None) => {
                 self.err.push(Error::PriorCheckoutOutOfRange {
                     desired: branch_no,
-                    available: prior_checkouts_iter(&mut head.log_iter())
-                        .map(Iterator::count)
-                        .unwrap_or(0),
+                    available: prior_checkouts_iter(&mut head.log_iter()).map_or(0, Iterator::count),
                 });
                 None
             }
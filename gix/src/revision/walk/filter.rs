@@ -0,0 +1,187 @@
+use gix_object::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    FindExt,
+};
+
+use crate::{
+    revision::walk::{iter, Error, Filter},
+    Repository,
+};
+
+type Commits<'repo> = Box<dyn Iterator<Item = Result<gix_traverse::commit::Info, iter::Error>> + 'repo>;
+
+/// Return the commits of `commits` that pass `filter`, with the first ones skipped and the amount limited as configured.
+pub(super) fn apply<'repo>(
+    repo: &'repo Repository,
+    filter: Filter,
+    commits: Commits<'repo>,
+) -> Result<Commits<'repo>, Error> {
+    let Filter {
+        author,
+        committer,
+        grep,
+        all_match,
+        invert_grep,
+        ignore_case,
+        since,
+        until,
+        min_parents,
+        max_parents,
+        max_count,
+        skip,
+    } = filter;
+    let matcher = Matcher {
+        author: compile(author, ignore_case)?,
+        committer: compile(committer, ignore_case)?,
+        grep: compile(grep, ignore_case)?,
+        all_match,
+        invert_grep,
+        since,
+        until,
+        min_parents,
+        max_parents,
+    };
+    let commits = if matcher.needs_commit() {
+        let mut buf = Vec::new();
+        let mut ident = BString::default();
+        Box::new(commits.filter_map(move |res| match res {
+            Ok(info) => match matcher.is_match(repo, &info.id, &mut buf, &mut ident) {
+                Ok(true) => Some(Ok(info)),
+                Ok(false) => None,
+                Err(err) => Some(Err(err)),
+            },
+            Err(err) => Some(Err(err)),
+        }))
+    } else {
+        commits
+    };
+    Ok(match (skip, max_count) {
+        (0, None) => commits,
+        (skip, max_count) => Box::new(commits.skip(skip).take(max_count.unwrap_or(usize::MAX))),
+    })
+}
+
+/// A compiled [`Filter`], without the options that only limit the amount of returned commits.
+struct Matcher {
+    author: Vec<Pattern>,
+    committer: Vec<Pattern>,
+    grep: Vec<Pattern>,
+    all_match: bool,
+    invert_grep: bool,
+    since: Option<gix_date::SecondsSinceUnixEpoch>,
+    until: Option<gix_date::SecondsSinceUnixEpoch>,
+    min_parents: Option<usize>,
+    max_parents: Option<usize>,
+}
+
+impl Matcher {
+    fn needs_commit(&self) -> bool {
+        !(self.author.is_empty()
+            && self.committer.is_empty()
+            && self.grep.is_empty()
+            && self.since.is_none()
+            && self.until.is_none()
+            && self.min_parents.is_none()
+            && self.max_parents.is_none())
+    }
+
+    fn is_match(
+        &self,
+        repo: &Repository,
+        id: &gix_hash::oid,
+        buf: &mut Vec<u8>,
+        ident: &mut BString,
+    ) -> Result<bool, iter::Error> {
+        let commit = repo.objects.find_commit(id, buf)?;
+
+        let num_parents = commit.parents.len();
+        if self.min_parents.is_some_and(|min| num_parents < min)
+            || self.max_parents.is_some_and(|max| num_parents > max)
+        {
+            return Ok(false);
+        }
+
+        if self.since.is_some() || self.until.is_some() {
+            let time = commit.time()?.seconds;
+            if self.since.is_some_and(|since| time < since) || self.until.is_some_and(|until| time > until) {
+                return Ok(false);
+            }
+        }
+
+        let mut matches_ident = |patterns: &[Pattern], signature: gix_actor::SignatureRef<'_>| {
+            patterns.is_empty() || {
+                ident.clear();
+                ident.push_str(signature.name);
+                ident.push_str(" <");
+                ident.push_str(signature.email);
+                ident.push_byte(b'>');
+                patterns.iter().any(|pattern| pattern.is_match(ident.as_ref()))
+            }
+        };
+        if !matches_ident(&self.author, commit.author()?) || !matches_ident(&self.committer, commit.committer()?) {
+            return Ok(false);
+        }
+
+        if !self.grep.is_empty() {
+            let mut matches = self.grep.iter().map(|pattern| pattern.is_match(commit.message));
+            let is_match = if self.all_match {
+                matches.all(|is_match| is_match)
+            } else {
+                matches.any(|is_match| is_match)
+            };
+            if is_match == self.invert_grep {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// A pattern to match identities or commit messages with.
+enum Pattern {
+    #[cfg(feature = "revparse-regex")]
+    Regex(regex::bytes::Regex),
+    #[cfg(not(feature = "revparse-regex"))]
+    Substring { text: BString, ignore_case: bool },
+}
+
+impl Pattern {
+    fn is_match(&self, haystack: &BStr) -> bool {
+        match self {
+            #[cfg(feature = "revparse-regex")]
+            Pattern::Regex(regex) => regex.is_match(haystack),
+            #[cfg(not(feature = "revparse-regex"))]
+            Pattern::Substring { text, ignore_case } => {
+                if *ignore_case {
+                    haystack.to_ascii_lowercase().contains_str(text.to_ascii_lowercase())
+                } else {
+                    haystack.contains_str(text)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(feature = "revparse-regex")]
+fn compile(patterns: Vec<BString>, ignore_case: bool) -> Result<Vec<Pattern>, Error> {
+    patterns
+        .into_iter()
+        .map(|pattern| {
+            Ok(Pattern::Regex(
+                regex::bytes::RegexBuilder::new(pattern.to_str_lossy().as_ref())
+                    .multi_line(true)
+                    .case_insensitive(ignore_case)
+                    .unicode(!ignore_case)
+                    .build()?,
+            ))
+        })
+        .collect()
+}
+
+#[cfg(not(feature = "revparse-regex"))]
+fn compile(patterns: Vec<BString>, ignore_case: bool) -> Result<Vec<Pattern>, Error> {
+    Ok(patterns
+        .into_iter()
+        .map(|text| Pattern::Substring { text, ignore_case })
+        .collect())
+}
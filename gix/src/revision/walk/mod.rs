@@ -1,9 +1,15 @@
 use gix_hash::ObjectId;
+use gix_object::bstr::BString;
 use gix_object::FindExt;
 use gix_traverse::commit::simple::CommitTimeOrder;
 
 use crate::{ext::ObjectIdExt, revision, Repository};
 
+mod filter;
+#[cfg(feature = "attributes")]
+mod simplify;
+mod topo;
+
 /// The error returned by [`Platform::all()`] and [`Platform::selected()`].
 #[derive(Debug, thiserror::Error)]
 #[allow(missing_docs)]
@@ -14,6 +20,21 @@ pub enum Error {
     ShallowCommits(#[from] crate::shallow::read::Error),
     #[error(transparent)]
     ConfigBoolean(#[from] crate::config::boolean::Error),
+    #[cfg(feature = "attributes")]
+    #[error(transparent)]
+    FindCommit(#[from] gix_object::find::existing_iter::Error),
+    #[cfg(feature = "attributes")]
+    #[error(transparent)]
+    FindTree(#[from] gix_object::find::existing_object::Error),
+    #[cfg(feature = "attributes")]
+    #[error(transparent)]
+    DecodeCommit(#[from] gix_object::decode::Error),
+    #[cfg(feature = "attributes")]
+    #[error("Limiting the history to ancestry paths requires hidden tips whose descendants to return")]
+    AncestryPathWithoutHiddenTips,
+    #[cfg(feature = "revparse-regex")]
+    #[error(transparent)]
+    InvalidRegex(#[from] regex::Error),
 }
 
 /// Specify how to sort commits during a [revision::Walk] traversal.
@@ -64,6 +85,15 @@ pub enum Sorting {
         /// The amount of seconds since unix epoch to use as cut-off time.
         seconds: gix_date::SecondsSinceUnixEpoch,
     },
+    /// Commits are sorted so that no parent is returned before all of its children, while avoiding to intermix commits
+    /// of multiple lines of history, like `git log --topo-order` does, which is also implied by `git log --graph`.
+    ///
+    /// In the *sample history* the order would be `8, 7, 4, 2, 6, 5, 3, 1`.
+    ///
+    /// # Performance
+    ///
+    /// All commits are traversed before the first one is returned.
+    TopoOrder,
 }
 
 impl Sorting {
@@ -74,10 +104,78 @@ impl Sorting {
             Sorting::ByCommitTimeCutoff { seconds, order } => {
                 gix_traverse::commit::simple::Sorting::ByCommitTimeCutoff { order, seconds }
             }
+            Sorting::TopoOrder => gix_traverse::commit::simple::Sorting::ByCommitTime(CommitTimeOrder::NewestFirst),
         })
     }
 }
 
+/// Specify how to simplify the history of a [revision::Walk] traversal [limited by a pathspec](Platform::with_pathspec()),
+/// similar to the *History Simplification* options of `git log`.
+///
+/// A commit is *TREESAME* to a parent if both have the same content at all paths matching the pathspec.
+/// By default, commits that are TREESAME to their parents aren't returned, and for merges only the first parent
+/// they are TREESAME to is followed.
+///
+/// All flags are `false` by default.
+#[cfg(feature = "attributes")]
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct Simplification {
+    /// Like `--full-history`, follow all parents of merges even if they are TREESAME to one of them,
+    /// and return merges unless they are TREESAME to all of their parents.
+    pub full_history: bool,
+    /// Like `--simplify-merges`, return the history of `full_history`, but remove merges and parents that
+    /// don't contribute changes of their own.
+    ///
+    /// This implies `full_history` and `rewrite_parents`.
+    pub simplify_merges: bool,
+    /// Like `--ancestry-path`, only return commits that are descendants of one of the [hidden tips](Platform::with_hidden()).
+    ///
+    /// This implies `full_history`, and it also applies without pathspec.
+    pub ancestry_path: bool,
+    /// Like `--sparse`, return all traversed commits, even if they are TREESAME.
+    pub sparse: bool,
+    /// Like `--parents` or `--graph`, replace the [parents](Info::parent_ids) of returned commits with their closest
+    /// ancestors that are also returned, and return merges that are TREESAME if they connect multiple relevant parents.
+    pub rewrite_parents: bool,
+}
+
+/// Select which commits of a [revision::Walk] traversal are returned, similar to the *Commit Limiting* options of `git log`.
+///
+/// Commits that are filtered out are still traversed, so their ancestors may be returned.
+/// A commit has to meet all configured conditions to be returned, and the default filter returns all commits.
+///
+/// Identities and messages are matched with regular expressions if the `revparse-regex` feature is enabled,
+/// or as substrings otherwise. Note that Git uses POSIX basic regular expressions by default instead.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Filter {
+    /// Like `--author`, patterns of which at least one has to match the `Name <email>` of the author.
+    pub author: Vec<BString>,
+    /// Like `--committer`, patterns of which at least one has to match the `Name <email>` of the committer.
+    pub committer: Vec<BString>,
+    /// Like `--grep`, patterns of which at least one has to match the commit message.
+    ///
+    /// `^` and `$` match at the beginning and end of each line.
+    pub grep: Vec<BString>,
+    /// Like `--all-match`, require all [`grep`](Self::grep) patterns to match instead of at least one.
+    pub all_match: bool,
+    /// Like `--invert-grep`, only return commits whose message doesn't match the [`grep`](Self::grep) patterns.
+    pub invert_grep: bool,
+    /// Like `--regexp-ignore-case`, match all patterns without regard to the case of ASCII characters.
+    pub ignore_case: bool,
+    /// Like `--since`, only return commits committed at or after the given time, as parsed with [`gix_date::parse()`] for example.
+    pub since: Option<gix_date::SecondsSinceUnixEpoch>,
+    /// Like `--until`, only return commits committed at or before the given time.
+    pub until: Option<gix_date::SecondsSinceUnixEpoch>,
+    /// Like `--min-parents`, only return commits with at least this many parents, with `Some(2)` being equivalent to `--merges`.
+    pub min_parents: Option<usize>,
+    /// Like `--max-parents`, only return commits with at most this many parents, with `Some(1)` being equivalent to `--no-merges`.
+    pub max_parents: Option<usize>,
+    /// Like `--max-count`, stop the traversal once this many commits were returned.
+    pub max_count: Option<usize>,
+    /// Like `--skip`, don't return the first commits that pass the filter.
+    pub skip: usize,
+}
+
 /// Information about a commit that we obtained naturally as part of the iteration.
 #[derive(Debug, Clone)]
 pub struct Info<'repo> {
@@ -161,6 +259,11 @@ pub struct Platform<'repo> {
     pub(crate) parents: gix_traverse::commit::Parents,
     pub(crate) use_commit_graph: Option<bool>,
     pub(crate) commit_graph: Option<gix_commitgraph::Graph>,
+    #[cfg(feature = "attributes")]
+    pub(crate) pathspec: Option<crate::Pathspec<'repo>>,
+    #[cfg(feature = "attributes")]
+    pub(crate) simplification: Simplification,
+    pub(crate) filter: Filter,
 }
 
 impl<'repo> Platform<'repo> {
@@ -174,6 +277,11 @@ impl<'repo> Platform<'repo> {
             use_commit_graph: None,
             commit_graph: None,
             boundary: Vec::new(),
+            #[cfg(feature = "attributes")]
+            pathspec: None,
+            #[cfg(feature = "attributes")]
+            simplification: Default::default(),
+            filter: Default::default(),
         }
     }
 }
@@ -221,11 +329,12 @@ impl Platform<'_> {
     /// A boundary is distinctly different from exclusive revspecs `^branch-to-not-list` in Git log.
     ///
     /// If this is not desired, [set the sorting](Self::sorting()) to something else right after this call.
+    /// [`TopoOrder`](Sorting::TopoOrder) is kept as is.
     pub fn with_boundary(mut self, ids: impl IntoIterator<Item = impl Into<ObjectId>>) -> Self {
         let (mut cutoff, order) = match self.sorting {
             Sorting::ByCommitTimeCutoff { seconds, order } => (Some(seconds), order),
             Sorting::ByCommitTime(order) => (None, order),
-            Sorting::BreadthFirst => (None, CommitTimeOrder::default()),
+            Sorting::BreadthFirst | Sorting::TopoOrder => (None, CommitTimeOrder::default()),
         };
         for id in ids.into_iter() {
             let id = id.into();
@@ -239,7 +348,7 @@ impl Platform<'_> {
             }
         }
 
-        if let Some(cutoff) = cutoff {
+        if let Some(cutoff) = cutoff.filter(|_| !matches!(self.sorting, Sorting::TopoOrder)) {
             self.sorting = Sorting::ByCommitTimeCutoff { seconds: cutoff, order }
         }
         self
@@ -260,6 +369,40 @@ impl Platform<'_> {
     }
 }
 
+/// Path-limiting
+#[cfg(feature = "attributes")]
+impl<'repo> Platform<'repo> {
+    /// Only return commits that change paths matching `pathspec`, with the history simplified as
+    /// [configured](Self::with_simplification()).
+    ///
+    /// Changed-path Bloom filters of the commit-graph are used if it's [used](Self::use_commit_graph()) and has them.
+    ///
+    /// # Performance
+    ///
+    /// All commits are traversed before the first one is returned, like Git does with `--topo-order`.
+    pub fn with_pathspec(mut self, pathspec: crate::Pathspec<'repo>) -> Self {
+        self.pathspec = Some(pathspec);
+        self
+    }
+
+    /// Set how the history of [path-limited](Self::with_pathspec()) traversals is simplified.
+    ///
+    /// Note that [ancestry paths](Simplification::ancestry_path) also apply to traversals without pathspec.
+    pub fn with_simplification(mut self, simplification: Simplification) -> Self {
+        self.simplification = simplification;
+        self
+    }
+}
+
+/// Commit-limiting
+impl Platform<'_> {
+    /// Only return the commits that pass `filter`, which is applied after the history was [simplified](Self::with_simplification()).
+    pub fn with_filter(mut self, filter: Filter) -> Self {
+        self.filter = filter;
+        self
+    }
+}
+
 /// Produce the iterator
 impl<'repo> Platform<'repo> {
     /// For each commit, let `filter` return `true` if it and its parents should be included in the traversal, or `false`
@@ -279,54 +422,105 @@ impl<'repo> Platform<'repo> {
             commit_graph,
             mut boundary,
             hidden,
+            #[cfg(feature = "attributes")]
+            pathspec,
+            #[cfg(feature = "attributes")]
+            simplification,
+            filter: commit_filter,
         } = self;
         boundary.sort();
-        Ok(revision::Walk {
-            repo,
-            inner: Box::new(
-                gix_traverse::commit::Simple::filtered(tips, &repo.objects, {
-                    // Note that specific shallow handling for commit-graphs isn't needed as these contain
-                    // all information there is, and exclude shallow parents to be structurally consistent.
-                    let shallow_commits = repo.shallow_commits()?;
-                    let mut grafted_parents_to_skip = Vec::new();
-                    let mut buf = Vec::new();
-                    move |id| {
-                        if !filter(id) {
-                            return false;
-                        }
-                        let id = id.to_owned();
-                        if boundary.binary_search(&id).is_ok() {
+        let may_use_commit_graph = use_commit_graph.map_or_else(|| repo.config.may_use_commit_graph(), Ok)?;
+        #[cfg(feature = "attributes")]
+        let limited = (pathspec.is_some() || simplification.ancestry_path).then(|| (tips.clone(), hidden.clone()));
+        let inner = gix_traverse::commit::Simple::filtered(tips, &repo.objects, {
+            // Note that specific shallow handling for commit-graphs isn't needed as these contain
+            // all information there is, and exclude shallow parents to be structurally consistent.
+            let shallow_commits = repo.shallow_commits()?;
+            let mut grafted_parents_to_skip = Vec::new();
+            let mut buf = Vec::new();
+            move |id| {
+                if !filter(id) {
+                    return false;
+                }
+                let id = id.to_owned();
+                if boundary.binary_search(&id).is_ok() {
+                    return false;
+                }
+                match shallow_commits.as_ref() {
+                    Some(commits) => {
+                        if let Ok(idx) = grafted_parents_to_skip.binary_search(&id) {
+                            grafted_parents_to_skip.remove(idx);
                             return false;
                         }
-                        match shallow_commits.as_ref() {
-                            Some(commits) => {
-                                if let Ok(idx) = grafted_parents_to_skip.binary_search(&id) {
-                                    grafted_parents_to_skip.remove(idx);
-                                    return false;
-                                }
-                                if commits.binary_search(&id).is_ok() {
-                                    if let Ok(commit) = repo.objects.find_commit_iter(&id, &mut buf) {
-                                        grafted_parents_to_skip.extend(commit.parent_ids());
-                                        grafted_parents_to_skip.sort();
-                                    }
-                                }
-                                true
+                        if commits.binary_search(&id).is_ok() {
+                            if let Ok(commit) = repo.objects.find_commit_iter(&id, &mut buf) {
+                                grafted_parents_to_skip.extend(commit.parent_ids());
+                                grafted_parents_to_skip.sort();
                             }
-                            None => true,
                         }
+                        true
                     }
+                    None => true,
+                }
+            }
+        })
+        .sorting(sorting.into_simple().expect("for now there is nothing else"))?
+        .parents(parents)
+        .hide(hidden)?
+        .commit_graph(commit_graph.or(may_use_commit_graph.then(|| repo.commit_graph().ok()).flatten()));
+
+        #[cfg(feature = "attributes")]
+        if let Some((tips, hidden)) = limited {
+            let shallow_commits = repo.shallow_commits()?;
+            let commits = inner
+                .map(|res| {
+                    res.map(|mut info| {
+                        // Shallow commits are roots, as their parents aren't available.
+                        if shallow_commits
+                            .as_ref()
+                            .is_some_and(|commits| commits.binary_search(&info.id).is_ok())
+                        {
+                            info.parent_ids.clear();
+                        }
+                        info
+                    })
                 })
-                .sorting(sorting.into_simple().expect("for now there is nothing else"))?
-                .parents(parents)
-                .hide(hidden)?
-                .commit_graph(
-                    commit_graph.or(use_commit_graph
-                        .map_or_else(|| self.repo.config.may_use_commit_graph(), Ok)?
-                        .then(|| self.repo.commit_graph().ok())
-                        .flatten()),
-                )
-                .map(|res| res.map_err(iter::Error::from)),
-            ),
+                .collect::<Result<Vec<_>, _>>()?;
+            let commits = simplify::Simplify {
+                repo,
+                pathspec,
+                graph: may_use_commit_graph.then(|| repo.commit_graph().ok()).flatten(),
+                hidden,
+                first_parent_only: matches!(parents, gix_traverse::commit::Parents::First),
+                options: simplification,
+            }
+            .run(&tips, commits)?;
+            let commits = if matches!(sorting, Sorting::TopoOrder) {
+                topo::sort(commits)
+            } else {
+                commits
+            };
+            return Ok(revision::Walk {
+                repo,
+                inner: filter::apply(repo, commit_filter, Box::new(commits.into_iter().map(Ok)))?,
+            });
+        }
+
+        if matches!(sorting, Sorting::TopoOrder) {
+            let commits = topo::sort(inner.collect::<Result<Vec<_>, _>>()?);
+            return Ok(revision::Walk {
+                repo,
+                inner: filter::apply(repo, commit_filter, Box::new(commits.into_iter().map(Ok)))?,
+            });
+        }
+
+        Ok(revision::Walk {
+            repo,
+            inner: filter::apply(
+                repo,
+                commit_filter,
+                Box::new(inner.map(|res| res.map_err(iter::Error::from))),
+            )?,
         })
     }
     /// Return an iterator to traverse all commits reachable as configured by the [Platform].
@@ -348,6 +542,10 @@ pub mod iter {
     pub enum Error {
         #[error(transparent)]
         SimpleTraversal(#[from] gix_traverse::commit::simple::Error),
+        #[error(transparent)]
+        FindCommit(#[from] gix_object::find::existing_object::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
     }
 }
 
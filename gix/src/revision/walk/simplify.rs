@@ -0,0 +1,714 @@
+use std::collections::VecDeque;
+
+use gix_hash::{oid, ObjectId};
+use gix_hashtable::{HashMap, HashSet};
+use gix_object::{
+    bstr::{BStr, BString, ByteSlice, ByteVec},
+    FindExt,
+};
+use gix_pathspec::{MagicSignature, SearchMode};
+
+use crate::{
+    revision::walk::{Error, Simplification},
+    Repository,
+};
+
+/// A commit of a limited traversal, along with what's known about it to simplify the history.
+struct Node {
+    /// The commit as returned by the traversal, with all of its parents.
+    info: gix_traverse::commit::Info,
+    /// The parents that remain after simplification.
+    parents: Vec<ObjectId>,
+    /// For each of `parents`, `true` if the commit is TREESAME to it.
+    treesame: Vec<bool>,
+    /// If `true`, the commit doesn't change the paths of interest.
+    is_treesame: bool,
+    /// If `false`, the commit was removed from the set of interesting commits after the traversal.
+    interesting: bool,
+}
+
+/// Simplify the history of all `commits` returned by a traversal, like Git does for `git log` and `git rev-list`.
+pub(super) struct Simplify<'repo> {
+    pub repo: &'repo Repository,
+    pub pathspec: Option<crate::Pathspec<'repo>>,
+    pub graph: Option<gix_commitgraph::Graph>,
+    pub hidden: Vec<ObjectId>,
+    pub first_parent_only: bool,
+    pub options: Simplification,
+}
+
+impl Simplify<'_> {
+    /// Return the commits out of `commits` that remain after simplifying the history starting at `tips`, in order.
+    /// Their parents are the ones that remain after simplification, or rewritten ones if this is configured.
+    pub fn run(
+        self,
+        tips: &[ObjectId],
+        commits: Vec<gix_traverse::commit::Info>,
+    ) -> Result<Vec<gix_traverse::commit::Info>, Error> {
+        let Simplify {
+            repo,
+            pathspec,
+            graph,
+            hidden,
+            first_parent_only,
+            options,
+        } = self;
+        let prune = pathspec
+            .as_ref()
+            .is_some_and(|spec| spec.search().patterns().len() != 0);
+        let bloom_paths = pathspec.as_ref().and_then(|spec| bloom_paths(spec.search()));
+        let order: Vec<_> = commits.iter().map(|info| info.id).collect();
+        let mut state = State {
+            repo,
+            pathspec,
+            graph,
+            bloom_paths,
+            bloom_keys: None,
+            trees: HashMap::default(),
+            buf: Vec::new(),
+            path: BString::default(),
+            nodes: commits
+                .into_iter()
+                .map(|info| {
+                    let parents = info.parent_ids.to_vec();
+                    let node = Node {
+                        treesame: vec![false; parents.len()],
+                        parents,
+                        is_treesame: false,
+                        interesting: true,
+                        info,
+                    };
+                    (node.info.id, node)
+                })
+                .collect(),
+            hidden,
+            first_parent_only,
+            prune,
+            simplify_history: !(options.full_history || options.simplify_merges || options.ancestry_path),
+            dense: !options.sparse,
+        };
+
+        let walked = if state.simplify_history {
+            let mut walked = HashSet::default();
+            let mut queue: VecDeque<_> = tips.iter().copied().filter(|id| state.is_interesting(id)).collect();
+            while let Some(id) = queue.pop_front() {
+                if !walked.insert(id) {
+                    continue;
+                }
+                state.try_to_simplify(&id)?;
+                queue.extend(
+                    state.nodes[&id]
+                        .parents
+                        .iter()
+                        .filter(|id| state.is_interesting(id) && !walked.contains(*id))
+                        .copied(),
+                );
+            }
+            walked
+        } else {
+            for id in &order {
+                state.try_to_simplify(id)?;
+            }
+            order.iter().copied().collect()
+        };
+
+        if options.ancestry_path {
+            if state.hidden.is_empty() {
+                return Err(Error::AncestryPathWithoutHiddenTips);
+            }
+            state.limit_to_ancestry(&order);
+            // Merges may have become TREESAME now that some of their parents aren't interesting anymore.
+            if state.prune && state.dense && !state.first_parent_only {
+                for id in &order {
+                    if state.is_interesting(id) && !state.nodes[id].is_treesame {
+                        state.update_treesame(id);
+                    }
+                }
+            }
+        }
+
+        let simplified_to_self = if options.simplify_merges {
+            Some(state.simplify_merges(&order)?)
+        } else {
+            None
+        };
+
+        let want_ancestry = options.rewrite_parents || options.simplify_merges;
+        let mut out = Vec::new();
+        for id in order {
+            if !walked.contains(&id)
+                || !state.is_interesting(&id)
+                || simplified_to_self.as_ref().is_some_and(|set| !set.contains(&id))
+            {
+                continue;
+            }
+            let node = &state.nodes[&id];
+            if state.prune && state.dense && node.is_treesame {
+                // Merges that are TREESAME are still needed to connect multiple relevant parents.
+                let connects_relevant_parents =
+                    want_ancestry && node.parents.iter().filter(|parent| state.is_relevant(parent)).count() >= 2;
+                if !connects_relevant_parents {
+                    continue;
+                }
+            }
+            let parents = if state.prune && state.dense && want_ancestry {
+                state.rewritten_parents(&id)
+            } else {
+                node.parents.clone()
+            };
+            out.push(gix_traverse::commit::Info {
+                parent_ids: parents.into_iter().collect(),
+                ..node.info.clone()
+            });
+        }
+        Ok(out)
+    }
+}
+
+struct State<'repo> {
+    repo: &'repo Repository,
+    pathspec: Option<crate::Pathspec<'repo>>,
+    graph: Option<gix_commitgraph::Graph>,
+    /// For each pathspec, the paths which all must be contained in a Bloom filter for it to possibly match,
+    /// or `None` if Bloom filters can't be used with the pathspec.
+    bloom_paths: Option<Vec<Vec<BString>>>,
+    /// The keys for `bloom_paths`, along with the settings they were created with.
+    bloom_keys: Option<(gix_commitgraph::bloom::Settings, Vec<Vec<gix_commitgraph::bloom::Key>>)>,
+    /// A map of commits to their trees.
+    trees: HashMap<ObjectId, ObjectId>,
+    buf: Vec<u8>,
+    path: BString,
+    nodes: HashMap<ObjectId, Node>,
+    /// The tips that were hidden, which are always relevant.
+    hidden: Vec<ObjectId>,
+    first_parent_only: bool,
+    /// If `true`, commits can be TREESAME, as there is a pathspec to limit the history with.
+    prune: bool,
+    /// If `true`, follow only the first parent a merge is TREESAME to.
+    simplify_history: bool,
+    /// If `false`, commits with a single parent are never TREESAME.
+    dense: bool,
+}
+
+/// Simplification
+impl State<'_> {
+    /// Determine if `id` is TREESAME to its parents, and with default simplification, drop all parents
+    /// but the first one it's TREESAME to.
+    fn try_to_simplify(&mut self, id: &oid) -> Result<(), Error> {
+        if !self.prune {
+            return Ok(());
+        }
+        let parents = self.nodes[id].parents.clone();
+        if parents.is_empty() {
+            let is_treesame = self.is_treesame_to_empty(id)?;
+            self.node_mut(id).is_treesame = is_treesame;
+            return Ok(());
+        }
+        if !self.dense && parents.len() == 1 {
+            return Ok(());
+        }
+
+        let mut treesame = vec![false; parents.len()];
+        let (mut relevant_parents, mut relevant_change, mut irrelevant_change) = (0, false, false);
+        for (nth, parent) in parents.iter().enumerate() {
+            let is_relevant = self.is_relevant(parent);
+            if is_relevant {
+                relevant_parents += 1;
+            }
+            if self.is_treesame_to(id, parent, nth)? {
+                if !self.simplify_history || !is_relevant {
+                    treesame[nth] = true;
+                    continue;
+                }
+                let node = self.node_mut(id);
+                node.parents = vec![*parent];
+                node.treesame = vec![true];
+                node.is_treesame = true;
+                return Ok(());
+            }
+            if is_relevant {
+                relevant_change = true;
+            } else {
+                irrelevant_change = true;
+            }
+        }
+        let node = self.node_mut(id);
+        node.treesame = treesame;
+        node.is_treesame = !if relevant_parents != 0 {
+            relevant_change
+        } else {
+            irrelevant_change
+        };
+        Ok(())
+    }
+
+    /// Mark all commits that aren't descendants of hidden tips as uninteresting, processing them in `order`.
+    fn limit_to_ancestry(&mut self, order: &[ObjectId]) {
+        let mut on_path: HashSet<ObjectId> = self.hidden.iter().copied().collect();
+        loop {
+            let mut made_progress = false;
+            for id in order.iter().rev() {
+                if on_path.contains(id) || !self.is_interesting(id) {
+                    continue;
+                }
+                if self.nodes[id].parents.iter().any(|parent| on_path.contains(parent)) {
+                    on_path.insert(*id);
+                    made_progress = true;
+                }
+            }
+            if !made_progress {
+                break;
+            }
+        }
+        for (id, node) in self.nodes.iter_mut() {
+            if !on_path.contains(id) {
+                node.interesting = false;
+            }
+        }
+    }
+
+    /// Rewrite the parents of all interesting commits to what they simplify to, and remove parents that are
+    /// redundant, like `--simplify-merges` does.
+    /// Return the set of commits that simplify to themselves.
+    fn simplify_merges(&mut self, order: &[ObjectId]) -> Result<HashSet<ObjectId>, Error> {
+        let mut simplified = HashMap::<ObjectId, ObjectId>::default();
+        for id in order {
+            let mut stack = vec![*id];
+            while let Some(id) = stack.last().copied() {
+                if simplified.contains_key(&id) {
+                    stack.pop();
+                    continue;
+                }
+                if !self.is_interesting(&id) || self.nodes[&id].parents.is_empty() {
+                    simplified.insert(id, id);
+                    stack.pop();
+                    continue;
+                }
+                let pending: Vec<_> = self.nodes[&id]
+                    .parents
+                    .iter()
+                    .filter(|parent| self.is_interesting(parent) && !simplified.contains_key(*parent))
+                    .copied()
+                    .collect();
+                if !pending.is_empty() {
+                    stack.extend(pending);
+                    continue;
+                }
+                stack.pop();
+
+                let simplified_of = |id: &ObjectId| simplified.get(id).copied().unwrap_or(*id);
+                let parents: Vec<_> = self.nodes[&id].parents.iter().map(simplified_of).collect();
+                self.node_mut(&id).parents = parents;
+                let mut num_parents = self.remove_duplicate_parents(&id)?;
+                if num_parents > 1 {
+                    let parents = self.nodes[&id].parents.clone();
+                    let mut marked = self.redundant_parents(&parents)?;
+                    for parent in &parents {
+                        if !marked.contains(parent)
+                            && self
+                                .nodes
+                                .get(parent)
+                                .is_some_and(|p| p.parents.is_empty() && p.is_treesame)
+                        {
+                            marked.push(*parent);
+                        }
+                    }
+                    if !marked.is_empty() {
+                        self.leave_one_treesame_to_parent(&id, &mut marked);
+                    }
+                    if !marked.is_empty() {
+                        num_parents = self.remove_marked_parents(&id, &marked)?;
+                    }
+                }
+
+                let node = &self.nodes[&id];
+                let target = match self.one_relevant_parent(&node.parents) {
+                    Some(parent) if num_parents != 0 && node.is_treesame => simplified_of(&parent),
+                    _ => id,
+                };
+                simplified.insert(id, target);
+            }
+        }
+        Ok(simplified
+            .into_iter()
+            .filter_map(|(id, target)| (id == target).then_some(id))
+            .collect())
+    }
+
+    /// Return the parents of `id`, with each TREESAME one replaced by its closest ancestor that isn't,
+    /// or removed if there is none.
+    fn rewritten_parents(&self, id: &oid) -> Vec<ObjectId> {
+        let mut out = Vec::new();
+        for parent in &self.nodes[id].parents {
+            let mut parent = *parent;
+            let keep = loop {
+                let Some(node) = self
+                    .nodes
+                    .get(&parent)
+                    .filter(|node| node.interesting && node.is_treesame)
+                else {
+                    break true;
+                };
+                if node.parents.is_empty() {
+                    break false;
+                }
+                match self.one_relevant_parent(&node.parents) {
+                    Some(next) => parent = next,
+                    None => break true,
+                }
+            };
+            if keep && !out.contains(&parent) {
+                out.push(parent);
+            }
+        }
+        out
+    }
+
+    /// Remove the parents of `id` that are listed more than once, and return the amount of remaining parents.
+    fn remove_duplicate_parents(&mut self, id: &oid) -> Result<usize, Error> {
+        let mut nth = 0;
+        while nth < self.nodes[id].parents.len() {
+            let parents = &self.nodes[id].parents;
+            if parents[..nth].contains(&parents[nth]) {
+                self.remove_parent(id, nth)?;
+            } else {
+                nth += 1;
+            }
+        }
+        Ok(self.nodes[id].parents.len())
+    }
+
+    /// Remove the `marked` parents of `id`, and return the amount of remaining parents.
+    fn remove_marked_parents(&mut self, id: &oid, marked: &[ObjectId]) -> Result<usize, Error> {
+        let mut nth = 0;
+        let mut removed = false;
+        while nth < self.nodes[id].parents.len() {
+            if marked.contains(&self.nodes[id].parents[nth]) {
+                self.remove_parent(id, nth)?;
+                removed = true;
+            } else {
+                nth += 1;
+            }
+        }
+        // Removing parents can only make commits TREESAME.
+        if removed && !self.nodes[id].is_treesame {
+            self.update_treesame(id);
+        }
+        Ok(self.nodes[id].parents.len())
+    }
+
+    /// If `id` is only TREESAME to parents that are `marked` for removal, keep the first of these.
+    /// That way, the parent which would be followed with default simplification is retained.
+    fn leave_one_treesame_to_parent(&self, id: &oid, marked: &mut Vec<ObjectId>) {
+        let node = &self.nodes[id];
+        let mut first_marked = None;
+        for (parent, treesame) in node.parents.iter().zip(&node.treesame) {
+            if !treesame {
+                continue;
+            }
+            if !marked.contains(parent) {
+                return;
+            }
+            first_marked.get_or_insert(*parent);
+        }
+        if let Some(parent) = first_marked {
+            marked.retain(|id| *id != parent);
+        }
+    }
+
+    /// Remove the `nth` parent of `id`, and update its TREESAME state if it isn't a merge anymore.
+    fn remove_parent(&mut self, id: &oid, nth: usize) -> Result<(), Error> {
+        let dense = self.dense;
+        let node = self.node_mut(id);
+        node.parents.remove(nth);
+        node.treesame.remove(nth);
+        match node.parents.len() {
+            0 => {
+                let is_treesame = self.is_treesame_to_empty(id)?;
+                self.node_mut(id).is_treesame = is_treesame;
+            }
+            1 => node.is_treesame = node.treesame[0] && dense,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Recompute if the merge `id` is TREESAME, which is the case if it's TREESAME to all relevant parents,
+    /// or to all parents if none of them is relevant.
+    fn update_treesame(&mut self, id: &oid) {
+        let node = &self.nodes[id];
+        if node.parents.len() < 2 {
+            return;
+        }
+        let (mut relevant_parents, mut relevant_change, mut irrelevant_change) = (0, false, false);
+        for (parent, treesame) in node.parents.iter().zip(&node.treesame) {
+            if self.is_relevant(parent) {
+                relevant_parents += 1;
+                relevant_change |= !treesame;
+            } else {
+                irrelevant_change |= !treesame;
+            }
+        }
+        self.node_mut(id).is_treesame = !if relevant_parents != 0 {
+            relevant_change
+        } else {
+            irrelevant_change
+        };
+    }
+
+    /// Return the only parent of `parents` that matters, or `None` if there are multiple relevant parents or none at all.
+    fn one_relevant_parent(&self, parents: &[ObjectId]) -> Option<ObjectId> {
+        if self.first_parent_only || parents.len() == 1 {
+            return parents.first().copied();
+        }
+        let mut relevant = parents.iter().filter(|parent| self.is_relevant(parent));
+        match (relevant.next(), relevant.next()) {
+            (Some(parent), None) => Some(*parent),
+            _ => None,
+        }
+    }
+
+    /// Return all `parents` that are ancestors of one of the other `parents`.
+    fn redundant_parents(&mut self, parents: &[ObjectId]) -> Result<Vec<ObjectId>, Error> {
+        let mut out = Vec::new();
+        for candidate in parents {
+            for other in parents.iter().filter(|other| *other != candidate) {
+                if self.is_ancestor(candidate, other)? {
+                    out.push(*candidate);
+                    break;
+                }
+            }
+        }
+        Ok(out)
+    }
+
+    /// Return `true` if `ancestor` can be reached from `id` by following parents.
+    fn is_ancestor(&mut self, ancestor: &oid, id: &oid) -> Result<bool, Error> {
+        let min_generation = self
+            .graph
+            .as_ref()
+            .and_then(|graph| graph.commit_by_id(ancestor))
+            .map(|commit| commit.generation());
+        let mut seen = HashSet::default();
+        let mut queue = VecDeque::from([id.to_owned()]);
+        while let Some(id) = queue.pop_front() {
+            if id == ancestor {
+                return Ok(true);
+            }
+            if !seen.insert(id) {
+                continue;
+            }
+            let generation = self
+                .graph
+                .as_ref()
+                .and_then(|graph| graph.commit_by_id(id))
+                .map(|commit| commit.generation());
+            if min_generation.zip(generation).is_some_and(|(min, gen)| gen < min) {
+                continue;
+            }
+            queue.extend(self.parents_of(&id)?);
+        }
+        Ok(false)
+    }
+}
+
+/// Access
+impl State<'_> {
+    fn node_mut(&mut self, id: &oid) -> &mut Node {
+        self.nodes.get_mut(id).expect("only called for traversed commits")
+    }
+
+    fn is_interesting(&self, id: &oid) -> bool {
+        self.nodes.get(id).is_some_and(|node| node.interesting)
+    }
+
+    /// Interesting commits and hidden tips are relevant, but uninteresting commits aren't.
+    fn is_relevant(&self, id: &oid) -> bool {
+        self.is_interesting(id) || self.hidden.iter().any(|hidden| hidden.as_ref() == id)
+    }
+
+    /// Return all parents of the commit `id`.
+    fn parents_of(&mut self, id: &oid) -> Result<Vec<ObjectId>, Error> {
+        if let Some(node) = self.nodes.get(id) {
+            return Ok(node.info.parent_ids.to_vec());
+        }
+        if let Some(parents) = self.graph.as_ref().and_then(|graph| {
+            let commit = graph.commit_by_id(id)?;
+            commit
+                .iter_parents()
+                .map(|pos| pos.map(|pos| graph.id_at(pos).to_owned()))
+                .collect::<Result<Vec<_>, _>>()
+                .ok()
+        }) {
+            return Ok(parents);
+        }
+        Ok(self
+            .repo
+            .objects
+            .find_commit_iter(id, &mut self.buf)?
+            .parent_ids()
+            .collect())
+    }
+
+    fn tree_id(&mut self, id: &oid) -> Result<ObjectId, Error> {
+        if let Some(tree) = self.trees.get(id) {
+            return Ok(*tree);
+        }
+        let tree = match self.graph.as_ref().and_then(|graph| graph.commit_by_id(id)) {
+            Some(commit) => commit.root_tree_id().to_owned(),
+            None => self.repo.objects.find_commit_iter(id, &mut self.buf)?.tree_id()?,
+        };
+        self.trees.insert(id.to_owned(), tree);
+        Ok(tree)
+    }
+}
+
+/// Tree comparisons
+impl State<'_> {
+    /// Return `true` if `id` has the same content as its `nth` `parent` at all paths matching the pathspec.
+    fn is_treesame_to(&mut self, id: &oid, parent: &oid, nth: usize) -> Result<bool, Error> {
+        if nth == 0 && self.bloom_filter_excludes_change(id) {
+            return Ok(true);
+        }
+        let (lhs, rhs) = (self.tree_id(parent)?, self.tree_id(id)?);
+        Ok(!self.trees_differ(Some(lhs), Some(rhs))?)
+    }
+
+    /// Return `true` if `id` has no paths matching the pathspec.
+    fn is_treesame_to_empty(&mut self, id: &oid) -> Result<bool, Error> {
+        let tree = self.tree_id(id)?;
+        Ok(!self.trees_differ(None, Some(tree))?)
+    }
+
+    /// Return `true` if the changed-path Bloom filter of `id` shows that it definitely didn't change
+    /// paths matching the pathspec compared to its first parent.
+    fn bloom_filter_excludes_change(&mut self, id: &oid) -> bool {
+        let (Some(graph), Some(paths)) = (self.graph.as_ref(), self.bloom_paths.as_ref()) else {
+            return false;
+        };
+        let Some(filter) = graph.commit_by_id(id).and_then(|commit| commit.bloom_filter()) else {
+            return false;
+        };
+        let settings = *filter.settings();
+        let keys = match &mut self.bloom_keys {
+            Some((cached_settings, keys)) if *cached_settings == settings => keys,
+            keys => {
+                let new_keys = paths
+                    .iter()
+                    .map(|paths| {
+                        paths
+                            .iter()
+                            .map(|path| gix_commitgraph::bloom::Key::new(path, &settings))
+                            .collect()
+                    })
+                    .collect();
+                &mut keys.insert((settings, new_keys)).1
+            }
+        };
+        !keys
+            .iter()
+            .any(|keys: &Vec<_>| keys.iter().all(|key| filter.contains(key)))
+    }
+
+    /// Return `true` if the trees `lhs` and `rhs`, with `None` being an empty tree, differ in a path matching the pathspec.
+    fn trees_differ(&mut self, lhs: Option<ObjectId>, rhs: Option<ObjectId>) -> Result<bool, Error> {
+        self.path.clear();
+        self.trees_differ_at_path(lhs, rhs)
+    }
+
+    fn trees_differ_at_path(&mut self, lhs: Option<ObjectId>, rhs: Option<ObjectId>) -> Result<bool, Error> {
+        if lhs == rhs {
+            return Ok(false);
+        }
+        let lhs = self.tree_entries(lhs)?;
+        let rhs = self.tree_entries(rhs)?;
+        let (mut lhs, mut rhs) = (lhs.into_iter().peekable(), rhs.into_iter().peekable());
+        loop {
+            let (lhs, rhs) = match (lhs.peek(), rhs.peek()) {
+                (None, None) => return Ok(false),
+                (Some(a), Some(b)) => match a.cmp(b) {
+                    std::cmp::Ordering::Less => (lhs.next(), None),
+                    std::cmp::Ordering::Greater => (None, rhs.next()),
+                    std::cmp::Ordering::Equal => (lhs.next(), rhs.next()),
+                },
+                (Some(_), None) => (lhs.next(), None),
+                (None, Some(_)) => (None, rhs.next()),
+            };
+            if let (Some(a), Some(b)) = (&lhs, &rhs) {
+                if a.mode == b.mode && a.oid == b.oid {
+                    continue;
+                }
+            }
+            let name = &lhs.as_ref().or(rhs.as_ref()).expect("at least one side").filename;
+            let prev_len = self.path.len();
+            if !self.path.is_empty() {
+                self.path.push_byte(b'/');
+            }
+            self.path.push_str(name);
+
+            let tree_of = |entry: &Option<gix_object::tree::Entry>| {
+                entry
+                    .as_ref()
+                    .filter(|entry| entry.mode.is_tree())
+                    .map(|entry| entry.oid)
+            };
+            let (lhs_tree, rhs_tree) = (tree_of(&lhs), tree_of(&rhs));
+            let non_tree_changed = [&lhs, &rhs].into_iter().flatten().any(|entry| !entry.mode.is_tree());
+            let pathspec = self.pathspec.as_mut().expect("only called when pruning");
+            let mut differs = non_tree_changed && pathspec.is_included(self.path.as_bstr(), Some(false));
+            if !differs
+                && (lhs_tree.is_some() || rhs_tree.is_some())
+                && pathspec
+                    .search()
+                    .can_match_relative_path(self.path.as_bstr(), Some(true))
+            {
+                differs = self.trees_differ_at_path(lhs_tree, rhs_tree)?;
+            }
+            self.path.truncate(prev_len);
+            if differs {
+                return Ok(true);
+            }
+        }
+    }
+
+    fn tree_entries(&mut self, id: Option<ObjectId>) -> Result<Vec<gix_object::tree::Entry>, Error> {
+        Ok(match id {
+            Some(id) => self.repo.objects.find_tree(&id, &mut self.buf)?.into_owned().entries,
+            None => Vec::new(),
+        })
+    }
+}
+
+/// Return the paths that changed-path Bloom filters must contain for each pattern in `search` to possibly match,
+/// or `None` if one of the patterns can't be checked with Bloom filters.
+fn bloom_paths(search: &gix_pathspec::Search) -> Option<Vec<Vec<BString>>> {
+    search
+        .patterns()
+        .map(|pattern| {
+            if pattern.is_excluded()
+                || pattern.always_matches()
+                || !pattern.attributes.is_empty()
+                || pattern.signature.contains(MagicSignature::ICASE)
+            {
+                return None;
+            }
+            let mut path: &BStr = pattern.path();
+            if pattern.search_mode != SearchMode::Literal {
+                if let Some(wildcard) = path.find_byteset(b"*?[\\") {
+                    path = path[..wildcard]
+                        .rfind_byte(b'/')
+                        .map_or_else(Default::default, |end| path[..end].as_bstr());
+                }
+            }
+            let path = path.trim_end_with(|c| c == '/');
+            if path.is_empty() {
+                return None;
+            }
+            Some(
+                std::iter::successors(Some(path), |path| path.rfind_byte(b'/').map(|end| &path[..end]))
+                    .map(Into::into)
+                    .collect(),
+            )
+        })
+        .collect()
+}
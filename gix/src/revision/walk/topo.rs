@@ -0,0 +1,50 @@
+use gix_hash::ObjectId;
+use gix_traverse::commit::Info;
+
+/// Sort `commits`, which are expected in the order of their commit time, newest first, so that no parent is returned
+/// before all of its children, while avoiding to intermix commits of multiple lines of history.
+///
+/// This is what `sort_in_topological_order()` of Git does, in the *graph order* used by `--topo-order` and `--graph`.
+/// Parents that aren't part of `commits` are ignored.
+pub(super) fn sort(commits: Vec<Info>) -> Vec<Info> {
+    let mut ids: Vec<(ObjectId, usize)> = commits.iter().enumerate().map(|(idx, info)| (info.id, idx)).collect();
+    ids.sort_unstable();
+    let index_of = |id: &ObjectId| {
+        ids.binary_search_by(|(candidate, _)| candidate.cmp(id))
+            .ok()
+            .map(|pos| ids[pos].1)
+    };
+    let parents: Vec<Vec<usize>> = commits
+        .iter()
+        .map(|info| info.parent_ids.iter().filter_map(index_of).collect())
+        .collect();
+
+    // An in-degree of 1 means no child of the commit is left to be returned, and 0 that it was returned already.
+    let mut indegree = vec![1usize; commits.len()];
+    for &parent in parents.iter().flatten() {
+        indegree[parent] += 1;
+    }
+
+    // The tips are returned in the order they were traversed in, hence the reversal for use as stack.
+    let mut stack: Vec<usize> = (0..commits.len()).filter(|&idx| indegree[idx] == 1).rev().collect();
+    let mut order = Vec::with_capacity(commits.len());
+    while let Some(idx) = stack.pop() {
+        for &parent in &parents[idx] {
+            if indegree[parent] == 0 {
+                continue;
+            }
+            indegree[parent] -= 1;
+            if indegree[parent] == 1 {
+                stack.push(parent);
+            }
+        }
+        indegree[idx] = 0;
+        order.push(idx);
+    }
+
+    let mut commits: Vec<_> = commits.into_iter().map(Some).collect();
+    order
+        .into_iter()
+        .map(|idx| commits[idx].take().expect("each commit is returned once"))
+        .collect()
+}
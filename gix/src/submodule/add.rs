@@ -0,0 +1,169 @@
+use std::{path::Path, sync::atomic::AtomicBool};
+
+use gix_features::progress::{BoxedDynNestedProgress, DynNestedProgress};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    submodule::{modify, mutate},
+    Repository,
+};
+
+/// Options for use in [`Repository::add_submodule()`].
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    /// The name of the submodule, or `None` to use its path as name like Git does.
+    pub name: Option<BString>,
+}
+
+/// The outcome of [`Repository::add_submodule()`].
+#[derive(Debug, Clone)]
+pub struct Outcome {
+    /// The name of the submodule as written to `.gitmodules`.
+    pub name: BString,
+    /// The URL that was registered in the configuration of the superproject, with relative URLs being resolved.
+    pub url: BString,
+    /// The commit the submodule was checked out at, as recorded in the index of the superproject.
+    pub id: gix_hash::ObjectId,
+}
+
+/// The error returned by [`Repository::add_submodule()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Modify(#[from] modify::Error),
+    #[error("Refusing to add a submodule at '{path}' as it exists already")]
+    PathExists { path: BString },
+    #[error("Refusing to clone into the existing submodule repository at '{}'", path.display())]
+    GitDirExists { path: std::path::PathBuf },
+    #[error(transparent)]
+    ResolveUrl(#[from] gix_submodule::relative_url::Error),
+    #[error(transparent)]
+    PrepareClone(#[from] crate::clone::Error),
+    #[error(transparent)]
+    Clone(#[from] crate::clone::fetch::Error),
+    #[error(transparent)]
+    Connect(#[from] crate::submodule::connect::Error),
+    #[error(transparent)]
+    Open(#[from] crate::open::Error),
+    #[error(transparent)]
+    Checkout(#[from] crate::clone::checkout::main_worktree::Error),
+    #[error(transparent)]
+    FindHead(#[from] crate::reference::find::existing::Error),
+    #[error("The repository at '{url}' has no commit to check out")]
+    UnbornHead { url: BString },
+    #[error(transparent)]
+    ReadConfig(#[from] gix_config::file::init::from_paths::Error),
+    #[error(transparent)]
+    SetConfig(#[from] gix_config::file::set_raw_value::Error),
+}
+
+impl Repository {
+    /// Add a submodule cloned from `url` at `path` relative to the worktree of this repository, like `git submodule add` does,
+    /// and return information about it.
+    ///
+    /// The repository is cloned into [its place](crate::Submodule::git_dir()) within this repository and its default branch
+    /// is checked out at `path`. Then the submodule is written to `.gitmodules` and registered in the local configuration,
+    /// while its gitlink and the changed `.gitmodules` file are staged in the index.
+    /// Relative URLs are [resolved](gix_submodule::relative_url::resolve()) against the URL of the default remote, but are
+    /// written to `.gitmodules` unchanged.
+    ///
+    /// The name of the submodule is validated to not allow escaping the directory holding the repositories of submodules,
+    /// and nothing is changed if cloning fails.
+    pub fn add_submodule(
+        &self,
+        url: &BStr,
+        path: &BStr,
+        options: &Options,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::add_submodule()");
+        let workdir = self.workdir().ok_or(modify::Error::BareRepository)?;
+        let name = options.name.as_ref().map_or(path, |name| name.as_bstr());
+        mutate::validate(self, name, path)?;
+        let mut modules = mutate::modules_file(self)?;
+        modules.add(name, path, url).map_err(modify::Error::from)?;
+
+        let work_dir = workdir.join(gix_path::from_bstr(path));
+        let work_dir_is_empty = match std::fs::read_dir(&work_dir) {
+            Ok(mut entries) => entries.next().is_none(),
+            Err(_) => std::fs::symlink_metadata(&work_dir).is_err(),
+        };
+        if !work_dir_is_empty || mutate::index_has_path(self, path)? {
+            return Err(Error::PathExists { path: path.to_owned() });
+        }
+        let git_dir = self.common_dir().join("modules").join(gix_path::from_bstr(name));
+        if git_dir.exists() {
+            return Err(Error::GitDirExists { path: git_dir });
+        }
+
+        let resolved_url = mutate::resolve_url(self, url, None)?;
+        let id = match clone(
+            self,
+            resolved_url.as_ref(),
+            &work_dir,
+            &git_dir,
+            &mut progress.add_child(path.to_string()),
+            should_interrupt,
+        ) {
+            Ok(id) => id,
+            Err(err) => {
+                std::fs::remove_dir_all(&git_dir).ok();
+                std::fs::remove_dir_all(&work_dir).ok();
+                return Err(err);
+            }
+        };
+
+        let config_path = mutate::local_config_path(self);
+        let mut config = mutate::read_config(&config_path)?;
+        config.set_raw_value_by("submodule", Some(name), "url", resolved_url.as_bstr())?;
+        config.set_raw_value_by("submodule", Some(name), "active", "true")?;
+        mutate::write_config(&config_path, &config).map_err(|source| modify::Error::Io {
+            source,
+            path: config_path,
+        })?;
+        mutate::write_modules_and_index(self, &modules, None, Some((path, id)))?;
+
+        Ok(Outcome {
+            name: name.to_owned(),
+            url: resolved_url,
+            id,
+        })
+    }
+}
+
+/// Clone `url` into `git_dir`, check it out in `work_dir` and return the commit that was checked out.
+fn clone(
+    repo: &Repository,
+    url: &BStr,
+    work_dir: &Path,
+    git_dir: &Path,
+    progress: &mut BoxedDynNestedProgress,
+    should_interrupt: &AtomicBool,
+) -> Result<gix_hash::ObjectId, Error> {
+    let io_err = |path: &Path| {
+        let path = path.to_owned();
+        move |source| modify::Error::Io { source, path }
+    };
+    std::fs::create_dir_all(git_dir).map_err(io_err(git_dir))?;
+    let mut prepare = crate::clone::PrepareFetch::new(
+        gix_url::parse(url).map_err(crate::clone::Error::from)?,
+        git_dir,
+        crate::create::Kind::Bare,
+        Default::default(),
+        repo.options.clone(),
+    )?;
+    let (cloned, _outcome) = prepare.fetch_only(&mut *progress, should_interrupt)?;
+    drop(cloned);
+    std::fs::create_dir_all(work_dir).map_err(io_err(work_dir))?;
+    mutate::connect_work_tree_and_git_dir(work_dir, git_dir)?;
+
+    let sm_repo = crate::open_opts(git_dir, repo.options.clone())?;
+    crate::clone::checkout::main_worktree::checkout_into_empty_worktree(&sm_repo, None, progress, should_interrupt)?;
+    sm_repo
+        .head()?
+        .id()
+        .map(crate::Id::detach)
+        .ok_or_else(|| Error::UnbornHead { url: url.to_owned() })
+}
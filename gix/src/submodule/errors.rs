@@ -110,3 +110,215 @@ pub mod head_id {
         PathConfiguration(#[from] gix_submodule::config::path::Error),
     }
 }
+
+///
+pub mod init {
+    use crate::bstr::BString;
+
+    /// The error returned by [Submodule::init()](crate::Submodule::init()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        IsActive(#[from] crate::submodule::is_active::Error),
+        #[error("The submodule '{submodule}' has no 'url' field in .gitmodules")]
+        MissingUrl { submodule: BString },
+        #[error(transparent)]
+        ResolveUrl(#[from] gix_submodule::relative_url::Error),
+        #[error(transparent)]
+        ReadConfig(#[from] gix_config::file::init::from_paths::Error),
+        #[error(transparent)]
+        SetConfig(#[from] gix_config::file::set_raw_value::Error),
+        #[error("Could not write configuration file at '{}'", path.display())]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+    }
+}
+
+///
+pub mod sync {
+    use crate::bstr::BString;
+
+    /// The error returned by [Submodule::sync()](crate::Submodule::sync()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        IsActive(#[from] crate::submodule::is_active::Error),
+        #[error("The submodule '{submodule}' has no 'url' field in .gitmodules")]
+        MissingUrl { submodule: BString },
+        #[error(transparent)]
+        Path(#[from] gix_submodule::config::path::Error),
+        #[error(transparent)]
+        Open(#[from] crate::submodule::open::Error),
+        #[error(transparent)]
+        ResolveUrl(#[from] gix_submodule::relative_url::Error),
+        #[error(transparent)]
+        ReadConfig(#[from] gix_config::file::init::from_paths::Error),
+        #[error(transparent)]
+        SetConfig(#[from] gix_config::file::set_raw_value::Error),
+        #[error("Could not write configuration file at '{}'", path.display())]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+    }
+}
+
+///
+pub mod connect {
+    /// The error returned when connecting the worktree of a submodule with its repository.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        ReadConfig(#[from] gix_config::file::init::from_paths::Error),
+        #[error(transparent)]
+        SetConfig(#[from] gix_config::file::set_raw_value::Error),
+        #[error("Could not access '{}'", path.display())]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+    }
+}
+
+///
+pub mod absorb_git_dir {
+    /// The error returned by [Submodule::absorb_git_dir()](crate::Submodule::absorb_git_dir()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Path(#[from] gix_submodule::config::path::Error),
+        #[error("Refusing to move the submodule repository into the existing directory at '{}'", path.display())]
+        DestinationExists { path: std::path::PathBuf },
+        #[error("Could not move the submodule repository")]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+        #[error(transparent)]
+        Connect(#[from] crate::submodule::connect::Error),
+        #[error(transparent)]
+        Reconnect(#[from] crate::submodule::reconnect::Error),
+    }
+}
+
+///
+pub mod reconnect {
+    /// The error returned when reconnecting the worktrees of nested submodules with their repositories after these were moved.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Path(#[from] gix_submodule::config::path::Error),
+        #[error(transparent)]
+        Connect(#[from] crate::submodule::connect::Error),
+        #[error(transparent)]
+        OpenRepository(#[from] crate::open::Error),
+        #[error(transparent)]
+        Modules(#[from] crate::submodule::modules::Error),
+    }
+}
+
+///
+pub mod modify {
+    use crate::bstr::BString;
+
+    /// The error returned when changing `.gitmodules` and the index of the superproject to add, move or remove a submodule.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("Submodules can only be changed in repositories with a worktree")]
+        BareRepository,
+        #[error(transparent)]
+        InvalidName(#[from] gix_validate::submodule::name::Error),
+        #[error("The submodule path '{path}' is invalid")]
+        InvalidPath {
+            path: BString,
+            source: gix_validate::path::component::Error,
+        },
+        #[error(transparent)]
+        ProtectOptions(#[from] crate::config::boolean::Error),
+        #[error(transparent)]
+        OpenModulesFile(#[from] crate::submodule::open_modules_file::Error),
+        #[error(transparent)]
+        EditModules(#[from] gix_submodule::edit::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error(transparent)]
+        WriteBlob(#[from] crate::object::write::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_index::file::write::Error),
+        #[error("Could not access '{}'", path.display())]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+    }
+}
+
+///
+pub mod deinit {
+    /// The error returned by [Submodule::deinit()](crate::Submodule::deinit()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Validate(#[from] crate::submodule::modify::Error),
+        #[error(transparent)]
+        Path(#[from] gix_submodule::config::path::Error),
+        #[error("Refusing to remove the worktree at '{}' as it contains the repository of the submodule", path.display())]
+        OldForm { path: std::path::PathBuf },
+        #[error(transparent)]
+        ReadConfig(#[from] gix_config::file::init::from_paths::Error),
+        #[error("Could not access '{}'", path.display())]
+        Io {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+    }
+}
+
+///
+pub mod move_to {
+    use crate::bstr::BString;
+
+    /// The error returned by [Submodule::move_to()](crate::Submodule::move_to()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Modify(#[from] crate::submodule::modify::Error),
+        #[error(transparent)]
+        Path(#[from] gix_submodule::config::path::Error),
+        #[error(transparent)]
+        IndexId(#[from] crate::submodule::index_id::Error),
+        #[error("The submodule '{name}' isn't present in the index")]
+        NotInIndex { name: BString },
+        #[error("Refusing to move the submodule to '{path}' as it exists already")]
+        DestinationExists { path: BString },
+        #[error(transparent)]
+        Connect(#[from] crate::submodule::connect::Error),
+        #[error(transparent)]
+        Reconnect(#[from] crate::submodule::reconnect::Error),
+    }
+}
+
+///
+pub mod remove {
+    /// The error returned by [Submodule::remove()](crate::Submodule::remove()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Deinit(#[from] crate::submodule::deinit::Error),
+        #[error(transparent)]
+        Path(#[from] gix_submodule::config::path::Error),
+        #[error(transparent)]
+        Modify(#[from] crate::submodule::modify::Error),
+    }
+}
@@ -0,0 +1,353 @@
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+};
+
+use gix_features::progress::{BoxedDynNestedProgress, DynNestedProgress};
+use gix_hash::ObjectId;
+
+use crate::{
+    bstr::{BString, ByteSlice},
+    config::tree::Submodule as SubmoduleKey,
+    remote,
+    submodule::config::FetchRecurse,
+    Repository,
+};
+
+/// Options for use in [`Repository::fetch_submodules()`].
+#[derive(Debug, Default, Clone)]
+pub struct Options {
+    /// How to decide which submodules to fetch, or `None` to use the configuration of each submodule, like
+    /// `submodule.<name>.fetchRecurseSubmodules`, `fetch.recurseSubmodules` and `submodule.recurse`.
+    /// If nothing is configured, submodules are fetched [on demand](FetchRecurse::OnDemand).
+    pub recurse: Option<FetchRecurse>,
+    /// The number of submodules to fetch in parallel, with `0` meaning one per logical core, or `None` to use
+    /// `submodule.fetchJobs`, which defaults to `1`.
+    pub jobs: Option<usize>,
+}
+
+/// Information about a single submodule that was considered for fetching.
+#[derive(Debug, Clone)]
+pub struct Item {
+    /// The name of the submodule.
+    pub name: BString,
+    /// The path of the submodule, relative to the worktree of its superproject.
+    pub path: BString,
+    /// The outcome of the fetch operation, or `None` if the submodule wasn't fetched as no commit was missing.
+    pub outcome: Option<remote::fetch::Outcome>,
+    /// The submodules of this submodule that were considered after it was fetched.
+    pub submodules: Vec<Item>,
+}
+
+/// The outcome of [`Repository::fetch_submodules()`].
+#[derive(Debug, Clone, Default)]
+pub struct Outcome {
+    /// The populated submodules that were considered, in order of their appearance in `.gitmodules`.
+    pub submodules: Vec<Item>,
+}
+
+/// The error returned by [`Repository::fetch_submodules()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error(transparent)]
+    Modules(#[from] crate::submodule::modules::Error),
+    #[error(transparent)]
+    Path(#[from] crate::submodule::config::path::Error),
+    #[error(transparent)]
+    IsActive(#[from] crate::submodule::is_active::Error),
+    #[error(transparent)]
+    FetchRecurse(#[from] crate::submodule::fetch_recurse::Error),
+    #[error(transparent)]
+    Recurse(#[from] crate::config::boolean::Error),
+    #[error(transparent)]
+    FetchJobs(#[from] crate::config::unsigned_integer::Error),
+    #[error(transparent)]
+    IndexId(#[from] crate::submodule::index_id::Error),
+    #[error(transparent)]
+    FindObject(#[from] crate::object::find::existing::Error),
+    #[error(transparent)]
+    Tree(#[from] crate::object::commit::Error),
+    #[error("Failed to fetch submodule '{name}'")]
+    Submodule {
+        name: BString,
+        source: Box<submodule::Error>,
+    },
+}
+
+///
+pub mod submodule {
+    /// The error returned when fetching a single submodule as part of [`Repository::fetch_submodules()`](crate::Repository::fetch_submodules()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        Open(#[from] crate::open::Error),
+        #[error("The submodule repository at '{}' has no remote to fetch from", git_dir.display())]
+        NoRemote { git_dir: std::path::PathBuf },
+        #[error(transparent)]
+        FindRemote(#[from] crate::remote::find::existing::Error),
+        #[error(transparent)]
+        Refspec(#[from] gix_refspec::parse::Error),
+        #[error(transparent)]
+        Connect(#[from] crate::remote::connect::Error),
+        #[error(transparent)]
+        PrepareFetch(#[from] crate::remote::fetch::prepare::Error),
+        #[error(transparent)]
+        Fetch(#[from] crate::remote::fetch::Error),
+        #[error(transparent)]
+        Recurse(#[from] Box<super::Error>),
+    }
+}
+
+/// Everything needed to fetch one submodule, independently of the superproject.
+struct Task {
+    name: BString,
+    path: BString,
+    git_dir: PathBuf,
+    mode: FetchRecurse,
+    required: Vec<ObjectId>,
+}
+
+impl Repository {
+    /// Fetch the submodules of this repository from their default remotes, like `git fetch --recurse-submodules` does,
+    /// typically after this repository was fetched with the given `fetched` outcome.
+    ///
+    /// Only active submodules whose repository is present are considered. Depending on [`Options::recurse`] or the
+    /// configuration, they are fetched if a commit they should contain is missing, or always, or never.
+    /// The commits they should contain are recorded at the tips of the references that were updated in `fetched`,
+    /// or in the index if `fetched` is `None`.
+    /// The submodules of fetched submodules are handled the same way, recursively.
+    ///
+    /// Submodules are processed with [`Options::jobs`] threads, and the first error is returned after all submodules
+    /// that were started are done.
+    pub fn fetch_submodules(
+        &self,
+        fetched: Option<&remote::fetch::Outcome>,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: &Options,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::fetch_submodules()");
+        let Some(submodules) = self.submodules()? else {
+            return Ok(Outcome::default());
+        };
+        let recurse = self
+            .config
+            .resolved
+            .boolean(SubmoduleKey::RECURSE)
+            .map(|value| SubmoduleKey::RECURSE.enrich_error(value))
+            .transpose()?;
+        let tips: Vec<_> = fetched
+            .map(|outcome| {
+                let update_refs = match &outcome.status {
+                    remote::fetch::Status::NoPackReceived { update_refs, .. }
+                    | remote::fetch::Status::Change { update_refs, .. } => update_refs,
+                };
+                update_refs
+                    .edits
+                    .iter()
+                    .filter_map(|edit| match &edit.change {
+                        gix_ref::transaction::Change::Update {
+                            new: gix_ref::Target::Object(id),
+                            ..
+                        } => Some(*id),
+                        _ => None,
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut tip_trees = Vec::new();
+        for tip in tips {
+            if let Ok(commit) = self.find_object(tip)?.peel_to_commit() {
+                tip_trees.push(commit.tree()?);
+            }
+        }
+
+        let mut tasks = Vec::new();
+        for submodule in submodules {
+            if !submodule.is_active()? {
+                continue;
+            }
+            let state = submodule.state()?;
+            if !state.repository_exists {
+                continue;
+            }
+            let mode = match options.recurse {
+                Some(mode) => mode,
+                None => submodule.fetch_recurse()?.unwrap_or(if recurse == Some(true) {
+                    FetchRecurse::Always
+                } else {
+                    FetchRecurse::OnDemand
+                }),
+            };
+            if mode == FetchRecurse::Never {
+                continue;
+            }
+            let path = submodule.path()?.into_owned();
+            let mut required = Vec::new();
+            if fetched.is_none() {
+                required.extend(submodule.index_id()?);
+            } else {
+                for tree in &tip_trees {
+                    if let Some(entry) = tree.lookup_entry_by_path(gix_path::from_bstr(path.as_bstr()))? {
+                        if entry.mode().is_commit() {
+                            required.push(entry.object_id());
+                        }
+                    }
+                }
+            }
+            tasks.push(Task {
+                name: submodule.name().to_owned(),
+                path,
+                git_dir: submodule.git_dir_try_old_form()?,
+                mode,
+                required,
+            });
+        }
+
+        let jobs = jobs(self, options.jobs)?;
+        let open_options = &self.options;
+        let submodules = in_parallel(
+            jobs,
+            tasks,
+            progress,
+            |task| task.path.to_string(),
+            |task, progress| {
+                let name = task.name.clone();
+                fetch_submodule(open_options, task, progress, should_interrupt, options).map_err(|err| {
+                    Error::Submodule {
+                        name,
+                        source: Box::new(err),
+                    }
+                })
+            },
+        )?;
+        Ok(Outcome { submodules })
+    }
+}
+
+/// Fetch the submodule described by `task` if needed, and its submodules.
+fn fetch_submodule(
+    open_options: &crate::open::Options,
+    task: Task,
+    progress: &mut BoxedDynNestedProgress,
+    should_interrupt: &AtomicBool,
+    options: &Options,
+) -> Result<Item, submodule::Error> {
+    let repo = crate::open_opts(&task.git_dir, open_options.clone())?;
+    if task.mode == FetchRecurse::OnDemand && task.required.iter().all(|id| repo.has_object(id)) {
+        return Ok(Item {
+            name: task.name,
+            path: task.path,
+            outcome: None,
+            submodules: Vec::new(),
+        });
+    }
+    let outcome = fetch(&repo, progress, should_interrupt, None)?;
+    let submodules = repo
+        .fetch_submodules(Some(&outcome), progress, should_interrupt, options)
+        .map_err(Box::new)?
+        .submodules;
+    Ok(Item {
+        name: task.name,
+        path: task.path,
+        outcome: Some(outcome),
+        submodules,
+    })
+}
+
+/// Fetch from the default remote of `repo` with its configured refspecs, or just the commit `id` if set.
+pub(crate) fn fetch(
+    repo: &Repository,
+    progress: &mut BoxedDynNestedProgress,
+    should_interrupt: &AtomicBool,
+    id: Option<ObjectId>,
+) -> Result<remote::fetch::Outcome, submodule::Error> {
+    let mut remote =
+        repo.find_default_remote(remote::Direction::Fetch)
+            .ok_or_else(|| submodule::Error::NoRemote {
+                git_dir: repo.git_dir().to_owned(),
+            })??;
+    if let Some(id) = id {
+        remote = remote
+            .with_fetch_tags(remote::fetch::Tags::None)
+            .with_refspecs(Some(id.to_string().as_str()), remote::Direction::Fetch)?;
+    }
+    let outcome = remote
+        .connect(remote::Direction::Fetch)?
+        .prepare_fetch(&mut *progress, Default::default())?
+        .receive(&mut *progress, should_interrupt)?;
+    Ok(outcome)
+}
+
+/// Return the amount of threads to use for processing submodules, either as given by `jobs` or as configured in `repo`.
+pub(crate) fn jobs(repo: &Repository, jobs: Option<usize>) -> Result<usize, crate::config::unsigned_integer::Error> {
+    let jobs = match jobs {
+        Some(jobs) => jobs,
+        None => repo
+            .config
+            .resolved
+            .integer(SubmoduleKey::FETCH_JOBS)
+            .map(|value| SubmoduleKey::FETCH_JOBS.try_into_u64(value))
+            .transpose()?
+            .map_or(1, |jobs| jobs as usize),
+    };
+    Ok(if jobs == 0 {
+        std::thread::available_parallelism().map_or(1, Into::into)
+    } else {
+        jobs
+    })
+}
+
+/// Call `process` with each of `tasks` and a child of `progress` named by `name` on `jobs` threads, and return all
+/// results in order. After the first failure no new tasks are started, and the error of the first failed task is returned.
+pub(crate) fn in_parallel<T, O, E>(
+    jobs: usize,
+    tasks: Vec<T>,
+    progress: &mut dyn DynNestedProgress,
+    name: impl Fn(&T) -> String,
+    process: impl Fn(T, &mut BoxedDynNestedProgress) -> Result<O, E> + Sync,
+) -> Result<Vec<O>, E>
+where
+    T: Send,
+    O: Send,
+    E: Send,
+{
+    let jobs = jobs.clamp(1, tasks.len().max(1));
+    let queue: Vec<_> = tasks
+        .into_iter()
+        .map(|task| {
+            let progress = progress.add_child(name(&task));
+            (task, progress)
+        })
+        .enumerate()
+        .collect();
+    let queue = Mutex::new(queue.into_iter());
+    let failed = AtomicBool::new(false);
+    let results = Mutex::new(Vec::new());
+    std::thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| loop {
+                if failed.load(Ordering::Relaxed) {
+                    break;
+                }
+                let Some((index, (task, mut progress))) = queue.lock().expect("not poisoned").next() else {
+                    break;
+                };
+                let res = process(task, &mut progress);
+                if res.is_err() {
+                    failed.store(true, Ordering::Relaxed);
+                }
+                results.lock().expect("not poisoned").push((index, res));
+            });
+        }
+    });
+
+    let mut results = results.into_inner().expect("not poisoned");
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, res)| res).collect()
+}
@@ -21,6 +21,18 @@ pub(crate) const MODULES_FILE: &str = ".gitmodules";
 mod errors;
 pub use errors::*;
 
+mod mutate;
+
+///
+#[cfg(all(feature = "blocking-network-client", feature = "worktree-mutation"))]
+pub mod add;
+///
+#[cfg(feature = "blocking-network-client")]
+pub mod fetch;
+///
+#[cfg(all(feature = "blocking-network-client", feature = "worktree-mutation"))]
+pub mod update;
+
 /// A platform maintaining state needed to interact with submodules, created by [`Repository::submodules()].
 pub(crate) struct SharedState<'repo> {
     pub repo: &'repo Repository,
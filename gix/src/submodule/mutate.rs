@@ -0,0 +1,521 @@
+use std::{
+    borrow::Cow,
+    path::{Component, Path, PathBuf},
+};
+
+use crate::{
+    bstr::{BStr, BString, ByteSlice},
+    remote, submodule,
+    submodule::{absorb_git_dir, deinit, init, modify, move_to, reconnect, remove, sync},
+    Repository, Submodule,
+};
+
+/// Mutation
+impl Submodule<'_> {
+    /// Register this submodule in the configuration of the superproject, like `git submodule init` does, and return
+    /// the URL that was registered, or `None` if a URL was configured already.
+    ///
+    /// The URL is copied from `.gitmodules` to `submodule.<name>.url`, after [resolving](gix_submodule::relative_url::resolve())
+    /// it against the URL of the default remote of the superproject if it is relative.
+    /// If the submodule isn't [active](Self::is_active()), `submodule.<name>.active` is set as well,
+    /// and the `update` field is copied unless it is configured already, with commands being replaced by `none`.
+    ///
+    /// Note that the local configuration file of the superproject is changed on disk, but its configuration
+    /// snapshot isn't updated and the repository has to be reopened to see the changes.
+    pub fn init(&self) -> Result<Option<BString>, init::Error> {
+        use init::Error;
+
+        let repo = self.state.repo;
+        let name = self.name();
+        let config_path = local_config_path(repo);
+        let mut config = read_config(&config_path)?;
+        let mut changed = false;
+        if !self.is_active()? {
+            config.set_raw_value_by("submodule", Some(name), "active", "true")?;
+            changed = true;
+        }
+
+        let is_configured = |key: &str| {
+            config.string_by("submodule", Some(name), key).is_some()
+                || repo.config.resolved.string_by("submodule", Some(name), key).is_some()
+        };
+        let registered_url = if is_configured("url") {
+            None
+        } else {
+            let url = self.modules_value("url").ok_or_else(|| Error::MissingUrl {
+                submodule: name.to_owned(),
+            })?;
+            Some(resolve_url(repo, url.as_ref(), None)?)
+        };
+        let update = if is_configured("update") {
+            None
+        } else {
+            self.modules_value("update").map(|update| {
+                if update.starts_with(b"!") {
+                    "none".into()
+                } else {
+                    update
+                }
+            })
+        };
+
+        if let Some(url) = &registered_url {
+            config.set_raw_value_by("submodule", Some(name), "url", url.as_bstr())?;
+            changed = true;
+        }
+        if let Some(update) = update {
+            config.set_raw_value_by("submodule", Some(name), "update", update.as_bstr())?;
+            changed = true;
+        }
+        if changed {
+            write_config(&config_path, &config).map_err(|source| Error::Io {
+                source,
+                path: config_path,
+            })?;
+        }
+        Ok(registered_url)
+    }
+
+    /// Write the URL from `.gitmodules` to the configuration of the superproject and to the default remote of the submodule
+    /// repository if it is checked out, like `git submodule sync` does, and return the URL as written to the superproject,
+    /// or `None` if the submodule isn't [active](Self::is_active()) and nothing was done.
+    ///
+    /// Relative URLs are [resolved](gix_submodule::relative_url::resolve()) against the URL of the default remote
+    /// of the superproject. Use this after the URL of a submodule changed upstream.
+    ///
+    /// Note that the local configuration files are changed on disk, but configuration snapshots of open repositories
+    /// aren't updated and these have to be reopened to see the changes.
+    pub fn sync(&self) -> Result<Option<BString>, sync::Error> {
+        use sync::Error;
+
+        if !self.is_active()? {
+            return Ok(None);
+        }
+        let repo = self.state.repo;
+        let name = self.name();
+        let url = self.modules_value("url").ok_or_else(|| Error::MissingUrl {
+            submodule: name.to_owned(),
+        })?;
+        let superproject_url = resolve_url(repo, url.as_ref(), None)?;
+        let config_path = local_config_path(repo);
+        let mut config = read_config(&config_path)?;
+        config.set_raw_value_by("submodule", Some(name), "url", superproject_url.as_bstr())?;
+        write_config(&config_path, &config).map_err(|source| Error::Io {
+            source,
+            path: config_path,
+        })?;
+
+        if !self.state()?.worktree_checkout {
+            return Ok(Some(superproject_url));
+        }
+        let Some(submodule_repo) = self.open()? else {
+            return Ok(Some(superproject_url));
+        };
+        let path = self.path()?;
+        let up_path: BString = "../"
+            .repeat(path.split_str("/").filter(|c| !c.is_empty()).count())
+            .into();
+        let submodule_url = resolve_url(repo, url.as_ref(), Some(up_path.as_ref()))?;
+        let remote_name = submodule_repo
+            .remote_default_name(remote::Direction::Fetch)
+            .map_or_else(|| BString::from("origin"), Cow::into_owned);
+        let config_path = local_config_path(&submodule_repo);
+        let mut config = read_config(&config_path)?;
+        config.set_raw_value_by("remote", Some(remote_name.as_bstr()), "url", submodule_url.as_bstr())?;
+        write_config(&config_path, &config).map_err(|source| Error::Io {
+            source,
+            path: config_path,
+        })?;
+        Ok(Some(superproject_url))
+    }
+
+    /// Move the repository of a submodule that is located in its worktree, i.e. in the 'old-form', into
+    /// [its place](Self::git_dir()) within the repository of the superproject, like `git submodule absorbgitdirs` does.
+    /// Return `true` if it was moved, or `false` if there was nothing to do.
+    ///
+    /// A `.git` file pointing to the new location is written into the worktree of the submodule instead, and `core.worktree`
+    /// is set accordingly. Submodules of the submodule stay connected to their moved repositories.
+    pub fn absorb_git_dir(&self) -> Result<bool, absorb_git_dir::Error> {
+        use absorb_git_dir::Error;
+
+        let work_dir = self.work_dir()?;
+        let old_git_dir = work_dir.join(gix_discover::DOT_GIT_DIR);
+        if !old_git_dir.is_dir() {
+            return Ok(false);
+        }
+        let git_dir = self.git_dir();
+        if git_dir.exists() {
+            return Err(Error::DestinationExists { path: git_dir });
+        }
+        if let Some(parent) = git_dir.parent() {
+            std::fs::create_dir_all(parent).map_err(|source| Error::Io {
+                source,
+                path: parent.to_owned(),
+            })?;
+        }
+        std::fs::rename(&old_git_dir, &git_dir).map_err(|source| Error::Io {
+            source,
+            path: old_git_dir,
+        })?;
+        connect_work_tree_and_git_dir(&work_dir, &git_dir)?;
+        reconnect_nested_submodules(&git_dir, self.state.repo.options.clone())?;
+        Ok(true)
+    }
+
+    /// Remove the checkout of this submodule from the worktree of the superproject and drop the `submodule.<name>` section
+    /// from the local configuration of the superproject, like `git submodule deinit --force` does.
+    /// Return `true` if there was a checkout to remove.
+    ///
+    /// The now empty directory of the submodule is kept, as is its repository within [its place](Self::git_dir())
+    /// in the superproject so it can be checked out again later. Note that local changes in the worktree of the
+    /// submodule are lost, and that submodules in the 'old-form' have to be [absorbed](Self::absorb_git_dir()) first.
+    pub fn deinit(&self) -> Result<bool, deinit::Error> {
+        use deinit::Error;
+
+        let repo = self.state.repo;
+        let name = self.name();
+        validate(repo, name, self.path()?.as_ref())?;
+        let work_dir = self.work_dir()?;
+        if work_dir.join(gix_discover::DOT_GIT_DIR).is_dir() {
+            return Err(Error::OldForm { path: work_dir });
+        }
+        let io_err = |path: &Path| {
+            let path = path.to_owned();
+            move |source| Error::Io { source, path }
+        };
+
+        let mut removed = false;
+        if std::fs::symlink_metadata(&work_dir).is_ok_and(|md| md.is_dir()) {
+            for entry in std::fs::read_dir(&work_dir).map_err(io_err(&work_dir))? {
+                let path = entry.map_err(io_err(&work_dir))?.path();
+                let res = if std::fs::symlink_metadata(&path).is_ok_and(|md| md.is_dir()) {
+                    std::fs::remove_dir_all(&path)
+                } else {
+                    std::fs::remove_file(&path)
+                };
+                res.map_err(io_err(&path))?;
+                removed = true;
+            }
+        }
+
+        let config_path = local_config_path(repo);
+        let mut config = read_config(&config_path)?;
+        let mut changed = false;
+        while config.remove_section("submodule", Some(name)).is_some() {
+            changed = true;
+        }
+        if changed {
+            write_config(&config_path, &config).map_err(io_err(&config_path))?;
+        }
+        Ok(removed)
+    }
+
+    /// Move the worktree of this submodule to `path`, relative to the worktree of the superproject, like `git mv` does,
+    /// and change its path in `.gitmodules` and its gitlink in the index of the superproject accordingly.
+    ///
+    /// The `.git` file of the submodule and its `core.worktree` are rewritten to keep both connected.
+    /// Note that this instance keeps seeing the previous path, and [`Repository::submodules()`] has to be called again
+    /// to see the change.
+    pub fn move_to(&self, path: &BStr) -> Result<(), move_to::Error> {
+        use move_to::Error;
+
+        let repo = self.state.repo;
+        let name = self.name();
+        let workdir = repo.workdir().ok_or(modify::Error::BareRepository)?;
+        validate(repo, name, path)?;
+        let old_path = self.path()?;
+        if old_path.as_ref() == path {
+            return Ok(());
+        }
+        let id = self
+            .index_id()?
+            .ok_or_else(|| Error::NotInIndex { name: name.to_owned() })?;
+        let new_work_dir = workdir.join(gix_path::from_bstr(path));
+        if std::fs::symlink_metadata(&new_work_dir).is_ok() || index_has_path(repo, path)? {
+            return Err(Error::DestinationExists { path: path.to_owned() });
+        }
+
+        let mut modules = modules_file(repo)?;
+        modules.set_path(name, path).map_err(modify::Error::from)?;
+        let old_work_dir = self.work_dir()?;
+        if old_work_dir.exists() {
+            if let Some(parent) = new_work_dir.parent() {
+                std::fs::create_dir_all(parent).map_err(|source| modify::Error::Io {
+                    source,
+                    path: parent.to_owned(),
+                })?;
+            }
+            std::fs::rename(&old_work_dir, &new_work_dir).map_err(|source| modify::Error::Io {
+                source,
+                path: old_work_dir.clone(),
+            })?;
+            let git_dir = self.git_dir();
+            if new_work_dir.join(gix_discover::DOT_GIT_DIR).is_file() && git_dir.is_dir() {
+                connect_work_tree_and_git_dir(&new_work_dir, &git_dir)?;
+                reconnect_nested_submodules(&git_dir, repo.options.clone())?;
+            }
+            remove_empty_parents(workdir, &old_work_dir);
+        }
+        write_modules_and_index(repo, &modules, Some(old_path.as_ref()), Some((path, id)))?;
+        Ok(())
+    }
+
+    /// Remove this submodule from the superproject like `git rm` does, by [deinitializing](Self::deinit()) it, removing
+    /// its directory and its entry in `.gitmodules`, and removing its gitlink from the index of the superproject
+    /// while staging the changed `.gitmodules` file.
+    ///
+    /// Like in Git, the repository of the submodule within [its place](Self::git_dir()) in the superproject is kept.
+    pub fn remove(&self) -> Result<(), remove::Error> {
+        let repo = self.state.repo;
+        let workdir = repo.workdir().ok_or(modify::Error::BareRepository)?;
+        let path = self.path()?;
+        self.deinit()?;
+        let work_dir = self.work_dir()?;
+        match std::fs::remove_dir(&work_dir) {
+            Err(err) if err.kind() != std::io::ErrorKind::NotFound => {
+                return Err(modify::Error::Io {
+                    source: err,
+                    path: work_dir,
+                }
+                .into())
+            }
+            _ => remove_empty_parents(workdir, &work_dir),
+        }
+
+        let mut modules = modules_file(repo)?;
+        modules.remove(self.name());
+        write_modules_and_index(repo, &modules, Some(path.as_ref()), None)?;
+        Ok(())
+    }
+
+    /// Return the value of `key` of this submodule as stored in `.gitmodules`, ignoring overrides from the configuration
+    /// of the superproject.
+    pub(crate) fn modules_value(&self, key: &str) -> Option<BString> {
+        let ours = self.state.modules.config().meta();
+        self.state
+            .modules
+            .config()
+            .string_filter_by("submodule", Some(self.name()), key, |meta| std::ptr::eq(meta, ours))
+            .map(Cow::into_owned)
+    }
+
+    /// Return the URL to clone this submodule from, as configured in the superproject or as resolved from `.gitmodules`
+    /// if the submodule wasn't [initialized](Self::init()) yet.
+    #[cfg(all(feature = "blocking-network-client", feature = "worktree-mutation"))]
+    pub(crate) fn configured_url(&self) -> Result<BString, init::Error> {
+        let name = self.name();
+        if let Some(url) = self
+            .state
+            .repo
+            .config
+            .resolved
+            .string_by("submodule", Some(name), "url")
+        {
+            return Ok(url.into_owned());
+        }
+        let url = self.modules_value("url").ok_or_else(|| init::Error::MissingUrl {
+            submodule: name.to_owned(),
+        })?;
+        Ok(resolve_url(self.state.repo, url.as_ref(), None)?)
+    }
+}
+
+/// Resolve `url` against the URL of the default remote of `repo` if it's relative, or against the path to its worktree
+/// if there is no such remote, with `up_path` prepended to relative results.
+pub(super) fn resolve_url(
+    repo: &Repository,
+    url: &BStr,
+    up_path: Option<&BStr>,
+) -> Result<BString, gix_submodule::relative_url::Error> {
+    if !gix_submodule::relative_url::is_relative(url) {
+        return Ok(url.to_owned());
+    }
+    let remote_url = repo
+        .remote_default_name(remote::Direction::Fetch)
+        .and_then(|name| repo.config.resolved.string_by("remote", Some(name.as_ref()), "url"))
+        .map_or_else(
+            || {
+                let path = repo.workdir().unwrap_or_else(|| repo.git_dir());
+                let path = gix_path::realpath(path).unwrap_or_else(|_| path.to_owned());
+                gix_path::into_bstr(path).into_owned()
+            },
+            Cow::into_owned,
+        );
+    gix_submodule::relative_url::resolve(remote_url.as_ref(), url, up_path)
+}
+
+pub(super) fn local_config_path(repo: &Repository) -> PathBuf {
+    repo.common_dir().join("config")
+}
+
+pub(super) fn read_config(path: &Path) -> Result<gix_config::File<'static>, gix_config::file::init::from_paths::Error> {
+    if !path.is_file() {
+        return Ok(gix_config::File::new(gix_config::file::Metadata::from(
+            gix_config::Source::Local,
+        )));
+    }
+    gix_config::File::from_path_no_includes(path.to_owned(), gix_config::Source::Local)
+}
+
+pub(super) fn write_config(path: &Path, config: &gix_config::File<'static>) -> std::io::Result<()> {
+    std::fs::write(path, config.to_bstring())
+}
+
+/// Assure `name` can't be used to escape the directory holding the repositories of submodules, and that `path`
+/// is a valid path within the worktree of `repo`.
+pub(super) fn validate(repo: &Repository, name: &BStr, path: &BStr) -> Result<(), modify::Error> {
+    gix_validate::submodule::name(name)?;
+    let protect = repo.config.protect_options()?;
+    for component in path.split_str("/") {
+        gix_validate::path::component(component.as_bstr(), None, protect).map_err(|source| {
+            modify::Error::InvalidPath {
+                path: path.to_owned(),
+                source,
+            }
+        })?;
+    }
+    Ok(())
+}
+
+/// Load the `.gitmodules` file from the worktree of `repo`, or return an empty one if there is none.
+pub(super) fn modules_file(repo: &Repository) -> Result<gix_submodule::File, modify::Error> {
+    Ok(match repo.open_modules_file()? {
+        Some(modules) => modules,
+        None => gix_submodule::File::from_bytes(&[], repo.modules_path(), &repo.config.resolved)
+            .map_err(submodule::open_modules_file::Error::from)?,
+    })
+}
+
+/// Return `true` if the index of `repo` has an entry at `path` or below it.
+pub(super) fn index_has_path(repo: &Repository, path: &BStr) -> Result<bool, modify::Error> {
+    let index = repo.index_or_empty()?;
+    Ok(index.entries().iter().any(|entry| {
+        let entry_path = entry.path(&index);
+        entry_path == path || (entry_path.starts_with(path) && entry_path.get(path.len()) == Some(&b'/'))
+    }))
+}
+
+/// Write `modules` into the worktree of `repo` and stage it, while removing the entry at `remove` from the index
+/// and adding a gitlink to the given commit at the path of `add`.
+pub(super) fn write_modules_and_index(
+    repo: &Repository,
+    modules: &gix_submodule::File,
+    remove: Option<&BStr>,
+    add: Option<(&BStr, gix_hash::ObjectId)>,
+) -> Result<(), modify::Error> {
+    let modules_path = repo.modules_path().ok_or(modify::Error::BareRepository)?;
+    let data = modules.to_bstring();
+    std::fs::write(&modules_path, &data).map_err(|source| modify::Error::Io {
+        source,
+        path: modules_path.clone(),
+    })?;
+    let id = repo.write_blob(&data)?.detach();
+    let stat = gix_index::fs::Metadata::from_path_no_follow(&modules_path)
+        .ok()
+        .and_then(|md| gix_index::entry::Stat::from_fs(&md).ok())
+        .unwrap_or_default();
+
+    let modules_file: &BStr = submodule::MODULES_FILE.into();
+    let mut index = gix_index::File::clone(&*repo.index_or_empty()?);
+    index.remove_entries(|_, path, _| {
+        path == modules_file || Some(path) == remove || add.is_some_and(|(add, _)| add == path)
+    });
+    index.dangerously_push_entry(
+        stat,
+        id,
+        gix_index::entry::Flags::empty(),
+        gix_index::entry::Mode::FILE,
+        modules_file,
+    );
+    if let Some((path, id)) = add {
+        index.dangerously_push_entry(
+            Default::default(),
+            id,
+            gix_index::entry::Flags::empty(),
+            gix_index::entry::Mode::COMMIT,
+            path,
+        );
+    }
+    index.sort_entries();
+    index.write(Default::default())?;
+    Ok(())
+}
+
+/// Remove the directories leading to `dir` within `workdir` as long as they are empty.
+fn remove_empty_parents(workdir: &Path, dir: &Path) {
+    let mut dir = dir.parent();
+    while let Some(parent) = dir.filter(|dir| *dir != workdir) {
+        if std::fs::remove_dir(parent).is_err() {
+            break;
+        }
+        dir = parent.parent();
+    }
+}
+
+/// Write a `.git` file into `work_dir` that points to `git_dir`, and set `core.worktree` in `git_dir` to point back
+/// to `work_dir`, using relative paths for both like Git does. `git_dir` is marked as non-bare as well.
+pub(crate) fn connect_work_tree_and_git_dir(work_dir: &Path, git_dir: &Path) -> Result<(), submodule::connect::Error> {
+    use submodule::connect::Error;
+
+    let io_err = |path: &Path| {
+        let path = path.to_owned();
+        move |source| Error::Io { source, path }
+    };
+    let work_dir = gix_path::realpath(work_dir).map_err(|err| Error::Io {
+        source: std::io::Error::other(err),
+        path: work_dir.to_owned(),
+    })?;
+    let git_dir = gix_path::realpath(git_dir).map_err(|err| Error::Io {
+        source: std::io::Error::other(err),
+        path: git_dir.to_owned(),
+    })?;
+
+    let dot_git = work_dir.join(gix_discover::DOT_GIT_DIR);
+    crate::worktree::write_path_file(&dot_git, b"gitdir: ", &relative_path(&work_dir, &git_dir))
+        .map_err(io_err(&dot_git))?;
+
+    let config_path = git_dir.join("config");
+    let mut config = read_config(&config_path)?;
+    let worktree =
+        gix_path::to_unix_separators_on_windows(gix_path::into_bstr(relative_path(&git_dir, &work_dir))).into_owned();
+    config.set_raw_value_by("core", None, "worktree", worktree.as_bstr())?;
+    config.set_raw_value_by("core", None, "bare", "false")?;
+    write_config(&config_path, &config).map_err(io_err(&config_path))?;
+    Ok(())
+}
+
+/// Reconnect the worktrees of all checked out submodules of the repository at `git_dir` with their repositories
+/// within `git_dir`, recursively, after `git_dir` was moved.
+fn reconnect_nested_submodules(git_dir: &Path, options: crate::open::Options) -> Result<(), reconnect::Error> {
+    let repo = crate::open_opts(git_dir, options.clone())?;
+    let Some(submodules) = repo.submodules()? else {
+        return Ok(());
+    };
+    for submodule in submodules {
+        let nested_git_dir = submodule.git_dir();
+        let work_dir = submodule.work_dir()?;
+        if nested_git_dir.is_dir() && work_dir.join(gix_discover::DOT_GIT_DIR).is_file() {
+            connect_work_tree_and_git_dir(&work_dir, &nested_git_dir)?;
+            reconnect_nested_submodules(&nested_git_dir, options.clone())?;
+        }
+    }
+    Ok(())
+}
+
+/// Return the path to `to` relative to the directory `from`, with both paths being absolute and normalized.
+pub(crate) fn relative_path(from: &Path, to: &Path) -> PathBuf {
+    let from: Vec<_> = from.components().collect();
+    let to: Vec<_> = to.components().collect();
+    let common = from.iter().zip(to.iter()).take_while(|(a, b)| a == b).count();
+    let mut out = PathBuf::new();
+    for _ in &from[common..] {
+        out.push(Component::ParentDir);
+    }
+    for component in &to[common..] {
+        out.push(component);
+    }
+    if out.as_os_str().is_empty() {
+        out.push(Component::CurDir);
+    }
+    out
+}
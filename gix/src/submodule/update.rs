@@ -0,0 +1,633 @@
+use std::{path::PathBuf, sync::atomic::AtomicBool};
+
+use gix_features::progress::{BoxedDynNestedProgress, DynNestedProgress};
+use gix_hash::ObjectId;
+use gix_ref::transaction::{Change, LogChange, PreviousValue, RefEdit, RefLog};
+
+use crate::{
+    bstr::{BString, ByteSlice},
+    submodule::config,
+    Repository,
+};
+
+/// Options for use in [`Repository::update_submodules()`].
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// If `true`, [initialize](crate::Submodule::init()) submodules that aren't active yet before updating them,
+    /// like `git submodule update --init` does. Otherwise, inactive submodules are ignored.
+    pub init: bool,
+    /// The names of the submodules to update, or all submodules if empty.
+    pub names: Vec<BString>,
+    /// If `true`, update the submodules of each updated submodule as well, with the same options but for all of their submodules.
+    pub recursive: bool,
+    /// The way to update all submodules, or `None` to use the mode [configured](crate::Submodule::update()) for each submodule,
+    /// which defaults to [`checkout`](config::Update::Checkout).
+    ///
+    /// Note that submodules that were just cloned are always checked out.
+    pub mode: Option<config::Update>,
+    /// If `true`, fetch from the default remote of a submodule if the commit recorded in the superproject isn't present.
+    /// Otherwise, updating such a submodule fails.
+    pub fetch: bool,
+    /// The number of submodules to update in parallel, with `0` meaning one per logical core, or `None` to use
+    /// `submodule.fetchJobs`, which defaults to `1`.
+    pub jobs: Option<usize>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            init: false,
+            names: Vec::new(),
+            recursive: false,
+            mode: None,
+            fetch: true,
+            jobs: None,
+        }
+    }
+}
+
+/// What happened to a submodule during [`Repository::update_submodules()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    /// The submodule was skipped as its update mode is `none`.
+    Skipped,
+    /// The submodule already was at the commit recorded in the superproject, or contained it.
+    UpToDate,
+    /// `HEAD` of the submodule was detached at the given commit recorded in the superproject, which was checked out.
+    CheckedOut(ObjectId),
+    /// The current branch of the submodule was rebased onto the commit recorded in the superproject, or fast-forwarded to it,
+    /// with the given commit being the new tip.
+    Rebased(ObjectId),
+    /// The commit recorded in the superproject was merged into the current branch of the submodule,
+    /// with the given commit being the new tip after creating a merge commit or fast-forwarding.
+    Merged(ObjectId),
+    /// The configured command was run with the commit recorded in the superproject as argument.
+    Command(ObjectId),
+}
+
+/// Information about a single submodule that was updated.
+#[derive(Debug, Clone)]
+pub struct Item {
+    /// The name of the submodule.
+    pub name: BString,
+    /// The path of the submodule, relative to the worktree of its superproject.
+    pub path: BString,
+    /// The URL that was registered in the configuration of the superproject if the submodule was initialized.
+    pub initialized_url: Option<BString>,
+    /// If `true`, the repository of the submodule was cloned.
+    pub cloned: bool,
+    /// What happened to the submodule.
+    pub action: Action,
+    /// The submodules of this submodule that were updated if [recursion](Options::recursive) was enabled.
+    pub submodules: Vec<Item>,
+}
+
+/// The outcome of [`Repository::update_submodules()`].
+#[derive(Debug, Clone, Default)]
+pub struct Outcome {
+    /// The submodules that were considered in order of their appearance in `.gitmodules`.
+    pub submodules: Vec<Item>,
+}
+
+/// The error returned by [`Repository::update_submodules()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Cannot update submodules in a bare repository")]
+    BareRepository,
+    #[error("The submodule '{name}' doesn't exist")]
+    UnknownSubmodule { name: BString },
+    #[error(transparent)]
+    Modules(#[from] crate::submodule::modules::Error),
+    #[error(transparent)]
+    Path(#[from] config::path::Error),
+    #[error(transparent)]
+    UpdateMode(#[from] config::update::Error),
+    #[error(transparent)]
+    IsActive(#[from] crate::submodule::is_active::Error),
+    #[error(transparent)]
+    Init(#[from] crate::submodule::init::Error),
+    #[error(transparent)]
+    IndexId(#[from] crate::submodule::index_id::Error),
+    #[error(transparent)]
+    FetchJobs(#[from] crate::config::unsigned_integer::Error),
+    #[error("Failed to update submodule '{name}'")]
+    Submodule {
+        name: BString,
+        source: Box<submodule::Error>,
+    },
+}
+
+///
+pub mod submodule {
+    use crate::bstr::BString;
+
+    /// The error returned when updating a single submodule as part of [`Repository::update_submodules()`](crate::Repository::update_submodules()).
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        PrepareClone(#[from] crate::clone::Error),
+        #[error(transparent)]
+        Clone(#[from] crate::clone::fetch::Error),
+        #[error(transparent)]
+        Connect(#[from] crate::submodule::connect::Error),
+        #[error(transparent)]
+        Open(#[from] crate::open::Error),
+        #[error(transparent)]
+        Fetch(#[from] crate::submodule::fetch::submodule::Error),
+        #[error("The commit {id} recorded in the superproject isn't present in the submodule repository")]
+        MissingCommit { id: gix_hash::ObjectId },
+        #[error(transparent)]
+        Checkout(#[from] crate::clone::checkout::main_worktree::Error),
+        #[error(transparent)]
+        SwitchWorktree(#[from] super::switch::Error),
+        #[error(transparent)]
+        EditReference(#[from] crate::reference::edit::Error),
+        #[error(transparent)]
+        FindHead(#[from] crate::reference::find::existing::Error),
+        #[error("The update mode '{mode:?}' requires the 'merge' feature")]
+        UnsupportedMode { mode: gix_submodule::config::Update },
+        #[cfg(feature = "merge")]
+        #[error(transparent)]
+        Integrate(#[from] super::integrate::Error),
+        #[error("Failed to run update command '{command}'")]
+        SpawnCommand { command: BString, source: std::io::Error },
+        #[error("The update command '{command}' failed with {status}")]
+        CommandFailed {
+            command: BString,
+            status: std::process::ExitStatus,
+        },
+        #[error(transparent)]
+        Recurse(#[from] Box<super::Error>),
+    }
+}
+
+///
+pub mod switch {
+    use crate::bstr::BString;
+
+    /// The error returned when switching the worktree of a submodule to another commit.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        TreeId(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        HeadTreeId(#[from] crate::reference::head_tree_id::Error),
+        #[error(transparent)]
+        IndexFromTree(#[from] crate::repository::index_from_tree::Error),
+        #[error(transparent)]
+        OpenIndex(#[from] crate::worktree::open_index::Error),
+        #[error("Local changes to {} would be overwritten by the update", paths.iter().map(|path| format!("'{path}'")).collect::<Vec<_>>().join(", "))]
+        LocalChanges { paths: Vec<BString> },
+        #[error(transparent)]
+        UpdateWorktree(#[from] crate::worktree::update::Error),
+        #[error(transparent)]
+        WriteIndex(#[from] gix_index::file::write::Error),
+    }
+}
+
+///
+#[cfg(feature = "merge")]
+pub mod integrate {
+    use crate::bstr::BString;
+
+    /// The error returned when rebasing or merging the current branch of a submodule.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        MergeBase(#[from] crate::repository::merge_base::Error),
+        #[error(transparent)]
+        HeadId(#[from] crate::reference::head_id::Error),
+        #[error(transparent)]
+        MergeOptions(#[from] crate::repository::tree_merge_options::Error),
+        #[error(transparent)]
+        MergeIntoWorktree(#[from] crate::repository::merge_into_worktree::Error),
+        #[error("Merging the commit into the submodule caused conflicts in {}", paths.iter().map(|path| format!("'{path}'")).collect::<Vec<_>>().join(", "))]
+        MergeConflicts { paths: Vec<BString> },
+        #[error(transparent)]
+        FinishMerge(#[from] crate::repository::finish_merge::Error),
+        #[error(transparent)]
+        Walk(#[from] crate::revision::walk::Error),
+        #[error(transparent)]
+        WalkIter(#[from] crate::revision::walk::iter::Error),
+        #[error(transparent)]
+        FindCommit(#[from] crate::object::find::existing::with_conversion::Error),
+        #[error(transparent)]
+        DecodeCommit(#[from] gix_object::decode::Error),
+        #[error(transparent)]
+        MergeTrees(#[from] crate::repository::merge_trees::Error),
+        #[error("Rebasing commit {id} caused conflicts, leaving the submodule unchanged")]
+        RebaseConflict { id: gix_hash::ObjectId },
+        #[error(transparent)]
+        WriteTree(#[from] crate::object::tree::editor::write::Error),
+        #[error(transparent)]
+        Committer(#[from] crate::config::time::Error),
+        #[error("The committer of the rebased commits isn't configured")]
+        CommitterMissing,
+        #[error(transparent)]
+        NewCommit(#[from] crate::repository::new_commit_as::Error),
+    }
+}
+
+/// Everything needed to update one submodule, independently of the superproject.
+struct Task {
+    name: BString,
+    path: BString,
+    work_dir: PathBuf,
+    git_dir: PathBuf,
+    repository_exists: bool,
+    url: Option<BString>,
+    initialized_url: Option<BString>,
+    target: ObjectId,
+    mode: config::Update,
+}
+
+impl Repository {
+    /// Update the submodules of this repository to the commits recorded in its index, like `git submodule update` does,
+    /// and return information about each submodule that was updated.
+    ///
+    /// Active submodules without a repository are cloned into [their place](crate::Submodule::git_dir()) in the repository
+    /// of the superproject first, and checked out. Otherwise, they are updated according to their update mode:
+    ///
+    /// * `checkout` detaches `HEAD` at the recorded commit and checks it out, failing if files with local changes would be affected.
+    /// * `rebase` rebases the current branch onto the recorded commit, but leaves the submodule unchanged if there are conflicts.
+    /// * `merge` merges the recorded commit into the current branch, and leaves the merge in progress if there are conflicts.
+    /// * `!command` runs `command` with the recorded commit as argument in the worktree of the submodule.
+    /// * `none` skips the submodule.
+    ///
+    /// Rebasing and merging require the `merge` feature. Submodules are processed with [`Options::jobs`] threads,
+    /// and the first error is returned after all submodules that were started are done.
+    ///
+    /// Note that submodules that aren't present in the index are ignored, as are inactive submodules unless they are
+    /// [initialized](Options::init).
+    pub fn update_submodules(
+        &self,
+        progress: &mut dyn DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: &Options,
+    ) -> Result<Outcome, Error> {
+        let _span = gix_trace::coarse!("gix::Repository::update_submodules()");
+        if self.workdir().is_none() {
+            return Err(Error::BareRepository);
+        }
+        let Some(submodules) = self.submodules()? else {
+            return Ok(Outcome::default());
+        };
+        let submodules: Vec<_> = submodules.collect();
+        if let Some(name) = options
+            .names
+            .iter()
+            .find(|name| !submodules.iter().any(|sm| sm.name() == name.as_bstr()))
+        {
+            return Err(Error::UnknownSubmodule { name: name.clone() });
+        }
+
+        let mut tasks = Vec::new();
+        for submodule in submodules
+            .iter()
+            .filter(|sm| options.names.is_empty() || options.names.iter().any(|name| name.as_bstr() == sm.name()))
+        {
+            let Some(target) = submodule.index_id()? else {
+                continue;
+            };
+            let initialized_url = if submodule.is_active()? {
+                None
+            } else if options.init {
+                Some(
+                    submodule
+                        .init()?
+                        .unwrap_or_else(|| submodule.configured_url().unwrap_or_default()),
+                )
+            } else {
+                continue;
+            };
+            let state = submodule.state()?;
+            tasks.push(Task {
+                name: submodule.name().to_owned(),
+                path: submodule.path()?.into_owned(),
+                work_dir: submodule.work_dir()?,
+                git_dir: submodule.git_dir_try_old_form()?,
+                repository_exists: state.repository_exists,
+                url: if state.repository_exists {
+                    None
+                } else {
+                    Some(submodule.configured_url()?)
+                },
+                initialized_url,
+                target,
+                mode: options
+                    .mode
+                    .clone()
+                    .or(submodule.update()?)
+                    .unwrap_or(config::Update::Checkout),
+            });
+        }
+
+        let jobs = super::fetch::jobs(self, options.jobs)?;
+        let open_options = &self.options;
+        let submodules = super::fetch::in_parallel(
+            jobs,
+            tasks,
+            progress,
+            |task| task.path.to_string(),
+            |task, progress| {
+                let name = task.name.clone();
+                update_submodule(open_options, task, progress, should_interrupt, options).map_err(|err| {
+                    Error::Submodule {
+                        name,
+                        source: Box::new(err),
+                    }
+                })
+            },
+        )?;
+        Ok(Outcome { submodules })
+    }
+}
+
+/// Bring the submodule described by `task` to its target commit, cloning it if needed.
+fn update_submodule(
+    open_options: &crate::open::Options,
+    task: Task,
+    progress: &mut BoxedDynNestedProgress,
+    should_interrupt: &AtomicBool,
+    options: &Options,
+) -> Result<Item, submodule::Error> {
+    let mut just_cloned = false;
+    if !task.repository_exists {
+        let url = task.url.as_ref().expect("set if there is no repository");
+        std::fs::create_dir_all(&task.git_dir).map_err(|source| crate::submodule::connect::Error::Io {
+            source,
+            path: task.git_dir.clone(),
+        })?;
+        let mut prepare = crate::clone::PrepareFetch::new(
+            gix_url::parse(url.as_bstr()).map_err(crate::clone::Error::from)?,
+            &task.git_dir,
+            crate::create::Kind::Bare,
+            Default::default(),
+            open_options.clone(),
+        )?;
+        let (cloned, _outcome) = prepare.fetch_only(&mut *progress, should_interrupt)?;
+        drop(cloned);
+        std::fs::create_dir_all(&task.work_dir).map_err(|source| crate::submodule::connect::Error::Io {
+            source,
+            path: task.work_dir.clone(),
+        })?;
+        crate::submodule::mutate::connect_work_tree_and_git_dir(&task.work_dir, &task.git_dir)?;
+        just_cloned = true;
+    } else if !task.work_dir.join(gix_discover::DOT_GIT_DIR).exists() {
+        std::fs::create_dir_all(&task.work_dir).map_err(|source| crate::submodule::connect::Error::Io {
+            source,
+            path: task.work_dir.clone(),
+        })?;
+        crate::submodule::mutate::connect_work_tree_and_git_dir(&task.work_dir, &task.git_dir)?;
+        just_cloned = true;
+    }
+
+    let repo = crate::open_opts(&task.git_dir, open_options.clone())?;
+    if !repo.has_object(task.target) && options.fetch {
+        super::fetch::fetch(&repo, progress, should_interrupt, None)?;
+        if !repo.has_object(task.target) {
+            super::fetch::fetch(&repo, progress, should_interrupt, Some(task.target))?;
+        }
+    }
+    if !repo.has_object(task.target) {
+        return Err(submodule::Error::MissingCommit { id: task.target });
+    }
+
+    let action = if just_cloned {
+        set_head(&repo, task.target, false)?;
+        crate::clone::checkout::main_worktree::checkout_into_empty_worktree(&repo, None, progress, should_interrupt)?;
+        Action::CheckedOut(task.target)
+    } else {
+        let head_id = repo.head()?.id().map(crate::Id::detach);
+        match &task.mode {
+            config::Update::None => Action::Skipped,
+            _ if head_id == Some(task.target) => Action::UpToDate,
+            config::Update::Checkout => {
+                switch_worktree(&repo, task.target)?;
+                set_head(&repo, task.target, false)?;
+                Action::CheckedOut(task.target)
+            }
+            #[cfg(feature = "merge")]
+            config::Update::Rebase => apply::rebase(&repo, task.target)?,
+            #[cfg(feature = "merge")]
+            config::Update::Merge => apply::merge(&repo, task.target)?,
+            #[cfg(not(feature = "merge"))]
+            mode @ (config::Update::Rebase | config::Update::Merge) => {
+                return Err(submodule::Error::UnsupportedMode { mode: mode.clone() })
+            }
+            config::Update::Command(command) => {
+                let status = std::process::Command::from(
+                    gix_command::prepare(gix_path::from_bstr(command.as_bstr()).into_owned())
+                        .arg(task.target.to_string())
+                        .stdin(std::process::Stdio::null())
+                        .with_shell(),
+                )
+                .current_dir(&task.work_dir)
+                .status();
+                match status {
+                    Ok(status) if status.success() => Action::Command(task.target),
+                    Ok(status) => {
+                        return Err(submodule::Error::CommandFailed {
+                            command: command.clone(),
+                            status,
+                        })
+                    }
+                    Err(source) => {
+                        return Err(submodule::Error::SpawnCommand {
+                            command: command.clone(),
+                            source,
+                        })
+                    }
+                }
+            }
+        }
+    };
+
+    let submodules = if options.recursive && action != Action::Skipped {
+        let nested = Options {
+            names: Vec::new(),
+            ..options.clone()
+        };
+        repo.update_submodules(progress, should_interrupt, &nested)
+            .map_err(Box::new)?
+            .submodules
+    } else {
+        Vec::new()
+    };
+    Ok(Item {
+        name: task.name,
+        path: task.path,
+        initialized_url: task.initialized_url,
+        cloned: just_cloned && !task.repository_exists,
+        action,
+        submodules,
+    })
+}
+
+/// Point `HEAD` to `id`, either by detaching it or by updating the branch it points to if `update_branch` is `true`.
+fn set_head(repo: &Repository, id: ObjectId, update_branch: bool) -> Result<(), crate::reference::edit::Error> {
+    repo.edit_reference(RefEdit {
+        change: Change::Update {
+            log: LogChange {
+                mode: RefLog::AndReference,
+                force_create_reflog: false,
+                message: format!("submodule update: moving to {id}").into(),
+            },
+            expected: PreviousValue::Any,
+            new: gix_ref::Target::Object(id),
+        },
+        name: "HEAD".try_into().expect("valid"),
+        deref: update_branch,
+    })?;
+    Ok(())
+}
+
+/// Change the index and the worktree of `repo` from the tree of `HEAD` to the tree of `commit`, while keeping staged changes
+/// to files that don't differ between both trees, and fail if files with local changes would be affected.
+fn switch_worktree(repo: &Repository, commit: ObjectId) -> Result<(), switch::Error> {
+    use crate::repository::checkout::{changed_paths, update_stat};
+
+    let workdir = repo.workdir().expect("submodules have a worktree");
+    let target_tree = repo.find_commit(commit)?.tree_id()?;
+    let head_index = repo.index_from_tree(&repo.head_tree_id_or_empty()?)?;
+    let index = repo.index_or_empty()?;
+    let target_index = repo.index_from_tree(&target_tree)?;
+
+    let changes = changed_paths(&head_index, &target_index);
+    let staged = changed_paths(&index, &head_index);
+    let mut local_changes: Vec<_> = staged
+        .iter()
+        .filter(|path| changes.binary_search(path).is_ok())
+        .cloned()
+        .collect();
+    local_changes.extend(repo.worktree_changes(&index, &changes)?);
+    if !local_changes.is_empty() {
+        local_changes.sort();
+        local_changes.dedup();
+        return Err(switch::Error::LocalChanges { paths: local_changes });
+    }
+
+    let written = repo.update_worktree(workdir, &target_index, &changes)?;
+    let mut new_index = target_index;
+    update_stat(&mut new_index, &written, &index);
+    if !staged.is_empty() {
+        new_index.remove_entries(|_, path, _| staged.binary_search_by(|p| p.as_bstr().cmp(path)).is_ok());
+        for entry in index.entries() {
+            let path = entry.path(&index);
+            if staged.binary_search_by(|p| p.as_bstr().cmp(path)).is_ok() {
+                new_index.dangerously_push_entry(entry.stat, entry.id, entry.flags, entry.mode, path);
+            }
+        }
+        new_index.sort_entries();
+    }
+    new_index.write(Default::default())?;
+    Ok(())
+}
+
+#[cfg(feature = "merge")]
+mod apply {
+    use gix_hash::ObjectId;
+
+    use super::{integrate::Error, set_head, switch_worktree, Action};
+    use crate::Repository;
+
+    /// Rebase the current branch of `repo` onto `target`, or fast-forward it.
+    pub(super) fn rebase(repo: &Repository, target: ObjectId) -> Result<Action, super::submodule::Error> {
+        let head = repo.head_id().map_err(Error::from)?.detach();
+        let base = repo.merge_base(head, target).map_err(Error::from)?.detach();
+        if base == target {
+            return Ok(Action::UpToDate);
+        }
+        if base == head {
+            switch_worktree(repo, target)?;
+            set_head(repo, target, true)?;
+            return Ok(Action::Rebased(target));
+        }
+
+        let mut commits = Vec::new();
+        for info in repo
+            .rev_walk(Some(head))
+            .with_hidden(Some(target))
+            .all()
+            .map_err(Error::from)?
+        {
+            let info = info.map_err(Error::from)?;
+            if info.parent_ids.len() == 1 {
+                commits.push((info.id, info.parent_ids[0]));
+            }
+        }
+        commits.reverse();
+
+        let committer = repo.committer().ok_or(Error::CommitterMissing)?.map_err(Error::from)?;
+        let options = repo.tree_merge_options().map_err(Error::from)?;
+        let mut onto = target;
+        for (id, parent) in commits {
+            let commit = repo.find_commit(id).map_err(Error::from)?;
+            let parent_tree = repo
+                .find_commit(parent)
+                .map_err(Error::from)?
+                .tree_id()
+                .map_err(Error::from)?;
+            let onto_tree = repo
+                .find_commit(onto)
+                .map_err(Error::from)?
+                .tree_id()
+                .map_err(Error::from)?;
+            let mut outcome = repo
+                .merge_trees(
+                    parent_tree,
+                    onto_tree,
+                    commit.tree_id().map_err(Error::from)?,
+                    Default::default(),
+                    options.clone(),
+                )
+                .map_err(Error::from)?;
+            if outcome.has_unresolved_conflicts(crate::merge::tree::TreatAsUnresolved::default()) {
+                return Err(Error::RebaseConflict { id }.into());
+            }
+            let tree = outcome.tree.write().map_err(Error::from)?;
+            let decoded = commit.decode().map_err(Error::from)?;
+            let author = decoded.author().map_err(Error::from)?;
+            onto = repo
+                .new_commit_as(committer, author, decoded.message.to_string(), tree, Some(onto))
+                .map_err(Error::from)?
+                .id;
+        }
+        switch_worktree(repo, onto)?;
+        set_head(repo, onto, true)?;
+        Ok(Action::Rebased(onto))
+    }
+
+    /// Merge `target` into the current branch of `repo`, or fast-forward it.
+    pub(super) fn merge(repo: &Repository, target: ObjectId) -> Result<Action, super::submodule::Error> {
+        let head = repo.head_id().map_err(Error::from)?.detach();
+        let base = repo.merge_base(head, target).map_err(Error::from)?.detach();
+        if base == target {
+            return Ok(Action::UpToDate);
+        }
+        if base == head {
+            switch_worktree(repo, target)?;
+            set_head(repo, target, true)?;
+            return Ok(Action::Merged(target));
+        }
+
+        let options = repo.tree_merge_options().map_err(Error::from)?;
+        let outcome = repo
+            .merge_into_worktree(target, Default::default(), options.into())
+            .map_err(Error::from)?;
+        if !outcome.unresolved.is_empty() {
+            return Err(Error::MergeConflicts {
+                paths: outcome.unresolved,
+            }
+            .into());
+        }
+        let id = repo.finish_merge(None).map_err(Error::from)?.detach();
+        Ok(Action::Merged(id))
+    }
+}
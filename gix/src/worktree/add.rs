@@ -0,0 +1,296 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::atomic::AtomicBool,
+};
+
+use gix_ref::transaction::PreviousValue;
+
+use crate::{
+    bstr::{BString, ByteSlice, ByteVec},
+    Repository, ThreadSafeRepository,
+};
+
+/// Determine what `HEAD` of a new linked worktree should point to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Head {
+    /// Detach `HEAD` at the given commit.
+    Detached(gix_hash::ObjectId),
+    /// Let `HEAD` point to the existing branch with the given full name, like `refs/heads/main`.
+    Branch(gix_ref::FullName),
+    /// Create a new branch with the given full name that points to `target`, and let `HEAD` point to it.
+    NewBranch {
+        /// The full name of the branch to create, like `refs/heads/feature`.
+        name: gix_ref::FullName,
+        /// The commit the new branch should point to.
+        target: gix_hash::ObjectId,
+    },
+}
+
+/// Options for use in [`Repository::worktree_add()`].
+#[derive(Debug, Clone)]
+pub struct Options {
+    /// The identifier of the new worktree, which names its private git directory in `$GIT_COMMON_DIR/worktrees/`.
+    ///
+    /// If `None`, it's derived from the last component of the worktree path, with a number appended to make it unique.
+    pub id: Option<BString>,
+    /// If `true`, check out the files of the commit `HEAD` points to. Otherwise, the worktree remains empty.
+    pub checkout: bool,
+    /// If `Some`, lock the worktree after it was created, using the given reason which may be empty.
+    pub lock_reason: Option<BString>,
+    /// If `true`, allow checking out a branch that is already checked out in another worktree, or reset a branch
+    /// to be created if it already exists.
+    pub force: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            id: None,
+            checkout: true,
+            lock_reason: None,
+            force: false,
+        }
+    }
+}
+
+/// The error returned by [`Repository::worktree_add()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("The worktree directory at '{}' already exists and isn't empty", path.display())]
+    DestinationNotEmpty { path: PathBuf },
+    #[error("A worktree with id '{id}' already exists")]
+    IdTaken { id: BString },
+    #[error("The worktree id '{id}' is invalid as it must be a single path component")]
+    InvalidId { id: BString },
+    #[error("Branch '{name}' is already checked out in the worktree at '{}'", path.display())]
+    BranchCheckedOut { name: BString, path: PathBuf },
+    #[error("Could not access '{}'", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+    #[error(transparent)]
+    FindBranch(#[from] crate::reference::find::existing::Error),
+    #[error(transparent)]
+    CreateBranch(#[from] crate::reference::edit::Error),
+    #[error(transparent)]
+    OpenWorktree(#[from] crate::open::Error),
+    #[error(transparent)]
+    FindHead(#[from] crate::reference::find::Error),
+    #[error(transparent)]
+    Checkout(#[from] crate::clone::checkout::main_worktree::Error),
+}
+
+impl Repository {
+    /// Create a new linked worktree at `path` with `HEAD` set according to `head`, similar to `git worktree add`,
+    /// and return it along with the outcome of checking out its files.
+    ///
+    /// `path` may not exist or must be an empty directory. The worktree will be registered in `$GIT_COMMON_DIR/worktrees/<id>`,
+    /// which is kept locked until the worktree is fully set up so it's not pruned in the meantime.
+    /// If anything fails, all files and directories that were created are removed again, but a newly created branch remains.
+    pub fn worktree_add(
+        &self,
+        path: impl AsRef<Path>,
+        head: Head,
+        progress: &mut dyn gix_features::progress::DynNestedProgress,
+        should_interrupt: &AtomicBool,
+        options: Options,
+    ) -> Result<(Repository, gix_worktree_state::checkout::Outcome), Error> {
+        let _span = gix_trace::coarse!("gix::Repository::worktree_add()");
+        let path = path.as_ref();
+        let io_err = |path: &Path| {
+            let path = path.to_owned();
+            move |source| Error::Io { path, source }
+        };
+        if let Ok(mut entries) = std::fs::read_dir(path) {
+            if entries.next().is_some() {
+                return Err(Error::DestinationNotEmpty { path: path.to_owned() });
+            }
+        }
+        if let Head::Branch(name) = &head {
+            self.find_reference(name.as_ref())?;
+            if !options.force {
+                self.ensure_branch_is_not_checked_out(name.as_ref())?;
+            }
+        }
+
+        let worktrees_dir = gix_path::realpath(self.common_dir())
+            .map_err(|err| Error::Io {
+                path: self.common_dir().to_owned(),
+                source: std::io::Error::other(err),
+            })?
+            .join("worktrees");
+        std::fs::create_dir_all(&worktrees_dir).map_err(io_err(&worktrees_dir))?;
+        // Creating the directory claims the id, so concurrent additions can't use the same one.
+        let id = match options.id {
+            Some(id) => {
+                if id.is_empty() || id.contains_str("/") || id.contains_str("\\") || id == "." || id == ".." {
+                    return Err(Error::InvalidId { id });
+                }
+                let worktree_git_dir = worktrees_dir.join(gix_path::from_bstr(id.as_bstr()));
+                match std::fs::create_dir(&worktree_git_dir) {
+                    Ok(()) => id,
+                    Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+                        return Err(Error::IdTaken { id });
+                    }
+                    Err(err) => return Err(io_err(&worktree_git_dir)(err)),
+                }
+            }
+            None => create_unique_id(&worktrees_dir, path).map_err(io_err(&worktrees_dir))?,
+        };
+        let worktree_git_dir = worktrees_dir.join(gix_path::from_bstr(id.as_bstr()));
+
+        let mut cleanup = Cleanup {
+            worktree: Some((path.to_owned(), path.is_dir())),
+            git_dir: Some(worktree_git_dir.clone()),
+        };
+        std::fs::create_dir_all(path).map_err(io_err(path))?;
+        let base = gix_path::realpath(path).map_err(|err| Error::Io {
+            path: path.to_owned(),
+            source: std::io::Error::other(err),
+        })?;
+        let locked_path = worktree_git_dir.join("locked");
+        std::fs::write(&locked_path, "initializing\n").map_err(io_err(&locked_path))?;
+
+        let gitdir_path = worktree_git_dir.join("gitdir");
+        crate::worktree::write_path_file(&gitdir_path, b"", &base.join(gix_discover::DOT_GIT_DIR))
+            .map_err(io_err(&gitdir_path))?;
+        let commondir_path = worktree_git_dir.join("commondir");
+        std::fs::write(&commondir_path, "../..\n").map_err(io_err(&commondir_path))?;
+        let dot_git_path = base.join(gix_discover::DOT_GIT_DIR);
+        crate::worktree::write_path_file(&dot_git_path, b"gitdir: ", &worktree_git_dir)
+            .map_err(io_err(&dot_git_path))?;
+
+        let head_content: BString = match &head {
+            Head::Detached(id) => format!("{id}\n").into(),
+            Head::Branch(name) => format!("ref: {}\n", name.as_bstr()).into(),
+            Head::NewBranch { name, target } => {
+                self.reference(
+                    name.clone(),
+                    *target,
+                    if options.force {
+                        PreviousValue::Any
+                    } else {
+                        PreviousValue::MustNotExist
+                    },
+                    "branch: Created by worktree add",
+                )?;
+                format!("ref: {}\n", name.as_bstr()).into()
+            }
+        };
+        let head_path = worktree_git_dir.join("HEAD");
+        std::fs::write(&head_path, head_content).map_err(io_err(&head_path))?;
+
+        let repo: Repository =
+            ThreadSafeRepository::open_from_paths(worktree_git_dir, Some(base), self.options.clone())?.into();
+        let outcome = if options.checkout {
+            crate::clone::checkout::main_worktree::checkout_into_empty_worktree(
+                &repo,
+                None,
+                progress,
+                should_interrupt,
+            )?
+        } else {
+            Default::default()
+        };
+
+        match options.lock_reason {
+            Some(reason) => crate::worktree::write_lock_file(&locked_path, reason.as_ref()),
+            None => std::fs::remove_file(&locked_path),
+        }
+        .map_err(io_err(&locked_path))?;
+        cleanup.disarm();
+        Ok((repo, outcome))
+    }
+
+    /// Fail if the branch `name` is checked out in the main worktree or any linked worktree.
+    fn ensure_branch_is_not_checked_out(&self, name: &gix_ref::FullNameRef) -> Result<(), Error> {
+        let main = self.main_repo()?;
+        let mut candidates = Vec::new();
+        if let Some(workdir) = main.workdir() {
+            candidates.push((main.head_name()?, workdir.to_owned()));
+        }
+        for proxy in self.worktrees().map_err(|source| Error::Io {
+            path: self.common_dir().join("worktrees"),
+            source,
+        })? {
+            let base = proxy.base().unwrap_or_else(|_| proxy.git_dir().to_owned());
+            let repo = proxy.into_repo_with_possibly_inaccessible_worktree()?;
+            candidates.push((repo.head_name()?, base));
+        }
+        match candidates
+            .into_iter()
+            .find(|(head_name, _)| head_name.as_ref().is_some_and(|head_name| head_name.as_ref() == name))
+        {
+            Some((_, path)) => Err(Error::BranchCheckedOut {
+                name: name.as_bstr().to_owned(),
+                path,
+            }),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Derive a worktree id from the last component of `path` that isn't used yet in `worktrees_dir`, and claim it by creating
+/// its directory there.
+fn create_unique_id(worktrees_dir: &Path, path: &Path) -> std::io::Result<BString> {
+    let mut name: BString = path
+        .file_name()
+        .map(|name| gix_path::os_str_into_bstr(name).map_or_else(|_| BString::default(), ToOwned::to_owned))
+        .unwrap_or_default()
+        .iter()
+        .map(|b| {
+            if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.') {
+                *b
+            } else {
+                b'-'
+            }
+        })
+        .collect::<Vec<_>>()
+        .into();
+    while name.first() == Some(&b'.') {
+        name.remove(0);
+    }
+    if name.is_empty() {
+        name = "worktree".into();
+    }
+    let mut candidate = name.clone();
+    let mut counter = 1;
+    loop {
+        match std::fs::create_dir(worktrees_dir.join(gix_path::from_bstr(candidate.as_bstr()))) {
+            Ok(()) => return Ok(candidate),
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+                candidate = name.clone();
+                candidate.push_str(counter.to_string());
+                counter += 1;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+/// Remove everything that was created for a new worktree unless disarmed.
+struct Cleanup {
+    /// The worktree directory, and whether it existed as empty directory beforehand.
+    worktree: Option<(PathBuf, bool)>,
+    git_dir: Option<PathBuf>,
+}
+
+impl Cleanup {
+    fn disarm(&mut self) {
+        self.worktree.take();
+        self.git_dir.take();
+    }
+}
+
+impl Drop for Cleanup {
+    fn drop(&mut self) {
+        if let Some(git_dir) = self.git_dir.take() {
+            std::fs::remove_dir_all(git_dir).ok();
+        }
+        if let Some((worktree, existed)) = self.worktree.take() {
+            std::fs::remove_dir_all(&worktree).ok();
+            if existed {
+                std::fs::create_dir(worktree).ok();
+            }
+        }
+    }
+}
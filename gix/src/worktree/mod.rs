@@ -1,4 +1,4 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[cfg(feature = "worktree-archive")]
 pub use gix_archive as archive;
@@ -10,7 +10,7 @@ pub use gix_worktree_state as state;
 pub use gix_worktree_stream as stream;
 
 use crate::{
-    bstr::{BStr, BString},
+    bstr::{BStr, BString, ByteVec},
     Repository,
 };
 
@@ -109,9 +109,62 @@ pub(crate) fn id(git_dir: &std::path::Path, has_common_dir: bool) -> Option<&BSt
     (maybe_worktrees.file_name()?.to_str()? == "worktrees").then_some(candidate)
 }
 
+/// Write `path` into the file at `file_path` with `prefix`, using forward slashes for portability.
+pub(crate) fn write_path_file(file_path: &Path, prefix: &[u8], path: &Path) -> std::io::Result<()> {
+    let mut content = BString::from(prefix);
+    content.push_str(gix_path::to_unix_separators_on_windows(gix_path::into_bstr(path)).as_ref());
+    content.push_byte(b'\n');
+    std::fs::write(file_path, content)
+}
+
+/// Write `reason` into the `locked` file at `path`, leaving it empty if there is no reason.
+#[cfg(feature = "worktree-mutation")]
+pub(crate) fn write_lock_file(path: &Path, reason: &BStr) -> std::io::Result<()> {
+    let mut content = BString::from(reason);
+    if !content.is_empty() {
+        content.push_byte(b'\n');
+    }
+    std::fs::write(path, content)
+}
+
+///
+#[cfg(feature = "worktree-mutation")]
+pub mod update {
+    /// The error returned when checking or updating files in the worktree, like when merging or updating submodules.
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error(transparent)]
+        FilterPipeline(#[from] crate::repository::filter::pipeline::Error),
+        #[error(transparent)]
+        WorktreeFileToObject(#[from] crate::filter::pipeline::worktree_file_to_object::Error),
+        #[error(transparent)]
+        CheckoutOptions(#[from] crate::config::checkout_options::Error),
+        #[error(transparent)]
+        Checkout(#[from] gix_worktree_state::checkout::Error),
+        #[error("Failed to reopen object database as Arc (only if thread-safety wasn't compiled in)")]
+        OpenArcOdb(#[source] std::io::Error),
+        #[error("Could not remove '{}'", path.display())]
+        Remove {
+            source: std::io::Error,
+            path: std::path::PathBuf,
+        },
+    }
+}
+
 ///
 pub mod proxy;
 
+///
+#[cfg(feature = "worktree-mutation")]
+pub mod add;
+
+///
+pub mod prune;
+
+///
+pub mod repair;
+
 ///
 #[cfg(feature = "index")]
 pub mod open_index {
@@ -1,5 +1,8 @@
 #![allow(clippy::result_large_err)]
-use std::path::{Path, PathBuf};
+use std::{
+    io::Write,
+    path::{Path, PathBuf},
+};
 
 use crate::{
     bstr::{BStr, BString, ByteSlice},
@@ -24,6 +27,66 @@ pub mod into_repo {
     }
 }
 
+///
+#[cfg(feature = "status")]
+pub mod remove {
+    use std::path::PathBuf;
+
+    use crate::bstr::BString;
+
+    /// Options for use in [`Proxy::remove()`][super::Proxy::remove()].
+    #[derive(Debug, Default, Clone, Copy)]
+    pub struct Options {
+        /// If `true`, remove the worktree even if it has modified or untracked files.
+        pub force_dirty: bool,
+        /// If `true`, remove the worktree even if it is locked.
+        pub force_locked: bool,
+    }
+
+    /// The error returned by [`Proxy::remove()`][super::Proxy::remove()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The worktree is locked{}", reason.as_ref().filter(|r| !r.is_empty()).map(|r| format!(", reason: {r}")).unwrap_or_default())]
+        Locked { reason: Option<BString> },
+        #[error("The worktree at '{}' contains modified or untracked files", .base.display())]
+        Dirty { base: PathBuf },
+        #[error(transparent)]
+        Open(#[from] crate::open::Error),
+        #[error(transparent)]
+        IsDirty(#[from] crate::status::is_dirty::Error),
+        #[error(transparent)]
+        StatusPlatform(#[from] crate::status::Error),
+        #[error(transparent)]
+        StatusIter(#[from] crate::status::into_iter::Error),
+        #[error(transparent)]
+        StatusItem(#[from] crate::status::iter::Error),
+        #[error("Could not remove '{}'", path.display())]
+        Io { path: PathBuf, source: std::io::Error },
+    }
+}
+
+///
+pub mod move_to {
+    use std::path::PathBuf;
+
+    use crate::bstr::BString;
+
+    /// The error returned by [`Proxy::move_to()`][super::Proxy::move_to()].
+    #[derive(Debug, thiserror::Error)]
+    #[allow(missing_docs)]
+    pub enum Error {
+        #[error("The worktree is locked{}", reason.as_ref().filter(|r| !r.is_empty()).map(|r| format!(", reason: {r}")).unwrap_or_default())]
+        Locked { reason: Option<BString> },
+        #[error("Worktree at '{}' is inaccessible", .base.display())]
+        MissingWorktree { base: PathBuf },
+        #[error("The destination '{}' already exists", .path.display())]
+        DestinationExists { path: PathBuf },
+        #[error("Could not move or update '{}'", path.display())]
+        Io { path: PathBuf, source: std::io::Error },
+    }
+}
+
 impl<'repo> Proxy<'repo> {
     pub(crate) fn new(parent: &'repo Repository, git_dir: impl Into<PathBuf>) -> Self {
         Proxy {
@@ -107,4 +170,127 @@ impl Proxy<'_> {
         let repo = ThreadSafeRepository::open_from_paths(self.git_dir, base.into(), self.parent.options.clone())?;
         Ok(repo.into())
     }
+
+    /// Lock the worktree so it can't be pruned, moved or removed, and store `reason` along with the lock, similar to `git worktree lock`.
+    ///
+    /// Return `Ok(false)` if the worktree was already locked, in which case the existing reason is kept.
+    pub fn lock(&self, reason: Option<&BStr>) -> std::io::Result<bool> {
+        let path = self.git_dir.join("locked");
+        let mut content = BString::from(reason.unwrap_or_default());
+        if !content.is_empty() {
+            content.push(b'\n');
+        }
+        match std::fs::OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(mut file) => {
+                file.write_all(&content)?;
+                Ok(true)
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Unlock the worktree, similar to `git worktree unlock`.
+    ///
+    /// Return `Ok(false)` if the worktree wasn't locked.
+    pub fn unlock(&self) -> std::io::Result<bool> {
+        match std::fs::remove_file(self.git_dir.join("locked")) {
+            Ok(()) => Ok(true),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(false),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Delete the worktree checkout along with its private git directory, similar to `git worktree remove`.
+    ///
+    /// Unless overridden in `options`, this fails if the worktree is locked, or if it has modified or untracked files.
+    /// Ignored files don't prevent removal. If the checkout doesn't exist anymore, only the private git directory is removed.
+    #[cfg(feature = "status")]
+    pub fn remove(self, options: remove::Options) -> Result<(), remove::Error> {
+        if !options.force_locked && self.is_locked() {
+            return Err(remove::Error::Locked {
+                reason: self.lock_reason(),
+            });
+        }
+        let base = self.base().ok().filter(|base| base.is_dir());
+        if let Some(base) = &base {
+            if !options.force_dirty {
+                let repo: Repository = ThreadSafeRepository::open_from_paths(
+                    self.git_dir.clone(),
+                    Some(base.clone()),
+                    self.parent.options.clone(),
+                )?
+                .into();
+                let has_untracked_files = || -> Result<bool, remove::Error> {
+                    Ok(repo
+                        .status(gix_features::progress::Discard)?
+                        .untracked_files(crate::status::UntrackedFiles::Collapsed)
+                        .into_iter(None)?
+                        .next()
+                        .transpose()?
+                        .is_some())
+                };
+                if repo.is_dirty()? || has_untracked_files()? {
+                    return Err(remove::Error::Dirty { base: base.clone() });
+                }
+            }
+            std::fs::remove_dir_all(base).map_err(|source| remove::Error::Io {
+                path: base.clone(),
+                source,
+            })?;
+        }
+        std::fs::remove_dir_all(&self.git_dir).map_err(|source| remove::Error::Io {
+            path: self.git_dir.clone(),
+            source,
+        })?;
+        if let Some(worktrees_dir) = self.git_dir.parent() {
+            std::fs::remove_dir(worktrees_dir).ok();
+        }
+        Ok(())
+    }
+
+    /// Move the worktree checkout to `destination` and update its private git directory accordingly, similar to `git worktree move`.
+    ///
+    /// If `destination` is an existing directory, the worktree is moved into it, keeping its directory name.
+    /// Unless `force_locked` is `true`, this fails if the worktree is locked.
+    /// Return the new location of the worktree checkout.
+    pub fn move_to(&self, destination: impl AsRef<Path>, force_locked: bool) -> Result<PathBuf, move_to::Error> {
+        if !force_locked && self.is_locked() {
+            return Err(move_to::Error::Locked {
+                reason: self.lock_reason(),
+            });
+        }
+        let base = self
+            .base()
+            .ok()
+            .filter(|base| base.is_dir())
+            .ok_or_else(|| move_to::Error::MissingWorktree {
+                base: self.base().unwrap_or_else(|_| self.git_dir.join("gitdir")),
+            })?;
+        let mut destination = destination.as_ref().to_owned();
+        if destination.is_dir() {
+            if let Some(name) = base.file_name() {
+                destination.push(name);
+            }
+        }
+        if destination.exists() {
+            return Err(move_to::Error::DestinationExists { path: destination });
+        }
+        std::fs::rename(&base, &destination).map_err(|source| move_to::Error::Io {
+            path: base.clone(),
+            source,
+        })?;
+        let destination = gix_path::realpath(&destination).map_err(|err| move_to::Error::Io {
+            path: destination.clone(),
+            source: std::io::Error::other(err),
+        })?;
+        let gitdir_path = self.git_dir.join("gitdir");
+        crate::worktree::write_path_file(&gitdir_path, b"", &destination.join(gix_discover::DOT_GIT_DIR)).map_err(
+            |source| move_to::Error::Io {
+                path: gitdir_path.clone(),
+                source,
+            },
+        )?;
+        Ok(destination)
+    }
 }
@@ -0,0 +1,120 @@
+use std::path::PathBuf;
+
+use crate::{bstr::BString, Repository};
+
+/// Options for use in [`Repository::worktrees_prune()`].
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options {
+    /// Only prune worktrees whose checkout is missing if their `gitdir` file was last modified at or before this time.
+    ///
+    /// If `None`, all worktrees with a missing checkout are pruned.
+    pub expire: Option<gix_date::Time>,
+    /// If `true`, only determine what would be pruned without removing anything.
+    pub dry_run: bool,
+}
+
+/// The reason for a worktree to be pruned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reason {
+    /// The entry in `$GIT_COMMON_DIR/worktrees` isn't a directory.
+    NotADirectory,
+    /// The `gitdir` file doesn't exist.
+    MissingGitdirFile,
+    /// The `gitdir` file couldn't be read or is empty.
+    InvalidGitdirFile,
+    /// The `gitdir` file points to a location that doesn't exist.
+    MissingCheckout,
+}
+
+/// A worktree that was pruned by [`Repository::worktrees_prune()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pruned {
+    /// The id of the worktree, the name of its private git directory.
+    pub id: BString,
+    /// The reason for it to be pruned.
+    pub reason: Reason,
+}
+
+/// The error returned by [`Repository::worktrees_prune()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("Could not access '{}'", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+}
+
+impl Repository {
+    /// Remove the private git directories of linked worktrees whose checkout doesn't exist anymore, similar to `git worktree prune`.
+    ///
+    /// Locked worktrees are never pruned. If no worktree remains, `$GIT_COMMON_DIR/worktrees` is removed as well.
+    /// Return all worktrees that were pruned, or would have been pruned in [dry-run mode](Options::dry_run), sorted by id.
+    pub fn worktrees_prune(&self, options: Options) -> Result<Vec<Pruned>, Error> {
+        let worktrees_dir = self.common_dir().join("worktrees");
+        let io_err = |path: &std::path::Path| {
+            let path = path.to_owned();
+            move |source| Error::Io { path, source }
+        };
+        let entries = match std::fs::read_dir(&worktrees_dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(io_err(&worktrees_dir)(err)),
+        };
+        let mut out = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(io_err(&worktrees_dir))?;
+            let path = entry.path();
+            let Some(reason) = prune_reason(&path, options.expire) else {
+                continue;
+            };
+            if !options.dry_run {
+                if reason == Reason::NotADirectory {
+                    std::fs::remove_file(&path)
+                } else {
+                    std::fs::remove_dir_all(&path)
+                }
+                .map_err(io_err(&path))?;
+            }
+            out.push(Pruned {
+                id: gix_path::os_str_into_bstr(&entry.file_name()).map_or_else(
+                    |_| entry.file_name().to_string_lossy().into_owned().into(),
+                    ToOwned::to_owned,
+                ),
+                reason,
+            });
+        }
+        if !options.dry_run {
+            std::fs::remove_dir(&worktrees_dir).ok();
+        }
+        out.sort_by(|a, b| a.id.cmp(&b.id));
+        Ok(out)
+    }
+}
+
+/// Determine why the private git directory at `git_dir` should be pruned, if at all, like `should_prune_worktree()` in `git`.
+fn prune_reason(git_dir: &std::path::Path, expire: Option<gix_date::Time>) -> Option<Reason> {
+    if !git_dir.is_dir() {
+        return Some(Reason::NotADirectory);
+    }
+    if git_dir.join("locked").is_file() {
+        return None;
+    }
+    let gitdir_file = git_dir.join("gitdir");
+    let dot_git = match gix_discover::path::from_plain_file(&gitdir_file) {
+        None => return Some(Reason::MissingGitdirFile),
+        Some(Err(_)) => return Some(Reason::InvalidGitdirFile),
+        Some(Ok(path)) if path.as_os_str().is_empty() => return Some(Reason::InvalidGitdirFile),
+        Some(Ok(path)) => git_dir.join(path),
+    };
+    if dot_git.exists() {
+        return None;
+    }
+    let is_expired = match expire {
+        None => true,
+        Some(expire) => std::fs::symlink_metadata(&gitdir_file)
+            .and_then(|meta| meta.modified())
+            .ok()
+            .and_then(|mtime| mtime.duration_since(std::time::UNIX_EPOCH).ok())
+            .is_some_and(|mtime| mtime.as_secs() as gix_date::SecondsSinceUnixEpoch <= expire.seconds),
+    };
+    is_expired.then_some(Reason::MissingCheckout)
+}
@@ -0,0 +1,124 @@
+use std::path::{Path, PathBuf};
+
+use crate::{bstr::BString, Repository};
+
+/// A change made by [`Repository::worktrees_repair()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// The `.git` file of the worktree checkout at `path` was rewritten to point to the private git directory of worktree `id`.
+    DotGitFile {
+        /// The id of the worktree.
+        id: BString,
+        /// The location of the worktree checkout.
+        path: PathBuf,
+    },
+    /// The `gitdir` file in the private git directory of worktree `id` was rewritten to point to the worktree checkout at `path`.
+    GitdirFile {
+        /// The id of the worktree.
+        id: BString,
+        /// The location of the worktree checkout.
+        path: PathBuf,
+    },
+}
+
+/// The error returned by [`Repository::worktrees_repair()`].
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[error("'{}' is not a linked worktree of this repository", path.display())]
+    NotALinkedWorktree { path: PathBuf },
+    #[error("Could not access '{}'", path.display())]
+    Io { path: PathBuf, source: std::io::Error },
+}
+
+impl Repository {
+    /// Repair the links between linked worktrees and their private git directories, similar to `git worktree repair`.
+    ///
+    /// `moved_checkouts` are the new locations of worktree checkouts that were moved manually, which makes their
+    /// `gitdir` file point to the previous location. Their `.git` file is used to find their private git directory,
+    /// and if it points to a location that doesn't exist as the repository was moved as well, the worktree
+    /// with the same id is used instead.
+    ///
+    /// Thereafter, the `.git` file of every existing worktree checkout is rewritten if it doesn't point back to its
+    /// private git directory, which is the case if the repository was moved.
+    /// Return all changes that were made.
+    pub fn worktrees_repair(&self, moved_checkouts: &[PathBuf]) -> Result<Vec<Change>, Error> {
+        let mut out = Vec::new();
+        let worktrees_dir = self.common_dir().join("worktrees");
+        for checkout in moved_checkouts {
+            let checkout = gix_path::realpath(checkout).map_err(|err| Error::Io {
+                path: checkout.clone(),
+                source: std::io::Error::other(err),
+            })?;
+            let dot_git = checkout.join(gix_discover::DOT_GIT_DIR);
+            let git_dir = gix_discover::path::from_gitdir_file(&dot_git)
+                .ok()
+                .and_then(|git_dir| {
+                    let id = git_dir.file_name()?.to_owned();
+                    if git_dir.is_dir() {
+                        gix_path::realpath(&git_dir)
+                            .ok()
+                            .filter(|git_dir| git_dir.parent() == gix_path::realpath(&worktrees_dir).ok().as_deref())
+                    } else {
+                        Some(worktrees_dir.join(id)).filter(|git_dir| git_dir.is_dir())
+                    }
+                })
+                .ok_or_else(|| Error::NotALinkedWorktree { path: checkout.clone() })?;
+            let id = id_of(&git_dir);
+            let proxy = crate::worktree::Proxy::new(self, git_dir.clone());
+            if proxy.base().ok().as_deref() != Some(checkout.as_path()) {
+                let gitdir_path = git_dir.join("gitdir");
+                crate::worktree::write_path_file(&gitdir_path, b"", &dot_git).map_err(|source| Error::Io {
+                    path: gitdir_path,
+                    source,
+                })?;
+                out.push(Change::GitdirFile {
+                    id,
+                    path: checkout.clone(),
+                });
+            }
+        }
+
+        for proxy in self.worktrees().map_err(|source| Error::Io {
+            path: worktrees_dir.clone(),
+            source,
+        })? {
+            let Some(base) = proxy.base().ok().filter(|base| base.is_dir()) else {
+                continue;
+            };
+            let git_dir = gix_path::realpath(proxy.git_dir()).map_err(|err| Error::Io {
+                path: proxy.git_dir().to_owned(),
+                source: std::io::Error::other(err),
+            })?;
+            let dot_git = base.join(gix_discover::DOT_GIT_DIR);
+            if points_to(&dot_git, &git_dir) {
+                continue;
+            }
+            crate::worktree::write_path_file(&dot_git, b"gitdir: ", &git_dir)
+                .map_err(|source| Error::Io { path: dot_git, source })?;
+            out.push(Change::DotGitFile {
+                id: id_of(&git_dir),
+                path: base,
+            });
+        }
+        Ok(out)
+    }
+}
+
+/// Return `true` if the `.git` file at `dot_git` points to the directory `git_dir`.
+fn points_to(dot_git: &Path, git_dir: &Path) -> bool {
+    gix_discover::path::from_gitdir_file(dot_git)
+        .ok()
+        .and_then(|path| gix_path::realpath(path).ok())
+        .is_some_and(|path| path == git_dir)
+}
+
+fn id_of(git_dir: &Path) -> BString {
+    git_dir
+        .file_name()
+        .map(|name| {
+            gix_path::os_str_into_bstr(name)
+                .map_or_else(|_| name.to_string_lossy().into_owned().into(), ToOwned::to_owned)
+        })
+        .unwrap_or_default()
+}
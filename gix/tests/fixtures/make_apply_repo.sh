@@ -0,0 +1,26 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+seq 1 10 >file
+echo "echo hi" >exe && chmod +x exe
+mkdir dir && echo nested >dir/nested
+ln -s dir link
+git add -A
+git commit -q -m "base"
+git branch base
+
+git checkout -q -b after
+sed -i.bak 's/^5$/five/' file && rm file.bak
+echo new >new
+git rm -q dir/nested
+mkdir bin && git mv exe bin/exe
+git add -A
+git commit -q -m "after"
+
+git checkout -q -b ours base
+sed -i.bak 's/^5$/5 ours/' file && rm file.bak
+git commit -q -am "ours"
+
+git diff --binary -M base after >.git/change.patch
+git checkout -q base
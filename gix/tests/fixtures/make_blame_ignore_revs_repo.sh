@@ -0,0 +1,19 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+printf 'fn one() {\n    1\n}\n' > file.txt
+git add file.txt
+git commit -q -m c1
+
+printf 'fn one() {\n  1\n}\n' > file.txt
+git commit -q -am "c2: reformat"
+
+{
+  echo "# formatting"
+  git rev-parse HEAD
+} > .git-blame-ignore-revs
+git config blame.ignoreRevsFile .git-blame-ignore-revs
+
+printf 'fn one() {\n  1\n}\nfn two() {}\n' > file.txt
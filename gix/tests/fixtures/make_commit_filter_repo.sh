@@ -0,0 +1,75 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+# A history with distinct authors, committers, dates and messages, along with merges of two and three parents:
+#
+#	A---B---D---M---F-------O
+#	     \     /     \     /|
+#	      `---C       +---G |
+#	                   \    |
+#	                    `---H
+git init -q
+
+count=0
+# commit <message> <author> <committer> <date> <email-tld>
+function commit() {
+  local author=$2 committer=$3
+  count=$((count + 1))
+  echo "$1" > "file-$count"
+  git add -A
+  GIT_AUTHOR_NAME="$author" GIT_AUTHOR_EMAIL="$(echo "$author" | tr 'A-Z' 'a-z')@example.$5" \
+  GIT_COMMITTER_NAME="$committer" GIT_COMMITTER_EMAIL="$(echo "$committer" | tr 'A-Z' 'a-z')@example.com" \
+  GIT_AUTHOR_DATE="$4" GIT_COMMITTER_DATE="$4" \
+    git commit -q -m "$1"
+}
+
+# merge <message> <date> <branch>...
+function merge() {
+  local message=$1 date=$2
+  shift 2
+  GIT_AUTHOR_DATE="$date" GIT_COMMITTER_DATE="$date" git merge -q --no-ff -m "$message" "$@"
+}
+
+commit "initial commit" Alice Alice "2020-01-01 12:00:00 +0000" com
+commit "fix: typo in readme" Bob Alice "2020-02-01 12:00:00 +0000" org
+
+git checkout -q -b side
+commit $'feat: add parser\n\nThe parser is fast.' Carol Carol "2020-03-01 12:00:00 +0000" com
+
+git checkout -q main
+commit "docs: explain usage" Alice Alice "2020-03-15 12:00:00 +0000" com
+merge "Merge branch 'side'" "2020-04-01 12:00:00 +0000" side
+commit "fix: crash on empty input" Bob Carol "2020-05-01 12:00:00 +0000" org
+
+git checkout -q -b one
+commit "feat: one" Alice Alice "2020-05-15 12:00:00 +0000" com
+git checkout -q -b two main
+commit "feat: two" Carol Carol "2020-05-20 12:00:00 +0000" com
+git checkout -q main
+merge "Merge branches 'one' and 'two'" "2020-06-01 12:00:00 +0000" one two
+
+function baseline() {
+  local name=$1
+  shift
+  git rev-list "$@" main > "baseline.$name"
+}
+
+baseline author --author=Bob
+baseline author-email '--author=@example\.org'
+baseline authors --author=Bob --author=Carol
+baseline committer --committer=Carol
+baseline grep --grep=fix
+baseline grep-body '--grep=is fast'
+baseline grep-anchored '--grep=^docs'
+baseline greps --grep=fix --grep=readme
+baseline all-match --grep=fix --grep=readme --all-match
+baseline invert-grep --grep=fix --invert-grep
+baseline ignore-case -i --grep=FIX
+baseline author-and-grep --author=Bob --grep=crash
+baseline since-until '--since=2020-03-01 12:00:00 +0000' '--until=2020-05-01 12:00:00 +0000'
+baseline no-merges --no-merges
+baseline merges --merges
+baseline max-parents --max-parents=2
+baseline min-parents --min-parents=3
+baseline skip-and-max-count --skip=2 --max-count=3
+baseline grep-and-max-count --grep=feat --max-count=2
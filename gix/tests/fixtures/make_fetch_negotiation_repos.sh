@@ -0,0 +1,32 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+# Commit with a distinct date so the order in which haves are sent during negotiation is predictable.
+function commit_at() {
+  local seconds=${1:?first argument is the commit time}
+  local message=${2:?second argument is the message}
+  GIT_AUTHOR_DATE="$seconds +0000" GIT_COMMITTER_DATE="$seconds +0000" git commit -q --allow-empty -m "$message"
+}
+
+git init -q remote
+(cd remote
+  for i in $(seq 10); do commit_at $((1000000000 + i * 1000)) "c$i"; done
+)
+
+git clone -q remote local
+(cd local
+  # Commits the remote doesn't know, more recent than anything it has, so multiple rounds are needed to get to
+  # what's in common.
+  git checkout -q -b local-only-recent main
+  for i in $(seq 20); do commit_at $((1000100000 + i)) "recent $i"; done
+
+  # Commits the remote doesn't know which are sent after the first common commit, so the server signals
+  # that it's ready before we run out of haves.
+  git checkout -q -b local-only-old main~9
+  for i in $(seq 40); do commit_at $((1000009000 + i)) "old $i"; done
+  git checkout -q main
+)
+
+(cd remote
+  for i in $(seq 3); do commit_at $((1000200000 + i)) "new $i"; done
+)
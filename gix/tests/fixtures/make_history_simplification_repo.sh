@@ -0,0 +1,88 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+# The history from the 'History Simplification' section of `git help log`:
+#
+#	  .-A---M---N---O---P---Q
+#	 /     /   /   /   /   /
+#	I     B   C   D   E   Y
+#	 \   /   /   /   /   /
+#	  `-------------'   X
+git init -q
+
+function commit() {
+  git add -A
+  git commit -q -m "$1"
+  git tag "$1"
+}
+
+echo asdf > foo
+echo quux > quux
+commit I
+
+echo foo > foo
+commit A
+
+git checkout -q -b b I
+echo foo > foo
+commit B
+git checkout -q main
+git merge -q --no-ff -m M b
+git tag M
+
+git checkout -q -b c I
+echo c > other
+commit C
+git checkout -q main
+git merge -q --no-commit c
+echo foobar > foo
+git commit -q -am N
+git tag N
+
+git checkout -q -b d I
+echo baz > foo
+commit D
+git checkout -q main
+git merge -q d >/dev/null || true
+echo foobarbaz > foo
+git commit -q -am O
+git tag O
+
+git checkout -q -b e I
+echo xyzzy > quux
+commit E
+git checkout -q main
+git merge -q --no-commit e
+echo "quux xyzzy" > quux
+git commit -q -am P
+git tag P
+
+git checkout -q --orphan x
+git rm -rqf .
+echo side > side
+commit X
+echo side2 > side
+commit Y
+git checkout -q main
+git merge -q --allow-unrelated-histories -m Q Y
+git tag Q
+
+git commit-graph write --no-progress --reachable --changed-paths
+
+function baseline() {
+  local name=${1:?first argument is the name of the baseline}
+  shift
+  git rev-list "$@" > "baseline.$name"
+}
+
+baseline default Q -- foo
+baseline default-parents --parents Q -- foo
+baseline sparse --sparse Q -- foo
+baseline full-history --full-history Q -- foo
+baseline full-history-parents --full-history --parents Q -- foo
+baseline simplify-merges --simplify-merges --parents Q -- foo
+baseline ancestry-path --ancestry-path --parents D..Q -- foo
+baseline ancestry-path-without-pathspec --ancestry-path D..Q
+baseline first-parent --first-parent Q -- foo
+baseline glob Q -- 'q*'
+baseline directory Q -- .
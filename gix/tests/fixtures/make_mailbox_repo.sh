@@ -0,0 +1,30 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+seq 1 20 >file
+printf 'fn main() {\n    println!("hi");\n}\n' >main.rs
+echo "echo hi" >script
+git add -A
+git commit -q -m "base"
+git branch base
+
+sed -i.bak 's/^10$/ten/' file && rm file.bak
+GIT_AUTHOR_NAME="Jöhn Dœ" GIT_AUTHOR_EMAIL="john@example.com" \
+  git commit -q -am "Make the tenth line spelled out with ümlauts" -m "It's easier to read." -m "Really."
+
+git commit -q --allow-empty -m "empty"
+
+mkdir bin && git mv script bin/script && chmod +x bin/script
+printf 'fn main() {\n    println!("hello");\n}' >main.rs
+GIT_AUTHOR_NAME="Doe, Jane" git commit -q -am "Move the script into its own directory and make it executable while changing the greeting of the program to something longer"
+
+git rm -q file
+echo new >"new file"
+git add -A
+git commit -q -m "Replace the file" -m "=?UTF-8?q?not-encoded?= is kept."
+
+git branch series
+# The pathspec prunes the empty commit before numbering, which is what we do as well.
+git format-patch -q --stdout --signature=signature base..series -- . >.git/series.mbox
+git checkout -q base
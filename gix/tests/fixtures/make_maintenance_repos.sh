@@ -0,0 +1,33 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q repo
+(cd repo
+  echo 1 > file
+  git add file && git commit -q -m first
+  git repack -q -d
+
+  echo 2 > file
+  git commit -q -am second
+  git tag -a -m "annotated" v1
+  git repack -q -d
+
+  # Reachable loose objects.
+  echo 3 > file
+  git commit -q -am third
+  git branch other HEAD~1
+
+  # An unreachable packed object, in a pack of its own.
+  echo "unreachable packed" | git hash-object -w --stdin > ../unreachable-packed.id
+  git pack-objects -q .git/objects/pack/pack < ../unreachable-packed.id > /dev/null
+  rm .git/objects/"$(cut -c1-2 ../unreachable-packed.id)"/"$(cut -c3- ../unreachable-packed.id)"
+
+  # An unreachable loose object.
+  echo "unreachable loose" | git hash-object -w --stdin > ../unreachable-loose.id
+
+  # A pack that must not be touched.
+  echo "kept" | git hash-object -w --stdin > ../kept.id
+  git pack-objects -q .git/objects/pack/pack < ../kept.id > ../kept.pack-id
+  touch .git/objects/pack/pack-"$(cat ../kept.pack-id)".keep
+  rm .git/objects/"$(cut -c1-2 ../kept.id)"/"$(cut -c3- ../kept.id)"
+)
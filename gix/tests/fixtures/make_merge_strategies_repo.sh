@@ -0,0 +1,33 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+echo base > a
+echo base > b
+echo base > c
+printf '1\n2 x\n3\n4\n5 y\n6\n7\n8\n9 z\n' > ws
+git add . && git commit -q -m "base"
+
+for name in A B C; do
+  git checkout -q -b "$name" main
+  lowercase=$(echo "$name" | tr '[:upper:]' '[:lower:]')
+  echo "$name" > "$lowercase"
+  git commit -q -am "$name"
+done
+
+git checkout -q -b theirs-whitespace main
+printf '1\n2  x\n3\n4\n5 Y\n6\n7\n8\n9  z\n' > ws
+git commit -q -am "their whitespace"
+
+git checkout -q main
+printf '1\n2 x \n3\n4\n5  y\n6\n7\n8\n9 Z\n' > ws
+git commit -q -am "our whitespace"
+
+git checkout -q -b merged-octopus main
+git merge -q --no-edit A B C
+
+git checkout -q -b merged-ignore-space-change main
+git merge -q --no-edit -X ignore-space-change theirs-whitespace
+
+git checkout -q main
@@ -0,0 +1,34 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+
+printf '1\n2\n3\n4\n5\n' > clean
+echo base > conflict
+echo base > deleted
+echo base > unchanged
+mkdir dir && echo base > dir/file
+git add . && git commit -q -m "base"
+
+git checkout -q -b theirs-clean main
+printf '1\n2\n3\n4\nfive\n' > clean
+echo new > new
+echo new > dir/new
+git rm -q deleted
+git add . && git commit -q -m "their clean changes"
+
+git checkout -q -b theirs main
+printf '1\n2\n3\n4\nfive\n' > clean
+echo theirs > conflict
+echo new > new
+git add . && git commit -q -m "their conflicting changes"
+
+git checkout -q main
+printf 'one\n2\n3\n4\n5\n' > clean
+echo ours > conflict
+git commit -q -am "our changes"
+
+git checkout -q -b merged-by-git main
+git merge -q --no-edit theirs-clean
+
+git checkout -q main
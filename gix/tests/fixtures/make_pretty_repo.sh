@@ -0,0 +1,142 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+# A history with merges, an octopus merge, branches and tags, along with commit messages and identities
+# to exercise pretty formats, and a history to exercise the rendering of graphs.
+git init -q
+
+count=0
+# commit <message> [<author> <author-email>]
+function commit() {
+  count=$((count + 1))
+  echo "$count" > "file-$count"
+  git add -A
+  GIT_AUTHOR_DATE="$((1600000000 + count * 86400)) +0200" GIT_COMMITTER_DATE="$((1600000000 + count * 86400 + 60)) -0700" \
+  GIT_AUTHOR_NAME="${2:-Alice}" GIT_AUTHOR_EMAIL="${3:-alice@example.com}" \
+    git commit -q -m "$1"
+}
+
+# merge <message> <branch>...
+function merge() {
+  local message=$1
+  shift
+  count=$((count + 1))
+  GIT_AUTHOR_DATE="$((1600000000 + count * 86400)) +0200" GIT_COMMITTER_DATE="$((1600000000 + count * 86400)) +0200" \
+    git merge -q --no-ff -m "$message" "$@"
+}
+
+commit "initial commit"
+cat >.mailmap <<EOF
+Robert Roe <robert@example.com> <bob@old.example.com>
+EOF
+commit $'Add a mailmap\n\nso that Bob is known by his real name.' Bob bob@old.example.com
+commit $'A subject\nthat spans two lines\n\nThe body has\n\n\ttabs, blank lines\nand   trailing spaces   \n\nSigned-off-by: Alice <alice@example.com>\nCo-authored-by: Bob <bob@old.example.com>'
+git tag v1.0
+
+git checkout -q -b feature
+commit "feat: side work"
+commit "feat: more side work" Bob bob@old.example.com
+git checkout -q main
+commit $'fix: main work\n\nReviewed-by: Carol <carol@example.com>\n  who looked closely\n[a note]\nSigned-off-by: Alice <alice@example.com>'
+merge "Merge branch 'feature'" feature
+git tag -a -m "annotated" v2.0
+
+git checkout -q -b one
+commit "one"
+git checkout -q -b two main
+commit "two"
+git checkout -q -b three main
+commit "three"
+git checkout -q main
+merge "Merge branches 'one', 'two' and 'three'" one two three
+git branch other
+
+# A pretend `gpg` which judges signatures by their text, along with signed commits on a branch of their own.
+cat >fake-gpg <<'EOF'
+#!/bin/sh
+while [ $# -gt 0 ]; do
+  [ "$1" = --verify ] && signature=$2
+  shift
+done
+cat >/dev/null
+case "$(cat "$signature")" in
+  *untrusted*)
+    echo "[GNUPG:] GOODSIG 0123456789ABCDEF Alice <alice@example.com>"
+    echo "[GNUPG:] TRUST_UNDEFINED 0 pgp"
+    ;;
+  *good*)
+    echo "[GNUPG:] NEWSIG"
+    echo "[GNUPG:] GOODSIG 0123456789ABCDEF Alice <alice@example.com>"
+    echo "[GNUPG:] VALIDSIG 0123456789ABCDEF0123456789ABCDEF01234567 2020-09-13 1600000000 0 4 0 1 10 00 FEDCBA9876543210FEDCBA9876543210FEDCBA98"
+    echo "[GNUPG:] TRUST_ULTIMATE 0 pgp"
+    echo 'gpg: Good signature from "Alice <alice@example.com>" [ultimate]' >&2
+    ;;
+  *bad*)
+    echo "[GNUPG:] BADSIG 0123456789ABCDEF Alice <alice@example.com>"
+    echo 'gpg: BAD signature from "Alice <alice@example.com>" [ultimate]' >&2
+    exit 1
+    ;;
+  *)
+    echo "[GNUPG:] ERRSIG 0123456789ABCDEF 1 10 00 1600000000 9 -"
+    echo "[GNUPG:] NO_PUBKEY 0123456789ABCDEF"
+    echo "gpg: Can't check signature: No public key" >&2
+    exit 2
+    ;;
+esac
+EOF
+chmod +x fake-gpg
+
+# signed_commit <signature-text>
+function signed_commit() {
+  local parent
+  parent=$(git rev-parse -q --verify signed || git rev-parse main)
+  count=$((count + 1))
+  git update-ref refs/heads/signed "$(printf 'tree %s\nparent %s\nauthor Alice <alice@example.com> %s +0200\ncommitter Alice <alice@example.com> %s +0200\ngpgsig -----BEGIN PGP SIGNATURE-----\n \n %s\n -----END PGP SIGNATURE-----\n\nsigned: %s\n' \
+    "$(git rev-parse "main^{tree}")" "$parent" "$((1600000000 + count * 86400))" "$((1600000000 + count * 86400))" "$1" "$1" \
+    | git hash-object -t commit -w --stdin)"
+}
+signed_commit good
+signed_commit untrusted
+signed_commit bad
+signed_commit unknown
+git update-ref refs/heads/signed "$(git commit-tree -p signed -m "unsigned" "main^{tree}")"
+
+function baseline() {
+  local name=$1
+  shift
+  git log "$@" > "baseline.$name"
+}
+
+baseline format-hashes '--format=%H|%h|%T|%t|%P|%p'
+baseline format-idents '--format=%an|%ae|%al|%aN|%aE|%aL|%cn|%ce|%cl|%cN|%cE|%cL'
+baseline format-dates '--format=%ad|%aD|%ai|%aI|%at|%as|%cd|%cD|%ci|%cI|%ct|%cs'
+baseline format-message '--format=%s|%f|%b|%B|%e|%m'
+baseline format-trailers '--format=[%(trailers)][%(trailers:only,unfold)][%(trailers:key=Signed-off-by,valueonly)][%(trailers:separator=%x2C )]'
+baseline format-decorations '--format=%h%d|%D'
+baseline format-special '--format=%%|%x41|%n|%+s|%-b|% h|%Z'
+baseline format-padding '--format=%<(10)%h|%>(12)%s|%><(9)%an|%<(8,trunc)%s|%<(8,ltrunc)%s|%<(8,mtrunc)%s|%<|(40)%an|%>>(3)%ae'
+baseline format-separator '--format=format:%h %s'
+baseline format-colors --color=always '--format=%Cred%h%Creset %C(bold blue)%an%Creset %C(auto)%d%C(reset) %Cgreen%s'
+baseline format-no-colors '--format=%Cred%h%Creset %C(bold blue)%an%Creset %C(auto)%d%C(reset) %Cgreen%s'
+baseline oneline --pretty=oneline
+baseline short --pretty=short
+baseline medium --pretty=medium
+baseline full --pretty=full
+baseline fuller --pretty=fuller
+baseline raw --pretty=raw
+baseline medium-decorated --pretty=medium --decorate
+baseline abbreviated-oneline-decorated --oneline --decorate
+baseline no-mailmap --no-mailmap --pretty=medium
+baseline date-iso --date=iso --format=%ad
+baseline date-iso-strict --date=iso-strict --format=%ad
+baseline date-rfc --date=rfc --format=%ad
+baseline date-short --date=short --format=%ad
+baseline date-raw --date=raw --format=%ad
+baseline date-unix --date=unix --format=%ad
+baseline date-custom '--date=format:%Y/%m/%d %H' --format=%ad
+baseline graph-oneline --graph --oneline
+baseline graph-medium --graph --pretty=medium
+baseline graph-format --graph '--format=%h%n%s'
+git -c gpg.program="$PWD/fake-gpg" log --format='%G?|%GS|%GK|%GF|%GP|%GG' main..signed > baseline.format-signatures
+# Git fails on `%GT` if the trust level is unknown, so only good signatures are shown.
+git -c gpg.program="$PWD/fake-gpg" log --format='%GT' main..signed~3 > baseline.format-signature-trust
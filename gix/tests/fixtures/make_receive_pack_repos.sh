@@ -0,0 +1,33 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q --bare server.git
+
+git init -q client
+(cd client
+  echo 1 > file
+  git add file && git commit -q -m first
+  git branch other
+  git push -q ../server.git main other
+
+  echo 2 > file
+  git commit -q -am second
+  git rev-parse HEAD~1 > ../first.id
+  git rev-parse HEAD > ../second.id
+
+  # Everything needed to get from `first` to `second`, as thin pack just like `git push` would send it.
+  printf '%s\n^%s\n' "$(cat ../second.id)" "$(cat ../first.id)" | git pack-objects -q --revs --thin --stdout > ../first-to-second.pack
+  # Just the commit, lacking its tree.
+  git rev-parse HEAD | git pack-objects -q --stdout > ../second-commit-only.pack
+
+  git checkout -q --orphan unrelated
+  echo unrelated > file
+  git commit -q -am unrelated
+  git rev-parse HEAD > ../unrelated.id
+  git rev-parse HEAD | git pack-objects -q --revs --stdout > ../unrelated.pack
+)
+
+git init -q non-bare-server
+(cd non-bare-server
+  git fetch -q --update-head-ok ../client main:main
+)
@@ -0,0 +1,10 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q
+git checkout -q -b main
+git commit -q --allow-empty -m c1
+git commit -q --allow-empty -m c2
+git commit -q --allow-empty -m c3
+git branch keep
+git reset -q --hard HEAD~1
@@ -0,0 +1,25 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q --object-format=sha256 repo
+(cd repo
+  git checkout -q -b main
+  mkdir dir
+  echo a > a
+  echo b > dir/b
+  git add .
+  git commit -q -m c1
+  echo c > dir/c
+  git add dir/c
+  git commit -q -m c2
+  git branch other HEAD~1
+  git tag -a -m "annotated" v1 HEAD~1
+  git repack -adq
+
+  echo d > d
+  git add d
+  git commit -q -m c3
+  git commit-graph write --no-progress --reachable
+  git pack-refs --all
+  git branch loose-ref HEAD~2
+)
@@ -0,0 +1,64 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+mkdir upstream
+(cd upstream
+  git init -q nested
+  (cd nested
+    echo n1 > nested.txt
+    git add . && git commit -q -m n1
+  )
+
+  git init -q lib
+  (cd lib
+    echo c1 > lib.txt
+    git submodule add -q ../nested nested
+    git add . && git commit -q -m c1
+    echo c2 > lib.txt
+    git add . && git commit -q -m c2
+  )
+
+  git init -q super
+  (cd super
+    echo super > super.txt
+    git submodule add -q ../lib lib
+    git add . && git commit -q -m "add lib"
+  )
+)
+
+git clone -q upstream/super uninitialized
+
+git clone -q upstream/super old-form
+(cd old-form
+  git submodule init -q
+  git clone -q ../upstream/lib lib
+)
+
+git clone -q --recurse-submodules upstream/super checked-out
+(cd checked-out
+  git -C lib checkout -q HEAD~1
+)
+
+git clone -q --recurse-submodules upstream/super local-commit
+(cd local-commit/lib
+  git checkout -q -b local HEAD~1
+  echo local > local.txt
+  git add local.txt && git commit -q -m local
+)
+
+git clone -q --recurse-submodules upstream/super modified
+(cd modified/lib
+  git checkout -q HEAD~1
+  echo modified > lib.txt
+)
+
+(cd upstream
+  (cd lib
+    echo c3 > lib.txt
+    git commit -q -am c3
+  )
+  (cd super
+    git -C lib pull -q
+    git commit -q -am "update lib"
+  )
+)
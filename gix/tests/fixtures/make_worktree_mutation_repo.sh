@@ -0,0 +1,20 @@
+#!/usr/bin/env bash
+set -eu -o pipefail
+
+git init -q repo
+(cd repo
+  git checkout -q -b main
+  mkdir dir
+  echo a > a
+  echo c > dir/c
+  git add .
+  git commit -q -m c1
+  echo b > b
+  git add b
+  git commit -q -m c2
+  git branch other HEAD~1
+
+  git worktree add -q ../wt-other other
+  git worktree add -q --lock --reason "on removable media" ../wt-locked HEAD~1
+  git worktree add -q --detach ../wt-deleted && rm -Rf ../wt-deleted
+)
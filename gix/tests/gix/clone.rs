@@ -112,6 +112,139 @@ mod blocking_io {
         Ok(())
     }
 
+    #[test]
+    fn shallow_clone_follows_tags_into_fetched_history() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let (repo, _out) = gix::prepare_clone_bare(remote::repo("base").path(), tmp.path())?
+            .with_shallow(Shallow::DepthAtRemote(2.try_into()?))
+            .fetch_only(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+        let tags: Vec<_> = repo
+            .references()?
+            .tags()?
+            .map(|r| r.expect("valid").name().shorten().to_owned())
+            .collect();
+        assert_eq!(
+            tags,
+            ["b-tag"],
+            "only tags pointing into the fetched history are fetched, like Git does with single-branch clones"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn shallow_commits_can_be_pruned() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let (repo, _out) = gix::prepare_clone_bare(remote::repo("base").path(), tmp.path())?
+            .with_shallow(Shallow::DepthAtRemote(2.try_into()?))
+            .fetch_only(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+        let boundary = repo.shallow_commits()?.expect("shallow").to_vec();
+        assert!(
+            repo.prune_shallow_commits()?.is_empty(),
+            "nothing to do in a fresh clone"
+        );
+
+        let missing = hex_to_id("0000000000000000000000000000000000000001");
+        let mut content = std::fs::read(repo.shallow_file())?;
+        content.extend_from_slice(format!("{missing}\n").as_bytes());
+        std::fs::write(repo.shallow_file(), content)?;
+
+        assert_eq!(
+            repo.prune_shallow_commits()?,
+            [missing],
+            "commits that don't exist anymore are removed"
+        );
+        assert_eq!(
+            repo.shallow_commits()?.expect("still shallow").as_slice(),
+            boundary,
+            "the actual boundary is retained"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn bundle_uri_is_applied_before_fetching() -> crate::Result {
+        let bundle_dir = gix_testtools::tempfile::TempDir::new()?;
+        let bundle_path = bundle_dir.path().join("all.bundle");
+        assert!(gix_testtools::run_git(
+            remote::repo("base").path(),
+            &["bundle", "create", bundle_path.to_str().expect("valid UTF-8"), "--all"]
+        )?
+        .success());
+
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let (repo, out) = gix::prepare_clone_bare(remote::repo("base").path(), tmp.path())?
+            .with_bundle_uri(gix::path::into_bstr(bundle_path.as_path()).into_owned())
+            .fetch_only(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+        assert_eq!(out.bundles.len(), 1, "the bundle was applied");
+        assert!(
+            out.bundles[0]
+                .refs
+                .iter()
+                .all(|name| name.as_bstr().starts_with_str("refs/bundles/")),
+            "all bundle refs are placed into their own namespace"
+        );
+        let bundle_main = repo.find_reference("refs/bundles/heads/main")?.id().detach();
+        assert_eq!(
+            repo.find_reference("refs/remotes/origin/main")?.id(),
+            bundle_main,
+            "the fetch agrees with the bundle"
+        );
+        assert!(
+            matches!(out.status, gix::remote::fetch::Status::NoPackReceived { .. })
+                || matches!(out.status, gix::remote::fetch::Status::Change { ref write_pack_bundle, .. } if write_pack_bundle.index.num_objects == 0),
+            "all objects were provided by the bundle, so there is nothing to fetch"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn bundle_list_with_unusable_bundles_falls_back_to_fetching() -> crate::Result {
+        let bundle_dir = gix_testtools::tempfile::TempDir::new()?;
+        assert!(gix_testtools::run_git(
+            remote::repo("base").path(),
+            &[
+                "bundle",
+                "create",
+                bundle_dir.path().join("main.bundle").to_str().expect("valid UTF-8"),
+                "main"
+            ]
+        )?
+        .success());
+        let list_path = bundle_dir.path().join("bundle-list");
+        std::fs::write(
+            &list_path,
+            "[bundle]\n\tversion = 1\n\tmode = all\n[bundle \"missing\"]\n\turi = missing.bundle\n[bundle \"main\"]\n\turi = main.bundle\n",
+        )?;
+
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let (repo, out) = gix::prepare_clone_bare(remote::repo("base").path(), tmp.path())?
+            .with_bundle_uri(gix::path::into_bstr(list_path.as_path()).into_owned())
+            .fetch_only(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+        assert_eq!(
+            out.bundles.len(),
+            1,
+            "the missing bundle is skipped, the one with a relative path is applied"
+        );
+        assert!(repo.try_find_reference("refs/bundles/heads/main")?.is_some());
+        assert!(
+            repo.find_reference("refs/remotes/origin/a")?.id().object().is_ok(),
+            "branches not contained in the bundle are fetched"
+        );
+
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let (repo, out) = gix::prepare_clone_bare(remote::repo("base").path(), tmp.path())?
+            .with_bundle_uri(gix::path::into_bstr(bundle_dir.path().join("does-not-exist")).into_owned())
+            .fetch_only(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+        assert!(out.bundles.is_empty(), "an unusable bundle URI is ignored…");
+        assert!(
+            repo.find_reference("refs/remotes/origin/main")?.id().object().is_ok(),
+            "…and everything is fetched instead"
+        );
+        Ok(())
+    }
+
     #[test]
     fn from_shallow_prohibited_with_option() -> crate::Result {
         let tmp = gix_testtools::tempfile::TempDir::new()?;
@@ -147,8 +280,8 @@ mod blocking_io {
             vec![
                 hex_to_id("2d9d136fb0765f2e24c44a0f91984318d580d03b"),
                 hex_to_id("dfd0954dabef3b64f458321ef15571cc1a46d552"),
-                hex_to_id("dfd0954dabef3b64f458321ef15571cc1a46d552"),
-            ]
+            ],
+            "duplicate shallow commits sent by the remote are only written once"
         );
         assert_eq!(
             repo.config_snapshot().boolean("my.marker"),
@@ -543,6 +676,36 @@ mod blocking_io {
         assure_index_entries_on_disk(&index, repo.workdir().expect("non-bare"));
         Ok(())
     }
+    #[test]
+    fn fetch_and_checkout_with_submodules() -> crate::Result {
+        let tmp = gix_testtools::tempfile::TempDir::new()?;
+        let upstream = gix_testtools::scripted_fixture_read_only("make_submodule_update_repo.sh")?.join("upstream");
+        let mut prepare = gix::clone::PrepareFetch::new(
+            upstream.join("super"),
+            tmp.path(),
+            gix::create::Kind::WithWorktree,
+            Default::default(),
+            restricted(),
+        )?
+        .with_recurse_submodules(Some(true));
+        let (mut checkout, _out) =
+            prepare.fetch_then_checkout(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+        let (repo, _) = checkout.main_worktree(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+        let workdir = repo.workdir().expect("non-bare");
+        assert_eq!(std::fs::read(workdir.join("lib/lib.txt"))?.as_bstr(), "c3\n");
+        assert_eq!(std::fs::read(workdir.join("lib/nested/nested.txt"))?.as_bstr(), "n1\n");
+        let repo = gix::open_opts(workdir, restricted())?;
+        let sm = repo.submodules()?.expect("present").next().expect("one");
+        assert!(
+            sm.is_active()?,
+            "submodules are initialized, which is visible after reopening"
+        );
+        assert!(repo.git_dir().join("modules/lib/modules/nested").is_dir());
+        assert!(!sm.open()?.expect("cloned").is_dirty()?);
+        Ok(())
+    }
+
     #[test]
     fn fetch_and_checkout_specific_ref() -> crate::Result {
         let tmp = gix_testtools::tempfile::TempDir::new()?;
@@ -733,6 +896,112 @@ mod blocking_io {
         Ok(())
     }
 
+    #[test]
+    #[cfg(feature = "sha256")]
+    fn fetch_and_checkout_sha256_repo() -> crate::Result {
+        let remote_repo = gix_testtools::scripted_fixture_read_only("make_sha256_repo.sh")?.join("repo");
+        for version in [
+            gix::protocol::transport::Protocol::V1,
+            gix::protocol::transport::Protocol::V2,
+        ] {
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let mut prepare = gix::clone::PrepareFetch::new(
+                remote_repo.as_path(),
+                tmp.path(),
+                gix::create::Kind::WithWorktree,
+                Default::default(),
+                restricted().config_overrides(Some(format!("protocol.version={}", version as u8))),
+            )?;
+            let (mut checkout, out) =
+                prepare.fetch_then_checkout(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+            assert_eq!(out.ref_map.object_hash, gix::hash::Kind::Sha256);
+            let (repo, _) =
+                checkout.main_worktree(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+
+            assert_eq!(repo.object_hash(), gix::hash::Kind::Sha256, "{version:?}");
+            let config = repo.config_snapshot();
+            assert_eq!(config.integer("core.repositoryFormatVersion"), Some(1));
+            assert_eq!(
+                config.string("extensions.objectFormat").as_deref(),
+                Some("sha256".into())
+            );
+            assert_eq!(
+                config.strings("remote.origin.url").expect("set").len(),
+                1,
+                "the remote configuration isn't duplicated in memory"
+            );
+
+            let remote = gix::open_opts(&remote_repo, restricted())?;
+            assert_eq!(repo.head_id()?, remote.head_id()?);
+            assert_eq!(
+                repo.find_reference("refs/remotes/origin/other")?.id(),
+                remote.find_reference("refs/heads/other")?.id()
+            );
+            assert_eq!(
+                repo.find_reference("refs/tags/v1")?.id(),
+                remote.find_reference("refs/tags/v1")?.id()
+            );
+
+            let index = repo.index()?;
+            assert_eq!(index.entries().len(), 4, "All entries are known as per HEAD tree");
+            assure_index_entries_on_disk(&index, repo.workdir().expect("non-bare"));
+            assert!(
+                gix_testtools::run_git(tmp.path(), &["fsck", "--strict", "--no-progress"])?.success(),
+                "git can read the received pack, its index and the references we wrote"
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(all(feature = "sha256", unix))]
+    #[serial_test::serial]
+    fn sha256_repos_are_cloned_with_a_single_connection() -> crate::Result {
+        use std::os::unix::fs::PermissionsExt;
+
+        let remote_repo = gix_testtools::scripted_fixture_read_only("make_sha256_repo.sh")?.join("repo");
+        let bin = gix_testtools::tempfile::TempDir::new()?;
+        let connections = bin.path().join("connections");
+        let upload_pack = bin.path().join("git-upload-pack");
+        std::fs::write(
+            &upload_pack,
+            format!(
+                "#!/bin/sh\necho connected >>'{}'\nexec git upload-pack \"$@\"\n",
+                connections.display()
+            ),
+        )?;
+        std::fs::set_permissions(&upload_pack, std::fs::Permissions::from_mode(0o755))?;
+        let path = std::env::join_paths(
+            std::iter::once(bin.path().to_owned())
+                .chain(std::env::split_paths(&std::env::var_os("PATH").unwrap_or_default())),
+        )?;
+        let _env = gix_testtools::Env::new().set("PATH", path.into_string().expect("valid UTF-8"));
+
+        for version in [
+            gix::protocol::transport::Protocol::V1,
+            gix::protocol::transport::Protocol::V2,
+        ] {
+            std::fs::remove_file(&connections).ok();
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            let (repo, out) = gix::clone::PrepareFetch::new(
+                remote_repo.as_path(),
+                tmp.path(),
+                gix::create::Kind::Bare,
+                Default::default(),
+                restricted().config_overrides(Some(format!("protocol.version={}", version as u8))),
+            )?
+            .fetch_only(gix::progress::Discard, &std::sync::atomic::AtomicBool::default())?;
+            assert_eq!(repo.object_hash(), gix::hash::Kind::Sha256, "{version:?}");
+            assert!(matches!(out.status, gix::remote::fetch::Status::Change { .. }));
+            assert_eq!(
+                std::fs::read_to_string(&connections)?,
+                "connected\n",
+                "{version:?}: the repository is reconfigured without connecting again"
+            );
+        }
+        Ok(())
+    }
+
     #[test]
     fn fetch_only_without_configuration() -> crate::Result {
         let tmp = gix_testtools::tempfile::TempDir::new()?;
@@ -346,20 +346,84 @@ mod diff {
             ("Default", Algorithm::Myers),
             ("minimal", Algorithm::MyersMinimal),
             ("histogram", Algorithm::Histogram),
+            ("patience", Algorithm::Patience),
+            ("Patience", Algorithm::Patience),
         ] {
             assert_eq!(Diff::ALGORITHM.try_into_algorithm(bcow(actual))?, expected);
             assert!(Diff::ALGORITHM.validate(actual.into()).is_ok());
         }
         assert_eq!(
-            Diff::ALGORITHM
-                .try_into_algorithm(bcow("patience"))
+            Diff::ALGORITHM.try_into_algorithm(bcow("foo")).unwrap_err().to_string(),
+            "Unknown diff algorithm named 'foo'"
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "apply")]
+    fn color_moved() -> crate::Result {
+        use gix_diff::patch::color::Moved;
+        for (actual, expected) in [
+            ("no", None),
+            ("false", None),
+            ("true", Some(Moved::Zebra)),
+            ("default", Some(Moved::Zebra)),
+            ("zebra", Some(Moved::Zebra)),
+            ("dimmed-zebra", Some(Moved::Zebra)),
+            ("plain", Some(Moved::Plain)),
+            ("blocks", Some(Moved::Blocks)),
+        ] {
+            assert_eq!(Diff::COLOR_MOVED.try_into_color_moved(bcow(actual))?, expected);
+            assert!(Diff::COLOR_MOVED.validate(actual.into()).is_ok());
+        }
+        assert_eq!(
+            Diff::COLOR_MOVED
+                .try_into_color_moved(bcow("foo"))
                 .unwrap_err()
                 .to_string(),
-            "The 'patience' algorithm is not yet implemented"
+            "The key \"diff.colorMoved=foo\" was invalid"
         );
+        assert!(Diff::COLOR_MOVED.validate("foo".into()).is_err());
+        Ok(())
+    }
+}
+
+mod color {
+    use gix::config::tree::{color, Color, Key};
+
+    use crate::config::tree::bcow;
+
+    #[test]
+    fn when() -> crate::Result {
+        for (actual, expected) in [
+            ("auto", None),
+            ("true", None),
+            ("always", Some(true)),
+            ("never", Some(false)),
+            ("false", Some(false)),
+        ] {
+            assert_eq!(Color::UI.try_into_when(bcow(actual))?, expected);
+            assert!(Color::DIFF.validate(actual.into()).is_ok());
+        }
+        assert!(Color::UI.try_into_when(bcow("sometimes")).is_err());
+        assert!(Color::DIFF.validate("sometimes".into()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn diff_slots() -> crate::Result {
+        assert_eq!(color::Diff::OLD_MOVED.logical_name(), "color.diff.oldMoved");
         assert_eq!(
-            Diff::ALGORITHM.try_into_algorithm(bcow("foo")).unwrap_err().to_string(),
-            "Unknown diff algorithm named 'foo'"
+            color::Diff::META.try_into_color(bcow("bold yellow"))?.to_ansi(),
+            "\x1b[1;33m"
+        );
+        assert!(color::Diff::META.validate("bold yellow".into()).is_ok());
+        assert_eq!(
+            color::Diff::META
+                .try_into_color(bcow("bold pink"))
+                .unwrap_err()
+                .to_string(),
+            "The key \"color.diff.meta=bold pink\" was invalid"
         );
         Ok(())
     }
@@ -393,6 +457,40 @@ mod merge {
     }
 }
 
+#[cfg(feature = "revision")]
+mod log {
+    use gix::{
+        config::tree::{Key, Log},
+        pretty::{DateFormat, DateMode},
+    };
+
+    use crate::config::tree::bcow;
+
+    #[test]
+    fn date() -> crate::Result {
+        for (actual, expected) in [
+            ("iso", DateFormat::Iso8601.into()),
+            ("rfc2822", DateFormat::Rfc2822.into()),
+            (
+                "short-local",
+                DateMode {
+                    format: DateFormat::Short,
+                    local: true,
+                },
+            ),
+            ("format:%Y", DateFormat::Custom("%Y".into()).into()),
+        ] {
+            assert_eq!(Log::DATE.try_into_date_mode(bcow(actual))?, expected);
+            assert!(Log::DATE.validate(actual.into()).is_ok());
+        }
+        assert_eq!(
+            Log::DATE.try_into_date_mode(bcow("foo")).unwrap_err().to_string(),
+            "The key \"log.date=foo\" was invalid"
+        );
+        Ok(())
+    }
+}
+
 mod core {
     use std::time::Duration;
 
@@ -695,6 +793,11 @@ mod extensions {
             gix_hash::Kind::Sha1,
             "case-insensitive"
         );
+        #[cfg(feature = "sha256")]
+        assert_eq!(
+            Extensions::OBJECT_FORMAT.try_into_object_format(bcow("sha256"))?,
+            gix_hash::Kind::Sha256
+        );
         assert_eq!(
             Extensions::OBJECT_FORMAT
                 .try_into_object_format(bcow("invalid"))
@@ -703,6 +806,8 @@ mod extensions {
             "The key \"extensions.objectFormat=invalid\" was invalid"
         );
         assert!(Extensions::OBJECT_FORMAT.validate("sha1".into()).is_ok());
+        #[cfg(feature = "sha256")]
+        assert!(Extensions::OBJECT_FORMAT.validate("sha256".into()).is_ok());
         assert!(Extensions::OBJECT_FORMAT.validate("invalid".into()).is_err());
         Ok(())
     }
@@ -728,6 +833,52 @@ mod checkout {
     }
 }
 
+mod gc {
+    use std::time::{Duration, SystemTime};
+
+    use gix::config::tree::{Gc, Key};
+
+    use crate::config::tree::bcow;
+
+    #[test]
+    fn expiry() -> crate::Result {
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000_000);
+        for never in ["never", "false"] {
+            assert_eq!(Gc::PRUNE_EXPIRE.try_into_expiry(bcow(never), now)?, None);
+        }
+        for everything in ["now", "all"] {
+            assert_eq!(
+                Gc::REFLOG_EXPIRE
+                    .try_into_expiry(bcow(everything), now)?
+                    .map(|time| time.seconds),
+                Some(1_000_000_000)
+            );
+        }
+        assert_eq!(
+            Gc::PRUNE_EXPIRE
+                .try_into_expiry(bcow("2.weeks.ago"), now)?
+                .map(|time| time.seconds),
+            Some(1_000_000_000 - 14 * 24 * 60 * 60)
+        );
+        assert!(Gc::PRUNE_EXPIRE.validate("2.weeks.ago".into()).is_ok());
+        assert_eq!(
+            Gc::WORKTREE_PRUNE_EXPIRE
+                .try_into_expiry(bcow("1.week.ago"), now)?
+                .map(|time| time.seconds),
+            Some(1_000_000_000 - 7 * 24 * 60 * 60)
+        );
+        assert_eq!(
+            Gc::REFLOG_EXPIRE
+                .try_into_expiry(bcow("no date"), now)
+                .unwrap_err()
+                .to_string(),
+            "The date format at key \"gc.reflogExpire=no date\" was invalid"
+        );
+        assert!(Gc::REFLOG_EXPIRE.validate("no date".into()).is_err());
+        Ok(())
+    }
+}
+
 mod pack {
     use gix::config::tree::{Key, Pack};
 
@@ -839,7 +990,7 @@ mod gitoxide {
         #[test]
         fn connect_timeout() -> crate::Result {
             assert_eq!(
-                gitoxide::Http::CONNECT_TIMEOUT.validated_assignment_fmt(&Duration::from_millis(1000).as_millis())?,
+                gitoxide::Http::CONNECT_TIMEOUT.validated_assignment_fmt(&Duration::from_secs(1).as_millis())?,
                 "gitoxide.http.connectTimeout=1000"
             );
             Ok(())
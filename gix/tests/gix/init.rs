@@ -74,6 +74,39 @@ mod non_bare {
         );
         Ok(())
     }
+    #[test]
+    #[cfg(feature = "sha256")]
+    fn init_with_sha256_object_format() -> crate::Result {
+        let tmp = tempfile::tempdir()?;
+        let repo: gix::Repository = gix::ThreadSafeRepository::init_opts(
+            tmp.path(),
+            gix::create::Kind::WithWorktree,
+            gix::create::Options {
+                object_hash: gix::hash::Kind::Sha256,
+                ..Default::default()
+            },
+            crate::restricted(),
+        )?
+        .into();
+        assert_eq!(repo.object_hash(), gix::hash::Kind::Sha256);
+        let config = repo.config_snapshot();
+        assert_eq!(config.integer("core.repositoryFormatVersion"), Some(1));
+        assert_eq!(
+            config.string("extensions.objectFormat").as_deref(),
+            Some("sha256".into())
+        );
+
+        let empty_tree = repo.write_object(gix::objs::Tree::empty())?;
+        assert_eq!(empty_tree, gix::ObjectId::empty_tree(gix::hash::Kind::Sha256));
+        let id = repo.commit("HEAD", "initial", empty_tree, gix::commit::NO_PARENT_IDS)?;
+        assert_eq!(repo.head_id()?, id);
+        assert!(
+            gix_testtools::run_git(tmp.path(), &["fsck", "--strict", "--no-progress"])?.success(),
+            "git can read the repository and objects we wrote"
+        );
+        Ok(())
+    }
+
     #[test]
     fn init_into_empty_directory_creates_a_dot_git_dir() -> crate::Result {
         let tmp = tempfile::tempdir()?;
@@ -1,6 +1,174 @@
 use gix::remote::Direction;
 
 mod log {
+    use std::fmt::Write;
+
+    use gix::reference::log::expire::Options;
+
+    fn writable_repo() -> crate::Result<(gix::Repository, gix_testtools::tempfile::TempDir)> {
+        crate::repo_rw("make_reflog_expire_repo.sh")
+    }
+
+    fn messages(repo: &gix::Repository, name: &str) -> crate::Result<Vec<String>> {
+        let reference = repo.find_reference(name)?;
+        let mut platform = reference.log_iter();
+        Ok(platform
+            .all()?
+            .expect("log exists")
+            .map(|line| line.map(|line| line.message.to_string()))
+            .collect::<Result<_, _>>()?)
+    }
+
+    fn now() -> Option<gix::date::Time> {
+        Some(gix::date::Time::now_utc())
+    }
+
+    #[test]
+    fn expire_by_age() -> crate::Result {
+        let (repo, _tmp) = writable_repo()?;
+        let outcome = repo
+            .reflog_expire("refs/heads/main".try_into()?, Options::default())?
+            .expect("log exists");
+        assert_eq!(outcome.num_removed, 0, "nothing expires by default");
+
+        let outcome = repo
+            .reflog_expire(
+                "refs/heads/main".try_into()?,
+                Options {
+                    expire: now(),
+                    ..Default::default()
+                },
+            )?
+            .expect("log exists");
+        assert_eq!(outcome.num_removed, 4);
+        assert!(messages(&repo, "main")?.is_empty());
+        assert_eq!(
+            repo.find_reference("main")?.id(),
+            repo.rev_parse_single("keep~1")?,
+            "the reference itself is unchanged"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn expire_unreachable_entries() -> crate::Result {
+        let (repo, _tmp) = writable_repo()?;
+        let options = Options {
+            expire: None,
+            expire_unreachable: now(),
+        };
+        let outcome = repo
+            .reflog_expire("refs/heads/main".try_into()?, options)?
+            .expect("log exists");
+        assert_eq!(outcome.num_removed, 2, "c3 isn't reachable from main anymore");
+        assert_eq!(messages(&repo, "main")?, ["commit (initial): c1", "commit: c2"]);
+
+        let outcome = repo.reflog_expire("HEAD".try_into()?, options)?.expect("log exists");
+        assert_eq!(
+            outcome.num_removed, 0,
+            "for HEAD, all references are considered, and c3 is reachable from 'keep'"
+        );
+
+        repo.find_reference("keep")?.delete()?;
+        let outcome = repo.reflog_expire("HEAD".try_into()?, options)?.expect("log exists");
+        assert_eq!(outcome.num_removed, 2, "now c3 isn't reachable from anywhere");
+        Ok(())
+    }
+
+    #[test]
+    fn expire_unreachable_entries_traverses_old_commits_only_if_needed() -> crate::Result {
+        let (repo, _tmp) = writable_repo()?;
+        let recent = gix::date::Time::now_utc().seconds;
+        let day = 24 * 60 * 60;
+        // Make all entries recent while the commits remain old, so commits reachable only through old commits have to be found.
+        let log_path = repo.git_dir().join("logs").join("refs").join("heads").join("main");
+        let mut log = String::new();
+        for line in std::fs::read_to_string(&log_path)?.lines() {
+            let (header, message) = line.split_once('\t').expect("message");
+            let (header, _time_and_offset) = header
+                .rsplit_once(' ')
+                .and_then(|(h, _)| h.rsplit_once(' '))
+                .expect("time");
+            writeln!(log, "{header} {} +0000\t{message}", recent - day)?;
+        }
+        std::fs::write(&log_path, log)?;
+
+        let options = Options {
+            expire: Some(gix::date::Time::new(recent - 2 * day, 0)),
+            expire_unreachable: now(),
+        };
+        assert!(
+            repo.find_reference("main")?
+                .id()
+                .object()?
+                .into_commit()
+                .time()?
+                .seconds
+                < recent - 2 * day,
+            "the walk stops at the tip as it's older than the cutoff"
+        );
+        let outcome = repo
+            .reflog_expire("refs/heads/main".try_into()?, options)?
+            .expect("log exists");
+        assert_eq!(outcome.num_removed, 2, "c3 isn't reachable from main, but c1 is");
+        assert_eq!(messages(&repo, "main")?, ["commit (initial): c1", "commit: c2"]);
+        Ok(())
+    }
+
+    #[test]
+    fn expire_unreachable_entries_ignores_broken_references() -> crate::Result {
+        let (repo, _tmp) = writable_repo()?;
+        std::fs::write(
+            repo.git_dir().join("refs").join("heads").join("broken"),
+            "0123456789012345678901234567890123456789\n",
+        )?;
+        let options = Options {
+            expire: None,
+            expire_unreachable: now(),
+        };
+        let outcome = repo.reflog_expire("HEAD".try_into()?, options)?.expect("log exists");
+        assert_eq!(outcome.num_removed, 0, "c3 is still reachable from 'keep'");
+        Ok(())
+    }
+
+    #[test]
+    fn delete_entries_by_index_and_entire_logs() -> crate::Result {
+        let (repo, _tmp) = writable_repo()?;
+        let outcome = repo
+            .reflog_delete_entries("refs/heads/main".try_into()?, &[0, 2])?
+            .expect("log exists");
+        assert_eq!(outcome.num_removed, 2);
+        assert_eq!(messages(&repo, "main")?, ["commit (initial): c1", "commit: c3"]);
+
+        assert!(repo.reflog_delete("refs/heads/main".try_into()?)?);
+        assert!(!repo.find_reference("main")?.log_exists());
+        assert!(!repo.reflog_delete("refs/heads/main".try_into()?)?);
+        assert!(repo
+            .reflog_delete_entries("refs/heads/main".try_into()?, &[0])?
+            .is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn expire_options_from_configuration() -> crate::Result {
+        let (mut repo, _tmp) = writable_repo()?;
+        let options = repo.reflog_expire_options()?;
+        let now = gix::date::Time::now_utc().seconds;
+        let day = 24 * 60 * 60;
+        let expire = options.expire.expect("set by default").seconds;
+        assert!((now - 90 * day - expire).abs() < day, "90 days ago by default");
+        let unreachable = options.expire_unreachable.expect("set by default").seconds;
+        assert!((now - 30 * day - unreachable).abs() < day, "30 days ago by default");
+
+        let mut config = repo.config_snapshot_mut();
+        config.set_raw_value(&gix::config::tree::Gc::REFLOG_EXPIRE, "never")?;
+        config.set_raw_value(&gix::config::tree::Gc::REFLOG_EXPIRE_UNREACHABLE, "now")?;
+        drop(config);
+        let options = repo.reflog_expire_options()?;
+        assert_eq!(options.expire, None);
+        assert!(options.expire_unreachable.is_some());
+        Ok(())
+    }
 
     #[test]
     fn message() {
@@ -42,4 +42,84 @@ mod blocking_io {
             Ok(())
         }
     }
+
+    mod remote_helper {
+        use std::sync::atomic::AtomicBool;
+
+        use gix::remote::Direction::Fetch;
+        use serial_test::serial;
+
+        use crate::remote;
+
+        /// Make `git-remote-testing` available in the `PATH`.
+        fn with_testing_helper_in_path() -> crate::Result<gix_testtools::Env<'static>> {
+            let helper_dir = gix::path::realpath("../gix-transport/tests/fixtures/remote-helper")?;
+            let path = std::env::join_paths(
+                std::iter::once(helper_dir).chain(std::env::split_paths(&std::env::var_os("PATH").unwrap_or_default())),
+            )?;
+            Ok(gix_testtools::Env::new().set("PATH", path.into_string().expect("valid UTF-8")))
+        }
+
+        fn open_with_git_env(path: impl Into<std::path::PathBuf>) -> crate::Result<gix::Repository> {
+            Ok(gix::open_opts(
+                path,
+                gix::open::Options::isolated().permissions(gix::open::Permissions {
+                    env: gix::open::permissions::Environment {
+                        git_prefix: gix_sec::Permission::Allow,
+                        ..gix::open::permissions::Environment::all()
+                    },
+                    ..gix::open::Permissions::isolated()
+                }),
+            )?)
+        }
+
+        #[test]
+        #[serial]
+        fn fetch() -> crate::Result {
+            let _env = with_testing_helper_in_path()?;
+            let remote_repo = remote::repo("base");
+            let tmp = gix_testtools::tempfile::TempDir::new()?;
+            gix::init_bare(tmp.path())?;
+            let repo = open_with_git_env(tmp.path())?;
+
+            let url = format!("testing::{}", remote_repo.git_dir().display());
+            let remote = repo
+                .remote_at(url.as_str())?
+                .with_refspecs(Some("refs/heads/main:refs/remotes/origin/main"), Fetch)?;
+            let outcome = remote
+                .connect(Fetch)?
+                .prepare_fetch(gix::progress::Discard, Default::default())?
+                .receive(gix::progress::Discard, &AtomicBool::default())?;
+            assert!(
+                matches!(outcome.status, gix::remote::fetch::Status::Change { .. }),
+                "objects were received through the remote helper"
+            );
+            let main = remote_repo.find_reference("refs/heads/main")?.id().detach();
+            assert_eq!(repo.find_reference("refs/remotes/origin/main")?.id(), main);
+            assert!(repo.has_object(main));
+            Ok(())
+        }
+
+        #[test]
+        #[serial]
+        fn ext_is_denied_and_other_helpers_are_allowed_for_the_user() -> crate::Result {
+            for (env_value, should_allow) in [(None, true), (Some("0"), false)] {
+                let _env = env_value.map(|value| gix_testtools::Env::new().set("GIT_PROTOCOL_FROM_USER", value));
+                let repo = open_with_git_env(remote::repo("base").git_dir())?;
+                let err = repo.remote_at("ext::git %s")?.connect(Fetch).err();
+                assert!(
+                    matches!(err, Some(gix::remote::connect::Error::ProtocolDenied { .. })),
+                    "`ext` is never allowed by default"
+                );
+
+                let err = repo.remote_at("testing::/does/not/matter")?.connect(Fetch).err();
+                assert_eq!(
+                    !matches!(err, Some(gix::remote::connect::Error::ProtocolDenied { .. })),
+                    should_allow,
+                    "Value = {env_value:?}"
+                );
+            }
+            Ok(())
+        }
+    }
 }
@@ -349,6 +349,72 @@ mod blocking_and_async_io {
         Ok(())
     }
 
+    #[maybe_async::test(
+        feature = "blocking-network-client",
+        async(feature = "async-network-client-async-std", async_std::test)
+    )]
+    async fn fetch_with_stateful_v1_negotiation() -> crate::Result {
+        for (algorithm, expected_negotiation_rounds, expected_haves_in_last_round, expected_objects) in [
+            (gix::negotiate::Algorithm::Consecutive, 3, 0, 3),
+            (gix::negotiate::Algorithm::Skipping, 1, 12, 12),
+        ] {
+            for version in [
+                gix::protocol::transport::Protocol::V0,
+                gix::protocol::transport::Protocol::V1,
+            ] {
+                let tmp = gix_testtools::scripted_fixture_writable("make_fetch_negotiation_repos.sh")?;
+                let mut repo = gix::open_opts(tmp.path().join("local"), crate::restricted())?;
+                {
+                    let mut config = repo.config_snapshot_mut();
+                    config.set_value(
+                        &gix::config::tree::Protocol::VERSION,
+                        (version as u8).to_string().as_str(),
+                    )?;
+                    config.set_value(
+                        &gix::config::tree::Fetch::NEGOTIATION_ALGORITHM,
+                        algorithm.to_string().as_str(),
+                    )?;
+                }
+                let daemon = spawn_git_daemon_if_async(tmp.path())?;
+                let remote =
+                    into_daemon_remote_if_async(repo.remote_at(tmp.path().join("remote"))?, daemon.as_ref(), "remote");
+                let changes = remote
+                    .with_refspecs(Some("refs/heads/*:refs/remotes/origin/*"), Fetch)?
+                    .connect(Fetch)
+                    .await?
+                    .prepare_fetch(gix::progress::Discard, Default::default())
+                    .await?
+                    .receive(gix::progress::Discard, &AtomicBool::default())
+                    .await?;
+
+                match changes.status {
+                    Status::Change {
+                        write_pack_bundle,
+                        negotiate,
+                        ..
+                    } => {
+                        assert_eq!(
+                            negotiate.rounds.len(),
+                            expected_negotiation_rounds,
+                            "{algorithm:?}|{version:?}"
+                        );
+                        assert_eq!(
+                            negotiate.rounds.last().expect("at least one round").haves_sent,
+                            expected_haves_in_last_round,
+                            "{algorithm:?}|{version:?}: once the server signals it's ready, we only tell it we are done"
+                        );
+                        assert_eq!(
+                            write_pack_bundle.index.num_objects, expected_objects,
+                            "{algorithm:?}|{version:?}"
+                        );
+                    }
+                    _ => unreachable!("We expect a pack for sure"),
+                }
+            }
+        }
+        Ok(())
+    }
+
     #[maybe_async::test(
         feature = "blocking-network-client",
         async(feature = "async-network-client-async-std", async_std::test)
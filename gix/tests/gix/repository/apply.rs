@@ -0,0 +1,174 @@
+use gix::{
+    apply::{patch, Location, Options},
+    bstr::{BString, ByteSlice},
+};
+
+use crate::util::{named_repo, repo_rw};
+
+fn patch_files(repo: &gix::Repository) -> crate::Result<Vec<patch::File>> {
+    let data = std::fs::read(repo.git_dir().join("change.patch"))?;
+    Ok(patch::parse(&data, Default::default())?)
+}
+
+fn tree_id(repo: &gix::Repository, spec: &str) -> crate::Result<gix::ObjectId> {
+    Ok(repo.rev_parse_single(format!("{spec}^{{tree}}").as_str())?.detach())
+}
+
+#[test]
+fn to_tree_forward_and_in_reverse() -> crate::Result {
+    let repo = named_repo("make_apply_repo.sh")?.with_object_memory();
+    let files = patch_files(&repo)?;
+
+    let outcome = repo.apply_patch(&files, Location::Tree(tree_id(&repo, "base")?), Options::default())?;
+    assert_eq!(
+        outcome.tree_id,
+        Some(tree_id(&repo, "after")?),
+        "the result is the same as the commit the patch was created from"
+    );
+    assert_eq!(outcome.paths, ["exe", "bin/exe", "dir/nested", "file", "new"]);
+    assert!(outcome.conflicts.is_empty());
+
+    let outcome = repo.apply_patch(
+        &files,
+        Location::Tree(tree_id(&repo, "after")?),
+        Options {
+            reverse: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.tree_id, Some(tree_id(&repo, "base")?));
+    Ok(())
+}
+
+#[test]
+fn to_tree_with_three_way_merge() -> crate::Result {
+    let repo = named_repo("make_apply_repo.sh")?.with_object_memory();
+    let files = patch_files(&repo)?;
+    let ours = Location::Tree(tree_id(&repo, "ours")?);
+
+    let err = repo.apply_patch(&files, ours, Options::default()).unwrap_err();
+    assert!(
+        matches!(&err, gix::repository::apply_patch::Error::Apply { path, .. } if path == "file"),
+        "without a three-way merge, the conflicting hunk fails to apply: {err:?}"
+    );
+
+    let outcome = repo.apply_patch(
+        &files,
+        ours,
+        Options {
+            three_way: true,
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(outcome.conflicts, ["file"]);
+    let tree = repo.find_tree(outcome.tree_id.expect("tree location"))?;
+    let entry = tree.find_entry("file").expect("file is present");
+    let merged = entry.object()?.detach().data;
+    assert_eq!(
+        merged.as_bstr(),
+        "1\n2\n3\n4\n<<<<<<< ours\n5 ours\n=======\nfive\n>>>>>>> theirs\n6\n7\n8\n9\n10\n",
+        "the conflict is written with markers"
+    );
+    Ok(())
+}
+
+#[test]
+fn to_worktree_and_index() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_apply_repo.sh")?;
+    let files = patch_files(&repo)?;
+    let workdir = repo.workdir().expect("non-bare").to_owned();
+
+    let outcome = repo.apply_patch(&files, Location::WorktreeAndIndex, Options::default())?;
+    assert!(outcome.tree_id.is_none());
+
+    assert_eq!(std::fs::read(workdir.join("new"))?.as_bstr(), "new\n");
+    assert_eq!(
+        std::fs::read(workdir.join("file"))?.lines().nth(4),
+        Some(b"five".as_slice())
+    );
+    assert!(!workdir.join("exe").exists());
+    assert!(
+        !workdir.join("dir").exists(),
+        "directories left empty after deleting files are removed"
+    );
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(workdir.join("bin/exe"))?.permissions().mode();
+        assert_ne!(mode & 0o100, 0, "the executable bit is retained across renames");
+    }
+
+    let index = repo.open_index()?;
+    let paths: Vec<BString> = index.entries().iter().map(|e| e.path(&index).to_owned()).collect();
+    assert_eq!(paths, ["bin/exe", "file", "link", "new"]);
+    let entry = index.entry_by_path("bin/exe".into()).expect("present");
+    assert_eq!(entry.mode, gix::index::entry::Mode::FILE_EXECUTABLE);
+
+    let err = repo
+        .apply_patch(&files, Location::WorktreeAndIndex, Options::default())
+        .unwrap_err();
+    assert!(
+        matches!(err, gix::repository::apply_patch::Error::NotFound { .. }),
+        "applying the patch again fails as the files to rename are gone: {err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn to_index_only_and_to_worktree_only() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_apply_repo.sh")?;
+    let files = patch_files(&repo)?;
+    let workdir = repo.workdir().expect("non-bare").to_owned();
+
+    repo.apply_patch(&files, Location::Index, Options::default())?;
+    assert!(!workdir.join("new").exists(), "the worktree isn't touched");
+    assert!(workdir.join("exe").exists());
+    let index = repo.open_index()?;
+    assert!(index.entry_by_path("new".into()).is_some());
+    assert!(index.entry_by_path("exe".into()).is_none());
+
+    repo.apply_patch(
+        &files,
+        Location::Index,
+        Options {
+            reverse: true,
+            ..Default::default()
+        },
+    )?;
+    repo.apply_patch(&files, Location::Worktree, Options::default())?;
+    assert!(workdir.join("new").exists());
+    let index = repo.open_index()?;
+    assert!(
+        index.entry_by_path("exe".into()).is_some(),
+        "the index was restored by applying the patch in reverse, and isn't touched when applying to the worktree"
+    );
+    assert!(index.entry_by_path("new".into()).is_none());
+    Ok(())
+}
+
+#[test]
+fn unsafe_paths_are_rejected() -> crate::Result {
+    let repo = named_repo("make_apply_repo.sh")?.with_object_memory();
+    let base = Location::Tree(tree_id(&repo, "base")?);
+
+    let files = patch::parse(
+        b"diff --git a/../escape b/../escape\nnew file mode 100644\n--- /dev/null\n+++ b/../escape\n@@ -0,0 +1 @@\n+x\n",
+        Default::default(),
+    )?;
+    let err = repo.apply_patch(&files, base, Options::default()).unwrap_err();
+    assert!(
+        matches!(err, gix::repository::apply_patch::Error::InvalidPath { .. }),
+        "{err:?}"
+    );
+
+    let files = patch::parse(
+        b"diff --git a/link/file b/link/file\nnew file mode 100644\n--- /dev/null\n+++ b/link/file\n@@ -0,0 +1 @@\n+x\n",
+        Default::default(),
+    )?;
+    let err = repo.apply_patch(&files, base, Options::default()).unwrap_err();
+    assert!(
+        matches!(err, gix::repository::apply_patch::Error::BeyondSymlink { .. }),
+        "files can't be created through symbolic links: {err:?}"
+    );
+    Ok(())
+}
@@ -61,3 +61,64 @@ fn with_options() -> crate::Result {
 
     Ok(())
 }
+
+mod ignore_revs {
+    #[test]
+    fn from_config_and_options() -> crate::Result {
+        let repo = crate::named_repo("make_blame_ignore_revs_repo.sh")?;
+        let head = repo.head_commit()?;
+        let parent = head.parent_ids().next().expect("one parent").detach();
+
+        assert_eq!(
+            repo.blame_ignore_revs()?,
+            [head.id],
+            "the commit is read from `blame.ignoreRevsFile`, comments are skipped"
+        );
+
+        let outcome = repo.blame_file("file.txt".into(), head.id, Default::default())?;
+        assert!(
+            outcome.entries.iter().all(|entry| entry.commit_id == parent),
+            "the reformatted line is attributed to the parent"
+        );
+        assert_eq!(
+            outcome.entries.iter().filter(|entry| entry.ignored).count(),
+            1,
+            "only the reformatted line was passed on by the ignored commit"
+        );
+
+        let outcome = repo.blame_file(
+            "file.txt".into(),
+            head.id,
+            gix::repository::blame_file::Options {
+                ignore_revs: Some(Vec::new()),
+                ..Default::default()
+            },
+        )?;
+        assert!(
+            outcome.entries.iter().any(|entry| entry.commit_id == head.id),
+            "configured revisions are overridden by the options"
+        );
+        Ok(())
+    }
+}
+
+#[test]
+fn worktree() -> crate::Result {
+    let repo = crate::named_repo("make_blame_ignore_revs_repo.sh")?;
+
+    let outcome = repo.blame_file(
+        "file.txt".into(),
+        repo.head_id()?,
+        gix::repository::blame_file::Options {
+            worktree: true,
+            ..Default::default()
+        },
+    )?;
+    let uncommitted: Vec<_> = outcome
+        .entries_with_lines()
+        .filter(|(entry, _)| entry.commit_id.is_null())
+        .map(|(_, lines)| lines)
+        .collect();
+    assert_eq!(uncommitted, [vec![BString::from("fn two() {}\n")]]);
+    Ok(())
+}
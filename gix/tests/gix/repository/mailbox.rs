@@ -0,0 +1,263 @@
+use gix::{
+    bstr::{BString, ByteSlice},
+    mailbox,
+};
+
+use crate::util::{named_repo, repo_rw};
+
+fn series(repo: &gix::Repository) -> crate::Result<Vec<gix::ObjectId>> {
+    let mut commits = repo
+        .rev_walk([repo.rev_parse_single("series")?])
+        .with_hidden([repo.rev_parse_single("base")?])
+        .all()?
+        .map(|info| info.map(|info| info.id))
+        .collect::<Result<Vec<_>, _>>()?;
+    commits.reverse();
+    Ok(commits)
+}
+
+fn mbox(repo: &gix::Repository) -> crate::Result<Vec<u8>> {
+    Ok(std::fs::read(repo.git_dir().join("series.mbox"))?)
+}
+
+#[test]
+fn format_patch_matches_git() -> crate::Result {
+    let repo = named_repo("make_mailbox_repo.sh")?;
+    let patches = repo.format_patch(
+        series(&repo)?,
+        mailbox::format::Options {
+            signature: Some("signature".into()),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(
+        patches.iter().map(|patch| patch.number).collect::<Vec<_>>(),
+        [1, 2, 3],
+        "the empty commit is skipped and doesn't count"
+    );
+    assert_eq!(
+        patches.iter().map(mailbox::Patch::file_name).collect::<Vec<_>>(),
+        [
+            "0001-Make-the-tenth-line-spelled-out-with-mlauts.patch",
+            "0002-Move-the-script-into-its-own-directory-and-make-it-e.patch",
+            "0003-Replace-the-file.patch"
+        ]
+    );
+
+    let actual = patches
+        .iter()
+        .map(|patch| patch.data.clone())
+        .collect::<Vec<_>>()
+        .join(&b"\n"[..]);
+    assert_eq!(actual.as_bstr(), mbox(&repo)?.as_bstr());
+    Ok(())
+}
+
+#[test]
+fn format_patch_range_matches_git() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_mailbox_repo.sh")?;
+    let options = mailbox::format::Options {
+        signature: Some("signature".into()),
+        ..Default::default()
+    };
+    let patches = repo.format_patch_range("base..series", options.clone())?;
+    let actual = patches
+        .iter()
+        .map(|patch| patch.data.clone())
+        .collect::<Vec<_>>()
+        .join(&b"\n"[..]);
+    assert_eq!(actual.as_bstr(), mbox(&repo)?.as_bstr());
+
+    repo.reference(
+        "refs/heads/base",
+        repo.rev_parse_single("series")?,
+        gix::refs::transaction::PreviousValue::Any,
+        "move HEAD to the end of the series",
+    )?;
+    let patches = repo.format_patch_range("series~1", options.clone())?;
+    assert_eq!(
+        patches.iter().map(|patch| patch.subject.clone()).collect::<Vec<_>>(),
+        ["Replace the file"],
+        "a single revision is the start of a range up to `HEAD`"
+    );
+    assert_eq!(patches[0].number, 1);
+
+    let err = repo.format_patch_range("base...series", options).unwrap_err();
+    assert!(
+        matches!(err, gix::repository::format_patch::Error::UnsupportedSpec { .. }),
+        "{err:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn format_patch_single_commit_is_not_numbered() -> crate::Result {
+    let repo = named_repo("make_mailbox_repo.sh")?;
+    let patches = repo.format_patch(
+        Some(repo.rev_parse_single("series")?),
+        mailbox::format::Options {
+            subject_prefix: "PATCH v2".into(),
+            ..Default::default()
+        },
+    )?;
+    assert_eq!(patches.len(), 1);
+    assert!(
+        patches[0].data.contains_str("\nSubject: [PATCH v2] Replace the file\n"),
+        "{}",
+        patches[0].data
+    );
+    assert!(
+        patches[0].data.ends_with(b"+new\n"),
+        "without signature, the patch ends with the diff"
+    );
+    Ok(())
+}
+
+#[test]
+fn format_patch_without_commits_is_empty() -> crate::Result {
+    let repo = named_repo("make_mailbox_repo.sh")?;
+    let patches = repo.format_patch(
+        Vec::<gix::ObjectId>::new(),
+        mailbox::format::Options {
+            start_number: 0,
+            ..Default::default()
+        },
+    )?;
+    assert!(patches.is_empty());
+    Ok(())
+}
+
+#[test]
+fn parse_mbox_of_git() -> crate::Result {
+    let repo = named_repo("make_mailbox_repo.sh")?;
+    let messages = mailbox::parse(&mbox(&repo)?, Default::default())?;
+    assert_eq!(messages.len(), 3);
+
+    assert_eq!(messages[0].author.name, "Jöhn Dœ");
+    assert_eq!(messages[0].author.email, "john@example.com");
+    assert_eq!(messages[0].subject, "Make the tenth line spelled out with ümlauts");
+    assert_eq!(messages[0].body, "It's easier to read.\n\nReally.\n");
+    assert!(messages[0].patch.starts_with(b"---\n file | 2 +-\n"));
+
+    assert_eq!(messages[1].author.name, "Doe, Jane", "quoted names are unquoted");
+    assert_eq!(
+        messages[1].subject,
+        "Move the script into its own directory and make it executable while changing the greeting of the program to something longer",
+        "folded lines are joined"
+    );
+    assert_eq!(messages[1].body, "");
+
+    assert_eq!(
+        messages[2].body, "=?UTF-8?q?not-encoded?= is kept.\n",
+        "only headers are decoded"
+    );
+
+    let messages = mailbox::parse(&mbox(&repo)?, mailbox::parse::Options { keep_subject: true })?;
+    assert_eq!(messages[2].subject, "[PATCH 3/3] Replace the file");
+    Ok(())
+}
+
+#[test]
+fn parse_transfer_encodings_and_inbody_headers() -> crate::Result {
+    let data = b"From: =?ISO-8859-1?Q?J=F6rg?= <joerg@example.com>
+Subject: Re: [PATCH] =?UTF-8?B?w7xiZXI=?=
+ =?UTF-8?Q?_all?=
+Date: Thu, 7 Apr 2005 15:13:13 -0700
+Content-Type: text/plain; charset=ISO-8859-1
+Content-Transfer-Encoding: quoted-printable
+
+From: Other Author <other@example.com>
+
+The body has a very long line that is wrapped by the transfer encodin=
+g, and =E4 non-ASCII character.
+---
+diff --git a/file b/file
+";
+    let messages = mailbox::parse(data, Default::default())?;
+    assert_eq!(messages.len(), 1);
+    let message = &messages[0];
+    assert_eq!(
+        message.author,
+        gix_actor::Identity {
+            name: "Other Author".into(),
+            email: "other@example.com".into()
+        },
+        "headers in the body override those of the email"
+    );
+    assert_eq!(message.subject, "über all");
+    assert_eq!(message.time.map(|time| time.seconds), Some(1112911993));
+    assert_eq!(
+        message.body,
+        "The body has a very long line that is wrapped by the transfer encoding, and ä non-ASCII character.\n"
+    );
+    assert_eq!(message.patch, "---\ndiff --git a/file b/file\n");
+
+    let err = mailbox::parse(b"Subject: no author\n\nbody\n", Default::default()).unwrap_err();
+    assert!(matches!(err, mailbox::parse::Error::MissingAuthor { index: 0 }));
+    Ok(())
+}
+
+#[test]
+fn apply_mailbox_recreates_the_series() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_mailbox_repo.sh")?;
+    let messages = mailbox::parse(&mbox(&repo)?, Default::default())?;
+    let outcome = repo.apply_mailbox(&messages, Default::default())?;
+    assert_eq!(outcome.commits.len(), 3);
+    assert_eq!(repo.head_id()?, outcome.commits[2], "HEAD is advanced");
+
+    let expected_commits = series(&repo)?
+        .into_iter()
+        .map(|id| repo.find_commit(id))
+        .filter(|commit| {
+            commit
+                .as_ref()
+                .map_or(true, |commit| commit.message_raw_sloppy() != "empty\n")
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    for (actual, expected) in outcome.commits.iter().zip(&expected_commits) {
+        let actual = repo.find_commit(*actual)?;
+        assert_eq!(actual.tree_id()?, expected.tree_id()?);
+        assert_eq!(actual.message_raw()?, expected.message_raw()?);
+        assert_eq!(actual.author()?, expected.author()?);
+    }
+
+    let index = repo.index()?;
+    let blob_id = |path: &str| index.entry_by_path(path.into()).map(|entry| entry.id);
+    assert_eq!(blob_id("file"), None, "the index was updated");
+    assert_eq!(
+        blob_id("new file"),
+        Some(repo.rev_parse_single("series:new file")?.detach())
+    );
+    assert_eq!(
+        std::fs::read(repo.workdir().expect("non-bare").join("new file"))?,
+        b"new\n"
+    );
+    assert!(
+        !repo.workdir().expect("non-bare").join("file").exists(),
+        "the worktree was updated"
+    );
+    Ok(())
+}
+
+#[test]
+fn apply_mailbox_fails_on_patches_that_do_not_apply() -> crate::Result {
+    let (repo, _tmp) = repo_rw("make_mailbox_repo.sh")?;
+    let messages = mailbox::parse(&mbox(&repo)?, Default::default())?;
+    let base = repo.head_id()?.detach();
+
+    let err = repo.apply_mailbox(&messages[1..], Default::default()).unwrap_err();
+    assert!(
+        matches!(&err, gix::repository::apply_mailbox::Error::Apply { index: 1, .. }),
+        "the second patch deletes a file with content it doesn't have yet: {err:?}"
+    );
+    assert_ne!(repo.head_id()?, base, "the first patch was committed");
+
+    let mut message = messages[0].clone();
+    message.patch = BString::default();
+    let err = repo.apply_mailbox(&[message], Default::default()).unwrap_err();
+    assert!(matches!(
+        err,
+        gix::repository::apply_mailbox::Error::EmptyPatch { index: 0 }
+    ));
+    Ok(())
+}
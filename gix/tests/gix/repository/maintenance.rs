@@ -0,0 +1,328 @@
+use std::{path::Path, sync::atomic::AtomicBool};
+
+use gix::maintenance::repack;
+use gix_testtools::tempfile::TempDir;
+
+fn fixture() -> crate::Result<(TempDir, gix::Repository)> {
+    let dir = gix_testtools::scripted_fixture_writable("make_maintenance_repos.sh")?;
+    let repo = open(&dir)?;
+    Ok((dir, repo))
+}
+
+/// Open the repository anew so the object database doesn't know about previous changes.
+fn open(dir: &TempDir) -> crate::Result<gix::Repository> {
+    Ok(gix::open_opts(dir.path().join("repo"), crate::restricted())?)
+}
+
+fn id(dir: &TempDir, name: &str) -> crate::Result<gix::ObjectId> {
+    Ok(gix::ObjectId::from_hex(
+        std::fs::read_to_string(dir.path().join(format!("{name}.id")))?
+            .trim()
+            .as_bytes(),
+    )?)
+}
+
+fn packs(repo: &gix::Repository) -> crate::Result<Vec<String>> {
+    let mut out = Vec::new();
+    for entry in std::fs::read_dir(repo.objects.store_ref().path().join("pack"))? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "idx") {
+            out.push(path.file_stem().expect("present").to_string_lossy().into_owned());
+        }
+    }
+    out.sort();
+    Ok(out)
+}
+
+fn loose(repo: &gix::Repository) -> crate::Result<Vec<gix::ObjectId>> {
+    let mut out = gix::odb::loose::Store::at(repo.objects.store_ref().path(), repo.object_hash())
+        .iter()
+        .collect::<Result<Vec<_>, _>>()?;
+    out.sort();
+    Ok(out)
+}
+
+fn is_loose(repo: &gix::Repository, id: gix::ObjectId) -> crate::Result<bool> {
+    Ok(loose(repo)?.contains(&id))
+}
+
+fn has_object(dir: &TempDir, id: gix::ObjectId) -> crate::Result<bool> {
+    Ok(open(dir)?.has_object(id))
+}
+
+fn now() -> gix::date::Time {
+    gix::date::Time::now_utc()
+}
+
+fn kept_pack(dir: &TempDir) -> crate::Result<String> {
+    Ok(format!(
+        "pack-{}",
+        std::fs::read_to_string(dir.path().join("kept.pack-id"))?.trim()
+    ))
+}
+
+fn all_reachable_objects_exist(dir: &TempDir) -> crate::Result {
+    let repo = open(dir)?;
+    for commit in repo.head_id()?.ancestors().all()? {
+        let commit = commit?.object()?;
+        for entry in commit.tree()?.traverse().breadthfirst.files()? {
+            assert!(repo.has_object(entry.oid));
+        }
+    }
+    repo.find_reference("v1")?.peel_to_id()?;
+    Ok(())
+}
+
+#[test]
+fn pack_refs_moves_loose_references_into_packed_refs() -> crate::Result {
+    let (dir, repo) = fixture()?;
+    let main = repo.find_reference("main")?.id().detach();
+    let outcome = repo.pack_refs()?;
+    assert_eq!(outcome.num_packed, 3, "main, other and v1 are packed");
+
+    let refs_dir = repo.git_dir().join("refs");
+    for name in ["heads/main", "heads/other", "tags/v1"] {
+        assert!(!refs_dir.join(name).exists(), "{name} isn't loose anymore");
+    }
+    assert!(repo.git_dir().join("HEAD").is_file(), "symbolic refs stay");
+    assert!(refs_dir.is_dir(), "the refs directory is always kept");
+
+    let repo = open(&dir)?;
+    assert_eq!(repo.find_reference("main")?.id(), main);
+    assert_eq!(repo.head_id()?, main);
+    assert_eq!(repo.pack_refs()?.num_packed, 0, "nothing is left to pack");
+    Ok(())
+}
+
+#[test]
+fn repack_loose_objects_only_leaves_existing_packs_alone() -> crate::Result {
+    let (dir, repo) = fixture()?;
+    let packs_before = packs(&repo)?;
+    let outcome = repo.repack(
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+        repack::Options {
+            mode: repack::Mode::LooseObjects,
+            ..Default::default()
+        },
+    )?;
+    let bundle = outcome.write_pack_bundle.expect("loose objects were packed");
+    assert_eq!(bundle.keep_path, None, "the keep file is removed");
+    assert_eq!(
+        bundle.index.num_objects, 3,
+        "the third commit, its tree and the blob of the file"
+    );
+    assert_eq!(outcome.num_loose_objects_removed, 3);
+    assert!(outcome.removed_packs.is_empty());
+
+    let packs_after = packs(&repo)?;
+    assert_eq!(packs_after.len(), packs_before.len() + 1);
+    assert!(packs_before.iter().all(|pack| packs_after.contains(pack)));
+    assert_eq!(
+        loose(&repo)?,
+        [id(&dir, "unreachable-loose")?],
+        "unreachable loose objects are left to be pruned"
+    );
+    all_reachable_objects_exist(&dir)
+}
+
+#[test]
+fn repack_all_into_one_keeping_unreachable_objects() -> crate::Result {
+    let (dir, repo) = fixture()?;
+    let outcome = repo.repack(&mut gix::progress::Discard, &AtomicBool::default(), Default::default())?;
+    assert_eq!(outcome.removed_packs.len(), 3, "all packs that aren't kept");
+    assert_eq!(outcome.num_unreachable_objects_loosened, 0);
+    assert_eq!(outcome.num_unreachable_objects_dropped, 0);
+    assert!(outcome.write_pack_bundle.is_some());
+
+    let kept = kept_pack(&dir)?;
+    let packs = packs(&repo)?;
+    assert_eq!(packs.len(), 2, "the new pack and the kept one");
+    assert!(packs.contains(&kept));
+    assert_eq!(loose(&repo)?, [id(&dir, "unreachable-loose")?]);
+    assert!(
+        has_object(&dir, id(&dir, "unreachable-packed")?)?,
+        "it was packed again"
+    );
+    assert!(has_object(&dir, id(&dir, "kept")?)?);
+    all_reachable_objects_exist(&dir)?;
+
+    let outcome = open(&dir)?.repack(&mut gix::progress::Discard, &AtomicBool::default(), Default::default())?;
+    assert!(
+        outcome.write_pack_bundle.is_none() && outcome.removed_packs.is_empty(),
+        "nothing changes the second time around"
+    );
+    Ok(())
+}
+
+#[test]
+fn repack_all_into_one_loosens_or_drops_unreachable_objects() -> crate::Result {
+    for expect_loosened in [true, false] {
+        let (dir, repo) = fixture()?;
+        let expire = (!expect_loosened).then(now);
+        let unreachable_packed = id(&dir, "unreachable-packed")?;
+        let outcome = repo.repack(
+            &mut gix::progress::Discard,
+            &AtomicBool::default(),
+            repack::Options {
+                unreachable: repack::Unreachable::Loosen { expire },
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(outcome.num_unreachable_objects_loosened, usize::from(expect_loosened));
+        assert_eq!(outcome.num_unreachable_objects_dropped, usize::from(!expect_loosened));
+        assert_eq!(is_loose(&repo, unreachable_packed)?, expect_loosened);
+        assert_eq!(has_object(&dir, unreachable_packed)?, expect_loosened);
+        assert_eq!(packs(&repo)?.len(), 2);
+        all_reachable_objects_exist(&dir)?;
+    }
+    Ok(())
+}
+
+#[test]
+fn repack_rewrites_existing_multi_pack_index() -> crate::Result {
+    let (dir, repo) = fixture()?;
+    let pack_dir = repo.objects.store_ref().path().join("pack");
+    let index_paths: Vec<_> = packs(&repo)?
+        .into_iter()
+        .map(|name| pack_dir.join(format!("{name}.idx")))
+        .collect();
+    let mut file = std::fs::File::create(pack_dir.join("multi-pack-index"))?;
+    gix::odb::pack::multi_index::File::write_from_index_paths(
+        index_paths,
+        &mut file,
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+        gix::odb::pack::multi_index::write::Options {
+            object_hash: repo.object_hash(),
+        },
+    )?;
+    drop(file);
+
+    repo.repack(&mut gix::progress::Discard, &AtomicBool::default(), Default::default())?;
+    let multi_index = gix::odb::pack::multi_index::File::at(pack_dir.join("multi-pack-index"))?;
+    let mut names: Vec<_> = multi_index
+        .index_names()
+        .iter()
+        .map(|name| {
+            Path::new(name)
+                .file_stem()
+                .expect("present")
+                .to_string_lossy()
+                .into_owned()
+        })
+        .collect();
+    names.sort();
+    assert_eq!(names, packs(&repo)?, "it refers to exactly the remaining packs");
+    all_reachable_objects_exist(&dir)
+}
+
+#[test]
+fn prune_removes_unreachable_and_packed_loose_objects() -> crate::Result {
+    let (dir, repo) = fixture()?;
+    let in_the_past = gix::date::Time::new(0, 0);
+    let outcome = repo.prune(
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+        gix::maintenance::prune::Options {
+            expire: Some(in_the_past),
+        },
+    )?;
+    assert_eq!(
+        outcome,
+        gix::maintenance::prune::Outcome::default(),
+        "nothing is old enough, and no loose object is also packed"
+    );
+
+    let outcome = repo.prune(
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+        gix::maintenance::prune::Options { expire: None },
+    )?;
+    assert_eq!(outcome.num_unreachable_removed, 1);
+    assert_eq!(outcome.num_packed_removed, 0);
+    assert!(!is_loose(&repo, id(&dir, "unreachable-loose")?)?);
+    assert_eq!(loose(&repo)?.len(), 3, "reachable loose objects remain");
+    assert!(
+        has_object(&dir, id(&dir, "unreachable-packed")?)?,
+        "packed objects are never pruned"
+    );
+    all_reachable_objects_exist(&dir)
+}
+
+#[test]
+fn gc_in_auto_mode_only_runs_when_needed() -> crate::Result {
+    let (_dir, repo) = fixture()?;
+    let mut options = repo.gc_options()?;
+    assert!(options.pack_refs);
+    assert!(options.reflog_expire.expire.is_some() && options.prune_expire.is_some());
+    options.auto = true;
+    assert!(
+        repo.gc(&mut gix::progress::Discard, &AtomicBool::default(), options)?
+            .is_none(),
+        "there isn't enough to do in such a small repository"
+    );
+    options.auto_pack_limit = 1;
+    assert!(
+        repo.gc(&mut gix::progress::Discard, &AtomicBool::default(), options)?
+            .is_some(),
+        "but too many packs trigger a gc"
+    );
+    Ok(())
+}
+
+#[test]
+fn gc_packs_refs_expires_reflogs_repacks_and_prunes() -> crate::Result {
+    let (dir, repo) = fixture()?;
+    let options = gix::maintenance::gc::Options {
+        prune_expire: Some(now()),
+        ..repo.gc_options()?
+    };
+    let outcome = repo
+        .gc(&mut gix::progress::Discard, &AtomicBool::default(), options)?
+        .expect("not in auto-mode");
+    assert_eq!(outcome.pack_refs.map(|outcome| outcome.num_packed), Some(3));
+    assert_eq!(
+        outcome.num_reflog_entries_removed, 7,
+        "all reflog entries are from the fixed date of the fixture, which is long ago"
+    );
+    assert_eq!(outcome.repack.num_unreachable_objects_dropped, 1);
+    assert_eq!(outcome.prune.map(|outcome| outcome.num_unreachable_removed), Some(1));
+    assert!(!repo.common_dir().join("gc.pid.lock").exists(), "the lock is released");
+
+    assert!(loose(&repo)?.is_empty());
+    assert_eq!(packs(&repo)?.len(), 2, "the new pack and the kept pack");
+    assert!(!has_object(&dir, id(&dir, "unreachable-loose")?)?);
+    assert!(!has_object(&dir, id(&dir, "unreachable-packed")?)?);
+    all_reachable_objects_exist(&dir)
+}
+
+#[test]
+fn gc_options_from_configuration() -> crate::Result {
+    let (_dir, mut repo) = fixture()?;
+    let mut config = repo.config_snapshot_mut();
+    config.set_raw_value(&gix::config::tree::Gc::PRUNE_EXPIRE, "never")?;
+    config.set_raw_value(&gix::config::tree::Gc::REFLOG_EXPIRE, "2005-04-07T22:13:13")?;
+    config.set_raw_value(&gix::config::tree::Gc::PACK_REFS, "notbare")?;
+    config.set_raw_value(&gix::config::tree::Gc::AUTO, "0")?;
+    config.set_raw_value(&gix::config::tree::Gc::BIG_PACK_THRESHOLD, "1000")?;
+    drop(config);
+
+    let options = repo.gc_options()?;
+    assert_eq!(options.prune_expire, None);
+    assert_eq!(options.reflog_expire.expire.map(|time| time.seconds), Some(1112911993));
+    assert!(options.pack_refs, "the repository isn't bare");
+    assert_eq!(options.auto_threshold, 0);
+    assert_eq!(options.auto_pack_limit, 50, "the default");
+    assert_eq!(options.big_pack_threshold, Some(1000));
+    assert!(
+        repo.gc(
+            &mut gix::progress::Discard,
+            &AtomicBool::default(),
+            gix::maintenance::gc::Options { auto: true, ..options }
+        )?
+        .is_none(),
+        "auto-mode is disabled"
+    );
+    Ok(())
+}
@@ -11,3 +11,447 @@ fn tree_merge_options() -> crate::Result {
     );
     Ok(())
 }
+
+mod merge_commits {
+    use gix::merge::{commit::Strategy, tree::TreatAsUnresolved};
+
+    use crate::util::named_repo;
+
+    #[test]
+    fn ignore_whitespace_changes() -> crate::Result {
+        let repo = named_repo("make_merge_strategies_repo.sh")?.with_object_memory();
+        let ours = repo.rev_parse_single("main")?;
+        let theirs = repo.rev_parse_single("theirs-whitespace")?;
+
+        let options = repo.tree_merge_options()?;
+        let out = repo.merge_commits(ours, theirs, Default::default(), options.clone().into())?;
+        assert!(
+            out.tree_merge.has_unresolved_conflicts(TreatAsUnresolved::git()),
+            "whitespace changes conflict by default"
+        );
+
+        let options = options.with_whitespace(gix::diff::blob::whitespace::Mode::IgnoreChange);
+        let mut out = repo.merge_commits(ours, theirs, Default::default(), options.into())?;
+        assert!(!out.tree_merge.has_unresolved_conflicts(TreatAsUnresolved::git()));
+        assert_eq!(
+            out.tree_merge.tree.write()?,
+            repo.rev_parse_single("merged-ignore-space-change^{tree}")?,
+            "the result is the same as with `git merge -X ignore-space-change`"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn ours_strategy() -> crate::Result {
+        let repo = named_repo("make_merge_strategies_repo.sh")?.with_object_memory();
+        let ours = repo.rev_parse_single("main")?;
+        let options: gix::merge::commit::Options = repo.tree_merge_options()?.into();
+        let mut out = repo.merge_commits(
+            ours,
+            repo.rev_parse_single("theirs-whitespace")?,
+            Default::default(),
+            options.with_strategy(Strategy::Ours),
+        )?;
+        assert!(out.tree_merge.conflicts.is_empty());
+        assert_eq!(out.tree_merge.tree.write()?, repo.rev_parse_single("main^{tree}")?);
+        Ok(())
+    }
+}
+
+#[test]
+fn merge_commits_octopus() -> crate::Result {
+    let repo = named_repo("make_merge_strategies_repo.sh")?.with_object_memory();
+    let ours = repo.rev_parse_single("main")?;
+    let theirs = ["A", "B", "C"]
+        .into_iter()
+        .map(|name| repo.rev_parse_single(name).map(gix::Id::detach))
+        .collect::<Result<Vec<_>, _>>()?;
+    let out = repo.merge_commits_octopus(
+        ours,
+        theirs.iter().copied(),
+        Default::default(),
+        repo.tree_merge_options()?.into(),
+    )?;
+    assert_eq!(
+        out.tree_id,
+        repo.rev_parse_single("merged-octopus^{tree}")?,
+        "the result is the same as with `git merge A B C`"
+    );
+    assert_eq!(
+        out.parents,
+        [ours.detach()].into_iter().chain(theirs).collect::<Vec<_>>(),
+        "all commits are parents of the merge commit"
+    );
+    assert!(out.conflicts.is_empty());
+    Ok(())
+}
+
+#[cfg(feature = "worktree-mutation")]
+mod merge_into_worktree {
+    use gix::{
+        bstr::{BString, ByteSlice},
+        repository::merge_into_worktree::Error,
+    };
+
+    use crate::util::repo_rw;
+
+    fn options(repo: &gix::Repository) -> crate::Result<gix::merge::worktree::Options> {
+        Ok(repo.tree_merge_options()?.into())
+    }
+
+    fn stages(repo: &gix::Repository, path: &str) -> crate::Result<Vec<u32>> {
+        let index = repo.open_index()?;
+        Ok(index
+            .entries()
+            .iter()
+            .filter(|entry| entry.path(&index) == path)
+            .map(gix::index::Entry::stage_raw)
+            .collect())
+    }
+
+    #[test]
+    fn clean_merge_then_finish() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_merge_worktree_repo.sh")?;
+        let workdir = repo.workdir().expect("non-bare").to_owned();
+        let head = repo.head_id()?.detach();
+        let theirs = repo.rev_parse_single("theirs-clean")?.detach();
+
+        let outcome = repo.merge_into_worktree(theirs, Default::default(), options(&repo)?)?;
+        assert!(outcome.unresolved.is_empty());
+        assert_eq!(repo.state(), Some(gix::state::InProgress::Merge));
+        assert_eq!(std::fs::read(workdir.join("clean"))?.as_bstr(), "one\n2\n3\n4\nfive\n");
+        assert_eq!(std::fs::read(workdir.join("dir/new"))?.as_bstr(), "new\n");
+        assert!(!workdir.join("deleted").exists());
+        assert_eq!(
+            std::fs::read_to_string(repo.git_dir().join("MERGE_HEAD"))?,
+            format!("{theirs}\n")
+        );
+        assert_eq!(repo.find_reference("ORIG_HEAD")?.id(), head);
+        assert!(
+            repo.try_find_reference("AUTO_MERGE")?.is_none(),
+            "it's only written if there are conflicts"
+        );
+        assert_eq!(stages(&repo, "new")?, [0]);
+
+        let commit_id = repo.finish_merge(None)?;
+        let commit = commit_id.object()?.into_commit();
+        assert_eq!(commit.parent_ids().collect::<Vec<_>>(), [head, theirs]);
+        assert_eq!(commit.message_raw()?, format!("Merge commit '{theirs}'\n").as_str());
+        assert_eq!(
+            commit.tree_id()?,
+            repo.rev_parse_single("merged-by-git^{tree}")?,
+            "the result is the same as with `git merge`"
+        );
+        assert_eq!(repo.head_id()?, commit_id);
+        assert_eq!(repo.state(), None, "the merge state is removed");
+        assert!(!repo.git_dir().join("MERGE_MSG").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn conflicts_are_written_with_markers_and_stages() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_merge_worktree_repo.sh")?;
+        let workdir = repo.workdir().expect("non-bare").to_owned();
+        let theirs = repo.rev_parse_single("theirs")?.detach();
+
+        let outcome = repo.merge_into_worktree(theirs, Default::default(), options(&repo)?)?;
+        assert_eq!(outcome.unresolved, ["conflict"]);
+        assert_eq!(stages(&repo, "conflict")?, [1, 2, 3]);
+        assert_eq!(stages(&repo, "clean")?, [0], "the other file merged cleanly");
+        let conflict = std::fs::read(workdir.join("conflict"))?;
+        assert!(
+            conflict.starts_with(b"<<<<<<< HEAD\nours\n=======\ntheirs\n>>>>>>> "),
+            "{:?}",
+            conflict.as_bstr()
+        );
+        assert_eq!(repo.find_reference("AUTO_MERGE")?.id(), outcome.tree_id);
+        assert_eq!(
+            std::fs::read_to_string(repo.git_dir().join("MERGE_MSG"))?,
+            format!("Merge commit '{theirs}'\n\n# Conflicts:\n#\tconflict\n")
+        );
+
+        let err = repo.finish_merge(None).unwrap_err();
+        assert!(
+            matches!(&err, gix::repository::finish_merge::Error::Unmerged { paths } if paths == &["conflict"]),
+            "{err:?}"
+        );
+        let err = repo
+            .merge_into_worktree(theirs, Default::default(), options(&repo)?)
+            .unwrap_err();
+        assert!(matches!(err, Error::InProgress { .. }), "{err:?}");
+
+        let mut index = repo.open_index()?;
+        index.remove_entries(|_, path, _| path == "conflict");
+        let resolved = repo.write_blob("resolved\n")?.detach();
+        index.dangerously_push_entry(
+            Default::default(),
+            resolved,
+            gix::index::entry::Flags::empty(),
+            gix::index::entry::Mode::FILE,
+            "conflict".into(),
+        );
+        index.sort_entries();
+        index.write(Default::default())?;
+
+        let commit_id = repo.finish_merge(Some("resolved\n"))?;
+        let commit = commit_id.object()?.into_commit();
+        assert_eq!(commit.message_raw()?, "resolved\n");
+        assert_eq!(
+            commit
+                .tree()?
+                .lookup_entry_by_path("conflict")?
+                .expect("present")
+                .object_id(),
+            resolved
+        );
+        assert!(repo.try_find_reference("AUTO_MERGE")?.is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn abort_restores_head() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_merge_worktree_repo.sh")?;
+        let workdir = repo.workdir().expect("non-bare").to_owned();
+        let head_tree = repo.head_tree_id()?.detach();
+        std::fs::write(workdir.join("unchanged"), "local change\n")?;
+
+        repo.merge_into_worktree(repo.rev_parse_single("theirs")?, Default::default(), options(&repo)?)?;
+        repo.abort_merge()?;
+
+        assert_eq!(repo.state(), None);
+        assert!(repo.try_find_reference("AUTO_MERGE")?.is_none());
+        assert_eq!(std::fs::read(workdir.join("conflict"))?.as_bstr(), "ours\n");
+        assert_eq!(std::fs::read(workdir.join("clean"))?.as_bstr(), "one\n2\n3\n4\n5\n");
+        assert!(!workdir.join("new").exists());
+        assert_eq!(
+            std::fs::read(workdir.join("unchanged"))?.as_bstr(),
+            "local change\n",
+            "unrelated local changes are kept"
+        );
+        let index = repo.open_index()?;
+        let head_index = repo.index_from_tree(&head_tree)?;
+        let entries = |index: &gix::index::State| -> Vec<(BString, gix::ObjectId)> {
+            index
+                .entries()
+                .iter()
+                .map(|entry| (entry.path(index).to_owned(), entry.id))
+                .collect()
+        };
+        assert_eq!(entries(&index), entries(&head_index));
+
+        let err = repo.abort_merge().unwrap_err();
+        assert!(
+            matches!(err, gix::repository::abort_merge::Error::NoMergeInProgress),
+            "{err:?}"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn local_changes_prevent_the_merge() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_merge_worktree_repo.sh")?;
+        let workdir = repo.workdir().expect("non-bare").to_owned();
+        let theirs = repo.rev_parse_single("theirs")?.detach();
+
+        std::fs::write(workdir.join("clean"), "local change\n")?;
+        std::fs::write(workdir.join("new"), "untracked\n")?;
+        let err = repo
+            .merge_into_worktree(theirs, Default::default(), options(&repo)?)
+            .unwrap_err();
+        assert!(
+            matches!(&err, Error::LocalChanges { paths } if paths == &["clean", "new"]),
+            "{err:?}"
+        );
+        assert_eq!(repo.state(), None, "nothing was changed");
+        assert_eq!(std::fs::read(workdir.join("clean"))?.as_bstr(), "local change\n");
+
+        let err = repo
+            .merge_into_worktree(repo.rev_parse_single("main~1")?, Default::default(), options(&repo)?)
+            .unwrap_err();
+        assert!(matches!(err, Error::AlreadyUpToDate { .. }), "{err:?}");
+        Ok(())
+    }
+
+    #[test]
+    fn staged_changes_prevent_the_merge() -> crate::Result {
+        let (repo, _tmp) = repo_rw("make_merge_worktree_repo.sh")?;
+        let mut index = repo.open_index()?;
+        let id = repo.write_blob("staged\n")?.detach();
+        index
+            .entry_mut_by_path_and_stage("unchanged".into(), gix::index::entry::Stage::Unconflicted)
+            .expect("present")
+            .id = id;
+        index.write(Default::default())?;
+
+        let err = repo
+            .merge_into_worktree(
+                repo.rev_parse_single("theirs-clean")?,
+                Default::default(),
+                options(&repo)?,
+            )
+            .unwrap_err();
+        assert!(
+            matches!(&err, Error::LocalChanges { paths } if paths == &["unchanged"]),
+            "{err:?}"
+        );
+        Ok(())
+    }
+
+    mod rerere {
+        use std::time::{Duration, SystemTime};
+
+        use gix::{bstr::ByteSlice, merge::rerere::merge_rr};
+
+        use super::{options, stages};
+        use crate::util::repo_rw;
+
+        fn resolve(repo: &gix::Repository, path: &str, content: &str) -> crate::Result {
+            std::fs::write(repo.workdir().expect("non-bare").join(path), content)?;
+            let mut index = repo.open_index()?;
+            index.remove_entries(|_, entry_path, _| entry_path == path);
+            index.dangerously_push_entry(
+                Default::default(),
+                repo.write_blob(content)?.detach(),
+                gix::index::entry::Flags::empty(),
+                gix::index::entry::Mode::FILE,
+                path.into(),
+            );
+            index.sort_entries();
+            index.write(Default::default())?;
+            Ok(())
+        }
+
+        fn merge_rr(repo: &gix::Repository) -> crate::Result<Vec<merge_rr::Entry>> {
+            Ok(merge_rr::decode(&std::fs::read(repo.git_dir().join("MERGE_RR"))?)?)
+        }
+
+        #[test]
+        fn resolutions_are_recorded_replayed_and_forgotten() -> crate::Result {
+            let (repo, _tmp) = repo_rw("make_merge_worktree_repo.sh")?;
+            assert!(
+                repo.rerere_store()?.is_none(),
+                "disabled without configuration or 'rr-cache'"
+            );
+            std::fs::create_dir(repo.git_dir().join("rr-cache"))?;
+            let store = repo.rerere_store()?.expect("enabled as the directory exists");
+            let theirs = repo.rev_parse_single("theirs")?.detach();
+
+            let outcome = repo.merge_into_worktree(theirs, Default::default(), options(&repo)?)?;
+            assert_eq!(outcome.unresolved, ["conflict"]);
+            let entries = merge_rr(&repo)?;
+            assert_eq!(entries.len(), 1);
+            assert_eq!(entries[0].path, "conflict");
+            let id = entries[0].id;
+            assert!(store.preimage_path(&id).is_file());
+            assert!(!store.has_resolution(&id));
+
+            resolve(&repo, "conflict", "resolved\n")?;
+            repo.finish_merge(Some("resolved\n"))?;
+            assert!(
+                store.has_resolution(&id),
+                "the resolution is recorded when finishing the merge"
+            );
+            assert!(!repo.git_dir().join("MERGE_RR").exists());
+
+            let (mut repo, _tmp) = repo_rw("make_merge_worktree_repo.sh")?;
+            let workdir = repo.workdir().expect("non-bare").to_owned();
+            copy_dir(store.path(), &repo.git_dir().join("rr-cache"))?;
+            let outcome = repo.merge_into_worktree(theirs, Default::default(), options(&repo)?)?;
+            assert!(outcome.unresolved.is_empty(), "the recorded resolution was replayed");
+            assert_eq!(std::fs::read(workdir.join("conflict"))?.as_bstr(), "resolved\n");
+            assert_eq!(stages(&repo, "conflict")?, [0]);
+            repo.abort_merge()?;
+
+            repo.config_snapshot_mut()
+                .set_value(&gix::config::tree::Rerere::ENABLED, "false")?;
+            assert!(repo.rerere_store()?.is_none(), "configuration takes precedence");
+            let outcome = repo.merge_into_worktree(theirs, Default::default(), options(&repo)?)?;
+            assert_eq!(outcome.unresolved, ["conflict"]);
+            let err = repo.rerere_forget("conflict".into()).unwrap_err();
+            assert!(
+                matches!(err, gix::repository::rerere_forget::Error::Disabled),
+                "{err:?}"
+            );
+
+            repo.config_snapshot_mut()
+                .set_value(&gix::config::tree::Rerere::ENABLED, "true")?;
+            let store = repo.rerere_store()?.expect("enabled");
+            let forgotten = repo.rerere_forget("conflict".into())?;
+            assert_eq!(forgotten, Some(id));
+            assert!(!store.has_resolution(&id));
+            assert_eq!(
+                merge_rr(&repo)?,
+                [merge_rr::Entry {
+                    id,
+                    path: "conflict".into()
+                }],
+                "the path is recorded for its new resolution to be recorded"
+            );
+            assert_eq!(repo.rerere_forget("conflict".into())?, None, "nothing left to forget");
+            let err = repo.rerere_forget("clean".into()).unwrap_err();
+            assert!(
+                matches!(err, gix::repository::rerere_forget::Error::NotUnmerged { .. }),
+                "{err:?}"
+            );
+
+            resolve(&repo, "conflict", "resolved differently\n")?;
+            repo.finish_merge(None)?;
+            assert_eq!(
+                std::fs::read(store.postimage_path(&id))?.as_bstr(),
+                "resolved differently\n"
+            );
+            Ok(())
+        }
+
+        #[test]
+        fn gc_removes_old_entries() -> crate::Result {
+            let (mut repo, _tmp) = repo_rw("make_merge_worktree_repo.sh")?;
+            assert_eq!(repo.rerere_gc()?, None, "rerere is disabled");
+            repo.config_snapshot_mut()
+                .set_value(&gix::config::tree::Rerere::ENABLED, "true")?;
+            let store = repo.rerere_store()?.expect("enabled");
+            let resolved = store
+                .record_conflict(b"<<<<<<< a\nb\n=======\nc\n>>>>>>> d\n", 7)?
+                .expect("conflict");
+            store.record_resolution(&resolved, b"bc\n")?;
+            let unresolved = store
+                .record_conflict(b"<<<<<<< a\nx\n=======\ny\n>>>>>>> d\n", 7)?
+                .expect("conflict");
+
+            assert_eq!(repo.rerere_gc()?, Some(0), "everything is recent");
+
+            let days_ago = |days: u64| SystemTime::now() - Duration::from_secs(days * 24 * 60 * 60);
+            set_modified(&store.postimage_path(&resolved), days_ago(30))?;
+            set_modified(&store.preimage_path(&unresolved), days_ago(30))?;
+            assert_eq!(
+                repo.rerere_gc()?,
+                Some(1),
+                "unresolved conflicts expire after 15 days by default"
+            );
+            assert!(store.variants(&unresolved.hash)?.is_empty());
+
+            repo.config_snapshot_mut()
+                .set_value(&gix::config::tree::Rerere::RESOLVED, "10")?;
+            assert_eq!(repo.rerere_gc()?, Some(1), "the configured expiry is used");
+            assert!(store.variants(&resolved.hash)?.is_empty());
+            Ok(())
+        }
+
+        fn set_modified(path: &std::path::Path, time: SystemTime) -> std::io::Result<()> {
+            std::fs::File::options().append(true).open(path)?.set_modified(time)
+        }
+
+        fn copy_dir(from: &std::path::Path, to: &std::path::Path) -> std::io::Result<()> {
+            std::fs::create_dir_all(to)?;
+            for entry in std::fs::read_dir(from)? {
+                let entry = entry?;
+                if entry.file_type()?.is_dir() {
+                    copy_dir(&entry.path(), &to.join(entry.file_name()))?;
+                } else {
+                    std::fs::copy(entry.path(), to.join(entry.file_name()))?;
+                }
+            }
+            Ok(())
+        }
+    }
+}
@@ -1,5 +1,7 @@
 use gix::Repository;
 
+#[cfg(feature = "apply")]
+mod apply;
 #[cfg(feature = "blame")]
 mod blame;
 mod config;
@@ -7,14 +9,24 @@ mod config;
 mod excludes;
 #[cfg(feature = "attributes")]
 mod filter;
+#[cfg(feature = "mailbox")]
+mod mailbox;
+#[cfg(feature = "maintenance")]
+mod maintenance;
 #[cfg(feature = "merge")]
 mod merge;
 mod object;
 mod open;
 #[cfg(feature = "attributes")]
 mod pathspec;
+#[cfg(feature = "revision")]
+mod pretty;
+#[cfg(feature = "receive-pack")]
+mod receive_pack;
 mod reference;
 mod remote;
+#[cfg(feature = "sha256")]
+mod sha256;
 mod shallow;
 mod state;
 #[cfg(feature = "attributes")]
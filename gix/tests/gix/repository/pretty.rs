@@ -0,0 +1,242 @@
+use gix::{
+    bstr::ByteSlice,
+    pretty::{DateFormat, Format, Options},
+    revision::walk::Sorting,
+};
+
+use crate::util::named_repo;
+
+#[test]
+fn placeholders_match_git() -> crate::Result {
+    for (name, template) in [
+        ("format-hashes", "%H|%h|%T|%t|%P|%p"),
+        ("format-dates", "%ad|%aD|%ai|%aI|%at|%as|%cd|%cD|%ci|%cI|%ct|%cs"),
+        ("format-message", "%s|%f|%b|%B|%e|%m"),
+        (
+            "format-trailers",
+            "[%(trailers)][%(trailers:only,unfold)][%(trailers:key=Signed-off-by,valueonly)][%(trailers:separator=%x2C )]",
+        ),
+        ("format-decorations", "%h%d|%D"),
+        ("format-special", "%%|%x41|%n|%+s|%-b|% h|%Z"),
+        (
+            "format-padding",
+            "%<(10)%h|%>(12)%s|%><(9)%an|%<(8,trunc)%s|%<(8,ltrunc)%s|%<(8,mtrunc)%s|%<|(40)%an|%>>(3)%ae",
+        ),
+        (
+            "format-no-colors",
+            "%Cred%h%Creset %C(bold blue)%an%Creset %C(auto)%d%C(reset) %Cgreen%s",
+        ),
+    ] {
+        assert_matches_baseline(name, format(template))?;
+    }
+    if cfg!(feature = "mailmap") {
+        assert_matches_baseline(
+            "format-idents",
+            format("%an|%ae|%al|%aN|%aE|%aL|%cn|%ce|%cl|%cN|%cE|%cL"),
+        )?;
+    }
+    Ok(())
+}
+
+#[test]
+fn separator_and_colors_match_git() -> crate::Result {
+    let repo = named_repo("make_pretty_repo.sh")?;
+    assert_matches_baseline(
+        "format-separator",
+        Options {
+            format: repo.pretty_format("format:%h %s".into())?,
+            ..Default::default()
+        },
+    )?;
+    assert_matches_baseline(
+        "format-colors",
+        Options {
+            color: true,
+            ..format("%Cred%h%Creset %C(bold blue)%an%Creset %C(auto)%d%C(reset) %Cgreen%s")
+        },
+    )
+}
+
+#[test]
+#[cfg(feature = "mailmap")]
+fn presets_match_git() -> crate::Result {
+    for (name, format) in [
+        ("oneline", Format::Oneline),
+        ("short", Format::Short),
+        ("medium", Format::Medium),
+        ("full", Format::Full),
+        ("fuller", Format::Fuller),
+        ("raw", Format::Raw),
+    ] {
+        assert_matches_baseline(name, preset(format))?;
+    }
+    assert_matches_baseline(
+        "medium-decorated",
+        Options {
+            decorate: true,
+            ..preset(Format::Medium)
+        },
+    )?;
+    assert_matches_baseline(
+        "abbreviated-oneline-decorated",
+        Options {
+            abbrev_commit: true,
+            decorate: true,
+            ..preset(Format::Oneline)
+        },
+    )?;
+    assert_matches_baseline(
+        "no-mailmap",
+        Options {
+            mailmap: false,
+            ..preset(Format::Medium)
+        },
+    )
+}
+
+#[test]
+#[cfg(unix)]
+fn signature_placeholders_match_git() -> crate::Result {
+    let mut repo = named_repo("make_pretty_repo.sh")?;
+    let gpg = repo.workdir().expect("non-bare").join("fake-gpg");
+    repo.config_snapshot_mut()
+        .set_value(&gix::config::tree::Gpg::PROGRAM, gpg.to_str().expect("valid UTF-8"))?;
+    for (name, template, tip) in [
+        ("format-signatures", "%G?|%GS|%GK|%GF|%GP|%GG", "signed"),
+        ("format-signature-trust", "%GT", "signed~3"),
+    ] {
+        let mut printer = repo.pretty_printer(format(template))?;
+        let mut actual = Vec::new();
+        let tip = repo.rev_parse_single(tip)?;
+        for info in repo.rev_walk([tip]).with_hidden([repo.head_id()?]).all()? {
+            let info = info?;
+            printer.write_commit(&info.object()?, &info.parent_ids, &mut actual)?;
+        }
+        let expected = std::fs::read(repo.workdir().expect("non-bare").join(format!("baseline.{name}")))?;
+        assert_eq!(actual.as_bstr(), expected.as_bstr(), "{name}");
+    }
+    Ok(())
+}
+
+#[test]
+fn date_formats_match_git() -> crate::Result {
+    for (name, date) in [
+        ("date-iso", DateFormat::Iso8601),
+        ("date-iso-strict", DateFormat::Iso8601Strict),
+        ("date-rfc", DateFormat::Rfc2822),
+        ("date-short", DateFormat::Short),
+        ("date-raw", DateFormat::Raw),
+        ("date-unix", DateFormat::Unix),
+        ("date-custom", DateFormat::Custom("%Y/%m/%d %H".into())),
+    ] {
+        assert_matches_baseline(
+            name,
+            Options {
+                date: date.into(),
+                ..format("%ad")
+            },
+        )?;
+    }
+    Ok(())
+}
+
+#[test]
+#[cfg(feature = "mailmap")]
+fn graphs_match_git() -> crate::Result {
+    assert_matches_baseline(
+        "graph-oneline",
+        Options {
+            abbrev_commit: true,
+            graph: true,
+            ..preset(Format::Oneline)
+        },
+    )?;
+    assert_matches_baseline(
+        "graph-medium",
+        Options {
+            graph: true,
+            ..preset(Format::Medium)
+        },
+    )?;
+    assert_matches_baseline(
+        "graph-format",
+        Options {
+            graph: true,
+            ..format("%h%n%s")
+        },
+    )
+}
+
+#[test]
+fn pretty_format_resolves_presets_and_aliases() -> crate::Result {
+    let mut repo = named_repo("make_pretty_repo.sh")?;
+    {
+        let mut config = repo.config_snapshot_mut();
+        config.set_raw_value(&"pretty.ref", "%h (%s)")?;
+        config.set_raw_value(&"pretty.full-ref", "ref")?;
+        config.set_raw_value(&"pretty.medium", "%h")?;
+        config.set_raw_value(&"pretty.loop", "loop")?;
+    }
+    assert_eq!(
+        repo.pretty_format("ONE".into())?,
+        Format::Oneline,
+        "prefixes of presets"
+    );
+    assert_eq!(
+        repo.pretty_format("full".into())?,
+        Format::Full,
+        "presets win over aliases"
+    );
+    assert_eq!(
+        repo.pretty_format("medium".into())?,
+        Format::Medium,
+        "presets can't be redefined"
+    );
+    assert_eq!(
+        repo.pretty_format("full-".into())?,
+        repo.pretty_format("tformat:%h (%s)".into())?,
+        "aliases can refer to other aliases"
+    );
+    assert!(matches!(
+        repo.pretty_format("loop".into()),
+        Err(gix::pretty::format::Error::AliasLoop { .. })
+    ));
+    assert!(matches!(
+        repo.pretty_format("unknown".into()),
+        Err(gix::pretty::format::Error::Unknown { .. })
+    ));
+    Ok(())
+}
+
+fn format(template: &str) -> Options {
+    Options {
+        format: Format::from_bytes(template.into()).expect("valid"),
+        ..Default::default()
+    }
+}
+
+fn preset(format: Format) -> Options {
+    Options {
+        format,
+        mailmap: true,
+        ..Default::default()
+    }
+}
+
+fn assert_matches_baseline(name: &str, options: Options) -> crate::Result {
+    let repo = named_repo("make_pretty_repo.sh")?;
+    let sorting = if options.graph {
+        Sorting::TopoOrder
+    } else {
+        Sorting::ByCommitTime(Default::default())
+    };
+    let mut printer = repo.pretty_printer(options)?;
+    let mut actual = Vec::new();
+    for info in repo.rev_walk([repo.head_id()?]).sorting(sorting).all()? {
+        let info = info?;
+        printer.write_commit(&info.object()?, &info.parent_ids, &mut actual)?;
+    }
+    let expected = std::fs::read(repo.workdir().expect("non-bare").join(format!("baseline.{name}")))?;
+    assert_eq!(actual.as_bstr(), expected.as_bstr(), "{name}");
+    Ok(())
+}
@@ -0,0 +1,429 @@
+use std::{path::Path, sync::atomic::AtomicBool};
+
+use gix::{
+    bstr::{BString, ByteSlice},
+    packetline::{blocking_io::StreamingPeekableIter, read::ProgressAction, PacketLineRef},
+    receive_pack::Status,
+};
+use gix_testtools::tempfile::TempDir;
+
+use crate::util::hex_to_id;
+
+const NULL: &str = "0000000000000000000000000000000000000000";
+
+fn fixture() -> crate::Result<(TempDir, gix::Repository)> {
+    let dir = gix_testtools::scripted_fixture_writable("make_receive_pack_repos.sh")?;
+    let repo = gix::open_opts(dir.path().join("server.git"), crate::restricted())?;
+    Ok((dir, repo))
+}
+
+fn id(dir: &TempDir, name: &str) -> crate::Result<String> {
+    Ok(std::fs::read_to_string(dir.path().join(format!("{name}.id")))?
+        .trim()
+        .to_owned())
+}
+
+fn request(commands: &[(&str, &str, &str)], capabilities: &str, pack: Option<&Path>) -> crate::Result<Vec<u8>> {
+    let mut out = Vec::new();
+    for (idx, (old, new, name)) in commands.iter().enumerate() {
+        let mut line = format!("{old} {new} {name}");
+        if idx == 0 {
+            line.push('\0');
+            line.push_str(capabilities);
+        }
+        gix::packetline::blocking_io::encode::data_to_write(line.as_bytes(), &mut out)?;
+    }
+    gix::packetline::blocking_io::encode::flush_to_write(&mut out)?;
+    if let Some(pack) = pack {
+        out.extend(std::fs::read(pack)?);
+    }
+    Ok(out)
+}
+
+fn receive(repo: &gix::Repository, input: &[u8]) -> crate::Result<(gix::receive_pack::Outcome, Vec<BString>)> {
+    let mut output = Vec::new();
+    let outcome = repo.receive_pack(
+        &mut &input[..],
+        &mut output,
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+        Default::default(),
+    )?;
+    Ok((outcome, decode_lines(&output)))
+}
+
+fn decode_lines(mut input: &[u8]) -> Vec<BString> {
+    let mut out = Vec::new();
+    while !input.is_empty() {
+        let mut lines = StreamingPeekableIter::new(&mut input, &[PacketLineRef::Flush], false);
+        while let Some(line) = lines.read_line() {
+            let line = line.expect("no IO error").expect("valid packet line");
+            out.push(line.as_bstr().expect("data line").trim_end().into());
+        }
+    }
+    out
+}
+
+fn ref_id(repo: &gix::Repository, name: &str) -> crate::Result<Option<gix::ObjectId>> {
+    Ok(repo.try_find_reference(name)?.map(|r| r.id().detach()))
+}
+
+#[test]
+fn advertisement_lists_references_with_capabilities_on_first_line() -> crate::Result {
+    let (dir, repo) = fixture()?;
+    let mut out = Vec::new();
+    repo.receive_pack_advertisement(&mut out)?;
+    let lines = decode_lines(&out);
+    let first = id(&dir, "first")?;
+    assert_eq!(lines.len(), 2);
+    let (main, capabilities) = lines[0].split_once_str(b"\0").expect("capabilities are present");
+    assert_eq!(main.as_bstr(), format!("{first} refs/heads/main"));
+    assert!(capabilities.starts_with(b"report-status delete-refs side-band-64k"));
+    assert!(capabilities.contains_str("object-format=sha1 agent=git/oxide-"));
+    assert_eq!(lines[1], format!("{first} refs/heads/other"));
+
+    let empty = gix::init_bare(dir.path().join("empty.git"))?;
+    out.clear();
+    empty.receive_pack_advertisement(&mut out)?;
+    let lines = decode_lines(&out);
+    assert_eq!(lines.len(), 1);
+    assert!(lines[0].starts_with(format!("{NULL} capabilities^{{}}\0").as_bytes()));
+    Ok(())
+}
+
+#[test]
+fn fast_forward_with_thin_pack() -> crate::Result {
+    let (dir, repo) = fixture()?;
+    let (first, second) = (id(&dir, "first")?, id(&dir, "second")?);
+    let input = request(
+        &[(&first, &second, "refs/heads/main")],
+        "report-status agent=git/2",
+        Some(&dir.path().join("first-to-second.pack")),
+    )?;
+    let (outcome, lines) = receive(&repo, &input)?;
+    assert_eq!(lines, ["unpack ok", "ok refs/heads/main"]);
+    assert_eq!(outcome.commands.len(), 1);
+    assert_eq!(outcome.commands[0].1, Status::Ok);
+    assert_eq!(outcome.unpack_error, None);
+
+    let bundle = outcome.write_pack_bundle.expect("a pack was received");
+    assert_eq!(
+        bundle.keep_path, None,
+        "keep-files are removed after the refs were updated"
+    );
+    let index_path = bundle.index_path.expect("pack was written");
+    assert_eq!(
+        index_path.parent(),
+        Some(repo.objects.store_ref().path().join("pack").as_path()),
+        "the pack was moved out of quarantine"
+    );
+    assert!(index_path.is_file());
+    assert_eq!(ref_id(&repo, "refs/heads/main")?, Some(hex_to_id(&second)));
+    assert!(repo.has_object(hex_to_id(&second)));
+    assert_no_quarantine_left(&repo)?;
+    Ok(())
+}
+
+#[test]
+fn create_and_delete_with_side_band() -> crate::Result {
+    let (dir, repo) = fixture()?;
+    let first = id(&dir, "first")?;
+    let input = request(
+        &[(&first, NULL, "refs/heads/other"), (NULL, &first, "refs/tags/v1")],
+        "report-status side-band-64k",
+        Some(&dir.path().join("unrelated.pack")),
+    )?;
+    let mut output = Vec::new();
+    let outcome = repo.receive_pack(
+        &mut input.as_slice(),
+        &mut output,
+        &mut gix::progress::Discard,
+        &AtomicBool::default(),
+        Default::default(),
+    )?;
+    assert!(outcome.commands.iter().all(|(_, status)| status.is_ok()));
+
+    let mut bands = StreamingPeekableIter::new(output.as_slice(), &[PacketLineRef::Flush], false);
+    let mut report = Vec::new();
+    std::io::Read::read_to_end(
+        &mut bands.as_read_with_sidebands(|_is_error, _text| ProgressAction::Continue),
+        &mut report,
+    )?;
+    assert_eq!(
+        decode_lines(&report),
+        ["unpack ok", "ok refs/heads/other", "ok refs/tags/v1"],
+        "the report is sent on the data channel"
+    );
+    assert_eq!(ref_id(&repo, "refs/heads/other")?, None);
+    assert_eq!(ref_id(&repo, "refs/tags/v1")?, Some(hex_to_id(&first)));
+    Ok(())
+}
+
+#[test]
+fn packs_with_missing_objects_are_rejected() -> crate::Result {
+    let (dir, repo) = fixture()?;
+    let (first, second) = (id(&dir, "first")?, id(&dir, "second")?);
+    let input = request(
+        &[(&first, &second, "refs/heads/main")],
+        "report-status",
+        Some(&dir.path().join("second-commit-only.pack")),
+    )?;
+    let (outcome, lines) = receive(&repo, &input)?;
+    assert_eq!(lines, ["unpack ok", "ng refs/heads/main missing necessary objects"]);
+    assert!(outcome.write_pack_bundle.is_some());
+    assert_eq!(ref_id(&repo, "refs/heads/main")?, Some(hex_to_id(&first)));
+    assert!(
+        !repo.has_object(hex_to_id(&second)),
+        "objects in quarantine are discarded"
+    );
+    assert_no_quarantine_left(&repo)?;
+    Ok(())
+}
+
+#[test]
+fn stale_old_values_and_non_fast_forwards_are_rejected() -> crate::Result {
+    let (dir, mut repo) = fixture()?;
+    let (first, second, unrelated) = (id(&dir, "first")?, id(&dir, "second")?, id(&dir, "unrelated")?);
+    let input = request(
+        &[
+            (&second, &unrelated, "refs/heads/main"),
+            (&first, &unrelated, "refs/heads/other"),
+        ],
+        "report-status",
+        Some(&dir.path().join("unrelated.pack")),
+    )?;
+    let (_outcome, lines) = receive(&repo, &input)?;
+    assert_eq!(
+        lines,
+        [
+            "unpack ok",
+            "ng refs/heads/main failed to update ref",
+            "ok refs/heads/other"
+        ],
+        "without `atomic`, each reference is updated on its own"
+    );
+
+    repo.config_snapshot_mut()
+        .set_value(&gix::config::tree::Receive::DENY_NON_FAST_FORWARDS, "true")?;
+    let input = request(
+        &[(&unrelated, &first, "refs/heads/other")],
+        "report-status",
+        Some(&dir.path().join("unrelated.pack")),
+    )?;
+    let (_outcome, lines) = receive(&repo, &input)?;
+    assert_eq!(lines, ["unpack ok", "ng refs/heads/other non-fast-forward"]);
+    assert_eq!(ref_id(&repo, "refs/heads/other")?, Some(hex_to_id(&unrelated)));
+    Ok(())
+}
+
+#[test]
+fn atomic_pushes_fail_as_a_whole() -> crate::Result {
+    let (dir, mut repo) = fixture()?;
+    repo.config_snapshot_mut()
+        .set_value(&gix::config::tree::Receive::DENY_DELETES, "true")?;
+    let (first, unrelated) = (id(&dir, "first")?, id(&dir, "unrelated")?);
+    let input = request(
+        &[(NULL, &unrelated, "refs/heads/new"), (&first, NULL, "refs/heads/other")],
+        "report-status atomic",
+        Some(&dir.path().join("unrelated.pack")),
+    )?;
+    let (_outcome, lines) = receive(&repo, &input)?;
+    assert_eq!(
+        lines,
+        [
+            "unpack ok",
+            "ng refs/heads/new atomic push failure",
+            "ng refs/heads/other deletion prohibited"
+        ]
+    );
+    assert_eq!(ref_id(&repo, "refs/heads/new")?, None);
+    assert_eq!(ref_id(&repo, "refs/heads/other")?, Some(hex_to_id(&first)));
+    Ok(())
+}
+
+#[test]
+fn checked_out_branch_is_protected_in_non_bare_repositories() -> crate::Result {
+    let (dir, _repo) = fixture()?;
+    let repo = gix::open_opts(dir.path().join("non-bare-server"), crate::restricted())?;
+    let (second, unrelated) = (id(&dir, "second")?, id(&dir, "unrelated")?);
+    let input = request(
+        &[(&second, &unrelated, "refs/heads/main")],
+        "report-status",
+        Some(&dir.path().join("unrelated.pack")),
+    )?;
+    let (_outcome, lines) = receive(&repo, &input)?;
+    assert_eq!(
+        lines,
+        ["unpack ok", "ng refs/heads/main branch is currently checked out"]
+    );
+    Ok(())
+}
+
+#[test]
+fn nothing_to_do_if_client_sends_no_commands() -> crate::Result {
+    let (_dir, repo) = fixture()?;
+    let (outcome, lines) = receive(&repo, b"0000")?;
+    assert!(outcome.commands.is_empty());
+    assert!(lines.is_empty(), "nothing is reported");
+    Ok(())
+}
+
+#[cfg(unix)]
+mod hooks {
+    use std::os::unix::fs::PermissionsExt;
+
+    use std::sync::atomic::AtomicBool;
+
+    use gix::packetline::{blocking_io::StreamingPeekableIter, read::ProgressAction, PacketLineRef};
+
+    use super::{fixture, id, receive, ref_id, request};
+    use crate::util::hex_to_id;
+
+    fn write_hook(repo: &gix::Repository, name: &str, script: &str) -> crate::Result {
+        let path = repo.git_dir().join("hooks").join(name);
+        std::fs::create_dir_all(path.parent().expect("parent"))?;
+        std::fs::write(&path, format!("#!/bin/sh\n{script}"))?;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+        Ok(())
+    }
+
+    #[test]
+    fn pre_receive_sees_quarantined_objects_and_can_decline() -> crate::Result {
+        let (dir, repo) = fixture()?;
+        let (first, second) = (id(&dir, "first")?, id(&dir, "second")?);
+        write_hook(
+            &repo,
+            "pre-receive",
+            r#"cat > ../pre-receive.stdin
+test -d "$GIT_QUARANTINE_PATH/pack" && test "$GIT_OBJECT_DIRECTORY" = "$GIT_QUARANTINE_PATH" && echo "in quarantine"
+echo "options: $GIT_PUSH_OPTION_COUNT $GIT_PUSH_OPTION_0"
+exit 1
+"#,
+        )?;
+        let mut input = request(
+            &[(&first, &second, "refs/heads/main")],
+            "report-status push-options",
+            None,
+        )?;
+        gix::packetline::blocking_io::encode::data_to_write(b"ci.skip", &mut input)?;
+        gix::packetline::blocking_io::encode::flush_to_write(&mut input)?;
+        input.extend(std::fs::read(dir.path().join("first-to-second.pack"))?);
+
+        let (outcome, lines) = receive(&repo, &input)?;
+        assert_eq!(lines, ["unpack ok", "ng refs/heads/main pre-receive hook declined"]);
+        assert_eq!(outcome.push_options, ["ci.skip"]);
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("pre-receive.stdin"))?,
+            format!("{first} {second} refs/heads/main\n")
+        );
+        assert_eq!(ref_id(&repo, "refs/heads/main")?, Some(hex_to_id(&first)));
+        assert!(!repo.has_object(hex_to_id(&second)));
+        super::assert_no_quarantine_left(&repo)?;
+        Ok(())
+    }
+
+    #[test]
+    fn hooks_that_cannot_be_run_decline() -> crate::Result {
+        let (dir, repo) = fixture()?;
+        let (first, second) = (id(&dir, "first")?, id(&dir, "second")?);
+        let path = repo.git_dir().join("hooks").join("pre-receive");
+        std::fs::create_dir_all(path.parent().expect("parent"))?;
+        std::fs::write(&path, "#!/does/not/exist\n")?;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+
+        let mut input = request(&[(&first, &second, "refs/heads/main")], "report-status", None)?;
+        input.extend(std::fs::read(dir.path().join("first-to-second.pack"))?);
+        let (outcome, lines) = receive(&repo, &input)?;
+        assert_eq!(
+            lines,
+            ["unpack ok", "ng refs/heads/main pre-receive hook declined"],
+            "the client still learns what happened"
+        );
+        assert!(!outcome.commands[0].1.is_ok());
+        assert_eq!(ref_id(&repo, "refs/heads/main")?, Some(hex_to_id(&first)));
+        super::assert_no_quarantine_left(&repo)?;
+        Ok(())
+    }
+
+    #[test]
+    fn hooks_writing_a_lot_while_receiving_a_lot_do_not_block() -> crate::Result {
+        let (dir, repo) = fixture()?;
+        let unrelated = id(&dir, "unrelated")?;
+        write_hook(
+            &repo,
+            "pre-receive",
+            "head -c 200000 /dev/zero | tr '\\0' x\ncat > /dev/null\nexit 1\n",
+        )?;
+        let names: Vec<_> = (0..2000).map(|idx| format!("refs/heads/branch-{idx}")).collect();
+        let commands: Vec<_> = names
+            .iter()
+            .map(|name| (super::NULL, unrelated.as_str(), name.as_str()))
+            .collect();
+        let input = request(
+            &commands,
+            "report-status side-band-64k",
+            Some(&dir.path().join("unrelated.pack")),
+        )?;
+        let mut output = Vec::new();
+        repo.receive_pack(
+            &mut input.as_slice(),
+            &mut output,
+            &mut gix::progress::Discard,
+            &AtomicBool::default(),
+            Default::default(),
+        )?;
+
+        let mut bands = StreamingPeekableIter::new(output.as_slice(), &[PacketLineRef::Flush], false);
+        let mut hook_output = 0;
+        let mut report = Vec::new();
+        std::io::Read::read_to_end(
+            &mut bands.as_read_with_sidebands(|_is_error, text| {
+                hook_output += text.len();
+                ProgressAction::Continue
+            }),
+            &mut report,
+        )?;
+        assert_eq!(hook_output, 200_000, "all output of the hook was received");
+        let lines = super::decode_lines(&report);
+        assert_eq!(lines.len(), 2001, "the hook saw all input");
+        assert_eq!(lines[1], "ng refs/heads/branch-0 pre-receive hook declined");
+        Ok(())
+    }
+
+    #[test]
+    fn update_hook_declines_individual_refs_and_post_receive_sees_the_rest() -> crate::Result {
+        let (dir, repo) = fixture()?;
+        let (first, second) = (id(&dir, "first")?, id(&dir, "second")?);
+        write_hook(&repo, "update", r#"test "$1" != refs/heads/other"#)?;
+        write_hook(&repo, "post-receive", "cat > ../post-receive.stdin")?;
+        let input = request(
+            &[
+                (&first, &second, "refs/heads/main"),
+                (&first, &second, "refs/heads/other"),
+            ],
+            "report-status",
+            Some(&dir.path().join("first-to-second.pack")),
+        )?;
+        let (_outcome, lines) = receive(&repo, &input)?;
+        assert_eq!(
+            lines,
+            ["unpack ok", "ok refs/heads/main", "ng refs/heads/other hook declined"]
+        );
+        assert_eq!(
+            std::fs::read_to_string(dir.path().join("post-receive.stdin"))?,
+            format!("{first} {second} refs/heads/main\n")
+        );
+        assert_eq!(ref_id(&repo, "refs/heads/main")?, Some(hex_to_id(&second)));
+        assert_eq!(ref_id(&repo, "refs/heads/other")?, Some(hex_to_id(&first)));
+        Ok(())
+    }
+}
+
+fn assert_no_quarantine_left(repo: &gix::Repository) -> crate::Result {
+    let leftovers: Vec<_> = std::fs::read_dir(repo.objects.store_ref().path())?
+        .filter_map(Result::ok)
+        .filter(|e| e.file_name().to_string_lossy().starts_with("incoming-"))
+        .collect();
+    assert!(leftovers.is_empty(), "quarantine directories are always removed");
+    Ok(())
+}
@@ -0,0 +1,108 @@
+use gix::bstr::ByteSlice;
+
+use crate::util::{named_subrepo_opts, restricted};
+
+fn repo() -> crate::Result<gix::Repository> {
+    Ok(named_subrepo_opts("make_sha256_repo.sh", "repo", restricted())?)
+}
+
+#[test]
+fn objects_in_packs_and_loose_objects_can_be_read() -> crate::Result {
+    let repo = repo()?;
+    assert_eq!(repo.object_hash(), gix::hash::Kind::Sha256);
+
+    let head = repo.head_commit()?;
+    assert_eq!(head.id.kind(), gix::hash::Kind::Sha256);
+    assert_eq!(head.id.to_string().len(), 64);
+    assert_eq!(head.message_raw()?, "c3\n", "the head commit is a loose object");
+    assert_eq!(head.id().ancestors().all()?.count(), 3);
+
+    let first = repo.rev_parse_single("main~2")?.object()?.into_commit();
+    assert_eq!(first.message_raw()?, "c1\n", "the first commit is in a pack");
+
+    let tree = head.tree()?;
+    assert_eq!(
+        tree.iter()
+            .map(|entry| entry.map(|entry| entry.filename().to_owned()))
+            .collect::<Result<Vec<_>, _>>()?,
+        ["a", "d", "dir"]
+    );
+    let entry = tree.lookup_entry_by_path("dir/c")?.expect("present");
+    assert_eq!(entry.object()?.data.as_bstr(), "c\n");
+    Ok(())
+}
+
+#[test]
+fn references_can_be_peeled() -> crate::Result {
+    let repo = repo()?;
+    let first = repo.rev_parse_single("main~2")?.detach();
+    assert_eq!(
+        repo.find_reference("other")?.peel_to_id()?,
+        first,
+        "packed references can be read"
+    );
+    assert_eq!(
+        repo.find_reference("loose-ref")?.peel_to_id()?,
+        first,
+        "loose references can be read"
+    );
+
+    let tag = repo
+        .find_reference("v1")?
+        .peel_to_kind(gix::object::Kind::Tag)?
+        .into_tag();
+    assert_eq!(tag.target_id()?, first);
+    assert_eq!(repo.find_reference("v1")?.peel_to_id()?, first);
+    Ok(())
+}
+
+#[test]
+fn index_and_commit_graph_can_be_read() -> crate::Result {
+    let repo = repo()?;
+    let index = repo.index()?;
+    assert_eq!(index.object_hash(), gix::hash::Kind::Sha256);
+    assert_eq!(index.entries().len(), 4);
+    for entry in index.entries() {
+        assert!(repo.has_object(entry.id), "{}", entry.path(&index));
+    }
+
+    let graph = repo.commit_graph()?;
+    assert_eq!(graph.num_commits(), 3);
+    let head_id = repo.head_id()?;
+    let commit = graph.commit_by_id(head_id).expect("head is in the commit-graph");
+    assert_eq!(
+        commit.parent1()?.map(|pos| graph.id_at(pos).to_owned()),
+        Some(repo.rev_parse_single("main~1")?.detach())
+    );
+    Ok(())
+}
+
+#[test]
+fn objects_and_references_can_be_written() -> crate::Result {
+    let tmp = gix_testtools::scripted_fixture_writable("make_sha256_repo.sh")?;
+    let repo_dir = tmp.path().join("repo");
+    let repo: gix::Repository = gix::ThreadSafeRepository::open_opts(&repo_dir, restricted())?.into();
+
+    let blob_id = repo.write_blob("new\n")?;
+    let head = repo.head_commit()?;
+    let mut editor = repo.edit_tree(head.tree_id()?)?;
+    editor.upsert("new", gix::object::tree::EntryKind::Blob, blob_id)?;
+    let tree_id = editor.write()?;
+    let commit_id = repo.commit("HEAD", "c4", tree_id, [head.id])?;
+    assert_eq!(commit_id.kind(), gix::hash::Kind::Sha256);
+
+    let repo: gix::Repository = gix::ThreadSafeRepository::open_opts(&repo_dir, restricted())?.into();
+    assert_eq!(repo.head_id()?, commit_id);
+    let entry = repo
+        .head_commit()?
+        .tree()?
+        .lookup_entry_by_path("new")?
+        .expect("just written");
+    assert_eq!(entry.object_id(), blob_id);
+
+    assert!(
+        gix_testtools::run_git(&repo_dir, &["fsck", "--strict", "--no-progress"])?.success(),
+        "git considers all objects we wrote valid"
+    );
+    Ok(())
+}
@@ -400,3 +400,361 @@ fn run_assertions(main_repo: gix::Repository, should_be_bare: bool) {
         );
     }
 }
+
+mod mutation {
+    use std::path::{Path, PathBuf};
+
+    use gix::bstr::ByteSlice;
+
+    fn repo() -> crate::Result<(gix::Repository, gix_testtools::tempfile::TempDir)> {
+        let tmp = gix_testtools::scripted_fixture_writable_with_args(
+            "make_worktree_mutation_repo.sh",
+            None::<String>,
+            gix_testtools::Creation::ExecuteScript,
+        )?;
+        let repo = gix::open_opts(tmp.path().join("repo"), crate::restricted())?;
+        Ok((repo, tmp))
+    }
+
+    fn ids(repo: &gix::Repository) -> crate::Result<Vec<String>> {
+        Ok(repo.worktrees()?.iter().map(|proxy| proxy.id().to_string()).collect())
+    }
+
+    fn by_id<'repo>(repo: &'repo gix::Repository, id: &str) -> gix::worktree::Proxy<'repo> {
+        repo.worktree_proxy_by_id(id).expect("worktree exists")
+    }
+
+    fn realpath(path: impl AsRef<Path>) -> PathBuf {
+        gix::path::realpath(path.as_ref()).expect("path exists")
+    }
+
+    #[test]
+    #[cfg(feature = "worktree-mutation")]
+    fn add_with_new_branch() -> crate::Result {
+        use gix::worktree::add::{Head, Options};
+
+        let (repo, tmp) = repo()?;
+        let target = repo.head_id()?.detach();
+        let path = tmp.path().join("feature");
+        let (wt_repo, outcome) = repo.worktree_add(
+            &path,
+            Head::NewBranch {
+                name: "refs/heads/feature".try_into()?,
+                target,
+            },
+            &mut gix::progress::Discard,
+            &std::sync::atomic::AtomicBool::default(),
+            Options::default(),
+        )?;
+        assert_eq!(outcome.files_updated, 3);
+        assert_eq!(std::fs::read(path.join("dir/c"))?, b"c\n");
+        assert_eq!(
+            wt_repo.head_name()?.expect("not detached").as_bstr(),
+            "refs/heads/feature"
+        );
+        assert_eq!(wt_repo.head_id()?, target);
+        assert_eq!(wt_repo.workdir().map(realpath), Some(realpath(&path)));
+        assert_eq!(wt_repo.index()?.entries().len(), 3, "the index was written as well");
+
+        let proxy = by_id(&repo, "feature");
+        assert!(!proxy.is_locked(), "the initial lock is removed once done");
+        assert_eq!(realpath(proxy.base()?), realpath(&path));
+        assert_eq!(std::fs::read(proxy.git_dir().join("commondir"))?, b"../..\n");
+        assert_eq!(repo.find_reference("feature")?.id(), target);
+
+        let reopened = gix::open_opts(&path, crate::restricted())?;
+        assert_eq!(reopened.git_dir(), wt_repo.git_dir(), "the .git file points back");
+        assert!(!reopened.is_dirty()?, "the checkout matches the index");
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "worktree-mutation")]
+    fn add_detached_without_checkout_and_locked() -> crate::Result {
+        use gix::worktree::add::{Head, Options};
+
+        let (repo, tmp) = repo()?;
+        let target = repo.rev_parse_single("HEAD~1")?.detach();
+        let path = tmp.path().join("empty-dir");
+        std::fs::create_dir(&path)?;
+        let (wt_repo, outcome) = repo.worktree_add(
+            &path,
+            Head::Detached(target),
+            &mut gix::progress::Discard,
+            &std::sync::atomic::AtomicBool::default(),
+            Options {
+                id: Some("custom".into()),
+                checkout: false,
+                lock_reason: Some("hold on".into()),
+                force: false,
+            },
+        )?;
+        assert_eq!(outcome.files_updated, 0);
+        assert!(wt_repo.head_name()?.is_none(), "detached");
+        assert_eq!(wt_repo.head_id()?, target);
+        assert_eq!(std::fs::read_dir(&path)?.count(), 1, "only the .git file is present");
+
+        let proxy = by_id(&repo, "custom");
+        assert_eq!(
+            proxy.lock_reason().as_ref().map(|r| r.as_bstr()),
+            Some("hold on".into())
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "worktree-mutation")]
+    fn add_refusals_leave_nothing_behind() -> crate::Result {
+        use gix::worktree::add::{Error, Head, Options};
+
+        let (repo, tmp) = repo()?;
+        let add = |path: &Path, head: Head, options: Options| {
+            repo.worktree_add(
+                path,
+                head,
+                &mut gix::progress::Discard,
+                &std::sync::atomic::AtomicBool::default(),
+                options,
+            )
+        };
+        let before = ids(&repo)?;
+        let other: gix::refs::FullName = "refs/heads/other".try_into()?;
+
+        let err = add(&tmp.path().join("new"), Head::Branch(other.clone()), Options::default()).unwrap_err();
+        assert!(matches!(err, Error::BranchCheckedOut { .. }), "{err:?}");
+        let err = add(
+            &tmp.path().join("new"),
+            Head::Branch("refs/heads/main".try_into()?),
+            Options::default(),
+        )
+        .unwrap_err();
+        assert!(
+            matches!(err, Error::BranchCheckedOut { .. }),
+            "the main worktree is checked too"
+        );
+        let err = add(
+            &tmp.path().join("wt-other"),
+            Head::Branch(other.clone()),
+            Options::default(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::DestinationNotEmpty { .. }), "{err:?}");
+        let err = add(
+            &tmp.path().join("new"),
+            Head::Branch(other.clone()),
+            Options {
+                id: Some("wt-other".into()),
+                force: true,
+                ..Default::default()
+            },
+        )
+        .unwrap_err();
+        assert!(matches!(err, Error::IdTaken { .. }), "{err:?}");
+        assert!(!tmp.path().join("new").exists());
+        assert!(
+            repo.common_dir()
+                .join("worktrees")
+                .join("wt-other")
+                .join("gitdir")
+                .is_file(),
+            "the worktree that owns the id is left untouched"
+        );
+        assert_eq!(ids(&repo)?, before, "nothing was added");
+
+        let (wt_repo, _) = add(
+            &tmp.path().join("wt-other-again"),
+            Head::Branch(other),
+            Options {
+                force: true,
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(
+            by_id(&repo, "wt-other-again").git_dir(),
+            wt_repo.git_dir(),
+            "forcing allows the same branch to be checked out twice"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn lock_and_unlock() -> crate::Result {
+        let (repo, _tmp) = repo()?;
+        let proxy = by_id(&repo, "wt-other");
+        assert!(proxy.lock(Some("reason".into()))?);
+        assert!(!proxy.lock(None)?, "already locked");
+        assert_eq!(proxy.lock_reason().as_ref().map(|r| r.as_bstr()), Some("reason".into()));
+        assert!(proxy.unlock()?);
+        assert!(!proxy.unlock()?, "already unlocked");
+
+        assert!(proxy.lock(None)?);
+        assert_eq!(proxy.lock_reason().as_ref().map(|r| r.as_bstr()), Some("".into()));
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "status")]
+    fn remove() -> crate::Result {
+        use gix::worktree::proxy::remove::{Error, Options};
+
+        let (repo, tmp) = repo()?;
+        let err = by_id(&repo, "wt-locked").remove(Options::default()).unwrap_err();
+        assert!(matches!(err, Error::Locked { reason: Some(_) }), "{err:?}");
+
+        let checkout = tmp.path().join("wt-other");
+        std::fs::write(checkout.join("untracked"), b"")?;
+        let err = by_id(&repo, "wt-other").remove(Options::default()).unwrap_err();
+        assert!(matches!(err, Error::Dirty { .. }), "untracked files count: {err:?}");
+        std::fs::remove_file(checkout.join("untracked"))?;
+        std::fs::write(checkout.join("a"), b"changed")?;
+        let err = by_id(&repo, "wt-other").remove(Options::default()).unwrap_err();
+        assert!(matches!(err, Error::Dirty { .. }), "modifications count: {err:?}");
+
+        by_id(&repo, "wt-other").remove(Options {
+            force_dirty: true,
+            force_locked: false,
+        })?;
+        assert!(!checkout.exists());
+        by_id(&repo, "wt-locked").remove(Options {
+            force_dirty: false,
+            force_locked: true,
+        })?;
+        by_id(&repo, "wt-deleted").remove(Options::default())?;
+        assert!(ids(&repo)?.is_empty());
+        assert!(
+            !repo.common_dir().join("worktrees").exists(),
+            "the worktrees directory is removed once empty"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn move_to() -> crate::Result {
+        use gix::worktree::proxy::move_to::Error;
+
+        let (repo, tmp) = repo()?;
+        let err = by_id(&repo, "wt-locked")
+            .move_to(tmp.path().join("moved"), false)
+            .unwrap_err();
+        assert!(matches!(err, Error::Locked { .. }), "{err:?}");
+        let err = by_id(&repo, "wt-deleted")
+            .move_to(tmp.path().join("moved"), false)
+            .unwrap_err();
+        assert!(matches!(err, Error::MissingWorktree { .. }), "{err:?}");
+        let err = by_id(&repo, "wt-other")
+            .move_to(tmp.path().join("wt-locked/a"), false)
+            .unwrap_err();
+        assert!(matches!(err, Error::DestinationExists { .. }), "{err:?}");
+
+        let new_base = by_id(&repo, "wt-other").move_to(tmp.path().join("moved"), false)?;
+        assert_eq!(new_base, realpath(tmp.path().join("moved")));
+        assert_eq!(realpath(by_id(&repo, "wt-other").base()?), new_base);
+        let wt_repo = gix::open_opts(&new_base, crate::restricted())?;
+        assert_eq!(wt_repo.git_dir(), by_id(&repo, "wt-other").git_dir());
+
+        std::fs::create_dir(tmp.path().join("parent"))?;
+        let new_base = by_id(&repo, "wt-locked").move_to(tmp.path().join("parent"), true)?;
+        assert_eq!(
+            new_base,
+            realpath(tmp.path().join("parent/wt-locked")),
+            "existing directories are moved into"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn prune() -> crate::Result {
+        use gix::worktree::prune::{Options, Pruned, Reason};
+
+        let (repo, tmp) = repo()?;
+        std::fs::remove_dir_all(tmp.path().join("wt-locked"))?;
+        std::fs::write(repo.common_dir().join("worktrees/not-a-dir"), b"")?;
+        std::fs::create_dir(repo.common_dir().join("worktrees/no-gitdir"))?;
+
+        let pruned = repo.worktrees_prune(Options {
+            expire: Some(gix::date::Time::new(0, 0)),
+            dry_run: false,
+        })?;
+        assert_eq!(
+            pruned,
+            [
+                Pruned {
+                    id: "no-gitdir".into(),
+                    reason: Reason::MissingGitdirFile
+                },
+                Pruned {
+                    id: "not-a-dir".into(),
+                    reason: Reason::NotADirectory
+                }
+            ],
+            "missing checkouts are only pruned once expired"
+        );
+
+        let options = Options {
+            expire: None,
+            dry_run: true,
+        };
+        let expected = [Pruned {
+            id: "wt-deleted".into(),
+            reason: Reason::MissingCheckout,
+        }];
+        assert_eq!(repo.worktrees_prune(options)?, expected, "locked worktrees are kept");
+        assert_eq!(
+            ids(&repo)?,
+            ["wt-deleted", "wt-locked", "wt-other"],
+            "nothing happens in dry-run mode"
+        );
+        assert_eq!(
+            repo.worktrees_prune(Options {
+                dry_run: false,
+                ..options
+            })?,
+            expected
+        );
+        assert_eq!(ids(&repo)?, ["wt-locked", "wt-other"]);
+        Ok(())
+    }
+
+    #[test]
+    fn repair_moved_worktree() -> crate::Result {
+        use gix::worktree::repair::Change;
+
+        let (repo, tmp) = repo()?;
+        let moved = tmp.path().join("moved");
+        std::fs::rename(tmp.path().join("wt-other"), &moved)?;
+        let changes = repo.worktrees_repair(std::slice::from_ref(&moved))?;
+        assert_eq!(
+            changes,
+            [Change::GitdirFile {
+                id: "wt-other".into(),
+                path: realpath(&moved)
+            }]
+        );
+        assert_eq!(realpath(by_id(&repo, "wt-other").base()?), realpath(&moved));
+        assert!(repo.worktrees_repair(&[moved])?.is_empty(), "nothing left to repair");
+        Ok(())
+    }
+
+    #[test]
+    fn repair_broken_dot_git_file() -> crate::Result {
+        use gix::worktree::repair::Change;
+
+        let (repo, tmp) = repo()?;
+        let dot_git = tmp.path().join("wt-other/.git");
+        std::fs::write(&dot_git, b"gitdir: /does/not/exist\n")?;
+        let changes = repo.worktrees_repair(&[])?;
+        assert_eq!(
+            changes,
+            [Change::DotGitFile {
+                id: "wt-other".into(),
+                path: by_id(&repo, "wt-other").base()?
+            }]
+        );
+        let wt_repo = gix::open_opts(tmp.path().join("wt-other"), crate::restricted())?;
+        assert_eq!(
+            realpath(wt_repo.git_dir()),
+            realpath(by_id(&repo, "wt-other").git_dir())
+        );
+        Ok(())
+    }
+}
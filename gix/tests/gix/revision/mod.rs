@@ -1 +1,2 @@
 mod spec;
+mod walk;
@@ -0,0 +1,337 @@
+#[cfg(feature = "attributes")]
+mod simplification {
+    use gix::{bstr::ByteSlice, revision::walk::Simplification, ObjectId};
+
+    use crate::util::named_repo;
+
+    #[test]
+    fn default() -> crate::Result {
+        assert_matches_baseline("default", Simplification::default(), &["foo"])
+    }
+
+    #[test]
+    fn default_with_rewritten_parents() -> crate::Result {
+        let simplification = Simplification {
+            rewrite_parents: true,
+            ..Default::default()
+        };
+        assert_matches_baseline("default-parents", simplification, &["foo"])
+    }
+
+    #[test]
+    fn sparse() -> crate::Result {
+        let simplification = Simplification {
+            sparse: true,
+            ..Default::default()
+        };
+        assert_matches_baseline("sparse", simplification, &["foo"])
+    }
+
+    #[test]
+    fn full_history() -> crate::Result {
+        let simplification = Simplification {
+            full_history: true,
+            ..Default::default()
+        };
+        assert_matches_baseline("full-history", simplification, &["foo"])
+    }
+
+    #[test]
+    fn full_history_with_rewritten_parents() -> crate::Result {
+        let simplification = Simplification {
+            full_history: true,
+            rewrite_parents: true,
+            ..Default::default()
+        };
+        assert_matches_baseline("full-history-parents", simplification, &["foo"])
+    }
+
+    #[test]
+    fn simplify_merges() -> crate::Result {
+        let simplification = Simplification {
+            simplify_merges: true,
+            ..Default::default()
+        };
+        assert_matches_baseline("simplify-merges", simplification, &["foo"])
+    }
+
+    #[test]
+    fn ancestry_path() -> crate::Result {
+        let simplification = Simplification {
+            ancestry_path: true,
+            rewrite_parents: true,
+            ..Default::default()
+        };
+        assert_matches_baseline("ancestry-path", simplification, &["foo"])?;
+
+        let simplification = Simplification {
+            ancestry_path: true,
+            ..Default::default()
+        };
+        assert_matches_baseline("ancestry-path-without-pathspec", simplification, &[])?;
+
+        let repo = named_repo("make_history_simplification_repo.sh")?;
+        let res = repo
+            .rev_walk([id(&repo, "Q")?])
+            .with_simplification(simplification)
+            .all();
+        assert!(
+            matches!(res, Err(gix::revision::walk::Error::AncestryPathWithoutHiddenTips)),
+            "hidden tips are needed to find their descendants"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn first_parent() -> crate::Result {
+        assert_matches_baseline("first-parent", Simplification::default(), &["foo"])
+    }
+
+    #[test]
+    fn glob_and_directory_pathspecs() -> crate::Result {
+        assert_matches_baseline("glob", Simplification::default(), &["q*"])?;
+        assert_matches_baseline("directory", Simplification::default(), &["."])
+    }
+
+    /// Run the walk that the baseline `name` was created with, with and without commit-graph and its Bloom filters,
+    /// and compare the returned commits along with their parents if the baseline has them.
+    fn assert_matches_baseline(name: &str, simplification: Simplification, pathspec: &[&str]) -> crate::Result {
+        let repo = named_repo("make_history_simplification_repo.sh")?;
+        let baseline = std::fs::read(repo.workdir().expect("non-bare").join(format!("baseline.{name}")))?;
+        let with_parents = simplification.rewrite_parents || simplification.simplify_merges;
+        let mut expected = baseline
+            .lines()
+            .map(|line| {
+                let ids = line
+                    .split_str(" ")
+                    .map(ObjectId::from_hex)
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(if with_parents { ids } else { ids[..1].to_vec() })
+            })
+            .collect::<Result<Vec<_>, gix::hash::decode::Error>>()?;
+        expected.sort();
+
+        let hidden = if name.starts_with("ancestry-path") {
+            vec![id(&repo, "D")?]
+        } else {
+            Vec::new()
+        };
+        for use_commit_graph in [true, false] {
+            let mut platform = repo
+                .rev_walk([id(&repo, "Q")?])
+                .with_hidden(hidden.clone())
+                .with_simplification(simplification)
+                .use_commit_graph(use_commit_graph);
+            if !pathspec.is_empty() {
+                let index = repo.index_or_empty()?;
+                platform = platform.with_pathspec(repo.pathspec(
+                    false,
+                    pathspec,
+                    false,
+                    &index,
+                    gix::worktree::stack::state::attributes::Source::IdMapping,
+                )?);
+            }
+            if name == "first-parent" {
+                platform = platform.first_parent_only();
+            }
+            let mut actual = platform
+                .all()?
+                .map(|info| {
+                    let info = info?;
+                    let mut ids = vec![info.id];
+                    if with_parents {
+                        ids.extend(info.parent_ids);
+                    }
+                    Ok(ids)
+                })
+                .collect::<Result<Vec<_>, gix::revision::walk::iter::Error>>()?;
+            actual.sort();
+            assert_eq!(
+                actual, expected,
+                "{name}: commits and parents match Git's, with commit-graph = {use_commit_graph}"
+            );
+        }
+        Ok(())
+    }
+
+    fn id(repo: &gix::Repository, name: &str) -> crate::Result<ObjectId> {
+        Ok(repo.rev_parse_single(name)?.detach())
+    }
+}
+
+mod filter {
+    use gix::{
+        bstr::ByteSlice,
+        revision::walk::{Filter, Sorting},
+        ObjectId,
+    };
+
+    use crate::util::named_repo;
+
+    #[test]
+    fn author_and_committer() -> crate::Result {
+        assert_matches_baseline("author", author(&["Bob"]))?;
+        if cfg!(feature = "revparse-regex") {
+            assert_matches_baseline("author-email", author(&[r"@example\.org"]))?;
+        }
+        assert_matches_baseline("authors", author(&["Bob", "Carol"]))?;
+        assert_matches_baseline(
+            "committer",
+            Filter {
+                committer: vec!["Carol".into()],
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn grep_and_all_match_and_invert_grep() -> crate::Result {
+        assert_matches_baseline("grep", grep(&["fix"]))?;
+        assert_matches_baseline("grep-body", grep(&["is fast"]))?;
+        if cfg!(feature = "revparse-regex") {
+            assert_matches_baseline("grep-anchored", grep(&["^docs"]))?;
+        }
+        assert_matches_baseline("greps", grep(&["fix", "readme"]))?;
+        assert_matches_baseline(
+            "all-match",
+            Filter {
+                all_match: true,
+                ..grep(&["fix", "readme"])
+            },
+        )?;
+        assert_matches_baseline(
+            "invert-grep",
+            Filter {
+                invert_grep: true,
+                ..grep(&["fix"])
+            },
+        )?;
+        assert_matches_baseline(
+            "ignore-case",
+            Filter {
+                ignore_case: true,
+                ..grep(&["FIX"])
+            },
+        )?;
+        assert_matches_baseline(
+            "author-and-grep",
+            Filter {
+                author: vec!["Bob".into()],
+                ..grep(&["crash"])
+            },
+        )
+    }
+
+    #[test]
+    fn since_and_until_are_inclusive() -> crate::Result {
+        assert_matches_baseline(
+            "since-until",
+            Filter {
+                since: Some(gix::date::parse("2020-03-01 12:00:00 +0000", None)?.seconds),
+                until: Some(gix::date::parse("2020-05-01 12:00:00 +0000", None)?.seconds),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn parents() -> crate::Result {
+        assert_matches_baseline(
+            "no-merges",
+            Filter {
+                max_parents: Some(1),
+                ..Default::default()
+            },
+        )?;
+        assert_matches_baseline(
+            "merges",
+            Filter {
+                min_parents: Some(2),
+                ..Default::default()
+            },
+        )?;
+        assert_matches_baseline(
+            "max-parents",
+            Filter {
+                max_parents: Some(2),
+                ..Default::default()
+            },
+        )?;
+        assert_matches_baseline(
+            "min-parents",
+            Filter {
+                min_parents: Some(3),
+                ..Default::default()
+            },
+        )
+    }
+
+    #[test]
+    fn skip_and_max_count() -> crate::Result {
+        assert_matches_baseline(
+            "skip-and-max-count",
+            Filter {
+                skip: 2,
+                max_count: Some(3),
+                ..Default::default()
+            },
+        )?;
+        assert_matches_baseline(
+            "grep-and-max-count",
+            Filter {
+                max_count: Some(2),
+                ..grep(&["feat"])
+            },
+        )
+    }
+
+    #[test]
+    #[cfg(feature = "revparse-regex")]
+    fn invalid_regex() -> crate::Result {
+        let repo = named_repo("make_commit_filter_repo.sh")?;
+        let res = repo.rev_walk([repo.head_id()?]).with_filter(grep(&["("])).all();
+        assert!(
+            matches!(res, Err(gix::revision::walk::Error::InvalidRegex(_))),
+            "patterns are compiled before the traversal starts"
+        );
+        Ok(())
+    }
+
+    fn author(patterns: &[&str]) -> Filter {
+        Filter {
+            author: patterns.iter().map(|p| (*p).into()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn grep(patterns: &[&str]) -> Filter {
+        Filter {
+            grep: patterns.iter().map(|p| (*p).into()).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Return the commits reachable from `HEAD` that pass `filter`, newest first like Git does, and compare them to the baseline `name`.
+    fn assert_matches_baseline(name: &str, filter: Filter) -> crate::Result {
+        let repo = named_repo("make_commit_filter_repo.sh")?;
+        let baseline = std::fs::read(repo.workdir().expect("non-bare").join(format!("baseline.{name}")))?;
+        let expected = baseline
+            .lines()
+            .map(ObjectId::from_hex)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let actual = repo
+            .rev_walk([repo.head_id()?])
+            .sorting(Sorting::ByCommitTime(Default::default()))
+            .with_filter(filter)
+            .all()?
+            .map(|info| info.map(|info| info.id))
+            .collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(
+            actual, expected,
+            "{name}: the same commits are returned in the same order as by Git"
+        );
+        Ok(())
+    }
+}
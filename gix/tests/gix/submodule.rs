@@ -452,3 +452,529 @@ mod open {
         Ok(())
     }
 }
+
+fn repo_rw(name: &str) -> crate::Result<(gix::Repository, gix_testtools::tempfile::TempDir)> {
+    let tmp = gix_testtools::scripted_fixture_writable("make_submodule_update_repo.sh")?;
+    let repo = gix::open_opts(tmp.path().join(name), crate::util::restricted())?;
+    Ok((repo, tmp))
+}
+
+fn lib(repo: &gix::Repository) -> crate::Result<gix::Submodule<'_>> {
+    Ok(repo
+        .submodules()?
+        .expect("modules present")
+        .next()
+        .expect("one submodule"))
+}
+
+mod mutate {
+    use gix::bstr::ByteSlice;
+
+    use crate::submodule::{lib, repo_rw};
+
+    #[test]
+    fn init_registers_the_resolved_url() -> crate::Result {
+        let (repo, _tmp) = repo_rw("uninitialized")?;
+        let sm = lib(&repo)?;
+        assert!(!sm.is_active()?, "submodules aren't active until they are initialized");
+
+        let url = sm.init()?.expect("url was registered");
+        assert!(
+            url.ends_with(b"/upstream/lib"),
+            "the relative URL is resolved against the remote of the superproject: {url}"
+        );
+
+        let repo = gix::open_opts(repo.workdir().expect("non-bare"), crate::util::restricted())?;
+        let sm = lib(&repo)?;
+        assert!(sm.is_active()?);
+        assert_eq!(
+            repo.config_snapshot().string("submodule.lib.url").as_deref(),
+            Some(url.as_bstr())
+        );
+        assert_eq!(sm.init()?, None, "nothing changes if the URL is configured already");
+        Ok(())
+    }
+
+    #[test]
+    fn sync_writes_the_url_to_superproject_and_submodule() -> crate::Result {
+        let (repo, _tmp) = repo_rw("checked-out")?;
+        let workdir = repo.workdir().expect("non-bare").to_owned();
+        let modules = std::fs::read_to_string(workdir.join(".gitmodules"))?;
+        std::fs::write(workdir.join(".gitmodules"), modules.replace("../lib", "../moved"))?;
+
+        let repo = gix::open_opts(&workdir, crate::util::restricted())?;
+        let url = lib(&repo)?.sync()?.expect("active");
+        assert!(url.ends_with(b"/upstream/moved"), "{url}");
+
+        let repo = gix::open_opts(&workdir, crate::util::restricted())?;
+        assert_eq!(
+            repo.config_snapshot().string("submodule.lib.url").as_deref(),
+            Some(url.as_bstr())
+        );
+        let sm_repo = lib(&repo)?.open()?.expect("checked out");
+        assert_eq!(
+            sm_repo.config_snapshot().string("remote.origin.url").as_deref(),
+            Some(url.as_bstr()),
+            "absolute URLs are the same for both"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn absorb_git_dir_moves_the_repository_into_the_superproject() -> crate::Result {
+        let (repo, _tmp) = repo_rw("old-form")?;
+        let sm = lib(&repo)?;
+        assert!(sm.state()?.is_old_form);
+        let head = sm.open()?.expect("present").head_id()?.detach();
+
+        assert!(sm.absorb_git_dir()?);
+        let work_dir = sm.work_dir()?;
+        assert!(
+            work_dir.join(".git").is_file(),
+            "a .git file points to the new location"
+        );
+        assert!(sm.git_dir().is_dir());
+        let state = sm.state()?;
+        assert!(!state.is_old_form);
+        assert!(state.worktree_checkout);
+
+        let sm_repo = sm.open()?.expect("still present");
+        assert_eq!(sm_repo.head_id()?, head);
+        assert_eq!(
+            gix::path::realpath(sm_repo.workdir().expect("non-bare"))?,
+            gix::path::realpath(&work_dir)?
+        );
+        assert!(sm_repo.is_dirty().is_ok(), "the worktree is still usable");
+        assert!(!sm.absorb_git_dir()?, "nothing to do the second time");
+        Ok(())
+    }
+
+    #[test]
+    fn deinit_empties_the_worktree_and_drops_the_configuration() -> crate::Result {
+        let (repo, _tmp) = repo_rw("checked-out")?;
+        let sm = lib(&repo)?;
+        assert!(sm.deinit()?);
+
+        let work_dir = sm.work_dir()?;
+        assert!(work_dir.is_dir(), "the directory is kept");
+        assert_eq!(std::fs::read_dir(&work_dir)?.count(), 0);
+        assert!(sm.git_dir().is_dir(), "the repository is kept as well");
+
+        let repo = gix::open_opts(repo.workdir().expect("non-bare"), crate::util::restricted())?;
+        assert!(repo.config_snapshot().string("submodule.lib.url").is_none());
+        assert!(!lib(&repo)?.deinit()?, "nothing to do the second time");
+        Ok(())
+    }
+
+    #[test]
+    fn deinit_refuses_to_remove_repositories_in_the_worktree() -> crate::Result {
+        let (repo, _tmp) = repo_rw("old-form")?;
+        let err = lib(&repo)?.deinit().unwrap_err();
+        assert!(matches!(err, gix::submodule::deinit::Error::OldForm { .. }), "{err:?}");
+        assert!(lib(&repo)?.work_dir()?.join("lib.txt").is_file());
+        Ok(())
+    }
+
+    #[test]
+    fn move_to_changes_path_and_gitlink_and_keeps_the_repository_connected() -> crate::Result {
+        let (repo, _tmp) = repo_rw("checked-out")?;
+        let sm = lib(&repo)?;
+        let id = sm.index_id()?.expect("gitlink");
+        sm.move_to("libs/lib".into())?;
+
+        let workdir = repo.workdir().expect("non-bare");
+        assert!(!workdir.join("lib").exists());
+        assert!(workdir.join("libs/lib/lib.txt").is_file());
+
+        let repo = gix::open_opts(workdir, crate::util::restricted())?;
+        let index = repo.open_index()?;
+        assert!(index.entry_by_path("lib".into()).is_none());
+        let entry = index.entry_by_path("libs/lib".into()).expect("gitlink was moved");
+        assert_eq!(entry.mode, gix::index::entry::Mode::COMMIT);
+        assert_eq!(entry.id, id);
+        let modules_id = index.entry_by_path(".gitmodules".into()).expect("staged").id;
+        assert_eq!(
+            repo.find_object(modules_id)?.data.as_bstr(),
+            std::fs::read(workdir.join(".gitmodules"))?.as_bstr(),
+            "the changed .gitmodules file is staged"
+        );
+
+        let sm = lib(&repo)?;
+        assert_eq!(sm.path()?.as_ref(), "libs/lib");
+        let sm_repo = sm.open()?.expect("present");
+        assert_eq!(
+            gix::path::realpath(sm_repo.workdir().expect("non-bare"))?,
+            gix::path::realpath(workdir.join("libs/lib"))?
+        );
+        assert!(!sm_repo.is_dirty()?, "the worktree is still usable");
+        Ok(())
+    }
+
+    #[test]
+    fn move_to_validates_the_destination() -> crate::Result {
+        let (repo, _tmp) = repo_rw("checked-out")?;
+        let sm = lib(&repo)?;
+        for path in ["../outside", "a/../../b", ".git/modules", ""] {
+            assert!(
+                matches!(
+                    sm.move_to(path.into()),
+                    Err(gix::submodule::move_to::Error::Modify(
+                        gix::submodule::modify::Error::InvalidPath { .. }
+                    ))
+                ),
+                "{path:?} must be rejected"
+            );
+        }
+        assert!(matches!(
+            sm.move_to("super.txt".into()),
+            Err(gix::submodule::move_to::Error::DestinationExists { .. })
+        ));
+        assert!(sm.work_dir()?.join("lib.txt").is_file(), "nothing was changed");
+        Ok(())
+    }
+
+    #[test]
+    fn remove_drops_worktree_configuration_and_gitlink() -> crate::Result {
+        let (repo, _tmp) = repo_rw("checked-out")?;
+        let sm = lib(&repo)?;
+        let git_dir = sm.git_dir();
+        sm.remove()?;
+
+        let workdir = repo.workdir().expect("non-bare");
+        assert!(!workdir.join("lib").exists());
+        assert!(git_dir.is_dir(), "like in Git, the repository is kept");
+        assert_eq!(std::fs::read(workdir.join(".gitmodules"))?.as_bstr(), "");
+
+        let repo = gix::open_opts(workdir, crate::util::restricted())?;
+        assert!(repo.config_snapshot().string("submodule.lib.url").is_none());
+        let index = repo.open_index()?;
+        assert!(index.entry_by_path("lib".into()).is_none());
+        assert!(index.entry_by_path(".gitmodules".into()).is_some());
+        assert_eq!(repo.submodules()?.expect(".gitmodules is present").count(), 0);
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "blocking-network-client", feature = "worktree-mutation"))]
+mod add {
+    use std::sync::atomic::AtomicBool;
+
+    use gix::bstr::ByteSlice;
+
+    use crate::submodule::repo_rw;
+
+    fn add(
+        repo: &gix::Repository,
+        url: &str,
+        path: &str,
+        name: Option<&str>,
+    ) -> Result<gix::submodule::add::Outcome, gix::submodule::add::Error> {
+        repo.add_submodule(
+            url.into(),
+            path.into(),
+            &gix::submodule::add::Options {
+                name: name.map(Into::into),
+            },
+            &mut gix::progress::Discard,
+            &AtomicBool::default(),
+        )
+    }
+
+    #[test]
+    fn clones_checks_out_and_stages() -> crate::Result {
+        let (repo, _tmp) = repo_rw("checked-out")?;
+        let outcome = add(&repo, "../nested", "deps/nested", None)?;
+        assert_eq!(outcome.name, "deps/nested", "the path is the default name");
+        assert!(outcome.url.ends_with(b"/upstream/nested"), "{}", outcome.url);
+
+        let workdir = repo.workdir().expect("non-bare");
+        assert_eq!(std::fs::read(workdir.join("deps/nested/nested.txt"))?.as_bstr(), "n1\n");
+        assert!(workdir.join("deps/nested/.git").is_file());
+        assert!(repo.common_dir().join("modules/deps/nested").is_dir());
+        assert_eq!(
+            std::fs::read(workdir.join(".gitmodules"))?.as_bstr(),
+            "[submodule \"lib\"]\n\tpath = lib\n\turl = ../lib\n[submodule \"deps/nested\"]\n\tpath = deps/nested\n\turl = ../nested\n",
+            "the URL is written as given"
+        );
+
+        let repo = gix::open_opts(workdir, crate::util::restricted())?;
+        assert_eq!(
+            repo.config_snapshot().string("submodule.deps/nested.url").as_deref(),
+            Some(outcome.url.as_bstr())
+        );
+        let sm = repo
+            .submodules()?
+            .expect("present")
+            .find(|sm| sm.name() == "deps/nested")
+            .expect("added");
+        assert!(sm.is_active()?);
+        assert_eq!(sm.index_id()?, Some(outcome.id));
+        let sm_repo = sm.open()?.expect("cloned");
+        assert_eq!(sm_repo.head_id()?, outcome.id);
+        assert!(!sm_repo.head()?.is_detached(), "the default branch is checked out");
+        assert!(!sm_repo.is_dirty()?);
+        Ok(())
+    }
+
+    #[test]
+    fn names_are_validated_and_nothing_is_changed_on_error() -> crate::Result {
+        let (repo, _tmp) = repo_rw("checked-out")?;
+        let workdir = repo.workdir().expect("non-bare");
+        let modules = std::fs::read(workdir.join(".gitmodules"))?;
+        for name in ["../../hooks", "x/../../y", ".."] {
+            let err = add(&repo, "../nested", "nested", Some(name)).unwrap_err();
+            assert!(
+                matches!(
+                    err,
+                    gix::submodule::add::Error::Modify(gix::submodule::modify::Error::InvalidName(_))
+                ),
+                "{name} must be rejected as it escapes the modules directory: {err:?}"
+            );
+        }
+        assert!(matches!(
+            add(&repo, "../nested", "lib", Some("other")),
+            Err(gix::submodule::add::Error::Modify(
+                gix::submodule::modify::Error::EditModules(gix::submodule::edit::Error::PathExists { .. })
+            ))
+        ));
+        assert!(matches!(
+            add(&repo, "../nested", "super.txt", None),
+            Err(gix::submodule::add::Error::PathExists { .. })
+        ));
+        assert!(add(&repo, "../does-not-exist", "missing", None).is_err());
+        assert!(!workdir.join("missing").exists(), "the checkout is removed again");
+        assert!(!repo.common_dir().join("modules/missing").exists());
+        assert!(!workdir.join("nested").exists());
+        assert_eq!(std::fs::read(workdir.join(".gitmodules"))?, modules);
+        Ok(())
+    }
+}
+
+#[cfg(all(feature = "blocking-network-client", feature = "worktree-mutation"))]
+mod update {
+    use std::sync::atomic::AtomicBool;
+
+    use gix::{
+        bstr::ByteSlice,
+        submodule::{config::Update, update::Action},
+    };
+
+    use crate::submodule::{lib, repo_rw};
+
+    fn update(
+        repo: &gix::Repository,
+        options: gix::submodule::update::Options,
+    ) -> crate::Result<gix::submodule::update::Outcome> {
+        Ok(repo.update_submodules(&mut gix::progress::Discard, &AtomicBool::default(), &options)?)
+    }
+
+    fn with_mode(mode: Update) -> gix::submodule::update::Options {
+        gix::submodule::update::Options {
+            mode: Some(mode),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn inactive_submodules_are_ignored_unless_initialized() -> crate::Result {
+        let (repo, _tmp) = repo_rw("uninitialized")?;
+        assert!(update(&repo, Default::default())?.submodules.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn init_then_clone_recursively() -> crate::Result {
+        let (repo, _tmp) = repo_rw("uninitialized")?;
+        let target = lib(&repo)?.index_id()?.expect("gitlink");
+        let outcome = update(
+            &repo,
+            gix::submodule::update::Options {
+                init: true,
+                recursive: true,
+                jobs: Some(0),
+                ..Default::default()
+            },
+        )?;
+        assert_eq!(outcome.submodules.len(), 1);
+        let item = &outcome.submodules[0];
+        assert_eq!(item.path, "lib");
+        assert!(item
+            .initialized_url
+            .as_ref()
+            .is_some_and(|url| url.ends_with(b"/upstream/lib")));
+        assert!(item.cloned);
+        assert_eq!(item.action, Action::CheckedOut(target));
+        assert_eq!(item.submodules.len(), 1, "the nested submodule was handled as well");
+        assert_eq!(item.submodules[0].path, "nested");
+        assert!(item.submodules[0].cloned);
+
+        let workdir = repo.workdir().expect("non-bare");
+        assert_eq!(std::fs::read(workdir.join("lib/lib.txt"))?.as_bstr(), "c2\n");
+        assert_eq!(std::fs::read(workdir.join("lib/nested/nested.txt"))?.as_bstr(), "n1\n");
+        assert!(
+            workdir.join("lib/.git").is_file(),
+            "the repository is kept in the superproject"
+        );
+
+        let sm = lib(&repo)?;
+        assert!(sm.git_dir().is_dir());
+        let sm_repo = sm.open()?.expect("cloned");
+        assert!(sm_repo.head()?.is_detached());
+        assert_eq!(sm_repo.head_id()?, target);
+        assert!(!sm_repo.is_dirty()?, "the index matches the checkout");
+        Ok(())
+    }
+
+    #[test]
+    fn checkout_detaches_head() -> crate::Result {
+        let (repo, _tmp) = repo_rw("checked-out")?;
+        let target = lib(&repo)?.index_id()?.expect("gitlink");
+        let outcome = update(&repo, Default::default())?;
+        assert_eq!(outcome.submodules[0].action, Action::CheckedOut(target));
+        assert!(!outcome.submodules[0].cloned);
+
+        let sm_repo = lib(&repo)?.open()?.expect("present");
+        assert_eq!(sm_repo.head_id()?, target);
+        assert_eq!(
+            std::fs::read(sm_repo.workdir().expect("non-bare").join("lib.txt"))?.as_bstr(),
+            "c2\n"
+        );
+        assert!(!sm_repo.is_dirty()?);
+
+        let outcome = update(&repo, Default::default())?;
+        assert_eq!(outcome.submodules[0].action, Action::UpToDate);
+        Ok(())
+    }
+
+    #[test]
+    fn checkout_refuses_to_overwrite_local_changes() -> crate::Result {
+        let (repo, _tmp) = repo_rw("modified")?;
+        let head = lib(&repo)?.open()?.expect("present").head_id()?.detach();
+        let err = update(&repo, Default::default()).unwrap_err();
+        assert!(err.to_string().contains("Failed to update submodule 'lib'"), "{err}");
+        assert_eq!(
+            lib(&repo)?.open()?.expect("present").head_id()?,
+            head,
+            "nothing changed"
+        );
+        let sm_repo = lib(&repo)?.open()?.expect("present");
+        assert_eq!(
+            std::fs::read(sm_repo.workdir().expect("non-bare").join("lib.txt"))?.as_bstr(),
+            "modified\n"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn none_skips_the_submodule() -> crate::Result {
+        let (repo, _tmp) = repo_rw("checked-out")?;
+        let head = lib(&repo)?.open()?.expect("present").head_id()?.detach();
+        let outcome = update(&repo, with_mode(Update::None))?;
+        assert_eq!(outcome.submodules[0].action, Action::Skipped);
+        assert_eq!(lib(&repo)?.open()?.expect("present").head_id()?, head);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "merge")]
+    fn rebase_replays_local_commits_onto_the_recorded_commit() -> crate::Result {
+        let (repo, _tmp) = repo_rw("local-commit")?;
+        let target = lib(&repo)?.index_id()?.expect("gitlink");
+        let outcome = update(&repo, with_mode(Update::Rebase))?;
+        let Action::Rebased(id) = outcome.submodules[0].action else {
+            panic!("unexpected action: {:?}", outcome.submodules[0].action);
+        };
+
+        let sm_repo = lib(&repo)?.open()?.expect("present");
+        let head = sm_repo.head()?;
+        assert_eq!(
+            head.referent_name().map(|name| name.as_bstr().to_owned()),
+            Some("refs/heads/local".into()),
+            "the branch was moved"
+        );
+        assert_eq!(sm_repo.head_id()?, id);
+        let commit = sm_repo.find_commit(id)?;
+        assert_eq!(commit.parent_ids().collect::<Vec<_>>(), [target]);
+        assert_eq!(commit.message_raw()?, "local\n");
+        let workdir = sm_repo.workdir().expect("non-bare");
+        assert_eq!(std::fs::read(workdir.join("lib.txt"))?.as_bstr(), "c2\n");
+        assert_eq!(std::fs::read(workdir.join("local.txt"))?.as_bstr(), "local\n");
+        assert!(!sm_repo.is_dirty()?);
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "merge")]
+    fn merge_creates_a_merge_commit() -> crate::Result {
+        let (repo, _tmp) = repo_rw("local-commit")?;
+        let target = lib(&repo)?.index_id()?.expect("gitlink");
+        let head = lib(&repo)?.open()?.expect("present").head_id()?.detach();
+        let outcome = update(&repo, with_mode(Update::Merge))?;
+        let Action::Merged(id) = outcome.submodules[0].action else {
+            panic!("unexpected action: {:?}", outcome.submodules[0].action);
+        };
+
+        let sm_repo = lib(&repo)?.open()?.expect("present");
+        assert_eq!(sm_repo.head_id()?, id);
+        assert_eq!(
+            sm_repo.find_commit(id)?.parent_ids().collect::<Vec<_>>(),
+            [head, target]
+        );
+        let workdir = sm_repo.workdir().expect("non-bare");
+        assert_eq!(std::fs::read(workdir.join("lib.txt"))?.as_bstr(), "c2\n");
+        assert_eq!(std::fs::read(workdir.join("local.txt"))?.as_bstr(), "local\n");
+
+        let outcome = update(&repo, with_mode(Update::Merge))?;
+        assert_eq!(
+            outcome.submodules[0].action,
+            Action::UpToDate,
+            "the commit is contained"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn fetch_on_demand_only_fetches_missing_commits() -> crate::Result {
+        let (repo, _tmp) = repo_rw("checked-out")?;
+        let fetched = repo
+            .find_default_remote(gix::remote::Direction::Fetch)
+            .expect("origin")?
+            .connect(gix::remote::Direction::Fetch)?
+            .prepare_fetch(gix::progress::Discard, Default::default())?
+            .receive(gix::progress::Discard, &AtomicBool::default())?;
+        let new_tip = repo
+            .find_reference("refs/remotes/origin/main")?
+            .peel_to_commit()?
+            .tree()?
+            .lookup_entry_by_path("lib")?
+            .expect("gitlink")
+            .object_id();
+        assert!(!lib(&repo)?.open()?.expect("present").has_object(new_tip));
+
+        let fetch = |options: &gix::submodule::fetch::Options| {
+            repo.fetch_submodules(
+                Some(&fetched),
+                &mut gix::progress::Discard,
+                &AtomicBool::default(),
+                options,
+            )
+        };
+        let never = fetch(&gix::submodule::fetch::Options {
+            recurse: Some(gix::submodule::config::FetchRecurse::Never),
+            jobs: None,
+        })?;
+        assert!(never.submodules.is_empty());
+
+        let outcome = fetch(&Default::default())?;
+        assert_eq!(outcome.submodules.len(), 1);
+        assert!(outcome.submodules[0].outcome.is_some(), "the commit was missing");
+        assert!(lib(&repo)?.open()?.expect("present").has_object(new_tip));
+
+        let outcome = fetch(&Default::default())?;
+        assert!(
+            outcome.submodules[0].outcome.is_none(),
+            "nothing to do as the commit is present now"
+        );
+        Ok(())
+    }
+}
@@ -275,6 +275,7 @@ pub fn main() -> Result<()> {
         ),
         Subcommands::Diff(crate::plumbing::options::diff::Platform { cmd }) => match cmd {
             crate::plumbing::options::diff::SubCommands::Tree {
+                opts,
                 old_treeish,
                 new_treeish,
             } => prepare_and_run(
@@ -285,10 +286,11 @@ pub fn main() -> Result<()> {
                 progress_keep_open,
                 None,
                 move |_progress, out, _err| {
-                    core::repository::diff::tree(repository(Mode::Lenient)?, out, old_treeish, new_treeish)
+                    core::repository::diff::tree(repository(Mode::Lenient)?, out, old_treeish, new_treeish, opts.into())
                 },
             ),
             crate::plumbing::options::diff::SubCommands::File {
+                opts,
                 old_revspec,
                 new_revspec,
             } => prepare_and_run(
@@ -299,18 +301,87 @@ pub fn main() -> Result<()> {
                 progress_keep_open,
                 None,
                 move |_progress, out, _err| {
-                    core::repository::diff::file(repository(Mode::Lenient)?, out, old_revspec, new_revspec)
+                    core::repository::diff::file(repository(Mode::Lenient)?, out, old_revspec, new_revspec, opts.into())
                 },
             ),
         },
-        Subcommands::Log(crate::plumbing::options::log::Platform { pathspec }) => prepare_and_run(
+        Subcommands::Log(crate::plumbing::options::log::Platform {
+            pathspec,
+            full_history,
+            simplify_merges,
+            sparse,
+            author,
+            committer,
+            grep,
+            all_match,
+            invert_grep,
+            regexp_ignore_case,
+            since,
+            until,
+            min_parents,
+            max_parents,
+            no_merges,
+            max_count,
+            skip,
+            pretty,
+            oneline,
+            date,
+            abbrev_commit,
+            decorate,
+            use_mailmap,
+            no_mailmap,
+            graph,
+            color,
+        }) => prepare_and_run(
             "log",
             trace,
             verbose,
             progress,
             progress_keep_open,
             None,
-            move |_progress, out, _err| core::repository::log::log(repository(Mode::Lenient)?, out, pathspec),
+            move |_progress, out, _err| {
+                core::repository::log::log(
+                    repository(Mode::Lenient)?,
+                    out,
+                    pathspec,
+                    gix::revision::walk::Simplification {
+                        full_history,
+                        simplify_merges,
+                        sparse,
+                        ..Default::default()
+                    },
+                    gix::revision::walk::Filter {
+                        author,
+                        committer,
+                        grep,
+                        all_match,
+                        invert_grep,
+                        ignore_case: regexp_ignore_case,
+                        since: since.map(|time| time.seconds),
+                        until: until.map(|time| time.seconds),
+                        min_parents,
+                        max_parents: if no_merges { Some(1) } else { max_parents },
+                        max_count,
+                        skip,
+                    },
+                    core::repository::log::Format {
+                        pretty,
+                        oneline,
+                        date,
+                        abbrev_commit,
+                        decorate,
+                        mailmap: if use_mailmap {
+                            Some(true)
+                        } else if no_mailmap {
+                            Some(false)
+                        } else {
+                            None
+                        },
+                        graph,
+                        color,
+                    },
+                )
+            },
         ),
         Subcommands::Worktree(crate::plumbing::options::worktree::Platform { cmd }) => match cmd {
             crate::plumbing::options::worktree::SubCommands::List => prepare_and_run(
@@ -322,6 +393,106 @@ pub fn main() -> Result<()> {
                 None,
                 move |_progress, out, _err| core::repository::worktree::list(repository(Mode::Lenient)?, out, format),
             ),
+            crate::plumbing::options::worktree::SubCommands::Add {
+                new_branch,
+                detach,
+                no_checkout,
+                lock,
+                reason,
+                force,
+                path,
+                commitish,
+            } => prepare_and_run(
+                "worktree-add",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |progress, out, _err| {
+                    core::repository::worktree::add(
+                        repository(Mode::Lenient)?,
+                        progress,
+                        out,
+                        core::repository::worktree::add::Options {
+                            format,
+                            new_branch,
+                            detach,
+                            no_checkout,
+                            lock: lock.then_some(reason),
+                            force,
+                            path,
+                            commitish,
+                        },
+                    )
+                },
+            ),
+            crate::plumbing::options::worktree::SubCommands::Remove { force, worktree } => prepare_and_run(
+                "worktree-remove",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, _out, _err| {
+                    core::repository::worktree::remove(repository(Mode::Lenient)?, &worktree, force)
+                },
+            ),
+            crate::plumbing::options::worktree::SubCommands::Move {
+                force,
+                worktree,
+                new_path,
+            } => prepare_and_run(
+                "worktree-move",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    core::repository::worktree::move_to(repository(Mode::Lenient)?, &worktree, &new_path, force, out)
+                },
+            ),
+            crate::plumbing::options::worktree::SubCommands::Lock { reason, worktree } => prepare_and_run(
+                "worktree-lock",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, _out, _err| {
+                    core::repository::worktree::lock(repository(Mode::Lenient)?, &worktree, reason)
+                },
+            ),
+            crate::plumbing::options::worktree::SubCommands::Unlock { worktree } => prepare_and_run(
+                "worktree-unlock",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, _out, _err| core::repository::worktree::unlock(repository(Mode::Lenient)?, &worktree),
+            ),
+            crate::plumbing::options::worktree::SubCommands::Prune { dry_run, expire } => prepare_and_run(
+                "worktree-prune",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    core::repository::worktree::prune(repository(Mode::Lenient)?, dry_run, expire, out)
+                },
+            ),
+            crate::plumbing::options::worktree::SubCommands::Repair { paths } => prepare_and_run(
+                "worktree-repair",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| core::repository::worktree::repair(repository(Mode::Lenient)?, &paths, out),
+            ),
         },
         Subcommands::IsClean | Subcommands::IsChanged => {
             let mode = if matches!(cmd, Subcommands::IsClean) {
@@ -457,6 +628,125 @@ pub fn main() -> Result<()> {
                     )
                 },
             ),
+            crate::plumbing::options::submodule::Subcommands::Init { names } => prepare_and_run(
+                "submodule-init",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| core::repository::submodule::init(repository(Mode::Lenient)?, out, names),
+            ),
+            #[cfg(feature = "gitoxide-core-blocking-client")]
+            crate::plumbing::options::submodule::Subcommands::Update {
+                init,
+                recursive,
+                checkout,
+                rebase,
+                merge,
+                no_fetch,
+                jobs,
+                names,
+            } => prepare_and_run(
+                "submodule-update",
+                trace,
+                auto_verbose,
+                progress,
+                progress_keep_open,
+                core::repository::submodule::update::PROGRESS_RANGE,
+                move |progress, out, _err| {
+                    use gix::submodule::config::Update;
+                    core::repository::submodule::update::update(
+                        repository(Mode::LenientWithGitInstallConfig)?,
+                        progress,
+                        out,
+                        core::repository::submodule::update::Options {
+                            names,
+                            init,
+                            recursive,
+                            mode: if checkout {
+                                Some(Update::Checkout)
+                            } else if rebase {
+                                Some(Update::Rebase)
+                            } else if merge {
+                                Some(Update::Merge)
+                            } else {
+                                None
+                            },
+                            no_fetch,
+                            jobs,
+                        },
+                    )
+                },
+            ),
+            crate::plumbing::options::submodule::Subcommands::Sync { names } => prepare_and_run(
+                "submodule-sync",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| core::repository::submodule::sync(repository(Mode::Lenient)?, out, names),
+            ),
+            crate::plumbing::options::submodule::Subcommands::Absorbgitdirs { names } => prepare_and_run(
+                "submodule-absorbgitdirs",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    core::repository::submodule::absorb_git_dirs(repository(Mode::Lenient)?, out, names)
+                },
+            ),
+            #[cfg(feature = "gitoxide-core-blocking-client")]
+            crate::plumbing::options::submodule::Subcommands::Add { name, url, path } => prepare_and_run(
+                "submodule-add",
+                trace,
+                auto_verbose,
+                progress,
+                progress_keep_open,
+                core::repository::submodule::add::PROGRESS_RANGE,
+                move |progress, out, _err| {
+                    core::repository::submodule::add::add(
+                        repository(Mode::LenientWithGitInstallConfig)?,
+                        progress,
+                        out,
+                        url,
+                        path,
+                        name,
+                    )
+                },
+            ),
+            crate::plumbing::options::submodule::Subcommands::Deinit { names } => prepare_and_run(
+                "submodule-deinit",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| core::repository::submodule::deinit(repository(Mode::Lenient)?, out, names),
+            ),
+            crate::plumbing::options::submodule::Subcommands::Move { name, path } => prepare_and_run(
+                "submodule-move",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| {
+                    core::repository::submodule::move_to(repository(Mode::Lenient)?, out, name, path)
+                },
+            ),
+            crate::plumbing::options::submodule::Subcommands::Remove { names } => prepare_and_run(
+                "submodule-remove",
+                trace,
+                verbose,
+                progress,
+                progress_keep_open,
+                None,
+                move |_progress, out, _err| core::repository::submodule::remove(repository(Mode::Lenient)?, out, names),
+            ),
         },
         #[cfg(feature = "gitoxide-core-tools-archive")]
         Subcommands::Archive(crate::plumbing::options::archive::Platform {
@@ -605,6 +895,8 @@ pub fn main() -> Result<()> {
             remote,
             shallow,
             directory,
+            recurse_submodules,
+            bundle_uri,
         }) => {
             let opts = core::repository::clone::Options {
                 format,
@@ -613,6 +905,8 @@ pub fn main() -> Result<()> {
                 no_tags,
                 ref_name,
                 shallow: shallow.into(),
+                recurse_submodules,
+                bundle_uri: bundle_uri.map(gix::path::os_string_into_bstring).transpose()?,
             };
             prepare_and_run(
                 "clone",
@@ -632,6 +926,8 @@ pub fn main() -> Result<()> {
             open_negotiation_graph,
             remote,
             shallow,
+            recurse_submodules,
+            jobs,
             ref_spec,
         }) => {
             let opts = core::repository::fetch::Options {
@@ -643,6 +939,8 @@ pub fn main() -> Result<()> {
                 open_negotiation_graph,
                 shallow: shallow.into(),
                 ref_specs: ref_spec,
+                recurse_submodules: recurse_submodules.map(Into::into),
+                jobs,
             };
             prepare_and_run(
                 "fetch",
@@ -657,12 +955,30 @@ pub fn main() -> Result<()> {
             )
         }
         Subcommands::ConfigTree => show_progress(),
+        Subcommands::Credential(credential::Subcommands::Helper(cmd)) => {
+            use core::repository::credential_helper::Kind;
+            let (kind, action) = match cmd {
+                credential::helper::Subcommands::Store { file, action } => (Kind::Store { file }, action),
+                credential::helper::Subcommands::Cache {
+                    timeout,
+                    socket,
+                    action,
+                } => (Kind::Cache { timeout, socket }, action),
+            };
+            core::repository::credential_helper::function(kind, action)
+        }
+        Subcommands::Credential(credential::Subcommands::CacheDaemon { socket }) => {
+            core::repository::credential_helper::cache_daemon(&socket)
+        }
         Subcommands::Credential(cmd) => core::repository::credential(
             repository(Mode::StrictWithGitInstallConfig).ok(),
             match cmd {
                 credential::Subcommands::Fill => gix::credentials::program::main::Action::Get,
                 credential::Subcommands::Approve => gix::credentials::program::main::Action::Store,
                 credential::Subcommands::Reject => gix::credentials::program::main::Action::Erase,
+                credential::Subcommands::Helper(_) | credential::Subcommands::CacheDaemon { .. } => {
+                    unreachable!("handled above")
+                }
             },
         ),
         #[cfg(any(feature = "gitoxide-core-async-client", feature = "gitoxide-core-blocking-client"))]
@@ -1429,14 +1745,35 @@ pub fn main() -> Result<()> {
                 move |_progress, out, err| core::repository::odb::info(repository(Mode::Strict)?, format, out, err),
             ),
         },
-        Subcommands::Fsck(fsck::Platform { spec }) => prepare_and_run(
+        Subcommands::Fsck(fsck::Platform {
+            unreachable,
+            no_dangling,
+            lost_found,
+            connectivity_only,
+            spec,
+        }) => prepare_and_run(
             "fsck",
             trace,
             auto_verbose,
             progress,
             progress_keep_open,
-            None,
-            move |_progress, out, _err| core::repository::fsck(repository(Mode::Strict)?, spec, out),
+            core::repository::fsck::PROGRESS_RANGE,
+            move |progress, out, err| {
+                core::repository::fsck(
+                    repository(Mode::Strict)?,
+                    spec,
+                    progress,
+                    out,
+                    err,
+                    &should_interrupt,
+                    core::repository::fsck::Options {
+                        unreachable,
+                        dangling: !no_dangling,
+                        lost_found,
+                        connectivity_only,
+                    },
+                )
+            },
         ),
         Subcommands::Mailmap(cmd) => match cmd {
             mailmap::Subcommands::Entries => prepare_and_run(
@@ -1620,6 +1957,15 @@ pub fn main() -> Result<()> {
             file,
             ranges,
             since,
+            ignore_rev,
+            ignore_revs_file,
+            worktree,
+            moves,
+            copies,
+            copy_score,
+            porcelain,
+            line_porcelain,
+            incremental,
         } => prepare_and_run(
             "blame",
             trace,
@@ -1628,18 +1974,42 @@ pub fn main() -> Result<()> {
             progress_keep_open,
             None,
             move |_progress, out, err| {
-                let repo = repository(Mode::Lenient)?;
-                let diff_algorithm = repo.diff_algorithm()?;
-
+                use core::repository::blame::Format;
+                let format = if porcelain {
+                    Format::Porcelain
+                } else if line_porcelain {
+                    Format::LinePorcelain
+                } else if incremental {
+                    Format::Incremental
+                } else {
+                    Format::Human
+                };
+                let moves_and_copies = (moves.is_some() || copies > 0).then(|| {
+                    use gix::blame::{Copies, CopySource, MovesAndCopies};
+                    let defaults = MovesAndCopies::default();
+                    MovesAndCopies {
+                        move_score: moves.unwrap_or(defaults.move_score),
+                        copies: (copies > 0).then(|| Copies {
+                            source: match copies {
+                                1 => CopySource::FromSetOfModifiedFiles,
+                                2 => CopySource::FromSetOfModifiedFilesAndAllSourcesIfCreated,
+                                _ => CopySource::FromAllSources,
+                            },
+                            score: copy_score.unwrap_or(Copies::default().score),
+                        }),
+                    }
+                });
                 core::repository::blame::blame_file(
-                    repo,
+                    repository(Mode::Lenient)?,
                     &file,
-                    gix::blame::Options {
-                        diff_algorithm,
-                        ranges: gix::blame::BlameRanges::from_one_based_inclusive_ranges(ranges)?,
+                    core::repository::blame::Options {
+                        format,
+                        ranges,
                         since,
-                        rewrites: Some(gix::diff::Rewrites::default()),
-                        debug_track_path: false,
+                        ignore_revs: ignore_rev,
+                        ignore_revs_files: ignore_revs_file,
+                        worktree,
+                        moves_and_copies,
                     },
                     out,
                     statistics.then_some(err),
@@ -95,7 +95,7 @@ pub enum Subcommands {
     /// Interact with the object database.
     #[clap(subcommand)]
     Odb(odb::Subcommands),
-    /// Check for missing objects.
+    /// Check the connectivity and validity of all objects, and list dangling ones.
     Fsck(fsck::Platform),
     /// Interact with tree objects.
     #[clap(subcommand)]
@@ -179,6 +179,37 @@ pub enum Subcommands {
         /// Don't consider commits before the given date.
         #[clap(long,  value_parser=AsTime, value_name = "DATE")]
         since: Option<gix::date::Time>,
+        /// Attribute the changes of the given revision to its parents, in addition to those listed in `blame.ignoreRevsFile`.
+        #[clap(long, value_name = "REV")]
+        ignore_rev: Vec<gix::bstr::BString>,
+        /// Ignore the revisions listed in the given file. An empty path clears the revisions of all files before it.
+        #[clap(long, value_name = "FILE")]
+        ignore_revs_file: Vec<std::path::PathBuf>,
+        /// Blame the file as it is in the worktree, and attribute uncommitted changes to the null id.
+        #[clap(long, short = 'w')]
+        worktree: bool,
+        /// Attribute lines that were moved within the file to the commit that introduced them, like `git blame -M`.
+        ///
+        /// Moved lines must have more alphanumeric characters than the given score, which defaults to 20.
+        #[clap(short = 'M', value_name = "SCORE", num_args = 0..=1, require_equals = true, default_missing_value = "20")]
+        moves: Option<u32>,
+        /// Also attribute lines that were copied from files modified in the same commit, like `git blame -C`.
+        ///
+        /// Given twice, look in all files if the blamed file was created, and given three times, always look in all files.
+        #[clap(short = 'C', action = clap::ArgAction::Count)]
+        copies: u8,
+        /// The amount of alphanumeric characters copied lines must exceed, which defaults to 40.
+        #[clap(long, value_name = "SCORE", requires = "copies")]
+        copy_score: Option<u32>,
+        /// Show the details of each commit once, in a format suitable for consumption by scripts like `git blame --porcelain`.
+        #[clap(long, short = 'p', conflicts_with_all = ["line_porcelain", "incremental"])]
+        porcelain: bool,
+        /// Like `--porcelain`, but show the details of the commit for each line.
+        #[clap(long, conflicts_with = "incremental")]
+        line_porcelain: bool,
+        /// Show hunks as soon as the commit that introduced them is known, like `git blame --incremental`.
+        #[clap(long)]
+        incremental: bool,
     },
     /// Generate shell completions to stdout or a directory.
     #[clap(visible_alias = "generate-completions", visible_alias = "shell-completions")]
@@ -337,6 +368,8 @@ pub mod merge_base {
 }
 
 pub mod worktree {
+    use std::path::PathBuf;
+
     #[derive(Debug, clap::Parser)]
     #[command(about = "Commands for handling worktrees")]
     pub struct Platform {
@@ -348,6 +381,76 @@ pub mod worktree {
     pub enum SubCommands {
         /// List all worktrees, along with some accompanying information.
         List,
+        /// Create a new linked worktree and check out a branch or commit into it.
+        Add {
+            /// Create a new branch with the given name and check it out.
+            #[clap(long, short = 'b', conflicts_with = "detach")]
+            new_branch: Option<String>,
+            /// Detach `HEAD` in the new worktree.
+            #[clap(long)]
+            detach: bool,
+            /// Don't check out any files.
+            #[clap(long)]
+            no_checkout: bool,
+            /// Lock the new worktree.
+            #[clap(long)]
+            lock: bool,
+            /// The reason for locking the new worktree.
+            #[clap(long, requires = "lock")]
+            reason: Option<String>,
+            /// Check out a branch even if it's checked out elsewhere, or reset the branch to create.
+            #[clap(long, short = 'f')]
+            force: bool,
+            /// The location of the new worktree, which must not exist or be empty.
+            path: PathBuf,
+            /// The branch or commit to check out, defaulting to `HEAD`.
+            commitish: Option<String>,
+        },
+        /// Remove a linked worktree.
+        Remove {
+            /// Remove the worktree even if it has changes, and specify it twice to remove it even if it's locked.
+            #[clap(long, short = 'f', action = clap::ArgAction::Count)]
+            force: u8,
+            /// The id or location of the worktree.
+            worktree: String,
+        },
+        /// Move a linked worktree to a new location.
+        Move {
+            /// Specify it twice to move a locked worktree.
+            #[clap(long, short = 'f', action = clap::ArgAction::Count)]
+            force: u8,
+            /// The id or location of the worktree.
+            worktree: String,
+            /// The new location, or an existing directory to move the worktree into.
+            new_path: PathBuf,
+        },
+        /// Lock a linked worktree so it can't be pruned, moved or removed.
+        Lock {
+            /// The reason for locking the worktree.
+            #[clap(long)]
+            reason: Option<String>,
+            /// The id or location of the worktree.
+            worktree: String,
+        },
+        /// Unlock a linked worktree.
+        Unlock {
+            /// The id or location of the worktree.
+            worktree: String,
+        },
+        /// Remove information about linked worktrees whose checkout is missing.
+        Prune {
+            /// Only print what would be pruned.
+            #[clap(long, short = 'n')]
+            dry_run: bool,
+            /// Only prune worktrees older than the given time, like `3.months.ago`.
+            #[clap(long)]
+            expire: Option<String>,
+        },
+        /// Repair the links between worktrees and the repository, after either of them was moved manually.
+        Repair {
+            /// The new locations of worktrees that were moved.
+            paths: Vec<PathBuf>,
+        },
     }
 }
 
@@ -534,10 +637,108 @@ pub mod diff {
         pub cmd: SubCommands,
     }
 
+    #[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, clap::ValueEnum)]
+    pub enum WordDiff {
+        /// Enclose removed words in `[-` and `-]`, and added ones in `{+` and `+}`.
+        #[default]
+        Plain,
+        /// Only highlight changed words with colors.
+        Color,
+        /// A line-based format for use by scripts.
+        Porcelain,
+    }
+
+    impl From<WordDiff> for gix::diff::patch::word_diff::Mode {
+        fn from(value: WordDiff) -> Self {
+            match value {
+                WordDiff::Plain => gix::diff::patch::word_diff::Mode::Plain,
+                WordDiff::Color => gix::diff::patch::word_diff::Mode::Color,
+                WordDiff::Porcelain => gix::diff::patch::word_diff::Mode::Porcelain,
+            }
+        }
+    }
+
+    #[derive(Debug, clap::Parser)]
+    pub struct SharedOptions {
+        /// Print a patch of all changes.
+        #[clap(long, short = 'p')]
+        pub patch: bool,
+        /// Print the amount of changes per file along with a graph.
+        #[clap(long)]
+        pub stat: bool,
+        /// Print the amount of added and removed lines per file in a format for use by scripts.
+        #[clap(long)]
+        pub numstat: bool,
+        /// Print only the total amount of changed files, added and removed lines.
+        #[clap(long)]
+        pub shortstat: bool,
+        /// The amount of unchanged lines to show around each change.
+        #[clap(long, short = 'U', value_name = "LINES")]
+        pub unified: Option<u32>,
+        /// The amount of unchanged lines between changes that may additionally be shown to merge their hunks.
+        #[clap(long, value_name = "LINES", default_value_t = 0)]
+        pub inter_hunk_context: u32,
+        /// Ignore all whitespace when comparing lines.
+        #[clap(long, short = 'w', help_heading = Some("WHITESPACE"))]
+        pub ignore_all_space: bool,
+        /// Ignore changes in the amount of whitespace, and whitespace at the end of lines.
+        #[clap(long, short = 'b', help_heading = Some("WHITESPACE"))]
+        pub ignore_space_change: bool,
+        /// Ignore whitespace at the end of lines.
+        #[clap(long, help_heading = Some("WHITESPACE"))]
+        pub ignore_space_at_eol: bool,
+        /// Hide changes that only add or remove blank lines.
+        #[clap(long, help_heading = Some("WHITESPACE"))]
+        pub ignore_blank_lines: bool,
+        /// Show changed words instead of changed lines.
+        #[clap(long, value_name = "MODE", num_args = 0..=1, default_missing_value = "plain")]
+        pub word_diff: Option<WordDiff>,
+        /// The regular expression that matches a word, with sequences of non-whitespace characters being the default.
+        #[clap(long, value_name = "REGEX")]
+        pub word_diff_regex: Option<String>,
+        /// When to use colors, one of `always`, `never` or `auto`. Defaults to `color.diff` or `color.ui` if unset.
+        #[clap(long, value_name = "WHEN", num_args = 0..=1, default_missing_value = "always", value_parser = crate::shared::AsBString)]
+        pub color: Option<BString>,
+        /// How to detect and highlight moved lines, one of `no`, `default`, `plain`, `blocks` or `zebra`.
+        /// Defaults to `diff.colorMoved` if unset.
+        #[clap(long, value_name = "MODE", num_args = 0..=1, default_missing_value = "default", value_parser = crate::shared::AsBString)]
+        pub color_moved: Option<BString>,
+    }
+
+    impl From<SharedOptions> for gitoxide_core::repository::diff::Options {
+        fn from(value: SharedOptions) -> Self {
+            use gix::diff::blob::whitespace::Mode;
+            gitoxide_core::repository::diff::Options {
+                patch: value.patch,
+                stat: value.stat,
+                numstat: value.numstat,
+                shortstat: value.shortstat,
+                context_lines: value.unified,
+                inter_hunk_context: value.inter_hunk_context,
+                whitespace: if value.ignore_all_space {
+                    Mode::IgnoreAll
+                } else if value.ignore_space_change {
+                    Mode::IgnoreChange
+                } else if value.ignore_space_at_eol {
+                    Mode::IgnoreAtEol
+                } else {
+                    Mode::Exact
+                },
+                ignore_blank_lines: value.ignore_blank_lines,
+                word_diff: value.word_diff.map(Into::into),
+                word_diff_regex: value.word_diff_regex,
+                color: value.color,
+                color_moved: value.color_moved,
+            }
+        }
+    }
+
     #[derive(Debug, clap::Subcommand)]
     pub enum SubCommands {
         /// Diff two trees.
         Tree {
+            #[clap(flatten)]
+            opts: SharedOptions,
             /// A rev-spec representing the 'before' or old tree.
             #[clap(value_parser = crate::shared::AsBString)]
             old_treeish: BString,
@@ -547,6 +748,8 @@ pub mod diff {
         },
         /// Diff two versions of a file.
         File {
+            #[clap(flatten)]
+            opts: SharedOptions,
             /// A rev-spec representing the 'before' or old state of the file, like '@~100:file'
             #[clap(value_parser = crate::shared::AsBString)]
             old_revspec: BString,
@@ -566,6 +769,81 @@ pub mod log {
         /// The git path specification to show a log for.
         #[clap(value_parser = crate::shared::AsBString)]
         pub pathspec: Option<BString>,
+        /// Show all commits that change the path, instead of following only the parents a merge didn't change it in.
+        #[clap(long)]
+        pub full_history: bool,
+        /// Like `--full-history`, but remove merges that aren't needed to connect the remaining commits.
+        #[clap(long)]
+        pub simplify_merges: bool,
+        /// Also show commits that don't change the path, while still following only the parents of interest.
+        #[clap(long)]
+        pub sparse: bool,
+        /// Only show commits whose author matches one of the given patterns.
+        #[clap(long, value_parser = crate::shared::AsBString)]
+        pub author: Vec<BString>,
+        /// Only show commits whose committer matches one of the given patterns.
+        #[clap(long, value_parser = crate::shared::AsBString)]
+        pub committer: Vec<BString>,
+        /// Only show commits whose message matches one of the given patterns.
+        #[clap(long, value_parser = crate::shared::AsBString)]
+        pub grep: Vec<BString>,
+        /// Only show commits whose message matches all `--grep` patterns.
+        #[clap(long)]
+        pub all_match: bool,
+        /// Only show commits whose message doesn't match the `--grep` patterns.
+        #[clap(long)]
+        pub invert_grep: bool,
+        /// Match all patterns case-insensitively.
+        #[clap(long, short = 'i')]
+        pub regexp_ignore_case: bool,
+        /// Only show commits committed at or after the given date.
+        #[clap(long, value_parser = crate::shared::AsTime, value_name = "DATE")]
+        pub since: Option<gix::date::Time>,
+        /// Only show commits committed at or before the given date.
+        #[clap(long, value_parser = crate::shared::AsTime, value_name = "DATE")]
+        pub until: Option<gix::date::Time>,
+        /// Only show commits with at least the given amount of parents.
+        #[clap(long)]
+        pub min_parents: Option<usize>,
+        /// Only show commits with at most the given amount of parents.
+        #[clap(long)]
+        pub max_parents: Option<usize>,
+        /// Don't show merge commits, the same as `--max-parents 1`.
+        #[clap(long, conflicts_with = "max_parents")]
+        pub no_merges: bool,
+        /// Show at most the given amount of commits.
+        #[clap(long, short = 'n')]
+        pub max_count: Option<usize>,
+        /// Skip the given amount of commits before starting to show them.
+        #[clap(long, default_value_t = 0)]
+        pub skip: usize,
+        /// Show commits in the given format, a preset like `oneline` or `fuller`, an alias, or a template like `format:%h %s`.
+        #[clap(long, alias = "format", value_parser = crate::shared::AsBString, value_name = "FORMAT")]
+        pub pretty: Option<BString>,
+        /// Show each commit on a single line, the same as `--pretty oneline --abbrev-commit`.
+        #[clap(long)]
+        pub oneline: bool,
+        /// Show dates in the given format, like `iso`, `relative` or `format:%Y-%m-%d`.
+        #[clap(long, value_parser = crate::shared::AsBString, value_name = "FORMAT")]
+        pub date: Option<BString>,
+        /// Show abbreviated commit hashes in the headers of presets.
+        #[clap(long)]
+        pub abbrev_commit: bool,
+        /// Show the names of references pointing to each commit.
+        #[clap(long)]
+        pub decorate: bool,
+        /// Rewrite authors and committers of presets using the mailmap.
+        #[clap(long, conflicts_with = "no_mailmap")]
+        pub use_mailmap: bool,
+        /// Don't rewrite authors and committers of presets using the mailmap.
+        #[clap(long)]
+        pub no_mailmap: bool,
+        /// Draw a graph of the history next to the commits.
+        #[clap(long)]
+        pub graph: bool,
+        /// Color the output with ANSI escape codes.
+        #[clap(long)]
+        pub color: bool,
     }
 }
 
@@ -618,11 +896,41 @@ pub mod fetch {
         #[clap(long, short = 'r')]
         pub remote: Option<String>,
 
+        /// Fetch the submodules as well, either all of them, or only those whose recorded commits are missing.
+        ///
+        /// If unset, `fetch.recurseSubmodules` and `submodule.recurse` decide, and submodules are fetched on demand by default.
+        #[clap(long, value_name = "MODE", num_args = 0..=1, default_missing_value = "yes")]
+        pub recurse_submodules: Option<RecurseSubmodules>,
+
+        /// The number of submodules to fetch in parallel, with 0 meaning one per logical core.
+        #[clap(long, short = 'j')]
+        pub jobs: Option<usize>,
+
         /// Override the built-in and configured ref-specs with one or more of the given ones.
         #[clap(value_parser = crate::shared::AsBString)]
         pub ref_spec: Vec<gix::bstr::BString>,
     }
 
+    #[derive(Debug, Copy, Clone, PartialEq, Eq, clap::ValueEnum)]
+    pub enum RecurseSubmodules {
+        /// Fetch all populated submodules.
+        Yes,
+        /// Fetch only submodules whose recorded commits are missing.
+        OnDemand,
+        /// Don't fetch submodules.
+        No,
+    }
+
+    impl From<RecurseSubmodules> for gix::submodule::config::FetchRecurse {
+        fn from(value: RecurseSubmodules) -> Self {
+            match value {
+                RecurseSubmodules::Yes => gix::submodule::config::FetchRecurse::Always,
+                RecurseSubmodules::OnDemand => gix::submodule::config::FetchRecurse::OnDemand,
+                RecurseSubmodules::No => gix::submodule::config::FetchRecurse::Never,
+            }
+        }
+    }
+
     #[derive(Debug, clap::Parser)]
     pub struct ShallowOptions {
         /// Fetch with the history truncated to the given number of commits as seen from the remote.
@@ -698,6 +1006,14 @@ pub mod clone {
         #[clap(long = "ref", value_parser = crate::shared::AsPartialRefName, value_name = "REF_NAME")]
         pub ref_name: Option<gix::refs::PartialName>,
 
+        /// Initialize, clone and check out all submodules recursively after the main worktree was checked out.
+        #[clap(long)]
+        pub recurse_submodules: bool,
+
+        /// A path or URL to a bundle or bundle list to apply before fetching the remaining objects from the remote.
+        #[clap(long, value_name = "URI")]
+        pub bundle_uri: Option<OsString>,
+
         /// The directory to initialize with the new repository and to which all data should be written.
         pub directory: Option<PathBuf>,
     }
@@ -872,7 +1188,19 @@ pub mod odb {
 pub mod fsck {
     #[derive(Debug, clap::Parser)]
     pub struct Platform {
-        /// A revspec to start the connectivity check from.
+        /// Print all unreachable objects, not only the dangling ones.
+        #[clap(long)]
+        pub unreachable: bool,
+        /// Do not print dangling objects.
+        #[clap(long)]
+        pub no_dangling: bool,
+        /// Write dangling objects into `.git/lost-found`, with blobs being written with their content.
+        #[clap(long)]
+        pub lost_found: bool,
+        /// Only check that all reachable objects are present, without verifying their hash and syntax.
+        #[clap(long)]
+        pub connectivity_only: bool,
+        /// A revspec to start the check from, instead of all references, their reflogs, `HEAD` and the index.
         pub spec: Option<String>,
     }
 }
@@ -985,6 +1313,43 @@ pub mod credential {
         /// Try to resolve the given revspec and print the object names.
         #[clap(visible_alias = "erase")]
         Reject,
+        /// Run one of the built-in credential helpers directly, just like `git credential-store` or `git credential-cache`.
+        #[clap(subcommand)]
+        Helper(helper::Subcommands),
+        /// Run the daemon keeping credentials in memory for the `cache` helper, listening on the given socket.
+        ///
+        /// It's started automatically by the `cache` helper when needed.
+        CacheDaemon {
+            /// The path to the socket to listen on.
+            socket: std::path::PathBuf,
+        },
+    }
+
+    pub mod helper {
+        use std::path::PathBuf;
+
+        #[derive(Debug, clap::Subcommand)]
+        pub enum Subcommands {
+            /// Keep credentials in a plain-text file, reading the credential context from STDIN.
+            Store {
+                /// The file to keep credentials in, instead of `~/.git-credentials` and `$XDG_CONFIG_HOME/git/credentials`.
+                #[clap(long)]
+                file: Option<PathBuf>,
+                /// The action to perform, one of `get`, `store` or `erase`.
+                action: String,
+            },
+            /// Keep credentials in the memory of a daemon for a limited time, reading the credential context from STDIN.
+            Cache {
+                /// The amount of seconds to keep newly stored credentials.
+                #[clap(long)]
+                timeout: Option<u64>,
+                /// The path to the socket of the daemon, instead of `$XDG_CACHE_HOME/git/credential/socket`.
+                #[clap(long)]
+                socket: Option<PathBuf>,
+                /// The action to perform, one of `get`, `store`, `erase` or `exit`.
+                action: String,
+            },
+        }
     }
 }
 
@@ -1213,6 +1578,8 @@ pub mod index {
 }
 
 pub mod submodule {
+    use gix::bstr::BString;
+
     #[derive(Debug, clap::Parser)]
     pub struct Platform {
         #[clap(subcommand)]
@@ -1227,6 +1594,86 @@ pub mod submodule {
             #[clap(short = 'd', long)]
             dirty_suffix: Option<Option<String>>,
         },
+        /// Register submodules in the configuration of the superproject by copying their URL from `.gitmodules`.
+        Init {
+            /// The names of the submodules to initialize, or all of them if empty.
+            #[clap(value_parser = crate::shared::AsBString)]
+            names: Vec<BString>,
+        },
+        /// Clone missing submodules and bring all of them to the commits recorded in the superproject.
+        #[cfg(feature = "gitoxide-core-blocking-client")]
+        Update {
+            /// Initialize submodules that aren't active yet before updating them.
+            #[clap(long)]
+            init: bool,
+            /// Update the submodules of updated submodules as well.
+            #[clap(long)]
+            recursive: bool,
+            /// Detach `HEAD` at the recorded commit, overriding the configured update mode.
+            #[clap(long, conflicts_with_all = ["rebase", "merge"])]
+            checkout: bool,
+            /// Rebase the current branch onto the recorded commit, overriding the configured update mode.
+            #[clap(long, conflicts_with_all = ["checkout", "merge"])]
+            rebase: bool,
+            /// Merge the recorded commit into the current branch, overriding the configured update mode.
+            #[clap(long, conflicts_with_all = ["checkout", "rebase"])]
+            merge: bool,
+            /// Don't fetch missing commits from the remote of a submodule.
+            #[clap(long, short = 'N')]
+            no_fetch: bool,
+            /// The number of submodules to update in parallel, with 0 meaning one per logical core.
+            #[clap(long, short = 'j')]
+            jobs: Option<usize>,
+            /// The names of the submodules to update, or all of them if empty.
+            #[clap(value_parser = crate::shared::AsBString)]
+            names: Vec<BString>,
+        },
+        /// Write the URLs of submodules from `.gitmodules` to the configuration of the superproject and the submodule repositories.
+        Sync {
+            /// The names of the submodules to synchronize, or all of them if empty.
+            #[clap(value_parser = crate::shared::AsBString)]
+            names: Vec<BString>,
+        },
+        /// Move repositories of submodules that are contained in their worktree into the repository of the superproject.
+        Absorbgitdirs {
+            /// The names of the submodules whose repositories to move, or all of them if empty.
+            #[clap(value_parser = crate::shared::AsBString)]
+            names: Vec<BString>,
+        },
+        /// Clone a repository into a new submodule, and stage it along with the changed `.gitmodules` file.
+        #[cfg(feature = "gitoxide-core-blocking-client")]
+        Add {
+            /// The name of the submodule, which defaults to its path.
+            #[clap(long, value_parser = crate::shared::AsBString)]
+            name: Option<BString>,
+            /// The URL to clone from, which may be relative to the URL of the default remote of the superproject.
+            #[clap(value_parser = crate::shared::AsBString)]
+            url: BString,
+            /// The path at which to check out the submodule, relative to the worktree of the superproject.
+            #[clap(value_parser = crate::shared::AsBString)]
+            path: BString,
+        },
+        /// Remove the checkouts of submodules and their configuration in the superproject, keeping their repositories.
+        Deinit {
+            /// The names of the submodules to deinitialize, or all of them if empty.
+            #[clap(value_parser = crate::shared::AsBString)]
+            names: Vec<BString>,
+        },
+        /// Move a submodule to another path, and stage the change along with the changed `.gitmodules` file.
+        Move {
+            /// The name of the submodule to move.
+            #[clap(value_parser = crate::shared::AsBString)]
+            name: BString,
+            /// The new path of the submodule, relative to the worktree of the superproject.
+            #[clap(value_parser = crate::shared::AsBString)]
+            path: BString,
+        },
+        /// Remove submodules from the superproject, and stage the change along with the changed `.gitmodules` file.
+        Remove {
+            /// The names of the submodules to remove.
+            #[clap(required = true, value_parser = crate::shared::AsBString)]
+            names: Vec<BString>,
+        },
     }
 }
 
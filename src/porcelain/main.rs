@@ -41,7 +41,10 @@ pub fn main() -> Result<()> {
             crate::shared::STANDARD_RANGE,
             move |_progress, _out, _err| panic!("something went very wrong"),
         ),
-        Subcommands::Init { directory } => core::repository::init(directory).map(|_| ()),
+        Subcommands::Init {
+            directory,
+            object_format,
+        } => core::repository::init(directory, object_format).map(|_| ()),
         #[cfg(feature = "gitoxide-core-tools")]
         Subcommands::Tool(tool) => match tool {
             #[cfg(feature = "gitoxide-core-tools-query")]
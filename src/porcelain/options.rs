@@ -36,6 +36,9 @@ pub enum Subcommands {
         ///
         /// Defaults to the current working directory.
         directory: Option<PathBuf>,
+        /// The kind of hash to use for object ids, like `sha1` or `sha256` if supported by this build.
+        #[clap(long, default_value_t = gix::hash::Kind::default(), value_parser = crate::shared::AsHashKind)]
+        object_format: gix::hash::Kind,
     },
     /// A selection of useful tools.
     #[cfg(feature = "gitoxide-core-tools")]